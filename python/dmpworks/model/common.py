@@ -41,6 +41,8 @@ class Author(BaseModel):
         middle_names: The middle names of the author.
         surname: The surname of the author.
         full: The full name of the author.
+        suffix: The suffix of the author's name, e.g. "Jr.", if it had one.
+        title: The title of the author's name, e.g. "Dr.", if it had one.
     """
 
     model_config = {
@@ -55,6 +57,8 @@ class Author(BaseModel):
     middle_names: str | None
     surname: str | None
     full: str | None
+    suffix: str | None = None
+    title: str | None = None
 
 
 class Funder(BaseModel):
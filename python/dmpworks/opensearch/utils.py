@@ -7,6 +7,7 @@ from opensearchpy.exceptions import OpenSearchException, TransportError
 import pyarrow.dataset as ds
 
 from dmpworks.cli_utils import OpenSearchClientConfig
+from dmpworks.rust.bulk import OpenSearchLoader
 
 log = logging.getLogger(__name__)
 
@@ -73,6 +74,46 @@ def make_opensearch_client(config: OpenSearchClientConfig) -> OpenSearch:
     )
 
 
+def make_opensearch_loader(config: OpenSearchClientConfig, max_retries: int = 5) -> OpenSearchLoader:
+    """Create a Rust bulk loader for the same cluster and authentication as `make_opensearch_client`.
+
+    AWS credentials are resolved once, when the loader is created, so temporary credentials such as those of an IAM
+    role must outlast the load, or a new loader be created.
+
+    Args:
+        config: The OpenSearch client configuration.
+        max_retries: Retries of a bulk request while OpenSearch is overloaded.
+
+    Returns:
+        OpenSearchLoader: The bulk loader.
+    """
+    scheme = "https" if config.use_ssl else "http"
+    kwargs = {}
+    if config.auth_type == "aws":
+        if not config.aws_region or not config.aws_service:
+            raise ValueError("AWS authentication requires 'aws_region' and 'aws_service' to be defined.")
+        credentials = boto3.Session().get_credentials().get_frozen_credentials()
+        kwargs = {
+            "aws_region": config.aws_region,
+            "aws_service": config.aws_service,
+            "aws_access_key_id": credentials.access_key,
+            "aws_secret_access_key": credentials.secret_key,
+            "aws_session_token": credentials.token,
+        }
+    elif config.auth_type == "basic":
+        if not config.username or not config.password:
+            raise ValueError("Basic authentication requires 'username' and 'password' to be defined.")
+        kwargs = {"username": config.username, "password": config.password}
+
+    return OpenSearchLoader(
+        f"{scheme}://{config.host}:{config.port}",
+        verify_certs=config.verify_certs,
+        max_retries=max_retries,
+        timeout=config.timeout,
+        **kwargs,
+    )
+
+
 def load_dataset(in_dir: pathlib.Path) -> ds.Dataset:
     """Load a parquet dataset from a directory.
 
@@ -1,15 +1,107 @@
 from __future__ import annotations
 
-from typing import NamedTuple
+from collections.abc import Callable, Mapping, Sequence
+from typing import Any, Literal, NamedTuple, overload, TYPE_CHECKING
 
 from ._internal import (
     __version__,
+    AbstractWords,
+    author_count,
+    author_overlap as _author_overlap,
+    BoilerplateRules,
+    build_inverted_index,
+    bylines_match,
+    citation_name,
+    citekey,
+    cjk_surname_match,
+    clean_text,
+    combined_relevance,
+    compare_award_ids,
+    compare_names as _compare_names,
+    configure,
+    date_distance,
+    detect_language,
+    detect_language_many,
+    dmp_work_relevance,
+    doi_prefix,
+    doi_same_registrant,
+    ENGLISH_STOP_WORDS,
+    expand_given_name_variants,
+    extract_emails,
+    extract_grant_ids,
+    extract_funding_statements as _extract_funding_statements,
+    extract_identifiers as _extract_identifiers,
+    extract_institution_ids as _extract_institution_ids,
+    extract_title_metadata as _extract_title_metadata,
     has_alphabetic_initials,
+    initials_compatible,
+    is_organization,
+    issn_set_match,
+    JsonlReader,
+    keyword_overlap,
+    MarkupOptions,
+    NameOptions,
+    normalize_author_join,
+    normalize_award_id,
+    normalize_country,
+    normalize_date,
+    normalize_doi,
+    normalize_isbn,
+    normalize_issn,
+    normalize_keywords,
+    normalize_license,
+    normalize_orcid,
+    normalize_ror,
+    normalize_text,
+    normalize_title,
+    normalize_url as _normalize_url,
+    normalize_work_type,
+    nullify,
+    NullRules,
+    parse_affiliation as _parse_affiliation,
+    parse_citation as _parse_citation,
+    parse_date as _parse_date,
     parse_name as _parse_name,
+    parse_name_many as _parse_name_many,
+    parse_names_batch,
+    parse_names_list as _parse_names_list,
+    record_completeness,
+    record_fingerprint,
+    revert_and_detect_language,
+    revert_file,
     revert_inverted_index,
+    revert_inverted_index_many as _revert_inverted_index_many,
+    revert_inverted_indexes,
+    revert_iter,
+    romanize,
+    set_log_level,
+    shard_bucket,
+    shard_key,
+    split_sentences,
+    strip_boilerplate,
     strip_markup,
+    strip_markup_batch,
+    strip_markup_many,
+    surname_matches_expected,
+    tidy_reconstructed,
+    title_type_hint,
+    to_canonical_json,
+    tokenize,
+    tokenize_with_spans,
+    TokenizerOptions,
+    transform_crossref_work,
+    transform_datacite_record,
+    transform_dmp,
+    transform_openalex_work,
+    transform_works_many as _transform_works_many,
+    truncate_authors,
+    truncate_to_tokens,
+    WORK_TYPES,
 )
 
+if TYPE_CHECKING:
+    from ._internal import ParsedNameLike
+
 
 class ParsedName(NamedTuple):
     first_initial: str | None
@@ -20,10 +112,83 @@ class ParsedName(NamedTuple):
     full: str | None
 
 
+class NameSimilarity(NamedTuple):
+    exact_surname: bool
+    initials_compatible: bool
+    transliteration_match: bool
+    phonetic_match: bool
+    score: float
+
+
+class AuthorOverlap(NamedTuple):
+    pairs: list[tuple[int, int, float]]
+    score: float
+
+
+class IdentifierHit(NamedTuple):
+    kind: Literal["doi", "handle", "arxiv", "pmid", "pmcid", "accession"]
+    value: str
+    start: int
+    end: int
+
+
+class FundingStatement(NamedTuple):
+    sentence: str
+    funders: list[str]
+    award_ids: list[str]
+
+
+class InstitutionIds(NamedTuple):
+    ror: list[str]
+    grid: list[str]
+    ringgold: list[str]
+
+
+class ParsedAffiliation(NamedTuple):
+    institution: str | None
+    department: str | None
+    city: str | None
+    country: str | None
+
+
+class ParsedCitation(NamedTuple):
+    title: str | None
+    authors: list[str]
+    year: int | None
+    container: str | None
+    doi: str | None
+
+
+class ParsedDate(NamedTuple):
+    year: int
+    month: int | None
+    day: int | None
+
+
+class NormalizedUrl(NamedTuple):
+    kind: Literal["doi", "handle", "url"]
+    value: str
+
+
+class ParseError(NamedTuple):
+    row: int
+    field: str | None
+    message: str
+
+
+class TitleMetadata(NamedTuple):
+    title: str | None
+    version: str | None
+    part: int | None
+    years: list[int]
+
+
 def parse_name(
     raw_given_name: str | None = None,
     raw_surname: str | None = None,
     raw_full: str | None = None,
+    recase_surname: bool = False,
+    options: NameOptions | None = None,
 ) -> ParsedName:
     """Parse a name into its components.
 
@@ -31,17 +196,491 @@ def parse_name(
         raw_given_name: The raw given name.
         raw_surname: The raw surname.
         raw_full: The raw full name.
+        recase_surname: Whether to apply name-aware capitalization to the surname, e.g. "MCDONALD" -> "McDonald".
+        options: Rules for surname particles and family name first order, e.g. "Jan ten Brink" or "NAGY Imre",
+            applied to full names written without commas. By default neither rule is applied.
 
     Returns:
         ParsedName: A named tuple containing the parsed name components.
     """
-    return ParsedName(*_parse_name(raw_given_name, raw_surname, raw_full))
+    return ParsedName(*_parse_name(raw_given_name, raw_surname, raw_full, recase_surname, options))
+
+
+@overload
+def parse_name_many(
+    raw_full: Sequence[str | None],
+    recase_surname: bool = False,
+    num_threads: int | None = None,
+    options: NameOptions | None = None,
+    collect_errors: Literal[False] = False,
+) -> list[ParsedName]: ...
+
+
+@overload
+def parse_name_many(
+    raw_full: Sequence[str | None],
+    recase_surname: bool = False,
+    num_threads: int | None = None,
+    options: NameOptions | None = None,
+    *,
+    collect_errors: Literal[True],
+) -> tuple[list[ParsedName], list[ParseError]]: ...
+
+
+def parse_name_many(
+    raw_full: Sequence[str | None],
+    recase_surname: bool = False,
+    num_threads: int | None = None,
+    options: NameOptions | None = None,
+    collect_errors: bool = False,
+) -> list[ParsedName] | tuple[list[ParsedName], list[ParseError]]:
+    """Parse many full names in parallel, releasing the GIL.
+
+    Args:
+        raw_full: The raw full names.
+        recase_surname: Whether to apply name-aware capitalization to the surnames.
+        num_threads: The number of threads to parse with, defaults to the global thread pool.
+        options: Rules for surname particles and family name first order, see `parse_name`.
+        collect_errors: Whether to return an error for each name that no parser recognized, rather than logging a
+            warning.
+
+    Returns:
+        list[ParsedName] | tuple[list[ParsedName], list[ParseError]]: The parsed name components of each name, in
+        input order. Names that no parser recognized are split on their comma or last space. With collect_errors,
+        also the errors of those names, each a named tuple of its row index, a field of None and how it was split.
+    """
+    if not collect_errors:
+        return [ParsedName(*parsed) for parsed in _parse_name_many(raw_full, recase_surname, num_threads, options)]
+    parsed, errors = _parse_name_many(raw_full, recase_surname, num_threads, options, collect_errors=True)
+    return [ParsedName(*name) for name in parsed], [ParseError(*error) for error in errors]
+
+
+@overload
+def parse_names_list(
+    text: str | None,
+    recase_surname: bool = False,
+    options: NameOptions | None = None,
+    collect_errors: Literal[False] = False,
+) -> list[ParsedName]: ...
+
+
+@overload
+def parse_names_list(
+    text: str | None,
+    recase_surname: bool = False,
+    options: NameOptions | None = None,
+    *,
+    collect_errors: Literal[True],
+) -> tuple[list[ParsedName], list[ParseError]]: ...
+
+
+def parse_names_list(
+    text: str | None,
+    recase_surname: bool = False,
+    options: NameOptions | None = None,
+    collect_errors: bool = False,
+) -> list[ParsedName] | tuple[list[ParsedName], list[ParseError]]:
+    """Split a string holding several people or organizations and parse each name.
+
+    Args:
+        text: The raw names, e.g. "Smith, J.; Doe, A. and Lee, K.".
+        recase_surname: Whether to apply name-aware capitalization to the surnames.
+        options: Rules for surname particles and family name first order, see `parse_name`.
+        collect_errors: Whether to return an error for each name that no parser recognized, rather than logging a
+            warning.
+
+    Returns:
+        list[ParsedName] | tuple[list[ParsedName], list[ParseError]]: The parsed name components of each name, in
+        order. Organizations only have a full name. With collect_errors, also the errors of the names that no parser
+        recognized, each a named tuple of the name's index in the list, a field of None and how it was split.
+    """
+    if not collect_errors:
+        return [ParsedName(*parsed) for parsed in _parse_names_list(text, recase_surname, options)]
+    parsed, errors = _parse_names_list(text, recase_surname, options, collect_errors=True)
+    return [ParsedName(*name) for name in parsed], [ParseError(*error) for error in errors]
+
+
+def extract_identifiers(text: str | None) -> list[IdentifierHit]:
+    """Find the identifiers of works cited in free text, e.g. the outputs listed in a DMP's narrative.
+
+    DOIs, Handles, arXiv IDs, PubMed and PubMed Central IDs are found as resolver URLs, with labels such as "doi:" or
+    "PMID:", or bare where their form is unambiguous. Accession numbers are found in the forms of SRA, BioProject,
+    BioSample, GEO, ArrayExpress, EMDB and EMPIAR, and for GenBank and PDB after a label, e.g. "PDB ID 6VXX".
+
+    Args:
+        text: The free text to search.
+
+    Returns:
+        list[IdentifierHit]: The identifiers in order of position, each a named tuple of its kind, normalized value
+        and the character offsets of its match in the text, including any URL or label.
+    """
+    return [IdentifierHit(*hit) for hit in _extract_identifiers(text)]
+
+
+def extract_funding_statements(text: str | None) -> list[FundingStatement]:
+    """Find the funding acknowledgements in a work's abstract or a snippet of its full text.
+
+    Sentences are funding acknowledgements when they have a funding phrase, e.g. "funded by" or "grant No.", and name
+    a funder or an award. Funders are named after phrases such as "supported by" or "a grant from", and award numbers
+    are found in NIH and NSF forms or after a label, e.g. "grant No. 757123", and normalized with
+    `normalize_award_id`, taking the funder's formats when the sentence names a single funder.
+
+    Args:
+        text: The free text to search.
+
+    Returns:
+        list[FundingStatement]: The funding acknowledgements in order, each a named tuple of its sentence, the names
+        of its funders as written and its normalized award IDs.
+    """
+    return [FundingStatement(*statement) for statement in _extract_funding_statements(text)]
+
+
+def extract_institution_ids(text: str | None) -> InstitutionIds:
+    """Extract institution identifiers from a raw affiliation string or a JSON-serialized identifier object.
+
+    Args:
+        text: The raw affiliation string or JSON identifier object.
+
+    Returns:
+        InstitutionIds: A named tuple of the normalized ROR, GRID and Ringgold identifiers found.
+    """
+    return InstitutionIds(*_extract_institution_ids(text))
+
+
+def parse_affiliation(text: str | None) -> ParsedAffiliation:
+    """Split a raw affiliation string into its institution, department, city and country.
+
+    Args:
+        text: The raw affiliation string, e.g. "Dept. of Biology, University of Montana, Missoula, MT 59812, USA".
+
+    Returns:
+        ParsedAffiliation: A named tuple containing the parsed affiliation parts.
+    """
+    return ParsedAffiliation(*_parse_affiliation(text))
+
+
+def extract_title_metadata(text: str | None) -> TitleMetadata:
+    """Split the trailing version, part and year markers from a title, so that versions of a dataset share a base title.
+
+    Markers are version numbers ("v2", "(version 3)"), part numbers ("Part 2", "pt. II of IV") and years in brackets or
+    after punctuation ("(2019)", ", 2010–2015"), removed in any order while something is left of the title.
+
+    Args:
+        text: The title, e.g. "Soil moisture observations, Part 2 (2019) v3".
+
+    Returns:
+        TitleMetadata: A named tuple of the base title, e.g. "Soil moisture observations", the version, e.g. "3", the
+        part number with Roman numerals converted, and every year mentioned in the title in order. The title is None
+        when the text is empty.
+    """
+    return TitleMetadata(*_extract_title_metadata(text))
+
+
+def parse_citation(text: str | None) -> ParsedCitation:
+    """Parse a free-text citation, e.g. an output listed in a DMP's narrative, into its parts.
+
+    APA, MLA, Chicago, Vancouver and AGU styles are recognized by their shape, e.g. the year in parentheses after the
+    authors or the title in quotes; other citations are split at full stops into their authors, title and container.
+
+    Args:
+        text: The citation, e.g. "Smith, J., & Doe, A. (2020). Soil moisture. Journal of Hydrology, 12(3), 45–67.".
+
+    Returns:
+        ParsedCitation: A named tuple of the title, the author names as written, the year, the container (journal,
+        book or repository) and the normalized DOI, each None or empty when it can't be identified.
+    """
+    return ParsedCitation(*_parse_citation(text))
+
+
+def parse_date(date: str | Sequence | Mapping | None) -> ParsedDate | None:
+    """Parse a possibly partial date into its year, month and day.
+
+    Args:
+        date: A date string, e.g. "2021", "2021-03", "2021-03-05" or "March 2021", or Crossref date parts, e.g.
+            [2021, 3], [[2021, 3]] or {"date-parts": [[2021, 3]]}.
+
+    Returns:
+        ParsedDate | None: A named tuple of the date's year, month and day, or None when the date cannot be parsed.
+    """
+    parsed = _parse_date(date)
+    return ParsedDate(*parsed) if parsed is not None else None
+
+
+def compare_names(
+    a: str | ParsedNameLike | None,
+    b: str | ParsedNameLike | None,
+    nicknames: bool = False,
+    phonetic: bool = False,
+) -> NameSimilarity:
+    """Parse two full names and score how likely they refer to the same person.
+
+    Names in Cyrillic, Greek and CJK scripts are romanized first, so they compare against romanized forms. Titles and
+    suffixes such as "Dr." and "Jr." are ignored. Names already parsed, e.g. by `dmpworks.rust.names.parse_name_struct`,
+    are compared as they are.
+
+    Args:
+        a: The first raw full name or parsed name.
+        b: The second raw full name or parsed name.
+        nicknames: Whether given names that are variants of each other, e.g. "Bill" and "William", count as the same
+            given name.
+        phonetic: Whether surnames that sound alike, sharing a Double Metaphone code, e.g. "Schmidt" and "Smith",
+            also match, for spellings that vary across sources beyond diacritics.
+
+    Returns:
+        NameSimilarity: A named tuple of the surname, initials, transliteration and phonetic match flags and an
+        overall score between 0 and 1.
+    """
+    return NameSimilarity(*_compare_names(a, b, nicknames, phonetic))
+
+
+def author_overlap(
+    dmp_contributors: Sequence[str | ParsedNameLike | None],
+    work_authors: Sequence[str | ParsedNameLike | None],
+) -> AuthorOverlap:
+    """Parse the full names of a DMP's contributors and a work's authors and match them one-to-one.
+
+    Authors match when their surnames agree, allowing transliteration and near-identical spellings, and their initials
+    don't conflict; the matching maximizes the total similarity. Raw names in non-Latin scripts are romanized first,
+    parsed names are matched as they are, and names without a surname are left unmatched.
+
+    Args:
+        dmp_contributors: The raw full names or parsed names of the DMP's contributors.
+        work_authors: The raw full names or parsed names of the work's authors.
+
+    Returns:
+        AuthorOverlap: A named tuple of the matched (contributor index, author index, similarity) pairs, ordered by
+        contributor, and the summed similarity divided by the number of comparable names in the shorter list, 0.0 when
+        either list has none.
+    """
+    return AuthorOverlap(*_author_overlap(dmp_contributors, work_authors))
+
+
+def normalize_url(text: str | None) -> NormalizedUrl | None:
+    """Normalize a URL for comparison, resolving DOI and Handle resolver URLs to their identifiers.
+
+    The scheme and host are lowercased, and default ports, tracking query parameters (e.g. "utm_source" and "fbclid")
+    and trailing punctuation are removed. "doi:" and "hdl:" identifiers are also accepted.
+
+    Args:
+        text: The raw URL.
+
+    Returns:
+        NormalizedUrl | None: A named tuple of the kind, "doi", "handle" or "url", and the bare DOI, Handle or
+        normalized URL, or None when the text isn't a URL.
+    """
+    normalized = _normalize_url(text)
+    return NormalizedUrl(*normalized) if normalized is not None else None
+
+
+@overload
+def revert_inverted_index_many(
+    texts: Sequence[bytes | None],
+    null_if_equals: Sequence[str] | None = None,
+    num_threads: int | None = None,
+    collect_errors: Literal[False] = False,
+) -> list[str | None]: ...
+
+
+@overload
+def revert_inverted_index_many(
+    texts: Sequence[bytes | None],
+    null_if_equals: Sequence[str] | None = None,
+    num_threads: int | None = None,
+    *,
+    collect_errors: Literal[True],
+) -> tuple[list[str | None], list[ParseError]]: ...
+
+
+def revert_inverted_index_many(
+    texts: Sequence[bytes | None],
+    null_if_equals: Sequence[str] | None = None,
+    num_threads: int | None = None,
+    collect_errors: bool = False,
+) -> list[str | None] | tuple[list[str | None], list[ParseError]]:
+    """Revert many JSON-serialized inverted indexes in parallel, releasing the GIL.
+
+    Args:
+        texts: The JSON-serialized inverted indexes, each mapping words to their positions.
+        null_if_equals: Reverted texts to treat as missing, e.g. placeholder abstracts.
+        num_threads: The number of threads to revert with, defaults to the global thread pool.
+        collect_errors: Whether to return an error for each invalid index, rather than logging a warning.
+
+    Returns:
+        list[str | None] | tuple[list[str | None], list[ParseError]]: The reverted texts, in input order, None for
+        missing or invalid indexes. With collect_errors, also the errors of the invalid indexes, each a named tuple of
+        its row index, a field of None and the JSON error message.
+    """
+    if not collect_errors:
+        return _revert_inverted_index_many(texts, null_if_equals, num_threads)
+    texts, errors = _revert_inverted_index_many(texts, null_if_equals, num_threads, collect_errors=True)
+    return texts, [ParseError(*error) for error in errors]
+
+
+@overload
+def transform_works_many(
+    source: Literal["crossref", "datacite", "openalex"],
+    json_records: Sequence[bytes],
+    include_xpac: bool = False,
+    collect_errors: Literal[False] = False,
+    num_threads: int | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    updated_since: str | None = None,
+    created_since: str | None = None,
+) -> list[dict[str, Any] | None]: ...
+
+
+@overload
+def transform_works_many(
+    source: Literal["crossref", "datacite", "openalex"],
+    json_records: Sequence[bytes],
+    include_xpac: bool = False,
+    *,
+    collect_errors: Literal[True],
+    num_threads: int | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    updated_since: str | None = None,
+    created_since: str | None = None,
+) -> tuple[list[dict[str, Any] | None], list[ParseError], list[int]]: ...
+
+
+def transform_works_many(
+    source: Literal["crossref", "datacite", "openalex"],
+    json_records: Sequence[bytes],
+    include_xpac: bool = False,
+    collect_errors: bool = False,
+    num_threads: int | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    updated_since: str | None = None,
+    created_since: str | None = None,
+) -> list[dict[str, Any] | None] | tuple[list[dict[str, Any] | None], list[ParseError], list[int]]:
+    """Transform many raw works from one source into our normalized work schema in parallel, releasing the GIL.
+
+    Args:
+        source: The source of the works, which selects transform_crossref_work, transform_datacite_record or
+            transform_openalex_work.
+        json_records: The raw JSON-serialized works.
+        include_xpac: Whether to keep OpenAlex xpac works.
+        collect_errors: Whether to return an error for each record or field that can't be parsed, rather than raising
+            ValueError for invalid records and logging a warning for invalid fields.
+        num_threads: The number of threads to transform with, defaults to the global thread pool.
+        progress: Called with the number of works transformed so far and the total every 10,000 works, and once all
+            are transformed.
+        updated_since: An ISO date; works last updated before it are skipped, for incremental loads. Compares
+            Crossref's indexed, OpenAlex's updated_date and DataCite's updated dates.
+        created_since: An ISO date; works created before it are skipped. Compares Crossref's created, OpenAlex's
+            created_date and DataCite's created dates.
+
+    Returns:
+        list[dict[str, Any] | None] | tuple[list[dict[str, Any] | None], list[ParseError], list[int]]: The transformed
+        works, in input order, None for works without a valid DOI and works skipped by updated_since or created_since.
+        With collect_errors, invalid records are also None, and the errors are returned too, each a named tuple of its
+        row index, the field that couldn't be parsed (None when the record isn't valid JSON) and the error message,
+        followed by the row indexes of the skipped works.
+    """
+    since = {"updated_since": updated_since, "created_since": created_since}
+    if not collect_errors:
+        return _transform_works_many(
+            source, json_records, include_xpac, num_threads=num_threads, progress=progress, **since
+        )
+    works, errors, skipped = _transform_works_many(
+        source, json_records, include_xpac, True, num_threads, progress, **since
+    )
+    return works, [ParseError(*error) for error in errors], skipped
 
 
 __all__ = [
     "__version__",
+    "AbstractWords",
+    "author_count",
+    "author_overlap",
+    "BoilerplateRules",
+    "build_inverted_index",
+    "bylines_match",
+    "citation_name",
+    "citekey",
+    "cjk_surname_match",
+    "clean_text",
+    "combined_relevance",
+    "compare_award_ids",
+    "compare_names",
+    "configure",
+    "date_distance",
+    "detect_language",
+    "detect_language_many",
+    "dmp_work_relevance",
+    "doi_prefix",
+    "doi_same_registrant",
+    "ENGLISH_STOP_WORDS",
+    "expand_given_name_variants",
+    "extract_emails",
+    "extract_funding_statements",
+    "extract_grant_ids",
+    "extract_identifiers",
+    "extract_institution_ids",
+    "extract_title_metadata",
     "has_alphabetic_initials",
+    "initials_compatible",
+    "is_organization",
+    "issn_set_match",
+    "JsonlReader",
+    "keyword_overlap",
+    "MarkupOptions",
+    "NameOptions",
+    "normalize_author_join",
+    "normalize_award_id",
+    "normalize_country",
+    "normalize_date",
+    "normalize_doi",
+    "normalize_isbn",
+    "normalize_issn",
+    "normalize_keywords",
+    "normalize_license",
+    "normalize_orcid",
+    "normalize_ror",
+    "normalize_text",
+    "normalize_title",
+    "normalize_url",
+    "normalize_work_type",
+    "nullify",
+    "NullRules",
+    "parse_affiliation",
+    "parse_citation",
+    "parse_date",
     "parse_name",
+    "parse_name_many",
+    "parse_names_batch",
+    "parse_names_list",
+    "ParseError",
+    "record_completeness",
+    "record_fingerprint",
+    "revert_and_detect_language",
+    "revert_file",
     "revert_inverted_index",
+    "revert_inverted_index_many",
+    "revert_inverted_indexes",
+    "revert_iter",
+    "romanize",
+    "set_log_level",
+    "shard_bucket",
+    "shard_key",
+    "split_sentences",
+    "strip_boilerplate",
     "strip_markup",
+    "strip_markup_batch",
+    "strip_markup_many",
+    "surname_matches_expected",
+    "tidy_reconstructed",
+    "title_type_hint",
+    "to_canonical_json",
+    "tokenize",
+    "tokenize_with_spans",
+    "TokenizerOptions",
+    "transform_crossref_work",
+    "transform_datacite_record",
+    "transform_dmp",
+    "transform_openalex_work",
+    "transform_works_many",
+    "truncate_authors",
+    "truncate_to_tokens",
+    "WORK_TYPES",
 ]
@@ -1,11 +1,333 @@
-from collections.abc import Sequence
+from collections.abc import Callable, Iterable, Iterator, Mapping, Sequence
+from os import PathLike
+from typing import Any, Literal, Protocol, overload
+
+import numpy as np
+from numpy.typing import NDArray
 
 __version__: str
+ENGLISH_STOP_WORDS: list[str]
+
+def configure(
+    threads: int | None = None,
+    chunk_size: int | None = None,
+    string_cache: int | None = None,
+) -> dict[str, int | None]: ...
+
+WorkType = Literal[
+    "ARTICLE",
+    "AUDIO_VISUAL",
+    "BOOK",
+    "BOOK_CHAPTER",
+    "DATASET",
+    "DISSERTATION",
+    "EDITORIAL",
+    "ERRATUM",
+    "EVENT",
+    "GRANT",
+    "IMAGE",
+    "INTERACTIVE_RESOURCE",
+    "LETTER",
+    "OTHER",
+    "OUTPUT_MANAGEMENT_PLAN",
+    "PARATEXT",
+    "PEER_REVIEW",
+    "PHYSICAL_OBJECT",
+    "PREPRINT",
+    "REFERENCE_ENTRY",
+    "REPORT",
+    "RETRACTION",
+    "REVIEW",
+    "SOFTWARE",
+    "SOUND",
+    "STANDARD",
+    "SUPPLEMENTARY_MATERIALS",
+]
+WORK_TYPES: list[WorkType]
+
+DateLike = str | Sequence[int | None] | Sequence[Sequence[int | None]] | Mapping[str, Any]
+
+class ParsedNameLike(Protocol):
+    first_initial: str | None
+    given_name: str | None
+    middle_initials: str | None
+    middle_names: str | None
+    surname: str | None
+    full: str | None
+
+class ParsedName:
+    def __init__(
+        self,
+        first_initial: str | None = None,
+        given_name: str | None = None,
+        middle_initials: str | None = None,
+        middle_names: str | None = None,
+        surname: str | None = None,
+        full: str | None = None,
+        suffix: str | None = None,
+        title: str | None = None,
+    ) -> None: ...
+    @property
+    def first_initial(self) -> str | None: ...
+    @property
+    def given_name(self) -> str | None: ...
+    @property
+    def middle_initials(self) -> str | None: ...
+    @property
+    def middle_names(self) -> str | None: ...
+    @property
+    def surname(self) -> str | None: ...
+    @property
+    def full(self) -> str | None: ...
+    @property
+    def suffix(self) -> str | None: ...
+    @property
+    def title(self) -> str | None: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def to_dict(self) -> dict[str, str | None]: ...
+
+class ArrowArrayExportable(Protocol):
+    def __arrow_c_array__(self, requested_schema: object | None = None) -> tuple[object, object]: ...
+
+class ArrowSchemaExportable(Protocol):
+    def __arrow_c_schema__(self) -> object: ...
+
+class JsonlReader(Iterator[Any]):
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        fields: Sequence[str] | None = ...,
+        progress: Callable[[int, None], object] | None = ...,
+        source: Literal["crossref", "datacite", "openalex"] | None = ...,
+        updated_since: str | None = ...,
+        created_since: str | None = ...,
+    ) -> None: ...
+    def __iter__(self) -> JsonlReader: ...
+    def __next__(self) -> Any: ...
+    @property
+    def skipped(self) -> int: ...
+
+class AbstractWords(Iterator[str]):
+    def __iter__(self) -> AbstractWords: ...
+    def __next__(self) -> str: ...
+
+class FunderRegistry:
+    def __init__(self, entries: Mapping[str, Sequence[str]]) -> None: ...
+    def __len__(self) -> int: ...
+
+class InstitutionIndex:
+    def __init__(
+        self,
+        entries: Mapping[str, Sequence[str]],
+        acronyms: Mapping[str, Sequence[str]] | None = None,
+    ) -> None: ...
+    @staticmethod
+    def from_ror_dump(
+        path: str | PathLike[str],
+        aliases: Mapping[str, Sequence[str]] | None = None,
+        acronyms: Mapping[str, Sequence[str]] | None = None,
+    ) -> InstitutionIndex: ...
+    @property
+    def num_institutions(self) -> int: ...
+    def __len__(self) -> int: ...
+
+KeyNormalization = Literal["exact", "funder", "institution", "name"]
+
+class LookupTable:
+    def __init__(self, path: str | PathLike[str]) -> None: ...
+    @staticmethod
+    def build(
+        path: str | PathLike[str],
+        entries: Mapping[str, str | Sequence[str]],
+        normalization: KeyNormalization = "exact",
+    ) -> LookupTable: ...
+    @property
+    def path(self) -> PathLike[str]: ...
+    @property
+    def normalization(self) -> KeyNormalization: ...
+    def get(self, key: str, default: str | None = None) -> str | None: ...
+    def get_all(self, key: str) -> list[str]: ...
+    def get_many(self, keys: Sequence[str | None]) -> list[str | None]: ...
+    def __contains__(self, key: str) -> bool: ...
+    def __len__(self) -> int: ...
+
+NullPreset = Literal["not_available", "to_be_determined", "no_abstract", "blank", "punctuation"]
+PhoneticAlgorithm = Literal["double_metaphone", "nysiis"]
+BlockingScheme = Literal["surname_initial_year", "title_prefix", "funder_year"]
+
+class NullRules:
+    def __init__(
+        self,
+        values: Sequence[str] = (),
+        patterns: Sequence[str] = (),
+        presets: Sequence[NullPreset] | None = None,
+        case_insensitive: bool = True,
+    ) -> None: ...
+
+class BoilerplateRules:
+    def __init__(self, patterns: Sequence[str] = (), case_insensitive: bool = True) -> None: ...
+
+class MarkupOptions:
+    def __init__(
+        self,
+        decode_entities: bool = False,
+        jats: bool = False,
+        mathml: Literal["keep", "remove", "placeholder"] = "keep",
+        null_rules: NullRules | None = None,
+    ) -> None: ...
+
+class NameOptions:
+    def __init__(
+        self,
+        surname_particles: bool = False,
+        family_name_first: bool = False,
+        ambiguous_organization_words: bool = False,
+    ) -> None: ...
+
+class TokenizerOptions:
+    def __init__(
+        self,
+        fold: bool = True,
+        stop_words: Sequence[str] | None = None,
+        min_length: int = 1,
+        word_ngrams: tuple[int, int] = (1, 1),
+        char_ngrams: tuple[int, int] | None = None,
+    ) -> None: ...
+
+class Bm25Index:
+    def __init__(
+        self,
+        texts: Sequence[str | None],
+        k1: float = 1.2,
+        b: float = 0.75,
+        options: TokenizerOptions | None = None,
+    ) -> None: ...
+    def __len__(self) -> int: ...
+    def score(self, query: str) -> list[float]: ...
+    def rank(self, query: str, top_k: int | None = None) -> list[tuple[int, float]]: ...
+
+class IdfTable:
+    def __init__(
+        self,
+        texts: Sequence[str | None] | None = None,
+        options: TokenizerOptions | None = None,
+        num_threads: int | None = None,
+    ) -> None: ...
+    @property
+    def num_docs(self) -> int: ...
+    @property
+    def num_terms(self) -> int: ...
+    def update(self, texts: Sequence[str | None], num_threads: int | None = None) -> None: ...
+    def update_from_jsonl(
+        self,
+        path: str | PathLike[str],
+        fields: Sequence[str] = ("title", "abstract"),
+        num_threads: int | None = None,
+    ) -> int: ...
+    def idf(self, term: str) -> float: ...
+
+def tfidf_cosine(dmp_text: str, work_text: str, idf_table: IdfTable) -> float: ...
+def tfidf_cosine_many(
+    dmp_texts: Sequence[str | None],
+    work_texts: Sequence[str | None],
+    idf_table: IdfTable,
+    num_threads: int | None = None,
+) -> list[float | None]: ...
+
+class SamplingStrategy:
+    def __init__(
+        self,
+        per_stratum: int = 50,
+        by_work_type: bool = True,
+        year_gap_edges: Sequence[float] = (0.0, 2.0, 5.0),
+        similarity_edges: Sequence[float] = (0.25, 0.5, 0.75),
+        seed: int = 0,
+    ) -> None: ...
+
+NegativeKind = Literal["funder", "institution", "random"]
+
+class HardNegativeOptions:
+    def __init__(self, fraction: float = 0.5, same_funder: bool = True, same_institution: bool = True) -> None: ...
+
+class WorkQueryOptions:
+    def __init__(
+        self,
+        max_results: int = 100,
+        project_end_buffer_years: int = 3,
+        inner_hits_size: int = 50,
+        boosts: Mapping[str, float] | None = None,
+        minimum_should_match: str | None = None,
+        stop_words: Sequence[str] | None = None,
+        features: Mapping[str, bool] | None = None,
+    ) -> None: ...
+
+class EmbeddingTextOptions:
+    def __init__(
+        self,
+        markup: MarkupOptions | None = None,
+        form: Literal["NFC", "NFD", "NFKC", "NFKD"] = "NFC",
+        fold_diacritics: bool = False,
+        case_fold: bool = False,
+        max_tokens: int | None = 256,
+        separator: str = "\n",
+    ) -> None: ...
+
+class ParquetWriteOptions:
+    def __init__(self, row_group_size: int = 100_000, zstd_level: int = 3) -> None: ...
+
+CsvTransform = Literal["normalize_doi", "normalize_orcid", "normalize_ror", "parse_name", "strip_markup"]
+
+class CsvOptions:
+    def __init__(
+        self,
+        delimiter: str = ",",
+        quote: str | None = '"',
+        column_names: Sequence[str] | None = None,
+    ) -> None: ...
+
+class WorksFilter:
+    def __init__(
+        self,
+        min_year: int | None = None,
+        max_year: int | None = None,
+        source: str | None = None,
+        has_abstract: bool | None = None,
+    ) -> None: ...
+
+PipelineTransform = Literal[
+    "revert_inverted_index",
+    "strip_markup",
+    "strip_boilerplate",
+    "clean_text",
+    "nullify",
+    "normalize_doi",
+    "normalize_orcid",
+    "normalize_ror",
+    "normalize_title",
+]
+
+class Pipeline:
+    def __init__(
+        self,
+        transforms: Mapping[str, Sequence[PipelineTransform | tuple[Literal["truncate"], int]]],
+    ) -> None: ...
+    @property
+    def fields(self) -> list[str]: ...
+    def run(self, batch: ArrowArrayExportable, num_threads: int | None = None) -> ArrowArrayExportable: ...
+
+class WorksParquetReader(Iterator[ArrowArrayExportable]):
+    @property
+    def schema(self) -> ArrowSchemaExportable: ...
+    def __iter__(self) -> WorksParquetReader: ...
+    def __next__(self) -> ArrowArrayExportable: ...
 
 def parse_name(
     raw_given_name: str | None = ...,
     raw_surname: str | None = ...,
     raw_full: str | None = ...,
+    recase_surname: bool = ...,
+    options: NameOptions | None = ...,
 ) -> tuple[
     str | None,  # first_initial
     str | None,  # given_name
@@ -14,6 +336,629 @@ def parse_name(
     str | None,  # surname
     str | None,  # full
 ]: ...
+@overload
+def parse_name_many(
+    raw_full: Sequence[str | None],
+    recase_surname: bool = ...,
+    num_threads: int | None = ...,
+    options: NameOptions | None = ...,
+    collect_errors: Literal[False] = ...,
+) -> list[
+    tuple[
+        str | None,  # first_initial
+        str | None,  # given_name
+        str | None,  # middle_initials
+        str | None,  # middle_names
+        str | None,  # surname
+        str | None,  # full
+    ]
+]: ...
+@overload
+def parse_name_many(
+    raw_full: Sequence[str | None],
+    recase_surname: bool = ...,
+    num_threads: int | None = ...,
+    options: NameOptions | None = ...,
+    *,
+    collect_errors: Literal[True],
+) -> tuple[
+    list[
+        tuple[
+            str | None,  # first_initial
+            str | None,  # given_name
+            str | None,  # middle_initials
+            str | None,  # middle_names
+            str | None,  # surname
+            str | None,  # full
+            str | None,  # suffix
+            str | None,  # title
+        ]
+    ],
+    list[tuple[int, str | None, str]],
+]: ...
+@overload
+def parse_names_list(
+    text: str | None,
+    recase_surname: bool = ...,
+    options: NameOptions | None = ...,
+    collect_errors: Literal[False] = ...,
+) -> list[
+    tuple[
+        str | None,  # first_initial
+        str | None,  # given_name
+        str | None,  # middle_initials
+        str | None,  # middle_names
+        str | None,  # surname
+        str | None,  # full
+    ]
+]: ...
+@overload
+def parse_names_list(
+    text: str | None,
+    recase_surname: bool = ...,
+    options: NameOptions | None = ...,
+    *,
+    collect_errors: Literal[True],
+) -> tuple[
+    list[
+        tuple[
+            str | None,  # first_initial
+            str | None,  # given_name
+            str | None,  # middle_initials
+            str | None,  # middle_names
+            str | None,  # surname
+            str | None,  # full
+            str | None,  # suffix
+            str | None,  # title
+        ]
+    ],
+    list[tuple[int, str | None, str]],
+]: ...
+def parse_name_struct(
+    raw_given_name: str | None = None,
+    raw_surname: str | None = None,
+    raw_full: str | None = None,
+    recase_surname: bool = False,
+    options: NameOptions | None = None,
+) -> ParsedName: ...
+def parse_name_many_struct(
+    raw_full: Sequence[str | None],
+    recase_surname: bool = False,
+    num_threads: int | None = None,
+    options: NameOptions | None = None,
+) -> list[ParsedName]: ...
+def parse_names_list_struct(
+    text: str | None,
+    recase_surname: bool = False,
+    options: NameOptions | None = None,
+) -> list[ParsedName]: ...
+def is_organization(text: str | None, options: NameOptions | None = ...) -> bool: ...
 def revert_inverted_index(text: bytes | None, null_if_equals: Sequence[str] | None = ...) -> str | None: ...
-def strip_markup(text: str | None, null_if_equals: Sequence[str] | None = ...) -> str | None: ...
+@overload
+def revert_inverted_index_many(
+    texts: Sequence[bytes | None],
+    null_if_equals: Sequence[str] | None = ...,
+    num_threads: int | None = ...,
+    collect_errors: Literal[False] = ...,
+) -> list[str | None]: ...
+@overload
+def revert_inverted_index_many(
+    texts: Sequence[bytes | None],
+    null_if_equals: Sequence[str] | None = ...,
+    num_threads: int | None = ...,
+    *,
+    collect_errors: Literal[True],
+) -> tuple[list[str | None], list[tuple[int, str | None, str]]]: ...
+def revert_inverted_indexes(
+    indexes: ArrowArrayExportable,
+    null_if_equals: Sequence[str] | None = ...,
+    num_threads: int | None = ...,
+) -> ArrowArrayExportable: ...
+def strip_markup(
+    text: str | None,
+    null_if_equals: Sequence[str] | None = ...,
+    options: MarkupOptions | None = ...,
+) -> str | None: ...
+def strip_markup_many(
+    texts: Sequence[str | None],
+    null_if_equals: Sequence[str] | None = ...,
+    options: MarkupOptions | None = ...,
+    num_threads: int | None = ...,
+) -> list[str | None]: ...
+def strip_markup_batch(
+    texts: ArrowArrayExportable,
+    null_rules: NullRules | None = ...,
+    options: MarkupOptions | None = ...,
+    num_threads: int | None = ...,
+) -> ArrowArrayExportable: ...
+def profile_texts(
+    texts: ArrowArrayExportable,
+    null_rules: NullRules | None = None,
+    languages: bool = True,
+    num_threads: int | None = None,
+) -> dict[str, Any]: ...
+def nullify(text: str | None, rules: NullRules | None = None) -> str | None: ...
+def strip_boilerplate(text: str | None, rules: BoilerplateRules | None = None) -> str | None: ...
 def has_alphabetic_initials(text: str | None) -> bool: ...
+def normalize_title(
+    text: str | None,
+    normalize_subscripts: bool = ...,
+    strip_site_suffixes: bool = ...,
+    stop_words: Sequence[str] | None = ...,
+) -> str | None: ...
+def extract_title_metadata(
+    text: str | None,
+) -> tuple[
+    str | None,  # title
+    str | None,  # version
+    int | None,  # part
+    list[int],  # years
+]: ...
+def normalize_license(text: str | None) -> str | None: ...
+def normalize_doi(text: str | None) -> str | None: ...
+def dmp_work_relevance(dmp_text: str, work_abstract: str) -> float: ...
+def normalize_keywords(text: str | None) -> list[str]: ...
+def keyword_overlap(a: str | None, b: str | None) -> float: ...
+def shard_bucket(key: str, num_buckets: int) -> int: ...
+def shard_key(text: str | None, num_shards: int) -> int | None: ...
+def record_fingerprint(fields: Sequence[str | None]) -> str: ...
+def to_canonical_json(record: Any) -> str: ...
+def revert_and_detect_language(text: bytes | None) -> tuple[str, str | None] | None: ...
+def surname_matches_expected(text: str | None, expected: str) -> bool: ...
+def citation_name(text: str | None, style: Literal["vancouver", "apa"] = ...) -> str | None: ...
+def author_count(text: str | None) -> int: ...
+def truncate_authors(text: str | None, n: int) -> str: ...
+def normalize_author_join(text: str | None) -> str | None: ...
+def extract_grant_ids(text: str | None) -> list[str]: ...
+def extract_funding_statements(
+    text: str | None,
+) -> list[
+    tuple[
+        str,  # sentence
+        list[str],  # funders
+        list[str],  # award_ids
+    ]
+]: ...
+def extract_identifiers(
+    text: str | None,
+) -> list[
+    tuple[
+        Literal["doi", "handle", "arxiv", "pmid", "pmcid", "accession"],  # kind
+        str,  # value
+        int,  # start
+        int,  # end
+    ]
+]: ...
+def combined_relevance(signals: Mapping[str, float], weights: Mapping[str, float]) -> float: ...
+def normalize_date(text: str | None) -> str | None: ...
+def bylines_match(a: str | None, b: str | None, threshold: float = ...) -> bool: ...
+def tokenize_with_spans(text: str) -> list[tuple[str, int, int]]: ...
+def initials_compatible(a: ParsedNameLike, b: ParsedNameLike) -> float: ...
+def tidy_reconstructed(text: str, strip_trailing_authors: bool = ...) -> str: ...
+def record_completeness(
+    has_title: bool,
+    has_abstract: bool,
+    has_doi: bool,
+    author_count: int,
+    has_year: bool,
+) -> float: ...
+def revert_iter(text: bytes | None) -> AbstractWords: ...
+def doi_prefix(text: str | None) -> str | None: ...
+def doi_same_registrant(a: str | None, b: str | None) -> bool: ...
+def cjk_surname_match(a: str, b: str) -> float: ...
+def citekey(first_author: str | None, year: int | None) -> str | None: ...
+def issn_set_match(a: Sequence[str | None], b: Sequence[str | None]) -> bool: ...
+def title_type_hint(text: str | None) -> Literal["dataset", "software", "correction", "review"] | None: ...
+def revert_file(
+    input_path: str | PathLike[str],
+    output_path: str | PathLike[str],
+    parallel: bool = ...,
+    ordered: bool = ...,
+    progress: Callable[[int], object] | None = ...,
+    chunk_size: int | None = ...,
+) -> tuple[int, int]: ...
+def parse_names_batch(
+    names: ArrowArrayExportable,
+    recase_surname: bool = ...,
+    options: NameOptions | None = ...,
+) -> ArrowArrayExportable: ...
+def normalize_orcid(text: str | None) -> str | None: ...
+def normalize_ror(text: str | None) -> str | None: ...
+def extract_institution_ids(
+    text: str | None,
+) -> tuple[
+    list[str],  # ror
+    list[str],  # grid
+    list[str],  # ringgold
+]: ...
+def compare_names(
+    a: str | ParsedNameLike | None,
+    b: str | ParsedNameLike | None,
+    nicknames: bool = ...,
+    phonetic: bool = ...,
+) -> tuple[
+    bool,  # exact_surname
+    bool,  # initials_compatible
+    bool,  # transliteration_match
+    bool,  # phonetic_match
+    float,  # score
+]: ...
+def phonetic_encode(text: str, algorithm: PhoneticAlgorithm = ...) -> list[str]: ...
+def build_inverted_index(text: str | None) -> str | None: ...
+def normalize_text(
+    text: str | None,
+    form: Literal["NFC", "NFD", "NFKC", "NFKD"] = ...,
+    fold_diacritics: bool = ...,
+    case_fold: bool = ...,
+) -> str | None: ...
+def clean_text(text: str | None) -> str | None: ...
+def jaro_winkler(a: str, b: str) -> float: ...
+def jaro_winkler_many(
+    a: Sequence[str | None],
+    b: Sequence[str | None],
+    num_threads: int | None = ...,
+) -> list[float | None]: ...
+def normalized_levenshtein(a: str, b: str) -> float: ...
+def normalized_levenshtein_many(
+    a: Sequence[str | None],
+    b: Sequence[str | None],
+    num_threads: int | None = ...,
+) -> list[float | None]: ...
+def token_sort_ratio(a: str, b: str) -> float: ...
+def token_sort_ratio_many(
+    a: Sequence[str | None],
+    b: Sequence[str | None],
+    num_threads: int | None = ...,
+) -> list[float | None]: ...
+def token_set_ratio(a: str, b: str) -> float: ...
+def token_set_ratio_many(
+    a: Sequence[str | None],
+    b: Sequence[str | None],
+    num_threads: int | None = ...,
+) -> list[float | None]: ...
+def containment_score(short_text: str, long_text: str, shingle_size: int = 3) -> float: ...
+def containment_score_many(
+    short_texts: Sequence[str | None],
+    long_texts: Sequence[str | None],
+    shingle_size: int = 3,
+    num_threads: int | None = None,
+) -> list[float | None]: ...
+def detect_language(text: str | None) -> tuple[str, float] | None: ...
+def detect_language_many(
+    texts: Sequence[str | None],
+    num_threads: int | None = ...,
+) -> list[tuple[str, float] | None]: ...
+def transform_crossref_work(json_bytes: bytes) -> dict[str, Any] | None: ...
+def transform_datacite_record(json_bytes: bytes) -> dict[str, Any] | None: ...
+def transform_openalex_work(json_bytes: bytes, include_xpac: bool = False) -> dict[str, Any] | None: ...
+def transform_dmp(json_bytes: bytes) -> dict[str, Any] | None: ...
+@overload
+def transform_works_many(
+    source: Literal["crossref", "datacite", "openalex"],
+    json_records: Sequence[bytes],
+    include_xpac: bool = False,
+    collect_errors: Literal[False] = False,
+    num_threads: int | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    updated_since: str | None = None,
+    created_since: str | None = None,
+) -> list[dict[str, Any] | None]: ...
+@overload
+def transform_works_many(
+    source: Literal["crossref", "datacite", "openalex"],
+    json_records: Sequence[bytes],
+    include_xpac: bool = False,
+    *,
+    collect_errors: Literal[True],
+    num_threads: int | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    updated_since: str | None = None,
+    created_since: str | None = None,
+) -> tuple[list[dict[str, Any] | None], list[tuple[int, str | None, str]], list[int]]: ...
+def normalize_work_type(
+    source: Literal["crossref", "datacite", "openalex"],
+    raw_type: str | None,
+) -> WorkType | None: ...
+
+class FieldSelectors:
+    def __init__(self, selectors: Sequence[str]) -> None: ...
+    def __len__(self) -> int: ...
+
+def extract_fields(json_bytes: bytes, selectors: FieldSelectors | Sequence[str]) -> list[Any]: ...
+def extract_fields_many(
+    json_records: Sequence[bytes],
+    selectors: FieldSelectors | Sequence[str],
+    num_threads: int | None = ...,
+) -> list[list[Any]]: ...
+def external_sort(
+    input_path: str | PathLike[str],
+    output_path: str | PathLike[str],
+    key_selector: str,
+    dedupe: bool = False,
+    max_chunk_bytes: int = ...,
+    temp_dir: str | PathLike[str] | None = None,
+    num_threads: int | None = None,
+) -> tuple[int, int]: ...
+def normalize_funder_name(name: str | None) -> str | None: ...
+def match_funder(name: str | None, registry: FunderRegistry, threshold: float = 0.9) -> tuple[str, float] | None: ...
+def normalize_institution_name(name: str | None) -> str | None: ...
+def match_institution(
+    name: str | None,
+    index: InstitutionIndex,
+    threshold: float = 0.9,
+) -> tuple[str, float] | None: ...
+def match_institution_many(
+    names: Sequence[str | None],
+    index: InstitutionIndex,
+    threshold: float = 0.9,
+    num_threads: int | None = None,
+) -> list[tuple[str, float] | None]: ...
+def parse_affiliation(text: str | None) -> tuple[str | None, str | None, str | None, str | None]: ...
+def parse_citation(
+    text: str | None,
+) -> tuple[
+    str | None,  # title
+    list[str],  # authors
+    int | None,  # year
+    str | None,  # container
+    str | None,  # doi
+]: ...
+def normalize_country(text: str | None) -> str | None: ...
+def tokenize(text: str, options: TokenizerOptions | None = None) -> list[str]: ...
+def ranking_metrics(
+    ground_truth: Mapping[str, Mapping[str, float]],
+    results: Mapping[str, Sequence[str]],
+    ks: Sequence[int] = (10, 20, 100),
+    num_threads: int | None = None,
+) -> tuple[dict[str, float], dict[str, dict[str, float]]]: ...
+def pr_curve(scores: Sequence[float], labels: Sequence[bool]) -> tuple[list[float], list[float], list[float]]: ...
+def choose_threshold(
+    scores: Sequence[float],
+    labels: Sequence[bool],
+    target_precision: float,
+) -> tuple[float, float, float] | None: ...
+
+GroundTruthLabel = Literal["ACCEPTED", "REJECTED", "PENDING"]
+
+class GroundTruthRecord:
+    def __init__(self, dmp_doi: str, work_doi: str, status: str, reviewer: str | None = None) -> None: ...
+    @property
+    def dmp_doi(self) -> str: ...
+    @property
+    def work_doi(self) -> str: ...
+    @property
+    def status(self) -> GroundTruthLabel: ...
+    @property
+    def reviewer(self) -> str | None: ...
+    def to_dict(self) -> dict[str, str | None]: ...
+
+def load_ground_truth(path: str | PathLike[str], require_reviewer: bool = False) -> list[GroundTruthRecord]: ...
+def save_ground_truth(path: str | PathLike[str], records: Sequence[GroundTruthRecord]) -> None: ...
+def cohen_kappa(labels_a: Sequence[str | float | None], labels_b: Sequence[str | float | None]) -> float | None: ...
+def krippendorff_alpha(
+    label_matrix: Sequence[Sequence[str | float | None]],
+    level: Literal["nominal", "interval"] = "nominal",
+) -> float | None: ...
+def sample_candidate_pairs(
+    dmps: str | PathLike[str],
+    works: str | PathLike[str],
+    strategy: SamplingStrategy | None = None,
+) -> list[dict[str, Any]]: ...
+def generate_negative_pairs(
+    dmps: Sequence[Mapping[str, Any]],
+    works: Sequence[Mapping[str, Any]],
+    n_per_dmp: int = 5,
+    seed: int = 0,
+    hard_negative_opts: HardNegativeOptions | None = None,
+    positives: Iterable[tuple[str, str]] | None = None,
+    num_threads: int | None = None,
+) -> list[tuple[int, int, NegativeKind]]: ...
+def dedupe_works(
+    works: Iterable[Mapping[str, Any]],
+    title_threshold: float = 0.9,
+    source_priority: Sequence[str] | None = None,
+) -> tuple[list[int], list[int]]: ...
+def blocking_keys(record: Mapping[str, Any], schemes: Sequence[BlockingScheme] | None = None) -> list[str]: ...
+def blocking_keys_many(
+    records: Sequence[Mapping[str, Any]],
+    schemes: Sequence[BlockingScheme] | None = None,
+    num_threads: int | None = None,
+) -> list[list[str]]: ...
+def minhash_signature(text: str, num_perm: int = 128) -> list[int]: ...
+def minhash_signature_many(
+    texts: Sequence[str | None],
+    num_perm: int = 128,
+    num_threads: int | None = None,
+) -> list[list[int] | None]: ...
+def lsh_candidate_pairs(
+    signatures: Sequence[Sequence[int] | None],
+    num_bands: int = 16,
+) -> list[tuple[int, int]]: ...
+def parse_date(date: DateLike | None) -> tuple[int, int | None, int | None] | None: ...
+def date_distance(a: DateLike | None, b: DateLike | None, max_days: float = 365.0) -> float | None: ...
+def normalize_issn(text: str | None) -> str | None: ...
+def normalize_isbn(text: str | None) -> str | None: ...
+def extract_acronyms(text: str | None) -> list[tuple[str, str | None]]: ...
+def expand_acronyms(text: str | None, dictionary: Mapping[str, str] | None = ...) -> str | None: ...
+def extract_keyphrases(text: str | None, top_n: int = 10) -> list[tuple[str, float]]: ...
+def extract_keyphrases_many(
+    texts: Sequence[str | None],
+    top_n: int = 10,
+    num_threads: int | None = None,
+) -> list[list[tuple[str, float]]]: ...
+def build_work_query(dmp_fields: Mapping[str, Any], options: WorkQueryOptions | None = None) -> dict[str, Any]: ...
+def parse_msearch_response(
+    json_bytes: bytes,
+    dedupe_by: Literal["doi", "id"] = "doi",
+    rrf_k: float = 60.0,
+) -> list[dict[str, Any]]: ...
+def fuse_rankings(
+    rankings: Sequence[Sequence[tuple[str, float]]],
+    method: Literal["rrf", "combsum", "combmnz"] = "rrf",
+    normalization: Literal["minmax", "zscore"] | None = None,
+    weights: Sequence[float] | None = None,
+    rrf_k: float = 60.0,
+) -> list[tuple[str, float]]: ...
+def normalize_scores(scores: Sequence[float], normalization: Literal["minmax", "zscore"] = "minmax") -> list[float]: ...
+def score_candidate(
+    dmp: Mapping[str, Any],
+    work: Mapping[str, Any],
+    weights: Mapping[str, float] | None = None,
+) -> tuple[dict[str, float | None], float]: ...
+def score_candidates(
+    dmp: Mapping[str, Any],
+    works: Sequence[Mapping[str, Any]],
+    weights: Mapping[str, float] | None = None,
+    num_threads: int | None = None,
+) -> list[tuple[dict[str, float | None], float]]: ...
+
+def featurize_pairs(
+    pairs: Iterable[tuple[Mapping[str, Any], Mapping[str, Any]]],
+    num_threads: int | None = None,
+) -> tuple[NDArray[np.float32], list[str]]: ...
+
+class MatchModel:
+    def __init__(self, json: str) -> None: ...
+    @property
+    def intercept(self) -> float: ...
+    @property
+    def weights(self) -> dict[str, float]: ...
+    def predict(self, features: Mapping[str, float | None] | Sequence[float | None]) -> float: ...
+    def predict_many(
+        self,
+        features: Sequence[Mapping[str, float | None] | Sequence[float | None]],
+        num_threads: int | None = None,
+    ) -> list[float]: ...
+    def score(self, dmp: Mapping[str, Any], work: Mapping[str, Any]) -> float: ...
+    def score_candidates(
+        self,
+        dmp: Mapping[str, Any],
+        works: Sequence[Mapping[str, Any]],
+        num_threads: int | None = None,
+    ) -> list[float]: ...
+
+def author_overlap(
+    dmp_contributors: Sequence[str | ParsedNameLike | None],
+    work_authors: Sequence[str | ParsedNameLike | None],
+) -> tuple[list[tuple[int, int, float]], float]: ...
+def romanize(text: str) -> str: ...
+def expand_given_name_variants(name: str | None) -> list[str]: ...
+def extract_emails(text: str | None) -> list[str]: ...
+def normalize_url(text: str | None) -> tuple[Literal["doi", "handle", "url"], str] | None: ...
+def normalize_award_id(text: str | None, funder_hint: str | None = None) -> str | None: ...
+def compare_award_ids(a: str | None, b: str | None) -> bool: ...
+def split_sentences(text: str | None) -> list[str]: ...
+def truncate_to_tokens(text: str | None, max_tokens: int) -> str | None: ...
+def prepare_embedding_text(
+    title: str | None,
+    abstract_text: str | bytes | None,
+    options: EmbeddingTextOptions | None = None,
+) -> str | None: ...
+def prepare_embedding_text_many(
+    titles: Sequence[str | None],
+    abstracts: Sequence[str | bytes | None],
+    options: EmbeddingTextOptions | None = None,
+    num_threads: int | None = None,
+) -> list[str | None]: ...
+def cosine_similarity(
+    queries: NDArray[np.float32],
+    matrix: NDArray[np.float32],
+    num_threads: int | None = None,
+) -> NDArray[np.float32]: ...
+def top_k(
+    query_vec: NDArray[np.float32],
+    matrix: NDArray[np.float32],
+    k: int,
+    num_threads: int | None = None,
+) -> list[tuple[int, float]]: ...
+def write_works_parquet(
+    records: Iterable[dict[str, Any]],
+    path: str | PathLike[str],
+    options: ParquetWriteOptions | None = None,
+) -> int: ...
+def read_works_parquet(
+    path: str | PathLike[str],
+    columns: Sequence[str] | None = None,
+    filters: WorksFilter | None = None,
+    batch_size: int = 8192,
+) -> WorksParquetReader: ...
+def diff_snapshots(
+    old_path: str | PathLike[str],
+    new_path: str | PathLike[str],
+    key: str,
+    fields: Sequence[str] | None = None,
+    batch_size: int = 8192,
+) -> tuple[list[str], list[str], dict[str, list[str]]]: ...
+def write_candidates(
+    path: str | PathLike[str],
+    dmp_id: str,
+    candidates: Sequence[Mapping[str, Any]],
+    options: ParquetWriteOptions | None = None,
+) -> int: ...
+def write_candidates_many(
+    path: str | PathLike[str],
+    dmps: Mapping[str, Sequence[Mapping[str, Any]]],
+    options: ParquetWriteOptions | None = None,
+) -> int: ...
+def read_candidates(path: str | PathLike[str]) -> dict[str, list[dict[str, Any]]]: ...
+def transform_csv(
+    input_path: str | PathLike[str],
+    output_path: str | PathLike[str],
+    transforms: Mapping[str, CsvTransform] | None = None,
+    csv_options: CsvOptions | None = None,
+    parquet_options: ParquetWriteOptions | None = None,
+    num_threads: int | None = None,
+    progress: Callable[[int, None], object] | None = None,
+) -> int: ...
+def to_bulk_actions(
+    records: Iterable[Mapping[str, Any]],
+    index_name: str,
+    id_field: str | None = None,
+    max_chunk_bytes: int = 10_485_760,
+    max_chunk_actions: int = 500,
+) -> list[bytes]: ...
+
+class OpenSearchLoader:
+    def __init__(
+        self,
+        url: str,
+        username: str | None = None,
+        password: str | None = None,
+        aws_region: str | None = None,
+        aws_service: str = "es",
+        aws_access_key_id: str | None = None,
+        aws_secret_access_key: str | None = None,
+        aws_session_token: str | None = None,
+        verify_certs: bool = True,
+        gzip: bool = True,
+        max_retries: int = 5,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 60.0,
+        timeout: float = 60.0,
+    ) -> None: ...
+    @property
+    def url(self) -> str: ...
+    def send(self, body: bytes, index: str | None = None) -> dict[str, Any]: ...
+    def load(
+        self, bodies: Sequence[bytes], index: str | None = None, num_threads: int | None = None
+    ) -> list[dict[str, Any]]: ...
+
+IndexKind = Literal["works", "dmps"]
+SpaceType = Literal["cosinesimil", "l2", "innerproduct"]
+
+class IndexSchemaOptions:
+    def __init__(
+        self,
+        index: IndexKind,
+        number_of_shards: int | None = None,
+        number_of_replicas: int | None = None,
+        embedding_dimension: int | None = None,
+        embedding_field: str = "embedding",
+        space_type: SpaceType = "cosinesimil",
+        title_keyword: bool = False,
+    ) -> None: ...
+    @property
+    def index(self) -> IndexKind: ...
+
+def generate_index_mapping(options: IndexSchemaOptions) -> dict[str, Any]: ...
+def set_log_level(level: int | str) -> None: ...
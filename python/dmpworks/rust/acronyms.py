@@ -0,0 +1,8 @@
+"""Acronym detection and expansion in titles and funder names, so queries match both acronyms and their expansions."""
+
+from ._internal import expand_acronyms, extract_acronyms
+
+__all__ = [
+    "expand_acronyms",
+    "extract_acronyms",
+]
@@ -0,0 +1,8 @@
+"""Blocking keys of normalized works and DMPs, for generating candidate pairs to score without a search cluster."""
+
+from ._internal import blocking_keys, blocking_keys_many
+
+__all__ = [
+    "blocking_keys",
+    "blocking_keys_many",
+]
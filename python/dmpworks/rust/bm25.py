@@ -0,0 +1,7 @@
+"""In-memory BM25 scoring with OpenSearch's default parameters, for re-ranking candidate pools offline."""
+
+from ._internal import Bm25Index
+
+__all__ = [
+    "Bm25Index",
+]
@@ -0,0 +1,13 @@
+"""OpenSearch bulk request bodies built from records, chunked by size, and a client that loads them.
+
+The client speaks HTTP or HTTPS, with Basic authentication, e.g. for a local cluster, or with requests signed with AWS
+Signature Version 4 for Amazon OpenSearch Service, such as the deployed cluster. The AWS credentials are passed in,
+e.g. from `boto3`, as in `dmpworks.opensearch.utils.make_opensearch_loader`.
+"""
+
+from ._internal import OpenSearchLoader, to_bulk_actions
+
+__all__ = [
+    "OpenSearchLoader",
+    "to_bulk_actions",
+]
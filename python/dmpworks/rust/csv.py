@@ -0,0 +1,8 @@
+"""Streaming transformation of large CSV/TSV files into Parquet or JSONL, parsed and transformed in Rust."""
+
+from ._internal import CsvOptions, transform_csv
+
+__all__ = [
+    "CsvOptions",
+    "transform_csv",
+]
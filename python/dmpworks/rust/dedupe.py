@@ -0,0 +1,7 @@
+"""Clustering of duplicate normalized works across sources."""
+
+from ._internal import dedupe_works
+
+__all__ = [
+    "dedupe_works",
+]
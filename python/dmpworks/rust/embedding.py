@@ -0,0 +1,9 @@
+"""Preparation of work titles and abstracts as input text for dense vector embedding models."""
+
+from ._internal import EmbeddingTextOptions, prepare_embedding_text, prepare_embedding_text_many
+
+__all__ = [
+    "EmbeddingTextOptions",
+    "prepare_embedding_text",
+    "prepare_embedding_text_many",
+]
@@ -0,0 +1,32 @@
+"""Ranking metrics for evaluating DMP works-search results against ground truth, computed in parallel.
+
+`pr_curve` and `choose_threshold` evaluate scored DMP–work pairs labelled as matches or not, for choosing the score at
+which pairs are accepted as matches.
+
+`load_ground_truth` reads labelled DMP–work pairs from CSV or JSONL files with `dmp_doi`, `work_doi`, `status` and
+optional `reviewer` columns, validating every row and raising a `ValueError` listing the invalid ones, and
+`save_ground_truth` writes them back as `GroundTruthRecord` objects. `cohen_kappa` and `krippendorff_alpha` measure how
+far reviewers agree on the labels of the same pairs beyond chance.
+"""
+
+from ._internal import (
+    choose_threshold,
+    cohen_kappa,
+    GroundTruthRecord,
+    krippendorff_alpha,
+    load_ground_truth,
+    pr_curve,
+    ranking_metrics,
+    save_ground_truth,
+)
+
+__all__ = [
+    "choose_threshold",
+    "cohen_kappa",
+    "GroundTruthRecord",
+    "krippendorff_alpha",
+    "load_ground_truth",
+    "pr_curve",
+    "ranking_metrics",
+    "save_ground_truth",
+]
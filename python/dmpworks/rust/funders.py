@@ -0,0 +1,9 @@
+"""Funder name normalization and matching of free-text funder names against a preloaded funder registry."""
+
+from ._internal import FunderRegistry, match_funder, normalize_funder_name
+
+__all__ = [
+    "FunderRegistry",
+    "match_funder",
+    "normalize_funder_name",
+]
@@ -0,0 +1,12 @@
+"""Rank fusion and score normalization for merging a DMP's lexical, k-NN and feature-based candidate rankings.
+
+Rankings are lists of (document ID, score) pairs, best first. "rrf" fuses them by rank alone, while "combsum" and
+"combmnz" add up their scores, optionally "minmax" or "zscore" normalized within each ranking first.
+"""
+
+from ._internal import fuse_rankings, normalize_scores
+
+__all__ = [
+    "fuse_rankings",
+    "normalize_scores",
+]
@@ -0,0 +1,15 @@
+"""Institution name normalization and matching of affiliations against a preloaded index of institution aliases.
+
+`InstitutionIndex.from_ror_dump` builds an index from a ROR data dump, optionally with local aliases and acronyms,
+once per process, after which `match_institution` and `match_institution_many` resolve names with exact, acronym and
+fuzzy lookups without calling back into Python.
+"""
+
+from ._internal import InstitutionIndex, match_institution, match_institution_many, normalize_institution_name
+
+__all__ = [
+    "InstitutionIndex",
+    "match_institution",
+    "match_institution_many",
+    "normalize_institution_name",
+]
@@ -0,0 +1,8 @@
+"""RAKE keyphrase extraction from abstracts, for comparing DMP research topics against works."""
+
+from ._internal import extract_keyphrases, extract_keyphrases_many
+
+__all__ = [
+    "extract_keyphrases",
+    "extract_keyphrases_many",
+]
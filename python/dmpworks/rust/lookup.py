@@ -0,0 +1,12 @@
+"""Read-only lookup tables of normalized keys and their values, e.g. funder, institution or nickname aliases.
+
+`LookupTable.build` writes a table to a compact file once, and each worker process then opens it with
+`LookupTable(path)`, which memory-maps the file rather than loading it, so that all workers share one copy of the
+table in memory. Tables pickle as their path, so they can also be passed to `multiprocessing` workers directly.
+"""
+
+from ._internal import LookupTable
+
+__all__ = [
+    "LookupTable",
+]
@@ -0,0 +1,8 @@
+"""OpenSearch settings and mappings of the works and DMP indexes, generated from one definition in Rust."""
+
+from ._internal import generate_index_mapping, IndexSchemaOptions
+
+__all__ = [
+    "generate_index_mapping",
+    "IndexSchemaOptions",
+]
@@ -0,0 +1,13 @@
+"""MinHash signatures and LSH banding for blocking near-duplicate titles and abstracts before pairwise comparison."""
+
+from ._internal import (
+    lsh_candidate_pairs,
+    minhash_signature,
+    minhash_signature_many,
+)
+
+__all__ = [
+    "lsh_candidate_pairs",
+    "minhash_signature",
+    "minhash_signature_many",
+]
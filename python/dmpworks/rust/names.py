@@ -0,0 +1,15 @@
+"""Name parsing returning `ParsedName` objects with named attributes rather than tuples.
+
+`ParsedName` here is a class with the fields of the `dmpworks.rust.ParsedName` named tuple plus the name's suffix and
+title. It compares equal by value, is hashable and converts to a dict with `to_dict()`. It can be passed wherever a
+parsed name is accepted, e.g. to `compare_names`, `author_overlap` and `initials_compatible`.
+"""
+
+from ._internal import parse_name_many_struct, parse_name_struct, parse_names_list_struct, ParsedName
+
+__all__ = [
+    "parse_name_many_struct",
+    "parse_name_struct",
+    "parse_names_list_struct",
+    "ParsedName",
+]
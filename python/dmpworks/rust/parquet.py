@@ -0,0 +1,30 @@
+"""Parquet input and output of normalized works and DMPs' ranked candidates, read and written directly from Rust.
+
+Also diffs works snapshots. Candidate files hold the candidates of one DMP or, written with `write_candidates_many`, of
+many, and keep each candidate's feature scores and the query legs that retrieved it, so that a ranking can be explained
+without running the search again.
+"""
+
+from ._internal import (
+    ParquetWriteOptions,
+    WorksFilter,
+    WorksParquetReader,
+    diff_snapshots,
+    read_candidates,
+    read_works_parquet,
+    write_candidates,
+    write_candidates_many,
+    write_works_parquet,
+)
+
+__all__ = [
+    "ParquetWriteOptions",
+    "WorksFilter",
+    "WorksParquetReader",
+    "diff_snapshots",
+    "read_candidates",
+    "read_works_parquet",
+    "write_candidates",
+    "write_candidates_many",
+    "write_works_parquet",
+]
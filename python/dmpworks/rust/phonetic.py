@@ -0,0 +1,5 @@
+"""Phonetic encodings of names, under which surnames whose spellings vary across sources share a code."""
+
+from ._internal import phonetic_encode
+
+__all__ = ["phonetic_encode"]
@@ -0,0 +1,12 @@
+"""Declarative per-field transform pipelines run over Arrow record batches in Rust.
+
+A `Pipeline` maps field names to ordered lists of built-in transforms, e.g.
+`{"abstract": ["revert_inverted_index", "strip_markup", "clean_text", ("truncate", 512)]}`, and `run` applies every
+chain to a record batch in one call, returning a batch with the transformed columns as strings.
+"""
+
+from ._internal import Pipeline
+
+__all__ = [
+    "Pipeline",
+]
@@ -0,0 +1,11 @@
+"""Data-quality profiles of text fields, for reports that catch upstream schema and content regressions per snapshot.
+
+`profile_texts` takes an Arrow string or binary array, e.g. a column of a Parquet snapshot, and counts its null, blank,
+placeholder and invalid UTF-8 values, with the length distribution, markup remnants and language mix of the rest.
+"""
+
+from ._internal import profile_texts
+
+__all__ = [
+    "profile_texts",
+]
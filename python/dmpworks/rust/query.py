@@ -0,0 +1,14 @@
+"""OpenSearch works search query generation, shared by the live pipeline and the offline evaluator.
+
+`parse_msearch_response` merges the hits of an msearch response's sub-queries into one candidate list, each candidate
+a dict of its "key" (the DOI or ID it was deduplicated by), "id", fused "score", per sub-query "ranks" and "scores",
+"matched_queries" and "source".
+"""
+
+from ._internal import build_work_query, parse_msearch_response, WorkQueryOptions
+
+__all__ = [
+    "build_work_query",
+    "parse_msearch_response",
+    "WorkQueryOptions",
+]
@@ -0,0 +1,14 @@
+"""Stratified reservoir sampling of DMP–work candidate pairs for building manual review sets.
+
+`generate_negative_pairs` samples reproducible negative pairs for training a classifier: random works, and "hard"
+negatives sharing a funder or institution with the DMP, excluding the DMP's known matches.
+"""
+
+from ._internal import generate_negative_pairs, HardNegativeOptions, sample_candidate_pairs, SamplingStrategy
+
+__all__ = [
+    "generate_negative_pairs",
+    "HardNegativeOptions",
+    "sample_candidate_pairs",
+    "SamplingStrategy",
+]
@@ -0,0 +1,19 @@
+"""Feature vectors and weighted scores of candidate works for re-ranking DMP–work matches.
+
+The features are "title", "authors", "affiliations", "funders", "date" and "doi", each between 0 and 1 or None when
+either the DMP or the work lacks the data. By default they are weighted 1.0, 2.0, 0.5, 1.0, 0.5 and 3.0 respectively.
+
+`MatchModel` scores candidates with a logistic regression model trained offline on the same features, loaded from
+JSON such as `{"intercept": -4.2, "weights": {"title": 3.1, "authors": 2.4}, "fill_values": {"date": 0.5}}`, where
+`fill_values` are imputed for missing features, which otherwise count as 0.0. `featurize_pairs` computes the features
+of labeled DMP–work pairs as a float32 matrix for training such a model, e.g. in scikit-learn.
+"""
+
+from ._internal import MatchModel, featurize_pairs, score_candidate, score_candidates
+
+__all__ = [
+    "MatchModel",
+    "featurize_pairs",
+    "score_candidate",
+    "score_candidates",
+]
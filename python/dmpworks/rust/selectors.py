@@ -0,0 +1,9 @@
+"""Configurable field extraction from raw JSON records with dotted and indexed path selectors, compiled in Rust."""
+
+from ._internal import extract_fields, extract_fields_many, FieldSelectors
+
+__all__ = [
+    "extract_fields",
+    "extract_fields_many",
+    "FieldSelectors",
+]
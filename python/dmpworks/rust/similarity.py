@@ -0,0 +1,27 @@
+"""Fuzzy string similarity kernels for title and funder matching, as single pairs or pairwise over two columns."""
+
+from ._internal import (
+    containment_score,
+    containment_score_many,
+    jaro_winkler,
+    jaro_winkler_many,
+    normalized_levenshtein,
+    normalized_levenshtein_many,
+    token_set_ratio,
+    token_set_ratio_many,
+    token_sort_ratio,
+    token_sort_ratio_many,
+)
+
+__all__ = [
+    "containment_score",
+    "containment_score_many",
+    "jaro_winkler",
+    "jaro_winkler_many",
+    "normalized_levenshtein",
+    "normalized_levenshtein_many",
+    "token_set_ratio",
+    "token_set_ratio_many",
+    "token_sort_ratio",
+    "token_sort_ratio_many",
+]
@@ -0,0 +1,11 @@
+"""Sorting and deduplication of JSONL files larger than memory, by a key selected from each record.
+
+`external_sort` sorts chunks of the input in memory and merges them from temporary files, returning the number of
+records written and the number of duplicates dropped, e.g. to prepare works files sorted by DOI for `diff_snapshots`.
+"""
+
+from ._internal import external_sort
+
+__all__ = [
+    "external_sort",
+]
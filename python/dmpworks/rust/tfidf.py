@@ -0,0 +1,14 @@
+"""TF-IDF cosine similarity of DMP narratives and work abstracts, as single pairs or pairwise over two columns.
+
+An `IdfTable` holds the document frequencies of a works corpus. It is built incrementally with `update`, one batch of
+texts at a time, or streamed from a JSONL file of works with `update_from_jsonl`, and tokenizes documents and compared
+texts alike with its `TokenizerOptions`.
+"""
+
+from ._internal import IdfTable, tfidf_cosine, tfidf_cosine_many
+
+__all__ = [
+    "IdfTable",
+    "tfidf_cosine",
+    "tfidf_cosine_many",
+]
@@ -0,0 +1,8 @@
+"""Dense vector similarity over float32 numpy arrays, for re-ranking search candidates with precomputed embeddings."""
+
+from ._internal import cosine_similarity, top_k
+
+__all__ = [
+    "cosine_similarity",
+    "top_k",
+]
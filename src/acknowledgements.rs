@@ -0,0 +1,214 @@
+use crate::core::{
+    clean_text, compare_award_ids, is_organization, normalize_award_id, split_sentences,
+    NIH_GRANT_RE, NSF_ORG_AWARD_RE,
+};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Words and phrases marking a sentence as a funding acknowledgement, e.g. "funded by", "financial support" or
+/// "grant No.".
+static FUNDING_CUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:funded|funding|funds|financed|sponsored|supported\s+(?:in\s+part\s+|partly\s+|partially\s+)?by|financial(?:ly)?\s+support|acknowledges?|grants?|awards?|fellowships?|contracts?\s+(?:no|number))\b",
+    )
+    .unwrap()
+});
+
+/// Phrases introducing the funders of a funding acknowledgement, e.g. "supported by", "a grant from", "grant No. 12345
+/// from" or "and by". The `thanked` group marks phrases that also introduce people, e.g. "thank", and the `continued`
+/// group an award followed by more funders, e.g. "grant DMR 1507101 and the NIH", whose names must be funder names.
+static FUNDER_INTRODUCTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?:funded|financed|sponsored|supported|funding|support)\s+(?:(?:in\s+part|partly|partially|jointly|generously)\s+)?(?:(?:is|was|were)\s+)?(?:provided\s+)?(?:by|from|of)|(?:grants?|awards?|fellowships?|contracts?)(?:\s+(?:nos?\.?|numbers?|#))?(?:\s+[A-Z0-9/.\-]*[0-9][A-Z0-9/.\-]*)?\s+from|(?:and|also)\s+by|(?P<thanked>thanks?|grateful\s+to)|(?P<continued>[0-9][A-Z0-9/.\-]*,?\s+(?:and|&)(?:\s+(?:by|from))?))\s+",
+    )
+    .unwrap()
+});
+
+/// Where the names introduced by a `thanked` phrase of `FUNDER_INTRODUCTION_RE` end, before what they are thanked for.
+static THANKED_END_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\s+for\b").unwrap());
+
+/// Where the funders introduced by `FUNDER_INTRODUCTION_RE` end: at the award they give, e.g. "grant No.", a
+/// preposition continuing the sentence, e.g. "under" or "through", or a parenthesis or the end of a clause. Full stops
+/// don't end them, as they are in names such as "Alfred P. Sloan Foundation".
+static FUNDERS_END_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\s+(?:under|through|via|with|within|as\s+part|in\s+the\s+framework)\b|\s*\b(?:grants?|awards?|contracts?|projects?|fellowships?|agreements?|no\.|number)\b|\s*[(\[:;]|\s*,\s*(?:which|who|and\s+by)\b",
+    )
+    .unwrap()
+});
+
+/// Parentheses without digits after a funder's name, e.g. the acronym of "Medical Research Council (MRC)", which
+/// list funders continue after.
+static FUNDER_ASIDE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\([^()0-9]*\)").unwrap());
+
+/// Separators of a list of funders, e.g. "the NSF, the NIH and the Wellcome Trust".
+static FUNDER_SEPARATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*,\s*(?:and\s+|&\s*)?|\s+(?:and|&)\s+").unwrap());
+
+/// Award numbers after a label, alone or in a list, e.g. "grant No. 757123" or "awards EP/K503757/1 and
+/// EP/N509711/1".
+static LABELLED_AWARDS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:grants?|awards?|contracts?|projects?|agreements?|fellowships?)\s*(?:nos?\.?|numbers?|#|ids?)?\s*[:#]?\s*([A-Z0-9/.\-]*[0-9][A-Z0-9/.\-]*(?:\s*(?:,|and|&)\s*[A-Z0-9/.\-]*[0-9][A-Z0-9/.\-]*)*)",
+    )
+    .unwrap()
+});
+
+/// An award number of a list matched by `LABELLED_AWARDS_RE`.
+static AWARD_NUMBER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)[A-Z0-9/.\-]*[0-9][A-Z0-9/.\-]*").unwrap());
+
+/// Words of funder names that `is_organization` doesn't know, e.g. the "Trust" of "Wellcome Trust", compared
+/// lowercased.
+const FUNDER_WORDS: &[&str] = &["academy", "endowment", "fund", "funds", "trust"];
+
+/// A funding acknowledgement found in text, see `extract_funding_statements`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FundingStatement {
+    pub sentence: String,
+    /// The names of the funders as written, e.g. "National Science Foundation" or "NSF", in order.
+    pub funders: Vec<String>,
+    /// The award numbers, normalized with `normalize_award_id`, in order.
+    pub award_ids: Vec<String>,
+}
+
+/// Whether a funder name is an organization's name or acronym, rather than the first part of a name containing "and",
+/// e.g. "Engineering" of "Engineering and Physical Sciences Research Council".
+fn is_funder_name(name: &str) -> bool {
+    let is_acronym = |word: &str| {
+        (2..=8).contains(&word.len()) && word.chars().all(|c| c.is_ascii_uppercase() || c == '-')
+    };
+    is_organization(name)
+        || name
+            .split_whitespace()
+            .any(|word| is_acronym(word) || FUNDER_WORDS.contains(&word.to_lowercase().as_str()))
+}
+
+/// Trims a funder name of articles, punctuation and dashes, returning None for text that can't be a name, such as "our
+/// department" or a number.
+fn clean_funder_name(name: &str) -> Option<&str> {
+    let name = name.trim_matches(|c: char| {
+        c.is_whitespace() || matches!(c, ',' | '.' | '"' | '-' | '–' | '—')
+    });
+    let name = ["the ", "The ", "a ", "an "]
+        .iter()
+        .find_map(|article| name.strip_prefix(article))
+        .unwrap_or(name)
+        .trim();
+    let starts_uppercase = name.chars().next().is_some_and(char::is_uppercase);
+    (starts_uppercase && name.chars().filter(|c| c.is_alphabetic()).count() >= 2).then_some(name)
+}
+
+/// Splits the funders introduced in a sentence, e.g. "the NSF and the National Institutes of Health", into their
+/// names. Names are split at commas, and at "and" unless the part before it isn't a funder name of its own.
+fn split_funders(funders: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut start = 0;
+    for separator in FUNDER_SEPARATOR_RE.find_iter(funders) {
+        let before = &funders[start..separator.start()];
+        let is_comma = separator.as_str().contains(',');
+        let after = &funders[separator.end()..];
+        if is_comma || is_funder_name(before) || after.starts_with("the ") {
+            names.push(before);
+            start = separator.end();
+        }
+    }
+    names.push(&funders[start..]);
+    names.into_iter().filter_map(clean_funder_name).collect()
+}
+
+/// Finds the names of the funders introduced in a sentence, e.g. "supported by the NSF and the NIH".
+fn find_funders(sentence: &str) -> Vec<String> {
+    let mut funders: Vec<String> = Vec::new();
+    for introduction in FUNDER_INTRODUCTION_RE.captures_iter(sentence) {
+        let thanked = introduction.name("thanked").is_some();
+        let strict = thanked || introduction.name("continued").is_some();
+        let rest = FUNDER_ASIDE_RE.replace_all(&sentence[introduction.get(0).unwrap().end()..], "");
+        let mut end = FUNDERS_END_RE.find(&rest).map_or(rest.len(), |m| m.start());
+        if thanked {
+            end = THANKED_END_RE.find(&rest[..end]).map_or(end, |m| m.start());
+        }
+        for name in split_funders(&rest[..end]) {
+            if strict && !is_funder_name(name) {
+                continue;
+            }
+            if !funders
+                .iter()
+                .any(|funder| funder.eq_ignore_ascii_case(name))
+            {
+                funders.push(name.to_string());
+            }
+        }
+    }
+    funders
+}
+
+/// Finds the award numbers in a sentence, in formats specific enough to find without a label, e.g. "R01GM123456" or
+/// "DBI-1234567", or after one, e.g. "grant No. 757123", by their position.
+fn find_award_numbers(sentence: &str) -> Vec<(usize, &str)> {
+    let mut numbers: Vec<(usize, &str)> = NIH_GRANT_RE
+        .find_iter(sentence)
+        .chain(NSF_ORG_AWARD_RE.find_iter(sentence))
+        .map(|m| (m.start(), m.as_str()))
+        .collect();
+    for caps in LABELLED_AWARDS_RE.captures_iter(sentence) {
+        let list = caps.get(1).unwrap();
+        for number in AWARD_NUMBER_RE.find_iter(list.as_str()) {
+            let start = list.start() + number.start();
+            let number = number.as_str().trim_matches(['/', '.', '-']);
+            // Too short to be an award rather than e.g. "project 2"
+            if number.chars().filter(char::is_ascii_alphanumeric).count() >= 4 {
+                numbers.push((start, number));
+            }
+        }
+    }
+    numbers.sort_by_key(|(start, _)| *start);
+    numbers
+}
+
+/// Finds the award IDs in a sentence, normalized with `normalize_award_id`, taking the funder's formats when the
+/// sentence names a single funder. An award found both with and without a prefix, e.g. "DBI-1234567" and "1234567",
+/// is kept in its longer form.
+fn find_award_ids(sentence: &str, funders: &[String]) -> Vec<String> {
+    let funder_hint = match funders {
+        [funder] => Some(funder.as_str()),
+        _ => None,
+    };
+    let mut award_ids: Vec<String> = Vec::new();
+    for (_, number) in find_award_numbers(sentence) {
+        let Some(award_id) = normalize_award_id(Some(number), funder_hint) else {
+            continue;
+        };
+        match award_ids
+            .iter_mut()
+            .find(|existing| compare_award_ids(Some(existing), Some(&award_id)))
+        {
+            Some(existing) if award_id.len() > existing.len() => *existing = award_id,
+            Some(_) => {},
+            None => award_ids.push(award_id),
+        }
+    }
+    award_ids
+}
+
+/// Finds the funding acknowledgements in free text, such as a work's abstract or a snippet of its full text, and
+/// pulls the funders and award numbers from them, e.g. "This work was supported by the National Science Foundation
+/// under grant DBI-1234567." gives the funder "National Science Foundation" and the award "DBI-1234567". Sentences
+/// are funding acknowledgements when they have a funding phrase, e.g. "funded by" or "grant No.", and name a funder
+/// or an award, in order of appearance.
+pub fn extract_funding_statements(text: &str) -> Vec<FundingStatement> {
+    split_sentences(&clean_text(text))
+        .into_iter()
+        .filter(|sentence| FUNDING_CUE_RE.is_match(sentence))
+        .filter_map(|sentence| {
+            let funders = find_funders(&sentence);
+            let award_ids = find_award_ids(&sentence, &funders);
+            (!funders.is_empty() || !award_ids.is_empty()).then_some(FundingStatement {
+                sentence,
+                funders,
+                award_ids,
+            })
+        })
+        .collect()
+}
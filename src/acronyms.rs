@@ -0,0 +1,169 @@
+use crate::funders::FUNDER_ACRONYMS;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Words, with hyphenated and apostrophized parts kept together, e.g. "COVID-19".
+static WORD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\p{L}\p{N}]+(?:[-'’][\p{L}\p{N}]+)*").unwrap());
+
+/// Parenthetical asides, which may define an acronym, e.g. "Legacy Survey of Space and Time (LSST)".
+static PARENTHETICAL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\(([^()]*)\)").unwrap());
+
+/// Funder acronyms and their expansions, the default dictionary of `expand_acronyms`.
+static FUNDER_ACRONYM_DICTIONARY: LazyLock<HashMap<String, String>> = LazyLock::new(|| {
+    FUNDER_ACRONYMS
+        .iter()
+        .map(|(acronym, expansion)| (acronym.to_string(), expansion.to_string()))
+        .collect()
+});
+
+/// The acronym a word is, if any: a word with at least two capitals and no more lowercase letters than capitals,
+/// other than a Roman numeral, e.g. "LSST", "mRNA" or "COVID-19". A plural "s" is dropped, so "DMPs" gives "DMP".
+fn as_acronym(word: &str) -> Option<&str> {
+    let word = match word.strip_suffix('s') {
+        Some(singular) if singular.len() >= 2 && !singular.chars().any(char::is_lowercase) => {
+            singular
+        },
+        _ => word,
+    };
+    let upper = word.chars().filter(|c| c.is_uppercase()).count();
+    let lower = word.chars().filter(|c| c.is_lowercase()).count();
+    let roman_numeral = word.chars().all(|c| matches!(c, 'I' | 'V' | 'X'));
+    (upper >= 2 && upper >= lower && !roman_numeral).then_some(word)
+}
+
+/// Finds the acronyms in a text with their byte ranges, in order. Text where most words are acronyms, e.g. a title
+/// in upper case, has no distinguishable acronyms.
+fn acronym_spans(text: &str) -> Vec<(&str, Range<usize>)> {
+    let words: Vec<_> = WORD_RE.find_iter(text).collect();
+    let spans: Vec<(&str, Range<usize>)> = words
+        .iter()
+        .filter_map(|word| as_acronym(word.as_str()).map(|acronym| (acronym, word.range())))
+        .collect();
+    if words.len() >= 3 && spans.len() * 2 > words.len() {
+        return Vec::new();
+    }
+    spans
+}
+
+/// Finds the start of the expansion of `acronym` at the end of `candidate` with the Schwartz–Hearst algorithm:
+/// working backwards, each letter and digit of the acronym must match a character to the left of the previous match,
+/// with the first starting a word. Returns the byte offset the expansion starts at.
+fn long_form_start(acronym: &str, candidate: &str) -> Option<usize> {
+    let short: Vec<char> = acronym
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let long: Vec<(usize, char)> = candidate.char_indices().collect();
+
+    let mut position = long.len();
+    for (i, c) in short.iter().enumerate().rev() {
+        loop {
+            position = position.checked_sub(1)?;
+            let matches = long[position].1.to_lowercase().eq(std::iter::once(*c));
+            let word_start = position == 0 || !long[position - 1].1.is_alphanumeric();
+            if matches && (i > 0 || word_start) {
+                break;
+            }
+        }
+    }
+    (!short.is_empty()).then(|| long[position].0)
+}
+
+/// Checks that an expansion is plausible: at least two words, and longer than its acronym.
+fn is_expansion(acronym: &str, expansion: &str) -> bool {
+    expansion.split_whitespace().count() >= 2 && expansion.len() > acronym.len()
+}
+
+/// Finds the acronyms a text defines in parentheses, either after their expansion, "Legacy Survey of Space and Time
+/// (LSST)", or before it, "LSST (Legacy Survey of Space and Time)". The expansion before an acronym is looked for
+/// within its clause and at most `min(n + 5, 2n)` words back, for an acronym of n characters, as in Schwartz and
+/// Hearst (2003). The first definition of an acronym is kept.
+fn acronym_definitions(text: &str) -> HashMap<&str, String> {
+    let mut definitions = HashMap::new();
+    for caps in PARENTHETICAL_RE.captures_iter(text) {
+        let (whole, inner) = (caps.get(0).unwrap(), caps.get(1).unwrap());
+        let inner = inner.as_str().trim();
+        let before = text[..whole.start()].trim_end();
+
+        let single_word = WORD_RE
+            .find(inner)
+            .is_some_and(|word| word.range() == (0..inner.len()));
+        let definition = match as_acronym(inner).filter(|_| single_word) {
+            Some(acronym) => {
+                let clause_start = before
+                    .rfind(['.', ';', ':', '!', '?', '(', ')', '[', ']'])
+                    .map_or(0, |i| i + 1);
+                let letters = acronym.chars().filter(|c| c.is_alphanumeric()).count();
+                let words: Vec<&str> = before[clause_start..].split_whitespace().collect();
+                let window =
+                    words[words.len().saturating_sub((letters + 5).min(letters * 2))..].join(" ");
+                long_form_start(acronym, &window)
+                    .map(|start| (acronym, window[start..].to_string()))
+            },
+            None => WORD_RE
+                .find_iter(before)
+                .last()
+                .filter(|word| word.end() == before.len())
+                .and_then(|word| as_acronym(word.as_str()))
+                .and_then(|acronym| {
+                    long_form_start(acronym, inner)
+                        .map(|start| (acronym, inner[start..].to_string()))
+                }),
+        };
+        if let Some((acronym, expansion)) =
+            definition.filter(|(acronym, expansion)| is_expansion(acronym, expansion))
+        {
+            definitions.entry(acronym).or_insert(expansion);
+        }
+    }
+    definitions
+}
+
+/// Extracts the distinct acronyms in a text, e.g. from a DMP title or funder name, in order of first occurrence,
+/// each with the expansion the text defines it with, if any (see `acronym_definitions`).
+pub fn extract_acronyms(text: &str) -> Vec<(String, Option<String>)> {
+    let definitions = acronym_definitions(text);
+    let mut seen = HashSet::new();
+    acronym_spans(text)
+        .into_iter()
+        .filter(|(acronym, _)| seen.insert(*acronym))
+        .map(|(acronym, _)| {
+            let expansion = definitions.get(acronym).cloned();
+            (acronym.to_string(), expansion)
+        })
+        .collect()
+}
+
+/// Expands the acronyms in a text that are in `dictionary`, or in the built-in funder acronyms when None, by
+/// inserting the expansion in parentheses after the first occurrence of each acronym, so that the text matches both:
+/// "LSST data" becomes "LSST (Legacy Survey of Space and Time) data". Acronyms the text already defines, or whose
+/// expansion already appears in it, are left alone.
+pub fn expand_acronyms(text: &str, dictionary: Option<&HashMap<String, String>>) -> String {
+    let dictionary = dictionary.unwrap_or(&FUNDER_ACRONYM_DICTIONARY);
+    let definitions = acronym_definitions(text);
+    let lowercase = text.to_lowercase();
+
+    let mut expanded = String::with_capacity(text.len());
+    let mut last_end = 0;
+    let mut seen = HashSet::new();
+    for (acronym, range) in acronym_spans(text) {
+        if !seen.insert(acronym) || definitions.contains_key(acronym) {
+            continue;
+        }
+        let Some(expansion) = dictionary.get(acronym) else {
+            continue;
+        };
+        if lowercase.contains(&expansion.to_lowercase()) {
+            continue;
+        }
+        expanded.push_str(&text[last_end..range.end]);
+        expanded.push_str(&format!(" ({expansion})"));
+        last_end = range.end;
+    }
+    expanded.push_str(&text[last_end..]);
+    expanded
+}
@@ -0,0 +1,206 @@
+use crate::core::{
+    fold_name, normalize_doi, normalize_ror, parse_date, parse_name, tokenize_with_options,
+    TokenizerOptions, ENGLISH_STOP_WORDS,
+};
+use crate::funders::normalize_funder_name;
+use rayon::prelude::*;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Number of leading title words in a `TitlePrefix` key.
+const TITLE_PREFIX_WORDS: usize = 3;
+
+/// Years after a project's end that its outputs are still keyed under, as they are often published later.
+const OUTPUT_LAG_YEARS: i32 = 2;
+
+/// Most years a project's keys span, so that a mistyped end date doesn't key it under every year.
+const MAX_PROJECT_YEARS: i32 = 10;
+
+/// A way of grouping works and DMPs that may match into blocks, so that only pairs sharing a key are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockingScheme {
+    /// Each author's folded surname and first initial with the year, e.g. "surname_initial_year:smith_j_2020".
+    SurnameInitialYear,
+    /// The first three title words, folded and without stop words, e.g. "title_prefix:soil_moisture_dynamics".
+    TitlePrefix,
+    /// Each funder's ROR ID, Funder Registry DOI and normalized name with the year, e.g.
+    /// "funder_year:021nxhr62_2020".
+    FunderYear,
+}
+
+impl BlockingScheme {
+    pub const ALL: [BlockingScheme; 3] = [
+        BlockingScheme::SurnameInitialYear,
+        BlockingScheme::TitlePrefix,
+        BlockingScheme::FunderYear,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "surname_initial_year" => Some(BlockingScheme::SurnameInitialYear),
+            "title_prefix" => Some(BlockingScheme::TitlePrefix),
+            "funder_year" => Some(BlockingScheme::FunderYear),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BlockingScheme::SurnameInitialYear => "surname_initial_year",
+            BlockingScheme::TitlePrefix => "title_prefix",
+            BlockingScheme::FunderYear => "funder_year",
+        }
+    }
+}
+
+/// An author of a work or DMP, as in the records of `transform_works_many` and `transform_dmp`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BlockingAuthor {
+    pub surname: Option<String>,
+    pub first_initial: Option<String>,
+    pub full: Option<String>,
+}
+
+/// A funder of a work or DMP.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BlockingFunder {
+    pub name: Option<String>,
+    pub ror: Option<String>,
+    pub funder_doi: Option<String>,
+}
+
+/// A funding item of a DMP, naming its funder.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BlockingFunding {
+    pub funder: BlockingFunder,
+}
+
+/// The fields of a normalized work or DMP that blocking keys are built from. A work is dated by its publication
+/// year or date, and a DMP by its project's start and end dates.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BlockingRecord {
+    pub title: Option<String>,
+    pub authors: Vec<BlockingAuthor>,
+    pub publication_year: Option<i32>,
+    pub publication_date: Option<String>,
+    pub project_start: Option<String>,
+    pub project_end: Option<String>,
+    /// The funders of a work.
+    pub funders: Vec<BlockingFunder>,
+    /// The funding of a DMP.
+    pub funding: Vec<BlockingFunding>,
+}
+
+impl BlockingRecord {
+    /// The years the record is keyed under: a work's publication year, or each year of a DMP's project from its
+    /// start to `OUTPUT_LAG_YEARS` after its end, at most `MAX_PROJECT_YEARS` in all.
+    fn years(&self) -> Vec<i32> {
+        let year = |date: &Option<String>| parse_date(date.as_deref()).map(|date| date.year);
+        if let Some(year) = self
+            .publication_year
+            .or_else(|| year(&self.publication_date))
+        {
+            return vec![year];
+        }
+        match (year(&self.project_start), year(&self.project_end)) {
+            (Some(start), end) => {
+                let end = end.unwrap_or(start).max(start) + OUTPUT_LAG_YEARS;
+                (start..=end.min(start + MAX_PROJECT_YEARS - 1)).collect()
+            },
+            (None, Some(end)) => vec![end],
+            (None, None) => Vec::new(),
+        }
+    }
+
+    fn all_funders(&self) -> impl Iterator<Item = &BlockingFunder> {
+        self.funders
+            .iter()
+            .chain(self.funding.iter().map(|funding| &funding.funder))
+    }
+}
+
+/// Reduces a folded part of a key to its letters and digits.
+fn key_part(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// The folded surname and first initial of an author, parsing their full name when the parts are missing.
+fn surname_initial(author: &BlockingAuthor) -> Option<(String, String)> {
+    let parsed;
+    let (surname, initial) = match (&author.surname, &author.first_initial) {
+        (Some(surname), Some(initial)) => (surname, initial),
+        _ => {
+            parsed = parse_name(None, None, author.full.as_deref(), false);
+            (parsed.surname.as_ref()?, parsed.first_initial.as_ref()?)
+        },
+    };
+    let surname = key_part(&fold_name(surname));
+    let initial = key_part(&fold_name(initial)).chars().next()?.to_string();
+    (!surname.is_empty()).then_some((surname, initial))
+}
+
+/// The identifiers of a funder used as keys: its ROR ID, Funder Registry DOI and normalized name.
+fn funder_ids(funder: &BlockingFunder) -> Vec<String> {
+    let ror = normalize_ror(funder.ror.as_deref());
+    let doi = normalize_doi(funder.funder_doi.as_deref()).map(|doi| doi.replace('/', "."));
+    let name = normalize_funder_name(funder.name.as_deref()).map(|name| name.replace(' ', "-"));
+    [ror, doi, name].into_iter().flatten().collect()
+}
+
+/// Builds the blocking keys of a work or DMP under each scheme, prefixed with the scheme's name so that keys of
+/// different schemes never collide, deduplicated in order. A record without the fields a scheme needs, e.g. a year,
+/// has no keys under it, and titles shorter than three words key all of their words.
+pub fn blocking_keys(record: &BlockingRecord, schemes: &[BlockingScheme]) -> Vec<String> {
+    let years = record.years();
+    let mut keys = Vec::new();
+    for scheme in schemes {
+        let prefix = scheme.as_str();
+        match scheme {
+            BlockingScheme::SurnameInitialYear => {
+                for (surname, initial) in record.authors.iter().filter_map(surname_initial) {
+                    for year in &years {
+                        keys.push(format!("{prefix}:{surname}_{initial}_{year}"));
+                    }
+                }
+            },
+            BlockingScheme::TitlePrefix => {
+                let options = TokenizerOptions {
+                    stop_words: ENGLISH_STOP_WORDS.iter().map(|w| w.to_string()).collect(),
+                    ..Default::default()
+                };
+                let words =
+                    tokenize_with_options(record.title.as_deref().unwrap_or_default(), &options);
+                if !words.is_empty() {
+                    let words = &words[..words.len().min(TITLE_PREFIX_WORDS)];
+                    keys.push(format!("{prefix}:{}", words.join("_")));
+                }
+            },
+            BlockingScheme::FunderYear => {
+                for id in record.all_funders().flat_map(funder_ids) {
+                    for year in &years {
+                        keys.push(format!("{prefix}:{id}_{year}"));
+                    }
+                }
+            },
+        }
+    }
+    let mut seen = HashSet::new();
+    keys.retain(|key| seen.insert(key.clone()));
+    keys
+}
+
+/// Builds the blocking keys of many records with `blocking_keys` in parallel on the current rayon thread pool,
+/// preserving order.
+pub fn blocking_keys_many(
+    records: &[BlockingRecord],
+    schemes: &[BlockingScheme],
+) -> Vec<Vec<String>> {
+    records
+        .par_iter()
+        .map(|record| blocking_keys(record, schemes))
+        .collect()
+}
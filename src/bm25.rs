@@ -0,0 +1,107 @@
+use crate::core::{tokenize_with_options, TokenizerOptions};
+use std::collections::HashMap;
+
+/// OpenSearch's default BM25 term frequency saturation.
+pub const DEFAULT_K1: f64 = 1.2;
+
+/// OpenSearch's default BM25 document length normalization.
+pub const DEFAULT_B: f64 = 0.75;
+
+/// A small in-memory BM25 index over candidate texts, for re-ranking a candidate pool offline.
+pub struct Bm25Index {
+    options: TokenizerOptions,
+    k1: f64,
+    b: f64,
+    /// Number of tokens in each document, in input order.
+    doc_lengths: Vec<f64>,
+    avg_doc_length: f64,
+    /// Terms mapped to the documents they occur in and their frequency in each.
+    postings: HashMap<String, Vec<(usize, u32)>>,
+}
+
+impl Bm25Index {
+    /// Builds an index over texts, tokenized with `options`. Missing texts are indexed as empty documents so that
+    /// document indexes match the input positions.
+    pub fn new(texts: &[Option<String>], options: TokenizerOptions, k1: f64, b: f64) -> Self {
+        let mut doc_lengths = Vec::with_capacity(texts.len());
+        let mut postings: HashMap<String, Vec<(usize, u32)>> = HashMap::new();
+        for (doc, text) in texts.iter().enumerate() {
+            let tokens = text
+                .as_deref()
+                .map(|text| tokenize_with_options(text, &options))
+                .unwrap_or_default();
+            doc_lengths.push(tokens.len() as f64);
+
+            let mut frequencies: HashMap<String, u32> = HashMap::new();
+            for token in tokens {
+                *frequencies.entry(token).or_insert(0) += 1;
+            }
+            for (term, frequency) in frequencies {
+                postings.entry(term).or_default().push((doc, frequency));
+            }
+        }
+
+        let total: f64 = doc_lengths.iter().sum();
+        let avg_doc_length = if doc_lengths.is_empty() {
+            0.0
+        } else {
+            total / doc_lengths.len() as f64
+        };
+        Self {
+            options,
+            k1,
+            b,
+            doc_lengths,
+            avg_doc_length,
+            postings,
+        }
+    }
+
+    /// Returns the number of documents in the index.
+    pub fn len(&self) -> usize {
+        self.doc_lengths.len()
+    }
+
+    /// Scores every document against a query, in document order, using Lucene's BM25 formula:
+    /// `idf * tf / (tf + k1 * (1 - b + b * dl / avgdl))` with `idf = ln(1 + (N - df + 0.5) / (df + 0.5))`, summed over
+    /// the query's tokens, so that a repeated query token counts once per occurrence. Unlike Lucene, document lengths
+    /// are exact rather than lossily encoded, so scores can differ from the cluster's in the last few digits.
+    pub fn score(&self, query: &str) -> Vec<f64> {
+        let mut scores = vec![0.0; self.len()];
+        let n = self.len() as f64;
+        for token in tokenize_with_options(query, &self.options) {
+            let Some(postings) = self.postings.get(&token) else {
+                continue;
+            };
+            let df = postings.len() as f64;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for &(doc, frequency) in postings {
+                let tf = f64::from(frequency);
+                let length_norm = if self.avg_doc_length > 0.0 {
+                    1.0 - self.b + self.b * self.doc_lengths[doc] / self.avg_doc_length
+                } else {
+                    1.0
+                };
+                scores[doc] += idf * tf / (tf + self.k1 * length_norm);
+            }
+        }
+        scores
+    }
+
+    /// Ranks the documents matching a query by descending score, breaking ties by document index so that rankings
+    /// are deterministic, and keeps the first `top_k` when given. Documents matching none of the query's tokens are
+    /// left out.
+    pub fn rank(&self, query: &str, top_k: Option<usize>) -> Vec<(usize, f64)> {
+        let mut ranked: Vec<(usize, f64)> = self
+            .score(query)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        if let Some(top_k) = top_k {
+            ranked.truncate(top_k);
+        }
+        ranked
+    }
+}
@@ -0,0 +1,68 @@
+use serde_json::{json, Value};
+
+/// Default maximum size of a bulk request body, well under OpenSearch's default `http.max_content_length` of 100 MB.
+pub const DEFAULT_MAX_CHUNK_BYTES: usize = 10 * 1024 * 1024;
+
+/// Default maximum number of actions in a bulk request body, as in the opensearch-py bulk helpers.
+pub const DEFAULT_MAX_CHUNK_ACTIONS: usize = 500;
+
+/// The document ID in a record's `id_field`: a string, or an integer formatted as one. None when the field is
+/// missing, null, an empty string or another type.
+pub fn document_id(record: &Value, id_field: &str) -> Option<String> {
+    match record.get(id_field)? {
+        Value::String(id) if !id.is_empty() => Some(id.clone()),
+        Value::Number(id) if id.is_i64() || id.is_u64() => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Serializes a record as a bulk index action: the action line, naming the index and the document ID if there is
+/// one, followed by the source line, each terminated by a newline. JSON escapes any newlines within string values,
+/// so each line is a single JSON object.
+pub fn bulk_action(record: &Value, index_name: &str, id: Option<&str>) -> String {
+    let action = match id {
+        Some(id) => json!({"index": {"_index": index_name, "_id": id}}),
+        None => json!({"index": {"_index": index_name}}),
+    };
+    format!("{action}\n{record}\n")
+}
+
+/// Groups bulk actions into NDJSON bulk request bodies of at most `max_bytes` bytes and `max_actions` actions. An
+/// action larger than `max_bytes` on its own gets a body to itself, as it can't be split.
+#[derive(Debug)]
+pub struct BulkChunker {
+    max_bytes: usize,
+    max_actions: usize,
+    body: String,
+    actions: usize,
+}
+
+impl BulkChunker {
+    pub fn new(max_bytes: usize, max_actions: usize) -> Self {
+        Self {
+            max_bytes,
+            max_actions,
+            body: String::new(),
+            actions: 0,
+        }
+    }
+
+    /// Adds an action, returning the previous body when the action doesn't fit in it.
+    pub fn push(&mut self, action: &str) -> Option<String> {
+        let full = self.actions > 0
+            && (self.actions == self.max_actions
+                || self.body.len() + action.len() > self.max_bytes);
+        let body = full.then(|| {
+            self.actions = 0;
+            std::mem::take(&mut self.body)
+        });
+        self.body.push_str(action);
+        self.actions += 1;
+        body
+    }
+
+    /// Returns the last body, if it has any actions.
+    pub fn finish(self) -> Option<String> {
+        (self.actions > 0).then_some(self.body)
+    }
+}
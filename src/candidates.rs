@@ -0,0 +1,282 @@
+use crate::parquet_io::{writer_properties, ParquetWriteOptions};
+use arrow_array::builder::{
+    Float32Builder, Float64Builder, ListBuilder, StringBuilder, StructBuilder, UInt32Builder,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type, UInt32Type};
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::Result;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A query leg that retrieved a candidate, e.g. the lexical or k-NN search, with the candidate's place in its results.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CandidateLeg {
+    pub leg: String,
+    /// The 1-based position of the candidate in the leg's results.
+    pub rank: u32,
+    pub score: Option<f64>,
+}
+
+/// A work ranked as a candidate match of a DMP, with the provenance of its score.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Candidate {
+    pub doi: String,
+    pub score: Option<f64>,
+    /// The candidate's feature scores by name, e.g. those of `CANDIDATE_FEATURES`, None where a feature is missing.
+    #[serde(default)]
+    pub features: BTreeMap<String, Option<f32>>,
+    #[serde(default)]
+    pub legs: Vec<CandidateLeg>,
+}
+
+/// An `InvalidData` error for a candidates file that wasn't written by `write_candidates`.
+fn invalid_file(message: String) -> parquet::errors::ParquetError {
+    io::Error::new(io::ErrorKind::InvalidData, message).into()
+}
+
+/// The item field of the `legs` column, a struct per leg.
+fn legs_item() -> Arc<Field> {
+    let fields = Fields::from(vec![
+        Field::new("leg", DataType::Utf8, false),
+        Field::new("rank", DataType::UInt32, false),
+        Field::new("score", DataType::Float64, true),
+    ]);
+    Arc::new(Field::new("item", DataType::Struct(fields), false))
+}
+
+fn feature_fields(feature_names: &[&str]) -> Fields {
+    feature_names
+        .iter()
+        .map(|name| Field::new(*name, DataType::Float32, true))
+        .collect()
+}
+
+/// The schema of a candidates file: a row per candidate in rank order, with a float32 field of the `features` struct
+/// per feature. Parquet can't store a struct without fields, so `features` is left out when there are no features.
+fn candidates_schema(feature_names: &[&str]) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("dmp_id", DataType::Utf8, false),
+        Field::new("rank", DataType::UInt32, false),
+        Field::new("doi", DataType::Utf8, false),
+        Field::new("score", DataType::Float64, true),
+        Field::new("legs", DataType::List(legs_item()), false),
+    ];
+    if !feature_names.is_empty() {
+        let features = DataType::Struct(feature_fields(feature_names));
+        fields.push(Field::new("features", features, false));
+    }
+    Arc::new(Schema::new(fields))
+}
+
+/// Converts a DMP's candidates into a record batch with a column per feature of `feature_names`.
+fn candidates_batch(dmp_id: &str, candidates: &[Candidate], feature_names: &[&str]) -> RecordBatch {
+    let mut dmp_ids = StringBuilder::new();
+    let mut ranks = UInt32Builder::new();
+    let mut dois = StringBuilder::new();
+    let mut scores = Float64Builder::new();
+    let DataType::Struct(leg_fields) = legs_item().data_type().clone() else {
+        unreachable!("legs are structs")
+    };
+    let mut legs =
+        ListBuilder::new(StructBuilder::from_fields(leg_fields, 0)).with_field(legs_item());
+    let mut features = StructBuilder::from_fields(feature_fields(feature_names), candidates.len());
+
+    for (rank, candidate) in candidates.iter().enumerate() {
+        dmp_ids.append_value(dmp_id);
+        ranks.append_value(rank as u32 + 1);
+        dois.append_value(&candidate.doi);
+        scores.append_option(candidate.score);
+        let leg_builder = legs.values();
+        for leg in &candidate.legs {
+            leg_builder
+                .field_builder::<StringBuilder>(0)
+                .unwrap()
+                .append_value(&leg.leg);
+            leg_builder
+                .field_builder::<UInt32Builder>(1)
+                .unwrap()
+                .append_value(leg.rank);
+            leg_builder
+                .field_builder::<Float64Builder>(2)
+                .unwrap()
+                .append_option(leg.score);
+            leg_builder.append(true);
+        }
+        legs.append(true);
+        for (i, name) in feature_names.iter().enumerate() {
+            features
+                .field_builder::<Float32Builder>(i)
+                .unwrap()
+                .append_option(candidate.features.get(*name).copied().flatten());
+        }
+        features.append(true);
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(dmp_ids.finish()),
+        Arc::new(ranks.finish()),
+        Arc::new(dois.finish()),
+        Arc::new(scores.finish()),
+        Arc::new(legs.finish()),
+    ];
+    if !feature_names.is_empty() {
+        columns.push(Arc::new(features.finish()));
+    }
+    RecordBatch::try_new(candidates_schema(feature_names), columns)
+        .expect("columns match the candidates schema")
+}
+
+/// Writes a DMP's top-k candidates, best first, to a zstd-compressed Parquet file, overwriting any existing file,
+/// keeping each candidate's feature scores and the query legs that retrieved it so that a ranking can be explained
+/// without running the search again. Feature scores are stored as float32, like the feature matrices of
+/// `featurize_pairs`. Returns the number of candidates written.
+pub fn write_candidates(
+    path: &Path,
+    dmp_id: &str,
+    candidates: &[Candidate],
+    options: &ParquetWriteOptions,
+) -> Result<usize> {
+    write_candidates_many(path, &[(dmp_id, candidates)], options)
+}
+
+/// Writes the top-k candidates of many DMPs to one file like `write_candidates`, DMP by DMP in order, with a column
+/// per feature of any candidate. Returns the number of candidates written.
+pub fn write_candidates_many(
+    path: &Path,
+    dmps: &[(&str, &[Candidate])],
+    options: &ParquetWriteOptions,
+) -> Result<usize> {
+    let mut feature_names: Vec<&str> = dmps
+        .iter()
+        .flat_map(|(_, candidates)| candidates.iter())
+        .flat_map(|candidate| candidate.features.keys().map(String::as_str))
+        .collect();
+    feature_names.sort_unstable();
+    feature_names.dedup();
+
+    let properties = writer_properties(options)?;
+    let schema = candidates_schema(&feature_names);
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, Some(properties))?;
+    let mut written = 0;
+    for (dmp_id, candidates) in dmps {
+        writer.write(&candidates_batch(dmp_id, candidates, &feature_names))?;
+        written += candidates.len();
+    }
+    writer.finish()?;
+    Ok(written)
+}
+
+/// A column a candidates file must have, as the type `write_candidates` writes.
+fn column<'a, T: 'static>(
+    batch: &'a RecordBatch,
+    name: &str,
+    cast: impl FnOnce(&'a ArrayRef) -> Option<&'a T>,
+) -> Result<&'a T> {
+    batch
+        .column_by_name(name)
+        .and_then(cast)
+        .ok_or_else(|| invalid_file(format!("candidates file has no valid {name} column")))
+}
+
+/// Reads the candidates written by `write_candidates`, grouped by DMP in the order DMPs first appear and sorted by
+/// rank. Fails with `InvalidData` when the file has different columns.
+pub fn read_candidates(path: &Path) -> Result<Vec<(String, Vec<Candidate>)>> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+    let mut dmps: Vec<(String, Vec<(u32, Candidate)>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for batch in reader {
+        let batch = batch?;
+        let dmp_ids = column(&batch, "dmp_id", |c| c.as_string_opt::<i32>())?;
+        let ranks = column(&batch, "rank", |c| c.as_primitive_opt::<UInt32Type>())?;
+        let dois = column(&batch, "doi", |c| c.as_string_opt::<i32>())?;
+        let scores = column(&batch, "score", |c| c.as_primitive_opt::<Float64Type>())?;
+        let legs = column(&batch, "legs", |c| c.as_list_opt::<i32>())?;
+        // Files of candidates without features have no features column
+        let features = batch
+            .column_by_name("features")
+            .map(|c| {
+                c.as_struct_opt()
+                    .ok_or_else(|| invalid_file("candidate features must be a struct".into()))
+            })
+            .transpose()?;
+        let feature_columns = match features {
+            Some(features) => features
+                .columns()
+                .iter()
+                .map(|c| c.as_primitive_opt::<Float32Type>())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid_file("candidate features must be float32".into()))?,
+            None => Vec::new(),
+        };
+        let feature_names: Vec<&String> = features
+            .map(|features| features.fields().iter().map(|field| field.name()).collect())
+            .unwrap_or_default();
+
+        for row in 0..batch.num_rows() {
+            let row_legs = legs.value(row);
+            let row_legs = row_legs
+                .as_struct_opt()
+                .filter(|legs| legs.num_columns() == 3)
+                .ok_or_else(|| invalid_file("candidate legs must be structs".into()))?;
+            let (Some(leg_names), Some(leg_ranks), Some(leg_scores)) = (
+                row_legs.column(0).as_string_opt::<i32>(),
+                row_legs.column(1).as_primitive_opt::<UInt32Type>(),
+                row_legs.column(2).as_primitive_opt::<Float64Type>(),
+            ) else {
+                return Err(invalid_file("candidate legs have the wrong types".into()));
+            };
+            let candidate = Candidate {
+                doi: dois.value(row).to_string(),
+                score: scores.is_valid(row).then(|| scores.value(row)),
+                features: feature_names
+                    .iter()
+                    .zip(&feature_columns)
+                    .map(|(name, values)| {
+                        let value = values.is_valid(row).then(|| values.value(row));
+                        (name.to_string(), value)
+                    })
+                    .collect(),
+                legs: (0..row_legs.len())
+                    .map(|i| CandidateLeg {
+                        leg: leg_names.value(i).to_string(),
+                        rank: leg_ranks.value(i),
+                        score: leg_scores.is_valid(i).then(|| leg_scores.value(i)),
+                    })
+                    .collect(),
+            };
+
+            let dmp_id = dmp_ids.value(row);
+            let position = match positions.get(dmp_id) {
+                Some(position) => *position,
+                None => {
+                    positions.insert(dmp_id.to_string(), dmps.len());
+                    dmps.push((dmp_id.to_string(), Vec::new()));
+                    dmps.len() - 1
+                },
+            };
+            dmps[position].1.push((ranks.value(row), candidate));
+        }
+    }
+
+    Ok(dmps
+        .into_iter()
+        .map(|(dmp_id, mut candidates)| {
+            candidates.sort_by_key(|(rank, _)| *rank);
+            let candidates = candidates
+                .into_iter()
+                .map(|(_, candidate)| candidate)
+                .collect();
+            (dmp_id, candidates)
+        })
+        .collect())
+}
@@ -0,0 +1,241 @@
+use crate::core::{normalize_doi, split_names};
+use crate::identifiers::{trim_identifier, DOI_RE};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// URLs, which are dropped before the rest of a citation is parsed.
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:available\s+(?:at|from):?\s*|retrieved\s+from:?\s*)?\bhttps?://\S+")
+        .unwrap()
+});
+
+/// A year in parentheses after the authors, as in APA style: "(2020)", "(2020a)" or "(2020, March 3)".
+static PARENTHESIZED_YEAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(((?:1[6-9]|20)[0-9]{2})[a-z]?(?:,[^)]*)?\)\.?").unwrap());
+
+/// A year on its own after the authors, as in Harvard style: "2019." or "(2019).".
+static LEADING_YEAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\(?((?:1[6-9]|20)[0-9]{2})[a-z]?\)?[.,:]\s").unwrap());
+
+/// A publication year anywhere in a citation.
+static YEAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b((?:1[6-9]|20)[0-9]{2})[a-z]?\b").unwrap());
+
+/// A title in straight or curly double quotes, as in MLA and Chicago styles.
+static QUOTED_TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"["“]([^"“”]{2,}?)[.,]?["”]"#).unwrap());
+
+/// An author list in Vancouver style at the start of a citation: surnames followed by up to three initials without
+/// full stops, e.g. "Smith J, Doe AB, et al.".
+static VANCOUVER_AUTHORS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    let name = r"\p{Lu}[\p{L}'’\-]+(?:\s\p{L}[\p{L}'’\-]+)*\s\p{Lu}{1,3}";
+    Regex::new(&format!(r"^({name}(?:,\s*{name})*(?:,?\s*et\s+al)?)\.\s")).unwrap()
+});
+
+/// Where the volume, issue, pages or date following a container title start, e.g. ", 12(3), 45–67", " 12.3 (2020)"
+/// or "; 2020".
+static CONTAINER_DETAILS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:[,;:]?\s+(?:vol\.?|volume|no\.?|pp?\.)?\s*[0-9]|\s*\(|[,;:]\s*(?:vol\.?|volume|no\.?|pp?\.)\s)")
+        .unwrap()
+});
+
+/// Words abbreviated with a full stop that doesn't end a citation part, e.g. "Vol. 2" or "J. Mol. Biol.".
+const ABBREVIATIONS: &[&str] = &[
+    "ed", "eds", "vol", "no", "pp", "p", "al", "inc", "co", "dr", "st", "jr", "sr",
+];
+
+/// The parts of a free-text citation, see `parse_citation`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedCitation {
+    pub title: Option<String>,
+    /// The author names as written, e.g. "Smith, J.", in order.
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    /// The journal, book, proceedings or repository the work appeared in.
+    pub container: Option<String>,
+    /// The normalized DOI, see `normalize_doi`.
+    pub doi: Option<String>,
+}
+
+/// Checks whether the full stop ending `part` abbreviates its last word: an initial, e.g. the "J." of "Smith, J.", or
+/// one of `ABBREVIATIONS`.
+fn ends_with_abbreviation(part: &str) -> bool {
+    let word = part
+        .rsplit(|c: char| c.is_whitespace() || c == '(' || c == ',')
+        .next()
+        .unwrap_or_default();
+    let mut chars = word.chars();
+    let is_initial = matches!((chars.next(), chars.next()), (Some(c), None) if c.is_uppercase());
+    is_initial || ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+/// Splits a citation into its parts at full stops, question marks and exclamation marks followed by whitespace,
+/// except after initials and abbreviations. Question and exclamation marks are kept, as they belong to titles.
+fn split_parts(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        let followed_by_space = text[i + c.len_utf8()..].starts_with(char::is_whitespace);
+        if !followed_by_space || !matches!(c, '.' | '?' | '!') {
+            continue;
+        }
+        if c == '.' && ends_with_abbreviation(&text[start..i]) {
+            continue;
+        }
+        let end = if c == '.' { i } else { i + 1 };
+        parts.push(text[start..end].trim());
+        start = i + 1;
+    }
+    parts.push(text[start..].trim().trim_end_matches('.'));
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Checks whether a citation part is a list of people's names rather than a title: every name has at most four
+/// words, at least one starting with a capital, and the part isn't a single long phrase.
+fn looks_like_authors(part: &str) -> bool {
+    let names = split_names(part);
+    !names.is_empty()
+        && names.iter().all(|name| {
+            let words: Vec<&str> = name.split_whitespace().collect();
+            !words.is_empty()
+                && words.len() <= 4
+                && words
+                    .iter()
+                    .all(|word| word.starts_with(|c: char| c.is_uppercase()) || word.len() <= 3)
+        })
+        && (names.len() > 1 || part.contains(',') || part.split_whitespace().count() <= 3)
+}
+
+/// Trims the punctuation ending the authors of a citation, keeping the full stop of a final initial.
+fn author_list(text: &str) -> &str {
+    let text = text.trim().trim_end_matches([',', ':']).trim();
+    match text.strip_suffix('.') {
+        Some(trimmed) if !ends_with_abbreviation(trimmed) => trimmed,
+        _ => text,
+    }
+}
+
+/// The container title at the start of a citation part, before any volume, issue, pages or date. "In" before a book
+/// title is dropped, and parts that are only numbers give None.
+fn container_title(part: &str) -> Option<String> {
+    let part = part
+        .strip_prefix("In: ")
+        .or_else(|| part.strip_prefix("In "))
+        .unwrap_or(part);
+    let end = CONTAINER_DETAILS_RE
+        .find(part)
+        .map_or(part.len(), |m| m.start());
+    let title = part[..end].trim().trim_end_matches([',', ';', ':']).trim();
+    title
+        .chars()
+        .any(char::is_alphabetic)
+        .then(|| title.to_string())
+}
+
+/// Cleans a title, removing surrounding quotes and any trailing full stop or comma.
+fn clean_title(title: &str) -> Option<String> {
+    let title = title
+        .trim()
+        .trim_matches(['"', '“', '”'])
+        .trim_end_matches(['.', ','])
+        .trim();
+    title
+        .chars()
+        .any(char::is_alphabetic)
+        .then(|| title.to_string())
+}
+
+/// Parses a free-text citation, e.g. an output listed in a DMP's narrative, into its title, authors, year, container
+/// and DOI. Common styles are recognized by their shape: APA, with the year in parentheses after the authors; MLA and
+/// Chicago, with the title in quotes; and Vancouver, with initials after the surnames. Otherwise the citation is split
+/// into parts at full stops, the first part being the authors when it looks like a list of names, then the title and
+/// the container. Parts that can't be identified are None.
+pub fn parse_citation(text: &str) -> ParsedCitation {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let doi = DOI_RE
+        .find_iter(&text)
+        .find_map(|m| normalize_doi(Some(trim_identifier(m.as_str()))));
+    let text = DOI_RE.replace_all(&text, "");
+    let text = URL_RE.replace_all(&text, "");
+    let text = text.trim();
+
+    let mut citation = ParsedCitation {
+        doi,
+        ..Default::default()
+    };
+
+    // Authors, and the year when it follows them in parentheses
+    let year_after_authors = PARENTHESIZED_YEAR_RE
+        .captures(text)
+        .filter(|m| looks_like_authors(author_list(&text[..m.get(0).unwrap().start()])));
+    let rest = if let Some(m) = QUOTED_TITLE_RE.find(text).filter(|m| m.start() > 0) {
+        citation.authors = split_names(author_list(&text[..m.start()]));
+        &text[m.start()..]
+    } else if let Some(m) = year_after_authors {
+        let whole = m.get(0).unwrap();
+        citation.authors = split_names(author_list(&text[..whole.start()]));
+        citation.year = m[1].parse().ok();
+        text[whole.end()..].trim()
+    } else if let Some(m) = VANCOUVER_AUTHORS_RE.captures(text) {
+        citation.authors = split_names(&m[1]);
+        &text[m.get(0).unwrap().end()..]
+    } else {
+        match split_parts(text).first() {
+            Some(first) if first.len() < text.len() && looks_like_authors(first) => {
+                citation.authors = split_names(first);
+                text[text.find(first).unwrap() + first.len()..].trim_start_matches(['.', ' '])
+            },
+            _ => text,
+        }
+    };
+
+    let (rest, leading_year) = match LEADING_YEAR_RE.captures(rest) {
+        Some(m) if citation.year.is_none() => (&rest[m.get(0).unwrap().end()..], m[1].parse().ok()),
+        _ => (rest, None),
+    };
+    citation.year = citation.year.or(leading_year);
+    // AGU style separates the year, title and container with commas
+    let comma_separated = rest.trim_start().starts_with(',');
+    let rest = rest.trim_start_matches(['.', ',', ' ']);
+
+    // The title, quoted or the first remaining part, then the container
+    let after_title = match QUOTED_TITLE_RE
+        .captures(rest)
+        .filter(|m| m.get(0).unwrap().start() == 0)
+    {
+        Some(m) => {
+            citation.title = clean_title(&m[1]);
+            rest[m.get(0).unwrap().end()..].trim()
+        },
+        None if comma_separated => {
+            let (title, after) = rest.split_once(", ").unwrap_or((rest, ""));
+            citation.title = clean_title(title);
+            after
+        },
+        None => {
+            let parts = split_parts(rest);
+            citation.title = parts.first().and_then(|title| clean_title(title));
+            match parts.first() {
+                Some(title) => rest[rest.find(title).unwrap() + title.len()..].trim(),
+                None => "",
+            }
+        },
+    };
+    let after_title = after_title.trim_start_matches(['.', ',', ' ']);
+    citation.container = if comma_separated {
+        container_title(after_title)
+    } else {
+        split_parts(after_title)
+            .first()
+            .and_then(|part| container_title(part))
+    };
+
+    if citation.year.is_none() {
+        citation.year = YEAR_RE
+            .captures(after_title)
+            .or_else(|| YEAR_RE.captures(text))
+            .and_then(|m| m[1].parse().ok());
+    }
+    citation
+}
@@ -1,10 +1,34 @@
+use crate::phonetic;
+use arrow_array::builder::StringBuilder;
+use arrow_array::cast::AsArray;
+use arrow_array::{
+    Array, ArrayAccessor, ArrayRef, LargeBinaryArray, LargeStringArray, StringArray,
+    StringViewArray, StructArray,
+};
+use arrow_schema::{ArrowError, DataType, Field, Fields};
+use caseless::default_case_fold_str;
+use deunicode::deunicode;
+use flate2::bufread::MultiGzDecoder;
 use human_name::Name;
 use log::warn;
-use std::collections::HashMap;
+use rayon::prelude::*;
+use regex::{Regex, RegexSet};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
 use strip_tags::strip_tags;
+use strsim::{jaro_winkler, levenshtein};
+use twox_hash::{XxHash3_128, XxHash3_64};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
+use whatlang::Lang;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ParsedName {
     pub first_initial: Option<String>,
     pub given_name: Option<String>,
@@ -12,6 +36,105 @@ pub struct ParsedName {
     pub middle_names: Option<String>,
     pub surname: Option<String>,
     pub full: Option<String>,
+    /// Generational and post-nominal suffixes, e.g. "Jr." or "PhD".
+    pub suffix: Option<String>,
+    /// Titles preceding the name, e.g. "Dr." or "Prof.".
+    pub title: Option<String>,
+}
+
+impl ParsedName {
+    /// The name's fields in declaration order, as named by `PARSED_NAME_FIELDS`.
+    pub fn into_fields(self) -> [Option<String>; 8] {
+        [
+            self.first_initial,
+            self.given_name,
+            self.middle_initials,
+            self.middle_names,
+            self.surname,
+            self.full,
+            self.suffix,
+            self.title,
+        ]
+    }
+}
+
+/// Titles that may precede a person's name, compared lowercased and without full stops.
+const NAME_TITLES: &[&str] = &[
+    "dame",
+    "dr",
+    "miss",
+    "mr",
+    "mrs",
+    "ms",
+    "mx",
+    "prof",
+    "professor",
+    "rev",
+    "sir",
+];
+
+/// Generational and post-nominal suffixes that may follow a person's name, compared lowercased and without full
+/// stops.
+const NAME_SUFFIXES: &[&str] = &[
+    "dds", "dphil", "dvm", "esq", "frs", "ii", "iii", "iv", "jr", "mba", "md", "msc", "phd", "sr",
+];
+
+/// Lowercases a name token and removes its full stops and trailing comma, for comparison with `NAME_TITLES` and
+/// `NAME_SUFFIXES`.
+fn affix_key(token: &str) -> String {
+    token.trim_end_matches(',').replace('.', "").to_lowercase()
+}
+
+/// Joins affix tokens, without any trailing commas, into a single string.
+fn join_affixes(tokens: &[&str]) -> Option<String> {
+    (!tokens.is_empty()).then(|| {
+        tokens
+            .iter()
+            .map(|token| token.trim_end_matches(','))
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+/// Splits leading titles and trailing suffixes off a name, returning the title, the remaining name and the suffix.
+/// Suffixes are recognized at the end of each comma-separated part, so "Smith Jr., John" and "John Smith, PhD" both
+/// lose their suffix. Suffixes are only removed while more than `min_words` words of the name remain, so that with a
+/// minimum of two initials such as "Smith, M.D." are kept.
+fn split_name_affixes(text: &str, min_words: usize) -> (Option<String>, String, Option<String>) {
+    let mut parts: Vec<Vec<&str>> = text
+        .split(',')
+        .map(|part| part.split_whitespace().collect())
+        .collect();
+    let mut words: usize = parts.iter().map(Vec::len).sum();
+
+    let mut titles = Vec::new();
+    if let Some(first) = parts.iter_mut().find(|part| !part.is_empty()) {
+        while words > 1 && first.len() > 1 && NAME_TITLES.contains(&affix_key(first[0]).as_str()) {
+            titles.push(first.remove(0));
+            words -= 1;
+        }
+    }
+
+    let mut suffixes = Vec::new();
+    for part in parts.iter_mut().rev() {
+        while words > min_words {
+            let Some(token) =
+                part.pop_if(|token| NAME_SUFFIXES.contains(&affix_key(token).as_str()))
+            else {
+                break;
+            };
+            suffixes.insert(0, token);
+            words -= 1;
+        }
+    }
+
+    let name = parts
+        .iter()
+        .filter(|part| !part.is_empty())
+        .map(|part| part.join(" "))
+        .collect::<Vec<_>>()
+        .join(", ");
+    (join_affixes(&titles), name, join_affixes(&suffixes))
 }
 
 /// Attempts to parse a name string using simple splitting rules (comma or space) as a fallback mechanism.
@@ -47,34 +170,490 @@ pub fn has_alphabetic_initials(text: &str) -> bool {
         return false;
     }
 
-    let u = c as u32;
+    !is_cjk_char(c)
+}
 
-    // Denylist for CJK Unicode Blocks
-    let is_cjk = matches!(u,
+/// Checks if a character is a CJK (Chinese, Japanese, Korean) ideograph or syllable.
+fn is_cjk_char(c: char) -> bool {
+    // CJK Unicode Blocks
+    matches!(c as u32,
         0x3000..=0x31FF | // Hiragana, Katakana, Bopomofo, Hangul Jamo
         0x3400..=0x4DBF | // CJK Unified Ideographs Extension A
         0x4E00..=0x9FFF | // CJK Unified Ideographs (Main Block)
         0xAC00..=0xD7AF | // Hangul Syllables
         0xF900..=0xFAFF | // CJK Compatibility Ideographs
         0x20000..=0x2A6DF // CJK Unified Ideographs Extension B, C, D, E, etc.
-    );
+    )
+}
+
+/// Surname particles that are kept lowercase when recasing a surname.
+const SURNAME_PARTICLES: &[&str] = &[
+    "van", "von", "der", "den", "de", "del", "della", "da", "das", "dos", "di", "du", "la", "le",
+    "ten", "ter",
+];
+
+/// Words starting with "Mac" that are not "Mac" + surname, e.g. "Machado" rather than "MacHado".
+const MAC_EXCEPTIONS: &[&str] = &[
+    "machado", "macias", "macedo", "machin", "macek", "macha", "mackie", "macon", "macey", "macy",
+    "mack", "mace",
+];
+
+/// Uppercases the first character of a lowercase word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Capitalizes a single lowercase surname word, applying the Mc/Mac and O'/D' prefix rules.
+fn capitalize_surname_word(word: &str) -> String {
+    if let Some((prefix, rest)) = word.split_once('\'') {
+        if prefix.chars().count() == 1 && !rest.is_empty() {
+            return format!("{}'{}", capitalize(prefix), capitalize(rest));
+        }
+    }
+
+    if let Some(rest) = word.strip_prefix("mc") {
+        if rest.chars().count() > 1 {
+            return format!("Mc{}", capitalize(rest));
+        }
+    }
+
+    if let Some(rest) = word.strip_prefix("mac") {
+        if rest.chars().count() > 2 && !MAC_EXCEPTIONS.contains(&word) {
+            return format!("Mac{}", capitalize(rest));
+        }
+    }
+
+    capitalize(word)
+}
+
+/// Applies name-aware capitalization to a surname, e.g. "MCDONALD" → "McDonald", "o'brien" → "O'Brien" and
+/// "VAN DER BERG" → "van der Berg": Mc/Mac and O' prefixes are capitalized, particles are lowercased, and
+/// hyphenated parts are capitalized individually.
+fn recase_surname_text(surname: &str) -> String {
+    let lower = surname.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            if i + 1 < words.len() && SURNAME_PARTICLES.contains(word) {
+                word.to_string()
+            } else {
+                word.split('-')
+                    .map(capitalize_surname_word)
+                    .collect::<Vec<_>>()
+                    .join("-")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Common Hungarian surnames, lowercased. Hungarian names are written family name first, e.g. "Nagy Imre".
+const HUNGARIAN_SURNAMES: &[&str] = &[
+    "balogh",
+    "biró",
+    "bodnár",
+    "bognár",
+    "farkas",
+    "fazekas",
+    "fehér",
+    "fekete",
+    "fodor",
+    "gál",
+    "gulyás",
+    "hegedűs",
+    "horváth",
+    "juhász",
+    "katona",
+    "király",
+    "kis",
+    "kiss",
+    "kocsis",
+    "kovács",
+    "lakatos",
+    "magyar",
+    "mészáros",
+    "molnár",
+    "nagy",
+    "németh",
+    "oláh",
+    "orsós",
+    "papp",
+    "pintér",
+    "rácz",
+    "sipos",
+    "szabó",
+    "szalai",
+    "szilágyi",
+    "szűcs",
+    "takács",
+    "török",
+    "tóth",
+    "varga",
+    "vincze",
+];
+
+/// Common Hungarian given names, lowercased, which follow the family name in Hungarian name order.
+const HUNGARIAN_GIVEN_NAMES: &[&str] = &[
+    "ádám",
+    "ágnes",
+    "ákos",
+    "andrás",
+    "anikó",
+    "attila",
+    "balázs",
+    "béla",
+    "bence",
+    "boglárka",
+    "csaba",
+    "dániel",
+    "dávid",
+    "dóra",
+    "edit",
+    "emese",
+    "erika",
+    "erzsébet",
+    "eszter",
+    "éva",
+    "ferenc",
+    "gábor",
+    "gergely",
+    "györgy",
+    "gyula",
+    "ildikó",
+    "ilona",
+    "imre",
+    "irén",
+    "istván",
+    "jános",
+    "józsef",
+    "judit",
+    "julianna",
+    "károly",
+    "katalin",
+    "krisztián",
+    "krisztina",
+    "lajos",
+    "lászló",
+    "levente",
+    "margit",
+    "mária",
+    "márton",
+    "máté",
+    "mihály",
+    "miklós",
+    "nóra",
+    "orsolya",
+    "péter",
+    "réka",
+    "sándor",
+    "szabolcs",
+    "tamás",
+    "tibor",
+    "tímea",
+    "zoltán",
+    "zsófia",
+    "zsolt",
+    "zsuzsanna",
+];
+
+/// Options for `parse_name_with_options`, enabling the name-order rules needed for names from some locales.
+#[derive(Debug, Clone, Default)]
+pub struct NameOptions {
+    /// Keeps surname particles such as "van", "de", "da" and "von" with the surname they precede, e.g. "Jan ten
+    /// Brink" has the surname "ten Brink".
+    pub surname_particles: bool,
+    /// Detects names written family name first: names in Chinese, Japanese or Korean scripts, e.g. "张伟" or "田中
+    /// 太郎", names whose leading words are in capitals, e.g. "WANG Wei", and Hungarian names, e.g. "Nagy Imre".
+    /// Romanized East Asian names in mixed case are left given name first, as they are written in Crossref and
+    /// OpenAlex.
+    pub family_name_first: bool,
+    /// Also treats names with words that are common in people's names too, e.g. "Group", "Project", "School",
+    /// "Survey" or "Team", as organizations, e.g. "IPCC Working Group" or "British Geological Survey".
+    pub ambiguous_organization_words: bool,
+}
+
+/// Splits a name without commas into its given names and surname with the rules enabled in `options`, returning
+/// None when no rule applies and the name should be parsed as usual.
+fn split_name_with_options(name: &str, options: &NameOptions) -> Option<(String, String)> {
+    if name.contains(',') {
+        return None;
+    }
+    let words: Vec<&str> = name.split_whitespace().collect();
+
+    if options.family_name_first {
+        if words.iter().all(|word| word.chars().all(is_cjk_char)) {
+            return match words.as_slice() {
+                [word] => {
+                    let (surname, given) =
+                        word.split_at(word.char_indices().nth(cjk_surname_len(word)?)?.0);
+                    Some((given.to_string(), surname.to_string()))
+                },
+                [surname, given @ ..] => Some((given.join(" "), surname.to_string())),
+                [] => None,
+            };
+        }
+
+        let is_capitals = |word: &str| {
+            word.chars().filter(|c| c.is_alphabetic()).count() > 1
+                && !word.chars().any(char::is_lowercase)
+        };
+        let capitals = words.iter().take_while(|word| is_capitals(word)).count();
+        if capitals > 0
+            && capitals < words.len()
+            && !words[capitals..].iter().any(|w| is_capitals(w))
+        {
+            return Some((words[capitals..].join(" "), words[..capitals].join(" ")));
+        }
+
+        let lower: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        let is_given_name = |word: &String| HUNGARIAN_GIVEN_NAMES.contains(&word.as_str());
+        if let [surname, given @ ..] = &lower[..] {
+            if (1..=2).contains(&given.len())
+                && HUNGARIAN_SURNAMES.contains(&surname.as_str())
+                && !is_given_name(surname)
+                && given.iter().all(is_given_name)
+            {
+                return Some((words[1..].join(" "), words[0].to_string()));
+            }
+        }
+    }
+
+    if options.surname_particles && words.len() > 2 {
+        let last = words.len() - 1;
+        let is_particle = |word: &&str| SURNAME_PARTICLES.contains(&word.to_lowercase().as_str());
+        let start = (1..last).find(|&i| words[i..last].iter().all(is_particle))?;
+        return Some((words[..start].join(" "), words[start..].join(" ")));
+    }
+    None
+}
 
-    !is_cjk
+/// Builds a `ParsedName` from the given names and surname split by `split_name_with_options`. The first given name
+/// is the given name, unless it is an initial, and the others are middle names or initials, e.g. "Juan Carlos" has
+/// the middle name "Carlos" and the middle initial "C". Names in CJK scripts have no initials.
+fn name_from_split(
+    given: &str,
+    surname: String,
+    full: &str,
+    title: Option<String>,
+    suffix: Option<String>,
+) -> ParsedName {
+    let is_initial = |word: &str| {
+        has_alphabetic_initials(word) && word.trim_end_matches('.').chars().count() == 1
+    };
+    let initial = |word: &str| {
+        has_alphabetic_initials(word)
+            .then(|| {
+                word.graphemes(true)
+                    .next()
+                    .map(|grapheme| grapheme.to_uppercase())
+            })
+            .flatten()
+    };
+    let words: Vec<&str> = given.split_whitespace().collect();
+    let (first, middle) = match words.split_first() {
+        Some((first, middle)) => (Some(*first), middle),
+        None => (None, &[][..]),
+    };
+    let middle_initials: String = middle.iter().filter_map(|word| initial(word)).collect();
+    let middle_names: Vec<&str> = middle
+        .iter()
+        .copied()
+        .filter(|word| !is_initial(word))
+        .collect();
+    ParsedName {
+        first_initial: first.and_then(initial),
+        given_name: first.filter(|word| !is_initial(word)).map(str::to_string),
+        middle_initials: (!middle_initials.is_empty()).then_some(middle_initials),
+        middle_names: (!middle_names.is_empty()).then(|| middle_names.join(" ")),
+        surname: Some(surname),
+        full: Some(full.to_string()),
+        suffix,
+        title,
+    }
 }
 
 /// Parses a raw name string into a structured `ParsedName` object, utilizing `human_name` with a fallback strategy.
+/// When `recase_surname` is set the surname is recapitalized with name-aware rules for consistent display.
 pub fn parse_name(
     raw_given_name: Option<&str>,
     raw_surname: Option<&str>,
     raw_full: Option<&str>,
+    recase_surname: bool,
+) -> ParsedName {
+    parse_name_with_options(
+        raw_given_name,
+        raw_surname,
+        raw_full,
+        recase_surname,
+        &NameOptions::default(),
+    )
+}
+
+/// Parses a raw name like `parse_name`, first applying the surname particle and family name first rules enabled in
+/// `options` to full names written without commas.
+pub fn parse_name_with_options(
+    raw_given_name: Option<&str>,
+    raw_surname: Option<&str>,
+    raw_full: Option<&str>,
+    recase_surname: bool,
+    options: &NameOptions,
 ) -> ParsedName {
+    let (parsed, fallback) = parse_name_reporting_fallback(
+        raw_given_name,
+        raw_surname,
+        raw_full,
+        recase_surname,
+        options,
+    );
+    if let Some(message) = fallback {
+        warn!("{message}");
+    }
+    parsed
+}
+
+/// Parses a raw name like `parse_name_with_options`, returning the message describing how the name was split when
+/// no parser recognized it instead of logging it as a warning.
+fn parse_name_reporting_fallback(
+    raw_given_name: Option<&str>,
+    raw_surname: Option<&str>,
+    raw_full: Option<&str>,
+    recase_surname: bool,
+    options: &NameOptions,
+) -> (ParsedName, Option<String>) {
+    let (mut parsed, fallback) =
+        parse_name_components(raw_given_name, raw_surname, raw_full, options);
+    if recase_surname {
+        parsed.surname = parsed.surname.as_deref().map(recase_surname_text);
+    }
+    (parsed, fallback)
+}
+
+/// Parses many raw full names with `parse_name_with_options` in parallel on the current rayon thread pool,
+/// preserving order.
+pub fn parse_name_many(
+    names: &[Option<String>],
+    recase_surname: bool,
+    options: &NameOptions,
+) -> Vec<ParsedName> {
+    names
+        .par_iter()
+        .map(|name| parse_name_with_options(None, None, name.as_deref(), recase_surname, options))
+        .collect()
+}
+
+/// Parses many raw full names like `parse_name_many`, but returns a `ParseError` for each name that no parser
+/// recognized instead of logging a warning. Such names are still split on their comma or last space.
+pub fn parse_name_many_with_errors(
+    names: &[Option<String>],
+    recase_surname: bool,
+    options: &NameOptions,
+) -> (Vec<ParsedName>, Vec<ParseError>) {
+    let parsed: Vec<(ParsedName, Option<String>)> = names
+        .par_iter()
+        .map(|name| {
+            parse_name_reporting_fallback(None, None, name.as_deref(), recase_surname, options)
+        })
+        .collect();
+    names_with_errors(parsed)
+}
+
+/// Splits parsed names from the messages of those that fell back to `fallback_parse_name`, as `ParseError`s of their
+/// position.
+fn names_with_errors(
+    parsed: Vec<(ParsedName, Option<String>)>,
+) -> (Vec<ParsedName>, Vec<ParseError>) {
+    let mut errors = Vec::new();
+    let names = parsed
+        .into_iter()
+        .enumerate()
+        .map(|(row, (name, fallback))| {
+            if let Some(message) = fallback {
+                errors.push(ParseError {
+                    row,
+                    field: None,
+                    message,
+                });
+            }
+            name
+        })
+        .collect();
+    (names, errors)
+}
+
+/// Field names of the struct array returned by `parse_names_batch`, in `ParsedName` field order.
+pub(crate) const PARSED_NAME_FIELDS: [&str; 8] = [
+    "first_initial",
+    "given_name",
+    "middle_initials",
+    "middle_names",
+    "surname",
+    "full",
+    "suffix",
+    "title",
+];
+
+/// Parses a column of raw full names with `parse_name_with_options`, returning a struct array with a nullable string
+/// field for each `ParsedName` field. Null names give a row whose fields are all null.
+pub fn parse_names_batch(
+    names: &StringArray,
+    recase_surname: bool,
+    options: &NameOptions,
+) -> StructArray {
+    parsed_names_array(
+        names
+            .iter()
+            .map(|name| parse_name_with_options(None, None, name, recase_surname, options)),
+    )
+}
+
+/// The fields of the struct arrays built by `parsed_names_array`.
+pub(crate) fn parsed_name_fields() -> Fields {
+    PARSED_NAME_FIELDS
+        .iter()
+        .map(|name| Field::new(*name, DataType::Utf8, true))
+        .collect()
+}
+
+/// Builds a struct array of parsed names, with a nullable string field for each `ParsedName` field.
+pub(crate) fn parsed_names_array(names: impl IntoIterator<Item = ParsedName>) -> StructArray {
+    let mut builders: [StringBuilder; 8] = std::array::from_fn(|_| StringBuilder::new());
+    for parsed in names {
+        for (builder, value) in builders.iter_mut().zip(parsed.into_fields()) {
+            builder.append_option(value);
+        }
+    }
+
+    let columns: Vec<ArrayRef> = builders
+        .iter_mut()
+        .map(|builder| Arc::new(builder.finish()) as ArrayRef)
+        .collect();
+    StructArray::new(parsed_name_fields(), columns, None)
+}
+
+/// Parses the raw name parts into a `ParsedName` without any post-processing, with a message describing the split
+/// when the name could only be split by `fallback_parse_name`.
+fn parse_name_components(
+    raw_given_name: Option<&str>,
+    raw_surname: Option<&str>,
+    raw_full: Option<&str>,
+    options: &NameOptions,
+) -> (ParsedName, Option<String>) {
     let given = raw_given_name.map(str::trim).filter(|s| !s.is_empty());
     let surname = raw_surname.map(str::trim).filter(|s| !s.is_empty());
     let full = raw_full.map(str::trim).filter(|s| !s.is_empty());
 
     // If both given and surname are provided, build the final struct straight away
     if let (Some(g), Some(s)) = (given, surname) {
-        let first_initial = if has_alphabetic_initials(g) {
+        let (given_title, g, given_suffix) = split_name_affixes(g, 1);
+        let (surname_title, s, surname_suffix) = split_name_affixes(s, 1);
+        let first_initial = if has_alphabetic_initials(&g) {
             g.graphemes(true)
                 .next()
                 .map(|grapheme| grapheme.to_uppercase())
@@ -90,75 +669,438 @@ pub fn parse_name(
             .map(|f| f.to_string())
             .unwrap_or_else(|| format!("{} {}", g, s));
 
-        return ParsedName {
-            first_initial,
-            given_name: Some(g.to_string()),
-            middle_initials: None,
-            middle_names: None,
-            surname: Some(s.to_string()),
-            full: Some(full_name),
-        };
+        return (
+            ParsedName {
+                first_initial,
+                given_name: Some(g),
+                middle_initials: None,
+                middle_names: None,
+                surname: Some(s),
+                full: Some(full_name),
+                suffix: surname_suffix.or(given_suffix),
+                title: given_title.or(surname_title),
+            },
+            None,
+        );
     }
 
     // If full, given and surname are all None then return None
     let Some(text_to_parse) = full.or(given).or(surname) else {
-        return ParsedName {
-            first_initial: None,
-            given_name: None,
-            middle_initials: None,
-            middle_names: None,
-            surname: None,
-            full: None,
-        };
+        return (
+            ParsedName {
+                first_initial: None,
+                given_name: None,
+                middle_initials: None,
+                middle_names: None,
+                surname: None,
+                full: None,
+                suffix: None,
+                title: None,
+            },
+            None,
+        );
     };
 
-    if let Some(person) = Name::parse(text_to_parse) {
-        return ParsedName {
-            first_initial: Some(person.first_initial().to_string()),
-            given_name: person.given_name().map(|v| v.to_string()),
-            middle_initials: person.middle_initials().map(|v| v.to_string()),
-            middle_names: person.middle_names().map(|v| v.join(" ")),
-            surname: Some(person.surname().to_string()),
-            full: Some(text_to_parse.to_string()),
-        };
+    // Organization authors have no person name parts, only a cleaned full name
+    if is_organization_with_options(text_to_parse, options) {
+        return (
+            ParsedName {
+                first_initial: None,
+                given_name: None,
+                middle_initials: None,
+                middle_names: None,
+                surname: None,
+                full: Some(normalize_organization_name(text_to_parse)),
+                suffix: None,
+                title: None,
+            },
+            None,
+        );
+    }
+
+    // Split off titles and suffixes first so that neither parser mistakes them for name parts
+    let (title, name, suffix) = split_name_affixes(text_to_parse, 2);
+    if let Some((given, surname)) = split_name_with_options(&name, options) {
+        return (
+            name_from_split(&given, surname, text_to_parse, title, suffix),
+            None,
+        );
+    }
+    if let Some(person) = Name::parse(&name) {
+        let person_suffix = [person.generational_suffix(), person.honorific_suffix()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        return (
+            ParsedName {
+                first_initial: Some(person.first_initial().to_string()),
+                given_name: person.given_name().map(|v| v.to_string()),
+                middle_initials: person.middle_initials().map(|v| v.to_string()),
+                middle_names: person.middle_names().map(|v| v.join(" ")),
+                surname: Some(person.surname().to_string()),
+                full: Some(text_to_parse.to_string()),
+                suffix: suffix.or_else(|| join_affixes(&person_suffix)),
+                title: title.or_else(|| person.honorific_prefix().map(str::to_string)),
+            },
+            None,
+        );
     }
 
-    // Fallback if human_name fails
-    let (parsed_given, parsed_surname, parsed_full) = fallback_parse_name(text_to_parse);
-    warn!(
+    // Fallback if human_name fails. A title with a single name, e.g. "Dr. Smith", addresses the person by surname.
+    let (parsed_given, parsed_surname, parsed_full) = match title {
+        Some(_) if !name.contains(char::is_whitespace) => (None, Some(name.clone()), name.clone()),
+        _ => fallback_parse_name(&name),
+    };
+    let message = format!(
         "fallback_parse_name: given_name='{:?}', surname='{:?}', full='{}'",
         parsed_given, parsed_surname, parsed_full
     );
 
-    ParsedName {
+    let parsed = ParsedName {
         first_initial: None,
         given_name: parsed_given,
         middle_initials: None,
         middle_names: None,
         surname: parsed_surname,
         full: Some(text_to_parse.to_string()),
+        suffix,
+        title,
+    };
+    (parsed, Some(message))
+}
+
+/// Words that mark an author name as an organization rather than a person, compared lowercased and without a
+/// trailing full stop.
+const ORGANIZATION_WORDS: &[&str] = &[
+    "administration",
+    "agency",
+    "alliance",
+    "association",
+    "board",
+    "bureau",
+    "center",
+    "centre",
+    "collaboration",
+    "college",
+    "commission",
+    "committee",
+    "company",
+    "consortium",
+    "corp",
+    "corporation",
+    "council",
+    "department",
+    "dept",
+    "foundation",
+    "gmbh",
+    "hospital",
+    "inc",
+    "initiative",
+    "institute",
+    "lab",
+    "laboratory",
+    "labs",
+    "llc",
+    "ltd",
+    "ministry",
+    "museum",
+    "network",
+    "observatory",
+    "office",
+    "organisation",
+    "organization",
+    "partnership",
+    "program",
+    "programme",
+    "services",
+    "society",
+    "univ",
+    "university",
+];
+
+/// Words of organization names that are also people's names, e.g. "Jane School", which only mark names as
+/// organizations with `NameOptions::ambiguous_organization_words`.
+const AMBIGUOUS_ORGANIZATION_WORDS: &[&str] = &["group", "project", "school", "survey", "team"];
+
+/// Abbreviations expanded in organization names, keyed by the lowercased abbreviation without its full stop.
+const ORGANIZATION_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("assoc", "Association"),
+    ("ctr", "Center"),
+    ("dept", "Department"),
+    ("govt", "Government"),
+    ("inst", "Institute"),
+    ("intl", "International"),
+    ("lab", "Laboratory"),
+    ("natl", "National"),
+    ("univ", "University"),
+];
+
+/// Strips trailing full stops and commas from a name word and lowercases it, for comparison against word lists.
+fn organization_word_key(word: &str) -> String {
+    word.trim_end_matches(['.', ',']).to_lowercase()
+}
+
+/// Detects whether an author name is an organization, e.g. "Dept. of Health & Human Services", by looking for
+/// words such as "Department", "University", "Lab" or "Inc.".
+pub fn is_organization(text: &str) -> bool {
+    is_organization_with_options(text, &NameOptions::default())
+}
+
+/// Detects whether an author name is an organization like `is_organization`, also looking for the words of
+/// `AMBIGUOUS_ORGANIZATION_WORDS` when `options` enables them.
+pub fn is_organization_with_options(text: &str, options: &NameOptions) -> bool {
+    decode_html_entities(text).split_whitespace().any(|word| {
+        let key = organization_word_key(word);
+        ORGANIZATION_WORDS.contains(&key.as_str())
+            || (options.ambiguous_organization_words
+                && AMBIGUOUS_ORGANIZATION_WORDS.contains(&key.as_str()))
+    })
+}
+
+/// "et al." at the end of a list of names, which stands for people who aren't named.
+static ET_AL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i),?\s*\bet\.?\s+al\b\.?").unwrap());
+
+/// Separators that always split a list of names: semicolons, vertical bars and newlines.
+static NAME_LIST_SEPARATOR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[;|\n]").unwrap());
+
+/// Conjunctions joining the last names of a list, e.g. "Smith, J., Doe, A. and Lee, K.".
+static NAME_CONJUNCTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i),?\s+(?:and|&|und)\s+").unwrap());
+
+/// Given names written as initials, e.g. "J.", "J.A." or "J. A.".
+static INITIALS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:\p{Lu}[.\-]?\s*){1,4}$").unwrap());
+
+/// Whether two comma-separated parts of a list of names are one inverted name, "Surname, Given": the given part is
+/// initials, or the surname is a single word, possibly after particles like "van der", and the given part has at
+/// most three words.
+fn is_inverted_name(surname: &str, given: &str) -> bool {
+    if INITIALS_RE.is_match(given) {
+        return true;
     }
+    let words: Vec<String> = surname.split_whitespace().map(str::to_lowercase).collect();
+    let is_surname = words.split_last().is_some_and(|(_, particles)| {
+        particles
+            .iter()
+            .all(|w| SURNAME_PARTICLES.contains(&w.as_str()))
+    });
+    is_surname && given.split_whitespace().count() <= 3
 }
 
-/// Reconstructs the original text from a JSON-serialized inverted index (mapping words to their positions).
-pub fn revert_inverted_index(
-    text: Option<&[u8]>,
-    null_if_equals: Option<&[String]>,
-) -> Option<String> {
-    let bytes = text?;
-    if bytes.is_empty() {
-        return None;
+/// Checks whether a comma-separated part of a name list is only suffixes, e.g. the "Jr." of "Smith, John, Jr.".
+fn is_name_suffix(part: &str) -> bool {
+    let mut tokens = part.split_whitespace().peekable();
+    tokens.peek().is_some()
+        && tokens.all(|token| NAME_SUFFIXES.contains(&affix_key(token).as_str()))
+}
+
+/// Splits names separated by commas, keeping inverted names like "Smith, J." together along with any suffixes
+/// following them.
+fn split_comma_names(text: &str) -> Vec<String> {
+    let parts: Vec<&str> = text
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < parts.len() {
+        if let Some(name) = names.last_mut().filter(|_| is_name_suffix(parts[i])) {
+            *name = format!("{name}, {}", parts[i]);
+            i += 1;
+            continue;
+        }
+        match parts.get(i + 1) {
+            Some(given) if is_inverted_name(parts[i], given) => {
+                names.push(format!("{}, {given}", parts[i]));
+                i += 2;
+            },
+            _ => {
+                names.push(parts[i].to_string());
+                i += 1;
+            },
+        }
     }
+    names
+}
 
-    // Parse directly from bytes
-    let data: HashMap<String, Vec<u32>> = match serde_json::from_slice(bytes) {
-        Ok(v) => v,
-        Err(e) => {
-            warn!("revert_inverted_index: invalid json: {e}");
-            return None;
-        },
-    };
+/// Splits a list of names joined by conjunctions, unless it is an organization whose name includes one, e.g.
+/// "Department of Health and Human Services". An organization is only split when the other side is a full person
+/// name, e.g. "Jane Smith and Alpine Research Consortium".
+fn split_conjunctions<'a>(text: &'a str, options: &NameOptions) -> Vec<&'a str> {
+    let pieces: Vec<&str> = NAME_CONJUNCTION_RE.split(text).collect();
+    if pieces.len() > 1 && is_organization_with_options(text, options) {
+        let has_person = pieces.iter().any(|piece| {
+            !is_organization_with_options(piece, options) && piece.split_whitespace().count() >= 2
+        });
+        if !has_person {
+            return vec![text];
+        }
+    }
+    pieces
+}
+
+/// Splits a string holding several people or organizations into their names, e.g. "Smith, J.; Doe, A. and Lee, K."
+/// into "Smith, J.", "Doe, A." and "Lee, K.". Names are separated by semicolons, vertical bars, newlines,
+/// conjunctions and commas, with commas inside inverted names ("Smith, Jane") and organization names
+/// ("University of California, Berkeley") kept. A trailing "et al." is dropped.
+pub fn split_names(text: &str) -> Vec<String> {
+    split_names_with_options(text, &NameOptions::default())
+}
+
+/// Splits a string holding several names like `split_names`, recognizing organizations with
+/// `is_organization_with_options`.
+fn split_names_with_options(text: &str, options: &NameOptions) -> Vec<String> {
+    let text = ET_AL_RE.replace_all(text, "");
+    let mut names = Vec::new();
+    for segment in NAME_LIST_SEPARATOR_RE.split(&text) {
+        for piece in split_conjunctions(segment.trim(), options) {
+            let piece = piece.trim().trim_matches(',').trim();
+            if piece.is_empty() {
+                continue;
+            }
+            if is_organization_with_options(piece, options) {
+                names.push(piece.to_string());
+            } else {
+                names.extend(split_comma_names(piece));
+            }
+        }
+    }
+    names
+}
+
+/// Splits a string holding several names with `split_names` and parses each with `parse_name_with_options`.
+/// Organizations are returned with only their cleaned full name.
+pub fn parse_names_list(
+    text: &str,
+    recase_surname: bool,
+    options: &NameOptions,
+) -> Vec<ParsedName> {
+    split_names_with_options(text, options)
+        .iter()
+        .map(|name| parse_name_with_options(None, None, Some(name), recase_surname, options))
+        .collect()
+}
+
+/// Splits and parses several names like `parse_names_list`, but returns a `ParseError` for each name that no parser
+/// recognized, by its position in the list, instead of logging a warning.
+pub fn parse_names_list_with_errors(
+    text: &str,
+    recase_surname: bool,
+    options: &NameOptions,
+) -> (Vec<ParsedName>, Vec<ParseError>) {
+    names_with_errors(
+        split_names_with_options(text, options)
+            .iter()
+            .map(|name| {
+                parse_name_reporting_fallback(None, None, Some(name), recase_surname, options)
+            })
+            .collect(),
+    )
+}
+
+/// Decodes the named HTML entities common in metadata, e.g. "&amp;", and decimal or hexadecimal numeric entities.
+/// Unknown entities are left as they are.
+pub fn decode_html_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse().ok()))
+                    .flatten()
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Cleans an organization name into a canonical form: HTML entities are decoded, abbreviations such as "Dept." are
+/// expanded and whitespace is collapsed, e.g. "Dept. of Health &amp; Human Services" →
+/// "Department of Health & Human Services".
+fn normalize_organization_name(text: &str) -> String {
+    decode_html_entities(text)
+        .split_whitespace()
+        .map(|word| {
+            let key = organization_word_key(word);
+            let comma = if word.ends_with(',') { "," } else { "" };
+            ORGANIZATION_ABBREVIATIONS
+                .iter()
+                .find(|(abbreviation, _)| *abbreviation == key)
+                .map(|(_, expansion)| format!("{expansion}{comma}"))
+                .unwrap_or_else(|| word.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A value that a batch function couldn't parse, reported in its error-collection mode rather than logged as a
+/// warning and dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Index of the value in the batch.
+    pub row: usize,
+    /// The field of a record that couldn't be parsed, or None when the whole value couldn't be.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Parses a JSON-serialized inverted index (mapping words to their positions) into its words in position order,
+/// skipping gaps. Returns None when the index is empty or invalid.
+pub fn inverted_index_words(text: Option<&[u8]>) -> Option<Vec<String>> {
+    try_inverted_index_words(text).unwrap_or_else(|e| {
+        warn!("revert_inverted_index: invalid json: {e}");
+        None
+    })
+}
+
+/// Parses a JSON-serialized inverted index like `inverted_index_words`, returning the JSON error of an invalid
+/// index.
+fn try_inverted_index_words(text: Option<&[u8]>) -> serde_json::Result<Option<Vec<String>>> {
+    match text {
+        // Parse directly from bytes
+        Some(bytes) if !bytes.is_empty() => parse_inverted_index(bytes).map(Some),
+        _ => Ok(None),
+    }
+}
 
+/// Parses a JSON-serialized inverted index into its words in position order, skipping gaps.
+fn parse_inverted_index(bytes: &[u8]) -> serde_json::Result<Vec<String>> {
+    let data: HashMap<String, Vec<u32>> = serde_json::from_slice(bytes)?;
+    Ok(words_by_position(data))
+}
+
+/// Orders the words of a parsed inverted index by position, skipping gaps.
+pub fn words_by_position(data: HashMap<String, Vec<u32>>) -> Vec<String> {
     // Build words array by position
     let mut words: Vec<Option<String>> = Vec::new();
     for (word, positions) in data {
@@ -178,8 +1120,17 @@ pub fn revert_inverted_index(
         }
     }
 
-    // Join in order (skip gaps)
-    let mut iter = words.into_iter().flatten();
+    // Skip gaps
+    words.into_iter().flatten().collect()
+}
+
+/// Joins the words of a reverted inverted index into text, trimming and stripping markup from the result.
+pub fn join_abstract_words(
+    words: Vec<String>,
+    null_if_equals: Option<&[String]>,
+) -> Option<String> {
+    // Join in order
+    let mut iter = words.into_iter();
     let first = iter.next()?;
     let mut out = String::with_capacity(first.len() + 16);
     out.push_str(&first);
@@ -197,21 +1148,4421 @@ pub fn revert_inverted_index(
     strip_markup(Some(trimmed), null_if_equals)
 }
 
-/// Removes HTML tags and surrounding whitespace from the input text, with an option to treat specific results as null.
-pub fn strip_markup(text: Option<&str>, null_if_equals: Option<&[String]>) -> Option<String> {
-    let s = text?;
-    let stripped = strip_tags(s);
-    let trimmed = stripped.trim();
+/// Reconstructs the original text from a JSON-serialized inverted index (mapping words to their positions).
+pub fn revert_inverted_index(
+    text: Option<&[u8]>,
+    null_if_equals: Option<&[String]>,
+) -> Option<String> {
+    join_abstract_words(inverted_index_words(text)?, null_if_equals)
+}
 
-    if trimmed.is_empty() {
+/// Builds an OpenAlex-style JSON-serialized inverted index (mapping words to their positions) from text, the inverse
+/// of `revert_inverted_index`. Words are split on whitespace and kept as they are, including case and punctuation,
+/// and appear in the order of their first occurrence. Returns None for empty text.
+pub fn build_inverted_index(text: Option<&str>) -> Option<String> {
+    let mut index: Vec<(&str, Vec<usize>)> = Vec::new();
+    let mut lookup: HashMap<&str, usize> = HashMap::new();
+    for (position, word) in text?.split_whitespace().enumerate() {
+        let entry = *lookup.entry(word).or_insert_with(|| {
+            index.push((word, Vec::new()));
+            index.len() - 1
+        });
+        index[entry].1.push(position);
+    }
+    if index.is_empty() {
         return None;
     }
 
-    if let Some(values) = null_if_equals {
-        if values.iter().any(|v| trimmed == v.as_str()) {
-            return None;
+    let entries: Vec<String> = index
+        .iter()
+        .map(|(word, positions)| {
+            format!(
+                "{}:{}",
+                serde_json::Value::from(*word),
+                serde_json::Value::from(positions.as_slice())
+            )
+        })
+        .collect();
+    Some(format!("{{{}}}", entries.join(",")))
+}
+
+/// Reverts many JSON-serialized inverted indexes with `revert_inverted_index` in parallel on the current rayon thread
+/// pool, preserving order.
+pub fn revert_inverted_index_many(
+    texts: &[Option<Vec<u8>>],
+    null_if_equals: Option<&[String]>,
+) -> Vec<Option<String>> {
+    texts
+        .par_iter()
+        .map(|text| revert_inverted_index(text.as_deref(), null_if_equals))
+        .collect()
+}
+
+/// Reverts a column of JSON-serialized inverted indexes with `revert_inverted_index` in parallel on the current rayon
+/// thread pool, reading each index directly from the array's buffer. Null, empty and invalid indexes give null
+/// strings.
+pub fn revert_inverted_indexes(
+    indexes: &LargeBinaryArray,
+    null_if_equals: Option<&[String]>,
+) -> StringArray {
+    let texts: Vec<Option<String>> = (0..indexes.len())
+        .into_par_iter()
+        .map(|i| {
+            let index = indexes.is_valid(i).then(|| indexes.value(i));
+            revert_inverted_index(index, null_if_equals)
+        })
+        .collect();
+    StringArray::from(texts)
+}
+
+/// Reverts many JSON-serialized inverted indexes like `revert_inverted_index_many`, but returns a `ParseError` for
+/// each invalid index instead of logging a warning. Invalid indexes still revert to None.
+pub fn revert_inverted_index_many_with_errors(
+    texts: &[Option<Vec<u8>>],
+    null_if_equals: Option<&[String]>,
+) -> (Vec<Option<String>>, Vec<ParseError>) {
+    let reverted: Vec<serde_json::Result<Option<String>>> = texts
+        .par_iter()
+        .map(|text| {
+            let words = try_inverted_index_words(text.as_deref())?;
+            Ok(words.and_then(|words| join_abstract_words(words, null_if_equals)))
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    let texts = reverted
+        .into_iter()
+        .enumerate()
+        .map(|(row, text)| {
+            text.unwrap_or_else(|e| {
+                errors.push(ParseError {
+                    row,
+                    field: None,
+                    message: format!("invalid json: {e}"),
+                });
+                None
+            })
+        })
+        .collect();
+    (texts, errors)
+}
+
+/// Counts of lines handled by `revert_file`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RevertFileStats {
+    pub processed: usize,
+    pub malformed: usize,
+}
+
+/// Reverts a single NDJSON line into its output record, or None when the line is malformed.
+fn revert_line(line_number: usize, line: &[u8]) -> Option<String> {
+    let words = parse_inverted_index(line).ok()?;
+    let record = serde_json::json!({
+        "line": line_number,
+        "abstract": join_abstract_words(words, None),
+    });
+    Some(record.to_string())
+}
+
+/// Reconstructs abstracts from an NDJSON file with one JSON-serialized inverted index per line, writing an NDJSON
+/// file with a `{"line": ..., "abstract": ...}` record per input line, where "line" is the zero-based input line
+/// number. Blank lines are skipped and malformed lines are counted separately rather than written.
+///
+/// Lines are processed in chunks of `chunk_size`, in parallel with rayon when `parallel` is set. Output records
+/// follow input order when `ordered` is set; otherwise records within a chunk are written as they complete and
+/// only the "line" field identifies their input line. `progress` is called with the processed count after each
+/// chunk, and an error from it stops processing.
+pub fn revert_file(
+    input: &Path,
+    output: &Path,
+    parallel: bool,
+    ordered: bool,
+    chunk_size: usize,
+    mut progress: impl FnMut(usize) -> io::Result<()>,
+) -> io::Result<RevertFileStats> {
+    let reader = BufReader::new(File::open(input)?);
+    let writer = Mutex::new(BufWriter::new(File::create(output)?));
+    let mut stats = RevertFileStats::default();
+
+    let mut lines = reader.split(b'\n').enumerate();
+    loop {
+        let mut chunk = Vec::with_capacity(chunk_size);
+        for (line_number, line) in lines.by_ref() {
+            let mut line = line?;
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            chunk.push((line_number, line));
+            if chunk.len() >= chunk_size {
+                break;
+            }
         }
+        if chunk.is_empty() {
+            break;
+        }
+
+        let chunk_len = chunk.len();
+        let written = if parallel && !ordered {
+            let written = AtomicUsize::new(0);
+            chunk
+                .par_iter()
+                .filter_map(|(line_number, line)| revert_line(*line_number, line))
+                .try_for_each(|record| {
+                    written.fetch_add(1, Ordering::Relaxed);
+                    writeln!(writer.lock().unwrap(), "{record}")
+                })?;
+            written.into_inner()
+        } else {
+            let records: Vec<String> = if parallel {
+                chunk
+                    .par_iter()
+                    .filter_map(|(line_number, line)| revert_line(*line_number, line))
+                    .collect()
+            } else {
+                chunk
+                    .iter()
+                    .filter_map(|(line_number, line)| revert_line(*line_number, line))
+                    .collect()
+            };
+            let mut writer = writer.lock().unwrap();
+            for record in &records {
+                writeln!(writer, "{record}")?;
+            }
+            records.len()
+        };
+
+        stats.processed += written;
+        stats.malformed += chunk_len - written;
+        progress(stats.processed)?;
     }
 
-    Some(trimmed.to_string())
+    writer.into_inner().unwrap().flush()?;
+    Ok(stats)
+}
+
+/// A predicate choosing the records of a JSONL file to keep, see `JsonlRecords::filter`.
+type RecordFilter = Box<dyn Fn(&serde_json::Value) -> bool + Send>;
+
+/// Iterator over the records of a JSONL file, see `read_jsonl`.
+pub struct JsonlRecords {
+    lines: std::iter::Enumerate<io::Split<Box<dyn BufRead + Send>>>,
+    fields: Option<Vec<String>>,
+    keep: Option<RecordFilter>,
+    skipped: usize,
+}
+
+impl Iterator for JsonlRecords {
+    type Item = io::Result<serde_json::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (line_number, line) in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let record = serde_json::from_slice(&line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid json on line {}: {e}", line_number + 1),
+                )
+            });
+            if let (Ok(record), Some(keep)) = (&record, &self.keep) {
+                if !keep(record) {
+                    self.skipped += 1;
+                    continue;
+                }
+            }
+            return Some(record.map(|record| self.project(record)));
+        }
+        None
+    }
+}
+
+impl JsonlRecords {
+    /// Skips the records that `keep` rejects, e.g. those an incremental load has already seen. Records are filtered
+    /// before their fields are projected, so `keep` can read fields that aren't kept.
+    pub fn filter(mut self, keep: impl Fn(&serde_json::Value) -> bool + Send + 'static) -> Self {
+        self.keep = Some(Box::new(keep));
+        self
+    }
+
+    /// The number of records skipped by the filter so far.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Keeps only the projected top-level fields of an object record, with missing fields as null.
+    fn project(&self, record: serde_json::Value) -> serde_json::Value {
+        match (&self.fields, record) {
+            (Some(fields), serde_json::Value::Object(mut object)) => {
+                let projected = fields
+                    .iter()
+                    .map(|field| {
+                        let value = object.remove(field).unwrap_or(serde_json::Value::Null);
+                        (field.clone(), value)
+                    })
+                    .collect();
+                serde_json::Value::Object(projected)
+            },
+            (_, record) => record,
+        }
+    }
+}
+
+/// Opens a plain or gzip-compressed JSONL file for streaming, parsing one JSON record per line and skipping blank
+/// lines. Gzip compression is detected from the file's magic bytes rather than its extension. When `fields` is given
+/// only those top-level fields of object records are kept.
+pub fn read_jsonl(path: &Path, fields: Option<Vec<String>>) -> io::Result<JsonlRecords> {
+    Ok(JsonlRecords {
+        lines: open_text(path)?.split(b'\n').enumerate(),
+        fields,
+        keep: None,
+        skipped: 0,
+    })
+}
+
+/// Opens a plain or gzip-compressed text file for buffered reading, detecting gzip compression from the file's
+/// magic bytes.
+pub(crate) fn open_text(path: &Path) -> io::Result<Box<dyn BufRead + Send>> {
+    let mut file = BufReader::new(File::open(path)?);
+    let is_gzip = file.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    Ok(if is_gzip {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(file)
+    })
+}
+
+/// How MathML formulas are handled when stripping markup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MathMl {
+    /// Strip the tags and keep the formula's text, e.g. "x2" for x squared.
+    #[default]
+    Keep,
+    /// Drop formulas entirely.
+    Remove,
+    /// Replace each formula with its LaTeX `alttext` between dollar signs, e.g. "$x^2$", or "[formula]" without one.
+    Placeholder,
+}
+
+/// Built-in families of placeholder values treated as null by `NullRules`, matched case-insensitively and with an
+/// optional trailing period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullPreset {
+    /// "N/A", "NA", "none", "null", "unknown", "not applicable", ":unav" and similar.
+    NotAvailable,
+    /// "TBD", "TBA", "to be determined", "pending", "coming soon" and similar.
+    ToBeDetermined,
+    /// "No abstract available", "Abstract not available", "No description provided" and similar.
+    NoAbstract,
+    /// Text made only of whitespace, non-breaking space entities such as "&nbsp;" and zero-width characters.
+    Blank,
+    /// Text made only of punctuation and symbols, e.g. "-", "..." or "?".
+    Punctuation,
+}
+
+impl NullPreset {
+    pub const ALL: [NullPreset; 5] = [
+        NullPreset::NotAvailable,
+        NullPreset::ToBeDetermined,
+        NullPreset::NoAbstract,
+        NullPreset::Blank,
+        NullPreset::Punctuation,
+    ];
+
+    /// Parses a preset from its snake case name, e.g. "not_available".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "not_available" => Some(NullPreset::NotAvailable),
+            "to_be_determined" => Some(NullPreset::ToBeDetermined),
+            "no_abstract" => Some(NullPreset::NoAbstract),
+            "blank" => Some(NullPreset::Blank),
+            "punctuation" => Some(NullPreset::Punctuation),
+            _ => None,
+        }
+    }
+
+    /// The regex matching the preset's values in full.
+    fn pattern(self) -> &'static str {
+        match self {
+            NullPreset::NotAvailable => {
+                r"n/?a|n\.a|nil|none|null|unknown|:unav|not\s+(?:applicable|available|provided|specified)"
+            },
+            NullPreset::ToBeDetermined => {
+                r"tb[acd]|to\s+be\s+(?:announced|confirmed|decided|determined)|pending|coming\s+soon"
+            },
+            NullPreset::NoAbstract => concat!(
+                r"(?:no|without)\s+(?:abstract|description|summary)",
+                r"(?:\s+(?:is\s+)?(?:available|provided|received))?(?:\s+for\s+this\s+\w+)?",
+                r"|(?:abstract|description)\s+not\s+(?:available|provided)",
+                r"|this\s+\w+\s+has\s+no\s+abstract",
+            ),
+            NullPreset::Blank => {
+                r"(?:\s|&nbsp;|&#160;|&#x[aA]0;|[\u{200B}-\u{200D}\u{2060}\u{FEFF}])*"
+            },
+            NullPreset::Punctuation => r"[\p{P}\p{S}\s]+",
+        }
+    }
+}
+
+/// Rules for treating placeholder values as null with `nullify`: exact values, regex patterns that must match the
+/// whole trimmed text, and presets. The default rules use every preset.
+#[derive(Debug, Clone)]
+pub struct NullRules {
+    values: HashSet<String>,
+    case_insensitive: bool,
+    patterns: RegexSet,
+}
+
+impl NullRules {
+    /// Compiles the rules, failing when a pattern is not a valid regex. `case_insensitive` applies to the values and
+    /// patterns; presets always ignore case.
+    pub fn new(
+        values: &[String],
+        patterns: &[String],
+        presets: &[NullPreset],
+        case_insensitive: bool,
+    ) -> Result<Self, regex::Error> {
+        let flags = if case_insensitive { "(?i)" } else { "" };
+        let patterns = patterns
+            .iter()
+            .map(|pattern| format!("{flags}^(?:{pattern})$"))
+            .chain(
+                presets
+                    .iter()
+                    .map(|preset| format!(r"(?i)^(?:{})\.?$", preset.pattern())),
+            );
+        let values = values
+            .iter()
+            .map(|value| {
+                let value = value.trim();
+                if case_insensitive {
+                    default_case_fold_str(value)
+                } else {
+                    value.to_string()
+                }
+            })
+            .collect();
+        Ok(Self {
+            values,
+            case_insensitive,
+            patterns: RegexSet::new(patterns)?,
+        })
+    }
+
+    /// Whether trimmed text matches any of the rules.
+    pub fn matches(&self, text: &str) -> bool {
+        let is_value = if self.case_insensitive {
+            self.values.contains(&default_case_fold_str(text))
+        } else {
+            self.values.contains(text)
+        };
+        is_value || self.patterns.is_match(text)
+    }
+}
+
+impl Default for NullRules {
+    fn default() -> Self {
+        Self::new(&[], &[], &NullPreset::ALL, false).expect("preset patterns are valid")
+    }
+}
+
+/// The default `NullRules`, compiled once.
+pub static DEFAULT_NULL_RULES: LazyLock<NullRules> = LazyLock::new(NullRules::default);
+
+/// Trims text, returning None when it is empty or a placeholder value according to `rules`.
+pub fn nullify(text: Option<&str>, rules: &NullRules) -> Option<String> {
+    let trimmed = text?.trim();
+    (!trimmed.is_empty() && !rules.matches(trimmed)).then(|| trimmed.to_string())
+}
+
+/// Options for `strip_markup_with_options`. The defaults only strip tags, like `strip_markup`.
+#[derive(Debug, Clone, Default)]
+pub struct MarkupOptions {
+    /// Decodes named and numeric HTML entities, e.g. "&amp;" and "&#233;", after tags are stripped.
+    pub decode_entities: bool,
+    /// Drops JATS cross-reference labels (`<xref>`) and section titles (`<title>`), with or without the `jats:`
+    /// prefix, keeping the content of other elements such as `<italic>`.
+    pub jats: bool,
+    pub mathml: MathMl,
+    /// Treats stripped text matching these rules as null, in addition to `null_if_equals`.
+    pub null_rules: Option<NullRules>,
+}
+
+/// JATS cross-references, e.g. the citation label in `<xref ref-type="bibr" rid="b1">1</xref>`.
+static JATS_XREF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<(?:jats:)?xref\b[^>]*>.*?</(?:jats:)?xref>").unwrap());
+
+/// JATS section titles such as `<jats:title>Abstract</jats:title>`, which are headings rather than text.
+static JATS_TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<(?:jats:)?title\b[^>]*>.*?</(?:jats:)?title>").unwrap());
+
+/// MathML formulas, with or without the `mml:` prefix, capturing the opening tag's attributes.
+static MATHML_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<(?:mml:)?math\b([^>]*)>.*?</(?:mml:)?math>").unwrap());
+
+/// The `alttext` attribute of a MathML formula, which usually holds its LaTeX source.
+static ALTTEXT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"alttext\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+/// Removes HTML tags and surrounding whitespace from the input text, with an option to treat specific results as null.
+pub fn strip_markup(text: Option<&str>, null_if_equals: Option<&[String]>) -> Option<String> {
+    strip_markup_with_options(text, null_if_equals, &MarkupOptions::default())
+}
+
+/// Removes markup and surrounding whitespace from the input text like `strip_markup`, optionally handling JATS and
+/// MathML elements before tags are stripped and decoding HTML entities after. Results equal to one of
+/// `null_if_equals` are treated as null.
+pub fn strip_markup_with_options(
+    text: Option<&str>,
+    null_if_equals: Option<&[String]>,
+    options: &MarkupOptions,
+) -> Option<String> {
+    let mut s = Cow::Borrowed(text?);
+    if options.jats {
+        s = Cow::Owned(JATS_XREF_RE.replace_all(&s, "").into_owned());
+        s = Cow::Owned(JATS_TITLE_RE.replace_all(&s, " ").into_owned());
+    }
+    match options.mathml {
+        MathMl::Keep => {},
+        MathMl::Remove => s = Cow::Owned(MATHML_RE.replace_all(&s, "").into_owned()),
+        MathMl::Placeholder => {
+            s = Cow::Owned(
+                MATHML_RE
+                    .replace_all(&s, |caps: &regex::Captures| {
+                        ALTTEXT_RE
+                            .captures(&caps[1])
+                            .and_then(|alt| alt.get(1).or(alt.get(2)))
+                            .map(|alt| alt.as_str().trim())
+                            .filter(|alt| !alt.is_empty())
+                            .map_or("[formula]".to_string(), |alt| format!("${alt}$"))
+                    })
+                    .into_owned(),
+            )
+        },
+    }
+
+    let stripped = strip_tags(&s);
+    let stripped = if options.decode_entities {
+        decode_html_entities(&stripped)
+    } else {
+        stripped
+    };
+    let trimmed = stripped.trim();
+
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    if let Some(values) = null_if_equals {
+        if values.iter().any(|v| trimmed == v.as_str()) {
+            return None;
+        }
+    }
+    if let Some(rules) = &options.null_rules {
+        return nullify(Some(trimmed), rules);
+    }
+
+    Some(trimmed.to_string())
+}
+
+/// Strips markup from many texts with `strip_markup_with_options` in parallel on the current rayon thread pool,
+/// preserving order.
+pub fn strip_markup_many(
+    texts: &[Option<String>],
+    null_if_equals: Option<&[String]>,
+    options: &MarkupOptions,
+) -> Vec<Option<String>> {
+    texts
+        .par_iter()
+        .map(|text| strip_markup_with_options(text.as_deref(), null_if_equals, options))
+        .collect()
+}
+
+/// Strips markup from a column of texts with `strip_markup_with_options` in parallel on the current rayon thread
+/// pool, reading each text directly from the array's buffer. Stripped texts matching `null_rules` are treated as null,
+/// as are those matching the `null_rules` of `options`. Returns an array of the same string type as `texts`, failing
+/// when `texts` isn't a string array.
+pub fn strip_markup_batch(
+    texts: &dyn Array,
+    null_rules: Option<&NullRules>,
+    options: &MarkupOptions,
+) -> Result<ArrayRef, ArrowError> {
+    fn strip<'a>(
+        texts: impl ArrayAccessor<Item = &'a str> + Sync,
+        null_rules: Option<&NullRules>,
+        options: &MarkupOptions,
+    ) -> Vec<Option<String>> {
+        (0..texts.len())
+            .into_par_iter()
+            .map(|i| {
+                let text = texts.is_valid(i).then(|| texts.value(i));
+                strip_markup_with_options(text, None, options)
+                    .filter(|text| !null_rules.is_some_and(|rules| rules.matches(text)))
+            })
+            .collect()
+    }
+
+    Ok(match texts.data_type() {
+        DataType::Utf8 => Arc::new(StringArray::from(strip(
+            texts.as_string::<i32>(),
+            null_rules,
+            options,
+        ))),
+        DataType::LargeUtf8 => Arc::new(LargeStringArray::from(strip(
+            texts.as_string::<i64>(),
+            null_rules,
+            options,
+        ))),
+        DataType::Utf8View => Arc::new(StringViewArray::from(strip(
+            texts.as_string_view(),
+            null_rules,
+            options,
+        ))),
+        data_type => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "texts must be a string array, got {data_type}"
+            )))
+        },
+    })
+}
+
+/// A leading "Abstract" or "Summary" label followed by punctuation, e.g. "Abstract:" or "SUMMARY -".
+static ABSTRACT_LABEL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:graphical\s+)?(?:abstract|summary)\s*[:.\-–—]\s*").unwrap()
+});
+
+/// A leading "Abstract" or "Summary" label without punctuation, as left by stripping a JATS section title, when a
+/// capitalized word follows, e.g. "Abstract The study", capturing the word's first letter.
+static BARE_ABSTRACT_LABEL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:Abstract|ABSTRACT|Summary|SUMMARY)\s+(\p{Lu})").unwrap());
+
+/// A copyright or rights statement starting a sentence, which runs to the end of the text, e.g. "© 2020 Elsevier
+/// Ltd. All rights reserved.", capturing the statement. "(c)" must be followed by a year, or by "the" and a year or
+/// "rights reserved" in the same sentence, e.g. "(c) The Author(s) 2021", as it is also an item of a list.
+static COPYRIGHT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:^|[.!?]\s+)(©|\(c\)\s*(?:[0-9]{4}|the\b[^.!?]*?(?:\b[0-9]{4}\b|rights\s+reserved))|copyright\s*(?:©|\(c\)|[0-9]{4}|the\s+authors?\b)|all\s+rights\s+reserved)").unwrap()
+});
+
+/// The starts of publisher boilerplate sentences: licensing and open access statements, publisher and supplementary
+/// material notices, and funding statements.
+static BOILERPLATE_SENTENCE_RE: LazyLock<RegexSet> = LazyLock::new(|| {
+    RegexSet::new([
+        r"(?i)^this\s+article\s+is\s+protected\s+by\s+copyright",
+        r"(?i)^this\s+is\s+an\s+open[\s\-]access\s+article",
+        r"(?i)^(?:this\s+(?:article|work|paper)\s+is\s+)?(?:distributed|licensed|made\s+available)\s+under\s+(?:the\s+terms\s+of\s+)?(?:a|the)\s+(?:creative\s+commons|cc[\s\-]by)",
+        r"(?i)^published\s+by\s+(?:elsevier|springer|wiley|john\s+wiley|taylor|informa|sage|oxford|cambridge|mdpi|frontiers|the\s+royal\s+society|iop|aip|acs|ieee|hindawi|de\s+gruyter|karger|thieme|bmj|copernicus|cell\s+press)\b",
+        r"(?i)^springer\s+nature\s+remains\s+neutral",
+        r"(?i)^this\s+article\s+is\s+part\s+of\s+(?:the|a)\s+(?:theme|special|discussion)\s+(?:issue|meeting|topic)",
+        r"(?i)^the\s+online\s+version\s+(?:of\s+this\s+article\s+)?contains\s+supplementary\s+material",
+        r"(?i)^supplementary\s+(?:information|data|materials?)\b[^.]{0,60}\bavailable",
+        r"(?i)^(?:this|the)\s+(?:work|research|study|project|paper)\s+(?:was|is|has\s+been)\s+(?:\w+\s+){0,2}(?:supported|funded|financed|sponsored)\s+(?:in\s+part\s+)?by\b",
+        r"(?i)^funding(?:\s+(?:information|statement|sources?))?\s*:",
+    ])
+    .unwrap()
+});
+
+/// Rules for `strip_boilerplate`: regexes whose matches are removed, along with any whitespace following them, in
+/// addition to the built-in patterns. The default rules only use the built-in patterns.
+#[derive(Debug, Clone, Default)]
+pub struct BoilerplateRules {
+    patterns: Vec<Regex>,
+}
+
+impl BoilerplateRules {
+    /// Compiles the rules, failing when a pattern is not a valid regex.
+    pub fn new(patterns: &[String], case_insensitive: bool) -> Result<Self, regex::Error> {
+        let flags = if case_insensitive { "(?i)" } else { "" };
+        let patterns = patterns
+            .iter()
+            .map(|pattern| Regex::new(&format!(r"{flags}(?:{pattern})\s*")))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+}
+
+/// Removes boilerplate from an abstract, which would otherwise make unrelated works from the same publisher look
+/// similar: a leading "Abstract" or "Summary" label, a trailing copyright or rights statement and everything after
+/// it, and publisher boilerplate sentences such as open access licences, supplementary material notices and funding
+/// statements. Matches of the patterns in `rules` are removed first, and the remaining sentences, split with
+/// `split_sentences`, are joined by single spaces. Returns None when nothing else is left.
+pub fn strip_boilerplate(text: Option<&str>, rules: &BoilerplateRules) -> Option<String> {
+    let mut text = text?.trim().to_string();
+    for pattern in &rules.patterns {
+        text = pattern.replace_all(&text, "").trim().to_string();
+    }
+
+    let start = if let Some(m) = ABSTRACT_LABEL_RE.find(&text) {
+        m.end()
+    } else if let Some(caps) = BARE_ABSTRACT_LABEL_RE.captures(&text) {
+        caps.get(1).unwrap().start()
+    } else {
+        0
+    };
+    let end = COPYRIGHT_RE
+        .captures(&text[start..])
+        .map_or(text.len(), |caps| start + caps.get(1).unwrap().start());
+
+    let kept: Vec<String> = split_sentences(&text[start..end])
+        .into_iter()
+        .filter(|sentence| !BOILERPLATE_SENTENCE_RE.is_match(sentence))
+        .collect();
+    (!kept.is_empty()).then(|| kept.join(" "))
+}
+
+/// Maps a Unicode subscript or superscript digit to its regular ASCII digit.
+fn subscript_digit(c: char) -> Option<char> {
+    match c {
+        '\u{2080}'..='\u{2089}' => char::from_digit(c as u32 - 0x2080, 10),
+        '\u{2074}'..='\u{2079}' => char::from_digit(c as u32 - 0x2070, 10),
+        '\u{2070}' => Some('0'),
+        '\u{00B9}' => Some('1'),
+        '\u{00B2}' => Some('2'),
+        '\u{00B3}' => Some('3'),
+        _ => None,
+    }
+}
+
+/// Site and database names appended to titles harvested from landing pages, e.g. "Title | Nature" or
+/// "Title - PubMed".
+const TITLE_SITE_MARKERS: &[&str] = &[
+    "pubmed",
+    "pmc",
+    "europe pmc",
+    "ncbi",
+    "sciencedirect",
+    "springerlink",
+    "springer",
+    "wiley online library",
+    "taylor & francis online",
+    "jstor",
+    "researchgate",
+    "semantic scholar",
+    "google scholar",
+    "arxiv",
+    "biorxiv",
+    "medrxiv",
+    "ieee xplore",
+    "acm digital library",
+    "oxford academic",
+    "cambridge core",
+    "sage journals",
+    "plos one",
+    "frontiers",
+    "mdpi",
+    "zenodo",
+    "figshare",
+    "dryad",
+];
+
+/// Journal names that are also common title words, e.g. "Nature" or "Cell", only taken as site markers when they are
+/// written as the journal's name after a pipe, e.g. "Title | Nature", as " - Nature" may be part of the title.
+const TITLE_JOURNAL_MARKERS: &[&str] = &["Nature", "Science", "Cell"];
+
+/// Removes trailing " | Site" or " - Site" segments when the segment is a known site marker, or a journal name of
+/// `TITLE_JOURNAL_MARKERS` after a pipe. Other segments are kept, so titles with a legitimate " - " or " | "
+/// separator are unchanged.
+fn strip_title_site_suffixes(title: &str) -> &str {
+    let mut title = title.trim_end();
+    loop {
+        let Some((before, suffix)) = [" | ", " - ", " – ", " — "]
+            .iter()
+            .filter_map(|separator| title.rsplit_once(separator))
+            .max_by_key(|(before, _)| before.len())
+        else {
+            return title;
+        };
+
+        let suffix = suffix.trim();
+        let is_marker = TITLE_SITE_MARKERS.contains(&suffix.to_lowercase().as_str())
+            || (title[before.len()..].starts_with(" | ")
+                && TITLE_JOURNAL_MARKERS.contains(&suffix));
+        if before.trim().is_empty() || !is_marker {
+            return title;
+        }
+        title = before.trim_end();
+    }
+}
+
+/// Normalizes a work title for deduplication: strips markup, replaces punctuation with spaces, applies Unicode case
+/// folding and collapses whitespace. Optionally converts Unicode sub/superscript digits to regular digits so that
+/// "H₂O" and "H<sub>2</sub>O" normalize to the same title, strips trailing site names such as " | Nature", and drops
+/// `stop_words` (compared case-folded). Returns None when nothing is left.
+pub fn normalize_title(
+    text: Option<&str>,
+    normalize_subscripts: bool,
+    strip_site_suffixes: bool,
+    stop_words: Option<&[String]>,
+) -> Option<String> {
+    let stripped = strip_markup(text, None)?;
+    let stripped = clean_text(&stripped);
+    let stripped = if strip_site_suffixes {
+        strip_title_site_suffixes(&stripped)
+    } else {
+        &stripped
+    };
+
+    let mut out = String::with_capacity(stripped.len());
+    for c in stripped.chars() {
+        let c = if normalize_subscripts {
+            subscript_digit(c).unwrap_or(c)
+        } else {
+            c
+        };
+
+        if c.is_alphanumeric() {
+            out.push(c);
+        } else {
+            out.push(' ');
+        }
+    }
+
+    let folded = default_case_fold_str(&out);
+    let stop_words: HashSet<String> = stop_words
+        .unwrap_or_default()
+        .iter()
+        .map(|word| default_case_fold_str(word))
+        .collect();
+    let normalized = folded
+        .split_whitespace()
+        .filter(|word| !stop_words.contains(*word))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if normalized.is_empty() {
+        return None;
+    }
+
+    Some(normalized)
+}
+
+/// Characters separating a trailing marker from the rest of a title.
+const TITLE_MARKER_SEPARATORS: &[char] = &[' ', ',', ';', ':', '-', '–', '—', '_'];
+
+/// A version marker ending a title, e.g. "v2", "(version 3)" or "- ver. 1.0.2".
+static TRAILING_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:[\s,;:\-–—_]+|\b)[(\[]?\s*(?:v|ver\.?|version)\s*([0-9]+(?:\.[0-9]+)*)\s*[)\]]?\s*$")
+        .unwrap()
+});
+
+/// A part number ending a title, e.g. "Part 2", "(part II)" or "pt. 3 of 5".
+static TRAILING_PART_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:[\s,;:\-–—_]+|\b)[(\[]?\s*(?:part|pt\.?)\s*([0-9]+|[ivxl]+)(?:\s*(?:of|/)\s*(?:[0-9]+|[ivxl]+))?\s*[)\]]?\s*$")
+        .unwrap()
+});
+
+/// A year or range of years ending a title in brackets or after punctuation, e.g. "(2019)" or ", 2010–2015".
+static TRAILING_YEARS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\s*[(\[]\s*|\s*[,;:\-–—_]\s*)((?:1[89]|20)[0-9]{2})(?:\s*[\-–—/]\s*((?:1[89]|20)[0-9]{2}))?\s*[)\]]?\s*$")
+        .unwrap()
+});
+
+/// A year mentioned anywhere in a title.
+static YEAR_MENTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b((?:1[89]|20)[0-9]{2})\b").unwrap());
+
+/// The base of a title with the version, part and year markers that distinguish the members of a family of records,
+/// e.g. the versions of a dataset, see `extract_title_metadata`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TitleMetadata {
+    /// The title without its trailing markers.
+    pub title: Option<String>,
+    /// The version number, e.g. "2" or "1.0.2".
+    pub version: Option<String>,
+    /// The part number, with Roman numerals converted.
+    pub part: Option<u32>,
+    /// The years mentioned anywhere in the title, in order without duplicates.
+    pub years: Vec<i32>,
+}
+
+/// The value of a lowercase Roman numeral made of "i", "v", "x" and "l", or None when it isn't one.
+fn roman_numeral_value(numeral: &str) -> Option<u32> {
+    let digit = |c: char| match c {
+        'i' => Some(1),
+        'v' => Some(5),
+        'x' => Some(10),
+        'l' => Some(50),
+        _ => None,
+    };
+    let digits: Vec<u32> = numeral.chars().map(digit).collect::<Option<_>>()?;
+    let value = digits.iter().enumerate().fold(0, |value, (i, &d)| {
+        if digits.get(i + 1).is_some_and(|&next| next > d) {
+            value - d as i64
+        } else {
+            value + d as i64
+        }
+    });
+    u32::try_from(value).ok().filter(|value| *value > 0)
+}
+
+/// Splits the trailing version markers ("v2", "(version 3)"), part numbers ("Part 2", "pt. II of IV") and bracketed or
+/// punctuated years ("(2019)", ", 2010–2015") from a title, in any order, so that the versions and parts of a dataset
+/// share a base title. Markers are only removed while something is left of the title, and years that follow a
+/// number, e.g. the end of "Survey 2018-2019", are kept in it. Every year the title mentions is returned, in order.
+pub fn extract_title_metadata(text: Option<&str>) -> TitleMetadata {
+    let Some(text) = text.map(str::trim).filter(|text| !text.is_empty()) else {
+        return TitleMetadata::default();
+    };
+
+    let mut metadata = TitleMetadata::default();
+    let mut title = text;
+    let mut trailing_years = Vec::new();
+    loop {
+        // A marker is kept when nothing would be left of the title
+        let strip = |re: &Regex| {
+            re.captures(title).filter(|caps| {
+                let before = &title[..caps.get(0).unwrap().start()];
+                !before.trim_end_matches(TITLE_MARKER_SEPARATORS).is_empty()
+            })
+        };
+        // Unbracketed years directly after a number end a range, e.g. "2018-2019", rather than being a marker
+        let ends_range = |caps: &regex::Captures| {
+            let whole = caps.get(0).unwrap();
+            title[..whole.start()].ends_with(|c: char| c.is_ascii_digit())
+                && !whole.as_str().contains(['(', '['])
+        };
+        let end;
+        if let Some(caps) = strip(&TRAILING_VERSION_RE) {
+            metadata.version.get_or_insert_with(|| caps[1].to_string());
+            end = caps.get(0).unwrap().start();
+        } else if let Some(caps) = strip(&TRAILING_PART_RE) {
+            let number = caps[1].to_lowercase();
+            let part = number.parse().ok().or_else(|| roman_numeral_value(&number));
+            metadata.part = metadata.part.or(part);
+            end = caps.get(0).unwrap().start();
+        } else if let Some(caps) = strip(&TRAILING_YEARS_RE).filter(|caps| !ends_range(caps)) {
+            let years = caps
+                .iter()
+                .skip(1)
+                .flatten()
+                .filter_map(|m| m.as_str().parse().ok());
+            trailing_years.splice(0..0, years);
+            end = caps.get(0).unwrap().start();
+        } else {
+            break;
+        }
+        title = title[..end].trim_end_matches(TITLE_MARKER_SEPARATORS);
+    }
+
+    let mentioned = YEAR_MENTION_RE
+        .captures_iter(title)
+        .filter_map(|caps| caps[1].parse().ok());
+    for year in mentioned.chain(trailing_years) {
+        if !metadata.years.contains(&year) {
+            metadata.years.push(year);
+        }
+    }
+    metadata.title = Some(title.to_string());
+    metadata
+}
+
+/// Title prefixes that mark a work as a likely non-article, with the type they indicate, compared lowercased.
+const TITLE_TYPE_PREFIXES: &[(&str, &str)] = &[
+    ("correction:", "correction"),
+    ("correction to", "correction"),
+    ("corrigendum", "correction"),
+    ("erratum", "correction"),
+    ("review of", "review"),
+    ("book review", "review"),
+    ("dataset:", "dataset"),
+    ("data set:", "dataset"),
+    ("data from:", "dataset"),
+    ("software:", "software"),
+];
+
+/// Bracketed markers anywhere in a title that mark a work as a likely non-article, with the type they indicate.
+const TITLE_TYPE_MARKERS: &[(&str, &str)] = &[
+    ("[dataset]", "dataset"),
+    ("(dataset)", "dataset"),
+    ("[data set]", "dataset"),
+    ("[software]", "software"),
+    ("(software)", "software"),
+];
+
+/// Classifies a work's title as a likely non-article from leading markers such as "Dataset:", "Software:",
+/// "Correction:" or "Review of", or embedded markers such as "[Dataset]". Returns "dataset", "software",
+/// "correction" or "review", or None when the title has no marker.
+pub fn title_type_hint(text: Option<&str>) -> Option<String> {
+    let title = strip_markup(text, None)?.to_lowercase();
+
+    TITLE_TYPE_PREFIXES
+        .iter()
+        .find(|(prefix, _)| title.starts_with(prefix))
+        .or_else(|| {
+            TITLE_TYPE_MARKERS
+                .iter()
+                .find(|(marker, _)| title.contains(marker))
+        })
+        .map(|(_, hint)| hint.to_string())
+}
+
+/// Tokens that carry no information about which license is meant, e.g. "License", "International" or URL parts.
+const LICENSE_FILLER_WORDS: &[&str] = &[
+    "the",
+    "license",
+    "licence",
+    "licenses",
+    "licensed",
+    "version",
+    "v",
+    "international",
+    "unported",
+    "generic",
+    "http",
+    "https",
+    "www",
+    "org",
+    "opensource",
+    "spdx",
+    "legalcode",
+    "deed",
+];
+
+/// Canonical codes for non Creative Commons licenses, keyed by their normalized phrasing (see `license_tokens`).
+const LICENSE_TABLE: &[(&str, &str)] = &[
+    ("mit", "MIT"),
+    ("apache 2", "Apache-2.0"),
+    ("apache 2 0", "Apache-2.0"),
+    ("bsd 2 clause", "BSD-2-Clause"),
+    ("simplified bsd", "BSD-2-Clause"),
+    ("bsd 3 clause", "BSD-3-Clause"),
+    ("new bsd", "BSD-3-Clause"),
+    ("modified bsd", "BSD-3-Clause"),
+    ("gpl 2", "GPL-2.0"),
+    ("gpl 2 0", "GPL-2.0"),
+    ("gplv2", "GPL-2.0"),
+    ("gnu general public 2 0", "GPL-2.0"),
+    ("gpl 3", "GPL-3.0"),
+    ("gpl 3 0", "GPL-3.0"),
+    ("gplv3", "GPL-3.0"),
+    ("gnu general public 3 0", "GPL-3.0"),
+    ("lgpl 3 0", "LGPL-3.0"),
+    ("gnu lesser general public 3 0", "LGPL-3.0"),
+    ("agpl 3 0", "AGPL-3.0"),
+    ("gnu affero general public 3 0", "AGPL-3.0"),
+    ("mpl 2 0", "MPL-2.0"),
+    ("mozilla public 2 0", "MPL-2.0"),
+    ("odc by", "ODC-By-1.0"),
+    ("odc by 1 0", "ODC-By-1.0"),
+    ("open data commons attribution", "ODC-By-1.0"),
+    ("odbl", "ODbL-1.0"),
+    ("odbl 1 0", "ODbL-1.0"),
+    ("open data commons open database", "ODbL-1.0"),
+    ("pddl", "PDDL-1.0"),
+    ("open data commons public domain dedication and", "PDDL-1.0"),
+];
+
+/// Splits a license string into lowercase alphanumeric tokens, dropping filler words and "v" version prefixes.
+fn license_tokens(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !LICENSE_FILLER_WORDS.contains(t))
+        .map(|t| {
+            t.strip_prefix('v')
+                .filter(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+                .unwrap_or(t)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Builds a Creative Commons code, e.g. "CC-BY-NC-4.0", from license tokens. Returns None when the tokens do not
+/// describe a Creative Commons license.
+fn creative_commons_code(tokens: &[String]) -> Option<String> {
+    let has = |t: &str| tokens.iter().any(|token| token == t);
+    let has_pair = |a: &str, b: &str| tokens.windows(2).any(|w| w[0] == a && w[1] == b);
+
+    let is_cc =
+        has("cc") || has("cc0") || has("creativecommons") || has_pair("creative", "commons");
+    if !is_cc {
+        return None;
+    }
+
+    if has("cc0") || has("zero") || has_pair("public", "domain") {
+        return Some("CC0-1.0".to_string());
+    }
+
+    if !(has("by") || has("attribution")) {
+        return None;
+    }
+
+    let mut code = String::from("CC-BY");
+    if has("nc") || has("noncommercial") || has_pair("non", "commercial") {
+        code.push_str("-NC");
+    }
+    if has("sa") || has("sharealike") || has_pair("share", "alike") {
+        code.push_str("-SA");
+    } else if has("nd")
+        || has("noderivatives")
+        || has("noderivs")
+        || has_pair("no", "derivatives")
+        || has_pair("no", "derivs")
+    {
+        code.push_str("-ND");
+    }
+
+    // Version is the first numeric token, e.g. "4", "0" from "4.0"
+    if let Some(idx) = tokens
+        .iter()
+        .position(|t| t.chars().all(|c| c.is_ascii_digit()))
+    {
+        let minor = tokens
+            .get(idx + 1)
+            .filter(|t| t.chars().all(|c| c.is_ascii_digit()))
+            .map_or("0", |t| t.as_str());
+        code.push_str(&format!("-{}.{}", tokens[idx], minor));
+    }
+
+    Some(code)
+}
+
+/// Maps a license/rights string such as "CC BY 4.0" or "Creative Commons Attribution 4.0 International" to a
+/// canonical SPDX-like code, e.g. "CC-BY-4.0". Returns None when the license is not recognized.
+pub fn normalize_license(text: Option<&str>) -> Option<String> {
+    let tokens = license_tokens(text?);
+    if tokens.is_empty() {
+        return None;
+    }
+
+    if let Some(code) = creative_commons_code(&tokens) {
+        return Some(code);
+    }
+
+    let key = tokens.join(" ");
+    LICENSE_TABLE
+        .iter()
+        .find(|(phrase, _)| *phrase == key)
+        .map(|(_, code)| code.to_string())
+}
+
+/// Resolver hosts that DOI URLs are served from.
+const DOI_RESOLVER_HOSTS: &[&str] = &["doi.org/", "dx.doi.org/", "www.doi.org/"];
+
+/// Checks that a lowercased string has the DOI shape "10.<registrant>/<suffix>".
+fn is_valid_doi(doi: &str) -> bool {
+    let Some((prefix, suffix)) = doi.split_once('/') else {
+        return false;
+    };
+    let Some(registrant) = prefix.strip_prefix("10.") else {
+        return false;
+    };
+
+    !registrant.is_empty()
+        && registrant.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && registrant.starts_with(|c: char| c.is_ascii_digit())
+        && !suffix.trim().is_empty()
+        && !suffix.chars().any(char::is_whitespace)
+}
+
+/// Removes the URL query string and fragment from a DOI taken from a resolver URL.
+///
+/// DOIs may legally contain "#" and "?", so this is conservative: a fragment is only removed when something follows
+/// the "#" (SICI DOIs such as "10.1002/(sici)...;2-#" end in a bare "#"), and a query string is only removed when it
+/// looks like "key=value" parameters.
+fn strip_doi_url_suffixes(doi: &str) -> &str {
+    let mut doi = doi;
+    if let Some((before, fragment)) = doi.split_once('#') {
+        if !fragment.is_empty() {
+            doi = before;
+        }
+    }
+
+    if let Some((before, query)) = doi.split_once('?') {
+        let is_query = query.split('&').next().is_some_and(|param| {
+            param
+                .split_once('=')
+                .is_some_and(|(key, _)| !key.is_empty())
+        });
+        if is_query {
+            doi = before;
+        }
+    }
+
+    doi
+}
+
+/// Normalizes a DOI by removing resolver URL and "doi:" prefixes (and, for URLs, tracking query strings and
+/// fragments), lowercasing and validating the "10.xxxx/suffix" pattern. Returns None for invalid DOIs.
+pub fn normalize_doi(text: Option<&str>) -> Option<String> {
+    let lower = text?.trim().to_lowercase();
+
+    let url = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+        .unwrap_or(&lower);
+
+    let doi = if let Some(path) = DOI_RESOLVER_HOSTS
+        .iter()
+        .find_map(|host| url.strip_prefix(host))
+    {
+        strip_doi_url_suffixes(path)
+    } else if let Some(rest) = url.strip_prefix("doi:") {
+        rest.trim_start()
+    } else {
+        url
+    };
+
+    if !is_valid_doi(doi) {
+        return None;
+    }
+
+    Some(doi.to_string())
+}
+
+/// Normalizes a DOI like `normalize_doi`, also returning its registrant prefix ("10.xxxx") separately.
+pub fn normalize_doi_with_prefix(text: Option<&str>) -> Option<(String, String)> {
+    let doi = normalize_doi(text)?;
+    let (prefix, _) = doi.split_once('/')?;
+    let prefix = prefix.to_string();
+    Some((doi, prefix))
+}
+
+/// Extracts the registrant prefix ("10.xxxx") of a DOI, normalizing it first. Returns None for invalid DOIs.
+pub fn doi_prefix(text: Option<&str>) -> Option<String> {
+    normalize_doi_with_prefix(text).map(|(_, prefix)| prefix)
+}
+
+/// Checks whether two DOIs share a registrant prefix, e.g. two works from the same publisher. Returns false when
+/// either DOI is invalid.
+pub fn doi_same_registrant(a: Option<&str>, b: Option<&str>) -> bool {
+    match (doi_prefix(a), doi_prefix(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Normalizes an ISSN to the hyphenated "1234-567X" form, accepting an optional "ISSN" prefix and missing or extra
+/// hyphens and spaces. Returns None when the ISSN is malformed or its check digit is wrong.
+pub fn normalize_issn(text: Option<&str>) -> Option<String> {
+    let text = text?.trim();
+    let text = text
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("issn"))
+        .map_or(text, |_| &text[4..]);
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace() && *c != ':')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if chars.len() != 8 || !chars[..7].iter().all(char::is_ascii_digit) {
+        return None;
+    }
+
+    // Check digit: weighted sum of the first seven digits (weights 8 down to 2), modulo 11
+    let sum: u32 = chars[..7]
+        .iter()
+        .zip((2..=8).rev())
+        .map(|(c, weight)| c.to_digit(10).unwrap() * weight)
+        .sum();
+    let expected = match (11 - sum % 11) % 11 {
+        10 => 'X',
+        digit => char::from_digit(digit, 10).unwrap(),
+    };
+    if chars[7] != expected {
+        return None;
+    }
+
+    let digits: String = chars.into_iter().collect();
+    Some(format!("{}-{}", &digits[..4], &digits[4..]))
+}
+
+/// Checks whether two lists of ISSNs, e.g. a journal's print and electronic ISSNs, share any valid ISSN after
+/// normalization. Works that list only one of a journal's ISSNs still match.
+pub fn issn_set_match(a: Vec<Option<&str>>, b: Vec<Option<&str>>) -> bool {
+    let a: HashSet<String> = a.into_iter().filter_map(normalize_issn).collect();
+    b.into_iter()
+        .filter_map(normalize_issn)
+        .any(|issn| a.contains(&issn))
+}
+
+/// Computes the ISBN-13 (EAN-13) check digit of the first 12 digits of an ISBN-13: the weighted sum of the digits
+/// with alternating weights 1 and 3, subtracted from the next multiple of 10.
+fn isbn13_check_digit(digits: &[u32]) -> u32 {
+    let sum: u32 = digits[..12]
+        .iter()
+        .zip([1, 3].iter().cycle())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Normalizes an ISBN-10 or ISBN-13 to an unhyphenated ISBN-13, e.g. "0-306-40615-2" and "978-0-306-40615-7" both
+/// normalize to "9780306406157", accepting an optional "ISBN", "ISBN-10" or "ISBN-13" prefix and any hyphens and
+/// spaces. ISBN-10s are converted by prefixing "978" and recomputing the check digit. Returns None when the ISBN is
+/// malformed or its check digit is wrong.
+pub fn normalize_isbn(text: Option<&str>) -> Option<String> {
+    let text = text?.trim();
+    let text = match text
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("isbn"))
+    {
+        Some(_) => {
+            let rest = text[4..].trim_start_matches(['-', ' ']);
+            ["10", "13"]
+                .iter()
+                .find_map(|length| {
+                    rest.strip_prefix(length)
+                        .filter(|rest| rest.starts_with([':', ' ']))
+                })
+                .unwrap_or(rest)
+        },
+        None => text,
+    };
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace() && *c != ':')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let digits: Vec<u32> = match chars.len() {
+        10 => {
+            if !chars[..9].iter().all(char::is_ascii_digit) {
+                return None;
+            }
+            // Check digit: weighted sum of all ten digits (weights 10 down to 1, X = 10) must be divisible by 11
+            let check = match chars[9] {
+                'X' => 10,
+                c => c.to_digit(10)?,
+            };
+            let sum: u32 = chars[..9]
+                .iter()
+                .zip((2..=10).rev())
+                .map(|(c, weight)| c.to_digit(10).unwrap() * weight)
+                .sum();
+            if !(sum + check).is_multiple_of(11) {
+                return None;
+            }
+            let mut digits = vec![9, 7, 8];
+            digits.extend(chars[..9].iter().map(|c| c.to_digit(10).unwrap()));
+            digits.push(isbn13_check_digit(&digits));
+            digits
+        },
+        13 => {
+            let digits = chars
+                .iter()
+                .map(|c| c.to_digit(10))
+                .collect::<Option<Vec<u32>>>()?;
+            if !(digits.starts_with(&[9, 7, 8]) || digits.starts_with(&[9, 7, 9]))
+                || digits[12] != isbn13_check_digit(&digits)
+            {
+                return None;
+            }
+            digits
+        },
+        _ => return None,
+    };
+    Some(
+        digits
+            .iter()
+            .map(|digit| char::from_digit(*digit, 10).unwrap())
+            .collect(),
+    )
+}
+
+/// The digits of an ORCID iD in free text, URL or bare forms, e.g. "https://orcid.org/0000-0002-1825-0097",
+/// "0000 0002 1825 0097" or "0000000218250097".
+static ORCID_DIGITS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[^0-9A-Za-z])([0-9]{4})[-\s]?([0-9]{4})[-\s]?([0-9]{4})[-\s]?([0-9]{3}[0-9Xx])(?:$|[^0-9A-Za-z])",
+    )
+    .unwrap()
+});
+
+/// Computes the ISO 7064 MOD 11-2 check character of the first 15 digits of an ORCID iD.
+fn orcid_check_character(digits: &str) -> char {
+    let total = digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .fold(0, |total, digit| (total + digit) * 2);
+    match (12 - total % 11) % 11 {
+        10 => 'X',
+        digit => char::from_digit(digit, 10).unwrap(),
+    }
+}
+
+/// Extracts and normalizes an ORCID iD from URL or free-text forms to the bare "0000-0002-1825-0097" form,
+/// validating its ISO 7064 MOD 11-2 checksum. Returns None when no valid ORCID iD is found.
+pub fn normalize_orcid(text: Option<&str>) -> Option<String> {
+    ORCID_DIGITS_RE.captures_iter(text?).find_map(|caps| {
+        let digits = format!("{}{}{}{}", &caps[1], &caps[2], &caps[3], &caps[4]).to_uppercase();
+        if digits.ends_with(orcid_check_character(&digits[..15])) {
+            Some(format!(
+                "{}-{}-{}-{}",
+                &digits[..4],
+                &digits[4..8],
+                &digits[8..12],
+                &digits[12..]
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// A ROR ID in free text or URL form, e.g. "https://ror.org/03yrm5c26": a leading "0", six Crockford base32
+/// characters and a two digit checksum.
+static ROR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // Explicit ASCII classes, as case-insensitive classes also match e.g. 'ſ' and the Kelvin sign
+    Regex::new(
+        r"(?:^|[^0-9A-Za-z])(0[0-9A-HJ-KM-NP-TV-Za-hj-km-np-tv-z]{6}[0-9]{2})(?:$|[^0-9A-Za-z])",
+    )
+    .unwrap()
+});
+
+/// A GRID ID, e.g. "grid.30389.31".
+static GRID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bgrid\.\d+\.[0-9a-f]{1,2}\b").unwrap());
+
+/// A Ringgold ID labelled as such in free text, e.g. "Ringgold: 8785". Bare numbers are too ambiguous to extract.
+static RINGGOLD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bringgold(?:\s*id)?\W{0,3}(\d{3,6})\b").unwrap());
+
+/// Crockford base32 alphabet used by ROR IDs.
+const CROCKFORD_BASE32: &str = "0123456789abcdefghjkmnpqrstvwxyz";
+
+/// Validates the ISO 7064 MOD 97-10 checksum of a lowercase ROR ID.
+fn is_valid_ror(ror: &str) -> bool {
+    if ror.len() != 9 || !ror.is_ascii() {
+        return false;
+    }
+    let Some(value) = ror[..7].chars().try_fold(0u64, |value, c| {
+        CROCKFORD_BASE32
+            .find(c)
+            .map(|digit| value * 32 + digit as u64)
+    }) else {
+        return false;
+    };
+    ror[7..].parse::<u64>().ok() == Some(98 - (value * 100) % 97)
+}
+
+/// Extracts and normalizes a ROR ID from URL or free-text forms to the bare lowercase form, e.g.
+/// "https://ror.org/03yrm5c26" → "03yrm5c26", validating its checksum. Returns None when no valid ROR ID is found.
+pub fn normalize_ror(text: Option<&str>) -> Option<String> {
+    ROR_RE
+        .captures_iter(text?)
+        .map(|caps| caps[1].to_lowercase())
+        .find(|ror| is_valid_ror(ror))
+}
+
+/// Institution identifiers extracted from an affiliation.
+#[derive(Debug, Clone, Default)]
+pub struct InstitutionIds {
+    pub ror: Vec<String>,
+    pub grid: Vec<String>,
+    pub ringgold: Vec<String>,
+}
+
+impl InstitutionIds {
+    /// Adds an identifier to a list unless it is already present.
+    fn push_unique(ids: &mut Vec<String>, id: String) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+
+    /// Adds the identifiers found in free text.
+    fn extend_from_text(&mut self, text: &str) {
+        for caps in ROR_RE.captures_iter(text) {
+            let ror = caps[1].to_lowercase();
+            if is_valid_ror(&ror) {
+                Self::push_unique(&mut self.ror, ror);
+            }
+        }
+        for m in GRID_RE.find_iter(text) {
+            Self::push_unique(&mut self.grid, m.as_str().to_lowercase());
+        }
+        for caps in RINGGOLD_RE.captures_iter(text) {
+            Self::push_unique(&mut self.ringgold, caps[1].to_string());
+        }
+    }
+
+    /// Adds the identifiers found in a JSON identifier object, e.g. `{"ror": "https://ror.org/03yrm5c26"}` or
+    /// `{"identifier": "8785", "scheme": "Ringgold"}`, and the free text of its string values.
+    fn extend_from_json(&mut self, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::String(text) => self.extend_from_text(text),
+            serde_json::Value::Array(values) => {
+                values.iter().for_each(|v| self.extend_from_json(v))
+            },
+            serde_json::Value::Object(object) => {
+                // Identifier schemes are given by a "...scheme" or "type" key, or by the key itself
+                let scheme = object
+                    .iter()
+                    .find(|(key, _)| {
+                        let key = key.to_lowercase();
+                        key.ends_with("scheme") || key == "type"
+                    })
+                    .and_then(|(_, v)| v.as_str())
+                    .map(str::to_lowercase);
+                for (key, v) in object {
+                    let scheme = match key.to_lowercase().as_str() {
+                        "ror" | "grid" | "ringgold" => Some(key.to_lowercase()),
+                        key if key.ends_with("scheme") || key == "type" => None,
+                        _ => scheme.clone(),
+                    };
+                    match (scheme.as_deref(), v) {
+                        (Some("ringgold"), serde_json::Value::String(id)) => {
+                            let id = id.trim();
+                            if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+                                Self::push_unique(&mut self.ringgold, id.to_string());
+                            }
+                        },
+                        (Some("ringgold"), serde_json::Value::Number(id)) => {
+                            Self::push_unique(&mut self.ringgold, id.to_string());
+                        },
+                        _ => self.extend_from_json(v),
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Extracts ROR, GRID and Ringgold identifiers from a raw affiliation string or a JSON-serialized identifier object.
+/// ROR IDs are validated and normalized to their bare lowercase form and GRID IDs are lowercased. Ringgold IDs are
+/// only extracted when labelled, e.g. "Ringgold: 8785" or a "Ringgold" identifier scheme.
+pub fn extract_institution_ids(text: Option<&str>) -> InstitutionIds {
+    let mut ids = InstitutionIds::default();
+    let Some(text) = text else {
+        return ids;
+    };
+
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+            ids.extend_from_json(&value)
+        },
+        _ => ids.extend_from_text(text),
+    }
+    ids
+}
+
+/// An email address in free text, e.g. "j.doe@example.org".
+static EMAIL_IN_TEXT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+\-]+@[A-Za-z0-9\-]+(?:\.[A-Za-z0-9\-]+)*\.[A-Za-z]{2,}").unwrap()
+});
+
+/// Obfuscated "@" and "." in email addresses, e.g. "j.doe [at] example [dot] org".
+static OBFUSCATED_AT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s*[\[(]\s*at\s*[\])]\s*").unwrap());
+static OBFUSCATED_DOT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\s*[\[(]\s*dot\s*[\])]\s*").unwrap());
+
+/// Extracts the email addresses in free text, e.g. a DMP contributor field, including "mailto:" links and
+/// addresses obfuscated as "j.doe [at] example [dot] org". Addresses are lowercased and deduplicated in first-seen
+/// order.
+pub fn extract_emails(text: Option<&str>) -> Vec<String> {
+    let Some(text) = text else {
+        return Vec::new();
+    };
+    let text = OBFUSCATED_AT_RE.replace_all(text, "@");
+    let text = OBFUSCATED_DOT_RE.replace_all(&text, ".");
+
+    let mut seen = HashSet::new();
+    EMAIL_IN_TEXT_RE
+        .find_iter(&text)
+        .map(|m| m.as_str().trim_matches('.').to_lowercase())
+        .filter(|email| seen.insert(email.clone()))
+        .collect()
+}
+
+/// Query parameters added by analytics and marketing tools, removed when normalizing URLs. Parameters starting with
+/// "utm_" are also removed.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_ga", "_gl",
+    "_hsenc", "_hsmi", "ref_src",
+];
+
+/// Hosts of the Handle System resolver.
+const HANDLE_RESOLVER_HOSTS: &[&str] = &["hdl.handle.net", "handle.net"];
+
+/// A URL normalized by `normalize_url`: a DOI or Handle when the URL resolves one, otherwise the URL itself.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NormalizedUrl {
+    /// A DOI normalized with `normalize_doi`, e.g. "10.1234/abc".
+    Doi(String),
+    /// A Handle, e.g. "20.500.12345/678".
+    Handle(String),
+    Url(String),
+}
+
+/// Normalizes a URL for comparison: lowercases the scheme and host, removes default ports, tracking query
+/// parameters (e.g. "utm_source" and "fbclid") and a bare trailing "/", and trims trailing punctuation picked up
+/// from prose. URLs without a scheme are accepted when they start with "www.". DOI resolver and Handle resolver URLs,
+/// and "doi:" and "hdl:" identifiers, are resolved to the bare DOI or Handle. Returns None for anything else.
+pub fn normalize_url(text: Option<&str>) -> Option<NormalizedUrl> {
+    let text = text?.trim();
+    let text = text.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+    let text = if text.ends_with(')') && !text.contains('(') {
+        &text[..text.len() - 1]
+    } else {
+        text
+    };
+
+    if let Some(handle) = text
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("hdl:"))
+        .map(|_| text[4..].trim())
+    {
+        return (!handle.is_empty()).then(|| NormalizedUrl::Handle(handle.to_string()));
+    }
+    if let Some(doi) = normalize_doi(Some(text)) {
+        return Some(NormalizedUrl::Doi(doi));
+    }
+
+    let (scheme, rest) = match text.split_once("://") {
+        Some((scheme, rest)) if ["http", "https"].contains(&scheme.to_lowercase().as_str()) => {
+            (scheme.to_lowercase(), rest)
+        },
+        Some(_) => return None,
+        None if text.to_lowercase().starts_with("www.") => ("http".to_string(), text),
+        None => return None,
+    };
+
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(authority_end);
+    let mut host = authority.to_lowercase();
+    if let Some(bare) = host
+        .strip_suffix(if scheme == "https" { ":443" } else { ":80" })
+        .map(str::to_string)
+    {
+        host = bare;
+    }
+    if host.is_empty() || host.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let (rest, fragment) = rest
+        .split_once('#')
+        .map_or((rest, None), |(rest, fragment)| (rest, Some(fragment)));
+    let (path, query) = rest
+        .split_once('?')
+        .map_or((rest, None), |(path, query)| (path, Some(query)));
+
+    if HANDLE_RESOLVER_HOSTS.contains(&host.as_str()) {
+        let handle = path.trim_matches('/');
+        if let Some(doi) = normalize_doi(Some(handle)) {
+            return Some(NormalizedUrl::Doi(doi));
+        }
+        return (!handle.is_empty()).then(|| NormalizedUrl::Handle(handle.to_string()));
+    }
+
+    let params: Vec<&str> = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter(|param| {
+            let key = param
+                .split_once('=')
+                .map_or(*param, |(key, _)| key)
+                .to_lowercase();
+            !param.is_empty()
+                && !key.starts_with("utm_")
+                && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .collect();
+
+    let mut url = format!("{scheme}://{host}");
+    if path != "/" {
+        url.push_str(path);
+    }
+    if !params.is_empty() {
+        url.push('?');
+        url.push_str(&params.join("&"));
+    }
+    if let Some(fragment) = fragment.filter(|fragment| !fragment.is_empty()) {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    Some(NormalizedUrl::Url(url))
+}
+
+/// A raw affiliation string split into its parts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedAffiliation {
+    pub institution: Option<String>,
+    pub department: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Country names and common aliases, lowercased and without full stops, with the country names they map to.
+const COUNTRY_ALIASES: &[(&str, &str)] = &[
+    ("argentina", "Argentina"),
+    ("australia", "Australia"),
+    ("austria", "Austria"),
+    ("belgium", "Belgium"),
+    ("brasil", "Brazil"),
+    ("brazil", "Brazil"),
+    ("canada", "Canada"),
+    ("chile", "Chile"),
+    ("china", "China"),
+    ("colombia", "Colombia"),
+    ("czech republic", "Czech Republic"),
+    ("czechia", "Czech Republic"),
+    ("denmark", "Denmark"),
+    ("deutschland", "Germany"),
+    ("egypt", "Egypt"),
+    ("england", "United Kingdom"),
+    ("espana", "Spain"),
+    ("ethiopia", "Ethiopia"),
+    ("finland", "Finland"),
+    ("france", "France"),
+    ("germany", "Germany"),
+    ("greece", "Greece"),
+    ("hong kong", "Hong Kong"),
+    ("hungary", "Hungary"),
+    ("india", "India"),
+    ("indonesia", "Indonesia"),
+    ("iran", "Iran"),
+    ("ireland", "Ireland"),
+    ("israel", "Israel"),
+    ("italia", "Italy"),
+    ("italy", "Italy"),
+    ("japan", "Japan"),
+    ("kenya", "Kenya"),
+    ("korea", "South Korea"),
+    ("malaysia", "Malaysia"),
+    ("mexico", "Mexico"),
+    ("netherlands", "Netherlands"),
+    ("new zealand", "New Zealand"),
+    ("nigeria", "Nigeria"),
+    ("northern ireland", "United Kingdom"),
+    ("norway", "Norway"),
+    ("pakistan", "Pakistan"),
+    ("peoples republic of china", "China"),
+    ("peru", "Peru"),
+    ("philippines", "Philippines"),
+    ("poland", "Poland"),
+    ("portugal", "Portugal"),
+    ("pr china", "China"),
+    ("republic of korea", "South Korea"),
+    ("russia", "Russia"),
+    ("russian federation", "Russia"),
+    ("saudi arabia", "Saudi Arabia"),
+    ("scotland", "United Kingdom"),
+    ("singapore", "Singapore"),
+    ("south africa", "South Africa"),
+    ("south korea", "South Korea"),
+    ("spain", "Spain"),
+    ("sweden", "Sweden"),
+    ("switzerland", "Switzerland"),
+    ("taiwan", "Taiwan"),
+    ("thailand", "Thailand"),
+    ("the netherlands", "Netherlands"),
+    ("turkey", "Turkey"),
+    ("turkiye", "Turkey"),
+    ("uk", "United Kingdom"),
+    ("united kingdom", "United Kingdom"),
+    ("united states", "United States"),
+    ("united states of america", "United States"),
+    ("us", "United States"),
+    ("usa", "United States"),
+    ("viet nam", "Vietnam"),
+    ("vietnam", "Vietnam"),
+    ("wales", "United Kingdom"),
+];
+
+/// Words that mark an affiliation part as a department within an institution, compared lowercased and without a
+/// trailing full stop.
+const DEPARTMENT_WORDS: &[&str] = &[
+    "department",
+    "dept",
+    "division",
+    "faculty",
+    "group",
+    "program",
+    "programme",
+    "section",
+    "unit",
+];
+
+/// Words that mark an affiliation part as an institution, in preference order: universities and hospitals are
+/// preferred over the institutes and centers that are often hosted by them.
+const INSTITUTION_WORDS: &[&[&str]] = &[
+    &[
+        "hospital",
+        "universidad",
+        "universidade",
+        "universita",
+        "universitat",
+        "universite",
+        "university",
+        "univ",
+    ],
+    &[
+        "academy",
+        "agency",
+        "center",
+        "centre",
+        "college",
+        "company",
+        "corporation",
+        "council",
+        "foundation",
+        "inc",
+        "institut",
+        "institute",
+        "instituto",
+        "laboratory",
+        "ltd",
+        "ministry",
+        "museum",
+        "observatory",
+        "school",
+        "survey",
+    ],
+];
+
+/// Looks up the country an affiliation part names, e.g. "U.S.A." -> "United States".
+fn affiliation_country(part: &str) -> Option<&'static str> {
+    let key = deunicode(part).to_lowercase().replace('.', "");
+    COUNTRY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key.trim())
+        .map(|(_, country)| *country)
+}
+
+/// Returns the lowercased, transliterated words of an affiliation part, for comparison against word lists.
+fn affiliation_words(part: &str) -> Vec<String> {
+    deunicode(part)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Detects parts that are only postal codes or state abbreviations, e.g. "CA 94720" or "D-69117".
+fn is_postal_part(part: &str) -> bool {
+    part.split_whitespace().all(|word| {
+        word.chars().any(|c| c.is_ascii_digit())
+            || (word.len() <= 3 && word.chars().all(|c| c.is_ascii_uppercase()))
+    })
+}
+
+/// Splits a raw affiliation string, e.g. "Dept. of Biology, University of Montana, Missoula, MT 59812, USA", into
+/// its institution, department, city and country. Parts are separated by commas or semicolons; footnote markers,
+/// email addresses, postal codes and state abbreviations are dropped. The country is recognized from the last part,
+/// the institution is the most institution-like part (universities and hospitals over institutes and centers), the
+/// department is the first part that names a department, and the city is the first remaining part after the
+/// institution.
+pub fn parse_affiliation(text: Option<&str>) -> ParsedAffiliation {
+    let mut parsed = ParsedAffiliation::default();
+    let Some(text) = text else {
+        return parsed;
+    };
+
+    let decoded = decode_html_entities(text);
+    let mut parts: Vec<&str> = decoded
+        .split([',', ';'])
+        .map(|part| {
+            part.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit() || c.is_whitespace())
+                .trim_end_matches('.')
+                .trim()
+        })
+        .filter(|part| !part.is_empty() && !part.contains('@'))
+        .collect();
+
+    if let Some(country) = parts.last().and_then(|part| affiliation_country(part)) {
+        parsed.country = Some(country.to_string());
+        parts.pop();
+    }
+    parts.retain(|part| !is_postal_part(part));
+
+    let words: Vec<Vec<String>> = parts.iter().map(|part| affiliation_words(part)).collect();
+    let has_word =
+        |i: usize, list: &[&str]| words[i].iter().any(|word| list.contains(&word.as_str()));
+    let is_department = |i: usize| {
+        has_word(i, DEPARTMENT_WORDS)
+            || (words[i]
+                .first()
+                .is_some_and(|word| word == "school" || word == "college")
+                && words[i].get(1).is_some_and(|word| word == "of"))
+            || words[i].first().is_some_and(|word| word == "lab")
+    };
+
+    let institution = INSTITUTION_WORDS
+        .iter()
+        .find_map(|list| (0..parts.len()).find(|&i| has_word(i, list) && !is_department(i)))
+        .or_else(|| (0..parts.len()).find(|&i| !is_department(i)));
+    let department = (0..parts.len()).find(|&i| Some(i) != institution && is_department(i));
+    let city = institution.and_then(|institution| {
+        (institution + 1..parts.len()).find(|&i| {
+            Some(i) != department
+                && !is_department(i)
+                && INSTITUTION_WORDS.iter().all(|list| !has_word(i, list))
+        })
+    });
+
+    let clean = |i: usize| {
+        let part = parts[i]
+            .trim_end_matches(|c: char| c.is_ascii_digit() || c.is_whitespace() || c == '-');
+        (!part.is_empty()).then(|| normalize_organization_name(part))
+    };
+    parsed.institution = institution.and_then(clean);
+    parsed.department = department.and_then(clean);
+    parsed.city = city.and_then(clean);
+    parsed
+}
+
+/// ISO 3166-1 countries as (alpha-2 code, alpha-3 code, short name), names in the form compared by
+/// `normalize_country`.
+const ISO_COUNTRIES: &[(&str, &str, &str)] = &[
+    ("AD", "AND", "andorra"),
+    ("AE", "ARE", "united arab emirates"),
+    ("AF", "AFG", "afghanistan"),
+    ("AG", "ATG", "antigua and barbuda"),
+    ("AI", "AIA", "anguilla"),
+    ("AL", "ALB", "albania"),
+    ("AM", "ARM", "armenia"),
+    ("AO", "AGO", "angola"),
+    ("AQ", "ATA", "antarctica"),
+    ("AR", "ARG", "argentina"),
+    ("AS", "ASM", "american samoa"),
+    ("AT", "AUT", "austria"),
+    ("AU", "AUS", "australia"),
+    ("AW", "ABW", "aruba"),
+    ("AX", "ALA", "aland islands"),
+    ("AZ", "AZE", "azerbaijan"),
+    ("BA", "BIH", "bosnia and herzegovina"),
+    ("BB", "BRB", "barbados"),
+    ("BD", "BGD", "bangladesh"),
+    ("BE", "BEL", "belgium"),
+    ("BF", "BFA", "burkina faso"),
+    ("BG", "BGR", "bulgaria"),
+    ("BH", "BHR", "bahrain"),
+    ("BI", "BDI", "burundi"),
+    ("BJ", "BEN", "benin"),
+    ("BL", "BLM", "saint barthelemy"),
+    ("BM", "BMU", "bermuda"),
+    ("BN", "BRN", "brunei darussalam"),
+    ("BO", "BOL", "bolivia"),
+    ("BQ", "BES", "bonaire sint eustatius and saba"),
+    ("BR", "BRA", "brazil"),
+    ("BS", "BHS", "bahamas"),
+    ("BT", "BTN", "bhutan"),
+    ("BV", "BVT", "bouvet island"),
+    ("BW", "BWA", "botswana"),
+    ("BY", "BLR", "belarus"),
+    ("BZ", "BLZ", "belize"),
+    ("CA", "CAN", "canada"),
+    ("CC", "CCK", "cocos keeling islands"),
+    ("CD", "COD", "democratic republic of the congo"),
+    ("CF", "CAF", "central african republic"),
+    ("CG", "COG", "congo"),
+    ("CH", "CHE", "switzerland"),
+    ("CI", "CIV", "cote d ivoire"),
+    ("CK", "COK", "cook islands"),
+    ("CL", "CHL", "chile"),
+    ("CM", "CMR", "cameroon"),
+    ("CN", "CHN", "china"),
+    ("CO", "COL", "colombia"),
+    ("CR", "CRI", "costa rica"),
+    ("CU", "CUB", "cuba"),
+    ("CV", "CPV", "cabo verde"),
+    ("CW", "CUW", "curacao"),
+    ("CX", "CXR", "christmas island"),
+    ("CY", "CYP", "cyprus"),
+    ("CZ", "CZE", "czechia"),
+    ("DE", "DEU", "germany"),
+    ("DJ", "DJI", "djibouti"),
+    ("DK", "DNK", "denmark"),
+    ("DM", "DMA", "dominica"),
+    ("DO", "DOM", "dominican republic"),
+    ("DZ", "DZA", "algeria"),
+    ("EC", "ECU", "ecuador"),
+    ("EE", "EST", "estonia"),
+    ("EG", "EGY", "egypt"),
+    ("EH", "ESH", "western sahara"),
+    ("ER", "ERI", "eritrea"),
+    ("ES", "ESP", "spain"),
+    ("ET", "ETH", "ethiopia"),
+    ("FI", "FIN", "finland"),
+    ("FJ", "FJI", "fiji"),
+    ("FK", "FLK", "falkland islands"),
+    ("FM", "FSM", "micronesia"),
+    ("FO", "FRO", "faroe islands"),
+    ("FR", "FRA", "france"),
+    ("GA", "GAB", "gabon"),
+    ("GB", "GBR", "united kingdom"),
+    ("GD", "GRD", "grenada"),
+    ("GE", "GEO", "georgia"),
+    ("GF", "GUF", "french guiana"),
+    ("GG", "GGY", "guernsey"),
+    ("GH", "GHA", "ghana"),
+    ("GI", "GIB", "gibraltar"),
+    ("GL", "GRL", "greenland"),
+    ("GM", "GMB", "gambia"),
+    ("GN", "GIN", "guinea"),
+    ("GP", "GLP", "guadeloupe"),
+    ("GQ", "GNQ", "equatorial guinea"),
+    ("GR", "GRC", "greece"),
+    ("GS", "SGS", "south georgia and the south sandwich islands"),
+    ("GT", "GTM", "guatemala"),
+    ("GU", "GUM", "guam"),
+    ("GW", "GNB", "guinea bissau"),
+    ("GY", "GUY", "guyana"),
+    ("HK", "HKG", "hong kong"),
+    ("HM", "HMD", "heard island and mcdonald islands"),
+    ("HN", "HND", "honduras"),
+    ("HR", "HRV", "croatia"),
+    ("HT", "HTI", "haiti"),
+    ("HU", "HUN", "hungary"),
+    ("ID", "IDN", "indonesia"),
+    ("IE", "IRL", "ireland"),
+    ("IL", "ISR", "israel"),
+    ("IM", "IMN", "isle of man"),
+    ("IN", "IND", "india"),
+    ("IO", "IOT", "british indian ocean territory"),
+    ("IQ", "IRQ", "iraq"),
+    ("IR", "IRN", "iran"),
+    ("IS", "ISL", "iceland"),
+    ("IT", "ITA", "italy"),
+    ("JE", "JEY", "jersey"),
+    ("JM", "JAM", "jamaica"),
+    ("JO", "JOR", "jordan"),
+    ("JP", "JPN", "japan"),
+    ("KE", "KEN", "kenya"),
+    ("KG", "KGZ", "kyrgyzstan"),
+    ("KH", "KHM", "cambodia"),
+    ("KI", "KIR", "kiribati"),
+    ("KM", "COM", "comoros"),
+    ("KN", "KNA", "saint kitts and nevis"),
+    ("KP", "PRK", "north korea"),
+    ("KR", "KOR", "south korea"),
+    ("KW", "KWT", "kuwait"),
+    ("KY", "CYM", "cayman islands"),
+    ("KZ", "KAZ", "kazakhstan"),
+    ("LA", "LAO", "laos"),
+    ("LB", "LBN", "lebanon"),
+    ("LC", "LCA", "saint lucia"),
+    ("LI", "LIE", "liechtenstein"),
+    ("LK", "LKA", "sri lanka"),
+    ("LR", "LBR", "liberia"),
+    ("LS", "LSO", "lesotho"),
+    ("LT", "LTU", "lithuania"),
+    ("LU", "LUX", "luxembourg"),
+    ("LV", "LVA", "latvia"),
+    ("LY", "LBY", "libya"),
+    ("MA", "MAR", "morocco"),
+    ("MC", "MCO", "monaco"),
+    ("MD", "MDA", "moldova"),
+    ("ME", "MNE", "montenegro"),
+    ("MF", "MAF", "saint martin"),
+    ("MG", "MDG", "madagascar"),
+    ("MH", "MHL", "marshall islands"),
+    ("MK", "MKD", "north macedonia"),
+    ("ML", "MLI", "mali"),
+    ("MM", "MMR", "myanmar"),
+    ("MN", "MNG", "mongolia"),
+    ("MO", "MAC", "macao"),
+    ("MP", "MNP", "northern mariana islands"),
+    ("MQ", "MTQ", "martinique"),
+    ("MR", "MRT", "mauritania"),
+    ("MS", "MSR", "montserrat"),
+    ("MT", "MLT", "malta"),
+    ("MU", "MUS", "mauritius"),
+    ("MV", "MDV", "maldives"),
+    ("MW", "MWI", "malawi"),
+    ("MX", "MEX", "mexico"),
+    ("MY", "MYS", "malaysia"),
+    ("MZ", "MOZ", "mozambique"),
+    ("NA", "NAM", "namibia"),
+    ("NC", "NCL", "new caledonia"),
+    ("NE", "NER", "niger"),
+    ("NF", "NFK", "norfolk island"),
+    ("NG", "NGA", "nigeria"),
+    ("NI", "NIC", "nicaragua"),
+    ("NL", "NLD", "netherlands"),
+    ("NO", "NOR", "norway"),
+    ("NP", "NPL", "nepal"),
+    ("NR", "NRU", "nauru"),
+    ("NU", "NIU", "niue"),
+    ("NZ", "NZL", "new zealand"),
+    ("OM", "OMN", "oman"),
+    ("PA", "PAN", "panama"),
+    ("PE", "PER", "peru"),
+    ("PF", "PYF", "french polynesia"),
+    ("PG", "PNG", "papua new guinea"),
+    ("PH", "PHL", "philippines"),
+    ("PK", "PAK", "pakistan"),
+    ("PL", "POL", "poland"),
+    ("PM", "SPM", "saint pierre and miquelon"),
+    ("PN", "PCN", "pitcairn"),
+    ("PR", "PRI", "puerto rico"),
+    ("PS", "PSE", "palestine"),
+    ("PT", "PRT", "portugal"),
+    ("PW", "PLW", "palau"),
+    ("PY", "PRY", "paraguay"),
+    ("QA", "QAT", "qatar"),
+    ("RE", "REU", "reunion"),
+    ("RO", "ROU", "romania"),
+    ("RS", "SRB", "serbia"),
+    ("RU", "RUS", "russia"),
+    ("RW", "RWA", "rwanda"),
+    ("SA", "SAU", "saudi arabia"),
+    ("SB", "SLB", "solomon islands"),
+    ("SC", "SYC", "seychelles"),
+    ("SD", "SDN", "sudan"),
+    ("SE", "SWE", "sweden"),
+    ("SG", "SGP", "singapore"),
+    ("SH", "SHN", "saint helena"),
+    ("SI", "SVN", "slovenia"),
+    ("SJ", "SJM", "svalbard and jan mayen"),
+    ("SK", "SVK", "slovakia"),
+    ("SL", "SLE", "sierra leone"),
+    ("SM", "SMR", "san marino"),
+    ("SN", "SEN", "senegal"),
+    ("SO", "SOM", "somalia"),
+    ("SR", "SUR", "suriname"),
+    ("SS", "SSD", "south sudan"),
+    ("ST", "STP", "sao tome and principe"),
+    ("SV", "SLV", "el salvador"),
+    ("SX", "SXM", "sint maarten"),
+    ("SY", "SYR", "syria"),
+    ("SZ", "SWZ", "eswatini"),
+    ("TC", "TCA", "turks and caicos islands"),
+    ("TD", "TCD", "chad"),
+    ("TF", "ATF", "french southern territories"),
+    ("TG", "TGO", "togo"),
+    ("TH", "THA", "thailand"),
+    ("TJ", "TJK", "tajikistan"),
+    ("TK", "TKL", "tokelau"),
+    ("TL", "TLS", "timor leste"),
+    ("TM", "TKM", "turkmenistan"),
+    ("TN", "TUN", "tunisia"),
+    ("TO", "TON", "tonga"),
+    ("TR", "TUR", "turkey"),
+    ("TT", "TTO", "trinidad and tobago"),
+    ("TV", "TUV", "tuvalu"),
+    ("TW", "TWN", "taiwan"),
+    ("TZ", "TZA", "tanzania"),
+    ("UA", "UKR", "ukraine"),
+    ("UG", "UGA", "uganda"),
+    ("UM", "UMI", "united states minor outlying islands"),
+    ("US", "USA", "united states"),
+    ("UY", "URY", "uruguay"),
+    ("UZ", "UZB", "uzbekistan"),
+    ("VA", "VAT", "holy see"),
+    ("VC", "VCT", "saint vincent and the grenadines"),
+    ("VE", "VEN", "venezuela"),
+    ("VG", "VGB", "british virgin islands"),
+    ("VI", "VIR", "united states virgin islands"),
+    ("VN", "VNM", "vietnam"),
+    ("VU", "VUT", "vanuatu"),
+    ("WF", "WLF", "wallis and futuna"),
+    ("WS", "WSM", "samoa"),
+    ("YE", "YEM", "yemen"),
+    ("YT", "MYT", "mayotte"),
+    ("ZA", "ZAF", "south africa"),
+    ("ZM", "ZMB", "zambia"),
+    ("ZW", "ZWE", "zimbabwe"),
+];
+
+/// Country name variants, in the form compared by `normalize_country`, with the ISO 3166-1 alpha-2 codes they map
+/// to: full official names, former names, names in the country's own language and the constituent countries of the
+/// United Kingdom.
+const COUNTRY_VARIANTS: &[(&str, &str)] = &[
+    ("bolivia plurinational state of", "BO"),
+    ("brasil", "BR"),
+    ("britain", "GB"),
+    ("brunei", "BN"),
+    ("burma", "MM"),
+    ("cape verde", "CV"),
+    ("congo democratic republic of the", "CD"),
+    ("czech republic", "CZ"),
+    ("democratic peoples republic of korea", "KP"),
+    ("deutschland", "DE"),
+    ("dr congo", "CD"),
+    ("drc", "CD"),
+    ("east timor", "TL"),
+    ("england", "GB"),
+    ("espana", "ES"),
+    ("falkland islands malvinas", "FK"),
+    ("great britain", "GB"),
+    ("holland", "NL"),
+    ("hong kong sar", "HK"),
+    ("iran islamic republic of", "IR"),
+    ("islamic republic of iran", "IR"),
+    ("italia", "IT"),
+    ("ivory coast", "CI"),
+    ("korea", "KR"),
+    ("korea democratic peoples republic of", "KP"),
+    ("korea republic of", "KR"),
+    ("lao peoples democratic republic", "LA"),
+    ("macau", "MO"),
+    ("macedonia", "MK"),
+    ("micronesia federated states of", "FM"),
+    ("moldova republic of", "MD"),
+    ("northern ireland", "GB"),
+    ("osterreich", "AT"),
+    ("palestine state of", "PS"),
+    ("peoples republic of china", "CN"),
+    ("pr china", "CN"),
+    ("republic of korea", "KR"),
+    ("republic of moldova", "MD"),
+    ("republic of the congo", "CG"),
+    ("russian federation", "RU"),
+    ("schweiz", "CH"),
+    ("scotland", "GB"),
+    ("st kitts and nevis", "KN"),
+    ("st lucia", "LC"),
+    ("st vincent and the grenadines", "VC"),
+    ("state of palestine", "PS"),
+    ("suisse", "CH"),
+    ("swaziland", "SZ"),
+    ("syrian arab republic", "SY"),
+    ("taiwan province of china", "TW"),
+    ("tanzania united republic of", "TZ"),
+    ("the bahamas", "BS"),
+    ("the gambia", "GM"),
+    ("the netherlands", "NL"),
+    ("turkiye", "TR"),
+    ("uk", "GB"),
+    ("united kingdom of great britain and northern ireland", "GB"),
+    ("united republic of tanzania", "TZ"),
+    ("united states of america", "US"),
+    ("vatican", "VA"),
+    ("vatican city", "VA"),
+    ("venezuela bolivarian republic of", "VE"),
+    ("viet nam", "VN"),
+    ("virgin islands british", "VG"),
+    ("virgin islands us", "VI"),
+    ("wales", "GB"),
+];
+
+/// Simplifies a country name for lookup: transliterated, lowercased, without full stops and with other punctuation
+/// as spaces, e.g. "Côte d'Ivoire" -> "cote d ivoire" and "U.S.A." -> "usa".
+fn country_key(text: &str) -> String {
+    deunicode(text)
+        .to_lowercase()
+        .replace('.', "")
+        .replace('&', " and ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// ISO 3166-1 alpha-2 codes keyed by every recognized country name, variant, and alpha-2 and alpha-3 code.
+static COUNTRY_CODES: LazyLock<HashMap<String, &'static str>> = LazyLock::new(|| {
+    let mut codes = HashMap::new();
+    for &(alpha_2, alpha_3, name) in ISO_COUNTRIES {
+        codes.insert(alpha_2.to_lowercase(), alpha_2);
+        codes.insert(alpha_3.to_lowercase(), alpha_2);
+        codes.insert(name.to_string(), alpha_2);
+    }
+    for &(variant, alpha_2) in COUNTRY_VARIANTS {
+        codes.insert(variant.to_string(), alpha_2);
+    }
+    codes
+});
+
+/// Resolves a country name, common variant or ISO 3166-1 alpha-2 or alpha-3 code to its ISO 3166-1 alpha-2 code,
+/// ignoring case, diacritics and punctuation, e.g. "U.S.A.", "United States of America" and "us" all give "US".
+/// Returns None for text that isn't a recognized country.
+pub fn normalize_country(text: &str) -> Option<&'static str> {
+    COUNTRY_CODES.get(&country_key(text)).copied()
+}
+
+/// English stop words, matching the Lucene/OpenSearch `_english_` stop word list.
+pub const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// Splits text into lowercase words using Unicode word boundaries (UAX #29), the rules used by the OpenSearch ICU
+/// tokenizer, after cleaning it with `clean_text`.
+pub fn tokenize(text: &str) -> Vec<String> {
+    clean_text(text)
+        .unicode_words()
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Tokenizes text like `tokenize`, also returning the start and end byte offsets of each token in the original
+/// text, e.g. for building highlight spans. The tokens are those of `tokenize`, so a word split by an invisible
+/// character, e.g. the soft hyphen of "Hy\u{AD}brid", is one token whose span covers the invisible character.
+pub fn tokenize_with_spans(text: &str) -> Vec<(String, usize, usize)> {
+    let cleaned = clean_text(text);
+    let Cow::Owned(cleaned) = cleaned else {
+        return text
+            .unicode_word_indices()
+            .map(|(start, word)| (word.to_lowercase(), start, start + word.len()))
+            .collect();
+    };
+    // The byte range of the original character each byte of the cleaned text came from
+    let mut origins: Vec<(usize, usize)> = Vec::with_capacity(cleaned.len());
+    for (start, c) in text.char_indices() {
+        if is_invisible(c) {
+            continue;
+        }
+        let cleaned_len = if is_unusual_whitespace(c) {
+            1
+        } else {
+            c.len_utf8()
+        };
+        origins.extend(std::iter::repeat_n(
+            (start, start + c.len_utf8()),
+            cleaned_len,
+        ));
+    }
+    cleaned
+        .unicode_word_indices()
+        .map(|(start, word)| {
+            let end = start + word.len();
+            (word.to_lowercase(), origins[start].0, origins[end - 1].1)
+        })
+        .collect()
+}
+
+/// Abbreviations that end in a full stop without ending a sentence, lowercased.
+const SENTENCE_ABBREVIATIONS: &[&str] = &[
+    "al.", "approx.", "ca.", "cf.", "dr.", "e.g.", "eq.", "etc.", "fig.", "figs.", "i.e.", "no.",
+    "nos.", "prof.", "ref.", "vs.", "viz.",
+];
+
+/// Splits text into sentences with the Unicode sentence boundary rules, rejoining sentences split after an
+/// abbreviation (e.g. "et al." or "Fig.") or an initial (e.g. "J. Smith"). Sentences are trimmed and empty ones
+/// dropped.
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences: Vec<String> = Vec::new();
+    let mut continues = false;
+    for sentence in text.unicode_sentences() {
+        let sentence = sentence.trim();
+        if sentence.is_empty() {
+            continue;
+        }
+        match sentences.last_mut() {
+            Some(last) if continues => {
+                last.push(' ');
+                last.push_str(sentence);
+            },
+            _ => sentences.push(sentence.to_string()),
+        }
+        let last_word = sentence
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        continues = SENTENCE_ABBREVIATIONS.contains(&last_word.to_lowercase().as_str())
+            || (last_word.ends_with('.') && looks_like_initials(last_word));
+    }
+    sentences
+}
+
+/// Truncates text to at most `max_tokens` words (as counted by `tokenize`), cutting after a whole word and any
+/// punctuation closing it so that characters are never split. When the cut falls mid-sentence, the text is cut back
+/// to the end of the last whole sentence instead, provided that keeps at least half of the words. Text within the
+/// limit is returned unchanged.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    let spans = tokenize_with_spans(text);
+    if spans.len() <= max_tokens {
+        return text.to_string();
+    }
+    let Some(&(_, _, end)) = max_tokens.checked_sub(1).and_then(|last| spans.get(last)) else {
+        return String::new();
+    };
+    // Keep punctuation closing the last word, e.g. a full stop or bracket
+    let end = end
+        + text[end..]
+            .chars()
+            .take_while(|c| {
+                matches!(
+                    c,
+                    '.' | '!' | '?' | '…' | '。' | ')' | ']' | '"' | '”' | '’'
+                )
+            })
+            .map(char::len_utf8)
+            .sum::<usize>();
+    let truncated = &text[..end];
+
+    let sentences = split_sentences(truncated);
+    if sentences.len() > 1 {
+        let complete = &sentences[..sentences.len() - 1];
+        let kept_tokens: usize = complete
+            .iter()
+            .map(|sentence| tokenize(sentence).len())
+            .sum();
+        if kept_tokens * 2 >= max_tokens {
+            return complete.join(" ");
+        }
+    }
+    truncated.trim_end().to_string()
+}
+
+/// Options for `tokenize_with_options`, mirroring the analyzer settings of the search index.
+#[derive(Debug, Clone)]
+pub struct TokenizerOptions {
+    /// Applies NFKC normalization, case folding and diacritic folding before tokenizing, like the `lowercase`,
+    /// `icu_normalizer` and `asciifolding` filters.
+    pub fold: bool,
+    /// Tokens removed before n-grams are built, compared after folding.
+    pub stop_words: Vec<String>,
+    /// Tokens shorter than this many characters are removed before n-grams are built.
+    pub min_length: usize,
+    /// Minimum and maximum number of consecutive tokens joined by a space into word n-grams (shingles).
+    pub word_ngrams: (usize, usize),
+    /// Minimum and maximum lengths of the character n-grams each token is replaced with, if set.
+    pub char_ngrams: Option<(usize, usize)>,
+}
+
+impl Default for TokenizerOptions {
+    fn default() -> Self {
+        Self {
+            fold: true,
+            stop_words: Vec::new(),
+            min_length: 1,
+            word_ngrams: (1, 1),
+            char_ngrams: None,
+        }
+    }
+}
+
+/// Tokenizes text into Unicode words and then filters and expands them according to `options`: tokens are
+/// optionally folded, stop words and tokens shorter than the minimum length are removed, consecutive tokens are
+/// joined into word n-grams, ordered by start position and then length, and each of those is optionally replaced
+/// with its character n-grams, again ordered by start position and then length. Tokens shorter than the minimum
+/// character n-gram length produce no character n-grams.
+pub fn tokenize_with_options(text: &str, options: &TokenizerOptions) -> Vec<String> {
+    let text = if options.fold {
+        Cow::Owned(normalize_text(text, NormalizationForm::Nfkc, true, true))
+    } else {
+        Cow::Borrowed(text)
+    };
+
+    let tokens: Vec<String> = tokenize(&text)
+        .into_iter()
+        .filter(|token| token.chars().count() >= options.min_length)
+        .filter(|token| !options.stop_words.contains(token))
+        .collect();
+
+    let (min_words, max_words) = options.word_ngrams;
+    let mut ngrams = Vec::new();
+    for start in 0..tokens.len() {
+        for n in min_words..=max_words.min(tokens.len() - start) {
+            ngrams.push(tokens[start..start + n].join(" "));
+        }
+    }
+
+    let Some((min_chars, max_chars)) = options.char_ngrams else {
+        return ngrams;
+    };
+    let mut char_ngrams = Vec::new();
+    for ngram in ngrams {
+        let chars: Vec<char> = ngram.chars().collect();
+        for start in 0..chars.len() {
+            for n in min_chars..=max_chars.min(chars.len() - start) {
+                char_ngrams.push(chars[start..start + n].iter().collect());
+            }
+        }
+    }
+    char_ngrams
+}
+
+/// Counts the occurrences of each token in markup-stripped text, excluding stop words.
+fn term_frequencies(text: &str) -> HashMap<String, f64> {
+    let mut counts = HashMap::new();
+    let Some(stripped) = strip_markup(Some(text), None) else {
+        return counts;
+    };
+
+    for token in tokenize(&stripped) {
+        if !ENGLISH_STOP_WORDS.contains(&token.as_str()) {
+            *counts.entry(token).or_insert(0.0) += 1.0;
+        }
+    }
+    counts
+}
+
+/// Computes the cosine similarity between two sparse term frequency vectors.
+fn cosine_similarity(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, weight)| b.get(term).map(|other| weight * other))
+        .sum();
+    if dot == 0.0 {
+        return 0.0;
+    }
+
+    let norm_a = a.values().map(|w| w * w).sum::<f64>().sqrt();
+    let norm_b = b.values().map(|w| w * w).sum::<f64>().sqrt();
+    (dot / (norm_a * norm_b)).clamp(0.0, 1.0)
+}
+
+/// Scores how relevant a work abstract is to a DMP description as the cosine similarity (0–1) of their stop word
+/// filtered term frequencies.
+pub fn dmp_work_relevance(dmp_text: &str, work_abstract: &str) -> f64 {
+    cosine_similarity(
+        &term_frequencies(dmp_text),
+        &term_frequencies(work_abstract),
+    )
+}
+
+/// Unicode normalization forms supported by `normalize_text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Looks up a normalization form by its name, e.g. "NFC", ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "NFC" => Some(Self::Nfc),
+            "NFD" => Some(Self::Nfd),
+            "NFKC" => Some(Self::Nfkc),
+            "NFKD" => Some(Self::Nfkd),
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes text to a Unicode normalization form, optionally stripping diacritics ("é" → "e") and applying
+/// full Unicode case folding ("Straße" → "strasse"), so that composed and decomposed forms of the same text compare
+/// equal. Only diacritics that decompose into combining marks are stripped, so letters such as "ø" are kept.
+pub fn normalize_text(
+    text: &str,
+    form: NormalizationForm,
+    fold_diacritics: bool,
+    case_fold: bool,
+) -> String {
+    let mut out = if fold_diacritics {
+        let decomposed: String = match form {
+            NormalizationForm::Nfkc | NormalizationForm::Nfkd => text.nfkd().collect(),
+            NormalizationForm::Nfc | NormalizationForm::Nfd => text.nfd().collect(),
+        };
+        decomposed
+            .chars()
+            .filter(|c| !is_combining_mark(*c))
+            .collect()
+    } else {
+        text.to_string()
+    };
+
+    // Case folding can produce unnormalized text, so it is applied before the final normalization
+    if case_fold {
+        out = default_case_fold_str(&out);
+    }
+
+    match form {
+        NormalizationForm::Nfc => out.nfc().collect(),
+        NormalizationForm::Nfd => out.nfd().collect(),
+        NormalizationForm::Nfkc => out.nfkc().collect(),
+        NormalizationForm::Nfkd => out.nfkd().collect(),
+    }
+}
+
+/// Whether a character renders as nothing: zero-width spaces, joiners and non-joiners, the word joiner, byte order
+/// marks, soft hyphens, bidirectional formatting marks, invisible math operators and control characters other than
+/// whitespace.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    ) || (c.is_control() && !c.is_whitespace())
+}
+
+/// Whether a character is whitespace other than a space, tab or line break, e.g. a no-break or thin space.
+fn is_unusual_whitespace(c: char) -> bool {
+    c.is_whitespace() && !matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Removes characters that render as nothing, e.g. zero-width spaces, byte order marks, soft hyphens and control
+/// characters, and replaces unusual whitespace such as no-break and thin spaces with regular spaces, so that texts
+/// that render identically compare equal. Text without such characters is borrowed unchanged.
+pub fn clean_text(text: &str) -> Cow<'_, str> {
+    if !text
+        .chars()
+        .any(|c| is_invisible(c) || is_unusual_whitespace(c))
+    {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .filter(|c| !is_invisible(*c))
+            .map(|c| if is_unusual_whitespace(c) { ' ' } else { c })
+            .collect(),
+    )
+}
+
+/// Transliterates text to its closest ASCII representation, e.g. "Müller" → "Muller" and "北京" → "Bei Jing".
+pub fn transliterate(text: &str) -> String {
+    deunicode(text)
+}
+
+/// Romanizations of lowercase Cyrillic letters, following BGN/PCGN for Russian with the Ukrainian letters added.
+/// The hard and soft signs are dropped.
+fn romanize_cyrillic_letter(c: char) -> Option<&'static str> {
+    Some(match c {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'ґ' => "g",
+        'д' => "d",
+        'е' | 'ё' | 'э' => "e",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' | 'ы' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    })
+}
+
+/// Romanizations of the most common Korean surnames, which rarely follow the Revised Romanization, e.g. "Kim" rather
+/// than "Gim".
+const KOREAN_SURNAMES: &[(char, &str)] = &[
+    ('김', "Kim"),
+    ('이', "Lee"),
+    ('박', "Park"),
+    ('최', "Choi"),
+    ('정', "Jung"),
+    ('강', "Kang"),
+    ('조', "Cho"),
+    ('윤', "Yoon"),
+    ('장', "Jang"),
+    ('임', "Lim"),
+    ('한', "Han"),
+    ('오', "Oh"),
+    ('서', "Seo"),
+    ('신', "Shin"),
+    ('권', "Kwon"),
+    ('황', "Hwang"),
+    ('안', "Ahn"),
+    ('송', "Song"),
+    ('유', "Yoo"),
+    ('류', "Ryu"),
+    ('홍', "Hong"),
+    ('전', "Jeon"),
+    ('고', "Ko"),
+    ('문', "Moon"),
+    ('양', "Yang"),
+    ('손', "Son"),
+    ('배', "Bae"),
+    ('백', "Baek"),
+    ('허', "Heo"),
+    ('노', "Noh"),
+    ('남', "Nam"),
+];
+
+/// Chinese compound surnames, in simplified and traditional forms.
+const CHINESE_COMPOUND_SURNAMES: &[&str] = &[
+    "欧阳", "歐陽", "司马", "司馬", "诸葛", "諸葛", "上官", "东方", "東方", "皇甫", "令狐", "慕容",
+    "夏侯", "尉迟", "尉遲", "公孙", "公孫", "长孙", "長孫", "宇文", "司徒",
+];
+
+/// Checks if a character is a Cyrillic letter.
+fn is_cyrillic_char(c: char) -> bool {
+    matches!(c as u32, 0x0400..=0x04FF)
+}
+
+/// Checks if a character is a Greek letter.
+fn is_greek_char(c: char) -> bool {
+    matches!(c as u32, 0x0370..=0x03FF | 0x1F00..=0x1FFF)
+}
+
+/// Romanizes a run of CJK characters syllable by syllable, e.g. "小龙" → "Xiaolong".
+fn romanize_cjk_run(chars: &[char]) -> String {
+    let syllables: String = chars
+        .iter()
+        .map(|c| deunicode(&c.to_string()).trim().to_lowercase())
+        .collect();
+    capitalize(&syllables)
+}
+
+/// Romanizes a run of Cyrillic characters. Word-final "ий" and "ый" become "y" as in the usual English spellings,
+/// e.g. "Юрий" → "Yury", and all-caps words stay in capitals.
+fn romanize_cyrillic_run(chars: &[char]) -> String {
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (romanized, consumed) = match lower[i..] {
+            ['и' | 'ы', 'й'] => ("y".to_string(), 2),
+            [c, ..] => (
+                romanize_cyrillic_letter(c)
+                    .map_or_else(|| deunicode(&c.to_string()), str::to_string),
+                1,
+            ),
+            [] => break,
+        };
+        if chars[i].is_uppercase() {
+            if chars.get(i + 1).is_some_and(|c| c.is_uppercase()) {
+                out.push_str(&romanized.to_uppercase());
+            } else {
+                out.push_str(&capitalize(&romanized));
+            }
+        } else {
+            out.push_str(&romanized);
+        }
+        i += consumed;
+    }
+    out
+}
+
+/// Romanizes a CJK name written surname first without spaces, e.g. "张伟" or "김민준", returning it given name
+/// first as romanized in Crossref and OpenAlex, e.g. "Wei Zhang" and "Minjun Kim". Returns None unless the word is
+/// a Chinese or Korean name of 2 to 4 characters.
+fn romanize_cjk_name(word: &str) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let surname_len = cjk_surname_len(word)?;
+    let (surname, given) = chars.split_at(surname_len);
+    let surname = match KOREAN_SURNAMES
+        .iter()
+        .find(|(syllable, _)| surname == [*syllable])
+    {
+        Some((_, romanized)) => romanized.to_string(),
+        None => romanize_cjk_run(surname),
+    };
+    Some(format!("{} {surname}", romanize_cjk_run(given)))
+}
+
+/// The number of characters of the surname at the start of a Chinese or Korean name written without spaces, e.g. 1
+/// for "张伟" and 2 for "欧阳娜娜": Chinese compound surnames take two characters and other surnames one. Returns
+/// None unless the word is a Chinese or Korean name of 2 to 4 characters.
+fn cjk_surname_len(word: &str) -> Option<usize> {
+    let chars: Vec<char> = word.chars().collect();
+    let is_han = |c: &char| matches!(*c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2A6DF);
+    let is_hangul = |c: &char| matches!(*c as u32, 0xAC00..=0xD7AF);
+    if !(2..=4).contains(&chars.len()) {
+        return None;
+    }
+
+    if chars.iter().all(is_hangul) {
+        return Some(1);
+    }
+    if chars.iter().all(is_han) {
+        let is_compound = chars.len() > 2
+            && CHINESE_COMPOUND_SURNAMES
+                .iter()
+                .any(|surname| word.starts_with(surname));
+        return Some(if is_compound { 2 } else { 1 });
+    }
+    None
+}
+
+/// Romanizes a name written in Cyrillic, Greek or CJK scripts so it can be compared against the romanized forms in
+/// Crossref and OpenAlex, e.g. "Юрий Хрущёв" → "Yury Khrushchev",
+/// "Γιώργος Παπαδόπουλος" → "Giorgos Papadopoulos" and "张伟" → "Wei Zhang".
+/// Chinese and Korean names written surname first without spaces are reordered given name first. Japanese kanji are
+/// romanized with their Chinese readings, so Japanese names only match when written in kana. Latin script text,
+/// including its diacritics, is returned unchanged.
+pub fn romanize(text: &str) -> String {
+    let needs_romanization = |c: char| is_cyrillic_char(c) || is_greek_char(c) || is_cjk_char(c);
+    if !text.chars().any(needs_romanization) {
+        return text.to_string();
+    }
+
+    text.split_whitespace()
+        .map(|word| {
+            if let Some(name) = romanize_cjk_name(word) {
+                return name;
+            }
+            let chars: Vec<char> = word.chars().collect();
+            let mut out = String::new();
+            let mut start = 0;
+            while start < chars.len() {
+                let script = |c: char| (is_cyrillic_char(c), is_greek_char(c), is_cjk_char(c));
+                let run_script = script(chars[start]);
+                let end = chars[start..]
+                    .iter()
+                    .position(|c| script(*c) != run_script)
+                    .map_or(chars.len(), |offset| start + offset);
+                let run = &chars[start..end];
+                match run_script {
+                    (true, _, _) => out.push_str(&romanize_cyrillic_run(run)),
+                    (_, true, _) => out.push_str(&deunicode(&run.iter().collect::<String>())),
+                    (_, _, true) => out.push_str(&romanize_cjk_run(run)),
+                    _ => out.extend(run),
+                }
+                start = end;
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parses a raw name with `parse_name` after romanizing it, see `romanize`, so that names in non-Latin scripts
+/// compare against their romanized forms.
+pub fn parse_romanized_name(
+    raw_given_name: Option<&str>,
+    raw_surname: Option<&str>,
+    raw_full: Option<&str>,
+) -> ParsedName {
+    let given_name = raw_given_name.map(romanize);
+    let surname = raw_surname.map(romanize);
+    let full = raw_full.map(romanize);
+    parse_name(
+        given_name.as_deref(),
+        surname.as_deref(),
+        full.as_deref(),
+        false,
+    )
+}
+
+/// Splits a delimited keywords/subjects field on ";", "," and newlines, returning each keyword trimmed,
+/// lowercased, transliterated and whitespace-collapsed, deduplicated in first-seen order.
+pub fn normalize_keywords(text: Option<&str>) -> Vec<String> {
+    let Some(text) = text else {
+        return Vec::new();
+    };
+
+    let mut keywords: Vec<String> = Vec::new();
+    for part in text.split([';', ',', '\n', '\r']) {
+        let keyword = transliterate(part)
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !keyword.is_empty() && !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
+    }
+    keywords
+}
+
+/// Computes the Jaccard overlap of two keywords/subjects fields after `normalize_keywords`. Returns 0.0 when both
+/// fields are empty, as there is no subject signal to compare.
+pub fn keyword_overlap(a: Option<&str>, b: Option<&str>) -> f64 {
+    let a: HashSet<String> = normalize_keywords(a).into_iter().collect();
+    let b: HashSet<String> = normalize_keywords(b).into_iter().collect();
+
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Assigns a key to one of `num_buckets` buckets using a stable XXH3 hash, so records can be partitioned
+/// reproducibly across workers and runs. Returns 0 when `num_buckets` is 0.
+pub fn shard_bucket(key: &str, num_buckets: u32) -> u32 {
+    if num_buckets == 0 {
+        return 0;
+    }
+
+    (XxHash3_64::oneshot(key.as_bytes()) % u64::from(num_buckets)) as u32
+}
+
+/// Assigns a name or title to one of `num_shards` shards with `shard_bucket` after normalizing it, cleaning, folding
+/// to lowercase ASCII and keeping only its words, so that "Müller", "MULLER" and "muller " share a shard. Returns
+/// None when the text has no letters or digits.
+pub fn shard_key(text: &str, num_shards: u32) -> Option<u32> {
+    let folded = fold_name(&clean_text(text));
+    let words: Vec<&str> = folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    (!words.is_empty()).then(|| shard_bucket(&words.join(" "), num_shards))
+}
+
+/// Computes a stable 128-bit XXH3 fingerprint of a record's normalized fields as 32 lowercase hex digits, for use as a
+/// document or cluster ID when a record has no DOI. Each field is encoded with a null marker and its length before
+/// hashing, so the field boundaries are unambiguous: `["ab", "c"]`, `["a", "bc"]` and `["abc", None]` all differ.
+/// The fingerprint depends only on the field bytes, so it is identical across runs, machines and Python versions.
+pub fn record_fingerprint(fields: &[Option<&str>]) -> String {
+    let mut encoded = Vec::new();
+    for field in fields {
+        match field {
+            Some(field) => {
+                encoded.push(1);
+                encoded.extend_from_slice(&(field.len() as u64).to_le_bytes());
+                encoded.extend_from_slice(field.as_bytes());
+            },
+            None => encoded.push(0),
+        }
+    }
+    format!("{:032x}", XxHash3_128::oneshot(&encoded))
+}
+
+/// Serializes a record as canonical JSON, so that equal records always serialize to the same bytes, for fingerprinting
+/// and byte-level regression tests of the transformers. Follows RFC 8785: there is no whitespace, object keys are
+/// sorted by their UTF-16 code units, strings escape only quotes, backslashes and control characters, and numbers are
+/// written in their shortest round-trip form, with floats that are whole numbers written as integers so that `1.0` and
+/// `1` are equal.
+pub fn to_canonical_json(value: &serde_json::Value) -> String {
+    let mut json = String::new();
+    write_canonical_json(value, &mut json);
+    json
+}
+
+fn write_canonical_json(value: &serde_json::Value, json: &mut String) {
+    match value {
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => json.push_str(&i.to_string()),
+            (None, Some(u)) => json.push_str(&u.to_string()),
+            _ => json.push_str(&canonical_float(n.as_f64().unwrap_or_default())),
+        },
+        serde_json::Value::Array(values) => {
+            json.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_canonical_json(value, json);
+            }
+            json.push(']');
+        },
+        serde_json::Value::Object(object) => {
+            // Keys outside the Basic Multilingual Plane sort before U+E000 to U+FFFF by their UTF-16 surrogates
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            json.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                json.push_str(&serde_json::Value::from(key.as_str()).to_string());
+                json.push(':');
+                write_canonical_json(value, json);
+            }
+            json.push('}');
+        },
+        // serde_json escapes strings as RFC 8785 does
+        _ => json.push_str(&value.to_string()),
+    }
+}
+
+/// Formats a finite float as ECMAScript does: whole numbers below 10^21 as integers, other numbers from 10^-6 to
+/// 10^21 in decimal notation and everything else in exponential notation, always with the fewest digits that round
+/// trip.
+fn canonical_float(f: f64) -> String {
+    if f == 0.0 {
+        // Also -0.0
+        return "0".to_string();
+    }
+    let magnitude = f.abs();
+    if (1e-6..1e21).contains(&magnitude) {
+        return f.to_string();
+    }
+    let exponential = format!("{f:e}");
+    match exponential.split_once("e") {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            format!("{mantissa}e+{exponent}")
+        },
+        _ => exponential,
+    }
+}
+
+/// Maximum number of characters of a text sampled for language detection.
+const LANGUAGE_DETECTION_SAMPLE_CHARS: usize = 1000;
+
+/// Maps a `whatlang` language to its ISO 639-1 code.
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Epo => "eo",
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Yid => "yi",
+        Lang::Pol => "pl",
+        Lang::Amh => "am",
+        Lang::Jav => "jv",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Mar => "mr",
+        Lang::Kan => "kn",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Tam => "ta",
+        Lang::Vie => "vi",
+        Lang::Urd => "ur",
+        Lang::Tha => "th",
+        Lang::Guj => "gu",
+        Lang::Uzb => "uz",
+        Lang::Pan => "pa",
+        Lang::Aze => "az",
+        Lang::Ind => "id",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Sin => "si",
+        Lang::Khm => "km",
+        Lang::Tuk => "tk",
+        Lang::Aka => "ak",
+        Lang::Zul => "zu",
+        Lang::Sna => "sn",
+        Lang::Afr => "af",
+        Lang::Lat => "la",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+        Lang::Cym => "cy",
+    }
+}
+
+/// Detects the language of a text, returning its ISO 639-1 code and the detection confidence (0–1). Only the
+/// first `LANGUAGE_DETECTION_SAMPLE_CHARS` characters are examined.
+pub fn detect_language(text: &str) -> Option<(String, f64)> {
+    let sample = match text.char_indices().nth(LANGUAGE_DETECTION_SAMPLE_CHARS) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    };
+
+    let info = whatlang::detect(sample)?;
+    Some((iso_639_1(info.lang()).to_string(), info.confidence()))
+}
+
+/// Detects the languages of many texts with `detect_language` in parallel on the current rayon thread pool,
+/// preserving order. Missing texts give None.
+pub fn detect_language_many(texts: &[Option<String>]) -> Vec<Option<(String, f64)>> {
+    texts
+        .par_iter()
+        .map(|text| detect_language(text.as_deref()?))
+        .collect()
+}
+
+/// Reconstructs an abstract from a JSON-serialized inverted index and detects its language in one pass, returning
+/// the abstract and its ISO 639-1 code. Returns None when the index is empty or invalid.
+pub fn revert_and_detect_language(text: Option<&[u8]>) -> Option<(String, Option<String>)> {
+    let abstract_text = revert_inverted_index(text, None)?;
+    let language = detect_language(&abstract_text).map(|(code, _)| code);
+    Some((abstract_text, language))
+}
+
+/// Folds a name for comparison by transliterating to ASCII, lowercasing and collapsing whitespace.
+pub(crate) fn fold_name(name: &str) -> String {
+    transliterate(name)
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Two-character Chinese surnames, in simplified and traditional forms, e.g. "欧阳" (Ouyang) and "司马" (Sima).
+const CJK_COMPOUND_SURNAMES: &[&str] = &[
+    "欧阳", "歐陽", "司马", "司馬", "诸葛", "諸葛", "上官", "东方", "東方", "皇甫", "令狐", "公孙",
+    "公孫", "慕容", "尉迟", "尉遲", "夏侯", "长孙", "長孫", "宇文", "司徒", "轩辕", "軒轅", "端木",
+    "澹台", "闻人", "聞人", "南宫", "南宮", "独孤", "獨孤", "呼延", "申屠", "西门", "西門",
+];
+
+/// A surname or given name of a CJK name: its characters when written in CJK script, and its romanization without
+/// tone marks, lowercased and without spaces, e.g. "zhang" for both "张" and "Zhāng".
+struct CjkNamePart {
+    chars: Option<Vec<char>>,
+    romanized: String,
+}
+
+impl CjkNamePart {
+    fn from_chars(chars: &[char]) -> Self {
+        let romanized = chars
+            .iter()
+            .map(|c| deunicode(&c.to_string()).trim().to_lowercase())
+            .collect();
+        Self {
+            chars: Some(chars.to_vec()),
+            romanized,
+        }
+    }
+
+    fn from_words(words: &[&str]) -> Self {
+        let romanized = words
+            .iter()
+            .flat_map(|word| deunicode(word).chars().collect::<Vec<_>>())
+            .filter(char::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        Self {
+            chars: None,
+            romanized,
+        }
+    }
+
+    /// 1.0 when the parts are written the same, ignoring tone marks, 0.9 when they differ but share a romanization,
+    /// e.g. simplified "伟" and traditional "偉", or "张" and "Zhang", and 0.0 otherwise.
+    fn similarity(&self, other: &Self) -> f64 {
+        match (&self.chars, &other.chars) {
+            (Some(a), Some(b)) if a == b => 1.0,
+            (None, None) if self.romanized == other.romanized => 1.0,
+            _ if self.romanized == other.romanized => 0.9,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Whether a character can be part of a romanized name, e.g. a letter of "Zhāng" or "Ou-yang".
+fn is_romanized_name_char(c: char) -> bool {
+    c.is_ascii_alphabetic()
+        || (matches!(c, '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}') && c.is_alphabetic())
+        // Combining diacritics, e.g. decomposed tone marks
+        || matches!(c, '\u{0300}'..='\u{036F}')
+        || matches!(c, '-' | '\'' | '’')
+        || c.is_whitespace()
+}
+
+/// The possible (surname, given name) readings of a CJK name. A name in CJK script is read surname first, with a
+/// two-character surname when it starts with one of `CJK_COMPOUND_SURNAMES` and has more characters, e.g. "欧阳修".
+/// A romanized name, e.g. "Zhang Wei" or "Wei Zhang", is read with its first or its last word as the surname. Returns
+/// no readings for other text.
+fn cjk_name_readings(text: &str) -> Vec<(CjkNamePart, CjkNamePart)> {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.is_empty() {
+        return Vec::new();
+    }
+    if chars.iter().all(|c| is_cjk_char(*c)) {
+        let compound = chars.len() > 2
+            && CJK_COMPOUND_SURNAMES.contains(&chars[..2].iter().collect::<String>().as_str());
+        let (surname, given) = chars.split_at(if compound { 2 } else { 1 });
+        return vec![(
+            CjkNamePart::from_chars(surname),
+            CjkNamePart::from_chars(given),
+        )];
+    }
+    if !text.chars().all(is_romanized_name_char) || !chars.iter().any(|c| c.is_alphabetic()) {
+        return Vec::new();
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut readings = vec![(
+        CjkNamePart::from_words(&words[..1]),
+        CjkNamePart::from_words(&words[1..]),
+    )];
+    if words.len() > 1 {
+        let (given, surname) = words.split_at(words.len() - 1);
+        readings.push((
+            CjkNamePart::from_words(surname),
+            CjkNamePart::from_words(given),
+        ));
+    }
+    readings
+}
+
+/// Compares two CJK names, written in CJK script surname first, e.g. "张伟" or "欧阳修", or romanized in either order,
+/// e.g. "Zhāng Wěi" or "Wei Zhang". The surnames and given names are compared by their characters and by their
+/// romanizations without tone marks, so that variant forms and romanizations of the same name still match. Returns
+/// 0.0 when the surnames differ or when either input isn't a CJK or romanized name, half the surname similarity when
+/// only the surnames match, and up to 1.0 when the whole names match, taking the best reading of romanized names.
+pub fn cjk_surname_match(a: &str, b: &str) -> f64 {
+    let (readings_a, readings_b) = (cjk_name_readings(a), cjk_name_readings(b));
+    let mut best: f64 = 0.0;
+    for (surname_a, given_a) in &readings_a {
+        for (surname_b, given_b) in &readings_b {
+            let surname = surname_a.similarity(surname_b);
+            if surname == 0.0 {
+                continue;
+            }
+            // Given names that differ halve the score, leaving only the surname match
+            let given = if given_a.romanized.is_empty() && given_b.romanized.is_empty() {
+                1.0
+            } else {
+                given_a.similarity(given_b).max(0.5)
+            };
+            best = best.max(surname * given);
+        }
+    }
+    best
+}
+
+/// Checks whether the surname parsed from a name matches an expected surname, ignoring case and transliteration
+/// differences and tolerating a small number of edits (none for surnames up to 3 characters, 1 up to 8 and 2
+/// beyond).
+pub fn surname_matches_expected(text: Option<&str>, expected: &str) -> bool {
+    let parsed = parse_name(None, None, text, false);
+    let Some(surname) = parsed.surname.as_deref().map(fold_name) else {
+        return false;
+    };
+    let expected = fold_name(expected);
+    if surname.is_empty() || expected.is_empty() {
+        return false;
+    }
+
+    let max_edits = match surname.chars().count().max(expected.chars().count()) {
+        0..=3 => 0,
+        4..=8 => 1,
+        _ => 2,
+    };
+    levenshtein(&surname, &expected) <= max_edits
+}
+
+/// Collects the given and middle name initials of a parsed name, e.g. "JM" for "John Michael Smith". The given
+/// name initial is derived from the given name when the parser did not produce one.
+fn name_initials(parsed: &ParsedName) -> String {
+    let first_initial = parsed.first_initial.clone().or_else(|| {
+        parsed
+            .given_name
+            .as_deref()
+            .filter(|g| has_alphabetic_initials(g))
+            .and_then(|g| g.graphemes(true).next().map(str::to_uppercase))
+    });
+
+    first_initial
+        .into_iter()
+        .chain(parsed.middle_initials.clone())
+        .collect()
+}
+
+/// Returns the surname to cite a parsed name by, treating single-word names as a surname.
+fn citation_surname(parsed: &ParsedName) -> Option<&str> {
+    let full = parsed.full.as_deref()?;
+    match parsed.surname.as_deref() {
+        Some(surname) => Some(surname),
+        None if !full.contains(char::is_whitespace) => Some(full),
+        None => None,
+    }
+}
+
+/// Formats a name as an abbreviated author citation: "vancouver" gives "Smith JM" and "apa" gives "Smith, J. M.".
+/// Single-word names are treated as a surname. Returns None for empty input or an unknown style.
+pub fn citation_name(text: Option<&str>, style: &str) -> Option<String> {
+    let parsed = parse_name(None, None, text, false);
+    let surname = citation_surname(&parsed)?;
+    let initials = name_initials(&parsed);
+
+    match style {
+        "vancouver" if initials.is_empty() => Some(surname.to_string()),
+        "vancouver" => Some(format!("{} {}", surname, initials)),
+        "apa" if initials.is_empty() => Some(surname.to_string()),
+        "apa" => {
+            let initials = initials
+                .graphemes(true)
+                .map(|initial| format!("{}.", initial))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Some(format!("{}, {}", surname, initials))
+        },
+        _ => None,
+    }
+}
+
+/// Builds a canonical "first-author-year" citation key such as "smith2021" from the first author's name and the
+/// publication year. The surname is transliterated to lowercase ASCII letters and digits, so "van der Berg" gives
+/// "vanderberg". Returns None if the surname or year is missing.
+pub fn citekey(first_author: Option<&str>, year: Option<i32>) -> Option<String> {
+    let year = year?;
+    let parsed = parse_name(None, None, first_author, false);
+    let surname: String = transliterate(citation_surname(&parsed)?)
+        .to_lowercase()
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    if surname.is_empty() {
+        return None;
+    }
+
+    Some(format!("{surname}{year}"))
+}
+
+/// Trailing markers indicating that an author list has been truncated.
+const ET_AL_MARKERS: &[&str] = &["et al.", "et. al.", "et al", "et alii"];
+
+/// Removes a trailing "et al." from a byline, returning the remaining byline and whether a marker was removed. The
+/// marker must start a word, so a name ending in "et", e.g. "Janet Al", is kept.
+fn strip_et_al(text: &str) -> (&str, bool) {
+    let byline = text.trim();
+    for marker in ET_AL_MARKERS {
+        let Some(start) = byline.len().checked_sub(marker.len()) else {
+            continue;
+        };
+        if byline.is_char_boundary(start)
+            && byline[start..].eq_ignore_ascii_case(marker)
+            && byline[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| c.is_whitespace() || matches!(c, ',' | ';'))
+        {
+            let rest = byline[..start].trim_end_matches([' ', ',', ';']);
+            return (rest, true);
+        }
+    }
+    (byline, false)
+}
+
+/// Checks whether a byline segment consists only of initials, e.g. "J.", "J. M." or "JM".
+fn looks_like_initials(text: &str) -> bool {
+    let mut tokens = text.split_whitespace().peekable();
+    tokens.peek().is_some()
+        && tokens.all(|token| {
+            let letters: Vec<char> = token.chars().filter(|c| *c != '.' && *c != '-').collect();
+            (1..=3).contains(&letters.len()) && letters.iter().all(|c| c.is_uppercase())
+        })
+}
+
+/// Splits an author byline into individual author names. Authors may be separated by ";", ",", "and" or "&", and
+/// "Surname, Initials" pairs such as "Smith, J., Doe, A." are kept together. A trailing "et al." is dropped.
+pub fn split_authors(text: &str) -> Vec<String> {
+    let (byline, _) = strip_et_al(text);
+
+    // Authors are separated by semicolons when present, otherwise by commas, with "and"/"&" before the last author
+    let separator = if byline.contains(';') { ";" } else { "," };
+    let joined = [", and ", "; and ", ", & ", "; & ", " and ", " & "]
+        .iter()
+        .fold(byline.to_string(), |acc, conjunction| {
+            acc.replace(conjunction, separator)
+        });
+    let segments: Vec<&str> = joined.split(separator).collect();
+    let segments: Vec<&str> = segments
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    // Re-pair "Surname, Initials" segments split apart by commas
+    let mut authors: Vec<String> = Vec::with_capacity(segments.len());
+    let mut i = 0;
+    while i < segments.len() {
+        match segments.get(i + 1) {
+            Some(next) if !looks_like_initials(segments[i]) && looks_like_initials(next) => {
+                authors.push(format!("{}, {}", segments[i], next));
+                i += 2;
+            },
+            _ => {
+                authors.push(segments[i].to_string());
+                i += 1;
+            },
+        }
+    }
+    authors
+}
+
+/// Counts the authors in a byline using `split_authors`.
+pub fn author_count(text: Option<&str>) -> usize {
+    text.map_or(0, |t| split_authors(t).len())
+}
+
+/// Keeps the first `n` authors of a byline joined with "; ", appending "et al." when authors were dropped.
+pub fn truncate_authors(text: Option<&str>, n: usize) -> String {
+    let authors = text.map(split_authors).unwrap_or_default();
+    let kept = authors[..n.min(authors.len())].join("; ");
+
+    if authors.len() <= n {
+        return kept;
+    }
+    if kept.is_empty() {
+        return "et al.".to_string();
+    }
+    format!("{} et al.", kept)
+}
+
+/// Re-joins the authors of a byline with the canonical "; " separator, so "A, B, and C", "A, B & C" and
+/// "A; B; C" all become "A; B; C". Returns None when the byline contains no authors.
+pub fn normalize_author_join(text: Option<&str>) -> Option<String> {
+    let authors = split_authors(text?);
+    if authors.is_empty() {
+        return None;
+    }
+
+    Some(authors.join("; "))
+}
+
+/// NIH grant numbers, e.g. "1R01-CA123456-01": optional application type, activity code, institute code, serial
+/// number and optional support year/suffixes.
+pub(crate) static NIH_GRANT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[1-9]?([RUPKFTDS][0-9]{2})[\s-]?([A-Z]{2})[\s-]?([0-9]{6})(?:-[0-9]{2}(?:[AS][0-9]+)*)?\b")
+        .unwrap()
+});
+
+/// Codes of the NSF directorates, divisions and offices that prefix award numbers, current and former, e.g. "DBI" for
+/// Biological Infrastructure or "ACI", the former code of "OAC".
+const NSF_ORG_CODES: &[&str] = &[
+    "ACI", "AGS", "ANI", "ANT", "ARC", "AST", "ATM", "BCS", "BES", "BIO", "CBET", "CCF", "CCR",
+    "CHE", "CISE", "CMMI", "CNS", "CTS", "DBI", "DEB", "DGE", "DMI", "DMR", "DMS", "DRL", "DUE",
+    "EAR", "ECCS", "ECS", "EDU", "EEC", "EES", "EFMA", "EHR", "EIA", "ENG", "EPS", "ESI", "GEO",
+    "HRD", "IBN", "IIP", "IIS", "IOS", "ITE", "MCB", "MPS", "OAC", "OCE", "OCI", "OIA", "OISE",
+    "OMA", "OPP", "PHY", "PLR", "REC", "RISE", "SBE", "SES", "SMA", "TIP",
+];
+
+/// NSF award numbers with the prefix of an NSF organization in `NSF_ORG_CODES`, e.g. "DBI-1234567" or "DMR 1507101".
+pub(crate) static NSF_ORG_AWARD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\b({})[\s-]?([0-9]{{7}})\b",
+        NSF_ORG_CODES.join("|")
+    ))
+    .unwrap()
+});
+
+/// Seven digit award numbers introduced by a grant/award keyword, e.g. "Grant No. 1234567" or "award 1234567".
+static KEYWORD_AWARD_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:grant|award|contract)s?\s*(?:no\.?|number|#|id)?\s*[:#]?\s*([0-9]{7})\b")
+        .unwrap()
+});
+
+/// Extracts NIH and NSF style grant/award IDs from free text, normalized to "R01CA123456", "DBI-1234567" or
+/// "1234567" forms, deduplicated in first-seen order.
+pub fn extract_grant_ids(text: Option<&str>) -> Vec<String> {
+    let Some(text) = text else {
+        return Vec::new();
+    };
+
+    // Collect (position, id, serial number) so that results can be ordered by first appearance
+    let mut hits: Vec<(usize, String, String)> = Vec::new();
+    for caps in NIH_GRANT_RE.captures_iter(text) {
+        let id = format!("{}{}{}", &caps[1], &caps[2], &caps[3]).to_uppercase();
+        hits.push((caps.get(0).unwrap().start(), id, caps[3].to_string()));
+    }
+    for caps in NSF_ORG_AWARD_RE.captures_iter(text) {
+        let id = format!("{}-{}", &caps[1], &caps[2]);
+        hits.push((caps.get(0).unwrap().start(), id, caps[2].to_string()));
+    }
+    for caps in KEYWORD_AWARD_RE.captures_iter(text) {
+        let digits = caps[1].to_string();
+        hits.push((caps.get(1).unwrap().start(), digits.clone(), digits));
+    }
+    hits.sort_by_key(|(start, _, _)| *start);
+
+    // Skip bare award numbers that were also found with an organization prefix
+    let prefixed: HashSet<&str> = hits
+        .iter()
+        .filter(|(_, id, serial)| id != serial)
+        .map(|(_, _, serial)| serial.as_str())
+        .collect();
+
+    let mut ids: Vec<String> = Vec::new();
+    for (_, id, serial) in &hits {
+        if id == serial && prefixed.contains(serial.as_str()) {
+            continue;
+        }
+        if !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+    ids
+}
+
+/// NSF award URLs, e.g. "https://www.nsf.gov/awardsearch/showAward?AWD_ID=2234213".
+static NSF_AWARD_URL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)AWD_ID=([0-9]{7})\b").unwrap());
+
+/// ERC grant agreement numbers, e.g. "ERC-2017-STG 757123" or "ERC grant agreement No. 757123".
+static ERC_GRANT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b([0-9]{6})\b").unwrap());
+
+/// Labels before award numbers, e.g. "Grant No. " or "Award #".
+static AWARD_LABEL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:(?:nsf|nih)\b[\s:-]*)?(?:(?:grant|award|contract|project)s?\b\s*)?(?:(?:no\.?|number|#|id)\s*)?[:#]?\s*")
+        .unwrap()
+});
+
+/// Funders whose award numbers `normalize_award_id` parses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AwardFunder {
+    Nih,
+    Nsf,
+    Erc,
+}
+
+/// Names, acronyms, ROR IDs and funder DOIs identifying each funder in a `normalize_award_id` funder hint.
+const AWARD_FUNDER_HINTS: &[(AwardFunder, &[&str])] = &[
+    (
+        AwardFunder::Nih,
+        &[
+            "nih",
+            "national institutes of health",
+            "01cwqze88",
+            "10.13039/100000002",
+        ],
+    ),
+    (
+        AwardFunder::Nsf,
+        &[
+            "nsf",
+            "national science foundation",
+            "021nxhr62",
+            "10.13039/100000001",
+        ],
+    ),
+    (
+        AwardFunder::Erc,
+        &[
+            "erc",
+            "european research council",
+            "0472cxd90",
+            "10.13039/501100000781",
+        ],
+    ),
+];
+
+/// Identifies the funder in a funder hint, a name, acronym, ROR ID or funder DOI.
+fn award_funder(hint: &str) -> Option<AwardFunder> {
+    let hint = hint.trim().to_lowercase();
+    let hint = normalize_ror(Some(&hint))
+        .or_else(|| normalize_doi(Some(&hint)))
+        .unwrap_or(hint);
+    AWARD_FUNDER_HINTS
+        .iter()
+        .find(|(_, names)| names.contains(&hint.as_str()))
+        .map(|(funder, _)| *funder)
+}
+
+/// An award number parsed by `parse_award_id`.
+#[derive(Debug, Clone, PartialEq)]
+enum AwardId {
+    /// An NIH core project number: activity code, institute code and serial number.
+    Nih {
+        activity_code: String,
+        institute_code: String,
+        serial_number: String,
+    },
+    /// An NSF award number, with its organization prefix when present.
+    Nsf {
+        org_id: Option<String>,
+        award_number: String,
+    },
+    /// An ERC grant agreement number.
+    Erc(String),
+    /// Any other award number, uppercased without separators.
+    Other(String),
+}
+
+impl AwardId {
+    /// The canonical string form, e.g. "R01GM123456", "DBI-1234567" or "757123".
+    fn canonical(&self) -> String {
+        match self {
+            Self::Nih {
+                activity_code,
+                institute_code,
+                serial_number,
+            } => format!("{activity_code}{institute_code}{serial_number}"),
+            Self::Nsf {
+                org_id: Some(org_id),
+                award_number,
+            } => format!("{org_id}-{award_number}"),
+            Self::Nsf {
+                org_id: None,
+                award_number,
+            } => award_number.clone(),
+            Self::Erc(number) | Self::Other(number) => number.clone(),
+        }
+    }
+
+    /// The number identifying the award within its funder, ignoring prefixes that are often omitted.
+    fn number(&self) -> String {
+        match self {
+            Self::Nih {
+                institute_code,
+                serial_number,
+                ..
+            } => format!("{institute_code}{serial_number}"),
+            Self::Nsf { award_number, .. } => award_number.clone(),
+            Self::Erc(number) | Self::Other(number) => number.clone(),
+        }
+    }
+}
+
+/// Parses an award number, trying the formats of the hinted funder, or all known formats without a hint.
+fn parse_award_id(text: &str, funder: Option<AwardFunder>) -> Option<AwardId> {
+    let upper = text.trim().to_uppercase();
+    let might_be = |candidate: AwardFunder| funder.is_none_or(|funder| funder == candidate);
+
+    if might_be(AwardFunder::Nih) {
+        if let Some(caps) = NIH_GRANT_RE.captures(&upper) {
+            return Some(AwardId::Nih {
+                activity_code: caps[1].to_string(),
+                institute_code: caps[2].to_string(),
+                serial_number: caps[3].to_string(),
+            });
+        }
+    }
+
+    if might_be(AwardFunder::Nsf) {
+        if let Some(caps) = NSF_AWARD_URL_RE.captures(&upper) {
+            return Some(AwardId::Nsf {
+                org_id: None,
+                award_number: caps[1].to_string(),
+            });
+        }
+        if let Some(caps) = NSF_ORG_AWARD_RE.captures(&upper) {
+            return Some(AwardId::Nsf {
+                org_id: Some(caps[1].to_string()),
+                award_number: caps[2].to_string(),
+            });
+        }
+    }
+
+    // ERC grant agreement numbers are too ambiguous to parse without an ERC hint or mention
+    let mentions_erc = upper.contains("ERC") || upper.contains("GRANT AGREEMENT");
+    if funder == Some(AwardFunder::Erc) || (funder.is_none() && mentions_erc) {
+        if let Some(caps) = ERC_GRANT_RE.captures(&upper) {
+            return Some(AwardId::Erc(caps[1].to_string()));
+        }
+    }
+
+    let unlabelled = AWARD_LABEL_RE.replace(&upper, "");
+    let number: String = unlabelled
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    if !number.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if funder == Some(AwardFunder::Nsf)
+        && number.len() == 7
+        && number.chars().all(|c| c.is_ascii_digit())
+    {
+        return Some(AwardId::Nsf {
+            org_id: None,
+            award_number: number,
+        });
+    }
+    Some(AwardId::Other(number))
+}
+
+/// Canonicalizes an award number so that differently formatted references to the same award are equal:
+/// - NIH grant numbers become their core project number, e.g. "1 R01 GM123456-01A1" → "R01GM123456".
+/// - NSF award numbers keep their organization prefix, e.g. "NSF DBI 1234567" → "DBI-1234567", and award URLs give
+///   the bare number.
+/// - ERC grant agreements become their six digit number, e.g. "ERC-2017-STG 757123" → "757123".
+/// - Other award numbers are uppercased without labels ("Grant No.") and separators, e.g. "EP/K503757/1" →
+///   "EPK5037571".
+///
+/// `funder_hint` (a funder name, acronym, ROR ID or funder DOI) restricts parsing to that funder's format; without it
+/// all formats are tried. Returns None when the text contains no award number.
+pub fn normalize_award_id(text: Option<&str>, funder_hint: Option<&str>) -> Option<String> {
+    let funder = funder_hint.and_then(award_funder);
+    parse_award_id(text?, funder).map(|award| award.canonical())
+}
+
+/// Checks whether two award numbers refer to the same award: their canonical forms (see `normalize_award_id`) are
+/// equal, or they have the same number within the funder when one omits a prefix, e.g. "1234567" and "DBI-1234567",
+/// or "GM123456" and "R01GM123456".
+pub fn compare_award_ids(a: Option<&str>, b: Option<&str>) -> bool {
+    let (Some(a), Some(b)) = (
+        a.and_then(|a| parse_award_id(a, None)),
+        b.and_then(|b| parse_award_id(b, None)),
+    ) else {
+        return false;
+    };
+    if a.canonical() == b.canonical() {
+        return true;
+    }
+    let same_funder = match (&a, &b) {
+        (
+            AwardId::Nsf {
+                org_id: Some(x), ..
+            },
+            AwardId::Nsf {
+                org_id: Some(y), ..
+            },
+        ) => x == y,
+        (AwardId::Other(_), AwardId::Other(_)) => false,
+        (AwardId::Other(_), _) | (_, AwardId::Other(_)) => true,
+        _ => std::mem::discriminant(&a) == std::mem::discriminant(&b),
+    };
+    same_funder && a.number() == b.number()
+}
+
+/// Combines named relevance signals (e.g. "title", "abstract", "author") into a single 0–1 score as their weighted
+/// mean. Only signals that are present and have a weight contribute, so a missing signal does not count as zero.
+/// Returns 0.0 when no weighted signals are provided.
+pub fn combined_relevance(signals: &HashMap<String, f64>, weights: &HashMap<String, f64>) -> f64 {
+    let (weighted_sum, total_weight) = signals
+        .iter()
+        .filter_map(|(name, value)| weights.get(name).map(|weight| (*value, *weight)))
+        .filter(|(value, weight)| value.is_finite() && weight.is_finite() && *weight > 0.0)
+        .fold((0.0, 0.0), |(sum, total), (value, weight)| {
+            (sum + value * weight, total + weight)
+        });
+
+    if total_weight == 0.0 {
+        return 0.0;
+    }
+
+    (weighted_sum / total_weight).clamp(0.0, 1.0)
+}
+
+/// Numeric dates: "2021", "2021-03", "2021-03-05", "2021/3/5", optionally followed by an RFC 3339 time.
+static NUMERIC_DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^([0-9]{4})(?:[-/]([0-9]{1,2})(?:[-/]([0-9]{1,2})(?:[t ][0-9]{2}:[0-9]{2}.*)?)?)?$",
+    )
+    .unwrap()
+});
+
+/// Dates with a month name: "March 2021", "5 Mar 2021" or "March 5, 2021".
+static MONTH_NAME_DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:([0-9]{1,2})\s+)?([a-z]+)\.?(?:\s+([0-9]{1,2}))?,?\s+([0-9]{4})$").unwrap()
+});
+
+/// Resolves an English month name or abbreviation to its number, e.g. "mar" and "march" → 3.
+fn month_number(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    if name.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| month.starts_with(name) || (name == "sept" && *month == "september"))
+        .map(|i| i as u32 + 1)
+}
+
+/// Returns the number of days in a month, accounting for leap years.
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A possibly partial calendar date: a year, optionally with a month and then a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedDate {
+    pub year: i32,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+}
+
+impl ParsedDate {
+    /// Validates a date's parts, returning None when the month or day is out of range. A day without a month is
+    /// ignored.
+    pub fn new(year: i32, month: Option<u32>, day: Option<u32>) -> Option<Self> {
+        match (month, day) {
+            (None, _) => Some(Self {
+                year,
+                month: None,
+                day: None,
+            }),
+            (Some(m), _) if !(1..=12).contains(&m) => None,
+            (Some(m), Some(d)) if d < 1 || d > days_in_month(year, m) => None,
+            (month, day) => Some(Self { year, month, day }),
+        }
+    }
+
+    /// Formats the date as "YYYY", "YYYY-MM" or "YYYY-MM-DD" depending on the parts available.
+    pub fn to_iso(self) -> String {
+        match (self.month, self.day) {
+            (Some(m), Some(d)) => format!("{:04}-{:02}-{:02}", self.year, m, d),
+            (Some(m), None) => format!("{:04}-{:02}", self.year, m),
+            _ => format!("{:04}", self.year),
+        }
+    }
+
+    /// Returns the first and last days the date covers, as days since 1970-01-01, e.g. "2021" covers 2021-01-01 to
+    /// 2021-12-31.
+    pub fn day_range(&self) -> (i64, i64) {
+        let first_month = self.month.unwrap_or(1);
+        let last_month = self.month.unwrap_or(12);
+        let first = days_from_civil(self.year, first_month, self.day.unwrap_or(1));
+        let last = days_from_civil(
+            self.year,
+            last_month,
+            self.day
+                .unwrap_or_else(|| days_in_month(self.year, last_month)),
+        );
+        (first, last)
+    }
+}
+
+/// Converts a proleptic Gregorian date to days since 1970-01-01, using Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Parses a date string (year, year-month, ISO date, RFC 3339 timestamp or "Month YYYY") into its parts. Returns None
+/// when the date cannot be parsed or is not a valid calendar date.
+pub fn parse_date(text: Option<&str>) -> Option<ParsedDate> {
+    let text = text?.trim().to_lowercase();
+
+    if let Some(caps) = NUMERIC_DATE_RE.captures(&text) {
+        let year = caps[1].parse().ok()?;
+        let month = caps.get(2).and_then(|m| m.as_str().parse().ok());
+        let day = caps.get(3).and_then(|d| d.as_str().parse().ok());
+        return ParsedDate::new(year, month, day);
+    }
+
+    if let Some(caps) = MONTH_NAME_DATE_RE.captures(&text) {
+        let year = caps[4].parse().ok()?;
+        let month = month_number(&caps[2])?;
+        let day = caps
+            .get(1)
+            .or(caps.get(3))
+            .and_then(|d| d.as_str().parse().ok());
+        return ParsedDate::new(year, Some(month), day);
+    }
+
+    None
+}
+
+/// Builds a date from Crossref-style date parts, e.g. `[2021, 3, 5]`, `[2021, 3]` or `[2021]`. Parts after the
+/// first missing one are ignored. Returns None when there is no year or the date is not a valid calendar date.
+pub fn date_from_parts(parts: &[Option<i64>]) -> Option<ParsedDate> {
+    let mut parts = parts.iter().map_while(|part| *part);
+    let year = i32::try_from(parts.next()?).ok()?;
+    let month = parts.next().map(u32::try_from).transpose().ok()?;
+    let day = parts.next().map(u32::try_from).transpose().ok()?;
+    ParsedDate::new(year, month, day)
+}
+
+/// Normalizes a date string (year, year-month, ISO date, RFC 3339 timestamp or "Month YYYY") to the most precise
+/// ISO form available: "YYYY", "YYYY-MM" or "YYYY-MM-DD". Returns None when the date cannot be parsed.
+pub fn normalize_date(text: Option<&str>) -> Option<String> {
+    parse_date(text).map(|date| date.to_iso())
+}
+
+/// Normalized distance (0–1) between two possibly partial dates: the number of days between the periods they cover
+/// divided by `max_days` and capped at 1, so that dates whose periods overlap, e.g. "2021" and "2021-03-05", are 0.0
+/// apart and dates at least `max_days` apart are 1.0 apart.
+pub fn date_distance(a: &ParsedDate, b: &ParsedDate, max_days: f64) -> f64 {
+    let ((first_a, last_a), (first_b, last_b)) = (a.day_range(), b.day_range());
+    let gap = (first_b - last_a).max(first_a - last_b).max(0) as f64;
+    if max_days <= 0.0 {
+        return if gap > 0.0 { 1.0 } else { 0.0 };
+    }
+    (gap / max_days).min(1.0)
+}
+
+/// Minimum similarity for two author names to be considered the same person.
+const MIN_AUTHOR_SIMILARITY: f64 = 0.5;
+
+/// Scores how likely two parsed names refer to the same person (0–1): surnames must match after transliteration
+/// (allowing near-identical spellings) and given name initials must not conflict.
+fn author_similarity(a: &ParsedName, b: &ParsedName) -> f64 {
+    let (Some(surname_a), Some(surname_b)) = (a.surname.as_deref(), b.surname.as_deref()) else {
+        return 0.0;
+    };
+    let (surname_a, surname_b) = (fold_name(surname_a), fold_name(surname_b));
+    if surname_a.is_empty() || surname_b.is_empty() {
+        return 0.0;
+    }
+
+    let surname_score = if surname_a == surname_b {
+        1.0
+    } else {
+        let score = jaro_winkler(&surname_a, &surname_b);
+        if score < 0.92 {
+            return 0.0;
+        }
+        score
+    };
+
+    surname_score * initials_compatible(a, b)
+}
+
+/// Groups of interchangeable given names, the formal name first followed by its spelling variants, nicknames and short
+/// forms. A nickname may belong to several groups, e.g. "chris" to both "christopher" and "christine".
+const GIVEN_NAME_VARIANTS: &[&[&str]] = &[
+    &["abigail", "abby", "gail"],
+    &["albert", "al", "bert"],
+    &["alexander", "alex", "alec", "sandy", "sasha"],
+    &["alexandra", "alex", "sandra", "sandy", "sasha"],
+    &["alfred", "al", "alf", "fred"],
+    &["andrew", "andy", "drew"],
+    &["anthony", "tony"],
+    &["barbara", "barb", "babs"],
+    &["benjamin", "ben", "benny"],
+    &[
+        "catherine",
+        "kathryn",
+        "katherine",
+        "cathy",
+        "kathy",
+        "kate",
+        "katie",
+        "kat",
+    ],
+    &["charles", "charlie", "chuck", "chas"],
+    &["christina", "christine", "chris", "tina", "kristy"],
+    &["christopher", "chris", "kit"],
+    &["daniel", "dan", "danny"],
+    &["david", "dave", "davy"],
+    &["deborah", "debbie", "deb"],
+    &["donald", "don", "donnie"],
+    &["dorothy", "dot", "dottie"],
+    &["edward", "ed", "eddie", "ted", "ned"],
+    &[
+        "elizabeth",
+        "liz",
+        "lizzie",
+        "beth",
+        "betty",
+        "betsy",
+        "eliza",
+        "libby",
+    ],
+    &["eugene", "gene"],
+    &["frances", "fran", "frankie"],
+    &["francis", "frank", "fran"],
+    &["frederick", "fred", "freddie"],
+    &["gregory", "greg"],
+    &["harold", "harry", "hal"],
+    &["henry", "harry", "hank"],
+    &["isabel", "isabella", "bella", "izzy"],
+    &["jacob", "jake"],
+    &["james", "jim", "jimmy", "jamie"],
+    &["jennifer", "jen", "jenny"],
+    &["jessica", "jess", "jessie"],
+    &["john", "jack", "johnny", "jon"],
+    &["jonathan", "jon", "jonny"],
+    &["joseph", "joe", "joey"],
+    &["joshua", "josh"],
+    &["judith", "judy"],
+    &["kenneth", "ken", "kenny"],
+    &["lawrence", "laurence", "larry"],
+    &["leonard", "leo", "len", "lenny"],
+    &[
+        "margaret", "maggie", "meg", "peggy", "marge", "margie", "greta",
+    ],
+    &["matthew", "matt"],
+    &["michael", "mike", "mick", "mickey"],
+    &["nathan", "nathaniel", "nat", "nate"],
+    &["nicholas", "nick", "nicky"],
+    &["pamela", "pam"],
+    &["patricia", "pat", "patty", "trish"],
+    &["patrick", "pat", "paddy"],
+    &["peter", "pete"],
+    &["philip", "phillip", "phil"],
+    &["raymond", "ray"],
+    &["rebecca", "becky", "becca"],
+    &["richard", "rick", "ricky", "dick", "rich"],
+    &["robert", "rob", "bob", "bobby", "robbie", "bert"],
+    &["ronald", "ron", "ronnie"],
+    &["samantha", "sam", "sammy"],
+    &["samuel", "sam", "sammy"],
+    &["stephen", "steven", "steve"],
+    &["susan", "sue", "susie"],
+    &["theodore", "ted", "teddy", "theo"],
+    &["thomas", "tom", "tommy"],
+    &["timothy", "tim", "timmy"],
+    &["victoria", "vicky", "tori"],
+    &["walter", "walt", "wally"],
+    &["william", "bill", "billy", "will", "willy", "liam"],
+    &["zachary", "zach", "zack"],
+];
+
+/// Expands a given name into its interchangeable variants: the name itself followed by the formal names and
+/// nicknames it shares a group with, e.g. "Bill" → ["Bill", "William", "Billy", "Will", "Willy", "Liam"]. Variants
+/// are capitalized and deduplicated ignoring case and diacritics. Returns an empty list for an empty name, and only
+/// the name itself when it has no known variants.
+pub fn expand_given_name_variants(name: &str) -> Vec<String> {
+    let name = name.trim();
+    let folded = fold_name(name);
+    if folded.is_empty() {
+        return Vec::new();
+    }
+
+    let mut variants = vec![name.to_string()];
+    let mut seen = HashSet::from([folded.clone()]);
+    for group in GIVEN_NAME_VARIANTS
+        .iter()
+        .filter(|group| group.contains(&folded.as_str()))
+    {
+        for variant in group.iter() {
+            if seen.insert(variant.to_string()) {
+                variants.push(capitalize(variant));
+            }
+        }
+    }
+    variants
+}
+
+/// Checks whether two given names are variants of each other, e.g. "Bill" and "William" or "Bob" and "Robert".
+fn given_names_are_variants(a: &str, b: &str) -> bool {
+    let b = fold_name(b);
+    expand_given_name_variants(a)
+        .iter()
+        .any(|variant| fold_name(variant) == b)
+}
+
+/// Similarity of a pair of names, as returned by `compare_parsed_names`.
+#[derive(Debug, Clone, Copy)]
+pub struct NameSimilarity {
+    /// The surnames are identical, ignoring case, once names in non-Latin scripts are romanized.
+    pub exact_surname: bool,
+    /// The given name initials don't conflict.
+    pub initials_compatible: bool,
+    /// The surnames are identical after transliteration, e.g. "Müller" and "Muller".
+    pub transliteration_match: bool,
+    /// The surnames share a Double Metaphone code, e.g. "Meyer" and "Maier". Only checked when comparing with
+    /// `phonetic` set, otherwise false.
+    pub phonetic_match: bool,
+    /// Overall likelihood (0–1) that the names refer to the same person.
+    pub score: f64,
+}
+
+/// Scores how likely two parsed names refer to the same person, see `NameSimilarity`. The names are compared as they
+/// are, so raw names in non-Latin scripts should be parsed with `parse_romanized_name`. With `nicknames` set, given
+/// names that are variants of each other (see `expand_given_name_variants`) count as the same given name, so "Bill
+/// Smith" matches "William Smith". Titles and suffixes are ignored, so "Dr. John Smith Jr." matches "J. Smith". With
+/// `phonetic` set, surnames that sound alike but are spelt too differently to match, e.g. "Schmidt" and "Smith", score
+/// 0.9 before weighting by the initials.
+pub fn compare_parsed_names(
+    a: ParsedName,
+    mut b: ParsedName,
+    nicknames: bool,
+    phonetic: bool,
+) -> NameSimilarity {
+    if nicknames {
+        if let (Some(given_a), Some(given_b)) = (a.given_name.as_deref(), b.given_name.as_deref()) {
+            if given_names_are_variants(given_a, given_b) {
+                b.given_name = a.given_name.clone();
+                b.first_initial = a.first_initial.clone();
+            }
+        }
+    }
+
+    let surnames = a.surname.as_deref().zip(b.surname.as_deref());
+    let phonetic_match = phonetic && surnames.is_some_and(|(a, b)| phonetic::sounds_alike(a, b));
+    let mut score = author_similarity(&a, &b);
+    if phonetic_match && score == 0.0 {
+        score = 0.9 * initials_compatible(&a, &b);
+    }
+    NameSimilarity {
+        exact_surname: surnames.is_some_and(|(a, b)| a.to_lowercase() == b.to_lowercase()),
+        initials_compatible: initials_compatible(&a, &b) > 0.0,
+        transliteration_match: surnames.is_some_and(|(a, b)| {
+            let folded = fold_name(a);
+            !folded.is_empty() && folded == fold_name(b)
+        }),
+        phonetic_match,
+        score,
+    }
+}
+
+/// Checks whether `needle` appears within `haystack` in order, not necessarily contiguously.
+fn is_subsequence(needle: &[char], haystack: &[char]) -> bool {
+    let mut haystack = haystack.iter();
+    needle.iter().all(|c| haystack.any(|h| h == c))
+}
+
+/// Scores how compatible the given and middle name initials of two names are (0–1):
+/// - 1.0 when the initials are identical or one is a prefix/subset of the other ("J" vs "JM", "JL" vs "JML").
+/// - 0.8 when either name has no initials, as nothing conflicts but there is less evidence.
+/// - 0.5 when the first initials agree but later ones conflict, or when one name appears to use a middle name as
+///   its given name ("M" vs "JM").
+/// - 0.0 when the initials conflict.
+pub fn initials_compatible(a: &ParsedName, b: &ParsedName) -> f64 {
+    let initials_a: Vec<char> = fold_name(&name_initials(a)).chars().collect();
+    let initials_b: Vec<char> = fold_name(&name_initials(b)).chars().collect();
+    if initials_a.is_empty() || initials_b.is_empty() {
+        return 0.8;
+    }
+
+    let (shorter, longer) = if initials_a.len() <= initials_b.len() {
+        (&initials_a, &initials_b)
+    } else {
+        (&initials_b, &initials_a)
+    };
+    let same_first = shorter[0] == longer[0];
+
+    match (same_first, is_subsequence(shorter, longer)) {
+        (true, true) => 1.0,
+        (true, false) | (false, true) => 0.5,
+        (false, false) => 0.0,
+    }
+}
+
+/// Solves the assignment problem for a cost matrix with no more rows than columns using the Hungarian algorithm,
+/// returning the column assigned to each row such that the total cost is minimal.
+fn hungarian(cost: &[Vec<f64>]) -> Vec<usize> {
+    let rows = cost.len();
+    let cols = cost.first().map_or(0, Vec::len);
+
+    // 1-indexed potentials and matching, with index 0 as a sentinel
+    let mut u = vec![0.0; rows + 1];
+    let mut v = vec![0.0; cols + 1];
+    let mut row_of_col = vec![0usize; cols + 1];
+    let mut way = vec![0usize; cols + 1];
+
+    for row in 1..=rows {
+        row_of_col[0] = row;
+        let mut col0 = 0;
+        let mut min_slack = vec![f64::INFINITY; cols + 1];
+        let mut used = vec![false; cols + 1];
+
+        loop {
+            used[col0] = true;
+            let row0 = row_of_col[col0];
+            let mut delta = f64::INFINITY;
+            let mut col1 = 0;
+            for col in 1..=cols {
+                if used[col] {
+                    continue;
+                }
+                let slack = cost[row0 - 1][col - 1] - u[row0] - v[col];
+                if slack < min_slack[col] {
+                    min_slack[col] = slack;
+                    way[col] = col0;
+                }
+                if min_slack[col] < delta {
+                    delta = min_slack[col];
+                    col1 = col;
+                }
+            }
+            for col in 0..=cols {
+                if used[col] {
+                    u[row_of_col[col]] += delta;
+                    v[col] -= delta;
+                } else {
+                    min_slack[col] -= delta;
+                }
+            }
+            col0 = col1;
+            if row_of_col[col0] == 0 {
+                break;
+            }
+        }
+
+        loop {
+            let col1 = way[col0];
+            row_of_col[col0] = row_of_col[col1];
+            col0 = col1;
+            if col0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment = vec![0; rows];
+    for (col, row) in row_of_col.iter().enumerate().skip(1) {
+        if *row != 0 {
+            assignment[row - 1] = col - 1;
+        }
+    }
+    assignment
+}
+
+/// Finds the optimal one-to-one matching between two author lists, returning (index in `a`, index in `b`,
+/// similarity) for each matched pair with a similarity of at least `MIN_AUTHOR_SIMILARITY`.
+fn assign_authors(a: &[ParsedName], b: &[ParsedName]) -> Vec<(usize, usize, f64)> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    // The Hungarian algorithm requires rows <= columns
+    let transposed = a.len() > b.len();
+    let (rows, cols) = if transposed { (b, a) } else { (a, b) };
+    let similarity: Vec<Vec<f64>> = rows
+        .iter()
+        .map(|r| cols.iter().map(|c| author_similarity(r, c)).collect())
+        .collect();
+    let cost: Vec<Vec<f64>> = similarity
+        .iter()
+        .map(|row| row.iter().map(|s| 1.0 - s).collect())
+        .collect();
+
+    hungarian(&cost)
+        .into_iter()
+        .enumerate()
+        .map(|(row, col)| (row, col, similarity[row][col]))
+        .filter(|(_, _, score)| *score >= MIN_AUTHOR_SIMILARITY)
+        .map(|(row, col, score)| {
+            if transposed {
+                (col, row, score)
+            } else {
+                (row, col, score)
+            }
+        })
+        .collect()
+}
+
+/// Checks whether two author bylines likely list the same authors: the optimal author matching must cover at least
+/// `threshold` (0–1) of the authors. When either byline is truncated with "et al.", only the authors present in the
+/// shorter list are compared.
+pub fn bylines_match(a: Option<&str>, b: Option<&str>, threshold: f64) -> bool {
+    let (Some(a), Some(b)) = (a, b) else {
+        return false;
+    };
+    let parse = |byline: &str| -> Vec<ParsedName> {
+        split_authors(byline)
+            .iter()
+            .map(|author| parse_name(None, None, Some(author), false))
+            .collect()
+    };
+    let (authors_a, authors_b) = (parse(a), parse(b));
+    if authors_a.is_empty() || authors_b.is_empty() {
+        return false;
+    }
+
+    let truncated = strip_et_al(a).1 || strip_et_al(b).1;
+    let denominator = if truncated {
+        authors_a.len().min(authors_b.len())
+    } else {
+        authors_a.len().max(authors_b.len())
+    };
+
+    let matched: f64 = assign_authors(&authors_a, &authors_b)
+        .iter()
+        .map(|(_, _, score)| score)
+        .sum();
+    matched / denominator as f64 >= threshold
+}
+
+/// The optimal matching between two author lists, as returned by `author_overlap`.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorOverlap {
+    /// Matched pairs as (index in the first list, index in the second list, similarity), ordered by the first index.
+    pub pairs: Vec<(usize, usize, f64)>,
+    /// The summed similarity of the matched pairs divided by the number of comparable authors in the shorter list
+    /// (0–1).
+    pub score: f64,
+}
+
+/// Matches two author lists one-to-one, maximizing the total similarity with the Hungarian algorithm, where authors
+/// match when their surnames agree (allowing transliteration and near-identical spellings) and their initials don't
+/// conflict. Authors without a surname can't be compared and are left unmatched. Returns None when either list has no
+/// comparable authors.
+pub fn author_overlap(a: &[ParsedName], b: &[ParsedName]) -> Option<AuthorOverlap> {
+    let comparable = |names: &[ParsedName]| -> (Vec<usize>, Vec<ParsedName>) {
+        names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| name.surname.is_some())
+            .map(|(i, name)| (i, name.clone()))
+            .unzip()
+    };
+    let (indexes_a, names_a) = comparable(a);
+    let (indexes_b, names_b) = comparable(b);
+    if names_a.is_empty() || names_b.is_empty() {
+        return None;
+    }
+
+    let mut pairs: Vec<(usize, usize, f64)> = assign_authors(&names_a, &names_b)
+        .into_iter()
+        .map(|(i, j, score)| (indexes_a[i], indexes_b[j], score))
+        .collect();
+    pairs.sort_by_key(|(i, _, _)| *i);
+    let matched: f64 = pairs.iter().map(|(_, _, score)| score).sum();
+    Some(AuthorOverlap {
+        pairs,
+        score: matched / names_a.len().min(names_b.len()) as f64,
+    })
+}
+
+/// Whitespace before punctuation that is itself followed by whitespace or the end of the text, e.g. "word , next".
+/// Punctuation followed by other characters is left alone so that numbers such as ".5" are kept intact.
+static SPACE_BEFORE_PUNCTUATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s+([,.;:!?])(\s|$)").unwrap());
+
+/// Whitespace just inside opening or closing brackets, e.g. "( text )".
+static SPACE_INSIDE_BRACKETS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([(\[{])\s+|\s+([)\]}])").unwrap());
+
+/// Runs of repeated punctuation, e.g. "!!" or ",,". Runs of dots are handled separately to keep ellipses.
+static REPEATED_PUNCTUATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!{2,}|\?{2,}|,{2,}|;{2,}|:{2,}").unwrap());
+
+/// A doubled full stop that is not part of an ellipsis.
+static DOUBLE_FULL_STOP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[^.])\.\.([^.]|$)").unwrap());
+
+/// Removes whitespace just inside pairs of straight double quotes, e.g. `" quoted "` → `"quoted"`. Quotes are
+/// only paired when they are balanced, otherwise the text is returned unchanged.
+fn tidy_quote_spacing(text: &str) -> String {
+    if !text.matches('"').count().is_multiple_of(2) {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut inside = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            if inside {
+                let trimmed_len = out.trim_end().len();
+                out.truncate(trimmed_len);
+            }
+            out.push(c);
+            if !inside {
+                while chars.peek().is_some_and(|next| next.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            inside = !inside;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// An ORCID iD, optionally as an orcid.org URL, e.g. "0000-0002-1825-0097".
+static ORCID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?i:(https?://)?orcid\.org/)?\d{4}-\d{4}-\d{4}-\d{3}[\dX]$").unwrap()
+});
+
+/// An email address, e.g. "j.doe@example.org".
+static EMAIL_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap());
+
+/// Lowercase words allowed in author names and affiliations, e.g. "University of Oxford" or "Ludwig van Beethoven".
+const AUTHOR_BLOCK_CONNECTORS: &[&str] = &[
+    "and", "da", "de", "del", "der", "di", "du", "for", "la", "le", "of", "the", "van", "von",
+];
+
+/// Checks whether a trailing run of text looks like an author or affiliation block: comma or semicolon separated
+/// parts made only of capitalized words, initials, ORCIDs, emails and name connectors, with either an ORCID or email
+/// or at least three parts.
+fn looks_like_author_block(text: &str) -> bool {
+    let text = text.strip_suffix('.').unwrap_or(text);
+    let parts: Vec<&str> = text
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+    if parts.is_empty() {
+        return false;
+    }
+
+    let mut has_identifier = false;
+    let all_author_words = parts
+        .iter()
+        .flat_map(|part| part.split_whitespace())
+        .all(|word| {
+            if ORCID_RE.is_match(word) || EMAIL_RE.is_match(word) {
+                has_identifier = true;
+                return true;
+            }
+            let is_capitalized = word.chars().next().is_some_and(char::is_uppercase)
+                && word
+                    .chars()
+                    .all(|c| c.is_alphabetic() || c == '-' || c == '\'');
+            is_capitalized || looks_like_initials(word) || AUTHOR_BLOCK_CONNECTORS.contains(&word)
+        });
+
+    all_author_words && (has_identifier || parts.len() >= 3)
+}
+
+/// Removes a trailing author or affiliation block bled into an abstract from the page, e.g. "... in soils. Jane
+/// Smith, John Doe, University of Oxford, 0000-0002-1825-0097". Only whole sentences after a sentence boundary are
+/// removed, and the earliest boundary after which the entire rest of the text looks like an author block is used.
+fn strip_trailing_author_block(text: &str) -> &str {
+    let boundaries = text.char_indices().filter(|&(i, c)| {
+        // Ignore full stops after initials, e.g. "J. Smith"
+        let word = text[..i].rsplit(' ').next().unwrap_or_default();
+        matches!(c, '.' | '!' | '?')
+            && text[i + 1..].starts_with(' ')
+            && !(c == '.' && looks_like_initials(word))
+    });
+
+    for (i, _) in boundaries {
+        if looks_like_author_block(&text[i + 2..]) {
+            return &text[..=i];
+        }
+    }
+    text
+}
+
+/// Tidies spacing and punctuation artifacts in reconstructed abstracts: removes whitespace before punctuation and
+/// inside brackets and quotes, collapses repeated punctuation ("!!" → "!") and collapses whitespace. Changes are
+/// conservative so that scientific notation such as "p < .05" or "1,000" is unchanged. When `strip_trailing_authors`
+/// is set, a trailing author or affiliation block is also removed.
+pub fn tidy_reconstructed(text: &str, strip_trailing_authors: bool) -> String {
+    let tidied = SPACE_BEFORE_PUNCTUATION_RE.replace_all(text, "$1$2");
+    let tidied = SPACE_INSIDE_BRACKETS_RE.replace_all(&tidied, "$1$2");
+    let tidied = REPEATED_PUNCTUATION_RE
+        .replace_all(&tidied, |caps: &regex::Captures| caps[0][..1].to_string());
+    let tidied = DOUBLE_FULL_STOP_RE.replace_all(&tidied, "$1.$2");
+    let tidied = tidy_quote_spacing(&tidied);
+    let tidied = tidied.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if strip_trailing_authors {
+        strip_trailing_author_block(&tidied).to_string()
+    } else {
+        tidied
+    }
+}
+
+/// Number of authors at which a record's author list counts as complete.
+const COMPLETE_AUTHOR_COUNT: usize = 3;
+
+/// Scores how complete a record's matching metadata is (0–1) as a weighted sum: title 0.25, abstract 0.25, DOI 0.2,
+/// authors 0.2 (scaled up to `COMPLETE_AUTHOR_COUNT` authors) and publication year 0.1.
+pub fn record_completeness(
+    has_title: bool,
+    has_abstract: bool,
+    has_doi: bool,
+    author_count: usize,
+    has_year: bool,
+) -> f64 {
+    let authors = author_count.min(COMPLETE_AUTHOR_COUNT) as f64 / COMPLETE_AUTHOR_COUNT as f64;
+
+    [
+        (has_title as u8 as f64, 0.25),
+        (has_abstract as u8 as f64, 0.25),
+        (has_doi as u8 as f64, 0.2),
+        (authors, 0.2),
+        (has_year as u8 as f64, 0.1),
+    ]
+    .iter()
+    .map(|(value, weight)| value * weight)
+    .sum()
 }
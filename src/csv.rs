@@ -0,0 +1,405 @@
+use crate::core::{
+    normalize_doi, normalize_orcid, normalize_ror, open_text, parse_name, parsed_name_fields,
+    parsed_names_array, strip_markup, ParsedName, PARSED_NAME_FIELDS,
+};
+use crate::parquet_io::{writer_properties, ParquetWriteOptions, WRITE_BATCH_SIZE};
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::errors::Result;
+use rayon::prelude::*;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Options for reading delimited files.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Separates fields, e.g. `b','` for CSV or `b'\t'` for TSV.
+    pub delimiter: u8,
+    /// Quotes fields that contain delimiters, quotes or newlines, with quotes inside escaped by doubling them. None
+    /// reads quote characters literally, as in unquoted TSV.
+    pub quote: Option<u8>,
+    /// Names of the columns of a file without a header row. When None the first row is the header.
+    pub column_names: Option<Vec<String>>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: Some(b'"'),
+            column_names: None,
+        }
+    }
+}
+
+/// An `InvalidData` error for a record of a delimited file.
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Streaming parser over the records of a delimited file, following RFC 4180: quoted fields may contain delimiters,
+/// newlines and doubled quotes, and records may end with "\n" or "\r\n". A quote that doesn't start a field is read
+/// literally. Blank lines are skipped.
+pub struct CsvRecords {
+    reader: Box<dyn BufRead + Send>,
+    options: CsvOptions,
+    /// Number of lines read so far, for error messages.
+    line_number: usize,
+}
+
+impl CsvRecords {
+    pub fn new(reader: Box<dyn BufRead + Send>, options: CsvOptions) -> Self {
+        Self {
+            reader,
+            options,
+            line_number: 0,
+        }
+    }
+
+    /// Number of lines read so far, i.e. the line that the last record returned ends on.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Reads the next record's fields as raw bytes, or None at the end of the file.
+    fn read_record(&mut self) -> io::Result<Option<Vec<Vec<u8>>>> {
+        let start_line = self.line_number + 1;
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let (mut quoted, mut in_quotes) = (false, false);
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                if in_quotes {
+                    return Err(invalid_data(format!(
+                        "unterminated quoted field starting on line {start_line}"
+                    )));
+                }
+                if fields.is_empty() && field.is_empty() && !quoted {
+                    return Ok(None);
+                }
+                fields.push(field);
+                return Ok(Some(fields));
+            }
+            self.line_number += 1;
+
+            let mut bytes = line.iter().copied().peekable();
+            while let Some(byte) = bytes.next() {
+                if in_quotes {
+                    if Some(byte) == self.options.quote {
+                        if bytes.peek() == self.options.quote.as_ref() {
+                            field.push(byte);
+                            bytes.next();
+                        } else {
+                            in_quotes = false;
+                        }
+                    } else {
+                        field.push(byte);
+                    }
+                } else if Some(byte) == self.options.quote && field.is_empty() && !quoted {
+                    (quoted, in_quotes) = (true, true);
+                } else if byte == self.options.delimiter {
+                    fields.push(std::mem::take(&mut field));
+                    quoted = false;
+                } else if byte == b'\n' {
+                    if field.last() == Some(&b'\r') {
+                        field.pop();
+                    }
+                    if fields.is_empty() && field.is_empty() && !quoted {
+                        break;
+                    }
+                    fields.push(field);
+                    return Ok(Some(fields));
+                } else {
+                    field.push(byte);
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for CsvRecords {
+    type Item = io::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.read_record() {
+            Ok(record) => record?,
+            Err(e) => return Some(Err(e)),
+        };
+        let line_number = self.line_number;
+        Some(
+            record
+                .into_iter()
+                .map(|field| {
+                    String::from_utf8(field)
+                        .map_err(|_| invalid_data(format!("invalid UTF-8 on line {line_number}")))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Opens a plain or gzip-compressed delimited file for streaming, returning its column names, from the header row or
+/// `column_names`, and its records.
+pub fn read_csv(path: &Path, options: CsvOptions) -> io::Result<(Vec<String>, CsvRecords)> {
+    let column_names = options.column_names.clone();
+    let mut records = CsvRecords::new(open_text(path)?, options);
+    let columns = match column_names {
+        Some(columns) => columns,
+        None => records.next().transpose()?.unwrap_or_default(),
+    };
+    Ok((columns, records))
+}
+
+/// A transformation applied to the values of a column by `transform_csv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnTransform {
+    NormalizeDoi,
+    NormalizeOrcid,
+    NormalizeRor,
+    /// Parses a full name with `parse_name` into a struct of the `ParsedName` fields.
+    ParseName,
+    StripMarkup,
+}
+
+impl ColumnTransform {
+    /// Looks up a transform by the name of the function it applies, e.g. "normalize_doi".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normalize_doi" => Some(ColumnTransform::NormalizeDoi),
+            "normalize_orcid" => Some(ColumnTransform::NormalizeOrcid),
+            "normalize_ror" => Some(ColumnTransform::NormalizeRor),
+            "parse_name" => Some(ColumnTransform::ParseName),
+            "strip_markup" => Some(ColumnTransform::StripMarkup),
+            _ => None,
+        }
+    }
+
+    /// Applies the transform to a field, None when it is empty.
+    fn apply(self, value: Option<&str>) -> TransformedField {
+        match self {
+            ColumnTransform::NormalizeDoi => TransformedField::Text(normalize_doi(value)),
+            ColumnTransform::NormalizeOrcid => TransformedField::Text(normalize_orcid(value)),
+            ColumnTransform::NormalizeRor => TransformedField::Text(normalize_ror(value)),
+            ColumnTransform::ParseName => {
+                TransformedField::Name(value.map(|name| parse_name(None, None, Some(name), false)))
+            },
+            ColumnTransform::StripMarkup => TransformedField::Text(strip_markup(value, None)),
+        }
+    }
+}
+
+/// A field after its column's transform.
+enum TransformedField {
+    Text(Option<String>),
+    Name(Option<ParsedName>),
+}
+
+impl TransformedField {
+    /// Converts the field to JSON, a parsed name as an object of its fields.
+    fn into_json(self) -> Value {
+        match self {
+            TransformedField::Text(text) => text.into(),
+            TransformedField::Name(Some(parsed)) => Value::Object(
+                PARSED_NAME_FIELDS
+                    .iter()
+                    .zip(parsed.into_fields())
+                    .map(|(field, value)| (field.to_string(), value.into()))
+                    .collect(),
+            ),
+            TransformedField::Name(None) => Value::Null,
+        }
+    }
+}
+
+/// The format `transform_csv` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Jsonl,
+    Parquet,
+}
+
+/// Writes transformed records as Parquet or JSONL.
+enum RecordWriter {
+    Jsonl(BufWriter<File>),
+    Parquet(Box<ArrowWriter<File>>, SchemaRef),
+}
+
+/// The Parquet schema of a transformed file: nullable strings, with `parse_name` columns as structs of nullable
+/// strings.
+fn output_schema(columns: &[String], transforms: &[Option<ColumnTransform>]) -> SchemaRef {
+    let fields: Vec<Field> = columns
+        .iter()
+        .zip(transforms)
+        .map(|(column, transform)| match transform {
+            Some(ColumnTransform::ParseName) => {
+                Field::new(column, DataType::Struct(parsed_name_fields()), true)
+            },
+            _ => Field::new(column, DataType::Utf8, true),
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+impl RecordWriter {
+    fn write(&mut self, records: Vec<Vec<TransformedField>>, columns: &[String]) -> Result<()> {
+        match self {
+            RecordWriter::Jsonl(writer) => {
+                for record in records {
+                    let object: Map<String, Value> = columns
+                        .iter()
+                        .cloned()
+                        .zip(record.into_iter().map(TransformedField::into_json))
+                        .collect();
+                    serde_json::to_writer(&mut *writer, &object).map_err(io::Error::from)?;
+                    writer.write_all(b"\n")?;
+                }
+            },
+            RecordWriter::Parquet(writer, schema) => {
+                let mut fields: Vec<Vec<TransformedField>> = columns
+                    .iter()
+                    .map(|_| Vec::with_capacity(records.len()))
+                    .collect();
+                for record in records {
+                    for (column, field) in fields.iter_mut().zip(record) {
+                        column.push(field);
+                    }
+                }
+                let arrays: Vec<ArrayRef> = fields.into_iter().map(column_array).collect();
+                writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+            },
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            RecordWriter::Jsonl(mut writer) => writer.flush()?,
+            RecordWriter::Parquet(writer, _) => {
+                writer.close()?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Builds the Arrow array of a column of transformed fields, with fields of empty names all null.
+fn column_array(fields: Vec<TransformedField>) -> ArrayRef {
+    if matches!(fields.first(), Some(TransformedField::Name(_))) {
+        let names = fields.into_iter().map(|field| match field {
+            TransformedField::Name(Some(parsed)) => parsed,
+            _ => parse_name(None, None, None, false),
+        });
+        return Arc::new(parsed_names_array(names));
+    }
+    let strings: StringArray = fields
+        .into_iter()
+        .map(|field| match field {
+            TransformedField::Text(text) => text,
+            TransformedField::Name(_) => None,
+        })
+        .collect();
+    Arc::new(strings)
+}
+
+/// Transforms a batch of records in parallel on the current rayon thread pool, preserving order. Empty fields are
+/// None.
+fn transform_records(
+    records: &[Vec<String>],
+    transforms: &[Option<ColumnTransform>],
+) -> Vec<Vec<TransformedField>> {
+    records
+        .par_iter()
+        .map(|record| {
+            record
+                .iter()
+                .zip(transforms)
+                .map(|(value, transform)| {
+                    let value = Some(value.as_str()).filter(|value| !value.is_empty());
+                    match transform {
+                        Some(transform) => transform.apply(value),
+                        None => TransformedField::Text(value.map(str::to_string)),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Stream-transforms a plain or gzip-compressed delimited file into Parquet or JSONL, `WRITE_BATCH_SIZE` records at a
+/// time, applying each column's transform from `transforms` and keeping other columns as strings. Empty fields are
+/// null, and `parse_name` columns become structs of the `ParsedName` fields. `progress` is called with the number of
+/// records written so far after each batch, and stops the transformation when it returns an error. Returns the number
+/// of records written. Fails with `InvalidInput` when a transformed column isn't in the file and `InvalidData` for
+/// records with the wrong number of fields, unterminated quotes or invalid UTF-8.
+pub fn transform_csv(
+    input: &Path,
+    output: &Path,
+    transforms: &HashMap<String, ColumnTransform>,
+    format: OutputFormat,
+    csv_options: CsvOptions,
+    write_options: &ParquetWriteOptions,
+    mut progress: impl FnMut(usize) -> io::Result<()>,
+) -> Result<usize> {
+    let (columns, records) = read_csv(input, csv_options)?;
+    if let Some(missing) = transforms.keys().find(|column| !columns.contains(column)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("column not found: {missing}"),
+        )
+        .into());
+    }
+    let column_transforms: Vec<Option<ColumnTransform>> = columns
+        .iter()
+        .map(|column| transforms.get(column).copied())
+        .collect();
+
+    let file = File::create(output)?;
+    let mut writer = match format {
+        OutputFormat::Jsonl => RecordWriter::Jsonl(BufWriter::new(file)),
+        OutputFormat::Parquet => {
+            let schema = output_schema(&columns, &column_transforms);
+            let properties = writer_properties(write_options)?;
+            RecordWriter::Parquet(
+                Box::new(ArrowWriter::try_new(
+                    file,
+                    schema.clone(),
+                    Some(properties),
+                )?),
+                schema,
+            )
+        },
+    };
+
+    let mut rows = 0;
+    let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+    let mut records = records.peekable();
+    while let Some(record) = records.next() {
+        let record = record?;
+        if record.len() != columns.len() {
+            return Err(invalid_data(format!(
+                "record {} has {} fields, expected {}",
+                rows + batch.len() + 1,
+                record.len(),
+                columns.len()
+            ))
+            .into());
+        }
+        batch.push(record);
+        if batch.len() == WRITE_BATCH_SIZE || records.peek().is_none() {
+            writer.write(transform_records(&batch, &column_transforms), &columns)?;
+            rows += batch.len();
+            batch.clear();
+            progress(rows)?;
+        }
+    }
+    writer.finish()?;
+    Ok(rows)
+}
@@ -0,0 +1,183 @@
+use crate::core::{normalize_doi, normalize_title, tokenize};
+use crate::minhash::{lsh_candidate_pairs, minhash_signatures};
+use crate::similarity::token_sort_ratio;
+use std::collections::{HashMap, HashSet};
+
+/// Number of values in the MinHash signatures of titles that fuzzy title comparisons are blocked by.
+const TITLE_SIGNATURE_SIZE: usize = 64;
+
+/// Number of LSH bands the title signatures are split into. Titles are compared when any band of two values is
+/// equal, which catches nearly every pair of titles sharing half their character shingles.
+const TITLE_SIGNATURE_BANDS: usize = 32;
+
+/// The fields of a normalized work used to find its duplicates and choose a cluster's representative.
+#[derive(Debug, Clone, Default)]
+pub struct DedupeRecord {
+    pub doi: Option<String>,
+    pub title: Option<String>,
+    pub year: Option<i32>,
+    pub source: Option<String>,
+    /// Number of populated fields, preferring fuller records as representatives.
+    pub completeness: usize,
+}
+
+/// Duplicate clusters of a list of works.
+#[derive(Debug, Clone, Default)]
+pub struct DedupeClusters {
+    /// Cluster ID of each work, in input order. Clusters are numbered in order of their first work.
+    pub cluster_ids: Vec<usize>,
+    /// Index of the preferred work of each cluster, by cluster ID.
+    pub representatives: Vec<usize>,
+}
+
+/// Disjoint-set forest with path halving and union by size.
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+    }
+}
+
+/// Unions every group of works sharing a key with its first work.
+fn union_by_key<K: std::hash::Hash + Eq>(
+    sets: &mut UnionFind,
+    keys: impl Iterator<Item = (usize, K)>,
+) {
+    let mut first: HashMap<K, usize> = HashMap::new();
+    for (i, key) in keys {
+        match first.get(&key) {
+            Some(&j) => sets.union(i, j),
+            None => {
+                first.insert(key, i);
+            },
+        }
+    }
+}
+
+/// Clusters duplicate works across sources. Works are duplicates when their normalized DOIs are equal, when their
+/// normalized titles and publication years are equal, or when they were published in the same year and their
+/// normalized titles have a `token_sort_ratio` of at least `title_threshold`; duplicates are clustered
+/// transitively. Rather than comparing every pair of titles in a year, only titles whose MinHash signatures of their
+/// sorted tokens share an LSH band (see `lsh_candidate_pairs`) are compared. Each cluster's representative is the work
+/// from the source listed earliest in `source_priority` (unlisted sources last), then the most complete work, then the
+/// first.
+pub fn dedupe_works(
+    records: &[DedupeRecord],
+    title_threshold: f64,
+    source_priority: &[String],
+) -> DedupeClusters {
+    let mut sets = UnionFind::new(records.len());
+
+    union_by_key(
+        &mut sets,
+        records
+            .iter()
+            .enumerate()
+            .filter_map(|(i, record)| Some((i, normalize_doi(record.doi.as_deref())?))),
+    );
+
+    let titles: Vec<Option<String>> = records
+        .iter()
+        .map(|record| normalize_title(record.title.as_deref(), true, true, None))
+        .collect();
+    union_by_key(
+        &mut sets,
+        records
+            .iter()
+            .enumerate()
+            .filter_map(|(i, record)| Some((i, (titles[i].clone()?, record.year?)))),
+    );
+
+    // Fuzzy titles are only compared within a publication year block. Works with equal titles and years are already
+    // clustered, so only the first of them is compared
+    let mut seen: HashSet<(&str, i32)> = HashSet::new();
+    let mut years: HashMap<i32, Vec<usize>> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        if let (Some(year), Some(title)) = (record.year, titles[i].as_deref()) {
+            if seen.insert((title, year)) {
+                years.entry(year).or_default().push(i);
+            }
+        }
+    }
+    for block in years.values() {
+        let sorted_titles: Vec<Option<String>> = block
+            .iter()
+            .map(|&i| {
+                let mut tokens = tokenize(titles[i].as_deref().unwrap());
+                tokens.sort_unstable();
+                Some(tokens.join(" "))
+            })
+            .collect();
+        let signatures = minhash_signatures(&sorted_titles, TITLE_SIGNATURE_SIZE);
+        for (a, b) in lsh_candidate_pairs(&signatures, TITLE_SIGNATURE_BANDS) {
+            let (i, j) = (block[a], block[b]);
+            if sets.find(i) == sets.find(j) {
+                continue;
+            }
+            let (a, b) = (titles[i].as_deref().unwrap(), titles[j].as_deref().unwrap());
+            if token_sort_ratio(a, b) >= title_threshold {
+                sets.union(i, j);
+            }
+        }
+    }
+
+    let priority = |record: &DedupeRecord| {
+        record
+            .source
+            .as_ref()
+            .and_then(|source| {
+                source_priority
+                    .iter()
+                    .position(|s| s.eq_ignore_ascii_case(source))
+            })
+            .unwrap_or(source_priority.len())
+    };
+
+    let mut clusters = DedupeClusters::default();
+    let mut cluster_of_root: HashMap<usize, usize> = HashMap::new();
+    for (i, record) in records.iter().enumerate() {
+        let root = sets.find(i);
+        let next_id = cluster_of_root.len();
+        let cluster = *cluster_of_root.entry(root).or_insert(next_id);
+        clusters.cluster_ids.push(cluster);
+
+        if cluster == clusters.representatives.len() {
+            clusters.representatives.push(i);
+            continue;
+        }
+        let best = &records[clusters.representatives[cluster]];
+        let better = (priority(record), std::cmp::Reverse(record.completeness))
+            < (priority(best), std::cmp::Reverse(best.completeness));
+        if better {
+            clusters.representatives[cluster] = i;
+        }
+    }
+    clusters
+}
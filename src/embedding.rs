@@ -0,0 +1,110 @@
+use crate::core::{
+    normalize_text, revert_inverted_index, strip_markup_with_options, tokenize, truncate_to_tokens,
+    MarkupOptions, MathMl, NormalizationForm,
+};
+use rayon::prelude::*;
+
+/// A work's abstract, either as text (possibly with JATS or HTML markup) or as a JSON-serialized OpenAlex inverted
+/// index.
+#[derive(Debug, Clone)]
+pub enum AbstractSource {
+    Text(String),
+    InvertedIndex(Vec<u8>),
+}
+
+/// Options for `prepare_embedding_text`.
+#[derive(Debug, Clone)]
+pub struct EmbeddingTextOptions {
+    pub markup: MarkupOptions,
+    pub form: NormalizationForm,
+    pub fold_diacritics: bool,
+    pub case_fold: bool,
+    /// Maximum number of words (as counted by `tokenize`, not model tokens) in the prepared text, if set.
+    pub max_tokens: Option<usize>,
+    /// Joins the title and abstract.
+    pub separator: String,
+}
+
+impl Default for EmbeddingTextOptions {
+    fn default() -> Self {
+        Self {
+            markup: MarkupOptions {
+                decode_entities: true,
+                jats: true,
+                mathml: MathMl::Placeholder,
+                null_rules: None,
+            },
+            form: NormalizationForm::Nfc,
+            fold_diacritics: false,
+            case_fold: false,
+            max_tokens: Some(256),
+            separator: "\n".to_string(),
+        }
+    }
+}
+
+/// Strips markup from a field, normalizes its Unicode and collapses its whitespace.
+fn clean_field(text: Option<&str>, options: &EmbeddingTextOptions) -> Option<String> {
+    let stripped = strip_markup_with_options(text, None, &options.markup)?;
+    let normalized = normalize_text(
+        &stripped,
+        options.form,
+        options.fold_diacritics,
+        options.case_fold,
+    );
+    let collapsed = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!collapsed.is_empty()).then_some(collapsed)
+}
+
+/// Prepares a work's title and abstract for an embedding model in one pass: reverts an inverted index abstract,
+/// strips markup, normalizes Unicode and whitespace, then joins the title and abstract with the separator. The
+/// abstract is truncated with `truncate_to_tokens` to fit within `max_tokens` after the title, so the title is always
+/// kept whole unless it alone exceeds the limit. Returns None when neither field has any text.
+pub fn prepare_embedding_text(
+    title: Option<&str>,
+    abstract_source: Option<&AbstractSource>,
+    options: &EmbeddingTextOptions,
+) -> Option<String> {
+    let abstract_text = match abstract_source {
+        Some(AbstractSource::Text(text)) => Some(text.clone()),
+        Some(AbstractSource::InvertedIndex(bytes)) => revert_inverted_index(Some(bytes), None),
+        None => None,
+    };
+    let mut title = clean_field(title, options);
+    let mut abstract_text = clean_field(abstract_text.as_deref(), options);
+
+    if let Some(max_tokens) = options.max_tokens {
+        let title_tokens = title.as_deref().map_or(0, |title| tokenize(title).len());
+        if title_tokens >= max_tokens {
+            title = title.map(|title| truncate_to_tokens(&title, max_tokens));
+            abstract_text = None;
+        } else {
+            abstract_text = abstract_text
+                .map(|text| truncate_to_tokens(&text, max_tokens - title_tokens))
+                .filter(|text| !text.is_empty());
+        }
+    }
+
+    match (title, abstract_text) {
+        (Some(title), Some(abstract_text)) => {
+            Some(format!("{title}{}{abstract_text}", options.separator))
+        },
+        (title, abstract_text) => title.or(abstract_text),
+    }
+}
+
+/// Prepares many works' embedding texts with `prepare_embedding_text` in parallel on the current rayon thread pool,
+/// preserving order.
+pub fn prepare_embedding_text_many(
+    titles: &[Option<String>],
+    abstracts: &[Option<AbstractSource>],
+    options: &EmbeddingTextOptions,
+) -> Vec<Option<String>> {
+    titles
+        .par_iter()
+        .zip(abstracts.par_iter())
+        .map(|(title, abstract_source)| {
+            prepare_embedding_text(title.as_deref(), abstract_source.as_ref(), options)
+        })
+        .collect()
+}
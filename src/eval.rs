@@ -0,0 +1,318 @@
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Ranking metrics averaged over all queries, and for each query sorted by query ID. Metrics are named like ranx's,
+/// e.g. "ndcg@10", in order of `k` and then metric name.
+#[derive(Debug, Clone, Default)]
+pub struct RankingMetrics {
+    pub mean: Vec<(String, f64)>,
+    pub per_query: Vec<(String, Vec<(String, f64)>)>,
+}
+
+/// Computes MAP, MRR, NDCG, precision and recall at each cutoff for one query's ranked results. Documents with a
+/// relevance above zero are relevant; NDCG uses the relevance as the gain with a `log2(rank + 1)` discount.
+fn query_metrics(
+    relevance: &HashMap<String, f64>,
+    ranked: &[String],
+    ks: &[usize],
+) -> Vec<(String, f64)> {
+    let num_relevant = relevance.values().filter(|rel| **rel > 0.0).count() as f64;
+    let mut ideal: Vec<f64> = relevance
+        .values()
+        .copied()
+        .filter(|rel| *rel > 0.0)
+        .collect();
+    ideal.sort_by(|a, b| b.total_cmp(a));
+
+    // Duplicate results only count at their first rank
+    let mut seen = HashSet::new();
+    let gains: Vec<f64> = ranked
+        .iter()
+        .map(|doc| {
+            let rel = relevance.get(doc).copied().unwrap_or(0.0).max(0.0);
+            if seen.insert(doc) {
+                rel
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let mut metrics = Vec::with_capacity(ks.len() * 5);
+    for &k in ks {
+        let top = &gains[..k.min(gains.len())];
+        let mut hits = 0.0;
+        let mut precision_sum = 0.0;
+        let mut reciprocal_rank = 0.0;
+        let mut dcg = 0.0;
+        for (i, &gain) in top.iter().enumerate() {
+            let rank = (i + 1) as f64;
+            if gain > 0.0 {
+                hits += 1.0;
+                precision_sum += hits / rank;
+                if reciprocal_rank == 0.0 {
+                    reciprocal_rank = 1.0 / rank;
+                }
+                dcg += gain / (rank + 1.0).log2();
+            }
+        }
+        let idcg: f64 = ideal
+            .iter()
+            .take(k)
+            .enumerate()
+            .map(|(i, gain)| gain / ((i + 2) as f64).log2())
+            .sum();
+
+        let ratio = |a: f64, b: f64| if b > 0.0 { a / b } else { 0.0 };
+        metrics.push((format!("map@{k}"), ratio(precision_sum, num_relevant)));
+        metrics.push((format!("mrr@{k}"), reciprocal_rank));
+        metrics.push((format!("ndcg@{k}"), ratio(dcg, idcg)));
+        metrics.push((format!("precision@{k}"), hits / k as f64));
+        metrics.push((format!("recall@{k}"), ratio(hits, num_relevant)));
+    }
+    metrics
+}
+
+/// Computes precision@k, recall@k, MRR, MAP and NDCG for each query in the ground truth (query IDs mapped to
+/// document relevance judgments) against ranked results (query IDs mapped to document IDs, best first), in parallel
+/// on the current rayon thread pool, along with their means over all queries. Queries missing from the results score
+/// zero, as with ranx's `make_comparable`, and results for queries without ground truth are ignored.
+pub fn ranking_metrics(
+    ground_truth: &HashMap<String, HashMap<String, f64>>,
+    results: &HashMap<String, Vec<String>>,
+    ks: &[usize],
+) -> RankingMetrics {
+    let mut queries: Vec<&String> = ground_truth.keys().collect();
+    queries.sort();
+
+    let per_query: Vec<(String, Vec<(String, f64)>)> = queries
+        .into_par_iter()
+        .map(|query| {
+            let ranked = results.get(query).map(Vec::as_slice).unwrap_or_default();
+            (
+                query.clone(),
+                query_metrics(&ground_truth[query], ranked, ks),
+            )
+        })
+        .collect();
+
+    // Without any queries every mean is zero
+    let mut mean = query_metrics(&HashMap::new(), &[], ks);
+    for (i, (_, value)) in mean.iter_mut().enumerate() {
+        if !per_query.is_empty() {
+            let total: f64 = per_query.iter().map(|(_, metrics)| metrics[i].1).sum();
+            *value = total / per_query.len() as f64;
+        }
+    }
+    RankingMetrics { mean, per_query }
+}
+
+/// The precision and recall of accepting pairs scoring at or above each threshold, the distinct scores in descending
+/// order, as with scikit-learn's `precision_recall_curve`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrCurve {
+    pub thresholds: Vec<f64>,
+    pub precision: Vec<f64>,
+    pub recall: Vec<f64>,
+}
+
+/// Computes the precision–recall curve of scored pairs labelled as matches or not, sorting them in parallel on the
+/// current rayon thread pool. Recall is zero when no pair is a match.
+pub fn pr_curve(scores: &[f64], labels: &[bool]) -> PrCurve {
+    let mut pairs: Vec<(f64, bool)> = scores.iter().copied().zip(labels.iter().copied()).collect();
+    pairs.par_sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+    let num_matches = pairs.iter().filter(|(_, label)| *label).count() as f64;
+
+    let mut curve = PrCurve::default();
+    let mut true_positives = 0.0;
+    for (i, &(score, label)) in pairs.iter().enumerate() {
+        if label {
+            true_positives += 1.0;
+        }
+        // Pairs with equal scores are accepted together
+        if pairs.get(i + 1).is_some_and(|(next, _)| *next == score) {
+            continue;
+        }
+        curve.thresholds.push(score);
+        curve.precision.push(true_positives / (i + 1) as f64);
+        curve.recall.push(if num_matches > 0.0 {
+            true_positives / num_matches
+        } else {
+            0.0
+        });
+    }
+    curve
+}
+
+/// Chooses the threshold for accepting pairs that has the highest recall while keeping precision at or above
+/// `target_precision`, returning the threshold with its precision and recall, or None when no threshold reaches the
+/// target. Of thresholds with equal recall the highest is chosen.
+pub fn choose_threshold(
+    scores: &[f64],
+    labels: &[bool],
+    target_precision: f64,
+) -> Option<(f64, f64, f64)> {
+    let curve = pr_curve(scores, labels);
+    let mut best: Option<(f64, f64, f64)> = None;
+    for ((&threshold, &precision), &recall) in curve
+        .thresholds
+        .iter()
+        .zip(&curve.precision)
+        .zip(&curve.recall)
+    {
+        if precision >= target_precision && best.is_none_or(|(_, _, best)| recall > best) {
+            best = Some((threshold, precision, recall));
+        }
+    }
+    best
+}
+
+/// A label given to an item by an annotator, e.g. "ACCEPTED" or a graded relevance of 2. Numbers compare equal by
+/// value, so 1 and 1.0 are the same label.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    Text(String),
+    Number(f64),
+}
+
+/// How differences between labels are weighed by `krippendorff_alpha`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasurementLevel {
+    /// Labels are categories that either agree or don't.
+    Nominal,
+    /// Labels are numbers that disagree by the square of their difference.
+    Interval,
+}
+
+impl MeasurementLevel {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "nominal" => Some(MeasurementLevel::Nominal),
+            "interval" => Some(MeasurementLevel::Interval),
+            _ => None,
+        }
+    }
+}
+
+/// The index of a label among the distinct labels seen so far, adding it when new. Annotations are few enough that
+/// a linear search is faster than hashing.
+fn category_index(categories: &mut Vec<Annotation>, label: &Annotation) -> usize {
+    categories
+        .iter()
+        .position(|category| category == label)
+        .unwrap_or_else(|| {
+            categories.push(label.clone());
+            categories.len() - 1
+        })
+}
+
+/// Computes Cohen's kappa between two annotators' labels of the same items, the agreement beyond that expected by
+/// chance from each annotator's label frequencies. Items either annotator didn't label are skipped. Returns None
+/// when no item has both labels or when chance agreement is certain, i.e. both annotators gave every item the same
+/// single label.
+pub fn cohen_kappa(
+    labels_a: &[Option<Annotation>],
+    labels_b: &[Option<Annotation>],
+) -> Option<f64> {
+    let mut categories = Vec::new();
+    let pairs: Vec<(usize, usize)> = labels_a
+        .iter()
+        .zip(labels_b)
+        .filter_map(|(a, b)| Some((a.as_ref()?, b.as_ref()?)))
+        .map(|(a, b)| {
+            (
+                category_index(&mut categories, a),
+                category_index(&mut categories, b),
+            )
+        })
+        .collect();
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let mut counts_a = vec![0.0; categories.len()];
+    let mut counts_b = vec![0.0; categories.len()];
+    let mut agreements = 0.0;
+    for &(a, b) in &pairs {
+        counts_a[a] += 1.0;
+        counts_b[b] += 1.0;
+        if a == b {
+            agreements += 1.0;
+        }
+    }
+    let observed = agreements / n;
+    let expected: f64 = counts_a
+        .iter()
+        .zip(&counts_b)
+        .map(|(a, b)| (a / n) * (b / n))
+        .sum();
+    (expected < 1.0).then(|| (observed - expected) / (1.0 - expected))
+}
+
+/// Computes Krippendorff's alpha for labels of items by any number of annotators, given as one row per annotator and
+/// one column per item with None where an annotator didn't label an item. Items with fewer than two labels are
+/// skipped. Interval labels must be numbers, and a text label is an error. Returns None when fewer than two labels
+/// remain or when they can't disagree, i.e. every label is the same.
+pub fn krippendorff_alpha(
+    matrix: &[Vec<Option<Annotation>>],
+    level: MeasurementLevel,
+) -> Result<Option<f64>, String> {
+    let mut categories = Vec::new();
+    let num_items = matrix.iter().map(Vec::len).max().unwrap_or(0);
+    let items: Vec<Vec<usize>> = (0..num_items)
+        .map(|item| {
+            matrix
+                .iter()
+                .filter_map(|row| row.get(item)?.as_ref())
+                .map(|label| category_index(&mut categories, label))
+                .collect()
+        })
+        .collect();
+
+    let values = match level {
+        MeasurementLevel::Nominal => Vec::new(),
+        MeasurementLevel::Interval => categories
+            .iter()
+            .map(|category| match category {
+                Annotation::Number(value) => Ok(*value),
+                Annotation::Text(text) => Err(format!(
+                    "interval alpha requires numeric labels, got {text:?}"
+                )),
+            })
+            .collect::<Result<Vec<f64>, String>>()?,
+    };
+    let distance = |a: usize, b: usize| match level {
+        MeasurementLevel::Nominal => f64::from(u8::from(a != b)),
+        MeasurementLevel::Interval => (values[a] - values[b]).powi(2),
+    };
+
+    // The coincidence matrix, counting each ordered pair of labels of an item weighted by the item's other labels
+    let mut coincidences = vec![vec![0.0; categories.len()]; categories.len()];
+    for labels in items.iter().filter(|labels| labels.len() >= 2) {
+        let weight = 1.0 / (labels.len() - 1) as f64;
+        for (i, &a) in labels.iter().enumerate() {
+            for (j, &b) in labels.iter().enumerate() {
+                if i != j {
+                    coincidences[a][b] += weight;
+                }
+            }
+        }
+    }
+    let totals: Vec<f64> = coincidences.iter().map(|row| row.iter().sum()).collect();
+    let n: f64 = totals.iter().sum();
+    if n < 2.0 {
+        return Ok(None);
+    }
+
+    let mut observed = 0.0;
+    let mut expected = 0.0;
+    for a in 0..categories.len() {
+        for b in 0..categories.len() {
+            let distance = distance(a, b);
+            observed += coincidences[a][b] * distance;
+            expected += totals[a] * totals[b] * distance;
+        }
+    }
+    Ok((expected > 0.0).then(|| 1.0 - (n - 1.0) * observed / expected))
+}
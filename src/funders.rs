@@ -0,0 +1,175 @@
+use crate::core::decode_html_entities;
+use crate::settings::{self, Normalizer};
+use crate::similarity::token_sort_ratio;
+use deunicode::deunicode;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Acronyms DMPs commonly record in place of a funder's name, with the names they expand to.
+pub(crate) static FUNDER_ACRONYMS: &[(&str, &str)] = &[
+    ("AHRC", "Arts and Humanities Research Council"),
+    ("ANR", "Agence Nationale de la Recherche"),
+    (
+        "BBSRC",
+        "Biotechnology and Biological Sciences Research Council",
+    ),
+    ("CIHR", "Canadian Institutes of Health Research"),
+    ("DARPA", "Defense Advanced Research Projects Agency"),
+    ("DFG", "Deutsche Forschungsgemeinschaft"),
+    ("DOD", "Department of Defense"),
+    ("DOE", "Department of Energy"),
+    ("EPA", "Environmental Protection Agency"),
+    (
+        "EPSRC",
+        "Engineering and Physical Sciences Research Council",
+    ),
+    ("ERC", "European Research Council"),
+    ("ESRC", "Economic and Social Research Council"),
+    ("HHMI", "Howard Hughes Medical Institute"),
+    ("IMLS", "Institute of Museum and Library Services"),
+    ("JSPS", "Japan Society for the Promotion of Science"),
+    ("MRC", "Medical Research Council"),
+    ("NASA", "National Aeronautics and Space Administration"),
+    ("NEH", "National Endowment for the Humanities"),
+    ("NERC", "Natural Environment Research Council"),
+    ("NHMRC", "National Health and Medical Research Council"),
+    ("NIH", "National Institutes of Health"),
+    ("NIST", "National Institute of Standards and Technology"),
+    ("NOAA", "National Oceanic and Atmospheric Administration"),
+    (
+        "NSERC",
+        "Natural Sciences and Engineering Research Council of Canada",
+    ),
+    ("NSF", "National Science Foundation"),
+    ("NSFC", "National Natural Science Foundation of China"),
+    (
+        "NWO",
+        "Nederlandse Organisatie voor Wetenschappelijk Onderzoek",
+    ),
+    ("SNSF", "Swiss National Science Foundation"),
+    ("SSHRC", "Social Sciences and Humanities Research Council"),
+    ("STFC", "Science and Technology Facilities Council"),
+    ("UKRI", "UK Research and Innovation"),
+    ("USDA", "United States Department of Agriculture"),
+    ("USGS", "United States Geological Survey"),
+];
+
+/// Words that carry no weight when comparing funder names, in English and the languages common in funder names.
+static FUNDER_STOP_WORDS: &[&str] = &[
+    "and", "at", "de", "der", "des", "die", "du", "for", "fur", "in", "la", "le", "of", "on",
+    "the", "und",
+];
+
+/// Parenthetical asides, e.g. the acronym in "National Science Foundation (NSF)".
+static PARENTHETICAL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\([^()]*\)").unwrap());
+
+/// Acronyms written with periods, e.g. "N.S.F." or "U.S.".
+static DOTTED_ACRONYM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:[A-Za-z]\.){2,}").unwrap());
+
+/// Expands a name that is only a known funder acronym, e.g. "NSF" -> "National Science Foundation".
+fn expand_funder_acronym(name: &str) -> Option<&'static str> {
+    if !name.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    FUNDER_ACRONYMS
+        .iter()
+        .find(|(acronym, _)| *acronym == name)
+        .map(|(_, expansion)| *expansion)
+}
+
+/// Normalizes a free-text funder name for matching: HTML entities are decoded, parenthetical asides dropped, dotted
+/// acronyms collapsed, a name that is only a known acronym expanded, and the result transliterated to ASCII,
+/// lowercased, stripped of punctuation and stop words, and whitespace-collapsed. For example, "NSF", "N.S.F." and
+/// "The National Science Foundation (NSF)" all normalize to "national science foundation". Returns None when no
+/// words are left.
+pub fn normalize_funder_name(name: Option<&str>) -> Option<String> {
+    settings::cached(Normalizer::FunderName, name?, |name| {
+        normalize_funder_name_uncached(name)
+    })
+}
+
+fn normalize_funder_name_uncached(name: &str) -> Option<String> {
+    let decoded = decode_html_entities(name.trim());
+
+    // Drop asides unless the name is only an aside
+    let without_asides = PARENTHETICAL_RE.replace_all(&decoded, " ");
+    let name = if without_asides.trim().is_empty() {
+        decoded.replace(['(', ')'], " ")
+    } else {
+        without_asides.into_owned()
+    };
+
+    let undotted =
+        DOTTED_ACRONYM_RE.replace_all(&name, |caps: &regex::Captures| caps[0].replace('.', ""));
+    let trimmed = undotted.trim();
+    let expanded = expand_funder_acronym(trimmed).unwrap_or(trimmed);
+
+    let folded = deunicode(expanded).to_lowercase().replace('&', " and ");
+    let words: Vec<&str> = folded
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty() && !FUNDER_STOP_WORDS.contains(word))
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// A lookup table of funder IDs and their names (e.g. preferred and alternative labels from the Crossref Funder
+/// Registry or ROR), normalized once up front so that many free-text names can be matched against it.
+pub struct FunderRegistry {
+    /// Funder ID and normalized name pairs, ordered by funder ID.
+    names: Vec<(String, String)>,
+    /// Normalized names mapped to the index of their first entry in `names`.
+    exact: HashMap<String, usize>,
+}
+
+impl FunderRegistry {
+    /// Builds a registry from funder IDs and their names. Names that normalize to nothing are skipped.
+    pub fn new(entries: impl IntoIterator<Item = (String, Vec<String>)>) -> Self {
+        let mut entries: Vec<(String, Vec<String>)> = entries.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut names = Vec::new();
+        let mut exact = HashMap::new();
+        for (id, funder_names) in entries {
+            for name in funder_names {
+                if let Some(normalized) = normalize_funder_name(Some(&name)) {
+                    exact.entry(normalized.clone()).or_insert(names.len());
+                    names.push((id.clone(), normalized));
+                }
+            }
+        }
+        Self { names, exact }
+    }
+
+    /// Returns the number of funder names in the registry.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+/// Matches a free-text funder name against a registry, returning the best matching funder ID and its score (0–1) when
+/// the score is at least `threshold`. Names that normalize to the same name as a registry entry score 1.0; otherwise
+/// normalized names are compared with `token_sort_ratio`. Ties go to the smallest funder ID, so results are
+/// deterministic.
+pub fn match_funder(
+    name: Option<&str>,
+    registry: &FunderRegistry,
+    threshold: f64,
+) -> Option<(String, f64)> {
+    let normalized = normalize_funder_name(name)?;
+
+    if let Some(&index) = registry.exact.get(&normalized) {
+        return Some((registry.names[index].0.clone(), 1.0));
+    }
+
+    let mut best: Option<(&str, f64)> = None;
+    for (id, candidate) in &registry.names {
+        let score = token_sort_ratio(&normalized, candidate);
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((id, score));
+        }
+    }
+    best.filter(|(_, score)| *score >= threshold)
+        .map(|(id, score)| (id.to_string(), score))
+}
@@ -0,0 +1,189 @@
+use std::collections::{HashMap, HashSet};
+
+/// The constant added to ranks by reciprocal rank fusion, as in Cormack et al. (2009) and OpenSearch's hybrid search.
+pub const DEFAULT_RRF_K: f64 = 60.0;
+
+/// How `fuse_rankings` combines rankings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FusionMethod {
+    /// Reciprocal rank fusion: the sum of `1 / (k + rank)` over the rankings, ignoring scores.
+    Rrf,
+    /// The sum of the document's scores over the rankings.
+    CombSum,
+    /// The sum of the document's scores multiplied by the number of rankings it appears in.
+    CombMnz,
+}
+
+impl FusionMethod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rrf" => Some(FusionMethod::Rrf),
+            "combsum" => Some(FusionMethod::CombSum),
+            "combmnz" => Some(FusionMethod::CombMnz),
+            _ => None,
+        }
+    }
+}
+
+/// How the scores of each ranking are put on a common scale before they are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreNormalization {
+    /// Scales scores to 0–1 between the ranking's lowest and highest score. Scores are all 1.0 when they are equal.
+    MinMax,
+    /// Standardizes scores by the ranking's mean and standard deviation. Scores are all 0.0 when they are equal.
+    ZScore,
+}
+
+impl ScoreNormalization {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "minmax" => Some(ScoreNormalization::MinMax),
+            "zscore" => Some(ScoreNormalization::ZScore),
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes a ranking's scores, see `ScoreNormalization`.
+pub fn normalize_scores(scores: &[f64], normalization: ScoreNormalization) -> Vec<f64> {
+    if scores.is_empty() {
+        return Vec::new();
+    }
+    match normalization {
+        ScoreNormalization::MinMax => {
+            let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            scores
+                .iter()
+                .map(|score| {
+                    if max > min {
+                        (score - min) / (max - min)
+                    } else {
+                        1.0
+                    }
+                })
+                .collect()
+        },
+        ScoreNormalization::ZScore => {
+            let n = scores.len() as f64;
+            let mean = scores.iter().sum::<f64>() / n;
+            let std = (scores
+                .iter()
+                .map(|score| (score - mean).powi(2))
+                .sum::<f64>()
+                / n)
+                .sqrt();
+            scores
+                .iter()
+                .map(|score| if std > 0.0 { (score - mean) / std } else { 0.0 })
+                .collect()
+        },
+    }
+}
+
+/// The fused scores of documents, with the number of rankings each appears in, in order of first appearance.
+#[derive(Default)]
+struct Fused<'a> {
+    order: Vec<&'a str>,
+    scores: HashMap<&'a str, (f64, usize)>,
+}
+
+impl<'a> Fused<'a> {
+    /// Adds one ranking's contributions, counting documents repeated within it at their first occurrence.
+    fn add(&mut self, contributions: impl IntoIterator<Item = (&'a str, f64)>) {
+        let mut seen = HashSet::new();
+        for (doc, contribution) in contributions {
+            if !seen.insert(doc) {
+                continue;
+            }
+            let (score, count) = self.scores.entry(doc).or_insert_with(|| {
+                self.order.push(doc);
+                (0.0, 0)
+            });
+            *score += contribution;
+            *count += 1;
+        }
+    }
+
+    /// The documents with their fused scores, best first, ties keeping the order documents first appear in.
+    fn ranked(self, multiply_by_count: bool) -> Vec<(String, f64)> {
+        let mut fused: Vec<(String, f64)> = self
+            .order
+            .into_iter()
+            .map(|doc| {
+                let (score, count) = self.scores[doc];
+                let score = if multiply_by_count {
+                    score * count as f64
+                } else {
+                    score
+                };
+                (doc.to_string(), score)
+            })
+            .collect();
+        fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+        fused
+    }
+}
+
+/// The reciprocal rank fusion contributions of a ranking's documents, `weight / (k + rank)`, ranking documents
+/// repeated within it at their first occurrence.
+fn rrf_contributions<'a>(
+    docs: impl IntoIterator<Item = &'a str>,
+    weight: f64,
+    k: f64,
+) -> Vec<(&'a str, f64)> {
+    let mut seen = HashSet::new();
+    docs.into_iter()
+        .filter(|doc| seen.insert(*doc))
+        .enumerate()
+        .map(|(i, doc)| (doc, weight / (k + (i + 1) as f64)))
+        .collect()
+}
+
+/// Fuses rankings of documents, each best first, with reciprocal rank fusion: each document scores the sum over the
+/// rankings it appears in of `1 / (k + rank)`, ranks counting from 1, so that documents ranked highly by several
+/// rankings come first whatever the scales of their original scores. A document repeated within a ranking counts at
+/// its first rank. Returns the documents with their fused scores, best first, ties keeping the order documents first
+/// appear in.
+pub fn reciprocal_rank_fusion(rankings: &[Vec<String>], k: f64) -> Vec<(String, f64)> {
+    let mut fused = Fused::default();
+    for ranking in rankings {
+        fused.add(rrf_contributions(
+            ranking.iter().map(String::as_str),
+            1.0,
+            k,
+        ));
+    }
+    fused.ranked(false)
+}
+
+/// Fuses scored rankings of documents, each best first, e.g. the lexical, k-NN and feature-based candidates of a DMP,
+/// into one ranking with `method`. Each ranking's contributions are multiplied by its weight, 1.0 when `weights` is
+/// shorter, and for CombSUM and CombMNZ its scores are first normalized with `normalization`, if any. A document
+/// repeated within a ranking counts at its first occurrence. Returns the documents with their fused scores, best
+/// first, ties keeping the order documents first appear in.
+pub fn fuse_rankings(
+    rankings: &[Vec<(String, f64)>],
+    method: FusionMethod,
+    normalization: Option<ScoreNormalization>,
+    weights: &[f64],
+    rrf_k: f64,
+) -> Vec<(String, f64)> {
+    let mut fused = Fused::default();
+    for (i, ranking) in rankings.iter().enumerate() {
+        let weight = weights.get(i).copied().unwrap_or(1.0);
+        let docs = ranking.iter().map(|(doc, _)| doc.as_str());
+        match method {
+            FusionMethod::Rrf => fused.add(rrf_contributions(docs, weight, rrf_k)),
+            FusionMethod::CombSum | FusionMethod::CombMnz => {
+                let scores: Vec<f64> = ranking.iter().map(|(_, score)| *score).collect();
+                let scores = match normalization {
+                    Some(normalization) => normalize_scores(&scores, normalization),
+                    None => scores,
+                };
+                fused.add(docs.zip(scores).map(|(doc, score)| (doc, weight * score)));
+            },
+        }
+    }
+    fused.ranked(method == FusionMethod::CombMnz)
+}
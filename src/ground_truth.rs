@@ -0,0 +1,352 @@
+use crate::core::{normalize_doi, open_text, ParseError};
+use crate::csv::{CsvOptions, CsvRecords};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+
+/// The DOI prefix of DMP Tool DMP IDs, which bare IDs such as "D17598" are registered under.
+pub const DMP_DOI_PREFIX: &str = "10.48321";
+
+/// The columns of a ground-truth file, in the order they are written.
+pub const GROUND_TRUTH_COLUMNS: [&str; 4] = ["dmp_doi", "work_doi", "status", "reviewer"];
+
+/// The columns a ground-truth file must have, as the reviewer is optional.
+const REQUIRED_COLUMNS: [&str; 3] = ["dmp_doi", "work_doi", "status"];
+
+/// The review status of a DMP–work pair, as in the DMP Tool's related works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Label {
+    Accepted,
+    Rejected,
+    Pending,
+}
+
+impl Label {
+    pub const ALL: [Label; 3] = [Label::Accepted, Label::Rejected, Label::Pending];
+
+    /// Looks up a label by name, ignoring case and surrounding whitespace.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|label| label.as_str().eq_ignore_ascii_case(name))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Label::Accepted => "ACCEPTED",
+            Label::Rejected => "REJECTED",
+            Label::Pending => "PENDING",
+        }
+    }
+
+    /// Whether a reviewer has judged the pair, i.e. it is accepted or rejected.
+    pub fn is_reviewed(self) -> bool {
+        self != Label::Pending
+    }
+}
+
+/// A labelled DMP–work pair of a ground-truth file, with normalized DOIs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GroundTruthRecord {
+    pub dmp_doi: String,
+    pub work_doi: String,
+    pub status: Label,
+    /// Who reviewed the pair, None when unknown.
+    pub reviewer: Option<String>,
+}
+
+/// A field of a record that failed validation, with a description of the problem.
+pub type FieldError = (&'static str, String);
+
+/// A field's trimmed value, or an error when it is missing or blank.
+fn present<'a>(field: &'static str, value: Option<&'a str>) -> Result<&'a str, FieldError> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or((field, format!("missing {field}")))
+}
+
+impl GroundTruthRecord {
+    /// Validates and normalizes the fields of a record: the DMP ID must be a DMP Tool DOI, the work DOI must be valid
+    /// and the status a known label. Blank reviewers are None, and when `require_reviewer` is set accepted and
+    /// rejected pairs must name theirs.
+    pub fn new(
+        dmp_doi: Option<&str>,
+        work_doi: Option<&str>,
+        status: Option<&str>,
+        reviewer: Option<&str>,
+        require_reviewer: bool,
+    ) -> Result<Self, FieldError> {
+        let dmp_id = present("dmp_doi", dmp_doi)?;
+        let dmp_doi = normalize_dmp_doi(Some(dmp_id)).ok_or_else(|| {
+            (
+                "dmp_doi",
+                format!("invalid DMP ID, expected a {DMP_DOI_PREFIX} DOI: {dmp_id}"),
+            )
+        })?;
+        let doi = present("work_doi", work_doi)?;
+        let work_doi =
+            normalize_doi(Some(doi)).ok_or_else(|| ("work_doi", format!("invalid DOI: {doi}")))?;
+        let name = present("status", status)?;
+        let status = Label::from_name(name).ok_or_else(|| {
+            (
+                "status",
+                format!("unknown status: {name}, expected ACCEPTED, REJECTED or PENDING"),
+            )
+        })?;
+        let reviewer = reviewer
+            .map(str::trim)
+            .filter(|reviewer| !reviewer.is_empty())
+            .map(str::to_string);
+        if require_reviewer && status.is_reviewed() && reviewer.is_none() {
+            return Err((
+                "reviewer",
+                format!("missing reviewer of {} pair", status.as_str()),
+            ));
+        }
+
+        Ok(Self {
+            dmp_doi,
+            work_doi,
+            status,
+            reviewer,
+        })
+    }
+
+    /// The record's fields as text, in the order of `GROUND_TRUTH_COLUMNS`.
+    fn fields(&self) -> [Option<&str>; 4] {
+        [
+            Some(&self.dmp_doi),
+            Some(&self.work_doi),
+            Some(self.status.as_str()),
+            self.reviewer.as_deref(),
+        ]
+    }
+}
+
+/// Normalizes a DMP ID to a lowercase DMP Tool DOI, accepting resolver URLs, "doi:" prefixes and bare IDs such as
+/// "D17598". Returns None for other DOIs and invalid IDs.
+pub fn normalize_dmp_doi(text: Option<&str>) -> Option<String> {
+    let text = text?.trim();
+    let bare = !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric());
+    let doi = if bare {
+        normalize_doi(Some(&format!("{DMP_DOI_PREFIX}/{text}")))
+    } else {
+        normalize_doi(Some(text))
+    }?;
+    doi.strip_prefix(DMP_DOI_PREFIX)
+        .is_some_and(|suffix| suffix.starts_with('/'))
+        .then_some(doi)
+}
+
+/// The formats ground-truth files are read and written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroundTruthFormat {
+    /// A header row naming the columns, then one pair per row.
+    Csv,
+    /// One JSON object per line, keyed by column name.
+    Jsonl,
+}
+
+impl GroundTruthFormat {
+    /// The format of a file from its extension, ".csv", ".jsonl" or ".ndjson", ignoring a trailing ".gz".
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        let name = name.strip_suffix(".gz").unwrap_or(&name);
+        match Path::new(name).extension()?.to_str()? {
+            "csv" => Some(GroundTruthFormat::Csv),
+            "jsonl" | "ndjson" => Some(GroundTruthFormat::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// The fields of a row of a ground-truth file before validation, in the order of `GROUND_TRUTH_COLUMNS`.
+type RawRow = [Option<String>; 4];
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn unknown_format(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "ground truth path must end in .csv, .jsonl or .ndjson, got {}",
+            path.display()
+        ),
+    )
+}
+
+/// Reads the rows of a CSV ground-truth file with their line numbers. A row with the wrong number of fields is an
+/// error rather than being read with its fields shifted.
+fn read_csv_rows(path: &Path) -> io::Result<Vec<(usize, Result<RawRow, ParseError>)>> {
+    let mut records = CsvRecords::new(open_text(path)?, CsvOptions::default());
+    let columns = records.next().transpose()?.unwrap_or_default();
+    let missing: Vec<&str> = REQUIRED_COLUMNS
+        .into_iter()
+        .filter(|column| !columns.iter().any(|c| c.trim() == *column))
+        .collect();
+    if !missing.is_empty() {
+        return Err(invalid_data(format!(
+            "ground truth file is missing columns: {}",
+            missing.join(", ")
+        )));
+    }
+    let indices =
+        GROUND_TRUTH_COLUMNS.map(|column| columns.iter().position(|c| c.trim() == column));
+
+    let mut rows = Vec::new();
+    while let Some(record) = records.next().transpose()? {
+        let line = records.line_number();
+        let row = if record.len() == columns.len() {
+            Ok(indices.map(|index| index.map(|index| record[index].clone())))
+        } else {
+            Err(ParseError {
+                row: line,
+                field: None,
+                message: format!("expected {} fields, got {}", columns.len(), record.len()),
+            })
+        };
+        rows.push((line, row));
+    }
+    Ok(rows)
+}
+
+/// Reads the rows of a JSONL ground-truth file with their line numbers, skipping blank lines.
+fn read_jsonl_rows(path: &Path) -> io::Result<Vec<(usize, Result<RawRow, ParseError>)>> {
+    let mut rows = Vec::new();
+    for (index, line) in open_text(path)?.split(b'\n').enumerate() {
+        let line = line?;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let row = index + 1;
+        let error = |field: Option<&str>, message: String| ParseError {
+            row,
+            field: field.map(str::to_string),
+            message,
+        };
+        let parsed = match serde_json::from_slice::<serde_json::Value>(&line) {
+            Ok(serde_json::Value::Object(mut object)) => {
+                let mut fields: RawRow = Default::default();
+                let mut result = Ok(());
+                for (field, column) in fields.iter_mut().zip(GROUND_TRUTH_COLUMNS) {
+                    match object.remove(column) {
+                        None | Some(serde_json::Value::Null) => {},
+                        Some(serde_json::Value::String(value)) => *field = Some(value),
+                        Some(_) => {
+                            result = Err(error(Some(column), format!("{column} must be a string")));
+                            break;
+                        },
+                    }
+                }
+                result.map(|_| fields)
+            },
+            Ok(_) => Err(error(None, "expected a JSON object".to_string())),
+            Err(e) => Err(error(None, format!("invalid json: {e}"))),
+        };
+        rows.push((row, parsed));
+    }
+    Ok(rows)
+}
+
+/// Reads and validates a plain or gzip-compressed ground-truth file of labelled DMP–work pairs in CSV or JSONL
+/// format, chosen by its extension, returning its valid records in file order and an error for each invalid row,
+/// numbered by line. A pair labelled more than once is an error on each line after the first, so that conflicting
+/// labels can't silently change metrics.
+pub fn load_ground_truth(
+    path: &Path,
+    require_reviewer: bool,
+) -> io::Result<(Vec<GroundTruthRecord>, Vec<ParseError>)> {
+    let rows = match GroundTruthFormat::from_path(path).ok_or_else(|| unknown_format(path))? {
+        GroundTruthFormat::Csv => read_csv_rows(path)?,
+        GroundTruthFormat::Jsonl => read_jsonl_rows(path)?,
+    };
+
+    let mut records = Vec::with_capacity(rows.len());
+    let mut errors = Vec::new();
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    for (line, row) in rows {
+        let record = row.and_then(|[dmp_doi, work_doi, status, reviewer]| {
+            GroundTruthRecord::new(
+                dmp_doi.as_deref(),
+                work_doi.as_deref(),
+                status.as_deref(),
+                reviewer.as_deref(),
+                require_reviewer,
+            )
+            .map_err(|(field, message)| ParseError {
+                row: line,
+                field: Some(field.to_string()),
+                message,
+            })
+        });
+        match record {
+            Ok(record) => {
+                let pair = (record.dmp_doi.clone(), record.work_doi.clone());
+                match seen.get(&pair) {
+                    Some(first) => errors.push(ParseError {
+                        row: line,
+                        field: None,
+                        message: format!("duplicate of the pair on line {first}"),
+                    }),
+                    None => {
+                        seen.insert(pair, line);
+                        records.push(record);
+                    },
+                }
+            },
+            Err(error) => errors.push(error),
+        }
+    }
+    Ok((records, errors))
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break, doubling any quotes inside.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes ground-truth records to a plain CSV or JSONL file, chosen by its extension, with the columns of
+/// `GROUND_TRUTH_COLUMNS`. Missing reviewers are empty in CSV and null in JSONL.
+pub fn save_ground_truth(path: &Path, records: &[GroundTruthRecord]) -> io::Result<()> {
+    let format = GroundTruthFormat::from_path(path)
+        .filter(|_| {
+            path.extension()
+                .is_none_or(|extension| !extension.eq_ignore_ascii_case("gz"))
+        })
+        .ok_or_else(|| unknown_format(path))?;
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        GroundTruthFormat::Csv => {
+            writeln!(writer, "{}", GROUND_TRUTH_COLUMNS.join(","))?;
+            for record in records {
+                let fields: Vec<String> = record
+                    .fields()
+                    .into_iter()
+                    .map(|field| csv_field(field.unwrap_or_default()))
+                    .collect();
+                writeln!(writer, "{}", fields.join(","))?;
+            }
+        },
+        GroundTruthFormat::Jsonl => {
+            for record in records {
+                let object: serde_json::Map<String, serde_json::Value> = GROUND_TRUTH_COLUMNS
+                    .into_iter()
+                    .zip(record.fields())
+                    .map(|(column, value)| (column.to_string(), value.into()))
+                    .collect();
+                serde_json::to_writer(&mut writer, &object)?;
+                writer.write_all(b"\n")?;
+            }
+        },
+    }
+    writer.flush()
+}
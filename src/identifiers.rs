@@ -0,0 +1,201 @@
+use crate::core::normalize_doi;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// A DOI in running text, as a resolver URL, with a "doi:" label or bare, e.g. "https://doi.org/10.1234/abc".
+pub(crate) static DOI_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(?:https?://(?:dx\.|www\.)?doi\.org/|\bdoi:?\s*)?\b10\.[0-9]{4,9}/[^\s"<>]+"#)
+        .unwrap()
+});
+
+/// A Handle, as a resolver URL or with an "hdl:" label, e.g. "https://hdl.handle.net/20.500.12345/678".
+static HANDLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)(?:(?:https?://)?hdl\.handle\.net/|\bhdl:\s*)([0-9]+(?:\.[0-9]+)*/[^\s"<>]+)"#,
+    )
+    .unwrap()
+});
+
+/// An arXiv identifier, new style "2101.01234" or old style "hep-th/9901001", with an "arXiv:" label or as an
+/// arxiv.org URL, optionally versioned.
+static ARXIV_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(?:\barxiv:\s*|(?:https?://)?(?:www\.)?arxiv\.org/(?:abs|pdf)/)([0-9]{4}\.[0-9]{4,5}|[a-z\-]+(?:\.[a-z]{2})?/[0-9]{7})(v[0-9]+)?",
+    )
+    .unwrap()
+});
+
+/// A PubMed ID, with a "PMID" label or as a PubMed URL, e.g. "PMID: 12345678".
+static PMID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(?:\bpmid:?\s*|(?:https?://)?(?:pubmed\.ncbi\.nlm\.nih\.gov/|(?:www\.)?ncbi\.nlm\.nih\.gov/pubmed/))([0-9]{1,8})\b",
+    )
+    .unwrap()
+});
+
+/// A PubMed Central ID, e.g. "PMC1234567".
+static PMCID_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bPMC[0-9]{4,8}\b").unwrap());
+
+/// Accession numbers of sequence and structure databases, in forms specific enough to find without a label: SRA
+/// runs, experiments, samples and studies ("SRR1234567"), BioProjects ("PRJNA123456"), BioSamples
+/// ("SAMN12345678"), GEO series and samples ("GSE12345"), ArrayExpress experiments ("E-MTAB-1234") and EMDB and
+/// EMPIAR entries ("EMD-1234", "EMPIAR-10001").
+static ACCESSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:[SDE]R[RXSP][0-9]{6,9}|PRJ[NED][A-Z][0-9]+|SAM[NED][A-Z]?[0-9]+|GS[EM][0-9]{3,8}|E-[A-Z]{4}-[0-9]+|EMD-[0-9]{4,5}|EMPIAR-[0-9]{5})\b",
+    )
+    .unwrap()
+});
+
+/// GenBank and PDB accession numbers, which are only recognized after a label, e.g. "GenBank accession MN908947.3"
+/// or "PDB ID 6VXX".
+static LABELLED_ACCESSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:genbank|accession)(?:\s+(?:no\.?|numbers?|accession))?:?\s*([A-Z]{1,2}[0-9]{5,6}(?:\.[0-9]+)?)\b|\bPDB(?:\s+(?:id|code|entry))?:?\s*([0-9][A-Z0-9]{3})\b",
+    )
+    .unwrap()
+});
+
+/// The types of identifiers found by `extract_identifiers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierKind {
+    Doi,
+    Handle,
+    Arxiv,
+    Pmid,
+    Pmcid,
+    Accession,
+}
+
+impl IdentifierKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentifierKind::Doi => "doi",
+            IdentifierKind::Handle => "handle",
+            IdentifierKind::Arxiv => "arxiv",
+            IdentifierKind::Pmid => "pmid",
+            IdentifierKind::Pmcid => "pmcid",
+            IdentifierKind::Accession => "accession",
+        }
+    }
+}
+
+/// An identifier found in text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifierHit {
+    pub kind: IdentifierKind,
+    /// The normalized identifier: a lowercase DOI, a Handle, an unversioned arXiv ID, PMID digits, or an uppercase
+    /// PMCID or accession number.
+    pub value: String,
+    /// Character offsets of the identifier in the text, including any resolver URL or label, end exclusive.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Trims the punctuation that ends a sentence or clause after an identifier in running text, along with closing
+/// parentheses and brackets that open before it, e.g. "(doi:10.1234/abc)." gives "doi:10.1234/abc".
+pub(crate) fn trim_identifier(identifier: &str) -> &str {
+    let mut identifier = identifier.trim_end_matches(['.', ',', ';', ':', '\'', '"', '”', '’']);
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        while identifier.ends_with(close)
+            && identifier.matches(close).count() > identifier.matches(open).count()
+        {
+            identifier = identifier[..identifier.len() - 1].trim_end_matches(['.', ',', ';']);
+        }
+    }
+    identifier
+}
+
+/// Finds the DOIs, Handles, arXiv IDs, PubMed and PubMed Central IDs and accession numbers in free text, e.g. the
+/// outputs cited in a DMP's narrative, in order of their position. Where identifiers overlap, e.g. a PMCID within a
+/// DOI, the one starting first, or the longer one when they start together, is kept.
+pub fn extract_identifiers(text: &str) -> Vec<IdentifierHit> {
+    // Byte ranges with the kind and normalized value of each identifier
+    let mut hits: Vec<(usize, usize, IdentifierKind, String)> = Vec::new();
+    for m in DOI_RE.find_iter(text) {
+        let raw = trim_identifier(m.as_str());
+        if let Some(doi) = normalize_doi(Some(raw)) {
+            hits.push((m.start(), m.start() + raw.len(), IdentifierKind::Doi, doi));
+        }
+    }
+    for caps in HANDLE_RE.captures_iter(text) {
+        let (whole, handle) = (caps.get(0).unwrap(), caps.get(1).unwrap());
+        let handle = trim_identifier(handle.as_str());
+        let end = whole.start() + trim_identifier(whole.as_str()).len();
+        hits.push((
+            whole.start(),
+            end,
+            IdentifierKind::Handle,
+            handle.to_string(),
+        ));
+    }
+    for caps in ARXIV_RE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        hits.push((
+            whole.start(),
+            whole.end(),
+            IdentifierKind::Arxiv,
+            caps[1].to_lowercase(),
+        ));
+    }
+    for caps in PMID_RE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        hits.push((
+            whole.start(),
+            whole.end(),
+            IdentifierKind::Pmid,
+            caps[1].to_string(),
+        ));
+    }
+    for m in PMCID_RE.find_iter(text) {
+        hits.push((
+            m.start(),
+            m.end(),
+            IdentifierKind::Pmcid,
+            m.as_str().to_uppercase(),
+        ));
+    }
+    for m in ACCESSION_RE.find_iter(text) {
+        hits.push((
+            m.start(),
+            m.end(),
+            IdentifierKind::Accession,
+            m.as_str().to_string(),
+        ));
+    }
+    for caps in LABELLED_ACCESSION_RE.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let accession = caps.get(1).or_else(|| caps.get(2)).unwrap();
+        hits.push((
+            whole.start(),
+            whole.end(),
+            IdentifierKind::Accession,
+            accession.as_str().to_uppercase(),
+        ));
+    }
+
+    hits.sort_by_key(|(start, end, _, _)| (*start, std::cmp::Reverse(*end)));
+    let mut kept: Vec<(usize, usize, IdentifierKind, String)> = Vec::new();
+    for hit in hits {
+        if kept.last().is_none_or(|(_, end, _, _)| hit.0 >= *end) {
+            kept.push(hit);
+        }
+    }
+
+    // Convert byte offsets into character offsets, counting forwards as the hits are in order
+    let mut chars = 0;
+    let mut bytes = 0;
+    let mut char_offset = |byte: usize| {
+        chars += text[bytes..byte].chars().count();
+        bytes = byte;
+        chars
+    };
+    kept.into_iter()
+        .map(|(start, end, kind, value)| IdentifierHit {
+            kind,
+            value,
+            start: char_offset(start),
+            end: char_offset(end),
+        })
+        .collect()
+}
@@ -0,0 +1,327 @@
+use crate::core::{decode_html_entities, normalize_ror, open_text, parse_affiliation};
+use crate::settings::{self, Normalizer};
+use crate::similarity::token_sort_ratio;
+use deunicode::deunicode;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Abbreviations common in affiliations, with the words they are expanded to so that both forms normalize alike.
+static INSTITUTION_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("centre", "center"),
+    ("coll", "college"),
+    ("ctr", "center"),
+    ("dept", "department"),
+    ("inst", "institute"),
+    ("intl", "international"),
+    ("natl", "national"),
+    ("univ", "university"),
+    ("universitat", "university"),
+    ("universite", "university"),
+    ("universiteit", "university"),
+    ("universitaet", "university"),
+];
+
+/// Parenthetical asides, e.g. the acronym in "University of California, Los Angeles (UCLA)".
+static PARENTHETICAL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\([^()]*\)").unwrap());
+
+/// The number of a query's rarest words whose names are fuzzy-matched against it. Names sharing none of them, e.g.
+/// because both are misspelled, aren't compared.
+const FUZZY_CANDIDATE_WORDS: usize = 2;
+
+/// Normalizes a free-text institution name for matching: HTML entities are decoded, parenthetical asides and a
+/// leading "the" dropped, and the result transliterated to ASCII, lowercased, stripped of punctuation and
+/// whitespace-collapsed, with common abbreviations expanded. For example, "Univ. of Montana" and "The University of
+/// Montana" both normalize to "university of montana". Returns None when no words are left.
+pub fn normalize_institution_name(name: Option<&str>) -> Option<String> {
+    settings::cached(Normalizer::InstitutionName, name?, |name| {
+        normalize_institution_name_uncached(name)
+    })
+}
+
+fn normalize_institution_name_uncached(name: &str) -> Option<String> {
+    let decoded = decode_html_entities(name.trim());
+    let without_asides = PARENTHETICAL_RE.replace_all(&decoded, " ");
+    let name = if without_asides.trim().is_empty() {
+        decoded.replace(['(', ')'], " ")
+    } else {
+        without_asides.into_owned()
+    };
+
+    let folded = deunicode(&name)
+        .to_lowercase()
+        .replace('&', " and ")
+        .replace('\'', "");
+    let mut words: Vec<&str> = folded
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            INSTITUTION_ABBREVIATIONS
+                .iter()
+                .find(|(abbreviation, _)| *abbreviation == word)
+                .map_or(word, |(_, expansion)| *expansion)
+        })
+        .collect();
+    if words.len() > 1 && words[0] == "the" {
+        words.remove(0);
+    }
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Normalizes an acronym for lookup: its letters, digits and ampersands uppercased, e.g. "U.C.L.A." → "UCLA".
+fn normalize_acronym(acronym: &str) -> Option<String> {
+    let acronym: String = acronym
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '&')
+        .flat_map(char::to_uppercase)
+        .collect();
+    (acronym.chars().count() >= 2).then_some(acronym)
+}
+
+/// Checks whether a name is written as an acronym: a single word of at least two characters that are capitals,
+/// digits, ampersands or full stops, e.g. "MIT" or "U.C.L.A.".
+fn looks_like_acronym(name: &str) -> bool {
+    let name = name.trim();
+    name.chars().filter(|c| c.is_alphanumeric()).count() >= 2
+        && name
+            .chars()
+            .all(|c| c.is_uppercase() || c.is_ascii_digit() || c == '&' || c == '.')
+}
+
+/// An institution of an `InstitutionIndex`: its ID, e.g. a ROR ID, with its names and acronyms.
+#[derive(Debug, Clone, Default)]
+pub struct InstitutionEntry {
+    pub id: String,
+    /// Its display name, labels in other languages and aliases.
+    pub names: Vec<String>,
+    pub acronyms: Vec<String>,
+}
+
+/// A name of an institution in a ROR dump, in the v2 schema.
+#[derive(Deserialize)]
+struct RorName {
+    value: String,
+    #[serde(default)]
+    types: Vec<String>,
+}
+
+/// A label of an institution in another language, in the v1 schema.
+#[derive(Deserialize)]
+struct RorLabel {
+    label: String,
+}
+
+/// A record of a ROR data dump, with the fields of both its v2 names and the v1 name, aliases, acronyms and labels.
+#[derive(Deserialize)]
+struct RorRecord {
+    id: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    names: Vec<RorName>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    acronyms: Vec<String>,
+    #[serde(default)]
+    labels: Vec<RorLabel>,
+}
+
+impl RorRecord {
+    /// The record as an entry keyed by its bare ROR ID, or None when it is withdrawn or its ID is invalid.
+    fn into_entry(self) -> Option<InstitutionEntry> {
+        if self.status.as_deref() == Some("withdrawn") {
+            return None;
+        }
+        let mut entry = InstitutionEntry {
+            id: normalize_ror(Some(&self.id))?,
+            ..Default::default()
+        };
+        for name in self.names {
+            if name.types.iter().any(|t| t == "acronym") {
+                entry.acronyms.push(name.value);
+            } else {
+                entry.names.push(name.value);
+            }
+        }
+        entry.names.extend(self.name);
+        entry.names.extend(self.aliases);
+        entry
+            .names
+            .extend(self.labels.into_iter().map(|label| label.label));
+        entry.acronyms.extend(self.acronyms);
+        Some(entry)
+    }
+}
+
+/// A lookup table of institution IDs and their names and acronyms, e.g. from a ROR data dump, normalized and indexed
+/// once up front so that many free-text names can be matched against it.
+#[derive(Debug, Clone, Default)]
+pub struct InstitutionIndex {
+    /// Institution ID and normalized name pairs, ordered by ID.
+    names: Vec<(String, String)>,
+    /// Normalized names mapped to the index of their first entry in `names`.
+    exact: HashMap<String, usize>,
+    /// Normalized acronyms mapped to the IDs of the institutions using them, in order.
+    acronyms: HashMap<String, BTreeSet<String>>,
+    /// Words of normalized names mapped to the indexes of the names they occur in, ascending.
+    postings: HashMap<String, Vec<usize>>,
+    num_institutions: usize,
+}
+
+impl InstitutionIndex {
+    /// Builds an index from institutions, merging entries with the same ID. Names that normalize to nothing are
+    /// skipped.
+    pub fn new(entries: impl IntoIterator<Item = InstitutionEntry>) -> Self {
+        let mut merged: HashMap<String, InstitutionEntry> = HashMap::new();
+        for entry in entries {
+            let merged = merged
+                .entry(entry.id.clone())
+                .or_insert_with(|| InstitutionEntry {
+                    id: entry.id.clone(),
+                    ..Default::default()
+                });
+            merged.names.extend(entry.names);
+            merged.acronyms.extend(entry.acronyms);
+        }
+        let mut entries: Vec<InstitutionEntry> = merged.into_values().collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut index = Self {
+            num_institutions: entries.len(),
+            ..Default::default()
+        };
+        for entry in entries {
+            let normalized: BTreeSet<String> = entry
+                .names
+                .iter()
+                .filter_map(|name| normalize_institution_name(Some(name)))
+                .collect();
+            for name in normalized {
+                let position = index.names.len();
+                index.exact.entry(name.clone()).or_insert(position);
+                for word in name.split(' ').collect::<BTreeSet<_>>() {
+                    index
+                        .postings
+                        .entry(word.to_string())
+                        .or_default()
+                        .push(position);
+                }
+                index.names.push((entry.id.clone(), name));
+            }
+            for acronym in entry.acronyms.iter().filter_map(|a| normalize_acronym(a)) {
+                index
+                    .acronyms
+                    .entry(acronym)
+                    .or_default()
+                    .insert(entry.id.clone());
+            }
+        }
+        index
+    }
+
+    /// Builds an index from a plain or gzip-compressed ROR data dump, a JSON array of organization records in the v1
+    /// or v2 schema, keyed by bare ROR IDs. Withdrawn organizations are skipped, and `extra` entries, e.g. local
+    /// aliases, are added to those of the dump.
+    pub fn from_ror_dump(
+        path: &Path,
+        extra: impl IntoIterator<Item = InstitutionEntry>,
+    ) -> io::Result<Self> {
+        let records: Vec<RorRecord> = serde_json::from_reader(open_text(path)?).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("invalid ROR dump: {e}"))
+        })?;
+        let entries = records.into_iter().filter_map(RorRecord::into_entry);
+        Ok(Self::new(entries.chain(extra)))
+    }
+
+    /// Returns the number of institution names in the index.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns the number of institutions in the index.
+    pub fn num_institutions(&self) -> usize {
+        self.num_institutions
+    }
+
+    /// The best `token_sort_ratio` match of a normalized name among the names sharing one of its
+    /// `FUZZY_CANDIDATE_WORDS` rarest words, with ties going to the smallest ID.
+    fn best_fuzzy_match(&self, normalized: &str) -> Option<(&str, f64)> {
+        let mut words: Vec<&Vec<usize>> = normalized
+            .split(' ')
+            .filter_map(|word| self.postings.get(word))
+            .collect();
+        words.sort_by_key(|postings| postings.len());
+        let candidates: BTreeSet<usize> = words
+            .into_iter()
+            .take(FUZZY_CANDIDATE_WORDS)
+            .flatten()
+            .copied()
+            .collect();
+
+        let mut best: Option<(&str, f64)> = None;
+        for index in candidates {
+            let (id, candidate) = &self.names[index];
+            let score = token_sort_ratio(normalized, candidate);
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((id, score));
+            }
+        }
+        best
+    }
+}
+
+/// Matches a free-text institution name, e.g. a DMP contributor's affiliation, against an index, returning the best
+/// matching institution ID and its score (0–1) when the score is at least `threshold`. Names that normalize to the
+/// same name as an indexed one score 1.0, as do acronyms used by only one institution; acronyms shared by several
+/// are ambiguous and match nothing. Otherwise the institution is taken from the name as an affiliation string, e.g.
+/// "Dept. of Biology, University of Montana, Missoula", and compared exactly and then with `token_sort_ratio`
+/// against the names sharing its rarest words.
+pub fn match_institution(
+    name: Option<&str>,
+    index: &InstitutionIndex,
+    threshold: f64,
+) -> Option<(String, f64)> {
+    let name = name?;
+    let normalized = normalize_institution_name(Some(name))?;
+    if let Some(&position) = index.exact.get(&normalized) {
+        return Some((index.names[position].0.clone(), 1.0));
+    }
+    if looks_like_acronym(name) {
+        let ids = index.acronyms.get(&normalize_acronym(name)?)?;
+        return match ids.len() {
+            1 => ids.first().map(|id| (id.clone(), 1.0)),
+            _ => None,
+        };
+    }
+
+    let institution = parse_affiliation(Some(name))
+        .institution
+        .and_then(|institution| normalize_institution_name(Some(&institution)))
+        .unwrap_or(normalized);
+    if let Some(&position) = index.exact.get(&institution) {
+        return Some((index.names[position].0.clone(), 1.0));
+    }
+    index
+        .best_fuzzy_match(&institution)
+        .filter(|(_, score)| *score >= threshold)
+        .map(|(id, score)| (id.to_string(), score))
+}
+
+/// Matches many names with `match_institution` in parallel on the current rayon thread pool, preserving order.
+pub fn match_institution_many(
+    names: &[Option<String>],
+    index: &InstitutionIndex,
+    threshold: f64,
+) -> Vec<Option<(String, f64)>> {
+    names
+        .par_iter()
+        .map(|name| match_institution(name.as_deref(), index, threshold))
+        .collect()
+}
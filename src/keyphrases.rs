@@ -0,0 +1,99 @@
+use crate::core::tokenize_with_spans;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Words that split candidate keyphrases, a compact version of the SMART stop list RAKE is usually run with.
+static RAKE_STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "across", "after", "again", "against", "all", "almost", "also",
+    "although", "always", "among", "an", "and", "another", "any", "are", "around", "as", "at",
+    "be", "because", "been", "before", "being", "below", "between", "both", "but", "by", "can",
+    "could", "did", "do", "does", "done", "due", "during", "each", "either", "enough", "etc",
+    "even", "ever", "every", "for", "from", "further", "had", "has", "have", "having", "he", "her",
+    "here", "hers", "him", "his", "how", "however", "i", "if", "in", "into", "is", "it", "its",
+    "itself", "just", "less", "made", "mainly", "make", "many", "may", "more", "most", "mostly",
+    "much", "must", "my", "neither", "no", "nor", "not", "of", "often", "on", "once", "one",
+    "only", "or", "other", "our", "ours", "out", "over", "own", "per", "perhaps", "quite",
+    "rather", "really", "same", "several", "she", "should", "show", "showed", "shown", "shows",
+    "since", "so", "some", "such", "than", "that", "the", "their", "theirs", "them", "then",
+    "there", "these", "they", "this", "those", "though", "through", "thus", "to", "too", "under",
+    "until", "up", "upon", "us", "use", "used", "using", "various", "very", "was", "we", "well",
+    "were", "what", "when", "where", "whether", "which", "while", "who", "whom", "whose", "why",
+    "will", "with", "within", "without", "would", "yet", "you", "your",
+];
+
+/// Candidate keyphrases longer than this many words are dropped, as RAKE otherwise favours long runs of words
+/// between stop words.
+const MAX_PHRASE_WORDS: usize = 4;
+
+/// Splits text into RAKE candidate keyphrases: runs of lowercase words delimited by stop words, purely numeric words
+/// and punctuation other than hyphens and apostrophes.
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    let mut phrase: Vec<String> = Vec::new();
+    let mut last_end = 0;
+    for (word, start, end) in tokenize_with_spans(text) {
+        let punctuated = text[last_end..start]
+            .chars()
+            .any(|c| !c.is_whitespace() && c != '-' && c != '\'' && c != '’');
+        last_end = end;
+        let delimiter = RAKE_STOP_WORDS.contains(&word.as_str())
+            || word.chars().all(|c| c.is_numeric() || c == '.' || c == ',');
+        if punctuated || delimiter {
+            phrases.push(std::mem::take(&mut phrase));
+        }
+        if !delimiter {
+            phrase.push(word);
+        }
+    }
+    phrases.push(phrase);
+    phrases.retain(|phrase| !phrase.is_empty() && phrase.len() <= MAX_PHRASE_WORDS);
+    phrases
+}
+
+/// Extracts up to `top_n` keyphrases from a text with RAKE (Rapid Automatic Keyword Extraction): each word of the
+/// candidate phrases is scored by its degree (the total length of the phrases it occurs in) divided by its frequency,
+/// and each distinct phrase by the sum of its words' scores. Phrases are returned lowercased with their scores, best
+/// first, breaking ties by first occurrence.
+pub fn extract_keyphrases(text: &str, top_n: usize) -> Vec<(String, f64)> {
+    let phrases = candidate_phrases(text);
+
+    let mut frequency: HashMap<&str, f64> = HashMap::new();
+    let mut degree: HashMap<&str, f64> = HashMap::new();
+    for phrase in &phrases {
+        for word in phrase {
+            *frequency.entry(word).or_insert(0.0) += 1.0;
+            *degree.entry(word).or_insert(0.0) += phrase.len() as f64;
+        }
+    }
+
+    let mut scored: Vec<(String, f64)> = Vec::new();
+    for phrase in &phrases {
+        let joined = phrase.join(" ");
+        if scored.iter().any(|(seen, _)| *seen == joined) {
+            continue;
+        }
+        let score = phrase
+            .iter()
+            .map(|word| degree[word.as_str()] / frequency[word.as_str()])
+            .sum();
+        scored.push((joined, score));
+    }
+
+    // A stable sort keeps phrases with equal scores in order of first occurrence
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(top_n);
+    scored
+}
+
+/// Extracts keyphrases from many texts with `extract_keyphrases` in parallel on the current rayon thread pool,
+/// giving no keyphrases for missing texts.
+pub fn extract_keyphrases_many(texts: &[Option<String>], top_n: usize) -> Vec<Vec<(String, f64)>> {
+    texts
+        .par_iter()
+        .map(|text| {
+            text.as_deref()
+                .map(|text| extract_keyphrases(text, top_n))
+                .unwrap_or_default()
+        })
+        .collect()
+}
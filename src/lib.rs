@@ -1,23 +1,186 @@
+use arrow_array::cast::AsArray;
+use arrow_array::Array;
+use arrow_schema::{ArrowError, DataType};
+use log::warn;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
+use parquet::errors::ParquetError;
+use pyo3::exceptions::{PyIOError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyBool, PyDict, PyFloat, PyList, PyMapping, PyTuple};
+use pyo3::IntoPyObjectExt;
+use pyo3_arrow::{PyArray, PyRecordBatch, PySchema};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
+mod acknowledgements;
+mod acronyms;
+mod blocking;
+mod bm25;
+mod bulk;
+mod candidates;
+mod citations;
 mod core;
+mod csv;
+mod dedupe;
+mod embedding;
+mod eval;
+mod funders;
+mod fusion;
+mod ground_truth;
+mod identifiers;
+mod institutions;
+mod keyphrases;
+mod loader;
+mod logging;
+mod lookup;
+mod mappings;
+mod minhash;
+mod parquet_io;
+mod phonetic;
+mod pipeline;
+mod profile;
+mod query;
+mod sampler;
+mod scoring;
+mod selectors;
+mod settings;
+mod similarity;
+mod sort;
+mod tfidf;
+mod transform;
+mod vectors;
+
+/// A parsed name passed from Python, extracted from the attributes of a `ParsedName` class or named tuple.
+#[derive(FromPyObject)]
+struct ParsedNameArg {
+    first_initial: Option<String>,
+    given_name: Option<String>,
+    middle_initials: Option<String>,
+    middle_names: Option<String>,
+    surname: Option<String>,
+    full: Option<String>,
+    #[pyo3(default)]
+    suffix: Option<String>,
+    #[pyo3(default)]
+    title: Option<String>,
+}
+
+impl From<ParsedNameArg> for core::ParsedName {
+    fn from(arg: ParsedNameArg) -> Self {
+        core::ParsedName {
+            first_initial: arg.first_initial,
+            given_name: arg.given_name,
+            middle_initials: arg.middle_initials,
+            middle_names: arg.middle_names,
+            surname: arg.surname,
+            full: arg.full,
+            suffix: arg.suffix,
+            title: arg.title,
+        }
+    }
+}
+
+/// A name passed from Python to the comparison functions, either a raw full name or an already parsed name.
+#[derive(FromPyObject)]
+enum NameArg {
+    Raw(String),
+    Parsed(ParsedNameArg),
+}
+
+/// Parses a raw name, romanizing names in non-Latin scripts, or takes a parsed name as it is.
+fn parse_name_arg(name: Option<NameArg>) -> core::ParsedName {
+    match name {
+        Some(NameArg::Parsed(parsed)) => parsed.into(),
+        Some(NameArg::Raw(raw)) => core::parse_romanized_name(None, None, Some(&raw)),
+        None => core::parse_romanized_name(None, None, None),
+    }
+}
+
+/// Runs `f` with the GIL released, on a rayon thread pool of `num_threads` threads when given, reused across calls, or
+/// otherwise on the pool set up by `configure`, if any, or the global pool.
+fn run_parallel<T, F>(py: Python<'_>, num_threads: Option<usize>, f: F) -> PyResult<T>
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    py.detach(|| match num_threads {
+        Some(0) => Err(PyValueError::new_err("num_threads must be greater than 0")),
+        Some(n) => settings::sized_thread_pool(n)
+            .map(|pool| pool.install(f))
+            .map_err(|e| PyValueError::new_err(e.to_string())),
+        None => Ok(match settings::thread_pool() {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }),
+    })
+}
 
 #[pyfunction]
-#[pyo3(signature = (raw_given_name=None, raw_surname=None, raw_full=None))]
-fn parse_name(
-    raw_given_name: Option<&str>,
-    raw_surname: Option<&str>,
-    raw_full: Option<&str>,
-) -> (
+#[pyo3(signature = (threads = None, chunk_size = None, string_cache = None))]
+fn configure(
+    py: Python<'_>,
+    threads: Option<usize>,
+    chunk_size: Option<usize>,
+    string_cache: Option<usize>,
+) -> PyResult<Bound<'_, PyDict>> {
+    if chunk_size == Some(0) {
+        return Err(PyValueError::new_err("chunk_size must be greater than 0"));
+    }
+    if let Some(threads) = threads {
+        settings::set_threads(threads).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+    if let Some(chunk_size) = chunk_size {
+        settings::set_chunk_size(chunk_size);
+    }
+    if let Some(capacity) = string_cache {
+        settings::set_string_cache(capacity);
+    }
+    let current = settings::settings();
+    let dict = PyDict::new(py);
+    dict.set_item("threads", current.threads)?;
+    dict.set_item("chunk_size", current.chunk_size)?;
+    dict.set_item("string_cache", current.string_cache)?;
+    Ok(dict)
+}
+
+/// Number of records between calls of a progress callback, for batch functions without batches of their own.
+const PROGRESS_INTERVAL: usize = 10_000;
+
+/// Calls an optional progress callback with the number of records processed so far and the total number, if known,
+/// from a thread without the GIL. An exception raised by the callback is kept in `callback_error`, and an I/O error
+/// returned to stop processing.
+fn call_progress(
+    progress: Option<&Py<PyAny>>,
+    processed: usize,
+    total_hint: Option<usize>,
+    callback_error: &mut Option<PyErr>,
+) -> io::Result<()> {
+    let Some(callback) = progress else {
+        return Ok(());
+    };
+    Python::attach(|py| callback.call1(py, (processed, total_hint)))
+        .map(|_| ())
+        .map_err(|e| {
+            *callback_error = Some(e);
+            io::Error::other("progress callback failed")
+        })
+}
+
+/// A `ParsedName` as a tuple of its name fields, which the Python package wraps in a named tuple. Its suffix and title
+/// are only returned by the `*_struct` functions, so the tuple keeps its six fields.
+type ParsedNameTuple = (
     Option<String>,
     Option<String>,
     Option<String>,
     Option<String>,
     Option<String>,
     Option<String>,
-) {
-    let parsed = core::parse_name(raw_given_name, raw_surname, raw_full);
+);
 
+fn parsed_name_tuple(parsed: core::ParsedName) -> ParsedNameTuple {
     (
         parsed.first_initial,
         parsed.given_name,
@@ -28,6 +191,278 @@ fn parse_name(
     )
 }
 
+#[pyclass(frozen)]
+struct NameOptions {
+    options: core::NameOptions,
+}
+
+#[pymethods]
+impl NameOptions {
+    #[new]
+    #[pyo3(signature = (
+        surname_particles = false,
+        family_name_first = false,
+        ambiguous_organization_words = false,
+    ))]
+    fn new(
+        surname_particles: bool,
+        family_name_first: bool,
+        ambiguous_organization_words: bool,
+    ) -> Self {
+        Self {
+            options: core::NameOptions {
+                surname_particles,
+                family_name_first,
+                ambiguous_organization_words,
+            },
+        }
+    }
+}
+
+/// The name options to parse with, defaulting to `core::NameOptions::default()`.
+fn name_options(options: Option<&NameOptions>) -> core::NameOptions {
+    options.map_or_else(Default::default, |options| options.options.clone())
+}
+
+#[pyfunction]
+#[pyo3(signature = (raw_given_name=None, raw_surname=None, raw_full=None, recase_surname=false, options=None))]
+fn parse_name(
+    raw_given_name: Option<&str>,
+    raw_surname: Option<&str>,
+    raw_full: Option<&str>,
+    recase_surname: bool,
+    options: Option<&NameOptions>,
+) -> ParsedNameTuple {
+    let options = name_options(options);
+    let parsed = core::parse_name_with_options(
+        raw_given_name,
+        raw_surname,
+        raw_full,
+        recase_surname,
+        &options,
+    );
+    parsed_name_tuple(parsed)
+}
+
+#[pyfunction]
+#[pyo3(signature = (raw_full, recase_surname=false, num_threads=None, options=None, collect_errors=false))]
+fn parse_name_many(
+    py: Python<'_>,
+    raw_full: Vec<Option<String>>,
+    recase_surname: bool,
+    num_threads: Option<usize>,
+    options: Option<&NameOptions>,
+    collect_errors: bool,
+) -> PyResult<Py<PyAny>> {
+    let options = name_options(options);
+    if collect_errors {
+        let (parsed, errors) = run_parallel(py, num_threads, || {
+            core::parse_name_many_with_errors(&raw_full, recase_surname, &options)
+        })?;
+        let parsed: Vec<ParsedNameTuple> = parsed.into_iter().map(parsed_name_tuple).collect();
+        (parsed, parse_error_tuples(errors)).into_py_any(py)
+    } else {
+        let parsed = run_parallel(py, num_threads, || {
+            core::parse_name_many(&raw_full, recase_surname, &options)
+        })?;
+        let parsed: Vec<ParsedNameTuple> = parsed.into_iter().map(parsed_name_tuple).collect();
+        parsed.into_py_any(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, recase_surname=false, options=None, collect_errors=false))]
+fn parse_names_list(
+    py: Python<'_>,
+    text: Option<&str>,
+    recase_surname: bool,
+    options: Option<&NameOptions>,
+    collect_errors: bool,
+) -> PyResult<Py<PyAny>> {
+    let options = name_options(options);
+    let (parsed, errors) = match text {
+        Some(text) if collect_errors => {
+            core::parse_names_list_with_errors(text, recase_surname, &options)
+        },
+        Some(text) => (
+            core::parse_names_list(text, recase_surname, &options),
+            Vec::new(),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+    let parsed: Vec<ParsedNameTuple> = parsed.into_iter().map(parsed_name_tuple).collect();
+    if collect_errors {
+        (parsed, parse_error_tuples(errors)).into_py_any(py)
+    } else {
+        parsed.into_py_any(py)
+    }
+}
+
+#[pyclass(frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
+struct ParsedName {
+    name: core::ParsedName,
+}
+
+#[pymethods]
+impl ParsedName {
+    #[new]
+    #[pyo3(signature = (
+        first_initial = None,
+        given_name = None,
+        middle_initials = None,
+        middle_names = None,
+        surname = None,
+        full = None,
+        suffix = None,
+        title = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        first_initial: Option<String>,
+        given_name: Option<String>,
+        middle_initials: Option<String>,
+        middle_names: Option<String>,
+        surname: Option<String>,
+        full: Option<String>,
+        suffix: Option<String>,
+        title: Option<String>,
+    ) -> Self {
+        Self {
+            name: core::ParsedName {
+                first_initial,
+                given_name,
+                middle_initials,
+                middle_names,
+                surname,
+                full,
+                suffix,
+                title,
+            },
+        }
+    }
+
+    #[getter]
+    fn first_initial(&self) -> Option<&str> {
+        self.name.first_initial.as_deref()
+    }
+
+    #[getter]
+    fn given_name(&self) -> Option<&str> {
+        self.name.given_name.as_deref()
+    }
+
+    #[getter]
+    fn middle_initials(&self) -> Option<&str> {
+        self.name.middle_initials.as_deref()
+    }
+
+    #[getter]
+    fn middle_names(&self) -> Option<&str> {
+        self.name.middle_names.as_deref()
+    }
+
+    #[getter]
+    fn surname(&self) -> Option<&str> {
+        self.name.surname.as_deref()
+    }
+
+    #[getter]
+    fn full(&self) -> Option<&str> {
+        self.name.full.as_deref()
+    }
+
+    #[getter]
+    fn suffix(&self) -> Option<&str> {
+        self.name.suffix.as_deref()
+    }
+
+    #[getter]
+    fn title(&self) -> Option<&str> {
+        self.name.title.as_deref()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let fields = core::PARSED_NAME_FIELDS
+            .iter()
+            .zip(self.name.clone().into_fields())
+            .map(|(field, value)| Ok(format!("{field}={}", value.into_pyobject(py)?.repr()?)))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!("ParsedName({})", fields.join(", ")))
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        core::PARSED_NAME_FIELDS
+            .into_iter()
+            .zip(self.name.clone().into_fields())
+            .into_py_dict(py)
+    }
+}
+
+impl From<core::ParsedName> for ParsedName {
+    fn from(name: core::ParsedName) -> Self {
+        Self { name }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (raw_given_name=None, raw_surname=None, raw_full=None, recase_surname=false, options=None))]
+fn parse_name_struct(
+    raw_given_name: Option<&str>,
+    raw_surname: Option<&str>,
+    raw_full: Option<&str>,
+    recase_surname: bool,
+    options: Option<&NameOptions>,
+) -> ParsedName {
+    let options = name_options(options);
+    core::parse_name_with_options(
+        raw_given_name,
+        raw_surname,
+        raw_full,
+        recase_surname,
+        &options,
+    )
+    .into()
+}
+
+#[pyfunction]
+#[pyo3(signature = (raw_full, recase_surname=false, num_threads=None, options=None))]
+fn parse_name_many_struct(
+    py: Python<'_>,
+    raw_full: Vec<Option<String>>,
+    recase_surname: bool,
+    num_threads: Option<usize>,
+    options: Option<&NameOptions>,
+) -> PyResult<Vec<ParsedName>> {
+    let options = name_options(options);
+    let parsed = run_parallel(py, num_threads, || {
+        core::parse_name_many(&raw_full, recase_surname, &options)
+    })?;
+    Ok(parsed.into_iter().map(ParsedName::from).collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, recase_surname=false, options=None))]
+fn parse_names_list_struct(
+    text: Option<&str>,
+    recase_surname: bool,
+    options: Option<&NameOptions>,
+) -> Vec<ParsedName> {
+    let Some(text) = text else {
+        return Vec::new();
+    };
+    let options = name_options(options);
+    let parsed = core::parse_names_list(text, recase_surname, &options);
+    parsed.into_iter().map(ParsedName::from).collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, options = None))]
+fn is_organization(text: Option<&str>, options: Option<&NameOptions>) -> bool {
+    let options = name_options(options);
+    text.is_some_and(|text| core::is_organization_with_options(text, &options))
+}
+
 #[pyfunction]
 #[pyo3(signature = (text, null_if_equals = None))]
 fn revert_inverted_index(
@@ -37,10 +472,311 @@ fn revert_inverted_index(
     core::revert_inverted_index(text, null_if_equals.as_deref())
 }
 
+/// A `core::ParseError` as a (row, field, message) tuple.
+type ParseErrorTuple = (usize, Option<String>, String);
+
+fn parse_error_tuples(errors: Vec<core::ParseError>) -> Vec<ParseErrorTuple> {
+    errors
+        .into_iter()
+        .map(|error| (error.row, error.field, error.message))
+        .collect()
+}
+
 #[pyfunction]
-#[pyo3(signature = (text, null_if_equals = None))]
-fn strip_markup(text: Option<&str>, null_if_equals: Option<Vec<String>>) -> Option<String> {
-    core::strip_markup(text, null_if_equals.as_deref())
+#[pyo3(signature = (texts, null_if_equals = None, num_threads = None, collect_errors = false))]
+fn revert_inverted_index_many(
+    py: Python<'_>,
+    texts: Vec<Option<Vec<u8>>>,
+    null_if_equals: Option<Vec<String>>,
+    num_threads: Option<usize>,
+    collect_errors: bool,
+) -> PyResult<Py<PyAny>> {
+    if collect_errors {
+        let (reverted, errors) = run_parallel(py, num_threads, || {
+            core::revert_inverted_index_many_with_errors(&texts, null_if_equals.as_deref())
+        })?;
+        (reverted, parse_error_tuples(errors)).into_py_any(py)
+    } else {
+        run_parallel(py, num_threads, || {
+            core::revert_inverted_index_many(&texts, null_if_equals.as_deref())
+        })?
+        .into_py_any(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (indexes, null_if_equals = None, num_threads = None))]
+fn revert_inverted_indexes(
+    py: Python<'_>,
+    indexes: PyArray,
+    null_if_equals: Option<Vec<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<PyArray> {
+    let (array, _) = indexes.into_inner();
+    if !matches!(
+        array.data_type(),
+        DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Utf8View
+    ) {
+        return Err(PyTypeError::new_err(format!(
+            "indexes must be a binary or string array, got {}",
+            array.data_type()
+        )));
+    }
+
+    let reverted = run_parallel(py, num_threads, || {
+        // A no-op for large binary arrays, otherwise a single copy of the buffers
+        let indexes = arrow_cast::cast(&array, &DataType::LargeBinary)?;
+        Ok::<_, ArrowError>(core::revert_inverted_indexes(
+            indexes.as_binary::<i64>(),
+            null_if_equals.as_deref(),
+        ))
+    })?
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyArray::from_array_ref(Arc::new(reverted)))
+}
+
+#[pyclass(frozen)]
+struct NullRules {
+    rules: core::NullRules,
+}
+
+#[pymethods]
+impl NullRules {
+    #[new]
+    #[pyo3(signature = (values = Vec::new(), patterns = Vec::new(), presets = None, case_insensitive = true))]
+    fn new(
+        values: Vec<String>,
+        patterns: Vec<String>,
+        presets: Option<Vec<String>>,
+        case_insensitive: bool,
+    ) -> PyResult<Self> {
+        // All presets apply unless they are listed explicitly
+        let presets = match presets {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    core::NullPreset::from_name(name).ok_or_else(|| {
+                        PyValueError::new_err(format!(
+                            "presets must be 'not_available', 'to_be_determined', 'no_abstract', 'blank' or \
+                             'punctuation', got '{name}'"
+                        ))
+                    })
+                })
+                .collect::<PyResult<Vec<_>>>()?,
+            None => core::NullPreset::ALL.to_vec(),
+        };
+        let rules = core::NullRules::new(&values, &patterns, &presets, case_insensitive)
+            .map_err(|e| PyValueError::new_err(format!("invalid pattern: {e}")))?;
+        Ok(Self { rules })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, rules = None))]
+fn nullify(text: Option<&str>, rules: Option<&NullRules>) -> Option<String> {
+    let rules = rules.map_or(&*core::DEFAULT_NULL_RULES, |rules| &rules.rules);
+    core::nullify(text, rules)
+}
+
+#[pyclass(frozen)]
+struct BoilerplateRules {
+    rules: core::BoilerplateRules,
+}
+
+#[pymethods]
+impl BoilerplateRules {
+    #[new]
+    #[pyo3(signature = (patterns = Vec::new(), case_insensitive = true))]
+    fn new(patterns: Vec<String>, case_insensitive: bool) -> PyResult<Self> {
+        let rules = core::BoilerplateRules::new(&patterns, case_insensitive)
+            .map_err(|e| PyValueError::new_err(format!("invalid pattern: {e}")))?;
+        Ok(Self { rules })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, rules = None))]
+fn strip_boilerplate(text: Option<&str>, rules: Option<&BoilerplateRules>) -> Option<String> {
+    let default = core::BoilerplateRules::default();
+    let rules = rules.map_or(&default, |rules| &rules.rules);
+    core::strip_boilerplate(text, rules)
+}
+
+#[pyclass(frozen)]
+struct MarkupOptions {
+    options: core::MarkupOptions,
+}
+
+#[pymethods]
+impl MarkupOptions {
+    #[new]
+    #[pyo3(signature = (decode_entities = false, jats = false, mathml = "keep", null_rules = None))]
+    fn new(
+        decode_entities: bool,
+        jats: bool,
+        mathml: &str,
+        null_rules: Option<&NullRules>,
+    ) -> PyResult<Self> {
+        let mathml = match mathml {
+            "keep" => core::MathMl::Keep,
+            "remove" => core::MathMl::Remove,
+            "placeholder" => core::MathMl::Placeholder,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "mathml must be 'keep', 'remove' or 'placeholder', got '{mathml}'"
+                )))
+            },
+        };
+        Ok(Self {
+            options: core::MarkupOptions {
+                decode_entities,
+                jats,
+                mathml,
+                null_rules: null_rules.map(|null_rules| null_rules.rules.clone()),
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, null_if_equals = None, options = None))]
+fn strip_markup(
+    text: Option<&str>,
+    null_if_equals: Option<Vec<String>>,
+    options: Option<&MarkupOptions>,
+) -> Option<String> {
+    let default = core::MarkupOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    core::strip_markup_with_options(text, null_if_equals.as_deref(), options)
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, null_if_equals = None, options = None, num_threads = None))]
+fn strip_markup_many(
+    py: Python<'_>,
+    texts: Vec<Option<String>>,
+    null_if_equals: Option<Vec<String>>,
+    options: Option<&MarkupOptions>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<String>>> {
+    let default = core::MarkupOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    run_parallel(py, num_threads, || {
+        core::strip_markup_many(&texts, null_if_equals.as_deref(), options)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, null_rules = None, options = None, num_threads = None))]
+fn strip_markup_batch(
+    py: Python<'_>,
+    texts: PyArray,
+    null_rules: Option<&NullRules>,
+    options: Option<&MarkupOptions>,
+    num_threads: Option<usize>,
+) -> PyResult<PyArray> {
+    let (array, _) = texts.into_inner();
+    if !matches!(
+        array.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    ) {
+        return Err(PyTypeError::new_err(format!(
+            "texts must be a string array, got {}",
+            array.data_type()
+        )));
+    }
+    let null_rules = null_rules.map(|null_rules| &null_rules.rules);
+    let default = core::MarkupOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    let stripped = run_parallel(py, num_threads, || {
+        core::strip_markup_batch(&array, null_rules, options)
+    })?
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyArray::from_array_ref(stripped))
+}
+
+/// A share of a count, or 0.0 of none.
+fn rate(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, null_rules = None, languages = true, num_threads = None))]
+fn profile_texts<'py>(
+    py: Python<'py>,
+    texts: PyArray,
+    null_rules: Option<&NullRules>,
+    languages: bool,
+    num_threads: Option<usize>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (array, _) = texts.into_inner();
+    let array = match array.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            arrow_cast::cast(&array, &DataType::Utf8)
+        },
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => Ok(array),
+        data_type => {
+            return Err(PyTypeError::new_err(format!(
+                "texts must be a string or binary array, got {data_type}"
+            )))
+        },
+    }
+    .and_then(|array| arrow_cast::cast(&array, &DataType::Binary))
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let rules = null_rules.map_or(&*core::DEFAULT_NULL_RULES, |rules| &rules.rules);
+    let profile = run_parallel(py, num_threads, || {
+        profile::profile_texts(array.as_binary::<i32>(), rules, languages)
+    })?;
+
+    let values = profile.values();
+    let dict = PyDict::new(py);
+    dict.set_item("count", profile.count)?;
+    dict.set_item("nulls", profile.nulls)?;
+    dict.set_item("blanks", profile.blanks)?;
+    dict.set_item("placeholders", profile.placeholders)?;
+    dict.set_item("values", values)?;
+    dict.set_item("invalid_utf8", profile.invalid_utf8)?;
+    dict.set_item("markup", profile.markup)?;
+    dict.set_item("null_rate", rate(profile.nulls, profile.count))?;
+    dict.set_item("blank_rate", rate(profile.blanks, profile.count))?;
+    dict.set_item(
+        "placeholder_rate",
+        rate(profile.placeholders, profile.count),
+    )?;
+    dict.set_item(
+        "invalid_utf8_rate",
+        rate(profile.invalid_utf8, profile.count),
+    )?;
+    dict.set_item("markup_rate", rate(profile.markup, values))?;
+    let lengths = match &profile.lengths {
+        Some(lengths) => {
+            let distribution = PyDict::new(py);
+            distribution.set_item("min", lengths.min)?;
+            distribution.set_item("p10", lengths.p10)?;
+            distribution.set_item("p50", lengths.p50)?;
+            distribution.set_item("p90", lengths.p90)?;
+            distribution.set_item("p99", lengths.p99)?;
+            distribution.set_item("max", lengths.max)?;
+            distribution.set_item("mean", lengths.mean)?;
+            distribution.into_any()
+        },
+        None => py.None().into_bound(py),
+    };
+    dict.set_item("lengths", lengths)?;
+    if languages {
+        dict.set_item("languages", profile.languages.into_py_dict(py)?)?;
+        dict.set_item("undetected_language", profile.undetected_language)?;
+    }
+    Ok(dict)
 }
 
 #[pyfunction]
@@ -49,20 +785,3689 @@ fn has_alphabetic_initials(text: Option<&str>) -> bool {
     text.is_some_and(core::has_alphabetic_initials)
 }
 
-#[pymodule]
-fn _internal(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+#[pyfunction]
+#[pyo3(signature = (text, normalize_subscripts = false, strip_site_suffixes = false, stop_words = None))]
+fn normalize_title(
+    text: Option<&str>,
+    normalize_subscripts: bool,
+    strip_site_suffixes: bool,
+    stop_words: Option<Vec<String>>,
+) -> Option<String> {
+    core::normalize_title(
+        text,
+        normalize_subscripts,
+        strip_site_suffixes,
+        stop_words.as_deref(),
+    )
+}
 
-    // Add Python functions
-    m.add_function(wrap_pyfunction!(parse_name, m)?)?;
-    m.add_function(wrap_pyfunction!(revert_inverted_index, m)?)?;
-    m.add_function(wrap_pyfunction!(strip_markup, m)?)?;
-    m.add_function(wrap_pyfunction!(has_alphabetic_initials, m)?)?;
+/// A `core::TitleMetadata` as a (title, version, part, years) tuple.
+type TitleMetadataTuple = (Option<String>, Option<String>, Option<u32>, Vec<i32>);
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_title_metadata(text: Option<&str>) -> TitleMetadataTuple {
+    let metadata = core::extract_title_metadata(text);
+    (
+        metadata.title,
+        metadata.version,
+        metadata.part,
+        metadata.years,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_license(text: Option<&str>) -> Option<String> {
+    core::normalize_license(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_doi(text: Option<&str>) -> Option<String> {
+    core::normalize_doi(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp_text, work_abstract))]
+fn dmp_work_relevance(dmp_text: &str, work_abstract: &str) -> f64 {
+    core::dmp_work_relevance(dmp_text, work_abstract)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_keywords(text: Option<&str>) -> Vec<String> {
+    core::normalize_keywords(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn keyword_overlap(a: Option<&str>, b: Option<&str>) -> f64 {
+    core::keyword_overlap(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (key, num_buckets))]
+fn shard_bucket(key: &str, num_buckets: u32) -> PyResult<u32> {
+    if num_buckets == 0 {
+        return Err(PyValueError::new_err("num_buckets must be greater than 0"));
+    }
+    Ok(core::shard_bucket(key, num_buckets))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, num_shards))]
+fn shard_key(text: Option<&str>, num_shards: u32) -> PyResult<Option<u32>> {
+    if num_shards == 0 {
+        return Err(PyValueError::new_err("num_shards must be greater than 0"));
+    }
+    Ok(text.and_then(|text| core::shard_key(text, num_shards)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (fields))]
+fn record_fingerprint(fields: Vec<Option<String>>) -> String {
+    let fields: Vec<Option<&str>> = fields.iter().map(Option::as_deref).collect();
+    core::record_fingerprint(&fields)
+}
+
+#[pyfunction]
+#[pyo3(signature = (record))]
+fn to_canonical_json(record: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(core::to_canonical_json(&py_to_json(record)?))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn revert_and_detect_language(text: Option<&[u8]>) -> Option<(String, Option<String>)> {
+    core::revert_and_detect_language(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, expected))]
+fn surname_matches_expected(text: Option<&str>, expected: &str) -> bool {
+    core::surname_matches_expected(text, expected)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, style = "vancouver"))]
+fn citation_name(text: Option<&str>, style: &str) -> Option<String> {
+    core::citation_name(text, style)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn author_count(text: Option<&str>) -> usize {
+    core::author_count(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, n))]
+fn truncate_authors(text: Option<&str>, n: usize) -> String {
+    core::truncate_authors(text, n)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_author_join(text: Option<&str>) -> Option<String> {
+    core::normalize_author_join(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_grant_ids(text: Option<&str>) -> Vec<String> {
+    core::extract_grant_ids(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_identifiers(text: Option<&str>) -> Vec<(&'static str, String, usize, usize)> {
+    identifiers::extract_identifiers(text.unwrap_or_default())
+        .into_iter()
+        .map(|hit| (hit.kind.as_str(), hit.value, hit.start, hit.end))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_funding_statements(text: Option<&str>) -> Vec<(String, Vec<String>, Vec<String>)> {
+    acknowledgements::extract_funding_statements(text.unwrap_or_default())
+        .into_iter()
+        .map(|statement| (statement.sentence, statement.funders, statement.award_ids))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (signals, weights))]
+fn combined_relevance(signals: HashMap<String, f64>, weights: HashMap<String, f64>) -> f64 {
+    core::combined_relevance(&signals, &weights)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_date(text: Option<&str>) -> Option<String> {
+    core::normalize_date(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, threshold = 0.8))]
+fn bylines_match(a: Option<&str>, b: Option<&str>, threshold: f64) -> bool {
+    core::bylines_match(a, b, threshold)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn tokenize_with_spans(text: &str) -> Vec<(String, usize, usize)> {
+    core::tokenize_with_spans(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn initials_compatible(a: ParsedNameArg, b: ParsedNameArg) -> f64 {
+    core::initials_compatible(&a.into(), &b.into())
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, strip_trailing_authors=false))]
+fn tidy_reconstructed(text: &str, strip_trailing_authors: bool) -> String {
+    core::tidy_reconstructed(text, strip_trailing_authors)
+}
+
+#[pyfunction]
+#[pyo3(signature = (has_title, has_abstract, has_doi, author_count, has_year))]
+fn record_completeness(
+    has_title: bool,
+    has_abstract: bool,
+    has_doi: bool,
+    author_count: usize,
+    has_year: bool,
+) -> f64 {
+    core::record_completeness(has_title, has_abstract, has_doi, author_count, has_year)
+}
+
+/// Iterator over the words of an abstract reconstructed from an inverted index, in position order.
+#[pyclass]
+struct AbstractWords {
+    words: std::vec::IntoIter<String>,
+}
+
+#[pymethods]
+impl AbstractWords {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<String> {
+        // Words are stripped of markup individually, skipping words that are only markup
+        slf.words
+            .by_ref()
+            .find_map(|word| core::strip_markup(Some(&word), None))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn revert_iter(text: Option<&[u8]>) -> AbstractWords {
+    AbstractWords {
+        words: core::inverted_index_words(text)
+            .unwrap_or_default()
+            .into_iter(),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn doi_prefix(text: Option<&str>) -> Option<String> {
+    core::doi_prefix(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn doi_same_registrant(a: Option<&str>, b: Option<&str>) -> bool {
+    core::doi_same_registrant(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn cjk_surname_match(a: &str, b: &str) -> f64 {
+    core::cjk_surname_match(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (first_author, year))]
+fn citekey(first_author: Option<&str>, year: Option<i32>) -> Option<String> {
+    core::citekey(first_author, year)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn issn_set_match(a: Vec<Option<String>>, b: Vec<Option<String>>) -> bool {
+    core::issn_set_match(
+        a.iter().map(Option::as_deref).collect(),
+        b.iter().map(Option::as_deref).collect(),
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn title_type_hint(text: Option<&str>) -> Option<String> {
+    core::title_type_hint(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, parallel=false, ordered=true, progress=None, chunk_size=None))]
+fn revert_file(
+    py: Python<'_>,
+    input_path: PathBuf,
+    output_path: PathBuf,
+    parallel: bool,
+    ordered: bool,
+    progress: Option<Py<PyAny>>,
+    chunk_size: Option<usize>,
+) -> PyResult<(usize, usize)> {
+    let chunk_size = chunk_size.unwrap_or_else(settings::chunk_size);
+    if chunk_size == 0 {
+        return Err(PyValueError::new_err("chunk_size must be greater than 0"));
+    }
+
+    let (result, callback_error) = run_parallel(py, None, || {
+        let mut callback_error = None;
+        let result = core::revert_file(
+            &input_path,
+            &output_path,
+            parallel,
+            ordered,
+            chunk_size,
+            |processed| {
+                let Some(callback) = &progress else {
+                    return Ok(());
+                };
+                Python::attach(|py| callback.call1(py, (processed,)))
+                    .map(|_| ())
+                    .map_err(|e| {
+                        callback_error = Some(e);
+                        io::Error::other("progress callback failed")
+                    })
+            },
+        );
+        (result, callback_error)
+    })?;
+
+    // Surface the callback's own exception rather than the I/O error used to stop processing
+    if let Some(e) = callback_error {
+        return Err(e);
+    }
+    let stats = result?;
+    Ok((stats.processed, stats.malformed))
+}
+
+#[pyfunction]
+#[pyo3(signature = (names, recase_surname=false, options=None))]
+fn parse_names_batch(
+    py: Python<'_>,
+    names: PyArray,
+    recase_surname: bool,
+    options: Option<&NameOptions>,
+) -> PyResult<PyArray> {
+    let (array, _) = names.into_inner();
+    if !matches!(
+        array.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    ) {
+        return Err(PyTypeError::new_err(format!(
+            "names must be a string array, got {}",
+            array.data_type()
+        )));
+    }
+
+    let options = name_options(options);
+    let parsed = py
+        .detach(|| {
+            let strings = arrow_cast::cast(&array, &DataType::Utf8)?;
+            Ok::<_, ArrowError>(core::parse_names_batch(
+                strings.as_string::<i32>(),
+                recase_surname,
+                &options,
+            ))
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyArray::from_array_ref(Arc::new(parsed)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_orcid(text: Option<&str>) -> Option<String> {
+    core::normalize_orcid(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_ror(text: Option<&str>) -> Option<String> {
+    core::normalize_ror(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_institution_ids(text: Option<&str>) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let ids = core::extract_institution_ids(text);
+    (ids.ror, ids.grid, ids.ringgold)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, nicknames=false, phonetic=false))]
+fn compare_names(
+    a: Option<NameArg>,
+    b: Option<NameArg>,
+    nicknames: bool,
+    phonetic: bool,
+) -> (bool, bool, bool, bool, f64) {
+    let similarity =
+        core::compare_parsed_names(parse_name_arg(a), parse_name_arg(b), nicknames, phonetic);
+    (
+        similarity.exact_surname,
+        similarity.initials_compatible,
+        similarity.transliteration_match,
+        similarity.phonetic_match,
+        similarity.score,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, algorithm = "double_metaphone"))]
+fn phonetic_encode(text: &str, algorithm: &str) -> PyResult<Vec<String>> {
+    let algorithm = phonetic::PhoneticAlgorithm::from_name(algorithm).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "unknown phonetic algorithm: {algorithm}, expected double_metaphone or nysiis"
+        ))
+    })?;
+    Ok(phonetic::phonetic_encode(text, algorithm))
+}
+
+/// A `ParsedCitation` as a tuple of its fields, which the Python package wraps in a named tuple.
+type ParsedCitationTuple = (
+    Option<String>,
+    Vec<String>,
+    Option<i32>,
+    Option<String>,
+    Option<String>,
+);
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn parse_citation(text: Option<&str>) -> ParsedCitationTuple {
+    let citation = citations::parse_citation(text.unwrap_or_default());
+    (
+        citation.title,
+        citation.authors,
+        citation.year,
+        citation.container,
+        citation.doi,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn build_inverted_index(text: Option<&str>) -> Option<String> {
+    core::build_inverted_index(text)
+}
+
+/// Converts a JSON value into the equivalent Python object.
+fn json_to_py<'py>(py: Python<'py>, value: &serde_json::Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        serde_json::Value::Null => Ok(py.None().into_bound(py)),
+        serde_json::Value::Bool(b) => b.into_bound_py_any(py),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_bound_py_any(py),
+            (None, Some(u)) => u.into_bound_py_any(py),
+            _ => n.as_f64().into_bound_py_any(py),
+        },
+        serde_json::Value::String(s) => s.into_bound_py_any(py),
+        serde_json::Value::Array(values) => {
+            let list = PyList::empty(py);
+            for v in values {
+                list.append(json_to_py(py, v)?)?;
+            }
+            Ok(list.into_any())
+        },
+        serde_json::Value::Object(object) => {
+            let dict = PyDict::new(py);
+            for (k, v) in object {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            Ok(dict.into_any())
+        },
+    }
+}
+
+/// Converts a Python object made of dicts, lists, tuples, strings, numbers, booleans and None into the equivalent JSON
+/// value. Dates and other objects with an `isoformat` method are converted to their ISO 8601 strings.
+fn py_to_json(value: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    if value.is_none() {
+        return Ok(serde_json::Value::Null);
+    }
+    if let Ok(b) = value.cast::<PyBool>() {
+        return Ok(b.is_true().into());
+    }
+    if let Ok(i) = value.extract::<i64>() {
+        return Ok(i.into());
+    }
+    if let Ok(f) = value.cast::<PyFloat>() {
+        return Ok(f.value().into());
+    }
+    if let Ok(s) = value.extract::<String>() {
+        return Ok(s.into());
+    }
+    if let Ok(dict) = value.cast::<PyDict>() {
+        let mut object = serde_json::Map::new();
+        for (k, v) in dict {
+            object.insert(k.extract()?, py_to_json(&v)?);
+        }
+        return Ok(object.into());
+    }
+    if value.cast::<PyList>().is_ok() || value.cast::<PyTuple>().is_ok() {
+        return value
+            .try_iter()?
+            .map(|v| py_to_json(&v?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(serde_json::Value::Array);
+    }
+    if value.hasattr("isoformat")? {
+        return Ok(value.call_method0("isoformat")?.extract::<String>()?.into());
+    }
+    Err(PyTypeError::new_err(format!(
+        "cannot convert {} to JSON",
+        value.get_type().name()?
+    )))
+}
+
+/// Deserializes a Python object converted with `py_to_json`, raising ValueError naming `what` when it doesn't fit.
+fn deserialize_py<T: serde::de::DeserializeOwned>(
+    value: &Bound<'_, PyAny>,
+    what: &str,
+) -> PyResult<T> {
+    serde_json::from_value(py_to_json(value)?)
+        .map_err(|e| PyValueError::new_err(format!("invalid {what}: {e}")))
+}
+
+/// Streaming reader over the records of a plain or gzip-compressed JSONL file, yielding one dict per line, optionally
+/// skipping the works of a source that an incremental load has already seen.
+#[pyclass]
+struct JsonlReader {
+    records: Mutex<core::JsonlRecords>,
+    /// Called with the number of records read every `PROGRESS_INTERVAL` records and at the end of the file.
+    progress: Option<Py<PyAny>>,
+    /// Number of records read so far, or None once the end of the file has been reported.
+    read: Mutex<Option<usize>>,
+}
+
+#[pymethods]
+impl JsonlReader {
+    #[new]
+    #[pyo3(signature = (
+        path,
+        fields=None,
+        progress=None,
+        source=None,
+        updated_since=None,
+        created_since=None,
+    ))]
+    fn new(
+        path: PathBuf,
+        fields: Option<Vec<String>>,
+        progress: Option<Py<PyAny>>,
+        source: Option<&str>,
+        updated_since: Option<&str>,
+        created_since: Option<&str>,
+    ) -> PyResult<Self> {
+        let filter = transform::IncrementalFilter {
+            updated_since: high_water_mark("updated_since", updated_since)?,
+            created_since: high_water_mark("created_since", created_since)?,
+        };
+        let mut records = core::read_jsonl(&path, fields)?;
+        match source {
+            Some(name) => {
+                let source = transform::WorkSource::from_name(name)
+                    .ok_or_else(|| PyValueError::new_err(format!("unknown source: {name}")))?;
+                records = records.filter(move |record| filter.keeps_record(source, record));
+            },
+            None if updated_since.is_some() || created_since.is_some() => {
+                return Err(PyValueError::new_err(
+                    "source is required with updated_since or created_since",
+                ));
+            },
+            None => {},
+        }
+        Ok(Self {
+            records: Mutex::new(records),
+            progress,
+            read: Mutex::new(Some(0)),
+        })
+    }
+
+    /// The number of records skipped by `updated_since` or `created_since` so far.
+    #[getter]
+    fn skipped(&self) -> usize {
+        self.records.lock().unwrap().skipped()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        // Read and parse without the GIL, only converting to Python objects with it
+        let record = py.detach(|| self.records.lock().unwrap().next());
+        self.report_progress(py, record.is_none())?;
+        match record {
+            Some(Ok(record)) => json_to_py(py, &record).map(Some),
+            Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData => {
+                Err(PyValueError::new_err(e.to_string()))
+            },
+            Some(Err(e)) => Err(e.into()),
+            None => Ok(None),
+        }
+    }
+}
+
+impl JsonlReader {
+    /// Counts a record, or the end of the file when `finished`, and calls the progress callback when it is due.
+    fn report_progress(&self, py: Python<'_>, finished: bool) -> PyResult<()> {
+        let Some(progress) = &self.progress else {
+            return Ok(());
+        };
+        let mut read = self.read.lock().unwrap();
+        let Some(count) = *read else {
+            return Ok(());
+        };
+        let (processed, due) = if finished {
+            *read = None;
+            (count, count == 0 || count % PROGRESS_INTERVAL != 0)
+        } else {
+            *read = Some(count + 1);
+            (count + 1, (count + 1) % PROGRESS_INTERVAL == 0)
+        };
+        if due {
+            progress.call1(py, (processed, None::<usize>))?;
+        }
+        Ok(())
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, form = "NFC", fold_diacritics = false, case_fold = false))]
+fn normalize_text(
+    text: Option<&str>,
+    form: &str,
+    fold_diacritics: bool,
+    case_fold: bool,
+) -> PyResult<Option<String>> {
+    let form = core::NormalizationForm::from_name(form).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "form must be one of NFC, NFD, NFKC or NFKD, got {form}"
+        ))
+    })?;
+    Ok(text.map(|text| core::normalize_text(text, form, fold_diacritics, case_fold)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn clean_text(text: Option<&str>) -> Option<String> {
+    text.map(|text| core::clean_text(text).into_owned())
+}
+
+/// Applies a similarity function pairwise over two equal-length columns, with the GIL released.
+fn similarity_many(
+    py: Python<'_>,
+    similarity: impl Fn(&str, &str) -> f64 + Send + Sync,
+    a: Vec<Option<String>>,
+    b: Vec<Option<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    if a.len() != b.len() {
+        return Err(PyValueError::new_err(format!(
+            "a and b must have the same length, got {} and {}",
+            a.len(),
+            b.len()
+        )));
+    }
+    run_parallel(py, num_threads, || similarity::pairwise(similarity, &a, &b))
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    similarity::jaro_winkler(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, num_threads=None))]
+fn jaro_winkler_many(
+    py: Python<'_>,
+    a: Vec<Option<String>>,
+    b: Vec<Option<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    similarity_many(py, similarity::jaro_winkler, a, b, num_threads)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn normalized_levenshtein(a: &str, b: &str) -> f64 {
+    similarity::normalized_levenshtein(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, num_threads=None))]
+fn normalized_levenshtein_many(
+    py: Python<'_>,
+    a: Vec<Option<String>>,
+    b: Vec<Option<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    similarity_many(py, similarity::normalized_levenshtein, a, b, num_threads)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    similarity::token_sort_ratio(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, num_threads=None))]
+fn token_sort_ratio_many(
+    py: Python<'_>,
+    a: Vec<Option<String>>,
+    b: Vec<Option<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    similarity_many(py, similarity::token_sort_ratio, a, b, num_threads)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn token_set_ratio(a: &str, b: &str) -> f64 {
+    similarity::token_set_ratio(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, num_threads=None))]
+fn token_set_ratio_many(
+    py: Python<'_>,
+    a: Vec<Option<String>>,
+    b: Vec<Option<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    similarity_many(py, similarity::token_set_ratio, a, b, num_threads)
+}
+
+fn check_shingle_size(shingle_size: usize) -> PyResult<usize> {
+    if shingle_size == 0 {
+        return Err(PyValueError::new_err("shingle_size must be greater than 0"));
+    }
+    Ok(shingle_size)
+}
+
+#[pyfunction]
+#[pyo3(signature = (short_text, long_text, shingle_size = 3))]
+fn containment_score(short_text: &str, long_text: &str, shingle_size: usize) -> PyResult<f64> {
+    let shingle_size = check_shingle_size(shingle_size)?;
+    Ok(similarity::containment_score(
+        short_text,
+        long_text,
+        shingle_size,
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (short_texts, long_texts, shingle_size = 3, num_threads = None))]
+fn containment_score_many(
+    py: Python<'_>,
+    short_texts: Vec<Option<String>>,
+    long_texts: Vec<Option<String>>,
+    shingle_size: usize,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    let shingle_size = check_shingle_size(shingle_size)?;
+    similarity_many(
+        py,
+        |a, b| similarity::containment_score(a, b, shingle_size),
+        short_texts,
+        long_texts,
+        num_threads,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn detect_language(text: Option<&str>) -> Option<(String, f64)> {
+    core::detect_language(text?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, num_threads=None))]
+fn detect_language_many(
+    py: Python<'_>,
+    texts: Vec<Option<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<(String, f64)>>> {
+    run_parallel(py, num_threads, || core::detect_language_many(&texts))
+}
+
+/// Converts a transformed work or DMP into a Python dict, raising ValueError when the record was not valid JSON.
+fn record_to_py<'py, T: serde::Serialize>(
+    py: Python<'py>,
+    record: serde_json::Result<Option<T>>,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let record = record.map_err(|e| PyValueError::new_err(format!("invalid json: {e}")))?;
+    record
+        .map(|record| {
+            let value =
+                serde_json::to_value(record).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            json_to_py(py, &value)
+        })
+        .transpose()
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_bytes))]
+fn transform_crossref_work<'py>(
+    py: Python<'py>,
+    json_bytes: &[u8],
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let work = transform::transform_crossref_work(json_bytes);
+    record_to_py(py, work)
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_bytes, include_xpac = false))]
+fn transform_openalex_work<'py>(
+    py: Python<'py>,
+    json_bytes: &[u8],
+    include_xpac: bool,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let work = transform::transform_openalex_work(json_bytes, include_xpac);
+    record_to_py(py, work)
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_bytes))]
+fn transform_datacite_record<'py>(
+    py: Python<'py>,
+    json_bytes: &[u8],
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let work = transform::transform_datacite_record(json_bytes);
+    record_to_py(py, work)
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_bytes))]
+fn transform_dmp<'py>(py: Python<'py>, json_bytes: &[u8]) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let dmp = transform::transform_dmp(json_bytes);
+    record_to_py(py, dmp)
+}
+
+/// Parses the date of an incremental load's high-water mark, raising ValueError naming the argument when it isn't a
+/// date.
+fn high_water_mark(name: &str, date: Option<&str>) -> PyResult<Option<core::ParsedDate>> {
+    date.map(|date| {
+        core::parse_date(Some(date))
+            .ok_or_else(|| PyValueError::new_err(format!("invalid {name}: {date}")))
+    })
+    .transpose()
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    source,
+    json_records,
+    include_xpac = false,
+    collect_errors = false,
+    num_threads = None,
+    progress = None,
+    updated_since = None,
+    created_since = None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn transform_works_many<'py>(
+    py: Python<'py>,
+    source: &str,
+    json_records: Vec<Vec<u8>>,
+    include_xpac: bool,
+    collect_errors: bool,
+    num_threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+    updated_since: Option<&str>,
+    created_since: Option<&str>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let source = transform::WorkSource::from_name(source)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown source: {source}")))?;
+    let filter = transform::IncrementalFilter {
+        updated_since: high_water_mark("updated_since", updated_since)?,
+        created_since: high_water_mark("created_since", created_since)?,
+    };
+
+    // Transform in chunks between progress calls, or all at once without a callback
+    let total = json_records.len();
+    let chunk_size = match progress {
+        Some(_) => PROGRESS_INTERVAL,
+        None => total.max(1),
+    };
+    let mut callback_error = None;
+    let (works, errors, skipped) = run_parallel(py, num_threads, || {
+        let mut works = Vec::with_capacity(total);
+        let mut errors = Vec::new();
+        let mut skipped = Vec::new();
+        for chunk in json_records.chunks(chunk_size) {
+            let (chunk_works, chunk_errors, chunk_skipped) =
+                transform::transform_works_many(source, chunk, include_xpac, &filter);
+            let offset = works.len();
+            errors.extend(chunk_errors.into_iter().map(|error| core::ParseError {
+                row: offset + error.row,
+                ..error
+            }));
+            skipped.extend(chunk_skipped.into_iter().map(|row| offset + row));
+            works.extend(chunk_works);
+            if call_progress(
+                progress.as_ref(),
+                works.len(),
+                Some(total),
+                &mut callback_error,
+            )
+            .is_err()
+            {
+                break;
+            }
+        }
+        (works, errors, skipped)
+    })?;
+    if let Some(e) = callback_error {
+        return Err(e);
+    }
+    if !collect_errors {
+        if let Some(error) = errors.iter().find(|error| error.field.is_none()) {
+            return Err(PyValueError::new_err(format!(
+                "record {}: {}",
+                error.row, error.message
+            )));
+        }
+        for error in &errors {
+            warn!(
+                "transform_works_many: record {}: invalid {}: {}",
+                error.row,
+                error.field.as_deref().unwrap_or_default(),
+                error.message
+            );
+        }
+    }
+
+    let works = works
+        .into_iter()
+        .map(|work| record_to_py(py, Ok(work)))
+        .collect::<PyResult<Vec<_>>>()?;
+    if collect_errors {
+        (works, parse_error_tuples(errors), skipped).into_bound_py_any(py)
+    } else {
+        works.into_bound_py_any(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (source, raw_type))]
+fn normalize_work_type(source: &str, raw_type: Option<&str>) -> PyResult<Option<&'static str>> {
+    let source = transform::WorkSource::from_name(source)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown source: {source}")))?;
+    Ok(raw_type
+        .filter(|raw_type| !raw_type.trim().is_empty())
+        .map(|raw_type| transform::normalize_work_type(source, raw_type).as_str()))
+}
+
+#[pyclass(frozen)]
+struct FieldSelectors {
+    selectors: Vec<selectors::Selector>,
+}
+
+#[pymethods]
+impl FieldSelectors {
+    #[new]
+    #[pyo3(signature = (selectors))]
+    fn new(selectors: Vec<String>) -> PyResult<Self> {
+        Ok(Self {
+            selectors: compile_selectors(&selectors)?,
+        })
+    }
+
+    fn __len__(&self) -> usize {
+        self.selectors.len()
+    }
+}
+
+/// Compiles selector paths, raising ValueError for a malformed one.
+fn compile_selectors(paths: &[String]) -> PyResult<Vec<selectors::Selector>> {
+    paths
+        .iter()
+        .map(|path| selectors::Selector::parse(path).map_err(PyValueError::new_err))
+        .collect()
+}
+
+/// Selectors passed from Python: compiled `FieldSelectors`, or paths compiled for a single call.
+#[derive(FromPyObject)]
+enum SelectorsArg<'py> {
+    Compiled(Bound<'py, FieldSelectors>),
+    Paths(Vec<String>),
+}
+
+impl SelectorsArg<'_> {
+    fn compile(&self) -> PyResult<Cow<'_, [selectors::Selector]>> {
+        match self {
+            SelectorsArg::Compiled(selectors) => Ok(Cow::Borrowed(&selectors.get().selectors)),
+            SelectorsArg::Paths(paths) => compile_selectors(paths).map(Cow::Owned),
+        }
+    }
+}
+
+/// Converts the values selected from a record into a Python list, with None for missing values.
+fn fields_to_py<'py>(
+    py: Python<'py>,
+    fields: &[Option<serde_json::Value>],
+) -> PyResult<Bound<'py, PyList>> {
+    let list = PyList::empty(py);
+    for field in fields {
+        match field {
+            Some(value) => list.append(json_to_py(py, value)?)?,
+            None => list.append(py.None())?,
+        }
+    }
+    Ok(list)
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_bytes, selectors))]
+fn extract_fields<'py>(
+    py: Python<'py>,
+    json_bytes: &[u8],
+    selectors: SelectorsArg<'py>,
+) -> PyResult<Bound<'py, PyList>> {
+    let selectors = selectors.compile()?;
+    let record: serde_json::Value = serde_json::from_slice(json_bytes)
+        .map_err(|e| PyValueError::new_err(format!("invalid json: {e}")))?;
+    fields_to_py(py, &selectors::extract_fields(&record, &selectors))
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_records, selectors, num_threads = None))]
+fn extract_fields_many<'py>(
+    py: Python<'py>,
+    json_records: Vec<Vec<u8>>,
+    selectors: SelectorsArg<'py>,
+    num_threads: Option<usize>,
+) -> PyResult<Bound<'py, PyList>> {
+    let selectors = selectors.compile()?;
+    let extracted = run_parallel(py, num_threads, || {
+        selectors::extract_fields_many(&json_records, &selectors)
+    })?;
+    let list = PyList::empty(py);
+    for (row, fields) in extracted.into_iter().enumerate() {
+        let fields = fields
+            .map_err(|e| PyValueError::new_err(format!("record {row}: invalid json: {e}")))?;
+        list.append(fields_to_py(py, &fields)?)?;
+    }
+    Ok(list)
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    input_path,
+    output_path,
+    key_selector,
+    dedupe = false,
+    max_chunk_bytes = sort::DEFAULT_MAX_CHUNK_BYTES,
+    temp_dir = None,
+    num_threads = None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn external_sort(
+    py: Python<'_>,
+    input_path: PathBuf,
+    output_path: PathBuf,
+    key_selector: &str,
+    dedupe: bool,
+    max_chunk_bytes: usize,
+    temp_dir: Option<PathBuf>,
+    num_threads: Option<usize>,
+) -> PyResult<(usize, usize)> {
+    if max_chunk_bytes == 0 {
+        return Err(PyValueError::new_err(
+            "max_chunk_bytes must be greater than 0",
+        ));
+    }
+    let key = selectors::Selector::parse(key_selector).map_err(PyValueError::new_err)?;
+    let stats = run_parallel(py, num_threads, || {
+        sort::external_sort(
+            &input_path,
+            &output_path,
+            &key,
+            dedupe,
+            max_chunk_bytes,
+            temp_dir.as_deref(),
+        )
+    })?
+    .map_err(|e| match e.kind() {
+        io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+        _ => e.into(),
+    })?;
+    Ok((stats.written, stats.duplicates))
+}
+
+#[pyclass]
+struct FunderRegistry {
+    registry: funders::FunderRegistry,
+}
+
+#[pymethods]
+impl FunderRegistry {
+    #[new]
+    #[pyo3(signature = (entries))]
+    fn new(py: Python<'_>, entries: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            registry: py.detach(|| funders::FunderRegistry::new(entries)),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.registry.len()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (name))]
+fn normalize_funder_name(name: Option<&str>) -> Option<String> {
+    funders::normalize_funder_name(name)
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, registry, threshold = 0.9))]
+fn match_funder(
+    name: Option<&str>,
+    registry: PyRef<'_, FunderRegistry>,
+    threshold: f64,
+) -> Option<(String, f64)> {
+    let registry = &registry.registry;
+    funders::match_funder(name, registry, threshold)
+}
+
+/// Institution entries from IDs mapped to names and IDs mapped to acronyms.
+fn institution_entries(
+    names: HashMap<String, Vec<String>>,
+    acronyms: Option<HashMap<String, Vec<String>>>,
+) -> Vec<institutions::InstitutionEntry> {
+    let names = names
+        .into_iter()
+        .map(|(id, names)| institutions::InstitutionEntry {
+            id,
+            names,
+            acronyms: Vec::new(),
+        });
+    let acronyms = acronyms
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(id, acronyms)| institutions::InstitutionEntry {
+            id,
+            names: Vec::new(),
+            acronyms,
+        });
+    names.chain(acronyms).collect()
+}
+
+#[pyclass(frozen)]
+struct InstitutionIndex {
+    index: institutions::InstitutionIndex,
+}
+
+#[pymethods]
+impl InstitutionIndex {
+    #[new]
+    #[pyo3(signature = (entries, acronyms = None))]
+    fn new(
+        py: Python<'_>,
+        entries: HashMap<String, Vec<String>>,
+        acronyms: Option<HashMap<String, Vec<String>>>,
+    ) -> Self {
+        let entries = institution_entries(entries, acronyms);
+        Self {
+            index: py.detach(|| institutions::InstitutionIndex::new(entries)),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path, aliases = None, acronyms = None))]
+    fn from_ror_dump(
+        py: Python<'_>,
+        path: PathBuf,
+        aliases: Option<HashMap<String, Vec<String>>>,
+        acronyms: Option<HashMap<String, Vec<String>>>,
+    ) -> PyResult<Self> {
+        let extra = institution_entries(aliases.unwrap_or_default(), acronyms);
+        let index = py
+            .detach(|| institutions::InstitutionIndex::from_ror_dump(&path, extra))
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+                _ => e.into(),
+            })?;
+        Ok(Self { index })
+    }
+
+    #[getter]
+    fn num_institutions(&self) -> usize {
+        self.index.num_institutions()
+    }
+
+    fn __len__(&self) -> usize {
+        self.index.len()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (name))]
+fn normalize_institution_name(name: Option<&str>) -> Option<String> {
+    institutions::normalize_institution_name(name)
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, index, threshold = 0.9))]
+fn match_institution(
+    name: Option<&str>,
+    index: PyRef<'_, InstitutionIndex>,
+    threshold: f64,
+) -> Option<(String, f64)> {
+    let index = &index.index;
+    institutions::match_institution(name, index, threshold)
+}
+
+#[pyfunction]
+#[pyo3(signature = (names, index, threshold = 0.9, num_threads = None))]
+fn match_institution_many(
+    py: Python<'_>,
+    names: Vec<Option<String>>,
+    index: PyRef<'_, InstitutionIndex>,
+    threshold: f64,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<(String, f64)>>> {
+    let index = &index.index;
+    run_parallel(py, num_threads, || {
+        institutions::match_institution_many(&names, index, threshold)
+    })
+}
+
+/// The values of a key of a lookup table, a string or a list of strings.
+#[derive(FromPyObject)]
+enum LookupValuesArg {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Declares its module so that the table can be pickled; see `__reduce__`.
+#[pyclass(frozen, module = "dmpworks.rust._internal")]
+struct LookupTable {
+    table: lookup::LookupTable,
+    path: PathBuf,
+}
+
+/// Maps errors opening or writing a lookup table to Python exceptions, with invalid files as ValueError.
+fn lookup_table_error(error: io::Error) -> PyErr {
+    match error.kind() {
+        io::ErrorKind::InvalidData => PyValueError::new_err(error.to_string()),
+        _ => error.into(),
+    }
+}
+
+#[pymethods]
+impl LookupTable {
+    #[new]
+    #[pyo3(signature = (path))]
+    fn new(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let table = py
+            .detach(|| lookup::LookupTable::open(&path))
+            .map_err(lookup_table_error)?;
+        Ok(Self { table, path })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (path, entries, normalization = "exact"))]
+    fn build(
+        py: Python<'_>,
+        path: PathBuf,
+        entries: &Bound<'_, PyMapping>,
+        normalization: &str,
+    ) -> PyResult<Self> {
+        let normalization =
+            lookup::KeyNormalization::from_name(normalization).ok_or_else(|| {
+                PyValueError::new_err(format!("unknown key normalization: {normalization}"))
+            })?;
+        // Read in the mapping's order, so that the values of keys normalizing alike are merged in a stable order.
+        let entries = entries
+            .items()?
+            .iter()
+            .map(|item| {
+                let (key, values): (String, LookupValuesArg) = item.extract()?;
+                Ok(match values {
+                    LookupValuesArg::One(value) => (key, vec![value]),
+                    LookupValuesArg::Many(values) => (key, values),
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let table = py
+            .detach(|| {
+                lookup::LookupTable::write(&path, entries, normalization)?;
+                lookup::LookupTable::open(&path)
+            })
+            .map_err(lookup_table_error)?;
+        Ok(Self { table, path })
+    }
+
+    #[getter]
+    fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    #[getter]
+    fn normalization(&self) -> &'static str {
+        self.table.normalization().as_str()
+    }
+
+    #[pyo3(signature = (key, default = None))]
+    fn get(&self, key: &str, default: Option<String>) -> Option<String> {
+        self.table
+            .get(key)
+            .first()
+            .map(|value| value.to_string())
+            .or(default)
+    }
+
+    #[pyo3(signature = (key))]
+    fn get_all(&self, key: &str) -> Vec<String> {
+        self.table.get(key).into_iter().map(String::from).collect()
+    }
+
+    #[pyo3(signature = (keys))]
+    fn get_many(&self, py: Python<'_>, keys: Vec<Option<String>>) -> Vec<Option<String>> {
+        py.detach(|| {
+            keys.iter()
+                .map(|key| Some(self.table.get(key.as_deref()?).first()?.to_string()))
+                .collect()
+        })
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        !self.table.get(key).is_empty()
+    }
+
+    fn __len__(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Pickles the table as its path, so that worker processes it is sent to map the same file.
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> (Bound<'py, pyo3::types::PyType>, (PathBuf,)) {
+        (slf.get_type(), (slf.get().path.clone(),))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LookupTable(path={:?}, normalization={:?}, len={})",
+            self.path.display().to_string(),
+            self.table.normalization().as_str(),
+            self.table.len()
+        )
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn parse_affiliation(
+    text: Option<&str>,
+) -> (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+) {
+    let parsed = core::parse_affiliation(text);
+    (
+        parsed.institution,
+        parsed.department,
+        parsed.city,
+        parsed.country,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_country(text: Option<&str>) -> Option<&'static str> {
+    core::normalize_country(text?)
+}
+
+#[pyclass(frozen)]
+struct TokenizerOptions {
+    options: core::TokenizerOptions,
+}
+
+/// Checks that an n-gram size range is non-empty and starts at one or more.
+fn check_ngram_range(name: &str, (min, max): (usize, usize)) -> PyResult<(usize, usize)> {
+    if min == 0 || min > max {
+        return Err(PyValueError::new_err(format!(
+            "{name} must be a (min, max) range with 1 <= min <= max, got ({min}, {max})"
+        )));
+    }
+    Ok((min, max))
+}
+
+#[pymethods]
+impl TokenizerOptions {
+    #[new]
+    #[pyo3(signature = (fold = true, stop_words = None, min_length = 1, word_ngrams = (1, 1), char_ngrams = None))]
+    fn new(
+        fold: bool,
+        stop_words: Option<Vec<String>>,
+        min_length: usize,
+        word_ngrams: (usize, usize),
+        char_ngrams: Option<(usize, usize)>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            options: core::TokenizerOptions {
+                fold,
+                stop_words: stop_words.unwrap_or_default(),
+                min_length,
+                word_ngrams: check_ngram_range("word_ngrams", word_ngrams)?,
+                char_ngrams: char_ngrams
+                    .map(|range| check_ngram_range("char_ngrams", range))
+                    .transpose()?,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, options = None))]
+fn tokenize(text: &str, options: Option<&TokenizerOptions>) -> Vec<String> {
+    let default = core::TokenizerOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    core::tokenize_with_options(text, options)
+}
+
+#[pyclass(frozen)]
+struct Bm25Index {
+    index: bm25::Bm25Index,
+}
+
+#[pymethods]
+impl Bm25Index {
+    #[new]
+    #[pyo3(signature = (texts, k1 = bm25::DEFAULT_K1, b = bm25::DEFAULT_B, options = None))]
+    fn new(
+        py: Python<'_>,
+        texts: Vec<Option<String>>,
+        k1: f64,
+        b: f64,
+        options: Option<&TokenizerOptions>,
+    ) -> PyResult<Self> {
+        if k1.is_nan() || k1 < 0.0 {
+            return Err(PyValueError::new_err(format!("k1 must be >= 0, got {k1}")));
+        }
+        if !(0.0..=1.0).contains(&b) {
+            return Err(PyValueError::new_err(format!(
+                "b must be between 0 and 1, got {b}"
+            )));
+        }
+        let options = options
+            .map(|options| options.options.clone())
+            .unwrap_or_default();
+        Ok(Self {
+            index: py.detach(|| bm25::Bm25Index::new(&texts, options, k1, b)),
+        })
+    }
+
+    fn __len__(&self) -> usize {
+        self.index.len()
+    }
+
+    #[pyo3(signature = (query))]
+    fn score(&self, py: Python<'_>, query: &str) -> Vec<f64> {
+        py.detach(|| self.index.score(query))
+    }
+
+    #[pyo3(signature = (query, top_k = None))]
+    fn rank(&self, py: Python<'_>, query: &str, top_k: Option<usize>) -> Vec<(usize, f64)> {
+        py.detach(|| self.index.rank(query, top_k))
+    }
+}
+
+#[pyclass]
+struct IdfTable {
+    table: tfidf::IdfTable,
+}
+
+#[pymethods]
+impl IdfTable {
+    #[new]
+    #[pyo3(signature = (texts = None, options = None, num_threads = None))]
+    fn new(
+        py: Python<'_>,
+        texts: Option<Vec<Option<String>>>,
+        options: Option<&TokenizerOptions>,
+        num_threads: Option<usize>,
+    ) -> PyResult<Self> {
+        let options = options
+            .map(|options| options.options.clone())
+            .unwrap_or_default();
+        let mut table = tfidf::IdfTable::new(options);
+        if let Some(texts) = texts {
+            run_parallel(py, num_threads, || table.update(&texts))?;
+        }
+        Ok(Self { table })
+    }
+
+    #[getter]
+    fn num_docs(&self) -> u64 {
+        self.table.num_docs()
+    }
+
+    #[getter]
+    fn num_terms(&self) -> usize {
+        self.table.num_terms()
+    }
+
+    #[pyo3(signature = (texts, num_threads = None))]
+    fn update(
+        &mut self,
+        py: Python<'_>,
+        texts: Vec<Option<String>>,
+        num_threads: Option<usize>,
+    ) -> PyResult<()> {
+        let table = &mut self.table;
+        run_parallel(py, num_threads, || table.update(&texts))
+    }
+
+    #[pyo3(signature = (path, fields = vec!["title".to_string(), "abstract".to_string()], num_threads = None))]
+    fn update_from_jsonl(
+        &mut self,
+        py: Python<'_>,
+        path: PathBuf,
+        fields: Vec<String>,
+        num_threads: Option<usize>,
+    ) -> PyResult<usize> {
+        let table = &mut self.table;
+        run_parallel(py, num_threads, || table.update_from_jsonl(&path, &fields))?.map_err(|e| {
+            match e.kind() {
+                io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+                _ => e.into(),
+            }
+        })
+    }
+
+    #[pyo3(signature = (term))]
+    fn idf(&self, term: &str) -> f64 {
+        self.table.idf(term)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp_text, work_text, idf_table))]
+fn tfidf_cosine(dmp_text: &str, work_text: &str, idf_table: PyRef<'_, IdfTable>) -> f64 {
+    let table = &idf_table.table;
+    tfidf::tfidf_cosine(dmp_text, work_text, table)
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp_texts, work_texts, idf_table, num_threads = None))]
+fn tfidf_cosine_many(
+    py: Python<'_>,
+    dmp_texts: Vec<Option<String>>,
+    work_texts: Vec<Option<String>>,
+    idf_table: PyRef<'_, IdfTable>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<f64>>> {
+    let table = &idf_table.table;
+    similarity_many(
+        py,
+        |a, b| tfidf::tfidf_cosine(a, b, table),
+        dmp_texts,
+        work_texts,
+        num_threads,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (ground_truth, results, ks = vec![10, 20, 100], num_threads = None))]
+fn ranking_metrics<'py>(
+    py: Python<'py>,
+    ground_truth: HashMap<String, HashMap<String, f64>>,
+    results: HashMap<String, Vec<String>>,
+    ks: Vec<usize>,
+    num_threads: Option<usize>,
+) -> PyResult<(Bound<'py, PyDict>, Bound<'py, PyDict>)> {
+    if ks.is_empty() || ks.contains(&0) {
+        return Err(PyValueError::new_err(format!(
+            "ks must be a non-empty list of cutoffs >= 1, got {ks:?}"
+        )));
+    }
+    let metrics = run_parallel(py, num_threads, || {
+        eval::ranking_metrics(&ground_truth, &results, &ks)
+    })?;
+
+    let per_query = PyDict::new(py);
+    for (query, query_metrics) in metrics.per_query {
+        per_query.set_item(query, query_metrics.into_py_dict(py)?)?;
+    }
+    Ok((metrics.mean.into_py_dict(py)?, per_query))
+}
+
+/// Checks that scored pairs have a label each and scores that aren't NaN.
+fn check_scored_pairs(scores: &[f64], labels: &[bool]) -> PyResult<()> {
+    if scores.len() != labels.len() {
+        return Err(PyValueError::new_err(format!(
+            "scores and labels must have the same length, got {} and {}",
+            scores.len(),
+            labels.len()
+        )));
+    }
+    if let Some(i) = scores.iter().position(|score| score.is_nan()) {
+        return Err(PyValueError::new_err(format!("score {i} is NaN")));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+#[pyo3(signature = (scores, labels))]
+fn pr_curve(
+    py: Python<'_>,
+    scores: Vec<f64>,
+    labels: Vec<bool>,
+) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    check_scored_pairs(&scores, &labels)?;
+    let curve = py.detach(|| eval::pr_curve(&scores, &labels));
+    Ok((curve.thresholds, curve.precision, curve.recall))
+}
+
+#[pyfunction]
+#[pyo3(signature = (scores, labels, target_precision))]
+fn choose_threshold(
+    py: Python<'_>,
+    scores: Vec<f64>,
+    labels: Vec<bool>,
+    target_precision: f64,
+) -> PyResult<Option<(f64, f64, f64)>> {
+    check_scored_pairs(&scores, &labels)?;
+    if !(0.0..=1.0).contains(&target_precision) {
+        return Err(PyValueError::new_err(format!(
+            "target_precision must be between 0 and 1, got {target_precision}"
+        )));
+    }
+    Ok(py.detach(|| eval::choose_threshold(&scores, &labels, target_precision)))
+}
+
+#[pyclass(frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
+struct GroundTruthRecord {
+    record: ground_truth::GroundTruthRecord,
+}
+
+#[pymethods]
+impl GroundTruthRecord {
+    #[new]
+    #[pyo3(signature = (dmp_doi, work_doi, status, reviewer = None))]
+    fn new(dmp_doi: &str, work_doi: &str, status: &str, reviewer: Option<&str>) -> PyResult<Self> {
+        let record = ground_truth::GroundTruthRecord::new(
+            Some(dmp_doi),
+            Some(work_doi),
+            Some(status),
+            reviewer,
+            false,
+        )
+        .map_err(|(field, message)| PyValueError::new_err(format!("{field}: {message}")))?;
+        Ok(Self { record })
+    }
+
+    #[getter]
+    fn dmp_doi(&self) -> &str {
+        &self.record.dmp_doi
+    }
+
+    #[getter]
+    fn work_doi(&self) -> &str {
+        &self.record.work_doi
+    }
+
+    #[getter]
+    fn status(&self) -> &'static str {
+        self.record.status.as_str()
+    }
+
+    #[getter]
+    fn reviewer(&self) -> Option<&str> {
+        self.record.reviewer.as_deref()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GroundTruthRecord(dmp_doi={:?}, work_doi={:?}, status={:?}, reviewer={})",
+            self.record.dmp_doi,
+            self.record.work_doi,
+            self.record.status.as_str(),
+            self.record
+                .reviewer
+                .as_ref()
+                .map_or("None".to_string(), |reviewer| format!("{reviewer:?}")),
+        )
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("dmp_doi", &self.record.dmp_doi)?;
+        dict.set_item("work_doi", &self.record.work_doi)?;
+        dict.set_item("status", self.record.status.as_str())?;
+        dict.set_item("reviewer", self.record.reviewer.as_deref())?;
+        Ok(dict)
+    }
+}
+
+/// Most invalid rows listed in the error raised by `load_ground_truth`.
+const MAX_LISTED_ERRORS: usize = 10;
+
+#[pyfunction]
+#[pyo3(signature = (path, require_reviewer = false))]
+fn load_ground_truth(
+    py: Python<'_>,
+    path: PathBuf,
+    require_reviewer: bool,
+) -> PyResult<Vec<GroundTruthRecord>> {
+    let (records, errors) = py
+        .detach(|| ground_truth::load_ground_truth(&path, require_reviewer))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => {
+                PyValueError::new_err(e.to_string())
+            },
+            _ => e.into(),
+        })?;
+    if !errors.is_empty() {
+        let mut lines: Vec<String> = errors
+            .iter()
+            .take(MAX_LISTED_ERRORS)
+            .map(|error| match &error.field {
+                Some(field) => format!("line {}: {field}: {}", error.row, error.message),
+                None => format!("line {}: {}", error.row, error.message),
+            })
+            .collect();
+        if errors.len() > MAX_LISTED_ERRORS {
+            lines.push(format!("and {} more", errors.len() - MAX_LISTED_ERRORS));
+        }
+        return Err(PyValueError::new_err(format!(
+            "{} invalid rows in ground truth file {}:\n{}",
+            errors.len(),
+            path.display(),
+            lines.join("\n")
+        )));
+    }
+    Ok(records
+        .into_iter()
+        .map(|record| GroundTruthRecord { record })
+        .collect())
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, records))]
+fn save_ground_truth(
+    py: Python<'_>,
+    path: PathBuf,
+    records: Vec<PyRef<'_, GroundTruthRecord>>,
+) -> PyResult<()> {
+    let records: Vec<ground_truth::GroundTruthRecord> =
+        records.iter().map(|record| record.record.clone()).collect();
+    py.detach(|| ground_truth::save_ground_truth(&path, &records))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidInput => PyValueError::new_err(e.to_string()),
+            _ => e.into(),
+        })
+}
+
+/// A label of an item by an annotator, a string or a number.
+#[derive(FromPyObject)]
+enum AnnotationArg {
+    Text(String),
+    Number(f64),
+}
+
+impl From<AnnotationArg> for eval::Annotation {
+    fn from(arg: AnnotationArg) -> Self {
+        match arg {
+            AnnotationArg::Text(text) => eval::Annotation::Text(text),
+            AnnotationArg::Number(value) => eval::Annotation::Number(value),
+        }
+    }
+}
+
+fn annotations(labels: Vec<Option<AnnotationArg>>) -> Vec<Option<eval::Annotation>> {
+    labels
+        .into_iter()
+        .map(|label| label.map(eval::Annotation::from))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (labels_a, labels_b))]
+fn cohen_kappa(
+    labels_a: Vec<Option<AnnotationArg>>,
+    labels_b: Vec<Option<AnnotationArg>>,
+) -> PyResult<Option<f64>> {
+    if labels_a.len() != labels_b.len() {
+        return Err(PyValueError::new_err(format!(
+            "labels_a and labels_b must have the same length, got {} and {}",
+            labels_a.len(),
+            labels_b.len()
+        )));
+    }
+    Ok(eval::cohen_kappa(
+        &annotations(labels_a),
+        &annotations(labels_b),
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (label_matrix, level = "nominal"))]
+fn krippendorff_alpha(
+    py: Python<'_>,
+    label_matrix: Vec<Vec<Option<AnnotationArg>>>,
+    level: &str,
+) -> PyResult<Option<f64>> {
+    let level = eval::MeasurementLevel::from_name(level)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown level: {level}")))?;
+    if let Some(row) = label_matrix
+        .iter()
+        .position(|row| row.len() != label_matrix[0].len())
+    {
+        return Err(PyValueError::new_err(format!(
+            "every row of label_matrix must have the same length, got {} and {} in row {row}",
+            label_matrix[0].len(),
+            label_matrix[row].len()
+        )));
+    }
+    let matrix: Vec<Vec<Option<eval::Annotation>>> =
+        label_matrix.into_iter().map(annotations).collect();
+    py.detach(|| eval::krippendorff_alpha(&matrix, level))
+        .map_err(PyValueError::new_err)
+}
+
+#[pyclass(frozen)]
+struct SamplingStrategy {
+    strategy: sampler::SamplingStrategy,
+}
+
+/// Checks that band edges are finite and strictly ascending.
+fn check_band_edges(name: &str, edges: Vec<f64>) -> PyResult<Vec<f64>> {
+    if edges.iter().any(|edge| !edge.is_finite()) || edges.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(PyValueError::new_err(format!(
+            "{name} must be finite and strictly ascending, got {edges:?}"
+        )));
+    }
+    Ok(edges)
+}
+
+#[pymethods]
+impl SamplingStrategy {
+    #[new]
+    #[pyo3(signature = (
+        per_stratum = 50,
+        by_work_type = true,
+        year_gap_edges = vec![0.0, 2.0, 5.0],
+        similarity_edges = vec![0.25, 0.5, 0.75],
+        seed = 0,
+    ))]
+    fn new(
+        per_stratum: usize,
+        by_work_type: bool,
+        year_gap_edges: Vec<f64>,
+        similarity_edges: Vec<f64>,
+        seed: u64,
+    ) -> PyResult<Self> {
+        if per_stratum == 0 {
+            return Err(PyValueError::new_err("per_stratum must be greater than 0"));
+        }
+        Ok(Self {
+            strategy: sampler::SamplingStrategy {
+                per_stratum,
+                by_work_type,
+                year_gap_edges: check_band_edges("year_gap_edges", year_gap_edges)?,
+                similarity_edges: check_band_edges("similarity_edges", similarity_edges)?,
+                seed,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmps, works, strategy = None))]
+fn sample_candidate_pairs<'py>(
+    py: Python<'py>,
+    dmps: PathBuf,
+    works: PathBuf,
+    strategy: Option<&SamplingStrategy>,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    let default = sampler::SamplingStrategy::default();
+    let strategy = strategy.map_or(&default, |strategy| &strategy.strategy);
+    let sample = py
+        .detach(|| sampler::sample_candidate_pairs(&dmps, &works, strategy))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+            _ => e.into(),
+        })?;
+
+    sample
+        .into_iter()
+        .map(|pair| {
+            let record = json_to_py(py, &pair.record)?;
+            let stratum = PyDict::new(py);
+            stratum.set_item("work_type", pair.stratum.work_type)?;
+            stratum.set_item("year_gap", pair.stratum.year_gap)?;
+            stratum.set_item("similarity", pair.stratum.similarity)?;
+            let sampled = PyDict::new(py);
+            sampled.set_item("index", pair.index)?;
+            sampled.set_item("stratum", stratum)?;
+            sampled.set_item("record", record)?;
+            Ok(sampled.into_any())
+        })
+        .collect()
+}
+
+#[pyclass(frozen)]
+struct HardNegativeOptions {
+    options: sampler::HardNegativeOptions,
+}
+
+#[pymethods]
+impl HardNegativeOptions {
+    #[new]
+    #[pyo3(signature = (fraction = 0.5, same_funder = true, same_institution = true))]
+    fn new(fraction: f64, same_funder: bool, same_institution: bool) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(PyValueError::new_err(format!(
+                "fraction must be between 0 and 1, got {fraction}"
+            )));
+        }
+        Ok(Self {
+            options: sampler::HardNegativeOptions {
+                fraction,
+                same_funder,
+                same_institution,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    dmps,
+    works,
+    n_per_dmp = 5,
+    seed = 0,
+    hard_negative_opts = None,
+    positives = None,
+    num_threads = None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn generate_negative_pairs(
+    py: Python<'_>,
+    dmps: &Bound<'_, PyAny>,
+    works: &Bound<'_, PyAny>,
+    n_per_dmp: usize,
+    seed: u64,
+    hard_negative_opts: Option<&HardNegativeOptions>,
+    positives: Option<Vec<(String, String)>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<(usize, usize, &'static str)>> {
+    if n_per_dmp == 0 {
+        return Err(PyValueError::new_err("n_per_dmp must be greater than 0"));
+    }
+    let dmps: Vec<sampler::NegativeSamplingRecord> = deserialize_py(dmps, "DMPs")?;
+    let works: Vec<sampler::NegativeSamplingRecord> = deserialize_py(works, "works")?;
+    let positives = positives.unwrap_or_default();
+    let default = sampler::HardNegativeOptions::default();
+    let options = hard_negative_opts.map_or(&default, |options| &options.options);
+    let pairs = run_parallel(py, num_threads, || {
+        sampler::generate_negative_pairs(&dmps, &works, &positives, n_per_dmp, seed, options)
+    })?;
+    Ok(pairs
+        .into_iter()
+        .map(|pair| (pair.dmp, pair.work, pair.kind.as_str()))
+        .collect())
+}
+
+/// Looks up blocking schemes by name, all of them when None.
+fn blocking_schemes(names: Option<Vec<String>>) -> PyResult<Vec<blocking::BlockingScheme>> {
+    let Some(names) = names else {
+        return Ok(blocking::BlockingScheme::ALL.to_vec());
+    };
+    names
+        .iter()
+        .map(|name| {
+            blocking::BlockingScheme::from_name(name)
+                .ok_or_else(|| PyValueError::new_err(format!("unknown blocking scheme: {name}")))
+        })
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (record, schemes = None))]
+fn blocking_keys(record: &Bound<'_, PyAny>, schemes: Option<Vec<String>>) -> PyResult<Vec<String>> {
+    let schemes = blocking_schemes(schemes)?;
+    let record: blocking::BlockingRecord = deserialize_py(record, "record")?;
+    Ok(blocking::blocking_keys(&record, &schemes))
+}
+
+#[pyfunction]
+#[pyo3(signature = (records, schemes = None, num_threads = None))]
+fn blocking_keys_many(
+    py: Python<'_>,
+    records: Vec<Bound<'_, PyAny>>,
+    schemes: Option<Vec<String>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Vec<String>>> {
+    let schemes = blocking_schemes(schemes)?;
+    let records: Vec<blocking::BlockingRecord> = records
+        .iter()
+        .map(|record| deserialize_py(record, "record"))
+        .collect::<PyResult<_>>()?;
+    run_parallel(py, num_threads, || {
+        blocking::blocking_keys_many(&records, &schemes)
+    })
+}
+
+/// Reads the fields `dedupe_works` needs from a normalized work dict.
+fn dedupe_record(work: &Bound<'_, PyDict>) -> PyResult<dedupe::DedupeRecord> {
+    let text = |key: &str| -> PyResult<Option<String>> {
+        match work.get_item(key)? {
+            Some(value) => value.extract(),
+            None => Ok(None),
+        }
+    };
+    let year = match work.get_item("publication_year")? {
+        Some(year) if !year.is_none() => Some(year.extract()?),
+        _ => text("publication_date")?.and_then(|date| date.get(..4)?.parse().ok()),
+    };
+
+    let mut completeness = 0;
+    for value in work.values() {
+        if value.is_truthy()? {
+            completeness += 1;
+        }
+    }
+    Ok(dedupe::DedupeRecord {
+        doi: text("doi")?,
+        title: text("title")?,
+        year,
+        source: text("source")?,
+        completeness,
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (works, title_threshold = 0.9, source_priority = None))]
+fn dedupe_works(
+    py: Python<'_>,
+    works: &Bound<'_, PyAny>,
+    title_threshold: f64,
+    source_priority: Option<Vec<String>>,
+) -> PyResult<(Vec<usize>, Vec<usize>)> {
+    let records = works
+        .try_iter()?
+        .map(|work| dedupe_record(work?.cast::<PyDict>()?))
+        .collect::<PyResult<Vec<_>>>()?;
+    let source_priority = source_priority.unwrap_or_default();
+    let clusters = py.detach(|| dedupe::dedupe_works(&records, title_threshold, &source_priority));
+    Ok((clusters.cluster_ids, clusters.representatives))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, num_perm = 128))]
+fn minhash_signature(text: &str, num_perm: usize) -> PyResult<Vec<u64>> {
+    if num_perm == 0 {
+        return Err(PyValueError::new_err("num_perm must be greater than 0"));
+    }
+    Ok(minhash::minhash_signature(text, num_perm))
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, num_perm = 128, num_threads = None))]
+fn minhash_signature_many(
+    py: Python<'_>,
+    texts: Vec<Option<String>>,
+    num_perm: usize,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<Vec<u64>>>> {
+    if num_perm == 0 {
+        return Err(PyValueError::new_err("num_perm must be greater than 0"));
+    }
+    run_parallel(py, num_threads, || {
+        minhash::minhash_signatures(&texts, num_perm)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (signatures, num_bands = 16))]
+fn lsh_candidate_pairs(
+    py: Python<'_>,
+    signatures: Vec<Option<Vec<u64>>>,
+    num_bands: usize,
+) -> PyResult<Vec<(usize, usize)>> {
+    if num_bands == 0 {
+        return Err(PyValueError::new_err("num_bands must be greater than 0"));
+    }
+    Ok(py.detach(|| minhash::lsh_candidate_pairs(&signatures, num_bands)))
+}
+
+/// Reads a date from a string, Crossref date parts (e.g. `[2021, 3]` or `[[2021, 3]]`) or a Crossref date object
+/// with a `date-parts` field.
+fn extract_date(value: &Bound<'_, PyAny>) -> PyResult<Option<core::ParsedDate>> {
+    if value.is_none() {
+        return Ok(None);
+    }
+    if let Ok(text) = value.extract::<String>() {
+        return Ok(core::parse_date(Some(&text)));
+    }
+    if let Ok(date) = value.cast::<PyDict>() {
+        return match date.get_item("date-parts")? {
+            Some(parts) => extract_date(&parts),
+            None => Ok(None),
+        };
+    }
+    if let Ok(parts) = value.extract::<Vec<Option<i64>>>() {
+        return Ok(core::date_from_parts(&parts));
+    }
+    if let Ok(parts) = value.extract::<Vec<Vec<Option<i64>>>>() {
+        return Ok(parts.first().and_then(|parts| core::date_from_parts(parts)));
+    }
+    Err(PyTypeError::new_err(
+        "date must be a string, date parts or a mapping with date-parts",
+    ))
+}
+
+#[pyfunction]
+#[allow(clippy::type_complexity)]
+#[pyo3(signature = (date))]
+fn parse_date(date: &Bound<'_, PyAny>) -> PyResult<Option<(i32, Option<u32>, Option<u32>)>> {
+    Ok(extract_date(date)?.map(|date| (date.year, date.month, date.day)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, max_days = 365.0))]
+fn date_distance(
+    a: &Bound<'_, PyAny>,
+    b: &Bound<'_, PyAny>,
+    max_days: f64,
+) -> PyResult<Option<f64>> {
+    let (Some(a), Some(b)) = (extract_date(a)?, extract_date(b)?) else {
+        return Ok(None);
+    };
+    Ok(Some(core::date_distance(&a, &b, max_days)))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_issn(text: Option<&str>) -> Option<String> {
+    core::normalize_issn(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_isbn(text: Option<&str>) -> Option<String> {
+    core::normalize_isbn(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_acronyms(text: Option<&str>) -> Vec<(String, Option<String>)> {
+    text.map(acronyms::extract_acronyms).unwrap_or_default()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, dictionary = None))]
+fn expand_acronyms(
+    text: Option<&str>,
+    dictionary: Option<HashMap<String, String>>,
+) -> Option<String> {
+    text.map(|text| acronyms::expand_acronyms(text, dictionary.as_ref()))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, top_n = 10))]
+fn extract_keyphrases(py: Python<'_>, text: Option<&str>, top_n: usize) -> Vec<(String, f64)> {
+    py.detach(|| {
+        text.map(|text| keyphrases::extract_keyphrases(text, top_n))
+            .unwrap_or_default()
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (texts, top_n = 10, num_threads = None))]
+fn extract_keyphrases_many(
+    py: Python<'_>,
+    texts: Vec<Option<String>>,
+    top_n: usize,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Vec<(String, f64)>>> {
+    run_parallel(py, num_threads, || {
+        keyphrases::extract_keyphrases_many(&texts, top_n)
+    })
+}
+
+#[pyclass(frozen)]
+struct WorkQueryOptions {
+    options: query::WorkQueryOptions,
+}
+
+#[pymethods]
+impl WorkQueryOptions {
+    #[new]
+    #[pyo3(signature = (
+        max_results = 100,
+        project_end_buffer_years = 3,
+        inner_hits_size = 50,
+        boosts = None,
+        minimum_should_match = None,
+        stop_words = None,
+        features = None,
+    ))]
+    fn new(
+        max_results: usize,
+        project_end_buffer_years: i32,
+        inner_hits_size: usize,
+        boosts: Option<HashMap<String, f64>>,
+        minimum_should_match: Option<String>,
+        stop_words: Option<Vec<String>>,
+        features: Option<HashMap<String, bool>>,
+    ) -> PyResult<Self> {
+        let mut query_boosts = query::QueryBoosts::default();
+        for (name, boost) in boosts.unwrap_or_default() {
+            let Some(slot) = query_boosts.get_mut(&name) else {
+                return Err(PyValueError::new_err(format!("unknown boost '{name}'")));
+            };
+            if boost.is_nan() || boost < 0.0 {
+                return Err(PyValueError::new_err(format!(
+                    "boost '{name}' must be non-negative, got {boost}"
+                )));
+            }
+            *slot = boost;
+        }
+        let mut query_features = query::QueryFeatures::default();
+        for (name, enabled) in features.unwrap_or_default() {
+            let Some(slot) = query_features.get_mut(&name) else {
+                return Err(PyValueError::new_err(format!("unknown feature '{name}'")));
+            };
+            *slot = enabled;
+        }
+        Ok(Self {
+            options: query::WorkQueryOptions {
+                max_results,
+                project_end_buffer_years,
+                inner_hits_size,
+                boosts: query_boosts,
+                minimum_should_match,
+                stop_words: stop_words.unwrap_or_default(),
+                features: query_features,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp_fields, options = None))]
+fn build_work_query<'py>(
+    py: Python<'py>,
+    dmp_fields: &Bound<'py, PyAny>,
+    options: Option<&WorkQueryOptions>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let dmp: query::DmpQueryFields = deserialize_py(dmp_fields, "DMP fields")?;
+    let default = query::WorkQueryOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    let query = query::build_work_query(&dmp, options);
+    json_to_py(py, &query)
+}
+
+/// Looks up a score normalization by name, raising ValueError for an unknown one.
+fn score_normalization(name: &str) -> PyResult<fusion::ScoreNormalization> {
+    fusion::ScoreNormalization::from_name(name).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "normalization must be 'minmax' or 'zscore', got {name}"
+        ))
+    })
+}
+
+/// Checks that `rrf_k` is a valid reciprocal rank fusion constant.
+fn check_rrf_k(rrf_k: f64) -> PyResult<f64> {
+    if rrf_k.is_nan() || rrf_k < 0.0 {
+        return Err(PyValueError::new_err(format!(
+            "rrf_k must be >= 0, got {rrf_k}"
+        )));
+    }
+    Ok(rrf_k)
+}
+
+#[pyfunction]
+#[pyo3(signature = (scores, normalization = "minmax"))]
+fn normalize_scores(scores: Vec<f64>, normalization: &str) -> PyResult<Vec<f64>> {
+    Ok(fusion::normalize_scores(
+        &scores,
+        score_normalization(normalization)?,
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    rankings,
+    method = "rrf",
+    normalization = None,
+    weights = None,
+    rrf_k = fusion::DEFAULT_RRF_K,
+))]
+fn fuse_rankings(
+    rankings: Vec<Vec<(String, f64)>>,
+    method: &str,
+    normalization: Option<&str>,
+    weights: Option<Vec<f64>>,
+    rrf_k: f64,
+) -> PyResult<Vec<(String, f64)>> {
+    let method = fusion::FusionMethod::from_name(method).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "method must be 'rrf', 'combsum' or 'combmnz', got {method}"
+        ))
+    })?;
+    let normalization = normalization.map(score_normalization).transpose()?;
+    if method == fusion::FusionMethod::Rrf && normalization.is_some() {
+        return Err(PyValueError::new_err(
+            "normalization only applies to the combsum and combmnz methods",
+        ));
+    }
+    let weights = weights.unwrap_or_default();
+    if !weights.is_empty() && weights.len() != rankings.len() {
+        return Err(PyValueError::new_err(format!(
+            "weights must have one weight for each of the {} rankings, got {}",
+            rankings.len(),
+            weights.len()
+        )));
+    }
+    let rrf_k = check_rrf_k(rrf_k)?;
+    Ok(fusion::fuse_rankings(
+        &rankings,
+        method,
+        normalization,
+        &weights,
+        rrf_k,
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (json_bytes, dedupe_by = "doi", rrf_k = fusion::DEFAULT_RRF_K))]
+fn parse_msearch_response<'py>(
+    py: Python<'py>,
+    json_bytes: &[u8],
+    dedupe_by: &str,
+    rrf_k: f64,
+) -> PyResult<Bound<'py, PyList>> {
+    let dedupe_by = query::DedupeBy::from_name(dedupe_by).ok_or_else(|| {
+        PyValueError::new_err(format!("dedupe_by must be 'doi' or 'id', got {dedupe_by}"))
+    })?;
+    let rrf_k = check_rrf_k(rrf_k)?;
+    let candidates = py
+        .detach(|| query::parse_msearch_response(json_bytes, dedupe_by, rrf_k))
+        .map_err(|e| PyValueError::new_err(format!("invalid msearch response: {e}")))?;
+    let list = PyList::empty(py);
+    for candidate in candidates {
+        let value =
+            serde_json::to_value(candidate).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        list.append(json_to_py(py, &value)?)?;
+    }
+    Ok(list)
+}
+
+/// Checks candidate feature weights, defaulting to `scoring::DEFAULT_FEATURE_WEIGHTS`.
+fn feature_weights(weights: Option<HashMap<String, f64>>) -> PyResult<HashMap<String, f64>> {
+    let Some(weights) = weights else {
+        return Ok(scoring::DEFAULT_FEATURE_WEIGHTS
+            .iter()
+            .map(|(name, weight)| (name.to_string(), *weight))
+            .collect());
+    };
+    if let Some(name) = weights
+        .keys()
+        .find(|name| !scoring::CANDIDATE_FEATURES.contains(&name.as_str()))
+    {
+        return Err(PyValueError::new_err(format!(
+            "unknown feature '{name}', expected one of {:?}",
+            scoring::CANDIDATE_FEATURES
+        )));
+    }
+    Ok(weights)
+}
+
+/// Converts a candidate's score to a dict of its features, in feature order, and its score.
+fn candidate_score_to_py(
+    py: Python<'_>,
+    scored: scoring::CandidateScore,
+) -> PyResult<(Bound<'_, PyDict>, f64)> {
+    Ok((scored.features.into_py_dict(py)?, scored.score))
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp, work, weights = None))]
+fn score_candidate<'py>(
+    py: Python<'py>,
+    dmp: &Bound<'py, PyAny>,
+    work: &Bound<'py, PyAny>,
+    weights: Option<HashMap<String, f64>>,
+) -> PyResult<(Bound<'py, PyDict>, f64)> {
+    let dmp: scoring::ScoringDmp = deserialize_py(dmp, "DMP")?;
+    let work: scoring::ScoringWork = deserialize_py(work, "work")?;
+    let weights = feature_weights(weights)?;
+    let scored = scoring::score_candidate(&dmp, &work, &weights);
+    candidate_score_to_py(py, scored)
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp, works, weights = None, num_threads = None))]
+fn score_candidates<'py>(
+    py: Python<'py>,
+    dmp: &Bound<'py, PyAny>,
+    works: &Bound<'py, PyAny>,
+    weights: Option<HashMap<String, f64>>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<(Bound<'py, PyDict>, f64)>> {
+    let dmp: scoring::ScoringDmp = deserialize_py(dmp, "DMP")?;
+    let works: Vec<scoring::ScoringWork> = deserialize_py(works, "works")?;
+    let weights = feature_weights(weights)?;
+    let scored = run_parallel(py, num_threads, || {
+        scoring::score_candidates(&dmp, &works, &weights)
+    })?;
+    scored
+        .into_iter()
+        .map(|scored| candidate_score_to_py(py, scored))
+        .collect()
+}
+
+#[pyfunction]
+#[pyo3(signature = (pairs, num_threads = None))]
+fn featurize_pairs<'py>(
+    py: Python<'py>,
+    pairs: &Bound<'py, PyAny>,
+    num_threads: Option<usize>,
+) -> PyResult<(Bound<'py, PyArray2<f32>>, Vec<&'static str>)> {
+    // Pairs of a DMP and its candidates usually share one DMP object, which is deserialized once. The objects are
+    // kept alive so that their addresses aren't reused by later ones.
+    let mut dmp_objects: Vec<Bound<'py, PyAny>> = Vec::new();
+    let mut dmp_positions: HashMap<*mut pyo3::ffi::PyObject, usize> = HashMap::new();
+    let mut dmps = Vec::new();
+    let mut works = Vec::new();
+    let mut indexes = Vec::new();
+    for (i, pair) in pairs.try_iter()?.enumerate() {
+        let pair = pair?;
+        let (dmp, work): (Bound<'py, PyAny>, Bound<'py, PyAny>) = pair
+            .extract()
+            .map_err(|_| PyTypeError::new_err(format!("pair {i} must be a (dmp, work) tuple")))?;
+        let dmp = match dmp_positions.get(&dmp.as_ptr()) {
+            Some(&position) => position,
+            None => {
+                dmps.push(deserialize_py::<scoring::ScoringDmp>(&dmp, "DMP")?);
+                dmp_positions.insert(dmp.as_ptr(), dmps.len() - 1);
+                dmp_objects.push(dmp);
+                dmps.len() - 1
+            },
+        };
+        works.push(deserialize_py::<scoring::ScoringWork>(&work, "work")?);
+        indexes.push((dmp, works.len() - 1));
+    }
+    let matrix = run_parallel(py, num_threads, || {
+        scoring::featurize_pairs(&dmps, &works, &indexes)
+    })?;
+    Ok((
+        matrix.into_pyarray(py),
+        scoring::CANDIDATE_FEATURES.to_vec(),
+    ))
+}
+
+#[pyclass(frozen)]
+struct MatchModel {
+    model: scoring::MatchModel,
+}
+
+/// Features passed from Python: a dict by feature name, as returned by `score_candidate`, or a list in feature order.
+#[derive(FromPyObject)]
+enum FeaturesArg {
+    Named(HashMap<String, Option<f64>>),
+    Vector(Vec<Option<f64>>),
+}
+
+impl FeaturesArg {
+    /// Orders the features as in `scoring::CANDIDATE_FEATURES`, raising ValueError for an unknown feature name or a
+    /// list of the wrong length.
+    fn ordered(self) -> PyResult<[Option<f64>; 6]> {
+        let mut ordered = [None; 6];
+        match self {
+            FeaturesArg::Named(features) => {
+                for (name, value) in features {
+                    let Some(i) = scoring::CANDIDATE_FEATURES.iter().position(|f| *f == name)
+                    else {
+                        return Err(PyValueError::new_err(format!(
+                            "unknown feature '{name}', expected one of {:?}",
+                            scoring::CANDIDATE_FEATURES
+                        )));
+                    };
+                    ordered[i] = value;
+                }
+            },
+            FeaturesArg::Vector(features) => {
+                if features.len() != ordered.len() {
+                    return Err(PyValueError::new_err(format!(
+                        "expected {} features {:?}, got {}",
+                        ordered.len(),
+                        scoring::CANDIDATE_FEATURES,
+                        features.len()
+                    )));
+                }
+                ordered.copy_from_slice(&features);
+            },
+        }
+        Ok(ordered)
+    }
+}
+
+#[pymethods]
+impl MatchModel {
+    #[new]
+    #[pyo3(signature = (json))]
+    fn new(json: &str) -> PyResult<Self> {
+        let model = scoring::MatchModel::from_json(json.as_bytes())
+            .map_err(|e| PyValueError::new_err(format!("invalid match model: {e}")))?;
+        Ok(Self { model })
+    }
+
+    #[getter]
+    fn intercept(&self) -> f64 {
+        self.model.intercept
+    }
+
+    #[getter]
+    fn weights<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        scoring::CANDIDATE_FEATURES
+            .into_iter()
+            .zip(self.model.coefficients)
+            .into_py_dict(py)
+    }
+
+    #[pyo3(signature = (features))]
+    fn predict(&self, features: FeaturesArg) -> PyResult<f64> {
+        Ok(self.model.predict(&features.ordered()?))
+    }
+
+    #[pyo3(signature = (features, num_threads = None))]
+    fn predict_many(
+        &self,
+        py: Python<'_>,
+        features: Vec<FeaturesArg>,
+        num_threads: Option<usize>,
+    ) -> PyResult<Vec<f64>> {
+        let features = features
+            .into_iter()
+            .map(FeaturesArg::ordered)
+            .collect::<PyResult<Vec<_>>>()?;
+        run_parallel(py, num_threads, || self.model.predict_many(&features))
+    }
+
+    #[pyo3(signature = (dmp, work))]
+    fn score(&self, dmp: &Bound<'_, PyAny>, work: &Bound<'_, PyAny>) -> PyResult<f64> {
+        let dmp: scoring::ScoringDmp = deserialize_py(dmp, "DMP")?;
+        let work: scoring::ScoringWork = deserialize_py(work, "work")?;
+        Ok(self.model.score(&dmp, &work))
+    }
+
+    #[pyo3(signature = (dmp, works, num_threads = None))]
+    fn score_candidates(
+        &self,
+        py: Python<'_>,
+        dmp: &Bound<'_, PyAny>,
+        works: &Bound<'_, PyAny>,
+        num_threads: Option<usize>,
+    ) -> PyResult<Vec<f64>> {
+        let dmp: scoring::ScoringDmp = deserialize_py(dmp, "DMP")?;
+        let works: Vec<scoring::ScoringWork> = deserialize_py(works, "works")?;
+        run_parallel(py, num_threads, || {
+            self.model.score_candidates(&dmp, &works)
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (dmp_contributors, work_authors))]
+fn author_overlap(
+    dmp_contributors: Vec<Option<NameArg>>,
+    work_authors: Vec<Option<NameArg>>,
+) -> (Vec<(usize, usize, f64)>, f64) {
+    let parse = |names: Vec<Option<NameArg>>| -> Vec<core::ParsedName> {
+        names.into_iter().map(parse_name_arg).collect()
+    };
+    core::author_overlap(&parse(dmp_contributors), &parse(work_authors))
+        .map_or((Vec::new(), 0.0), |overlap| (overlap.pairs, overlap.score))
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn romanize(text: &str) -> String {
+    core::romanize(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (name))]
+fn expand_given_name_variants(name: Option<&str>) -> Vec<String> {
+    name.map(core::expand_given_name_variants)
+        .unwrap_or_default()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn extract_emails(text: Option<&str>) -> Vec<String> {
+    core::extract_emails(text)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn normalize_url(text: Option<&str>) -> Option<(&'static str, String)> {
+    Some(match core::normalize_url(text)? {
+        core::NormalizedUrl::Doi(doi) => ("doi", doi),
+        core::NormalizedUrl::Handle(handle) => ("handle", handle),
+        core::NormalizedUrl::Url(url) => ("url", url),
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, funder_hint=None))]
+fn normalize_award_id(text: Option<&str>, funder_hint: Option<&str>) -> Option<String> {
+    core::normalize_award_id(text, funder_hint)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b))]
+fn compare_award_ids(a: Option<&str>, b: Option<&str>) -> bool {
+    core::compare_award_ids(a, b)
+}
+
+#[pyfunction]
+#[pyo3(signature = (text))]
+fn split_sentences(text: Option<&str>) -> Vec<String> {
+    text.map(core::split_sentences).unwrap_or_default()
+}
+
+#[pyfunction]
+#[pyo3(signature = (text, max_tokens))]
+fn truncate_to_tokens(text: Option<&str>, max_tokens: usize) -> Option<String> {
+    text.map(|text| core::truncate_to_tokens(text, max_tokens))
+}
+
+/// An abstract passed from Python: text, or the bytes of a JSON-serialized inverted index.
+#[derive(FromPyObject)]
+enum AbstractArg {
+    Text(String),
+    InvertedIndex(Vec<u8>),
+}
+
+impl From<AbstractArg> for embedding::AbstractSource {
+    fn from(arg: AbstractArg) -> Self {
+        match arg {
+            AbstractArg::Text(text) => embedding::AbstractSource::Text(text),
+            AbstractArg::InvertedIndex(bytes) => embedding::AbstractSource::InvertedIndex(bytes),
+        }
+    }
+}
+
+#[pyclass(frozen)]
+struct EmbeddingTextOptions {
+    options: embedding::EmbeddingTextOptions,
+}
+
+#[pymethods]
+impl EmbeddingTextOptions {
+    #[new]
+    #[pyo3(signature = (
+        markup = None,
+        form = "NFC",
+        fold_diacritics = false,
+        case_fold = false,
+        max_tokens = Some(256),
+        separator = "\n".to_string(),
+    ))]
+    fn new(
+        markup: Option<&MarkupOptions>,
+        form: &str,
+        fold_diacritics: bool,
+        case_fold: bool,
+        max_tokens: Option<usize>,
+        separator: String,
+    ) -> PyResult<Self> {
+        let form = core::NormalizationForm::from_name(form).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "form must be one of NFC, NFD, NFKC or NFKD, got {form}"
+            ))
+        })?;
+        let default = embedding::EmbeddingTextOptions::default();
+        Ok(Self {
+            options: embedding::EmbeddingTextOptions {
+                markup: markup.map_or(default.markup, |markup| markup.options.clone()),
+                form,
+                fold_diacritics,
+                case_fold,
+                max_tokens,
+                separator,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (title, abstract_text, options = None))]
+fn prepare_embedding_text(
+    title: Option<&str>,
+    abstract_text: Option<AbstractArg>,
+    options: Option<&EmbeddingTextOptions>,
+) -> Option<String> {
+    let default = embedding::EmbeddingTextOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    let abstract_source = abstract_text.map(embedding::AbstractSource::from);
+    embedding::prepare_embedding_text(title, abstract_source.as_ref(), options)
+}
+
+#[pyfunction]
+#[pyo3(signature = (titles, abstracts, options = None, num_threads = None))]
+fn prepare_embedding_text_many(
+    py: Python<'_>,
+    titles: Vec<Option<String>>,
+    abstracts: Vec<Option<AbstractArg>>,
+    options: Option<&EmbeddingTextOptions>,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<Option<String>>> {
+    if titles.len() != abstracts.len() {
+        return Err(PyValueError::new_err(format!(
+            "titles and abstracts must have the same length, got {} and {}",
+            titles.len(),
+            abstracts.len()
+        )));
+    }
+    let default = embedding::EmbeddingTextOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    let abstracts: Vec<Option<embedding::AbstractSource>> = abstracts
+        .into_iter()
+        .map(|abstract_text| abstract_text.map(embedding::AbstractSource::from))
+        .collect();
+    run_parallel(py, num_threads, || {
+        embedding::prepare_embedding_text_many(&titles, &abstracts, options)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (queries, matrix, num_threads = None))]
+fn cosine_similarity<'py>(
+    py: Python<'py>,
+    queries: PyReadonlyArray2<'py, f32>,
+    matrix: PyReadonlyArray2<'py, f32>,
+    num_threads: Option<usize>,
+) -> PyResult<Bound<'py, PyArray2<f32>>> {
+    let (queries, matrix) = (queries.as_array(), matrix.as_array());
+    if queries.ncols() != matrix.ncols() {
+        return Err(PyValueError::new_err(format!(
+            "queries and matrix must have the same number of columns, got {} and {}",
+            queries.ncols(),
+            matrix.ncols()
+        )));
+    }
+    let similarities = run_parallel(py, num_threads, || {
+        vectors::cosine_similarity(queries, matrix)
+    })?;
+    Ok(similarities.into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(signature = (query_vec, matrix, k, num_threads = None))]
+fn top_k(
+    py: Python<'_>,
+    query_vec: PyReadonlyArray1<'_, f32>,
+    matrix: PyReadonlyArray2<'_, f32>,
+    k: usize,
+    num_threads: Option<usize>,
+) -> PyResult<Vec<(usize, f32)>> {
+    let (query_vec, matrix) = (query_vec.as_array(), matrix.as_array());
+    if query_vec.len() != matrix.ncols() {
+        return Err(PyValueError::new_err(format!(
+            "query_vec must have as many elements as matrix has columns, got {} and {}",
+            query_vec.len(),
+            matrix.ncols()
+        )));
+    }
+    run_parallel(py, num_threads, || vectors::top_k(query_vec, matrix, k))
+}
+
+/// Converts a Parquet error into a Python exception: `ValueError` for invalid input or data, such as a malformed CSV
+/// record, the matching `OSError` for other I/O errors, `ValueError` for other wrapped errors such as a missing
+/// column, otherwise `OSError`.
+fn parquet_error(e: ParquetError) -> PyErr {
+    match e {
+        ParquetError::External(e) => match e.downcast::<io::Error>() {
+            Ok(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData
+                ) =>
+            {
+                PyValueError::new_err(e.to_string())
+            },
+            Ok(e) => (*e).into(),
+            Err(e) => PyValueError::new_err(e.to_string()),
+        },
+        e => PyIOError::new_err(e.to_string()),
+    }
+}
+
+#[pyclass(frozen)]
+struct ParquetWriteOptions {
+    options: parquet_io::ParquetWriteOptions,
+}
+
+#[pymethods]
+impl ParquetWriteOptions {
+    #[new]
+    #[pyo3(signature = (row_group_size = 100_000, zstd_level = 3))]
+    fn new(row_group_size: usize, zstd_level: i32) -> PyResult<Self> {
+        if row_group_size == 0 {
+            return Err(PyValueError::new_err(
+                "row_group_size must be greater than 0",
+            ));
+        }
+        if !(1..=22).contains(&zstd_level) {
+            return Err(PyValueError::new_err(format!(
+                "zstd_level must be between 1 and 22, got {zstd_level}"
+            )));
+        }
+        Ok(Self {
+            options: parquet_io::ParquetWriteOptions {
+                row_group_size,
+                zstd_level,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (records, path, options = None))]
+fn write_works_parquet(
+    py: Python<'_>,
+    records: &Bound<'_, PyAny>,
+    path: PathBuf,
+    options: Option<&ParquetWriteOptions>,
+) -> PyResult<usize> {
+    let default = parquet_io::ParquetWriteOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    let mut writer = py
+        .detach(|| parquet_io::WorksParquetWriter::create(&path, options))
+        .map_err(parquet_error)?;
+
+    // Convert records with the GIL in batches, writing each batch without it
+    let mut batch = Vec::with_capacity(parquet_io::WRITE_BATCH_SIZE);
+    for record in records.try_iter()? {
+        batch.push(deserialize_py::<transform::Work>(&record?, "work")?);
+        if batch.len() == parquet_io::WRITE_BATCH_SIZE {
+            py.detach(|| writer.write(&batch)).map_err(parquet_error)?;
+            batch.clear();
+        }
+    }
+    py.detach(|| {
+        writer.write(&batch)?;
+        writer.finish()
+    })
+    .map_err(parquet_error)
+}
+
+/// A single-byte delimiter or quote character for `CsvOptions`.
+fn csv_byte(value: &str, what: &str) -> PyResult<u8> {
+    match value.as_bytes() {
+        [byte] => Ok(*byte),
+        _ => Err(PyValueError::new_err(format!(
+            "{what} must be a single ASCII character, got {value:?}"
+        ))),
+    }
+}
+
+#[pyclass(frozen)]
+struct CsvOptions {
+    options: csv::CsvOptions,
+}
+
+#[pymethods]
+impl CsvOptions {
+    #[new]
+    #[pyo3(signature = (delimiter = ",", quote = Some("\""), column_names = None))]
+    fn new(
+        delimiter: &str,
+        quote: Option<&str>,
+        column_names: Option<Vec<String>>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            options: csv::CsvOptions {
+                delimiter: csv_byte(delimiter, "delimiter")?,
+                quote: quote.map(|quote| csv_byte(quote, "quote")).transpose()?,
+                column_names,
+            },
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    input_path,
+    output_path,
+    transforms = None,
+    csv_options = None,
+    parquet_options = None,
+    num_threads = None,
+    progress = None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn transform_csv(
+    py: Python<'_>,
+    input_path: PathBuf,
+    output_path: PathBuf,
+    transforms: Option<HashMap<String, String>>,
+    csv_options: Option<&CsvOptions>,
+    parquet_options: Option<&ParquetWriteOptions>,
+    num_threads: Option<usize>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<usize> {
+    let extension = output_path
+        .extension()
+        .and_then(|extension| extension.to_str());
+    let format = match extension {
+        Some("parquet") => csv::OutputFormat::Parquet,
+        Some("jsonl" | "ndjson") => csv::OutputFormat::Jsonl,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "output_path must end in .parquet, .jsonl or .ndjson, got {}",
+                output_path.display()
+            )))
+        },
+    };
+    let transforms = transforms
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(column, name)| {
+            let transform = csv::ColumnTransform::from_name(&name).ok_or_else(|| {
+                PyValueError::new_err(format!("unknown transform for column {column}: {name}"))
+            })?;
+            Ok((column, transform))
+        })
+        .collect::<PyResult<HashMap<_, _>>>()?;
+    let csv_options = csv_options.map_or_else(csv::CsvOptions::default, |o| o.options.clone());
+    let default = parquet_io::ParquetWriteOptions::default();
+    let parquet_options = parquet_options.map_or(&default, |o| &o.options);
+
+    let mut callback_error = None;
+    let result = run_parallel(py, num_threads, || {
+        csv::transform_csv(
+            &input_path,
+            &output_path,
+            &transforms,
+            format,
+            csv_options,
+            parquet_options,
+            |processed| call_progress(progress.as_ref(), processed, None, &mut callback_error),
+        )
+    })?;
+
+    // Surface the callback's own exception rather than the I/O error used to stop processing
+    if let Some(e) = callback_error {
+        return Err(e);
+    }
+    result.map_err(parquet_error)
+}
+
+/// A transform of a `Pipeline` field: its name, or its name and argument, e.g. ("truncate", 512).
+#[derive(FromPyObject)]
+enum TransformArg {
+    Name(String),
+    WithArg(String, usize),
+}
+
+#[pyclass(frozen)]
+struct Pipeline {
+    pipeline: pipeline::Pipeline,
+}
+
+#[pymethods]
+impl Pipeline {
+    #[new]
+    #[pyo3(signature = (transforms))]
+    fn new(transforms: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let mut fields = Vec::new();
+        for (field, chain) in transforms.iter() {
+            let field: String = field.extract()?;
+            let chain = chain
+                .extract::<Vec<TransformArg>>()?
+                .into_iter()
+                .map(|transform| {
+                    let (name, arg) = match transform {
+                        TransformArg::Name(name) => (name, None),
+                        TransformArg::WithArg(name, arg) => (name, Some(arg)),
+                    };
+                    pipeline::FieldTransform::from_name(&name, arg).map_err(|e| {
+                        PyValueError::new_err(format!("invalid transform for field {field}: {e}"))
+                    })
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            fields.push((field, chain));
+        }
+        Ok(Self {
+            pipeline: pipeline::Pipeline::new(fields),
+        })
+    }
+
+    /// The fields the pipeline transforms, in order.
+    #[getter]
+    fn fields(&self) -> Vec<String> {
+        self.pipeline.fields().map(str::to_string).collect()
+    }
+
+    #[pyo3(signature = (batch, num_threads = None))]
+    fn run(
+        &self,
+        py: Python<'_>,
+        batch: PyRecordBatch,
+        num_threads: Option<usize>,
+    ) -> PyResult<PyRecordBatch> {
+        let batch = batch.into_inner();
+        run_parallel(py, num_threads, || self.pipeline.run(&batch))?
+            .map(PyRecordBatch::new)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyclass(frozen)]
+struct WorksFilter {
+    filter: parquet_io::WorksFilter,
+}
+
+#[pymethods]
+impl WorksFilter {
+    #[new]
+    #[pyo3(signature = (min_year = None, max_year = None, source = None, has_abstract = None))]
+    fn new(
+        min_year: Option<i32>,
+        max_year: Option<i32>,
+        source: Option<String>,
+        has_abstract: Option<bool>,
+    ) -> PyResult<Self> {
+        if let (Some(min_year), Some(max_year)) = (min_year, max_year) {
+            if min_year > max_year {
+                return Err(PyValueError::new_err(format!(
+                    "min_year must not be after max_year, got {min_year} and {max_year}"
+                )));
+            }
+        }
+        Ok(Self {
+            filter: parquet_io::WorksFilter {
+                min_year,
+                max_year,
+                source,
+                has_abstract,
+            },
+        })
+    }
+}
+
+/// Streaming reader over the filtered record batches of a works Parquet file.
+#[pyclass]
+struct WorksParquetReader {
+    batches: Mutex<parquet_io::WorksParquetReader>,
+}
+
+#[pymethods]
+impl WorksParquetReader {
+    /// The schema of the batches, e.g. to build an empty table when no rows match.
+    #[getter]
+    fn schema(&self) -> PySchema {
+        PySchema::new(self.batches.lock().unwrap().schema())
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyRecordBatch>> {
+        // Decode and filter without the GIL
+        let batch = py.detach(|| self.batches.lock().unwrap().next());
+        batch
+            .transpose()
+            .map(|batch| batch.map(PyRecordBatch::new))
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, columns = None, filters = None, batch_size = 8192))]
+fn read_works_parquet(
+    py: Python<'_>,
+    path: PathBuf,
+    columns: Option<Vec<String>>,
+    filters: Option<&WorksFilter>,
+    batch_size: usize,
+) -> PyResult<WorksParquetReader> {
+    if batch_size == 0 {
+        return Err(PyValueError::new_err("batch_size must be greater than 0"));
+    }
+    let filter = filters
+        .map(|filters| filters.filter.clone())
+        .unwrap_or_default();
+    let batches = py
+        .detach(|| {
+            parquet_io::WorksParquetReader::open(&path, columns.as_deref(), filter, batch_size)
+        })
+        .map_err(parquet_error)?;
+    Ok(WorksParquetReader {
+        batches: Mutex::new(batches),
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (old_path, new_path, key, fields = None, batch_size = 8192))]
+fn diff_snapshots<'py>(
+    py: Python<'py>,
+    old_path: PathBuf,
+    new_path: PathBuf,
+    key: &str,
+    fields: Option<Vec<String>>,
+    batch_size: usize,
+) -> PyResult<(Vec<String>, Vec<String>, Bound<'py, PyDict>)> {
+    if batch_size == 0 {
+        return Err(PyValueError::new_err("batch_size must be greater than 0"));
+    }
+    let diff = py
+        .detach(|| {
+            parquet_io::diff_snapshots(&old_path, &new_path, key, fields.as_deref(), batch_size)
+        })
+        .map_err(parquet_error)?;
+    Ok((diff.added, diff.removed, diff.changed.into_py_dict(py)?))
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, dmp_id, candidates, options = None))]
+fn write_candidates(
+    py: Python<'_>,
+    path: PathBuf,
+    dmp_id: &str,
+    candidates: &Bound<'_, PyAny>,
+    options: Option<&ParquetWriteOptions>,
+) -> PyResult<usize> {
+    let candidates: Vec<candidates::Candidate> = deserialize_py(candidates, "candidates")?;
+    let default = parquet_io::ParquetWriteOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    py.detach(|| candidates::write_candidates(&path, dmp_id, &candidates, options))
+        .map_err(parquet_error)
+}
+
+#[pyfunction]
+#[pyo3(signature = (path, dmps, options = None))]
+fn write_candidates_many(
+    py: Python<'_>,
+    path: PathBuf,
+    dmps: &Bound<'_, PyMapping>,
+    options: Option<&ParquetWriteOptions>,
+) -> PyResult<usize> {
+    let dmps = dmps
+        .items()?
+        .iter()
+        .map(|item| {
+            let (dmp_id, candidates): (String, Bound<'_, PyAny>) = item.extract()?;
+            let candidates: Vec<candidates::Candidate> = deserialize_py(&candidates, "candidates")?;
+            Ok((dmp_id, candidates))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    let dmps: Vec<(&str, &[candidates::Candidate])> = dmps
+        .iter()
+        .map(|(dmp_id, candidates)| (dmp_id.as_str(), candidates.as_slice()))
+        .collect();
+    let default = parquet_io::ParquetWriteOptions::default();
+    let options = options.map_or(&default, |options| &options.options);
+    py.detach(|| candidates::write_candidates_many(&path, &dmps, options))
+        .map_err(parquet_error)
+}
+
+/// Converts a candidate read from a candidates file into a dict in the shape `write_candidates` takes.
+fn candidate_to_py<'py>(
+    py: Python<'py>,
+    candidate: candidates::Candidate,
+) -> PyResult<Bound<'py, PyDict>> {
+    let legs = PyList::empty(py);
+    for leg in candidate.legs {
+        let leg = [
+            ("leg", leg.leg.into_bound_py_any(py)?),
+            ("rank", leg.rank.into_bound_py_any(py)?),
+            ("score", leg.score.into_bound_py_any(py)?),
+        ];
+        legs.append(leg.into_py_dict(py)?)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("doi", candidate.doi)?;
+    dict.set_item("score", candidate.score)?;
+    dict.set_item("features", candidate.features.into_py_dict(py)?)?;
+    dict.set_item("legs", legs)?;
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (path))]
+fn read_candidates<'py>(py: Python<'py>, path: PathBuf) -> PyResult<Bound<'py, PyDict>> {
+    let dmps = py
+        .detach(|| candidates::read_candidates(&path))
+        .map_err(parquet_error)?;
+    let dict = PyDict::new(py);
+    for (dmp_id, candidates) in dmps {
+        let candidates = candidates
+            .into_iter()
+            .map(|candidate| candidate_to_py(py, candidate))
+            .collect::<PyResult<Vec<_>>>()?;
+        dict.set_item(dmp_id, candidates)?;
+    }
+    Ok(dict)
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    records,
+    index_name,
+    id_field = None,
+    max_chunk_bytes = bulk::DEFAULT_MAX_CHUNK_BYTES,
+    max_chunk_actions = bulk::DEFAULT_MAX_CHUNK_ACTIONS,
+))]
+fn to_bulk_actions(
+    records: &Bound<'_, PyAny>,
+    index_name: &str,
+    id_field: Option<&str>,
+    max_chunk_bytes: usize,
+    max_chunk_actions: usize,
+) -> PyResult<Vec<Vec<u8>>> {
+    if max_chunk_bytes == 0 || max_chunk_actions == 0 {
+        return Err(PyValueError::new_err(
+            "max_chunk_bytes and max_chunk_actions must be greater than 0",
+        ));
+    }
+    let mut chunker = bulk::BulkChunker::new(max_chunk_bytes, max_chunk_actions);
+    let mut bodies = Vec::new();
+    for (i, record) in records.try_iter()?.enumerate() {
+        let record = py_to_json(&record?)?;
+        if !record.is_object() {
+            return Err(PyTypeError::new_err(format!("record {i} must be a dict")));
+        }
+        let id =
+            match id_field {
+                Some(id_field) => Some(bulk::document_id(&record, id_field).ok_or_else(|| {
+                    PyValueError::new_err(format!("record {i} has no {id_field}"))
+                })?),
+                None => None,
+            };
+        let action = bulk::bulk_action(&record, index_name, id.as_deref());
+        bodies.extend(chunker.push(&action).map(String::into_bytes));
+    }
+    bodies.extend(chunker.finish().map(String::into_bytes));
+    Ok(bodies)
+}
+
+/// A bulk load report as a dict of the actions in the response, those that failed, the requests sent, why the body
+/// wasn't loaded if it wasn't, and the seconds taken.
+fn chunk_report_to_py<'py>(
+    py: Python<'py>,
+    report: &loader::ChunkReport,
+) -> PyResult<Bound<'py, PyDict>> {
+    let errors = PyList::empty(py);
+    for error in &report.errors {
+        let item = PyDict::new(py);
+        item.set_item("position", error.position)?;
+        item.set_item("id", &error.id)?;
+        item.set_item("status", error.status)?;
+        item.set_item("type", &error.error_type)?;
+        item.set_item("reason", &error.reason)?;
+        errors.append(item)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("items", report.items)?;
+    dict.set_item("errors", errors)?;
+    dict.set_item("attempts", report.attempts)?;
+    dict.set_item("error", &report.error)?;
+    dict.set_item("took", report.took.as_secs_f64())?;
+    Ok(dict)
+}
+
+fn seconds(name: &str, value: f64) -> PyResult<std::time::Duration> {
+    std::time::Duration::try_from_secs_f64(value).map_err(|_| {
+        PyValueError::new_err(format!("{name} must be a non-negative number of seconds"))
+    })
+}
+
+#[pyclass(frozen)]
+struct OpenSearchLoader {
+    loader: loader::OpenSearchLoader,
+}
+
+#[pymethods]
+impl OpenSearchLoader {
+    #[new]
+    #[pyo3(signature = (
+        url,
+        username = None,
+        password = None,
+        aws_region = None,
+        aws_service = "es".to_string(),
+        aws_access_key_id = None,
+        aws_secret_access_key = None,
+        aws_session_token = None,
+        verify_certs = true,
+        gzip = true,
+        max_retries = 5,
+        initial_backoff = 1.0,
+        max_backoff = 60.0,
+        timeout = 60.0,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        url: &str,
+        username: Option<String>,
+        password: Option<String>,
+        aws_region: Option<String>,
+        aws_service: String,
+        aws_access_key_id: Option<String>,
+        aws_secret_access_key: Option<String>,
+        aws_session_token: Option<String>,
+        verify_certs: bool,
+        gzip: bool,
+        max_retries: u32,
+        initial_backoff: f64,
+        max_backoff: f64,
+        timeout: f64,
+    ) -> PyResult<Self> {
+        let endpoint =
+            loader::Endpoint::parse(url).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let credentials = match (username, password) {
+            (Some(username), Some(password)) => Some((username, password)),
+            (None, None) => None,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "username and password must be given together",
+                ))
+            },
+        };
+        let aws = match (aws_region, aws_access_key_id, aws_secret_access_key) {
+            (Some(region), Some(access_key_id), Some(secret_access_key)) => {
+                Some(loader::AwsSigning {
+                    region,
+                    service: aws_service,
+                    access_key_id,
+                    secret_access_key,
+                    session_token: aws_session_token,
+                })
+            },
+            (None, None, None) if aws_session_token.is_none() => None,
+            _ => return Err(PyValueError::new_err(
+                "aws_region, aws_access_key_id and aws_secret_access_key must be given together",
+            )),
+        };
+        if credentials.is_some() && aws.is_some() {
+            return Err(PyValueError::new_err(
+                "requests are signed either with a username and password or with AWS credentials, not both",
+            ));
+        }
+        let timeout = seconds("timeout", timeout)?;
+        if timeout.is_zero() {
+            return Err(PyValueError::new_err("timeout must be greater than 0"));
+        }
+        let options = loader::LoaderOptions {
+            credentials,
+            aws,
+            verify_certs,
+            gzip,
+            max_retries,
+            initial_backoff: seconds("initial_backoff", initial_backoff)?,
+            max_backoff: seconds("max_backoff", max_backoff)?,
+            timeout,
+        };
+        Ok(Self {
+            loader: loader::OpenSearchLoader::new(endpoint, options),
+        })
+    }
+
+    #[getter]
+    fn url(&self) -> String {
+        let endpoint = self.loader.endpoint();
+        format!(
+            "{}://{}{}",
+            endpoint.scheme(),
+            endpoint.authority(),
+            endpoint.path
+        )
+    }
+
+    #[pyo3(signature = (body, index = None))]
+    fn send<'py>(
+        &self,
+        py: Python<'py>,
+        body: &[u8],
+        index: Option<&str>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let report = py.detach(|| self.loader.send(body, index))?;
+        chunk_report_to_py(py, &report)
+    }
+
+    #[pyo3(signature = (bodies, index = None, num_threads = None))]
+    fn load<'py>(
+        &self,
+        py: Python<'py>,
+        bodies: Vec<Vec<u8>>,
+        index: Option<&str>,
+        num_threads: Option<usize>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let reports = run_parallel(py, num_threads, || self.loader.send_all(&bodies, index))??;
+        reports
+            .iter()
+            .map(|report| chunk_report_to_py(py, report))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OpenSearchLoader({:?})", self.url())
+    }
+}
+
+#[pyclass(frozen)]
+struct IndexSchemaOptions {
+    options: mappings::IndexSchemaOptions,
+}
+
+#[pymethods]
+impl IndexSchemaOptions {
+    #[new]
+    #[pyo3(signature = (
+        index,
+        number_of_shards = None,
+        number_of_replicas = None,
+        embedding_dimension = None,
+        embedding_field = "embedding",
+        space_type = "cosinesimil",
+        title_keyword = false,
+    ))]
+    fn new(
+        index: &str,
+        number_of_shards: Option<u32>,
+        number_of_replicas: Option<u32>,
+        embedding_dimension: Option<usize>,
+        embedding_field: &str,
+        space_type: &str,
+        title_keyword: bool,
+    ) -> PyResult<Self> {
+        let index = mappings::IndexKind::from_name(index).ok_or_else(|| {
+            PyValueError::new_err(format!("index must be 'works' or 'dmps', got '{index}'"))
+        })?;
+        if number_of_shards == Some(0) {
+            return Err(PyValueError::new_err(
+                "number_of_shards must be greater than 0",
+            ));
+        }
+        if !mappings::SPACE_TYPES.contains(&space_type) {
+            return Err(PyValueError::new_err(format!(
+                "space_type must be one of {}, got '{space_type}'",
+                mappings::SPACE_TYPES.join(", ")
+            )));
+        }
+        let embedding = match embedding_dimension {
+            Some(0) => {
+                return Err(PyValueError::new_err(
+                    "embedding_dimension must be greater than 0",
+                ))
+            },
+            Some(dimension) => Some(mappings::EmbeddingField {
+                name: embedding_field.to_string(),
+                dimension,
+                space_type: space_type.to_string(),
+            }),
+            None => None,
+        };
+        Ok(Self {
+            options: mappings::IndexSchemaOptions {
+                number_of_shards,
+                number_of_replicas,
+                embedding,
+                title_keyword,
+                ..mappings::IndexSchemaOptions::new(index)
+            },
+        })
+    }
+
+    #[getter]
+    fn index(&self) -> &'static str {
+        self.options.index.as_str()
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (options))]
+fn generate_index_mapping<'py>(
+    py: Python<'py>,
+    options: &IndexSchemaOptions,
+) -> PyResult<Bound<'py, PyAny>> {
+    json_to_py(py, &mappings::generate_index_mapping(&options.options))
+}
+
+#[pyfunction]
+#[pyo3(signature = (level))]
+fn set_log_level(level: &Bound<'_, PyAny>) -> PyResult<()> {
+    let filter = match level.extract::<i64>() {
+        Ok(level) => logging::level_filter(level),
+        Err(_) => {
+            let name: String = level.extract()?;
+            logging::level_filter_from_name(&name)
+                .ok_or_else(|| PyValueError::new_err(format!("unknown log level: {name}")))?
+        },
+    };
+    log::set_max_level(filter);
+    Ok(())
+}
+
+#[pymodule]
+fn _internal(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+
+    // Add Python functions
+    m.add_function(wrap_pyfunction!(configure, m)?)?;
+    m.add_class::<NameOptions>()?;
+    m.add_function(wrap_pyfunction!(parse_name, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_name_many, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_names_list, m)?)?;
+    m.add_class::<ParsedName>()?;
+    m.add_function(wrap_pyfunction!(parse_name_struct, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_name_many_struct, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_names_list_struct, m)?)?;
+    m.add_function(wrap_pyfunction!(is_organization, m)?)?;
+    m.add_function(wrap_pyfunction!(revert_inverted_index, m)?)?;
+    m.add_function(wrap_pyfunction!(revert_inverted_index_many, m)?)?;
+    m.add_function(wrap_pyfunction!(revert_inverted_indexes, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_markup, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_markup_many, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_markup_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(profile_texts, m)?)?;
+    m.add_function(wrap_pyfunction!(has_alphabetic_initials, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_title, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_title_metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_license, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_doi, m)?)?;
+    m.add_function(wrap_pyfunction!(dmp_work_relevance, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_keywords, m)?)?;
+    m.add_function(wrap_pyfunction!(keyword_overlap, m)?)?;
+    m.add_function(wrap_pyfunction!(shard_bucket, m)?)?;
+    m.add_function(wrap_pyfunction!(shard_key, m)?)?;
+    m.add_function(wrap_pyfunction!(record_fingerprint, m)?)?;
+    m.add_function(wrap_pyfunction!(to_canonical_json, m)?)?;
+    m.add_function(wrap_pyfunction!(revert_and_detect_language, m)?)?;
+    m.add_function(wrap_pyfunction!(surname_matches_expected, m)?)?;
+    m.add_function(wrap_pyfunction!(citation_name, m)?)?;
+    m.add_function(wrap_pyfunction!(author_count, m)?)?;
+    m.add_function(wrap_pyfunction!(truncate_authors, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_author_join, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_grant_ids, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_identifiers, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_funding_statements, m)?)?;
+    m.add_function(wrap_pyfunction!(combined_relevance, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_date, m)?)?;
+    m.add_function(wrap_pyfunction!(bylines_match, m)?)?;
+    m.add_function(wrap_pyfunction!(tokenize_with_spans, m)?)?;
+    m.add_function(wrap_pyfunction!(initials_compatible, m)?)?;
+    m.add_function(wrap_pyfunction!(tidy_reconstructed, m)?)?;
+    m.add_function(wrap_pyfunction!(record_completeness, m)?)?;
+    m.add_function(wrap_pyfunction!(revert_iter, m)?)?;
+    m.add_class::<AbstractWords>()?;
+    m.add_function(wrap_pyfunction!(doi_prefix, m)?)?;
+    m.add_function(wrap_pyfunction!(doi_same_registrant, m)?)?;
+    m.add_function(wrap_pyfunction!(cjk_surname_match, m)?)?;
+    m.add_function(wrap_pyfunction!(citekey, m)?)?;
+    m.add_function(wrap_pyfunction!(issn_set_match, m)?)?;
+    m.add_function(wrap_pyfunction!(title_type_hint, m)?)?;
+    m.add_function(wrap_pyfunction!(revert_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_names_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_orcid, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_ror, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_institution_ids, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_names, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_citation, m)?)?;
+    m.add_function(wrap_pyfunction!(phonetic_encode, m)?)?;
+    m.add_function(wrap_pyfunction!(build_inverted_index, m)?)?;
+    m.add_class::<JsonlReader>()?;
+    m.add_function(wrap_pyfunction!(normalize_text, m)?)?;
+    m.add_function(wrap_pyfunction!(clean_text, m)?)?;
+    m.add_function(wrap_pyfunction!(jaro_winkler, m)?)?;
+    m.add_function(wrap_pyfunction!(jaro_winkler_many, m)?)?;
+    m.add_function(wrap_pyfunction!(normalized_levenshtein, m)?)?;
+    m.add_function(wrap_pyfunction!(normalized_levenshtein_many, m)?)?;
+    m.add_function(wrap_pyfunction!(token_sort_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(token_sort_ratio_many, m)?)?;
+    m.add_function(wrap_pyfunction!(token_set_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(token_set_ratio_many, m)?)?;
+    m.add_function(wrap_pyfunction!(containment_score, m)?)?;
+    m.add_function(wrap_pyfunction!(containment_score_many, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_language, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_language_many, m)?)?;
+    m.add_function(wrap_pyfunction!(transform_crossref_work, m)?)?;
+    m.add_function(wrap_pyfunction!(transform_datacite_record, m)?)?;
+    m.add_function(wrap_pyfunction!(transform_openalex_work, m)?)?;
+    m.add_function(wrap_pyfunction!(transform_dmp, m)?)?;
+    m.add_function(wrap_pyfunction!(transform_works_many, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_work_type, m)?)?;
+    m.add_class::<FieldSelectors>()?;
+    m.add_function(wrap_pyfunction!(extract_fields, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_fields_many, m)?)?;
+    m.add_function(wrap_pyfunction!(external_sort, m)?)?;
+    let work_types: Vec<&str> = transform::WorkType::ALL
+        .iter()
+        .map(|work_type| work_type.as_str())
+        .collect();
+    m.add("WORK_TYPES", work_types)?;
+    m.add_function(wrap_pyfunction!(normalize_funder_name, m)?)?;
+    m.add_function(wrap_pyfunction!(match_funder, m)?)?;
+    m.add_class::<FunderRegistry>()?;
+    m.add_class::<InstitutionIndex>()?;
+    m.add_function(wrap_pyfunction!(normalize_institution_name, m)?)?;
+    m.add_function(wrap_pyfunction!(match_institution, m)?)?;
+    m.add_function(wrap_pyfunction!(match_institution_many, m)?)?;
+    m.add_class::<LookupTable>()?;
+    m.add_function(wrap_pyfunction!(parse_affiliation, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_country, m)?)?;
+    m.add_function(wrap_pyfunction!(tokenize, m)?)?;
+    m.add_class::<MarkupOptions>()?;
+    m.add_class::<NullRules>()?;
+    m.add_function(wrap_pyfunction!(nullify, m)?)?;
+    m.add_class::<BoilerplateRules>()?;
+    m.add_function(wrap_pyfunction!(strip_boilerplate, m)?)?;
+    m.add_class::<TokenizerOptions>()?;
+    m.add("ENGLISH_STOP_WORDS", core::ENGLISH_STOP_WORDS.to_vec())?;
+    m.add_class::<Bm25Index>()?;
+    m.add_class::<IdfTable>()?;
+    m.add_function(wrap_pyfunction!(tfidf_cosine, m)?)?;
+    m.add_function(wrap_pyfunction!(tfidf_cosine_many, m)?)?;
+    m.add_function(wrap_pyfunction!(ranking_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(pr_curve, m)?)?;
+    m.add_function(wrap_pyfunction!(choose_threshold, m)?)?;
+    m.add_class::<GroundTruthRecord>()?;
+    m.add_function(wrap_pyfunction!(load_ground_truth, m)?)?;
+    m.add_function(wrap_pyfunction!(save_ground_truth, m)?)?;
+    m.add_function(wrap_pyfunction!(cohen_kappa, m)?)?;
+    m.add_function(wrap_pyfunction!(krippendorff_alpha, m)?)?;
+    m.add_function(wrap_pyfunction!(sample_candidate_pairs, m)?)?;
+    m.add_class::<SamplingStrategy>()?;
+    m.add_function(wrap_pyfunction!(generate_negative_pairs, m)?)?;
+    m.add_class::<HardNegativeOptions>()?;
+    m.add_function(wrap_pyfunction!(dedupe_works, m)?)?;
+    m.add_function(wrap_pyfunction!(blocking_keys, m)?)?;
+    m.add_function(wrap_pyfunction!(blocking_keys_many, m)?)?;
+    m.add_function(wrap_pyfunction!(minhash_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(minhash_signature_many, m)?)?;
+    m.add_function(wrap_pyfunction!(lsh_candidate_pairs, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_date, m)?)?;
+    m.add_function(wrap_pyfunction!(date_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_issn, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_isbn, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_acronyms, m)?)?;
+    m.add_function(wrap_pyfunction!(expand_acronyms, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_keyphrases, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_keyphrases_many, m)?)?;
+    m.add_function(wrap_pyfunction!(build_work_query, m)?)?;
+    m.add_class::<WorkQueryOptions>()?;
+    m.add_function(wrap_pyfunction!(parse_msearch_response, m)?)?;
+    m.add_function(wrap_pyfunction!(fuse_rankings, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_scores, m)?)?;
+    m.add_function(wrap_pyfunction!(score_candidate, m)?)?;
+    m.add_function(wrap_pyfunction!(score_candidates, m)?)?;
+    m.add_function(wrap_pyfunction!(featurize_pairs, m)?)?;
+    m.add_class::<MatchModel>()?;
+    m.add_function(wrap_pyfunction!(author_overlap, m)?)?;
+    m.add_function(wrap_pyfunction!(romanize, m)?)?;
+    m.add_function(wrap_pyfunction!(expand_given_name_variants, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_emails, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_url, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_award_id, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_award_ids, m)?)?;
+    m.add_function(wrap_pyfunction!(split_sentences, m)?)?;
+    m.add_function(wrap_pyfunction!(truncate_to_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(prepare_embedding_text, m)?)?;
+    m.add_function(wrap_pyfunction!(prepare_embedding_text_many, m)?)?;
+    m.add_class::<EmbeddingTextOptions>()?;
+    m.add_function(wrap_pyfunction!(cosine_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(top_k, m)?)?;
+    m.add_function(wrap_pyfunction!(write_works_parquet, m)?)?;
+    m.add_class::<CsvOptions>()?;
+    m.add_function(wrap_pyfunction!(transform_csv, m)?)?;
+    m.add_class::<Pipeline>()?;
+    m.add_class::<ParquetWriteOptions>()?;
+    m.add_function(wrap_pyfunction!(read_works_parquet, m)?)?;
+    m.add_class::<WorksFilter>()?;
+    m.add_class::<WorksParquetReader>()?;
+    m.add_function(wrap_pyfunction!(diff_snapshots, m)?)?;
+    m.add_function(wrap_pyfunction!(write_candidates, m)?)?;
+    m.add_function(wrap_pyfunction!(write_candidates_many, m)?)?;
+    m.add_function(wrap_pyfunction!(read_candidates, m)?)?;
+    m.add_function(wrap_pyfunction!(to_bulk_actions, m)?)?;
+    m.add_class::<OpenSearchLoader>()?;
+    m.add_class::<IndexSchemaOptions>()?;
+    m.add_function(wrap_pyfunction!(generate_index_mapping, m)?)?;
+
+    m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
 
-    // Configures logging for core functions.
-    // Enable with: export RUST_LOG=dmpworks_rust=debug before running
-    // transformations.
-    let _ = env_logger::try_init();
+    // Forwards warnings, e.g. about invalid JSON, to Python's logging module.
+    logging::init();
 
     Ok(())
 }
@@ -0,0 +1,742 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use hmac::{Hmac, KeyInit, Mac};
+use rayon::prelude::*;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    StreamOwned,
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// HTTP statuses of bulk requests that OpenSearch rejected because it is overloaded, which are retried.
+const RETRY_STATUSES: [u16; 2] = [429, 503];
+
+/// Largest response headers read before giving up on a response, so that a misbehaving server can't exhaust memory.
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// Largest response body read, far above that of any bulk response, for the same reason.
+const MAX_BODY_BYTES: usize = 256 * 1024 * 1024;
+
+/// An OpenSearch endpoint: its host, port, whether it is reached over TLS and the path its API is served under,
+/// without a trailing slash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub path: String,
+}
+
+impl Endpoint {
+    /// Parses an `http://host[:port][/path]` or `https://host[:port][/path]` URL.
+    pub fn parse(url: &str) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        let (tls, rest) = match url.split_once("://") {
+            Some(("http", rest)) => (false, rest),
+            Some(("https", rest)) => (true, rest),
+            _ => return Err(invalid(format!("invalid OpenSearch URL: {url}"))),
+        };
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        if authority.contains('@') {
+            return Err(invalid(
+                "credentials must be given as username and password, not in the URL".to_string(),
+            ));
+        }
+        let (host, port) = match authority.strip_prefix('[') {
+            // An IPv6 address, e.g. [::1]:9200
+            Some(bracketed) => {
+                let (host, port) = bracketed
+                    .split_once(']')
+                    .ok_or_else(|| invalid(format!("invalid OpenSearch URL: {url}")))?;
+                (host, port.strip_prefix(':'))
+            },
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| invalid(format!("invalid port in OpenSearch URL: {url}")))?,
+            None if tls => 443,
+            None => 80,
+        };
+        if host.is_empty() {
+            return Err(invalid(format!("invalid OpenSearch URL: {url}")));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            tls,
+            path: path.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub fn scheme(&self) -> &'static str {
+        if self.tls {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// The host and port, as in a URL, with IPv6 addresses in brackets, e.g. "[::1]:9200".
+    pub fn authority(&self) -> String {
+        format!("{}:{}", self.bracketed_host(), self.port)
+    }
+
+    /// The value of the `Host` header of requests: the authority, without the port when it is the scheme's default.
+    fn host_header(&self) -> String {
+        if self.port == if self.tls { 443 } else { 80 } {
+            self.bracketed_host()
+        } else {
+            self.authority()
+        }
+    }
+
+    fn bracketed_host(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        }
+    }
+}
+
+/// AWS credentials and the region and service of an Amazon OpenSearch Service domain ("es") or OpenSearch Serverless
+/// collection ("aoss"), for signing requests with AWS Signature Version 4.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AwsSigning {
+    pub region: String,
+    pub service: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// The session token of temporary credentials, e.g. those of an IAM role.
+    pub session_token: Option<String>,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes a path for a canonical request, leaving unreserved characters and slashes as they are.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            },
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Formats a time as the basic ISO 8601 date-time of AWS signatures, e.g. "20150830T123600Z".
+fn amz_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    // Howard Hinnant's `civil_from_days` algorithm
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let time_of_day = seconds % 86_400;
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60
+    )
+}
+
+impl AwsSigning {
+    /// The headers signing a POST of `body` to `path` on `host` at `time` with Signature Version 4: the date, the
+    /// hash of the body, the session token of temporary credentials and the `Authorization` header.
+    fn headers(
+        &self,
+        host: &str,
+        path: &str,
+        body: &[u8],
+        time: SystemTime,
+    ) -> Vec<(&'static str, String)> {
+        let date_time = amz_date(time);
+        let date = &date_time[..8];
+        let payload_hash = hex(&Sha256::digest(body));
+        let mut headers = vec![
+            ("host", host.to_string()),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", date_time.clone()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+            .collect();
+        let canonical_request = format!(
+            "POST\n{}\n\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+            canonical_uri(path)
+        );
+        let scope = format!("{date}/{}/{}/aws4_request", self.region, self.service);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{date_time}\n{scope}\n{}",
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = [date, &self.region, &self.service, "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_access_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part),
+            );
+        let signature = hex(&hmac_sha256(&key, &string_to_sign));
+
+        // The Host header is written with the rest of the request
+        headers.remove(0);
+        headers.push((
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                self.access_key_id
+            ),
+        ));
+        headers
+    }
+}
+
+/// Accepts any server certificate, for clusters with self-signed certificates when `verify_certs` is off. Handshake
+/// signatures are still checked, so the server must hold the key of the certificate it presents.
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// The TLS configuration of a loader, trusting the Mozilla root certificates, or any certificate without
+/// `verify_certs`.
+fn tls_config(verify_certs: bool) -> ClientConfig {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .expect("the ring provider supports the default protocol versions");
+    if verify_certs {
+        let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        builder.with_root_certificates(roots).with_no_client_auth()
+    } else {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+            .with_no_client_auth()
+    }
+}
+
+/// A connection to OpenSearch, over TLS for https endpoints.
+enum Connection {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientConnection, TcpStream>>),
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Connection::Plain(stream) => stream.read(buf),
+            Connection::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Connection::Plain(stream) => stream.write(buf),
+            Connection::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Connection::Plain(stream) => stream.flush(),
+            Connection::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Options for an `OpenSearchLoader`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoaderOptions {
+    /// Basic authentication credentials.
+    pub credentials: Option<(String, String)>,
+    /// How requests are signed for Amazon OpenSearch Service, instead of with Basic authentication.
+    pub aws: Option<AwsSigning>,
+    /// Whether the certificates of https endpoints are checked against the Mozilla root certificates.
+    pub verify_certs: bool,
+    /// Whether request bodies are gzip-compressed.
+    pub gzip: bool,
+    /// Retries of a bulk request rejected with a `RETRY_STATUSES` status or failing before its body is fully sent, e.g.
+    /// to connect, before giving up.
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each one after.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Timeout of connecting and of each read and write of a request.
+    pub timeout: Duration,
+}
+
+impl Default for LoaderOptions {
+    fn default() -> Self {
+        Self {
+            credentials: None,
+            aws: None,
+            verify_certs: true,
+            gzip: true,
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// An action of a bulk request that OpenSearch failed, with its position in the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemError {
+    pub position: usize,
+    pub id: Option<String>,
+    pub status: u16,
+    /// The type and reason of the error, e.g. "mapper_parsing_exception".
+    pub error_type: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// The outcome of sending one bulk request body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkReport {
+    /// Actions in the response.
+    pub items: usize,
+    pub errors: Vec<ItemError>,
+    /// Requests sent, including retries.
+    pub attempts: u32,
+    /// Why the body wasn't loaded at all, e.g. a non-retryable status or retries running out. None when OpenSearch
+    /// accepted the request, even if some of its actions failed.
+    pub error: Option<String>,
+    pub took: Duration,
+}
+
+/// Why a request failed: before its body was fully sent, so that OpenSearch can't have acted on it and it is safe to
+/// send again, or after, e.g. timing out waiting for the response, when its actions may already have been applied.
+enum PostError {
+    NotSent(io::Error),
+    Sent(io::Error),
+}
+
+/// An HTTP response: its status, `Retry-After` header in seconds and body.
+struct Response {
+    status: u16,
+    retry_after: Option<u64>,
+    body: Vec<u8>,
+}
+
+/// Reads a CRLF-terminated line of a response, failing on lines longer than `MAX_HEADER_BYTES`.
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = Vec::new();
+    reader
+        .take(MAX_HEADER_BYTES as u64)
+        .read_until(b'\n', &mut line)?;
+    if line.last() != Some(&b'\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "truncated HTTP response",
+        ));
+    }
+    String::from_utf8(line)
+        .map(|line| line.trim_end().to_string())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP response"))
+}
+
+/// Reads `length` more bytes of a body into `body`, or up to the end of the response when None, growing it as they
+/// arrive rather than allocating a length taken from the response up front. Fails once the body would be longer than
+/// `MAX_BODY_BYTES`.
+fn read_body_bytes(
+    reader: &mut impl BufRead,
+    body: &mut Vec<u8>,
+    length: Option<usize>,
+) -> io::Result<()> {
+    let too_large = || io::Error::new(io::ErrorKind::InvalidData, "HTTP response body too large");
+    let remaining = MAX_BODY_BYTES - body.len();
+    if length.is_some_and(|length| length > remaining) {
+        return Err(too_large());
+    }
+    // Without a length, one byte more than is allowed is read to tell a body of exactly the limit from a longer one
+    let read = reader
+        .take(length.unwrap_or(remaining + 1) as u64)
+        .read_to_end(body)?;
+    match length {
+        Some(length) if read < length => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "truncated HTTP response",
+        )),
+        None if read > remaining => Err(too_large()),
+        _ => Ok(()),
+    }
+}
+
+/// Reads a body sent with chunked transfer encoding.
+fn read_chunked(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP chunk size"))?;
+        if size == 0 {
+            // Skip any trailers
+            while !read_line(reader)?.is_empty() {}
+            return Ok(body);
+        }
+        read_body_bytes(reader, &mut body, Some(size))?;
+        read_line(reader)?;
+    }
+}
+
+fn read_response(reader: &mut impl BufRead) -> io::Result<Response> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid HTTP response");
+    let status_line = read_line(reader)?;
+    let status = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .filter(|_| status_line.starts_with("HTTP/1."))
+        .ok_or_else(invalid)?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    let mut retry_after = None;
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':').ok_or_else(invalid)?;
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = Some(value.parse().map_err(|_| invalid())?),
+            "transfer-encoding" => chunked = value.eq_ignore_ascii_case("chunked"),
+            "retry-after" => retry_after = value.parse().ok(),
+            _ => {},
+        }
+    }
+
+    let body = if chunked {
+        read_chunked(reader)?
+    } else {
+        let mut body = Vec::new();
+        read_body_bytes(reader, &mut body, content_length)?;
+        body
+    };
+    Ok(Response {
+        status,
+        retry_after,
+        body,
+    })
+}
+
+/// The failed actions of a bulk response, and the number of actions in it.
+fn parse_bulk_response(body: &[u8]) -> io::Result<(usize, Vec<ItemError>)> {
+    let response: Value = serde_json::from_slice(body).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid bulk response: {e}"),
+        )
+    })?;
+    let items = response["items"].as_array().map_or(&[][..], Vec::as_slice);
+    let mut errors = Vec::new();
+    if response["errors"].as_bool() == Some(true) {
+        for (position, item) in items.iter().enumerate() {
+            // Each item is an object with one key, the action's type
+            let Some(result) = item.as_object().and_then(|item| item.values().next()) else {
+                continue;
+            };
+            let Some(error) = result.get("error") else {
+                continue;
+            };
+            let text = |value: &Value| value.as_str().map(str::to_string);
+            errors.push(ItemError {
+                position,
+                id: result.get("_id").and_then(text),
+                status: result["status"].as_u64().unwrap_or_default() as u16,
+                error_type: error.get("type").and_then(text),
+                reason: error.get("reason").and_then(text).or_else(|| text(error)),
+            });
+        }
+    }
+    Ok((items.len(), errors))
+}
+
+/// A client that sends NDJSON bulk request bodies, e.g. from `to_bulk_actions`, to OpenSearch's `_bulk` API over
+/// HTTP/1.1, or HTTPS, retrying with exponential backoff while OpenSearch is overloaded. Requests are authenticated
+/// with Basic authentication, e.g. for a local cluster, or signed with AWS Signature Version 4 for Amazon OpenSearch
+/// Service, such as the deployed cluster.
+#[derive(Debug, Clone)]
+pub struct OpenSearchLoader {
+    endpoint: Endpoint,
+    options: LoaderOptions,
+    /// The TLS configuration of https endpoints, shared by their connections.
+    tls: Option<Arc<ClientConfig>>,
+}
+
+impl OpenSearchLoader {
+    pub fn new(endpoint: Endpoint, options: LoaderOptions) -> Self {
+        let tls = endpoint
+            .tls
+            .then(|| Arc::new(tls_config(options.verify_certs)));
+        Self {
+            endpoint,
+            options,
+            tls,
+        }
+    }
+
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Sends one request with a new connection, closed after the response so that nothing is left to drain.
+    fn post(&self, path: &str, body: &[u8]) -> Result<Response, PostError> {
+        let mut connection = self.write_request(path, body).map_err(PostError::NotSent)?;
+        read_response(&mut BufReader::new(&mut connection)).map_err(PostError::Sent)
+    }
+
+    /// Connects and writes a request, returning the connection once the whole body is sent.
+    fn write_request(&self, path: &str, body: &[u8]) -> io::Result<Connection> {
+        let Endpoint { host, port, .. } = &self.endpoint;
+        let mut last_error = None;
+        let mut stream = None;
+        for addr in (host.as_str(), *port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.options.timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                },
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let stream = stream.ok_or_else(|| {
+            last_error.unwrap_or_else(|| io::Error::other(format!("no addresses for {host}")))
+        })?;
+        stream.set_read_timeout(Some(self.options.timeout))?;
+        stream.set_write_timeout(Some(self.options.timeout))?;
+        let mut connection = match &self.tls {
+            Some(config) => {
+                let server_name = ServerName::try_from(host.clone()).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid TLS server name {host}: {e}"),
+                    )
+                })?;
+                let tls =
+                    ClientConnection::new(config.clone(), server_name).map_err(io::Error::other)?;
+                Connection::Tls(Box::new(StreamOwned::new(tls, stream)))
+            },
+            None => Connection::Plain(stream),
+        };
+
+        let host_header = self.endpoint.host_header();
+        let mut head = format!(
+            "POST {path} HTTP/1.1\r\nHost: {host_header}\r\nContent-Type: application/x-ndjson\r\n\
+             Content-Length: {}\r\nConnection: close\r\n",
+            body.len()
+        );
+        if self.options.gzip {
+            head.push_str("Content-Encoding: gzip\r\n");
+        }
+        if let Some((username, password)) = &self.options.credentials {
+            let token = BASE64.encode(format!("{username}:{password}"));
+            head.push_str(&format!("Authorization: Basic {token}\r\n"));
+        }
+        if let Some(aws) = &self.options.aws {
+            for (name, value) in aws.headers(&host_header, path, body, SystemTime::now()) {
+                head.push_str(&format!("{name}: {value}\r\n"));
+            }
+        }
+        head.push_str("\r\n");
+
+        let mut writer = io::BufWriter::new(&mut connection);
+        writer.write_all(head.as_bytes())?;
+        writer.write_all(body)?;
+        writer.flush()?;
+        drop(writer);
+        Ok(connection)
+    }
+
+    /// The wait before a retry, as the server asked or doubling from `initial_backoff` up to `max_backoff`.
+    fn backoff(&self, retry: u32, retry_after: Option<u64>) -> Duration {
+        let exponential = self
+            .options
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry));
+        retry_after
+            .map(Duration::from_secs)
+            .unwrap_or(exponential)
+            .min(self.options.max_backoff)
+    }
+
+    /// Sends a bulk request body to the `_bulk` API, or that of `index` when given, blocking until it is loaded or
+    /// given up on. Requests rejected with a 429 or 503 status, or failing before the body is fully sent, e.g. to
+    /// connect, are retried up to `max_retries` times, honouring any `Retry-After` header. Requests failing after, e.g.
+    /// timing out waiting for the response, aren't, as OpenSearch may have applied their actions already and sending
+    /// them again would, e.g., index documents with generated IDs twice. Failures are reported rather than returned as
+    /// errors, so that one bad body doesn't stop the rest of a load.
+    pub fn send(&self, body: &[u8], index: Option<&str>) -> io::Result<ChunkReport> {
+        let start = Instant::now();
+        let path = match index {
+            Some(index) => format!("{}/{index}/_bulk", self.endpoint.path),
+            None => format!("{}/_bulk", self.endpoint.path),
+        };
+        let compressed;
+        let body = if self.options.gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(body)?;
+            compressed = encoder.finish()?;
+            &compressed[..]
+        } else {
+            body
+        };
+
+        let mut report = ChunkReport::default();
+        loop {
+            report.attempts += 1;
+            let retry_after = match self.post(&path, body) {
+                Ok(response) if (200..300).contains(&response.status) => {
+                    match parse_bulk_response(&response.body) {
+                        Ok((items, errors)) => {
+                            (report.items, report.errors, report.error) = (items, errors, None);
+                        },
+                        Err(e) => report.error = Some(e.to_string()),
+                    }
+                    break;
+                },
+                Ok(response) if RETRY_STATUSES.contains(&response.status) => {
+                    report.error = Some(format!("HTTP {}", response.status));
+                    response.retry_after
+                },
+                Ok(response) => {
+                    let text = String::from_utf8_lossy(&response.body);
+                    report.error = Some(format!("HTTP {}: {}", response.status, text.trim()));
+                    break;
+                },
+                Err(PostError::NotSent(e)) => {
+                    report.error = Some(e.to_string());
+                    None
+                },
+                Err(PostError::Sent(e)) => {
+                    report.error = Some(format!("no response after sending the request: {e}"));
+                    break;
+                },
+            };
+            if report.attempts > self.options.max_retries {
+                let error = report.error.take().unwrap_or_default();
+                report.error = Some(format!(
+                    "gave up after {} attempts: {error}",
+                    report.attempts
+                ));
+                break;
+            }
+            thread::sleep(self.backoff(report.attempts - 1, retry_after));
+        }
+        report.took = start.elapsed();
+        Ok(report)
+    }
+
+    /// Sends many bulk request bodies with `send`, as many at a time as the current rayon thread pool has threads,
+    /// returning their reports in order.
+    pub fn send_all(
+        &self,
+        bodies: &[Vec<u8>],
+        index: Option<&str>,
+    ) -> io::Result<Vec<ChunkReport>> {
+        bodies
+            .par_iter()
+            .map(|body| self.send(body, index))
+            .collect()
+    }
+}
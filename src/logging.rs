@@ -0,0 +1,89 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+
+/// Forwards log records to Python's `logging` module, so they reach the application's handlers. Each record goes to
+/// the logger named after its Rust module path, e.g. "dmpworks.rust.core" for `dmpworks_rust::core`.
+struct PythonLogger;
+
+/// The Python logging level of a Rust log level; Python has no trace level, so trace records use 5.
+fn python_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+/// The Python logger name of a Rust log target.
+fn logger_name(target: &str) -> String {
+    target
+        .replacen("dmpworks_rust", "dmpworks.rust", 1)
+        .replace("::", ".")
+}
+
+impl Log for PythonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = python_level(record.level());
+        let message = record.args().to_string();
+        // Logging must not fail the operation that logs, so errors raised by handlers are discarded
+        let _ = Python::attach(|py| -> PyResult<()> {
+            let logging = py.import("logging")?;
+            let logger = logging.call_method1("getLogger", (logger_name(record.target()),))?;
+            if logger.call_method1("isEnabledFor", (level,))?.is_truthy()? {
+                logger.call_method1("log", (level, message))?;
+            }
+            Ok(())
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Converts a Python logging level into the most verbose Rust level it lets through: levels above CRITICAL (50)
+/// disable logging, and levels below DEBUG (10) include trace records.
+pub fn level_filter(level: i64) -> LevelFilter {
+    match level {
+        51.. => LevelFilter::Off,
+        40..=50 => LevelFilter::Error,
+        30..=39 => LevelFilter::Warn,
+        20..=29 => LevelFilter::Info,
+        10..=19 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Converts the name of a Python logging level, e.g. "WARNING", into a Rust level filter like `level_filter`, ignoring
+/// case. "TRACE" and "OFF" are also accepted.
+pub fn level_filter_from_name(name: &str) -> Option<LevelFilter> {
+    match name.to_uppercase().as_str() {
+        "OFF" => Some(LevelFilter::Off),
+        "CRITICAL" | "FATAL" | "ERROR" => Some(LevelFilter::Error),
+        "WARNING" | "WARN" => Some(LevelFilter::Warn),
+        "INFO" => Some(LevelFilter::Info),
+        "DEBUG" => Some(LevelFilter::Debug),
+        "TRACE" | "NOTSET" => Some(LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// Installs the logger that forwards to Python, unless another logger is installed already. Records below the
+/// level in the RUST_LOG environment variable, e.g. "debug", or WARNING by default, are dropped before reaching
+/// Python; `log::set_max_level` changes the level.
+pub fn init() {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Warn);
+    if log::set_logger(&PythonLogger).is_ok() {
+        log::set_max_level(level);
+    }
+}
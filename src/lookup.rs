@@ -0,0 +1,279 @@
+use crate::core::fold_name;
+use crate::funders::normalize_funder_name;
+use crate::institutions::normalize_institution_name;
+use memmap2::Mmap;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Leading bytes of a lookup table file, ending in its format version.
+const MAGIC: &[u8; 8] = b"DMPWLT01";
+
+/// Size of a lookup table file's header: the magic bytes, the key normalization padded to eight bytes, and the
+/// numbers of keys and of strings, keys and values together.
+const HEADER_LEN: usize = 32;
+
+/// How the keys of a `LookupTable` are normalized, both when it is written and when it is looked up, so that the
+/// table and its callers always agree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyNormalization {
+    /// Keys are used as given.
+    Exact,
+    /// Keys are normalized with `normalize_funder_name`.
+    Funder,
+    /// Keys are normalized with `normalize_institution_name`.
+    Institution,
+    /// Keys are folded like personal names, e.g. for nickname tables.
+    Name,
+}
+
+impl KeyNormalization {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "exact" => Some(KeyNormalization::Exact),
+            "funder" => Some(KeyNormalization::Funder),
+            "institution" => Some(KeyNormalization::Institution),
+            "name" => Some(KeyNormalization::Name),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeyNormalization::Exact => "exact",
+            KeyNormalization::Funder => "funder",
+            KeyNormalization::Institution => "institution",
+            KeyNormalization::Name => "name",
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            KeyNormalization::Exact => 0,
+            KeyNormalization::Funder => 1,
+            KeyNormalization::Institution => 2,
+            KeyNormalization::Name => 3,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(KeyNormalization::Exact),
+            1 => Some(KeyNormalization::Funder),
+            2 => Some(KeyNormalization::Institution),
+            3 => Some(KeyNormalization::Name),
+            _ => None,
+        }
+    }
+
+    /// Normalizes a key, returning None when nothing is left of it.
+    pub fn apply(self, key: &str) -> Option<String> {
+        match self {
+            KeyNormalization::Exact => (!key.is_empty()).then(|| key.to_string()),
+            KeyNormalization::Funder => normalize_funder_name(Some(key)),
+            KeyNormalization::Institution => normalize_institution_name(Some(key)),
+            KeyNormalization::Name => Some(fold_name(key)).filter(|key| !key.is_empty()),
+        }
+    }
+}
+
+fn invalid_table(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid lookup table: {message}"),
+    )
+}
+
+/// Reads the `i`th little-endian `u64` of `bytes`.
+fn read_u64(bytes: &[u8], i: usize) -> u64 {
+    u64::from_le_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap())
+}
+
+/// A sorted table of keys, each with one or more values, e.g. funder names and their ROR IDs or nicknames and the
+/// formal names they stand for, stored in a compact file that is memory-mapped read-only when opened. Worker
+/// processes opening the same file share one copy of it in the page cache instead of each building their own.
+///
+/// The file is a header followed by two arrays of little-endian `u64`s and the UTF-8 text of the table's strings,
+/// each key followed by its values. The first array holds the position of each key among the strings and the
+/// second the start of each string in the text, each with a final element for the end, so that key `i`'s values are
+/// the strings between it and key `i + 1`. Keys are sorted and unique, so they are looked up by binary search
+/// without any further index.
+pub struct LookupTable {
+    bytes: Mmap,
+    normalization: KeyNormalization,
+    num_keys: usize,
+    num_strings: usize,
+}
+
+impl LookupTable {
+    /// Writes a table of `entries` to `path`, replacing any file there. Keys are normalized with `normalization`,
+    /// skipping those that normalize to nothing or have no values, and the values of keys that normalize alike are
+    /// merged in order without duplicates. The table is written to a temporary file next to `path` and renamed into
+    /// place, so that processes with the old file open keep reading it unchanged. Returns the number of keys written.
+    pub fn write(
+        path: &Path,
+        entries: impl IntoIterator<Item = (String, Vec<String>)>,
+        normalization: KeyNormalization,
+    ) -> io::Result<usize> {
+        let mut merged: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (key, values) in entries {
+            let Some(key) = normalization.apply(&key) else {
+                continue;
+            };
+            let merged = merged.entry(key).or_default();
+            for value in values {
+                if !merged.contains(&value) {
+                    merged.push(value);
+                }
+            }
+        }
+        merged.retain(|_, values| !values.is_empty());
+
+        let mut text = String::new();
+        let mut key_positions = Vec::with_capacity(merged.len() + 1);
+        let mut string_starts = Vec::new();
+        for (key, values) in &merged {
+            key_positions.push(string_starts.len() as u64);
+            for string in std::iter::once(key).chain(values) {
+                string_starts.push(text.len() as u64);
+                text.push_str(string);
+            }
+        }
+        key_positions.push(string_starts.len() as u64);
+        let num_strings = string_starts.len();
+        string_starts.push(text.len() as u64);
+
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".tmp");
+        let tmp_path = path.with_file_name(file_name);
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[normalization.code(), 0, 0, 0, 0, 0, 0, 0])?;
+        writer.write_all(&(merged.len() as u64).to_le_bytes())?;
+        writer.write_all(&(num_strings as u64).to_le_bytes())?;
+        for offset in key_positions.iter().chain(&string_starts) {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        writer.write_all(text.as_bytes())?;
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(merged.len())
+    }
+
+    /// Opens a table written by `write`, checking its header, offsets and key order up front so that lookups can't
+    /// go out of bounds.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only and only read through `self.bytes`. Table files are replaced by renaming
+        // rather than written in place (see `write`), so the mapped pages don't change under a reader.
+        let bytes = unsafe { Mmap::map(&file)? };
+        let data = &bytes[..];
+        if data.len() < HEADER_LEN || &data[..8] != MAGIC {
+            return Err(invalid_table("not a lookup table file"));
+        }
+        let normalization = KeyNormalization::from_code(data[8])
+            .ok_or_else(|| invalid_table("unknown key normalization"))?;
+        let too_large = || invalid_table("too many entries");
+        let num_keys = usize::try_from(read_u64(data, 2)).map_err(|_| too_large())?;
+        let num_strings = usize::try_from(read_u64(data, 3)).map_err(|_| too_large())?;
+        let offsets_len = num_keys
+            .checked_add(num_strings)
+            .and_then(|n| n.checked_add(2)?.checked_mul(8))
+            .ok_or_else(too_large)?;
+        if data.len() - HEADER_LEN < offsets_len {
+            return Err(invalid_table("truncated offsets"));
+        }
+
+        let table = Self {
+            bytes,
+            normalization,
+            num_keys,
+            num_strings,
+        };
+        let text = std::str::from_utf8(table.text_bytes())
+            .map_err(|_| invalid_table("text is not valid UTF-8"))?;
+        let key_positions: Vec<u64> = (0..=num_keys).map(|i| table.key_position(i)).collect();
+        let string_starts: Vec<u64> = (0..=num_strings).map(|i| table.string_start(i)).collect();
+        // Every key has at least one value, and strings lie end to end within the text on character boundaries.
+        if key_positions[0] != 0
+            || key_positions
+                .windows(2)
+                .any(|w| w[0].checked_add(2).is_none_or(|min| w[1] < min))
+            || key_positions[num_keys] != num_strings as u64
+            || string_starts[0] != 0
+            || string_starts.windows(2).any(|w| w[1] < w[0])
+            || string_starts[num_strings] != text.len() as u64
+            || !string_starts
+                .iter()
+                .all(|&start| text.is_char_boundary(start as usize))
+        {
+            return Err(invalid_table("offsets out of order or out of bounds"));
+        }
+        if (1..num_keys).any(|i| table.key(i - 1) >= table.key(i)) {
+            return Err(invalid_table("keys not sorted"));
+        }
+        Ok(table)
+    }
+
+    /// The position among the strings of the `i`th key, or the number of strings for the key after the last.
+    fn key_position(&self, i: usize) -> u64 {
+        read_u64(&self.bytes[HEADER_LEN..], i)
+    }
+
+    /// The start in the text of the `i`th string, or the length of the text for the string after the last.
+    fn string_start(&self, i: usize) -> u64 {
+        read_u64(&self.bytes[HEADER_LEN..], self.num_keys + 1 + i)
+    }
+
+    /// The UTF-8 text of the strings, after the offsets.
+    fn text_bytes(&self) -> &[u8] {
+        &self.bytes[HEADER_LEN + (self.num_keys + self.num_strings + 2) * 8..]
+    }
+
+    /// The `i`th string.
+    fn string(&self, i: usize) -> &str {
+        let range = self.string_start(i) as usize..self.string_start(i + 1) as usize;
+        // The text and string boundaries were checked when the table was opened.
+        std::str::from_utf8(&self.text_bytes()[range]).unwrap_or_default()
+    }
+
+    /// The `i`th key.
+    fn key(&self, i: usize) -> &str {
+        self.string(self.key_position(i) as usize)
+    }
+
+    /// Returns the number of keys in the table.
+    pub fn len(&self) -> usize {
+        self.num_keys
+    }
+
+    pub fn normalization(&self) -> KeyNormalization {
+        self.normalization
+    }
+
+    /// Looks up the values of a key, normalizing it like the table's keys, returning an empty list when it is
+    /// missing.
+    pub fn get(&self, key: &str) -> Vec<&str> {
+        let Some(key) = self.normalization.apply(key) else {
+            return Vec::new();
+        };
+        let (mut low, mut high) = (0, self.num_keys);
+        while low < high {
+            let middle = (low + high) / 2;
+            match self.key(middle).cmp(key.as_str()) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => {
+                    let values = self.key_position(middle) + 1..self.key_position(middle + 1);
+                    return values.map(|i| self.string(i as usize)).collect();
+                },
+            }
+        }
+        Vec::new()
+    }
+}
@@ -0,0 +1,311 @@
+use crate::parquet_io::{AUTHOR_FIELDS, INSTITUTION_FIELDS};
+use serde_json::{json, Map, Value};
+
+/// An index of documents that works and DMPs are matched across.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexKind {
+    Works,
+    Dmps,
+}
+
+impl IndexKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "works" => Some(IndexKind::Works),
+            "dmps" => Some(IndexKind::Dmps),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IndexKind::Works => "works",
+            IndexKind::Dmps => "dmps",
+        }
+    }
+}
+
+/// Vector similarities supported by OpenSearch's k-NN fields.
+pub const SPACE_TYPES: [&str; 3] = ["cosinesimil", "l2", "innerproduct"];
+
+/// A k-NN vector field holding a document's embedding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingField {
+    pub name: String,
+    pub dimension: usize,
+    /// One of `SPACE_TYPES`.
+    pub space_type: String,
+}
+
+/// Options for `generate_index_mapping`. Shard and replica counts default to those of the index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSchemaOptions {
+    pub index: IndexKind,
+    pub number_of_shards: Option<u32>,
+    pub number_of_replicas: Option<u32>,
+    pub embedding: Option<EmbeddingField>,
+    /// Adds a folded keyword subfield to titles for exact title matches.
+    pub title_keyword: bool,
+}
+
+impl IndexSchemaOptions {
+    pub fn new(index: IndexKind) -> Self {
+        Self {
+            index,
+            number_of_shards: None,
+            number_of_replicas: None,
+            embedding: None,
+            title_keyword: false,
+        }
+    }
+}
+
+/// How a field of an index document is indexed.
+enum FieldKind {
+    /// An identifier or code matched exactly ignoring case, e.g. a DOI or ROR ID.
+    Identifier,
+    /// A value matched exactly, case-sensitively.
+    Keyword,
+    /// Free text analyzed for full-text search, with term vectors for highlighting.
+    Text,
+    /// A name of a person or organization, analyzed for full-text search with a folded keyword subfield for exact
+    /// matches.
+    Name,
+    Date,
+    /// Objects indexed separately, so that queries match fields of the same object.
+    Nested(Vec<IndexField>),
+    Object(Vec<IndexField>),
+}
+
+struct IndexField {
+    name: &'static str,
+    kind: FieldKind,
+}
+
+fn field(name: &'static str, kind: FieldKind) -> IndexField {
+    IndexField { name, kind }
+}
+
+/// The kind of a string field of the structs written by the transformers: identifiers are matched exactly and
+/// everything else is a name.
+fn struct_field_kind(name: &str) -> FieldKind {
+    match name {
+        "orcid" | "ror" => FieldKind::Identifier,
+        _ => FieldKind::Name,
+    }
+}
+
+/// The fields of authors, from those of the author structs written by the transformers.
+fn author_fields() -> Vec<IndexField> {
+    AUTHOR_FIELDS
+        .iter()
+        .map(|name| field(name, struct_field_kind(name)))
+        .collect()
+}
+
+/// The fields of institutions, from those of the institution structs written by the transformers.
+fn institution_fields() -> Vec<IndexField> {
+    INSTITUTION_FIELDS
+        .iter()
+        .map(|name| field(name, struct_field_kind(name)))
+        .collect()
+}
+
+fn funder_fields() -> Vec<IndexField> {
+    vec![
+        field("name", FieldKind::Name),
+        field("ror", FieldKind::Identifier),
+    ]
+}
+
+fn doi_fields() -> Vec<IndexField> {
+    vec![field("doi", FieldKind::Identifier)]
+}
+
+/// The parts of a parsed award ID.
+fn award_id_parts() -> FieldKind {
+    FieldKind::Nested(vec![
+        field("value", FieldKind::Keyword),
+        field("type", FieldKind::Keyword),
+    ])
+}
+
+fn works_fields() -> Vec<IndexField> {
+    use FieldKind::*;
+    vec![
+        field("doi", Identifier),
+        field("title", Text),
+        field("abstract_text", Text),
+        field("hash", Identifier),
+        field("work_type", Identifier),
+        field("publication_date", Date),
+        field("updated_date", Date),
+        field("publication_venue", Identifier),
+        field("institutions", Nested(institution_fields())),
+        field("authors", Nested(author_fields())),
+        field("funders", Nested(funder_fields())),
+        field("awards", Nested(vec![field("award_id", Identifier)])),
+        field(
+            "relations",
+            Object(vec![
+                field("intra_work_dois", Nested(doi_fields())),
+                field("possible_shared_project_dois", Nested(doi_fields())),
+                field("dataset_citation_dois", Nested(doi_fields())),
+            ]),
+        ),
+        field(
+            "source",
+            Object(vec![field("name", Identifier), field("url", Identifier)]),
+        ),
+    ]
+}
+
+fn dmps_fields() -> Vec<IndexField> {
+    use FieldKind::*;
+    vec![
+        field("doi", Identifier),
+        field("created", Date),
+        field("registered", Date),
+        field("modified", Date),
+        field("title", Text),
+        field("abstract_text", Text),
+        field("project_start", Date),
+        field("project_end", Date),
+        field("institutions", Nested(institution_fields())),
+        field("authors", Nested(author_fields())),
+        field(
+            "funding",
+            Nested(vec![
+                field("funder", Nested(funder_fields())),
+                field("funding_opportunity_id", Identifier),
+                field("status", Identifier),
+                field("award_id", Identifier),
+                field("funder_project_number", Identifier),
+            ]),
+        ),
+        field("published_outputs", Nested(doi_fields())),
+        field(
+            "external_data",
+            Object(vec![
+                field("updated", Date),
+                field(
+                    "awards",
+                    Nested(vec![
+                        field("funder", Object(funder_fields())),
+                        field(
+                            "award_id",
+                            Object(vec![
+                                field("class", Keyword),
+                                field("parts", award_id_parts()),
+                                field(
+                                    "related_awards",
+                                    Nested(vec![
+                                        field("class", Keyword),
+                                        field("parts", award_id_parts()),
+                                    ]),
+                                ),
+                            ]),
+                        ),
+                        field("funded_dois", Identifier),
+                    ]),
+                ),
+            ]),
+        ),
+    ]
+}
+
+/// A keyword subfield of folded text, for exact matches of analyzed fields.
+fn keyword_subfield() -> Value {
+    json!({"keyword": {"type": "keyword", "normalizer": "name_normalizer"}})
+}
+
+fn field_mapping(field: &IndexField, options: &IndexSchemaOptions) -> Value {
+    match &field.kind {
+        FieldKind::Identifier => json!({"type": "keyword", "normalizer": "lowercase"}),
+        FieldKind::Keyword => json!({"type": "keyword"}),
+        FieldKind::Text => {
+            let mut mapping = json!({
+                "type": "text",
+                "analyzer": "icu_analyzer",
+                "term_vector": "with_positions_offsets",
+            });
+            if field.name == "title" && options.title_keyword {
+                mapping["fields"] = keyword_subfield();
+            }
+            mapping
+        },
+        FieldKind::Name => json!({
+            "type": "text",
+            "analyzer": "icu_analyzer",
+            "fields": keyword_subfield(),
+        }),
+        FieldKind::Date => json!({"type": "date"}),
+        FieldKind::Nested(fields) => json!({
+            "type": "nested",
+            "properties": properties(fields, options),
+        }),
+        FieldKind::Object(fields) => json!({
+            "type": "object",
+            "properties": properties(fields, options),
+        }),
+    }
+}
+
+fn properties(fields: &[IndexField], options: &IndexSchemaOptions) -> Value {
+    let properties: Map<String, Value> = fields
+        .iter()
+        .map(|field| (field.name.to_string(), field_mapping(field, options)))
+        .collect();
+    Value::Object(properties)
+}
+
+/// Generates the settings and mappings of an OpenSearch index of works or DMPs, as the body of a create index
+/// request, from the one definition of each index's documents here. Author and institution fields are taken from the
+/// structs written by the transformers, so that the two can't drift apart. Text is analyzed with the ICU tokenizer and
+/// folded to ASCII, identifiers are lowercased keywords, and names have a folded keyword subfield. With an embedding,
+/// a k-NN vector field is added and k-NN enabled on the index.
+pub fn generate_index_mapping(options: &IndexSchemaOptions) -> Value {
+    let (fields, shards, replicas) = match options.index {
+        IndexKind::Works => (works_fields(), Some(18), Some(1)),
+        IndexKind::Dmps => (dmps_fields(), None, None),
+    };
+
+    let mut settings = Map::new();
+    if let Some(shards) = options.number_of_shards.or(shards) {
+        settings.insert("number_of_shards".to_string(), json!(shards));
+    }
+    if let Some(replicas) = options.number_of_replicas.or(replicas) {
+        settings.insert("number_of_replicas".to_string(), json!(replicas));
+    }
+    let folding = json!(["lowercase", "icu_normalizer", "asciifolding"]);
+    settings.insert(
+        "analysis".to_string(),
+        json!({
+            "normalizer": {
+                "lowercase": {"type": "custom", "filter": ["lowercase"]},
+                "name_normalizer": {"type": "custom", "filter": folding},
+            },
+            "analyzer": {
+                "icu_analyzer": {"type": "custom", "tokenizer": "icu_tokenizer", "filter": folding},
+            },
+        }),
+    );
+    if options.index == IndexKind::Works {
+        // Works with many authors exceed the default limit of 10,000 nested objects
+        settings.insert(
+            "index.mapping.nested_objects.limit".to_string(),
+            json!(20000),
+        );
+    }
+
+    let mut properties = properties(&fields, options);
+    if let Some(embedding) = &options.embedding {
+        settings.insert("index.knn".to_string(), json!(true));
+        properties[&embedding.name] = json!({
+            "type": "knn_vector",
+            "dimension": embedding.dimension,
+            "method": {"name": "hnsw", "space_type": embedding.space_type, "engine": "lucene"},
+        });
+    }
+    json!({"settings": settings, "mappings": {"properties": properties}})
+}
@@ -0,0 +1,95 @@
+use crate::core::{tokenize_with_options, TokenizerOptions};
+use crate::sampler::SplitMix64;
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use twox_hash::XxHash3_64;
+
+/// Number of characters in each shingle a text is split into.
+const SHINGLE_SIZE: usize = 3;
+
+/// The Mersenne prime 2^61 - 1, the modulus of the permutation hash functions.
+const MERSENNE_PRIME: u64 = (1 << 61) - 1;
+
+/// Seed of the permutation hash functions' parameters, fixed so that signatures are comparable across runs.
+const PERMUTATION_SEED: u64 = 1;
+
+/// Splits a text into the set of hashes of its overlapping character shingles, taken over its folded word tokens
+/// joined by a space so that case, diacritics, punctuation and spacing are ignored. Texts shorter than a shingle are
+/// a single shingle.
+fn shingle_hashes(text: &str) -> BTreeSet<u64> {
+    let tokens = tokenize_with_options(text, &TokenizerOptions::default());
+    let chars: Vec<char> = tokens.join(" ").chars().collect();
+    if chars.is_empty() {
+        return BTreeSet::new();
+    }
+    chars
+        .windows(SHINGLE_SIZE.min(chars.len()))
+        .map(|window| XxHash3_64::oneshot(window.iter().collect::<String>().as_bytes()))
+        .collect()
+}
+
+/// Computes the MinHash signature of a text: for each of `num_perm` hash functions `(a * h + b) mod (2^61 - 1)`, the
+/// minimum over the hashes of the text's character shingles. The fraction of equal values in two signatures
+/// estimates the Jaccard similarity of the texts' shingle sets. Texts without any shingles have a signature of
+/// `u64::MAX` values, which match nothing.
+pub fn minhash_signature(text: &str, num_perm: usize) -> Vec<u64> {
+    let shingles = shingle_hashes(text);
+    let mut rng = SplitMix64(PERMUTATION_SEED);
+    (0..num_perm)
+        .map(|_| {
+            let a = rng.next_u64() % (MERSENNE_PRIME - 1) + 1;
+            let b = rng.next_u64() % MERSENNE_PRIME;
+            shingles
+                .iter()
+                .map(|&h| {
+                    let permuted = (u128::from(a) * u128::from(h) + u128::from(b))
+                        % u128::from(MERSENNE_PRIME);
+                    permuted as u64
+                })
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+/// Computes the MinHash signature of each text in parallel on the current rayon thread pool, giving None where the
+/// text is missing.
+pub fn minhash_signatures(texts: &[Option<String>], num_perm: usize) -> Vec<Option<Vec<u64>>> {
+    texts
+        .par_iter()
+        .map(|text| Some(minhash_signature(text.as_deref()?, num_perm)))
+        .collect()
+}
+
+/// Finds candidate near-duplicate pairs by locality-sensitive hashing: each signature is split into `num_bands` bands
+/// of equal size, and two signatures are candidates when all values of any one band are equal. Missing signatures,
+/// signatures of empty texts and signatures with fewer values than bands are never bucketed. Pairs are returned once
+/// each as `(i, j)` with `i < j`, sorted.
+pub fn lsh_candidate_pairs(
+    signatures: &[Option<Vec<u64>>],
+    num_bands: usize,
+) -> Vec<(usize, usize)> {
+    let mut buckets: HashMap<(usize, &[u64]), Vec<usize>> = HashMap::new();
+    for (i, signature) in signatures.iter().enumerate() {
+        let Some(signature) = signature else {
+            continue;
+        };
+        let rows = signature.len() / num_bands;
+        if rows == 0 || signature.iter().all(|value| *value == u64::MAX) {
+            continue;
+        }
+        for (band, values) in signature.chunks_exact(rows).take(num_bands).enumerate() {
+            buckets.entry((band, values)).or_default().push(i);
+        }
+    }
+
+    let mut pairs = BTreeSet::new();
+    for members in buckets.values() {
+        for (n, &i) in members.iter().enumerate() {
+            for &j in &members[n + 1..] {
+                pairs.insert((i, j));
+            }
+        }
+    }
+    pairs.into_iter().collect()
+}
@@ -0,0 +1,759 @@
+use deunicode::deunicode;
+
+/// Maximum length of a Double Metaphone code.
+const DOUBLE_METAPHONE_LENGTH: usize = 4;
+
+/// Maximum length of a NYSIIS code, as in the original algorithm.
+const NYSIIS_LENGTH: usize = 6;
+
+/// A phonetic encoding of names, under which spelling variants that sound alike share a code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneticAlgorithm {
+    /// Lawrence Philips' Double Metaphone, which gives a primary and an alternate code for names with more than one
+    /// plausible pronunciation, e.g. "Schmidt" as "XMT" or "SMT".
+    DoubleMetaphone,
+    /// The New York State Identification and Intelligence System code.
+    Nysiis,
+}
+
+impl PhoneticAlgorithm {
+    /// Looks up an algorithm by its name, "double_metaphone" or "nysiis", ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "double_metaphone" => Some(PhoneticAlgorithm::DoubleMetaphone),
+            "nysiis" => Some(PhoneticAlgorithm::Nysiis),
+            _ => None,
+        }
+    }
+}
+
+/// Transliterates a name to ASCII and upper case, keeping only its letters, with words separated by single spaces.
+fn phonetic_letters(text: &str) -> Vec<u8> {
+    let transliterated = deunicode(text).to_uppercase();
+    let words: Vec<&str> = transliterated
+        .split(|c: char| !c.is_ascii_uppercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.join(" ").into_bytes()
+}
+
+/// Encodes a name with a phonetic algorithm, returning its distinct codes: the primary code then any different
+/// alternate one for Double Metaphone, or the single NYSIIS code. Names without any letters have no codes.
+pub fn phonetic_encode(text: &str, algorithm: PhoneticAlgorithm) -> Vec<String> {
+    match algorithm {
+        PhoneticAlgorithm::DoubleMetaphone => {
+            let (primary, alternate) = double_metaphone(text);
+            let mut codes = vec![primary];
+            if alternate != codes[0] {
+                codes.push(alternate);
+            }
+            codes.retain(|code| !code.is_empty());
+            codes
+        },
+        PhoneticAlgorithm::Nysiis => Some(nysiis(text))
+            .filter(|code| !code.is_empty())
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Checks whether two names share a Double Metaphone code, primary or alternate.
+pub fn sounds_alike(a: &str, b: &str) -> bool {
+    let codes_a = phonetic_encode(a, PhoneticAlgorithm::DoubleMetaphone);
+    let codes_b = phonetic_encode(b, PhoneticAlgorithm::DoubleMetaphone);
+    codes_a.iter().any(|code| codes_b.contains(code))
+}
+
+/// The primary and alternate Double Metaphone codes being built, each of at most `DOUBLE_METAPHONE_LENGTH`
+/// characters.
+#[derive(Default)]
+struct MetaphoneCodes {
+    primary: String,
+    alternate: String,
+}
+
+impl MetaphoneCodes {
+    fn push_primary(&mut self, code: &str) {
+        let room = DOUBLE_METAPHONE_LENGTH.saturating_sub(self.primary.len());
+        self.primary.push_str(&code[..code.len().min(room)]);
+    }
+
+    fn push_alternate(&mut self, code: &str) {
+        let room = DOUBLE_METAPHONE_LENGTH.saturating_sub(self.alternate.len());
+        self.alternate.push_str(&code[..code.len().min(room)]);
+    }
+
+    /// Adds the same code to both.
+    fn push(&mut self, code: &str) {
+        self.push_primary(code);
+        self.push_alternate(code);
+    }
+
+    /// Adds different codes to the primary and alternate.
+    fn push_both(&mut self, primary: &str, alternate: &str) {
+        self.push_primary(primary);
+        self.push_alternate(alternate);
+    }
+
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= DOUBLE_METAPHONE_LENGTH
+            && self.alternate.len() >= DOUBLE_METAPHONE_LENGTH
+    }
+}
+
+/// A name being encoded with Double Metaphone, with lookups that treat positions outside the name as no character.
+struct Metaphone {
+    name: Vec<u8>,
+    slavo_germanic: bool,
+}
+
+impl Metaphone {
+    fn at(&self, index: isize) -> u8 {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.name.get(index).copied())
+            .unwrap_or(0)
+    }
+
+    /// Checks whether the `length` characters from `start` are one of `options`.
+    fn is_any(&self, start: isize, length: usize, options: &[&str]) -> bool {
+        let Ok(start) = usize::try_from(start) else {
+            return false;
+        };
+        self.name
+            .get(start..start + length)
+            .is_some_and(|part| options.iter().any(|option| option.as_bytes() == part))
+    }
+
+    fn is_vowel(&self, index: isize) -> bool {
+        matches!(self.at(index), b'A' | b'E' | b'I' | b'O' | b'U' | b'Y')
+    }
+
+    fn last(&self) -> isize {
+        self.name.len() as isize - 1
+    }
+
+    fn is_germanic(&self) -> bool {
+        self.is_any(0, 4, &["VAN ", "VON "]) || self.is_any(0, 3, &["SCH"])
+    }
+
+    /// Encodes the letters from `index`, returning the index of the next letter to encode.
+    fn encode_at(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        let next = self.at(index + 1);
+        let skip_double = |letter: u8| if next == letter { index + 2 } else { index + 1 };
+        match self.at(index) {
+            b'A' | b'E' | b'I' | b'O' | b'U' | b'Y' => {
+                if index == 0 {
+                    codes.push("A");
+                }
+                index + 1
+            },
+            b'B' => {
+                codes.push("P");
+                skip_double(b'B')
+            },
+            b'C' => self.encode_c(codes, index),
+            b'D' => self.encode_d(codes, index),
+            b'F' => {
+                codes.push("F");
+                skip_double(b'F')
+            },
+            b'G' => self.encode_g(codes, index),
+            b'H' => {
+                // Only kept when first or between vowels
+                if (index == 0 || self.is_vowel(index - 1)) && self.is_vowel(index + 1) {
+                    codes.push("H");
+                    index + 2
+                } else {
+                    index + 1
+                }
+            },
+            b'J' => self.encode_j(codes, index),
+            b'K' => {
+                codes.push("K");
+                skip_double(b'K')
+            },
+            b'L' => self.encode_l(codes, index),
+            b'M' => {
+                codes.push("M");
+                let silent_b = self.is_any(index - 1, 3, &["UMB"])
+                    && (index + 1 == self.last() || self.is_any(index + 2, 2, &["ER"]));
+                if next == b'M' || silent_b {
+                    index + 2
+                } else {
+                    index + 1
+                }
+            },
+            b'N' => {
+                codes.push("N");
+                skip_double(b'N')
+            },
+            b'P' => {
+                if next == b'H' {
+                    codes.push("F");
+                    index + 2
+                } else {
+                    codes.push("P");
+                    if matches!(next, b'P' | b'B') {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+            },
+            b'Q' => {
+                codes.push("K");
+                skip_double(b'Q')
+            },
+            b'R' => {
+                // French, e.g. "Rogier", unless "Meier" or "Maier"
+                if index == self.last()
+                    && !self.slavo_germanic
+                    && self.is_any(index - 2, 2, &["IE"])
+                    && !self.is_any(index - 4, 2, &["ME", "MA"])
+                {
+                    codes.push_alternate("R");
+                } else {
+                    codes.push("R");
+                }
+                skip_double(b'R')
+            },
+            b'S' => self.encode_s(codes, index),
+            b'T' => self.encode_t(codes, index),
+            b'V' => {
+                codes.push("F");
+                skip_double(b'V')
+            },
+            b'W' => self.encode_w(codes, index),
+            b'X' => {
+                // French, e.g. "Breaux"
+                let silent = index == self.last()
+                    && (self.is_any(index - 3, 3, &["IAU", "EAU"])
+                        || self.is_any(index - 2, 2, &["AU", "OU"]));
+                if index == 0 {
+                    codes.push("S");
+                    return index + 1;
+                }
+                if !silent {
+                    codes.push("KS");
+                }
+                if matches!(next, b'C' | b'X') {
+                    index + 2
+                } else {
+                    index + 1
+                }
+            },
+            b'Z' => {
+                if next == b'H' {
+                    // Pinyin, e.g. "Zhao"
+                    codes.push("J");
+                    return index + 2;
+                }
+                if self.is_any(index + 1, 2, &["ZO", "ZI", "ZA"])
+                    || (self.slavo_germanic && index > 0 && self.at(index - 1) != b'T')
+                {
+                    codes.push_both("S", "TS");
+                } else {
+                    codes.push("S");
+                }
+                skip_double(b'Z')
+            },
+            _ => index + 1,
+        }
+    }
+
+    fn encode_c(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.is_germanic_ch(index) {
+            codes.push("K");
+            index + 2
+        } else if index == 0 && self.is_any(index, 6, &["CAESAR"]) {
+            codes.push("S");
+            index + 2
+        } else if self.is_any(index, 2, &["CH"]) {
+            self.encode_ch(codes, index)
+        } else if self.is_any(index, 2, &["CZ"]) && !self.is_any(index - 2, 4, &["WICZ"]) {
+            // "Czerny"
+            codes.push_both("S", "X");
+            index + 2
+        } else if self.is_any(index + 1, 3, &["CIA"]) {
+            // Italian, e.g. "Focaccia"
+            codes.push("X");
+            index + 3
+        } else if self.is_any(index, 2, &["CC"]) && !(index == 1 && self.at(0) == b'M') {
+            // Double C, but not "McClellan"
+            if self.is_any(index + 2, 1, &["I", "E", "H"]) && !self.is_any(index + 2, 2, &["HU"]) {
+                // "Accident" and "Succeed", otherwise Italian, e.g. "Bellocchio", but not "Bacchus"
+                if (index == 1 && self.at(0) == b'A')
+                    || self.is_any(index - 1, 5, &["UCCEE", "UCCES"])
+                {
+                    codes.push("KS");
+                } else {
+                    codes.push("X");
+                }
+                index + 3
+            } else {
+                codes.push("K");
+                index + 2
+            }
+        } else if self.is_any(index, 2, &["CK", "CG", "CQ"]) {
+            codes.push("K");
+            index + 2
+        } else if self.is_any(index, 2, &["CI", "CE", "CY"]) {
+            // Italian, e.g. "Ciao"
+            if self.is_any(index, 3, &["CIO", "CIE", "CIA"]) {
+                codes.push_both("S", "X");
+            } else {
+                codes.push("S");
+            }
+            index + 2
+        } else {
+            codes.push("K");
+            if self.is_any(index + 1, 2, &[" C", " Q", " G"]) {
+                // "Mac Caffrey" and "Mac Gregor"
+                index + 3
+            } else if self.is_any(index + 1, 1, &["C", "K", "Q"])
+                && !self.is_any(index + 1, 2, &["CE", "CI"])
+            {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    /// Checks for a Germanic "ch" sounded as "k" after "a", e.g. "Bacher" and "Macher", or Italian "chia".
+    fn is_germanic_ch(&self, index: isize) -> bool {
+        if self.is_any(index, 4, &["CHIA"]) {
+            return true;
+        }
+        if index <= 1 || self.is_vowel(index - 2) || !self.is_any(index - 1, 3, &["ACH"]) {
+            return false;
+        }
+        let after = self.at(index + 2);
+        (after != b'I' && after != b'E') || self.is_any(index - 2, 6, &["BACHER", "MACHER"])
+    }
+
+    fn encode_ch(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        // Greek roots at the start, e.g. "Chemistry" and "Chorus", but not "Chore"
+        let greek_start = index == 0
+            && (self.is_any(index + 1, 5, &["HARAC", "HARIS"])
+                || self.is_any(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.is_any(0, 5, &["CHORE"]);
+        // Germanic, Greek or otherwise "kh", e.g. "Orchestra", "Architect" and "Orchid"
+        let kh = self.is_germanic()
+            || self.is_any(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.is_any(index + 2, 1, &["T", "S"])
+            || ((self.is_any(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+                && (self.is_any(
+                    index + 2,
+                    1,
+                    &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                ) || index + 1 == self.last()));
+
+        if index > 0 && self.is_any(index, 4, &["CHAE"]) {
+            // "Michael"
+            codes.push_both("K", "X");
+        } else if greek_start || kh {
+            codes.push("K");
+        } else if index == 0 {
+            codes.push("X");
+        } else if self.is_any(0, 2, &["MC"]) {
+            // "McHugh"
+            codes.push("K");
+        } else {
+            codes.push_both("X", "K");
+        }
+        index + 2
+    }
+
+    fn encode_d(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.is_any(index, 2, &["DG"]) {
+            if self.is_any(index + 2, 1, &["I", "E", "Y"]) {
+                // "Edge"
+                codes.push("J");
+                index + 3
+            } else {
+                // "Edgar"
+                codes.push("TK");
+                index + 2
+            }
+        } else if self.is_any(index, 2, &["DT", "DD"]) {
+            codes.push("T");
+            index + 2
+        } else {
+            codes.push("T");
+            index + 1
+        }
+    }
+
+    fn encode_g(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        let next = self.at(index + 1);
+        if next == b'H' {
+            return self.encode_gh(codes, index);
+        }
+        if next == b'N' {
+            if index == 1 && self.is_vowel(0) && !self.slavo_germanic {
+                codes.push_both("KN", "N");
+            } else if !self.is_any(index + 2, 2, &["EY"]) && !self.slavo_germanic {
+                codes.push_both("N", "KN");
+            } else {
+                codes.push("KN");
+            }
+            return index + 2;
+        }
+        if self.is_any(index + 1, 2, &["LI"]) && !self.slavo_germanic {
+            // "Tagliaro"
+            codes.push_both("KL", "L");
+            return index + 2;
+        }
+        if index == 0
+            && (next == b'Y'
+                || self.is_any(
+                    index + 1,
+                    2,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ))
+        {
+            codes.push_both("K", "J");
+            return index + 2;
+        }
+        if (self.is_any(index + 1, 2, &["ER"]) || next == b'Y')
+            && !self.is_any(0, 6, &["DANGER", "RANGER", "MANGER"])
+            && !self.is_any(index - 1, 1, &["E", "I"])
+            && !self.is_any(index - 1, 3, &["RGY", "OGY"])
+        {
+            // "-ger-" and "-gy-"
+            codes.push_both("K", "J");
+            return index + 2;
+        }
+        if self.is_any(index + 1, 1, &["E", "I", "Y"])
+            || self.is_any(index - 1, 4, &["AGGI", "OGGI"])
+        {
+            if self.is_germanic() || self.is_any(index + 1, 2, &["ET"]) {
+                codes.push("K");
+            } else if self.is_any(index + 1, 3, &["IER"]) {
+                codes.push("J");
+            } else {
+                codes.push_both("J", "K");
+            }
+            return index + 2;
+        }
+        codes.push("K");
+        if next == b'G' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn encode_gh(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if index > 0 && !self.is_vowel(index - 1) {
+            codes.push("K");
+        } else if index == 0 {
+            // "Ghislane" and "Ghiradelli"
+            codes.push(if self.at(index + 2) == b'I' { "J" } else { "K" });
+        } else if (index > 1 && self.is_any(index - 2, 1, &["B", "H", "D"]))
+            || (index > 2 && self.is_any(index - 3, 1, &["B", "H", "D"]))
+            || (index > 3 && self.is_any(index - 4, 1, &["B", "H"]))
+        {
+            // Silent, e.g. "Hugh", "Bough" and "Broughton"
+        } else if index > 2
+            && self.at(index - 1) == b'U'
+            && self.is_any(index - 3, 1, &["C", "G", "L", "R", "T"])
+        {
+            // "Laugh", "McLaughlin", "Cough" and "Tough"
+            codes.push("F");
+        } else if index > 0 && self.at(index - 1) != b'I' {
+            codes.push("K");
+        }
+        index + 2
+    }
+
+    fn encode_j(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        let next = self.at(index + 1);
+        if self.is_any(index, 4, &["JOSE"]) || self.is_any(0, 4, &["SAN "]) {
+            // Spanish, e.g. "Jose" and "San Jacinto"
+            if (index == 0 && self.at(index + 4) == b' ')
+                || self.name.len() == 4
+                || self.is_any(0, 4, &["SAN "])
+            {
+                codes.push("H");
+            } else {
+                codes.push_both("J", "H");
+            }
+            return index + 1;
+        }
+        if index == 0 {
+            // "Jankelowicz"
+            codes.push_both("J", "A");
+        } else if self.is_vowel(index - 1) && !self.slavo_germanic && matches!(next, b'A' | b'O') {
+            // Spanish pronunciation of e.g. "Bajador"
+            codes.push_both("J", "H");
+        } else if index == self.last() {
+            codes.push_primary("J");
+        } else if !self.is_any(index + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.is_any(index - 1, 1, &["S", "K", "L"])
+        {
+            codes.push("J");
+        }
+        if next == b'J' {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+
+    fn encode_l(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.at(index + 1) != b'L' {
+            codes.push("L");
+            return index + 1;
+        }
+        // Spanish, e.g. "Cabrillo" and "Gallegos"
+        let spanish = (index == self.last() - 2
+            && self.is_any(index - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+            || ((self.is_any(self.last() - 1, 2, &["AS", "OS"])
+                || self.is_any(self.last(), 1, &["A", "O"]))
+                && self.is_any(index - 1, 4, &["ALLE"]));
+        if spanish {
+            codes.push_primary("L");
+        } else {
+            codes.push("L");
+        }
+        index + 2
+    }
+
+    fn encode_s(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.is_any(index - 1, 3, &["ISL", "YSL"]) {
+            // Silent, e.g. "Island", "Isle" and "Carlisle"
+            index + 1
+        } else if index == 0 && self.is_any(index, 5, &["SUGAR"]) {
+            codes.push_both("X", "S");
+            index + 1
+        } else if self.is_any(index, 2, &["SH"]) {
+            // Germanic, e.g. "Rosheim"
+            if self.is_any(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                codes.push("S");
+            } else {
+                codes.push("X");
+            }
+            index + 2
+        } else if self.is_any(index, 3, &["SIO", "SIA"]) || self.is_any(index, 4, &["SIAN"]) {
+            // Italian and Armenian
+            if self.slavo_germanic {
+                codes.push("S");
+            } else {
+                codes.push_both("S", "X");
+            }
+            index + 3
+        } else if (index == 0 && self.is_any(index + 1, 1, &["M", "N", "L", "W"]))
+            || self.is_any(index + 1, 1, &["Z"])
+        {
+            // Germanic and anglicized, so "Smith" matches "Schmidt" and "Snider" matches "Schneider"
+            codes.push_both("S", "X");
+            if self.is_any(index + 1, 1, &["Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        } else if self.is_any(index, 2, &["SC"]) {
+            self.encode_sc(codes, index)
+        } else {
+            // French, e.g. "Resnais" and "Artois"
+            if index == self.last() && self.is_any(index - 2, 2, &["AI", "OI"]) {
+                codes.push_alternate("S");
+            } else {
+                codes.push("S");
+            }
+            if self.is_any(index + 1, 1, &["S", "Z"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn encode_sc(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.at(index + 2) == b'H' {
+            if self.is_any(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                // Dutch, e.g. "School" and "Schenker"
+                if self.is_any(index + 3, 2, &["ER", "EN"]) {
+                    codes.push_both("X", "SK");
+                } else {
+                    codes.push("SK");
+                }
+            } else if index == 0 && !self.is_vowel(3) && self.at(3) != b'W' {
+                codes.push_both("X", "S");
+            } else {
+                codes.push("X");
+            }
+        } else if self.is_any(index + 2, 1, &["I", "E", "Y"]) {
+            codes.push("S");
+        } else {
+            codes.push("SK");
+        }
+        index + 3
+    }
+
+    fn encode_t(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.is_any(index, 4, &["TION"]) || self.is_any(index, 3, &["TIA", "TCH"]) {
+            codes.push("X");
+            index + 3
+        } else if self.is_any(index, 2, &["TH"]) || self.is_any(index, 3, &["TTH"]) {
+            // "Thomas" and "Thames", otherwise "0" for "th"
+            if self.is_any(index + 2, 2, &["OM", "AM"]) || self.is_germanic() {
+                codes.push("T");
+            } else {
+                codes.push_both("0", "T");
+            }
+            index + 2
+        } else {
+            codes.push("T");
+            if self.is_any(index + 1, 1, &["T", "D"]) {
+                index + 2
+            } else {
+                index + 1
+            }
+        }
+    }
+
+    fn encode_w(&self, codes: &mut MetaphoneCodes, index: isize) -> isize {
+        if self.is_any(index, 2, &["WR"]) {
+            codes.push("R");
+            return index + 2;
+        }
+        if index == 0 && (self.is_vowel(index + 1) || self.is_any(index, 2, &["WH"])) {
+            // "Wasserman" matches "Vasserman", and "Uomo" "Womo"
+            if self.is_vowel(index + 1) {
+                codes.push_both("A", "F");
+            } else {
+                codes.push("A");
+            }
+        } else if (index == self.last() && self.is_vowel(index - 1))
+            || self.is_any(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.is_any(0, 3, &["SCH"])
+        {
+            // "Arnow" matches "Arnoff"
+            codes.push_alternate("F");
+        } else if self.is_any(index, 4, &["WICZ", "WITZ"]) {
+            // Polish, e.g. "Filipowicz"
+            codes.push_both("TS", "FX");
+            return index + 4;
+        }
+        index + 1
+    }
+}
+
+/// Encodes a name with Double Metaphone into its primary and alternate codes of up to four characters, equal when
+/// the name has a single plausible pronunciation. "0" stands for "th" and "X" for "sh" and "ch".
+pub fn double_metaphone(text: &str) -> (String, String) {
+    let name = phonetic_letters(text);
+    let slavo_germanic = name
+        .windows(2)
+        .any(|pair| pair == b"CZ" || pair[0] == b'W' || pair[0] == b'K')
+        || name.last().is_some_and(|c| matches!(c, b'W' | b'K'));
+    let metaphone = Metaphone {
+        name,
+        slavo_germanic,
+    };
+
+    let mut codes = MetaphoneCodes::default();
+    // Silent first letters, e.g. "Gnome", "Knight", "Pneumatic", "Wright" and "Psychology"
+    let mut index = if metaphone.is_any(0, 2, &["GN", "KN", "PN", "WR", "PS"]) {
+        1
+    } else {
+        0
+    };
+    // "X" at the start sounds like "Z", e.g. "Xavier"
+    if metaphone.at(0) == b'X' {
+        codes.push("S");
+        index = 1;
+    }
+    while !codes.is_complete() && index <= metaphone.last() {
+        index = metaphone.encode_at(&mut codes, index);
+    }
+    (codes.primary, codes.alternate)
+}
+
+fn is_nysiis_vowel(c: u8) -> bool {
+    matches!(c, b'A' | b'E' | b'I' | b'O' | b'U')
+}
+
+/// Encodes a name with NYSIIS into a code of up to six letters, ignoring spaces between its words.
+pub fn nysiis(text: &str) -> String {
+    let mut name: Vec<u8> = phonetic_letters(text)
+        .into_iter()
+        .filter(|c| *c != b' ')
+        .collect();
+    if name.is_empty() {
+        return String::new();
+    }
+
+    // Translate the first and last letters
+    for (prefix, replacement) in [
+        (&b"MAC"[..], &b"MCC"[..]),
+        (b"KN", b"NN"),
+        (b"K", b"C"),
+        (b"PH", b"FF"),
+        (b"PF", b"FF"),
+        (b"SCH", b"SSS"),
+    ] {
+        if name.starts_with(prefix) {
+            name[..prefix.len()].copy_from_slice(replacement);
+            break;
+        }
+    }
+    if name.ends_with(b"EE") || name.ends_with(b"IE") {
+        name.truncate(name.len() - 2);
+        name.push(b'Y');
+    } else if [&b"DT"[..], b"RT", b"RD", b"NT", b"ND"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        name.truncate(name.len() - 2);
+        name.push(b'D');
+    }
+
+    let mut key = vec![name[0]];
+    for i in 1..name.len() {
+        let previous = name[i - 1];
+        let next = name.get(i + 1).copied().unwrap_or(b' ');
+        let after_next = name.get(i + 2).copied().unwrap_or(b' ');
+        let replacement: &[u8] = match name[i] {
+            b'E' if next == b'V' => b"AF",
+            c if is_nysiis_vowel(c) => b"A",
+            b'Q' => b"G",
+            b'Z' => b"S",
+            b'M' => b"N",
+            b'K' if next == b'N' => b"NN",
+            b'K' => b"C",
+            b'S' if next == b'C' && after_next == b'H' => b"SSS",
+            b'P' if next == b'H' => b"FF",
+            b'H' if !is_nysiis_vowel(previous) || !is_nysiis_vowel(next) => &[previous],
+            b'W' if is_nysiis_vowel(previous) => &[previous],
+            _ => &[],
+        };
+        if !replacement.is_empty() {
+            let end = (i + replacement.len()).min(name.len());
+            let replacement = replacement.to_vec();
+            name[i..end].copy_from_slice(&replacement[..end - i]);
+        }
+        if name[i] != previous {
+            key.push(name[i]);
+        }
+    }
+
+    // Drop a trailing "S", "A" of a trailing "AY", and then a trailing "A"
+    if key.len() > 1 && key.last() == Some(&b'S') {
+        key.pop();
+    }
+    if key.len() > 2 && key.ends_with(b"AY") {
+        key.remove(key.len() - 2);
+    }
+    if key.len() > 1 && key.last() == Some(&b'A') {
+        key.pop();
+    }
+    key.truncate(NYSIIS_LENGTH);
+    String::from_utf8(key).unwrap()
+}
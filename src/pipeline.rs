@@ -0,0 +1,184 @@
+use crate::core::{
+    clean_text, normalize_doi, normalize_orcid, normalize_ror, normalize_title, nullify,
+    revert_inverted_index, strip_boilerplate, strip_markup, truncate_to_tokens, BoilerplateRules,
+    DEFAULT_NULL_RULES,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// A built-in transform applied to the values of a field by a `Pipeline`, each taking and giving text that is None
+/// when empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldTransform {
+    /// Reconstructs text from a JSON-serialized inverted index with `revert_inverted_index`.
+    RevertInvertedIndex,
+    StripMarkup,
+    StripBoilerplate,
+    CleanText,
+    /// Treats placeholder values as null with `nullify` and the default rules.
+    Nullify,
+    NormalizeDoi,
+    NormalizeOrcid,
+    NormalizeRor,
+    NormalizeTitle,
+    /// Truncates text to at most this many words with `truncate_to_tokens`.
+    Truncate(usize),
+}
+
+impl FieldTransform {
+    /// Looks up a transform by the name of the function it applies, e.g. "strip_markup", with the argument it takes,
+    /// returning a description of the problem when the name is unknown or the argument is missing or unexpected.
+    pub fn from_name(name: &str, arg: Option<usize>) -> Result<Self, String> {
+        let transform = match name {
+            "revert_inverted_index" => FieldTransform::RevertInvertedIndex,
+            "strip_markup" => FieldTransform::StripMarkup,
+            "strip_boilerplate" => FieldTransform::StripBoilerplate,
+            "clean_text" => FieldTransform::CleanText,
+            "nullify" => FieldTransform::Nullify,
+            "normalize_doi" => FieldTransform::NormalizeDoi,
+            "normalize_orcid" => FieldTransform::NormalizeOrcid,
+            "normalize_ror" => FieldTransform::NormalizeRor,
+            "normalize_title" => FieldTransform::NormalizeTitle,
+            "truncate" => {
+                let max_tokens = arg.ok_or("truncate requires a maximum number of tokens")?;
+                return Ok(FieldTransform::Truncate(max_tokens));
+            },
+            _ => return Err(format!("unknown transform: {name}")),
+        };
+        match arg {
+            Some(_) => Err(format!("{name} takes no argument")),
+            None => Ok(transform),
+        }
+    }
+
+    /// Applies the transform to a value.
+    fn apply(self, value: Option<String>) -> Option<String> {
+        let value = value.as_deref();
+        match self {
+            FieldTransform::RevertInvertedIndex => {
+                revert_inverted_index(value.map(str::as_bytes), None)
+            },
+            FieldTransform::StripMarkup => strip_markup(value, None),
+            FieldTransform::StripBoilerplate => {
+                strip_boilerplate(value, &BoilerplateRules::default())
+            },
+            FieldTransform::CleanText => value
+                .map(|text| clean_text(text).trim().to_string())
+                .filter(|text| !text.is_empty()),
+            FieldTransform::Nullify => nullify(value, &DEFAULT_NULL_RULES),
+            FieldTransform::NormalizeDoi => normalize_doi(value),
+            FieldTransform::NormalizeOrcid => normalize_orcid(value),
+            FieldTransform::NormalizeRor => normalize_ror(value),
+            FieldTransform::NormalizeTitle => normalize_title(value, false, false, None),
+            FieldTransform::Truncate(max_tokens) => value
+                .map(|text| truncate_to_tokens(text, max_tokens))
+                .filter(|text| !text.is_empty()),
+        }
+    }
+}
+
+/// Ordered chains of transforms applied to fields of record batches, so that a record's fields are cleaned in one
+/// pass rather than one function call per transform.
+#[derive(Debug, Clone, Default)]
+pub struct Pipeline {
+    fields: Vec<(String, Vec<FieldTransform>)>,
+}
+
+impl Pipeline {
+    /// Builds a pipeline applying each field's transforms in order.
+    pub fn new(fields: Vec<(String, Vec<FieldTransform>)>) -> Self {
+        Self { fields }
+    }
+
+    /// The fields the pipeline transforms, in the order they were given.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|(field, _)| field.as_str())
+    }
+
+    /// Applies a chain of transforms to a value.
+    fn apply(transforms: &[FieldTransform], value: Option<&str>) -> Option<String> {
+        transforms
+            .iter()
+            .fold(value.map(str::to_string), |value, transform| {
+                transform.apply(value)
+            })
+    }
+
+    /// Runs the pipeline over a batch in parallel on the current rayon thread pool, replacing each transformed
+    /// column with a nullable string column and keeping the other columns unchanged. Transformed columns must be
+    /// strings or binary, which is decoded as UTF-8 with invalid sequences replaced.
+    pub fn run(&self, batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+        let schema = batch.schema();
+        let mut fields: Vec<Arc<Field>> = schema.fields().iter().cloned().collect();
+        let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+        for (name, transforms) in &self.fields {
+            let (index, field) = schema.column_with_name(name).ok_or_else(|| {
+                ArrowError::InvalidArgumentError(format!("missing column: {name}"))
+            })?;
+            let values = text_values(&columns[index], name)?;
+            let transformed: StringArray = values
+                .par_iter()
+                .map(|value| Self::apply(transforms, value.as_deref()))
+                .collect::<Vec<_>>()
+                .into();
+            columns[index] = Arc::new(transformed);
+            fields[index] = Arc::new(
+                Field::new(name, DataType::Utf8, true).with_metadata(field.metadata().clone()),
+            );
+        }
+        RecordBatch::try_new(
+            Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+            columns,
+        )
+    }
+}
+
+/// The values of a string or binary column as text, decoding binary as UTF-8.
+fn text_values<'a>(
+    array: &'a ArrayRef,
+    name: &str,
+) -> Result<Vec<Option<Cow<'a, str>>>, ArrowError> {
+    let values = match array.data_type() {
+        DataType::Utf8 => array
+            .as_string::<i32>()
+            .iter()
+            .map(|v| v.map(Cow::Borrowed))
+            .collect(),
+        DataType::LargeUtf8 => array
+            .as_string::<i64>()
+            .iter()
+            .map(|v| v.map(Cow::Borrowed))
+            .collect(),
+        DataType::Utf8View => array
+            .as_string_view()
+            .iter()
+            .map(|v| v.map(Cow::Borrowed))
+            .collect(),
+        DataType::Binary => array
+            .as_binary::<i32>()
+            .iter()
+            .map(|v| v.map(String::from_utf8_lossy))
+            .collect(),
+        DataType::LargeBinary => array
+            .as_binary::<i64>()
+            .iter()
+            .map(|v| v.map(String::from_utf8_lossy))
+            .collect(),
+        DataType::BinaryView => array
+            .as_binary_view()
+            .iter()
+            .map(|v| v.map(String::from_utf8_lossy))
+            .collect(),
+        DataType::Null => vec![None; array.len()],
+        data_type => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "column {name} must be strings or binary, got {data_type}"
+            )))
+        },
+    };
+    Ok(values)
+}
@@ -0,0 +1,161 @@
+use crate::core::{detect_language, NullRules};
+use arrow_array::{Array, BinaryArray};
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// Tags and HTML entities left in text, e.g. `<jats:p>`, `</i>`, `<br/>` or `&amp;`, which markup stripping should
+/// have removed.
+static MARKUP_REMNANT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)</?[a-z][\w:.-]*(?:\s[^<>]*)?/?>|&(?:[a-z][a-z0-9]{1,31}|#[0-9]{1,7}|#x[0-9a-f]{1,6});")
+        .unwrap()
+});
+
+/// The lengths of a field's values in characters, as nearest-rank percentiles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthDistribution {
+    pub min: usize,
+    pub p10: usize,
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+
+impl LengthDistribution {
+    /// The distribution of lengths, or None when there are none.
+    fn new(mut lengths: Vec<usize>) -> Option<Self> {
+        let count = lengths.len();
+        if count == 0 {
+            return None;
+        }
+        lengths.par_sort_unstable();
+        let percentile = |p: usize| lengths[(p * count).div_ceil(100).max(1) - 1];
+        Some(Self {
+            min: lengths[0],
+            p10: percentile(10),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: lengths[count - 1],
+            mean: lengths.iter().sum::<usize>() as f64 / count as f64,
+        })
+    }
+}
+
+/// Data-quality statistics of a field of texts, see `profile_texts`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextProfile {
+    pub count: usize,
+    pub nulls: usize,
+    /// Values that are empty or only whitespace.
+    pub blanks: usize,
+    /// Values that are placeholders according to the null rules, e.g. "N/A" or "No abstract available".
+    pub placeholders: usize,
+    /// Values that aren't valid UTF-8 or contain U+FFFD replacement characters, the marks of text decoded lossily
+    /// upstream.
+    pub invalid_utf8: usize,
+    /// Values with tags or HTML entities left in them.
+    pub markup: usize,
+    /// Lengths of the values in characters, after trimming.
+    pub lengths: Option<LengthDistribution>,
+    /// The number of values detected as each language, by ISO 639-1 code.
+    pub languages: BTreeMap<String, usize>,
+    pub undetected_language: usize,
+}
+
+impl TextProfile {
+    /// The number of values that are text: not null, blank or placeholders.
+    pub fn values(&self) -> usize {
+        self.count - self.nulls - self.blanks - self.placeholders
+    }
+}
+
+/// Partial statistics of some texts, merged into a `TextProfile`.
+#[derive(Default)]
+struct PartialProfile {
+    profile: TextProfile,
+    lengths: Vec<usize>,
+}
+
+impl PartialProfile {
+    fn add(mut self, text: Option<&[u8]>, rules: &NullRules, languages: bool) -> Self {
+        let profile = &mut self.profile;
+        profile.count += 1;
+        let Some(bytes) = text else {
+            profile.nulls += 1;
+            return self;
+        };
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            // Invalid text counts as a value, as its length and markup still say something about the field
+            profile.invalid_utf8 += 1;
+            let text = String::from_utf8_lossy(bytes);
+            self.add_text(text.trim(), rules, languages);
+            return self;
+        };
+        if text.contains('\u{FFFD}') {
+            profile.invalid_utf8 += 1;
+        }
+        self.add_text(text.trim(), rules, languages);
+        self
+    }
+
+    fn add_text(&mut self, text: &str, rules: &NullRules, languages: bool) {
+        let profile = &mut self.profile;
+        if text.is_empty() {
+            profile.blanks += 1;
+            return;
+        }
+        if rules.matches(text) {
+            profile.placeholders += 1;
+            return;
+        }
+        self.lengths.push(text.chars().count());
+        if MARKUP_REMNANT_RE.is_match(text) {
+            profile.markup += 1;
+        }
+        if languages {
+            match detect_language(text) {
+                Some((code, _)) => *profile.languages.entry(code).or_default() += 1,
+                None => profile.undetected_language += 1,
+            }
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        let (a, b) = (&mut self.profile, other.profile);
+        a.count += b.count;
+        a.nulls += b.nulls;
+        a.blanks += b.blanks;
+        a.placeholders += b.placeholders;
+        a.invalid_utf8 += b.invalid_utf8;
+        a.markup += b.markup;
+        a.undetected_language += b.undetected_language;
+        for (code, count) in b.languages {
+            *a.languages.entry(code).or_default() += count;
+        }
+        self.lengths.extend(other.lengths);
+        self
+    }
+}
+
+/// Profiles a field of texts for data-quality reports, in parallel on the current rayon thread pool: how many values
+/// are null, blank, placeholders according to `rules` or invalid UTF-8, and, of the values that are text, the
+/// distribution of their lengths, how many have markup left in them and, with `languages`, the mix of their detected
+/// languages. Texts are given as bytes so that values that aren't valid UTF-8 can be counted, and are profiled as
+/// decoded lossily.
+pub fn profile_texts(texts: &BinaryArray, rules: &NullRules, languages: bool) -> TextProfile {
+    let partial = (0..texts.len())
+        .into_par_iter()
+        .fold(PartialProfile::default, |partial, i| {
+            let text = texts.is_valid(i).then(|| texts.value(i));
+            partial.add(text, rules, languages)
+        })
+        .reduce(PartialProfile::default, PartialProfile::merge);
+    TextProfile {
+        lengths: LengthDistribution::new(partial.lengths),
+        ..partial.profile
+    }
+}
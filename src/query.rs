@@ -0,0 +1,638 @@
+use crate::core::{days_in_month, normalize_doi, parse_date, ParsedDate};
+use crate::fusion::reciprocal_rank_fusion;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// DMP projects without a start date are searched for works published since this date.
+const MIN_START_DATE: &str = "1990-01-01";
+
+/// Slop of the phrase queries matching institution and funder names.
+const ORGANIZATION_NAME_SLOP: u32 = 3;
+
+/// An author, institution or funder of a DMP: its ORCID iD or ROR ID and its surname or name.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct QueryEntity {
+    #[serde(alias = "orcid", alias = "ror")]
+    pub id: Option<String>,
+    #[serde(alias = "surname")]
+    pub name: Option<String>,
+}
+
+/// The fields of a DMP that the works search query is built from.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DmpQueryFields {
+    pub doi: Option<String>,
+    pub title: Option<String>,
+    pub abstract_text: Option<String>,
+    pub project_start: Option<String>,
+    pub project_end: Option<String>,
+    pub funded_dois: Vec<String>,
+    pub authors: Vec<QueryEntity>,
+    pub institutions: Vec<QueryEntity>,
+    pub funders: Vec<QueryEntity>,
+    /// The identifier variants of each of the DMP's awards.
+    pub awards: Vec<Vec<String>>,
+    /// The raw identifiers of each of the DMP's funding items, e.g. its award ID and funder project number, matched
+    /// instead of `awards` when the DMP has no parsed awards.
+    pub raw_awards: Vec<Vec<String>>,
+    pub published_output_dois: Vec<String>,
+}
+
+/// Boosts of the works search query's clauses.
+#[derive(Debug, Clone)]
+pub struct QueryBoosts {
+    pub funded_dois: f64,
+    pub awards: f64,
+    /// Matches on an author's ORCID iD or an institution's or funder's ROR ID.
+    pub entity_id: f64,
+    /// Matches on an author's surname or an institution's or funder's name.
+    pub entity_name: f64,
+    pub content: f64,
+    pub intra_work_dois: f64,
+    pub possible_shared_project_dois: f64,
+    pub dataset_citation_dois: f64,
+}
+
+impl Default for QueryBoosts {
+    fn default() -> Self {
+        Self {
+            funded_dois: 15.0,
+            awards: 10.0,
+            entity_id: 2.0,
+            entity_name: 1.0,
+            content: 1.0,
+            intra_work_dois: 10.0,
+            possible_shared_project_dois: 5.0,
+            dataset_citation_dois: 2.5,
+        }
+    }
+}
+
+impl QueryBoosts {
+    /// Returns the boost with the given name, e.g. "funded_dois", for overriding.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut f64> {
+        match name {
+            "funded_dois" => Some(&mut self.funded_dois),
+            "awards" => Some(&mut self.awards),
+            "entity_id" => Some(&mut self.entity_id),
+            "entity_name" => Some(&mut self.entity_name),
+            "content" => Some(&mut self.content),
+            "intra_work_dois" => Some(&mut self.intra_work_dois),
+            "possible_shared_project_dois" => Some(&mut self.possible_shared_project_dois),
+            "dataset_citation_dois" => Some(&mut self.dataset_citation_dois),
+            _ => None,
+        }
+    }
+}
+
+/// Toggles for the clauses of the works search query, all on by default. Disabled features are left out of the query,
+/// e.g. for ablation studies.
+#[derive(Debug, Clone)]
+pub struct QueryFeatures {
+    pub funded_dois: bool,
+    pub authors: bool,
+    pub institutions: bool,
+    pub funders: bool,
+    pub awards: bool,
+    pub content: bool,
+    pub relations: bool,
+}
+
+impl Default for QueryFeatures {
+    fn default() -> Self {
+        Self {
+            funded_dois: true,
+            authors: true,
+            institutions: true,
+            funders: true,
+            awards: true,
+            content: true,
+            relations: true,
+        }
+    }
+}
+
+impl QueryFeatures {
+    /// Returns the toggle of the feature with the given name, e.g. "awards", for overriding.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "funded_dois" => Some(&mut self.funded_dois),
+            "authors" => Some(&mut self.authors),
+            "institutions" => Some(&mut self.institutions),
+            "funders" => Some(&mut self.funders),
+            "awards" => Some(&mut self.awards),
+            "content" => Some(&mut self.content),
+            "relations" => Some(&mut self.relations),
+            _ => None,
+        }
+    }
+
+    /// The names of the disabled features, sorted.
+    pub fn disabled_names(&self) -> Vec<&'static str> {
+        [
+            ("authors", self.authors),
+            ("awards", self.awards),
+            ("content", self.content),
+            ("funded_dois", self.funded_dois),
+            ("funders", self.funders),
+            ("institutions", self.institutions),
+            ("relations", self.relations),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+/// Options for `build_work_query`. The defaults produce the baseline DMP works search query.
+#[derive(Debug, Clone)]
+pub struct WorkQueryOptions {
+    pub max_results: usize,
+    /// Number of years after the project end that works may still be published.
+    pub project_end_buffer_years: i32,
+    pub inner_hits_size: usize,
+    pub boosts: QueryBoosts,
+    /// The content (`more_like_this`) query's `minimum_should_match`, OpenSearch's "30%" when unset.
+    pub minimum_should_match: Option<String>,
+    /// Words the content query ignores, in addition to the index analyzer's own stop words.
+    pub stop_words: Vec<String>,
+    pub features: QueryFeatures,
+}
+
+impl Default for WorkQueryOptions {
+    fn default() -> Self {
+        Self {
+            max_results: 100,
+            project_end_buffer_years: 3,
+            inner_hits_size: 50,
+            boosts: QueryBoosts::default(),
+            minimum_should_match: None,
+            stop_words: Vec::new(),
+            features: QueryFeatures::default(),
+        }
+    }
+}
+
+/// A boost as JSON, integral boosts as integers like those of the Python baseline query builder, e.g. 15 rather than
+/// 15.0.
+fn boost_value(boost: f64) -> Value {
+    // Integers beyond 2^53 can't all be told apart as floats anyway
+    if boost.fract() == 0.0 && boost.abs() < 9_007_199_254_740_992.0 {
+        json!(boost as i64)
+    } else {
+        json!(boost)
+    }
+}
+
+/// Builds a nested query matching any of a path's nested documents, returning their inner hits.
+fn nested_query(path: &str, should: Vec<Value>, inner_hits_size: usize) -> Option<Value> {
+    if should.is_empty() {
+        return None;
+    }
+    Some(json!({
+        "nested": {
+            "path": path,
+            "query": {
+                "bool": {
+                    "minimum_should_match": 1,
+                    "should": should,
+                }
+            },
+            "inner_hits": {
+                "name": path,
+                "size": inner_hits_size,
+            },
+        }
+    }))
+}
+
+/// Builds a constant score term query named after its field and value, with a boost made by `boost_value` or, for
+/// the clauses the Python baseline query builder gives float boosts, a float.
+fn term_query(field: &str, value: &str, boost: Value) -> Value {
+    json!({
+        "constant_score": {
+            "_name": format!("{field}.{value}"),
+            "filter": {"term": {field: value}},
+            "boost": boost,
+        }
+    })
+}
+
+/// Builds a nested query matching entities (authors, institutions or funders) by ID, or by name with a phrase query,
+/// each entity scoring once with `dis_max` when both match.
+fn entity_query(
+    path: &str,
+    id_field: &str,
+    name_field: &str,
+    items: &[QueryEntity],
+    name_slop: Option<u32>,
+    options: &WorkQueryOptions,
+) -> Option<Value> {
+    let should = items
+        .iter()
+        .filter_map(|item| {
+            let mut queries = Vec::new();
+            if let Some(id) = &item.id {
+                queries.push(term_query(
+                    id_field,
+                    id,
+                    boost_value(options.boosts.entity_id),
+                ));
+            }
+            if let Some(name) = &item.name {
+                let mut phrase = json!({"query": name});
+                if let Some(slop) = name_slop {
+                    phrase["slop"] = json!(slop);
+                }
+                queries.push(json!({
+                    "constant_score": {
+                        "_name": format!("{name_field}.{name}"),
+                        "filter": {"match_phrase": {name_field: phrase}},
+                        "boost": boost_value(options.boosts.entity_name),
+                    }
+                }));
+            }
+            match queries.len() {
+                0 => None,
+                1 => queries.pop(),
+                _ => Some(json!({"dis_max": {"tie_breaker": 0, "queries": queries}})),
+            }
+        })
+        .collect();
+    nested_query(path, should, options.inner_hits_size)
+}
+
+/// Builds the `more_like_this` query matching works' titles and abstracts against the DMP's.
+fn content_query(content: &str, name: Option<&str>, options: &WorkQueryOptions) -> Value {
+    let mut query = Map::new();
+    if let Some(name) = name {
+        query.insert("_name".into(), json!(name));
+    }
+    query.insert("fields".into(), json!(["title", "abstract_text"]));
+    query.insert("like".into(), json!(content));
+    query.insert("min_term_freq".into(), json!(1));
+    if options.boosts.content != 1.0 {
+        query.insert("boost".into(), boost_value(options.boosts.content));
+    }
+    if let Some(minimum_should_match) = &options.minimum_should_match {
+        query.insert("minimum_should_match".into(), json!(minimum_should_match));
+    }
+    if !options.stop_words.is_empty() {
+        query.insert("stop_words".into(), json!(options.stop_words));
+    }
+    json!({"more_like_this": query})
+}
+
+/// Adds years to a date, moving February 29 to February 28 in non-leap years.
+fn add_years(date: ParsedDate, years: i32) -> String {
+    let year = date.year + years;
+    let day = date
+        .day
+        .zip(date.month)
+        .map(|(day, month)| day.min(days_in_month(year, month)));
+    ParsedDate {
+        year,
+        month: date.month,
+        day,
+    }
+    .to_iso()
+}
+
+/// Builds the OpenSearch works search query for a DMP, the same query as the Python baseline query builder with keys
+/// in the same order, so that the live pipeline and the offline evaluator send byte-identical msearch bodies. Works must match at least one of the
+/// DMP's funded DOIs, authors, awards (or raw award identifiers, when it has no parsed awards) or published outputs,
+/// are ranked up by matching institutions, funders and content, and are filtered to those published between the
+/// project start and the buffered project end, excluding other DMPs. Features disabled in `options` are left out.
+pub fn build_work_query(dmp: &DmpQueryFields, options: &WorkQueryOptions) -> Value {
+    let boosts = &options.boosts;
+    let features = &options.features;
+    let mut must = Vec::new();
+    let mut should = Vec::new();
+
+    if features.funded_dois && !dmp.funded_dois.is_empty() {
+        must.push(json!({
+            "constant_score": {
+                "_name": "funded_dois",
+                "boost": boost_value(boosts.funded_dois),
+                "filter": {"ids": {"values": dmp.funded_dois}},
+            }
+        }));
+    }
+
+    if features.authors {
+        must.extend(entity_query(
+            "authors",
+            "authors.orcid",
+            "authors.full",
+            &dmp.authors,
+            None,
+            options,
+        ));
+    }
+    if features.institutions {
+        should.extend(entity_query(
+            "institutions",
+            "institutions.ror",
+            "institutions.name",
+            &dmp.institutions,
+            Some(ORGANIZATION_NAME_SLOP),
+            options,
+        ));
+    }
+    if features.funders {
+        should.extend(entity_query(
+            "funders",
+            "funders.ror",
+            "funders.name",
+            &dmp.funders,
+            Some(ORGANIZATION_NAME_SLOP),
+            options,
+        ));
+    }
+
+    // Each award scores once however many of its variants match. Raw funding identifiers stand in for the awards of
+    // funders without an award ID parser
+    if features.awards {
+        let awards = if dmp.awards.is_empty() {
+            &dmp.raw_awards
+        } else {
+            &dmp.awards
+        };
+        let awards = awards
+            .iter()
+            .filter(|variants| !variants.is_empty())
+            .map(|variants| {
+                let queries: Vec<Value> = variants
+                    .iter()
+                    .map(|award_id| {
+                        term_query("awards.award_id", award_id, boost_value(boosts.awards))
+                    })
+                    .collect();
+                json!({"dis_max": {"tie_breaker": 0, "queries": queries}})
+            })
+            .collect();
+        must.extend(nested_query("awards", awards, options.inner_hits_size));
+    }
+
+    let content = if features.content {
+        [&dmp.title, &dmp.abstract_text]
+            .into_iter()
+            .flatten()
+            .filter(|text| !text.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        String::new()
+    };
+    if !content.is_empty() {
+        should.push(content_query(&content, Some("content"), options));
+    }
+
+    // Intra work DOIs are the same core work, shared project DOIs are e.g. supplements, and dataset citations are
+    // weaker evidence
+    if features.relations {
+        for (relation, boost) in [
+            ("intra_work_dois", boosts.intra_work_dois),
+            (
+                "possible_shared_project_dois",
+                boosts.possible_shared_project_dois,
+            ),
+            ("dataset_citation_dois", boosts.dataset_citation_dois),
+        ] {
+            let path = format!("relations.{relation}");
+            let doi_field = format!("{path}.doi");
+            let queries = dmp
+                .published_output_dois
+                .iter()
+                .map(|doi| term_query(&doi_field, doi, json!(boost)))
+                .collect();
+            must.extend(nested_query(&path, queries, options.inner_hits_size));
+        }
+    }
+
+    if must.is_empty() {
+        let disabled: Vec<String> = features
+            .disabled_names()
+            .iter()
+            .map(|name| format!("'{name}'"))
+            .collect();
+        warn!(
+            "No must-clause features produced a clause for DMP {}. Disabled features: [{}]. DMP will return zero \
+             results.",
+            dmp.doi.as_deref().unwrap_or("None"),
+            disabled.join(", ")
+        );
+    }
+
+    let start = parse_date(dmp.project_start.as_deref())
+        .map_or(MIN_START_DATE.to_string(), ParsedDate::to_iso);
+    let mut date_range = json!({"gte": start});
+    if let Some(end) = parse_date(dmp.project_end.as_deref()) {
+        date_range["lte"] = json!(add_years(end, options.project_end_buffer_years));
+    }
+    let filters = json!([
+        {"bool": {"must_not": {"term": {"work_type": "OUTPUT_MANAGEMENT_PLAN"}}}},
+        {"range": {"publication_date": date_range}},
+    ]);
+
+    let mut query = json!({
+        "size": options.max_results,
+        "query": {
+            "bool": {
+                "must": [{"bool": {"should": must, "minimum_should_match": 1}}],
+                "should": should,
+                "filter": filters,
+            },
+        },
+    });
+
+    if !content.is_empty() {
+        query["highlight"] = json!({
+            "pre_tags": ["<mark>"],
+            "post_tags": ["</mark>"],
+            "order": "score",
+            "require_field_match": true,
+            "fields": {
+                "title": {
+                    "type": "fvh",
+                    "number_of_fragments": 0,
+                    "fragment_size": 0,
+                    "no_match_size": 500,
+                },
+                "abstract_text": {
+                    "type": "fvh",
+                    "fragment_size": 160,
+                    "number_of_fragments": 2,
+                    "no_match_size": 160,
+                },
+            },
+            "highlight_query": content_query(&content, None, options),
+        });
+    }
+    query
+}
+
+/// How the hits of an msearch response's sub-queries are identified as the same candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupeBy {
+    /// The normalized DOI of the hit's `_source.doi`, falling back to its `_id`.
+    Doi,
+    /// The hit's `_id`.
+    Id,
+}
+
+impl DedupeBy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "doi" => Some(DedupeBy::Doi),
+            "id" => Some(DedupeBy::Id),
+            _ => None,
+        }
+    }
+}
+
+/// An OpenSearch msearch response, with one response for each sub-query of the request.
+#[derive(Debug, Deserialize)]
+struct MsearchResponse {
+    responses: Vec<MsearchItem>,
+}
+
+/// The response to one sub-query of an msearch request: its hits, or the error it failed with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MsearchItem {
+    hits: MsearchHits,
+    error: Option<Value>,
+}
+
+/// The hits of a sub-query, best first.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MsearchHits {
+    hits: Vec<MsearchHit>,
+}
+
+/// A hit of a sub-query. Its `matched_queries` are a list of names, or an object of names to scores when requested
+/// with `include_named_queries_score`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MsearchHit {
+    #[serde(rename = "_id")]
+    id: Option<String>,
+    #[serde(rename = "_score")]
+    score: Option<f64>,
+    #[serde(rename = "_source")]
+    source: Option<Value>,
+    matched_queries: Value,
+}
+
+impl MsearchHit {
+    fn matched_queries(&self) -> Vec<String> {
+        match &self.matched_queries {
+            Value::Array(names) => names
+                .iter()
+                .filter_map(|name| name.as_str().map(str::to_string))
+                .collect(),
+            Value::Object(scores) => scores.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A candidate work merged from the hits of an msearch response's sub-queries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MsearchCandidate {
+    /// The DOI or ID the hits were deduplicated by.
+    pub key: String,
+    /// The `_id` of the candidate's first hit.
+    pub id: Option<String>,
+    /// The reciprocal rank fusion score of the candidate's ranks.
+    pub score: f64,
+    /// The candidate's rank in each sub-query's hits, counting from 1, or None where it wasn't a hit.
+    pub ranks: Vec<Option<usize>>,
+    /// The candidate's `_score` in each sub-query's hits, or None where it wasn't a hit or wasn't scored.
+    pub scores: Vec<Option<f64>>,
+    /// The names of the queries the candidate matched in any sub-query, in order of first match.
+    pub matched_queries: Vec<String>,
+    /// The `_source` of the candidate's first hit.
+    pub source: Option<Value>,
+}
+
+/// Parses an OpenSearch msearch response, e.g. to the sub-queries searched for one DMP, into a single list of
+/// candidates: each sub-query's hits are flattened, deduplicated by DOI or ID within and across the sub-queries, and
+/// fused with reciprocal rank fusion (see `reciprocal_rank_fusion`, with `rrf_k`) into a ranking, best first. Hits
+/// without a key are dropped, and sub-queries that failed are logged and count as having no hits.
+pub fn parse_msearch_response(
+    bytes: &[u8],
+    dedupe_by: DedupeBy,
+    rrf_k: f64,
+) -> serde_json::Result<Vec<MsearchCandidate>> {
+    let response: MsearchResponse = serde_json::from_slice(bytes)?;
+    let num_queries = response.responses.len();
+    let mut candidates: HashMap<String, MsearchCandidate> = HashMap::new();
+    let mut rankings = Vec::with_capacity(num_queries);
+    for (query, item) in response.responses.into_iter().enumerate() {
+        if let Some(error) = &item.error {
+            let reason = error
+                .get("reason")
+                .and_then(Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_string);
+            warn!("parse_msearch_response: query {query} failed: {reason}");
+        }
+        let mut ranking = Vec::with_capacity(item.hits.hits.len());
+        for hit in item.hits.hits {
+            let doi = match dedupe_by {
+                DedupeBy::Doi => hit
+                    .source
+                    .as_ref()
+                    .and_then(|source| normalize_doi(source.get("doi").and_then(Value::as_str))),
+                DedupeBy::Id => None,
+            };
+            let Some(key) = doi.or_else(|| hit.id.clone()) else {
+                continue;
+            };
+            // Repeated hits within a sub-query keep their first rank
+            if ranking.contains(&key) {
+                continue;
+            }
+            ranking.push(key.clone());
+            let matched_queries = hit.matched_queries();
+            let candidate = candidates
+                .entry(key.clone())
+                .or_insert_with(|| MsearchCandidate {
+                    key,
+                    id: hit.id,
+                    score: 0.0,
+                    ranks: vec![None; num_queries],
+                    scores: vec![None; num_queries],
+                    matched_queries: Vec::new(),
+                    source: hit.source,
+                });
+            candidate.ranks[query] = Some(ranking.len());
+            candidate.scores[query] = hit.score;
+            for name in matched_queries {
+                if !candidate.matched_queries.contains(&name) {
+                    candidate.matched_queries.push(name);
+                }
+            }
+        }
+        rankings.push(ranking);
+    }
+
+    Ok(reciprocal_rank_fusion(&rankings, rrf_k)
+        .into_iter()
+        .map(|(key, score)| {
+            let mut candidate = candidates
+                .remove(&key)
+                .expect("every ranked key is a candidate");
+            candidate.score = score;
+            candidate
+        })
+        .collect())
+}
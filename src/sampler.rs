@@ -0,0 +1,406 @@
+use crate::core::{normalize_doi, normalize_ror, read_jsonl};
+use crate::funders::normalize_funder_name;
+use crate::institutions::normalize_institution_name;
+use crate::scoring::ScoringOrganization;
+use rayon::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io;
+use std::path::Path;
+
+/// How candidate DMP–work pairs are stratified and how many are sampled from each stratum.
+#[derive(Debug, Clone)]
+pub struct SamplingStrategy {
+    /// Maximum number of pairs sampled from each stratum.
+    pub per_stratum: usize,
+    /// Whether pairs are stratified by the work's type.
+    pub by_work_type: bool,
+    /// Ascending edges of the year gap bands, the work's publication year minus the DMP's project start year.
+    pub year_gap_edges: Vec<f64>,
+    /// Ascending edges of the similarity bands, over the candidate's `score`.
+    pub similarity_edges: Vec<f64>,
+    /// Seed for the random number generator, so that samples are reproducible.
+    pub seed: u64,
+}
+
+impl Default for SamplingStrategy {
+    fn default() -> Self {
+        Self {
+            per_stratum: 50,
+            by_work_type: true,
+            year_gap_edges: vec![0.0, 2.0, 5.0],
+            similarity_edges: vec![0.25, 0.5, 0.75],
+            seed: 0,
+        }
+    }
+}
+
+/// The stratum a pair was sampled from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stratum {
+    pub work_type: Option<String>,
+    pub year_gap: String,
+    pub similarity: String,
+}
+
+/// A sampled candidate pair: its position among the candidates file's records, stratum and record.
+#[derive(Debug, Clone)]
+pub struct SampledPair {
+    pub index: usize,
+    pub stratum: Stratum,
+    pub record: Value,
+}
+
+/// SplitMix64, a small, fast and well-distributed pseudo-random number generator.
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed integer below `bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+}
+
+/// Labels the band a value falls in given ascending band edges, e.g. "<0", "[0, 2)", ">=5", or "unknown" when the
+/// value is missing.
+fn band_label(value: Option<f64>, edges: &[f64]) -> String {
+    let Some(value) = value else {
+        return "unknown".to_string();
+    };
+    match edges.iter().position(|edge| value < *edge) {
+        None if edges.is_empty() => "all".to_string(),
+        None => format!(">={}", edges[edges.len() - 1]),
+        Some(0) => format!("<{}", edges[0]),
+        Some(i) => format!("[{}, {})", edges[i - 1], edges[i]),
+    }
+}
+
+/// Reads the year from an ISO 8601 date string, e.g. "2021-03-05", or a bare year number.
+fn year_of(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(year) => year.as_f64(),
+        Value::String(date) => date.trim().get(..4)?.parse::<u16>().ok().map(f64::from),
+        _ => None,
+    }
+}
+
+/// Loads each DMP's project start year from a JSONL file of DMP records with `doi` and `project_start` fields.
+fn load_project_start_years(dmps: &Path) -> io::Result<HashMap<String, f64>> {
+    let mut years = HashMap::new();
+    for record in read_jsonl(
+        dmps,
+        Some(vec!["doi".to_string(), "project_start".to_string()]),
+    )? {
+        let record = record?;
+        if let (Some(doi), Some(year)) =
+            (record["doi"].as_str(), year_of(record.get("project_start")))
+        {
+            years.insert(doi.to_lowercase(), year);
+        }
+    }
+    Ok(years)
+}
+
+/// Draws a stratified sample of DMP–work pairs for manual review, streaming a large candidates file. Each candidate
+/// is a JSONL record with `dmp_doi`, `work_doi`, `work_type`, `publication_date` and `score` fields, and is assigned
+/// a stratum from its work type, the gap between its publication year and the DMP's project start year (looked up
+/// in the DMPs file) and its similarity band. Reservoir sampling keeps up to `per_stratum` uniformly sampled pairs
+/// per stratum in memory, so the candidates file is read once and never loaded whole. Pairs are returned ordered by
+/// stratum and then position in the candidates file.
+pub fn sample_candidate_pairs(
+    dmps: &Path,
+    candidates: &Path,
+    strategy: &SamplingStrategy,
+) -> io::Result<Vec<SampledPair>> {
+    let project_start_years = load_project_start_years(dmps)?;
+    let mut rng = SplitMix64(strategy.seed);
+    let mut reservoirs: BTreeMap<Stratum, (u64, Vec<SampledPair>)> = BTreeMap::new();
+
+    for (index, record) in read_jsonl(candidates, None)?.enumerate() {
+        let record = record?;
+        let work_type = strategy
+            .by_work_type
+            .then(|| {
+                record
+                    .get("work_type")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+            .flatten();
+        let project_start = record
+            .get("dmp_doi")
+            .and_then(Value::as_str)
+            .and_then(|doi| project_start_years.get(&doi.to_lowercase()));
+        let year_gap = year_of(record.get("publication_date"))
+            .zip(project_start)
+            .map(|(published, start)| published - start);
+        let score = record.get("score").and_then(Value::as_f64);
+        let stratum = Stratum {
+            work_type,
+            year_gap: band_label(year_gap, &strategy.year_gap_edges),
+            similarity: band_label(score, &strategy.similarity_edges),
+        };
+
+        // Algorithm R: the nth pair replaces a random reservoir slot with probability per_stratum / n
+        let (seen, reservoir) = reservoirs.entry(stratum.clone()).or_default();
+        *seen += 1;
+        let pair = SampledPair {
+            index,
+            stratum,
+            record,
+        };
+        if reservoir.len() < strategy.per_stratum {
+            reservoir.push(pair);
+        } else {
+            let slot = rng.below(*seen) as usize;
+            if slot < strategy.per_stratum {
+                reservoir[slot] = pair;
+            }
+        }
+    }
+
+    let mut sample: Vec<SampledPair> = Vec::new();
+    for (_, (_, mut reservoir)) in reservoirs {
+        reservoir.sort_by_key(|pair| pair.index);
+        sample.extend(reservoir);
+    }
+    Ok(sample)
+}
+
+/// How many of each DMP's negative pairs are hard negatives, works sharing a funder or institution with the DMP, and
+/// which are mined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HardNegativeOptions {
+    /// Share (0–1) of each DMP's negatives that are hard, when it has enough hard candidates; the rest are random.
+    pub fraction: f64,
+    pub same_funder: bool,
+    pub same_institution: bool,
+}
+
+impl Default for HardNegativeOptions {
+    fn default() -> Self {
+        Self {
+            fraction: 0.5,
+            same_funder: true,
+            same_institution: true,
+        }
+    }
+}
+
+/// How a negative pair was sampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegativeKind {
+    /// A work sharing a funder with the DMP.
+    Funder,
+    /// A work sharing an institution, but no funder, with the DMP.
+    Institution,
+    /// A work drawn uniformly from all works.
+    Random,
+}
+
+impl NegativeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NegativeKind::Funder => "funder",
+            NegativeKind::Institution => "institution",
+            NegativeKind::Random => "random",
+        }
+    }
+}
+
+/// A negative pair: the positions of its DMP and work, and how it was sampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativePair {
+    pub dmp: usize,
+    pub work: usize,
+    pub kind: NegativeKind,
+}
+
+/// The fields of a DMP or work that negative pairs are sampled by: its DOI, the DOIs of the works a DMP lists, e.g.
+/// its funded DOIs and published outputs, and its funders and institutions.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NegativeSamplingRecord {
+    pub doi: Option<String>,
+    pub dois: Vec<String>,
+    pub funders: Vec<ScoringOrganization>,
+    pub institutions: Vec<ScoringOrganization>,
+}
+
+/// Keys of organizations, shared by two organizations when they are the same: their ROR IDs or DOIs, and their
+/// normalized names, so that an organization known only by name still shares a key with one that has an ID.
+fn organization_keys(
+    organizations: &[ScoringOrganization],
+    normalize_name: fn(Option<&str>) -> Option<String>,
+) -> BTreeSet<String> {
+    organizations
+        .iter()
+        .flat_map(|organization| {
+            let id = organization.id.as_deref();
+            let id = normalize_ror(id).or_else(|| normalize_doi(id));
+            let name =
+                normalize_name(organization.name.as_deref()).map(|name| format!("name:{name}"));
+            id.into_iter().chain(name)
+        })
+        .collect()
+}
+
+/// Organization keys mapped to the positions of the records having them, ascending.
+fn organization_index(keys: &[BTreeSet<String>]) -> HashMap<&str, Vec<usize>> {
+    let mut index: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (position, keys) in keys.iter().enumerate() {
+        for key in keys {
+            index.entry(key).or_default().push(position);
+        }
+    }
+    index
+}
+
+/// Draws up to `k` distinct candidates in random order, with a partial Fisher–Yates shuffle.
+fn sample_without_replacement(
+    candidates: &mut [usize],
+    k: usize,
+    rng: &mut SplitMix64,
+) -> Vec<usize> {
+    let k = k.min(candidates.len());
+    for i in 0..k {
+        let j = i + rng.below((candidates.len() - i) as u64) as usize;
+        candidates.swap(i, j);
+    }
+    candidates[..k].to_vec()
+}
+
+/// Generates up to `n_per_dmp` negative pairs per DMP for training a classifier: works that aren't known matches of
+/// the DMP, i.e. neither listed in its `dois` nor paired with its DOI in `positives`. Hard negatives, works sharing a
+/// funder or institution with the DMP by ROR ID, funder DOI or normalized name, make up `fraction` of each DMP's
+/// negatives where there are enough of them, and random negatives drawn uniformly from all works the rest. Each
+/// DMP's negatives are drawn from a generator seeded by `seed` and the DMP's position, so that the pairs are the same
+/// however many threads sample them in parallel on the current rayon thread pool. Pairs are returned ordered by DMP,
+/// hard negatives first. DMPs get fewer pairs when there aren't enough works that aren't known matches.
+pub fn generate_negative_pairs(
+    dmps: &[NegativeSamplingRecord],
+    works: &[NegativeSamplingRecord],
+    positives: &[(String, String)],
+    n_per_dmp: usize,
+    seed: u64,
+    options: &HardNegativeOptions,
+) -> Vec<NegativePair> {
+    let work_dois: Vec<Option<String>> = works
+        .iter()
+        .map(|work| normalize_doi(work.doi.as_deref()))
+        .collect();
+    let mut positive_dois: HashMap<String, HashSet<String>> = HashMap::new();
+    for (dmp_doi, work_doi) in positives {
+        if let (Some(dmp_doi), Some(work_doi)) =
+            (normalize_doi(Some(dmp_doi)), normalize_doi(Some(work_doi)))
+        {
+            positive_dois.entry(dmp_doi).or_default().insert(work_doi);
+        }
+    }
+
+    let funder_keys: Vec<BTreeSet<String>> = works
+        .iter()
+        .map(|work| organization_keys(&work.funders, normalize_funder_name))
+        .collect();
+    let institution_keys: Vec<BTreeSet<String>> = works
+        .iter()
+        .map(|work| organization_keys(&work.institutions, normalize_institution_name))
+        .collect();
+    let funder_index = organization_index(&funder_keys);
+    let institution_index = organization_index(&institution_keys);
+
+    let sample_dmp = |(position, dmp): (usize, &NegativeSamplingRecord)| -> Vec<NegativePair> {
+        let mut rng = SplitMix64(SplitMix64(seed ^ position as u64).next_u64());
+        let mut matches: HashSet<String> = dmp
+            .dois
+            .iter()
+            .filter_map(|doi| normalize_doi(Some(doi)))
+            .collect();
+        if let Some(listed) =
+            normalize_doi(dmp.doi.as_deref()).and_then(|doi| positive_dois.get(&doi))
+        {
+            matches.extend(listed.iter().cloned());
+        }
+        let is_negative = |work: usize| {
+            work_dois[work]
+                .as_deref()
+                .is_none_or(|doi| !matches.contains(doi))
+        };
+
+        let mut kinds: BTreeMap<usize, NegativeKind> = BTreeMap::new();
+        if options.same_institution {
+            for key in organization_keys(&dmp.institutions, normalize_institution_name) {
+                for &work in institution_index.get(key.as_str()).into_iter().flatten() {
+                    kinds.insert(work, NegativeKind::Institution);
+                }
+            }
+        }
+        if options.same_funder {
+            for key in organization_keys(&dmp.funders, normalize_funder_name) {
+                for &work in funder_index.get(key.as_str()).into_iter().flatten() {
+                    kinds.insert(work, NegativeKind::Funder);
+                }
+            }
+        }
+        let mut hard: Vec<usize> = kinds
+            .keys()
+            .copied()
+            .filter(|&work| is_negative(work))
+            .collect();
+        let n_hard = (n_per_dmp as f64 * options.fraction).round() as usize;
+        let mut pairs: Vec<NegativePair> = sample_without_replacement(&mut hard, n_hard, &mut rng)
+            .into_iter()
+            .map(|work| NegativePair {
+                dmp: position,
+                work,
+                kind: kinds[&work],
+            })
+            .collect();
+
+        // Rejection sampling, which is fast while negatives are plentiful, then a shuffle of those left over
+        let mut chosen: HashSet<usize> = pairs.iter().map(|pair| pair.work).collect();
+        let mut attempts = 0;
+        while pairs.len() < n_per_dmp && attempts < 4 * n_per_dmp {
+            attempts += 1;
+            let work = rng.below(works.len() as u64) as usize;
+            if is_negative(work) && chosen.insert(work) {
+                pairs.push(NegativePair {
+                    dmp: position,
+                    work,
+                    kind: NegativeKind::Random,
+                });
+            }
+        }
+        if pairs.len() < n_per_dmp {
+            let mut left: Vec<usize> = (0..works.len())
+                .filter(|&work| is_negative(work) && !chosen.contains(&work))
+                .collect();
+            let k = n_per_dmp - pairs.len();
+            pairs.extend(
+                sample_without_replacement(&mut left, k, &mut rng)
+                    .into_iter()
+                    .map(|work| NegativePair {
+                        dmp: position,
+                        work,
+                        kind: NegativeKind::Random,
+                    }),
+            );
+        }
+        pairs
+    };
+
+    if works.is_empty() {
+        return Vec::new();
+    }
+    let per_dmp: Vec<Vec<NegativePair>> = dmps.par_iter().enumerate().map(sample_dmp).collect();
+    per_dmp.into_iter().flatten().collect()
+}
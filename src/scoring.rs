@@ -0,0 +1,337 @@
+use crate::core::{
+    author_overlap, combined_relevance, normalize_doi, normalize_ror, normalize_title, parse_date,
+    parse_romanized_name, ParsedName,
+};
+use crate::funders::normalize_funder_name;
+use crate::similarity::token_sort_ratio;
+use numpy::ndarray::Array2;
+use rayon::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Minimum similarity of two normalized organization names for them to count as the same institution or funder.
+const MIN_ORGANIZATION_SIMILARITY: f64 = 0.9;
+
+/// Works published this many days or more outside the project period have a date proximity of 0.
+const MAX_DATE_DISTANCE_DAYS: f64 = 1096.0;
+
+/// Names of the features computed by `score_candidate`, in order.
+pub const CANDIDATE_FEATURES: [&str; 6] =
+    ["title", "authors", "affiliations", "funders", "date", "doi"];
+
+/// Default weights of the features in a candidate's score.
+pub const DEFAULT_FEATURE_WEIGHTS: [(&str, f64); 6] = [
+    ("title", 1.0),
+    ("authors", 2.0),
+    ("affiliations", 0.5),
+    ("funders", 1.0),
+    ("date", 0.5),
+    ("doi", 3.0),
+];
+
+/// An author of a DMP or work, parsed from whichever name parts are present.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScoringAuthor {
+    pub given_name: Option<String>,
+    pub surname: Option<String>,
+    pub full: Option<String>,
+}
+
+/// An institution or funder of a DMP or work: its ROR ID (or funder DOI) and name.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScoringOrganization {
+    #[serde(alias = "ror", alias = "funder_doi")]
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// The fields of a DMP that candidate works are scored against.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScoringDmp {
+    pub title: Option<String>,
+    pub project_start: Option<String>,
+    pub project_end: Option<String>,
+    pub authors: Vec<ScoringAuthor>,
+    pub institutions: Vec<ScoringOrganization>,
+    pub funders: Vec<ScoringOrganization>,
+    /// DOIs of works the DMP lists, e.g. its funded DOIs and published outputs.
+    pub dois: Vec<String>,
+}
+
+/// The fields of a candidate work in our normalized schema that are scored.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScoringWork {
+    pub doi: Option<String>,
+    pub title: Option<String>,
+    pub publication_date: Option<String>,
+    pub authors: Vec<ScoringAuthor>,
+    pub institutions: Vec<ScoringOrganization>,
+    pub funders: Vec<ScoringOrganization>,
+}
+
+/// A candidate's features, named as in `CANDIDATE_FEATURES` and None where either side lacks the data, and their
+/// weighted score.
+#[derive(Debug, Clone)]
+pub struct CandidateScore {
+    pub features: Vec<(&'static str, Option<f64>)>,
+    pub score: f64,
+}
+
+/// Parses authors' names, romanizing names in non-Latin scripts.
+fn parse_authors(authors: &[ScoringAuthor]) -> Vec<ParsedName> {
+    authors
+        .iter()
+        .map(|author| {
+            parse_romanized_name(
+                author.given_name.as_deref(),
+                author.surname.as_deref(),
+                author.full.as_deref(),
+            )
+        })
+        .collect()
+}
+
+/// Normalizes an organization ID, a ROR ID or a DOI, for comparison.
+fn organization_id(id: Option<&str>) -> Option<String> {
+    normalize_ror(id).or_else(|| normalize_doi(id))
+}
+
+/// Scores how likely two organizations are the same (0–1): 1.0 when their IDs are equal, otherwise the
+/// `token_sort_ratio` of their normalized names when it is at least `MIN_ORGANIZATION_SIMILARITY`.
+fn organization_similarity(a: &ScoringOrganization, b: &ScoringOrganization) -> f64 {
+    if let (Some(id_a), Some(id_b)) = (
+        organization_id(a.id.as_deref()),
+        organization_id(b.id.as_deref()),
+    ) {
+        if id_a == id_b {
+            return 1.0;
+        }
+    }
+    let (Some(name_a), Some(name_b)) = (
+        normalize_funder_name(a.name.as_deref()),
+        normalize_funder_name(b.name.as_deref()),
+    ) else {
+        return 0.0;
+    };
+    let score = token_sort_ratio(&name_a, &name_b);
+    if score >= MIN_ORGANIZATION_SIMILARITY {
+        score
+    } else {
+        0.0
+    }
+}
+
+/// The mean over the DMP's organizations of their best similarity to any of the work's organizations.
+fn organization_overlap(dmp: &[ScoringOrganization], work: &[ScoringOrganization]) -> Option<f64> {
+    if dmp.is_empty() || work.is_empty() {
+        return None;
+    }
+    let total: f64 = dmp
+        .iter()
+        .map(|a| {
+            work.iter()
+                .map(|b| organization_similarity(a, b))
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    Some(total / dmp.len() as f64)
+}
+
+/// Proximity (0–1) of a work's publication date to the project period: 1.0 when the work was published during the
+/// project, decreasing linearly to 0.0 at `MAX_DATE_DISTANCE_DAYS` before its start or after its end.
+fn date_proximity(dmp: &ScoringDmp, work: &ScoringWork) -> Option<f64> {
+    let (published_first, published_last) =
+        parse_date(work.publication_date.as_deref())?.day_range();
+    let (start, _) = parse_date(dmp.project_start.as_deref())?.day_range();
+    let end = parse_date(dmp.project_end.as_deref()).map_or(i64::MAX, |end| end.day_range().1);
+    let gap = (start - published_last)
+        .max(published_first.saturating_sub(end))
+        .max(0) as f64;
+    Some(1.0 - (gap / MAX_DATE_DISTANCE_DAYS).min(1.0))
+}
+
+/// Computes a candidate work's matching features against a DMP and their weighted mean, see `combined_relevance`:
+/// - title: `token_sort_ratio` of the normalized titles.
+/// - authors: the `author_overlap` score of the author lists.
+/// - affiliations and funders: the mean best similarity of each DMP institution or funder to the work's, matching
+///   on ROR IDs or funder DOIs, then names.
+/// - date: proximity of the publication date to the project period.
+/// - doi: 1.0 when the DMP lists the work's DOI, otherwise 0.0.
+///
+/// Weights missing from `weights` count as zero.
+pub fn score_candidate(
+    dmp: &ScoringDmp,
+    work: &ScoringWork,
+    weights: &HashMap<String, f64>,
+) -> CandidateScore {
+    let values = candidate_features(dmp, work);
+    let signals: HashMap<String, f64> = CANDIDATE_FEATURES
+        .iter()
+        .zip(values)
+        .filter_map(|(name, value)| Some((name.to_string(), value?)))
+        .collect();
+    CandidateScore {
+        features: CANDIDATE_FEATURES.into_iter().zip(values).collect(),
+        score: combined_relevance(&signals, weights),
+    }
+}
+
+/// Computes a candidate work's features against a DMP in `CANDIDATE_FEATURES` order, see `score_candidate`.
+fn candidate_features(dmp: &ScoringDmp, work: &ScoringWork) -> [Option<f64>; 6] {
+    let title = normalize_title(dmp.title.as_deref(), true, true, None)
+        .zip(normalize_title(work.title.as_deref(), true, true, None))
+        .map(|(a, b)| token_sort_ratio(&a, &b));
+
+    let authors = author_overlap(&parse_authors(&dmp.authors), &parse_authors(&work.authors))
+        .map(|overlap| overlap.score);
+
+    let doi = (!dmp.dois.is_empty())
+        .then(|| normalize_doi(work.doi.as_deref()))
+        .flatten()
+        .map(|doi| {
+            let listed = dmp
+                .dois
+                .iter()
+                .any(|listed| normalize_doi(Some(listed)).as_ref() == Some(&doi));
+            if listed {
+                1.0
+            } else {
+                0.0
+            }
+        });
+
+    [
+        title,
+        authors,
+        organization_overlap(&dmp.institutions, &work.institutions),
+        organization_overlap(&dmp.funders, &work.funders),
+        date_proximity(dmp, work),
+        doi,
+    ]
+}
+
+/// Scores many candidate works against a DMP with `score_candidate` in parallel on the current rayon thread pool,
+/// preserving order.
+pub fn score_candidates(
+    dmp: &ScoringDmp,
+    works: &[ScoringWork],
+    weights: &HashMap<String, f64>,
+) -> Vec<CandidateScore> {
+    works
+        .par_iter()
+        .map(|work| score_candidate(dmp, work, weights))
+        .collect()
+}
+
+/// Computes the features of many DMP–work pairs, given as indexes into `dmps` and `works` so that a DMP shared by
+/// many pairs is stored once, as a matrix for training a model such as `MatchModel`: a row per pair and a column
+/// per feature in `CANDIDATE_FEATURES` order, with missing features as NaN. Pairs are featurized in parallel on the
+/// current rayon thread pool.
+pub fn featurize_pairs(
+    dmps: &[ScoringDmp],
+    works: &[ScoringWork],
+    pairs: &[(usize, usize)],
+) -> Array2<f32> {
+    let rows: Vec<[Option<f64>; 6]> = pairs
+        .par_iter()
+        .map(|&(dmp, work)| candidate_features(&dmps[dmp], &works[work]))
+        .collect();
+    let values = rows
+        .iter()
+        .flatten()
+        .map(|value| value.map_or(f32::NAN, |value| value as f32))
+        .collect();
+    Array2::from_shape_vec((rows.len(), CANDIDATE_FEATURES.len()), values)
+        .expect("each row has a value for every feature")
+}
+
+/// The serialized form of a `MatchModel`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MatchModelSpec {
+    intercept: f64,
+    weights: HashMap<String, f64>,
+    #[serde(default)]
+    fill_values: HashMap<String, f64>,
+}
+
+/// A logistic regression model of whether a candidate work is a DMP's output, trained offline, e.g. in
+/// scikit-learn, on the features of `score_candidate`. It is loaded from JSON of the form
+/// `{"intercept": -4.2, "weights": {"title": 3.1, "authors": 2.4}, "fill_values": {"date": 0.5}}`, where `weights`
+/// are the model's coefficients by feature name and `fill_values` the values imputed for missing features. Features
+/// without a weight are unused and missing features without a fill value count as 0.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchModel {
+    pub intercept: f64,
+    /// Coefficients in `CANDIDATE_FEATURES` order.
+    pub coefficients: [f64; 6],
+    /// Values imputed for missing features, in `CANDIDATE_FEATURES` order.
+    pub fill_values: [f64; 6],
+}
+
+impl MatchModel {
+    /// Loads a model from JSON, returning a description of the problem when it is malformed, names an unknown
+    /// feature or has a value that isn't finite.
+    pub fn from_json(json: &[u8]) -> Result<Self, String> {
+        let spec: MatchModelSpec = serde_json::from_slice(json).map_err(|e| e.to_string())?;
+        let by_feature = |values: &HashMap<String, f64>, what: &str| -> Result<[f64; 6], String> {
+            let mut ordered = [0.0; 6];
+            for (name, value) in values {
+                let Some(i) = CANDIDATE_FEATURES.iter().position(|f| f == name) else {
+                    return Err(format!(
+                        "unknown feature '{name}' in {what}, expected one of {CANDIDATE_FEATURES:?}"
+                    ));
+                };
+                if !value.is_finite() {
+                    return Err(format!("{what} of '{name}' must be finite, got {value}"));
+                }
+                ordered[i] = *value;
+            }
+            Ok(ordered)
+        };
+        if !spec.intercept.is_finite() {
+            return Err(format!("intercept must be finite, got {}", spec.intercept));
+        }
+        Ok(Self {
+            intercept: spec.intercept,
+            coefficients: by_feature(&spec.weights, "weights")?,
+            fill_values: by_feature(&spec.fill_values, "fill_values")?,
+        })
+    }
+
+    /// The probability (0–1) that a candidate with the given features, in `CANDIDATE_FEATURES` order and None where
+    /// missing, is a match.
+    pub fn predict(&self, features: &[Option<f64>; 6]) -> f64 {
+        let logit = self.intercept
+            + features
+                .iter()
+                .zip(self.coefficients.iter().zip(&self.fill_values))
+                .map(|(value, (weight, fill))| weight * value.unwrap_or(*fill))
+                .sum::<f64>();
+        1.0 / (1.0 + (-logit).exp())
+    }
+
+    /// Predicts the probabilities of many candidates' features with `predict` in parallel on the current rayon thread
+    /// pool, preserving order.
+    pub fn predict_many(&self, features: &[[Option<f64>; 6]]) -> Vec<f64> {
+        features
+            .par_iter()
+            .map(|features| self.predict(features))
+            .collect()
+    }
+
+    /// The probability that a candidate work is a match for a DMP, from its features, see `score_candidate`.
+    pub fn score(&self, dmp: &ScoringDmp, work: &ScoringWork) -> f64 {
+        self.predict(&candidate_features(dmp, work))
+    }
+
+    /// Scores many candidate works against a DMP with `score` in parallel on the current rayon thread pool,
+    /// preserving order.
+    pub fn score_candidates(&self, dmp: &ScoringDmp, works: &[ScoringWork]) -> Vec<f64> {
+        works.par_iter().map(|work| self.score(dmp, work)).collect()
+    }
+}
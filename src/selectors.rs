@@ -0,0 +1,169 @@
+use rayon::prelude::*;
+use serde_json::Value;
+
+/// One step of a selector path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// A member of an object.
+    Key(String),
+    /// An element of an array, counted from the end when negative.
+    Index(i64),
+    /// Every element of an array, or every member value of an object.
+    Wildcard,
+}
+
+/// A compiled path expression selecting values from a JSON record, in a subset of JSONPath: dotted keys, array
+/// indexes and wildcards, e.g. "primary_location.source.issn_l", "authorships[0].author.orcid" or
+/// "authorships[*].institutions[*].ror". Indexes count from the end when negative, "[-1]" being the last element, and
+/// keys containing dots or brackets can be quoted, as in `["key.with.dots"]`. A leading "$" is optional.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    steps: Vec<Step>,
+}
+
+impl Selector {
+    /// Compiles a selector, returning a description of the problem when it is malformed.
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let rest = match path.strip_prefix('$') {
+            Some(rest) => rest.strip_prefix('.').unwrap_or(rest),
+            None => path,
+        };
+        let mut chars = rest.chars().peekable();
+        let mut steps = Vec::new();
+        // Whether a key is due, at the start and after each dot
+        let mut expect_key = !rest.is_empty() && !rest.starts_with('[');
+        while let Some(&c) = chars.peek() {
+            match c {
+                '.' if expect_key => return Err(format!("empty key in selector {path:?}")),
+                '.' => {
+                    chars.next();
+                    expect_key = true;
+                },
+                '[' => {
+                    chars.next();
+                    let mut inner = String::new();
+                    let quote = chars.next_if(|c| *c == '"' || *c == '\'');
+                    loop {
+                        match (chars.next(), quote) {
+                            (None, _) => {
+                                return Err(format!("unclosed bracket in selector {path:?}"))
+                            },
+                            (Some(c), Some(quote)) if c == quote => {
+                                if chars.next() != Some(']') {
+                                    return Err(format!(
+                                        "expected ] after quoted key in selector {path:?}"
+                                    ));
+                                }
+                                break;
+                            },
+                            (Some(']'), None) => break,
+                            (Some(c), _) => inner.push(c),
+                        }
+                    }
+                    steps.push(match (quote, inner.trim()) {
+                        (Some(_), _) => Step::Key(inner),
+                        (None, "*") => Step::Wildcard,
+                        (None, index) => Step::Index(index.parse().map_err(|_| {
+                            format!("invalid index {index:?} in selector {path:?}")
+                        })?),
+                    });
+                    expect_key = false;
+                },
+                _ if expect_key => {
+                    let mut key = String::new();
+                    while let Some(c) = chars.next_if(|c| *c != '.' && *c != '[') {
+                        key.push(c);
+                    }
+                    steps.push(if key == "*" {
+                        Step::Wildcard
+                    } else {
+                        Step::Key(key)
+                    });
+                    expect_key = false;
+                },
+                _ => return Err(format!("expected . or [ at {c:?} in selector {path:?}")),
+            }
+        }
+        if expect_key {
+            return Err(format!("empty key in selector {path:?}"));
+        }
+        Ok(Self { steps })
+    }
+
+    /// Whether the selector has a wildcard, so that it selects a list of values.
+    pub fn is_multiple(&self) -> bool {
+        self.steps.contains(&Step::Wildcard)
+    }
+
+    /// Selects the value at the selector's path, or None when the path is missing from the record or runs into a
+    /// value of the wrong type. A selector with wildcards selects the list of every value matched, flattened across
+    /// wildcards and empty when nothing matches.
+    pub fn select(&self, record: &Value) -> Option<Value> {
+        let mut matches = Vec::new();
+        collect_matches(record, &self.steps, &mut matches);
+        if self.is_multiple() {
+            Some(Value::Array(matches.into_iter().cloned().collect()))
+        } else {
+            matches.first().map(|value| (*value).clone())
+        }
+    }
+}
+
+/// Adds the values matched by `steps` from `value` to `matches`, in document order.
+fn collect_matches<'a>(value: &'a Value, steps: &[Step], matches: &mut Vec<&'a Value>) {
+    let Some((step, rest)) = steps.split_first() else {
+        matches.push(value);
+        return;
+    };
+    match (step, value) {
+        (Step::Key(key), Value::Object(object)) => {
+            if let Some(value) = object.get(key) {
+                collect_matches(value, rest, matches);
+            }
+        },
+        (Step::Index(index), Value::Array(values)) => {
+            let index = if *index < 0 {
+                values.len().checked_sub(index.unsigned_abs() as usize)
+            } else {
+                usize::try_from(*index).ok()
+            };
+            if let Some(value) = index.and_then(|index| values.get(index)) {
+                collect_matches(value, rest, matches);
+            }
+        },
+        (Step::Wildcard, Value::Array(values)) => {
+            for value in values {
+                collect_matches(value, rest, matches);
+            }
+        },
+        (Step::Wildcard, Value::Object(object)) => {
+            for value in object.values() {
+                collect_matches(value, rest, matches);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Selects the value of each selector from a record, see `Selector::select`.
+pub fn extract_fields(record: &Value, selectors: &[Selector]) -> Vec<Option<Value>> {
+    selectors
+        .iter()
+        .map(|selector| selector.select(record))
+        .collect()
+}
+
+/// Parses raw JSON records and selects the value of each selector from each, in parallel, with an error for each
+/// record that is not valid JSON.
+pub fn extract_fields_many(
+    records: &[Vec<u8>],
+    selectors: &[Selector],
+) -> Vec<serde_json::Result<Vec<Option<Value>>>> {
+    records
+        .par_iter()
+        .map(|bytes| {
+            let record: Value = serde_json::from_slice(bytes)?;
+            Ok(extract_fields(&record, selectors))
+        })
+        .collect()
+}
@@ -0,0 +1,137 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+/// Default number of records processed together by streaming functions that work through their input in chunks.
+pub const DEFAULT_CHUNK_SIZE: usize = 10_000;
+
+/// Number of independently locked shards of the string cache, so that threads normalizing in parallel rarely wait
+/// for each other.
+const CACHE_SHARDS: usize = 16;
+
+/// The process-wide runtime settings changed by `configure`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeSettings {
+    /// Threads of the pool that parallel functions run on when not given a number of threads, or None for rayon's
+    /// global pool, which has a thread per CPU unless `RAYON_NUM_THREADS` is set.
+    pub threads: Option<usize>,
+    pub chunk_size: usize,
+    /// Most normalized strings kept by the string cache, or 0 when it is disabled.
+    pub string_cache: usize,
+}
+
+static THREAD_POOL: RwLock<Option<Arc<rayon::ThreadPool>>> = RwLock::new(None);
+/// The pools built by `sized_thread_pool`, by number of threads.
+static SIZED_THREAD_POOLS: LazyLock<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> =
+    LazyLock::new(Mutex::default);
+static CHUNK_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_CHUNK_SIZE);
+static STRING_CACHE_CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the current settings.
+pub fn settings() -> RuntimeSettings {
+    RuntimeSettings {
+        threads: thread_pool().map(|pool| pool.current_num_threads()),
+        chunk_size: chunk_size(),
+        string_cache: STRING_CACHE_CAPACITY.load(Ordering::Relaxed),
+    }
+}
+
+/// Sets the number of threads of the default pool, with 0 going back to rayon's global pool.
+pub fn set_threads(threads: usize) -> Result<(), rayon::ThreadPoolBuildError> {
+    let pool = match threads {
+        0 => None,
+        n => Some(sized_thread_pool(n)?),
+    };
+    *THREAD_POOL.write().unwrap() = pool;
+    Ok(())
+}
+
+/// A pool of `threads` threads, built on first use and kept for the life of the process, so that functions given a
+/// number of threads don't start new threads on every call.
+pub fn sized_thread_pool(
+    threads: usize,
+) -> Result<Arc<rayon::ThreadPool>, rayon::ThreadPoolBuildError> {
+    let mut pools = SIZED_THREAD_POOLS.lock().unwrap();
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(pool.clone());
+    }
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()?,
+    );
+    pools.insert(threads, pool.clone());
+    Ok(pool)
+}
+
+/// The pool parallel functions run on when not given a number of threads, or None for rayon's global pool.
+pub fn thread_pool() -> Option<Arc<rayon::ThreadPool>> {
+    THREAD_POOL.read().unwrap().clone()
+}
+
+pub fn set_chunk_size(chunk_size: usize) {
+    CHUNK_SIZE.store(chunk_size, Ordering::Relaxed);
+}
+
+/// The number of records streaming functions process together, `DEFAULT_CHUNK_SIZE` unless configured.
+pub fn chunk_size() -> usize {
+    CHUNK_SIZE.load(Ordering::Relaxed)
+}
+
+/// Sets the capacity of the string cache, emptying it, with 0 disabling it.
+pub fn set_string_cache(capacity: usize) {
+    STRING_CACHE_CAPACITY.store(capacity, Ordering::Relaxed);
+    for shard in STRING_CACHE.iter() {
+        shard.lock().unwrap().clear();
+    }
+}
+
+/// The normalizers whose results the string cache holds, each in shards of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalizer {
+    FunderName,
+    InstitutionName,
+}
+
+impl Normalizer {
+    const COUNT: usize = 2;
+}
+
+type CacheShard = Mutex<HashMap<Box<str>, Option<Box<str>>>>;
+
+static STRING_CACHE: LazyLock<Vec<CacheShard>> = LazyLock::new(|| {
+    (0..Normalizer::COUNT * CACHE_SHARDS)
+        .map(|_| Mutex::default())
+        .collect()
+});
+
+/// Normalizes text with `normalize`, through the string cache when it is enabled, so that values repeated across
+/// many records, such as funder and institution names, are normalized once. A full shard of the cache is emptied
+/// rather than evicting entries one by one, which keeps lookups cheap.
+pub fn cached(
+    normalizer: Normalizer,
+    text: &str,
+    normalize: impl FnOnce(&str) -> Option<String>,
+) -> Option<String> {
+    let capacity = STRING_CACHE_CAPACITY.load(Ordering::Relaxed);
+    if capacity == 0 {
+        return normalize(text);
+    }
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    let shard = normalizer as usize * CACHE_SHARDS + hasher.finish() as usize % CACHE_SHARDS;
+    let shard = &STRING_CACHE[shard];
+    if let Some(normalized) = shard.lock().unwrap().get(text) {
+        return normalized.as_deref().map(str::to_string);
+    }
+
+    let normalized = normalize(text);
+    let mut shard = shard.lock().unwrap();
+    if shard.len() >= capacity.div_ceil(CACHE_SHARDS) {
+        shard.clear();
+    }
+    shard.insert(text.into(), normalized.as_deref().map(Box::from));
+    normalized
+}
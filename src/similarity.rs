@@ -0,0 +1,126 @@
+use crate::core::{clean_text, tokenize};
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+
+/// Jaro-Winkler similarity (0–1) of two strings, cleaned with `clean_text`.
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    strsim::jaro_winkler(&clean_text(a), &clean_text(b))
+}
+
+/// Levenshtein similarity (0–1) of two strings, cleaned with `clean_text`: one minus the edit distance divided by the
+/// longer string's length.
+pub fn normalized_levenshtein(a: &str, b: &str) -> f64 {
+    strsim::normalized_levenshtein(&clean_text(a), &clean_text(b))
+}
+
+/// Length of the longest common subsequence of two character sequences.
+fn longest_common_subsequence(a: &[char], b: &[char]) -> usize {
+    let mut row = vec![0; b.len() + 1];
+    for ca in a {
+        let mut diagonal = 0;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Indel similarity (0–1) of two strings, as used by fuzzy matching libraries' "ratio": twice the longest common
+/// subsequence divided by the combined length. Returns 0.0 when either string is empty.
+fn ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * longest_common_subsequence(&a, &b) as f64 / (a.len() + b.len()) as f64
+}
+
+/// Token sort similarity (0–1): the ratio of the two strings' lowercased word tokens, each sorted and joined, so that
+/// word order is ignored.
+pub fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    let sorted = |text: &str| {
+        let mut tokens = tokenize(text);
+        tokens.sort();
+        tokens.join(" ")
+    };
+    ratio(&sorted(a), &sorted(b))
+}
+
+/// Token set similarity (0–1): compares the sorted shared word tokens with each string's sorted tokens, taking the
+/// best ratio, so that word order and extra words in one string are ignored. Returns 1.0 when the tokens of one
+/// string are a subset of the other's and 0.0 when either string has no tokens.
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let tokens_a: BTreeSet<String> = tokenize(a).into_iter().collect();
+    let tokens_b: BTreeSet<String> = tokenize(b).into_iter().collect();
+    if tokens_a.is_empty() || tokens_b.is_empty() {
+        return 0.0;
+    }
+
+    let join = |tokens: Vec<&String>| {
+        tokens
+            .into_iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let intersection = join(tokens_a.intersection(&tokens_b).collect());
+    let only_a = join(tokens_a.difference(&tokens_b).collect());
+    let only_b = join(tokens_b.difference(&tokens_a).collect());
+    if !intersection.is_empty() && (only_a.is_empty() || only_b.is_empty()) {
+        return 1.0;
+    }
+
+    let combined = |rest: &str| {
+        [intersection.as_str(), rest]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let (combined_a, combined_b) = (combined(&only_a), combined(&only_b));
+    ratio(&intersection, &combined_a)
+        .max(ratio(&intersection, &combined_b))
+        .max(ratio(&combined_a, &combined_b))
+}
+
+/// The set of w-shingles of a text's tokens: its runs of `shingle_size` consecutive tokens.
+fn word_shingles(tokens: &[String], shingle_size: usize) -> BTreeSet<&[String]> {
+    tokens.windows(shingle_size).collect()
+}
+
+/// Containment (0–1) of a shorter text in a longer one: the fraction of the short text's w-shingles, runs of
+/// `shingle_size` consecutive lowercased word tokens, that are also shingles of the long text. Unlike symmetric
+/// similarities this isn't lowered by the long text's extra words, so a dataset title scores highly against the
+/// article title it is part of. A short text with fewer tokens than `shingle_size` is a single shingle of all of
+/// them. Returns 0.0 when the short text has no tokens.
+pub fn containment_score(short_text: &str, long_text: &str, shingle_size: usize) -> f64 {
+    let short_tokens = tokenize(short_text);
+    if short_tokens.is_empty() {
+        return 0.0;
+    }
+    let shingle_size = shingle_size.min(short_tokens.len());
+    let short = word_shingles(&short_tokens, shingle_size);
+    let long_tokens = tokenize(long_text);
+    let long = word_shingles(&long_tokens, shingle_size);
+    short.intersection(&long).count() as f64 / short.len() as f64
+}
+
+/// Applies a similarity function to each pair of values from two equal-length columns in parallel on the current
+/// rayon thread pool, giving None where either value is missing.
+pub fn pairwise(
+    similarity: impl Fn(&str, &str) -> f64 + Sync,
+    a: &[Option<String>],
+    b: &[Option<String>],
+) -> Vec<Option<f64>> {
+    a.par_iter()
+        .zip(b)
+        .map(|(a, b)| Some(similarity(a.as_deref()?, b.as_deref()?)))
+        .collect()
+}
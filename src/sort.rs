@@ -0,0 +1,275 @@
+use crate::core::open_text;
+use crate::selectors::Selector;
+use rayon::prelude::*;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Default bytes of records sorted in memory at a time by `external_sort`.
+pub const DEFAULT_MAX_CHUNK_BYTES: usize = 256 * 1024 * 1024;
+
+/// Most sorted chunks merged at a time, so that the number of open files stays bounded however large the input.
+const MAX_MERGE_WIDTH: usize = 64;
+
+/// Counts of records handled by `external_sort`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExternalSortStats {
+    /// Records written to the output.
+    pub written: usize,
+    /// Records dropped as duplicates of an earlier record's key.
+    pub duplicates: usize,
+}
+
+/// A sort key: the selected value as a string, with other JSON values as their JSON text, or None when the record
+/// has no value at the selector's path. Records without a key sort first.
+type SortKey = Option<String>;
+
+fn sort_key(record: &Value, key: &Selector) -> SortKey {
+    match key.select(record)? {
+        Value::Null => None,
+        Value::String(text) => Some(text),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Temporary files of sorted chunks, removed when dropped so that a failed sort doesn't leave them behind.
+struct ChunkFiles {
+    dir: PathBuf,
+    prefix: String,
+    paths: Vec<PathBuf>,
+}
+
+impl ChunkFiles {
+    fn new(dir: &Path, output: &Path) -> Self {
+        let name = output.file_name().unwrap_or_default().to_string_lossy();
+        Self {
+            dir: dir.to_path_buf(),
+            prefix: format!(".{name}.sort-{}", std::process::id()),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Creates the next chunk file.
+    fn create(&mut self) -> io::Result<(PathBuf, BufWriter<File>)> {
+        let path = self
+            .dir
+            .join(format!("{}-{}.tmp", self.prefix, self.paths.len()));
+        let writer = BufWriter::new(File::create(&path)?);
+        self.paths.push(path.clone());
+        Ok((path, writer))
+    }
+}
+
+impl Drop for ChunkFiles {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Writes a record to a chunk file, as its key in JSON, a tab and the record's line, so that it is merged without
+/// parsing the record again. JSON strings escape tabs, so the first tab always ends the key.
+fn write_chunk_line(writer: &mut impl Write, key: &SortKey, line: &[u8]) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, key)?;
+    writer.write_all(b"\t")?;
+    writer.write_all(line)?;
+    writer.write_all(b"\n")
+}
+
+/// Reads the next record of a chunk file written by `write_chunk_line`.
+fn read_chunk_line(reader: &mut impl BufRead) -> io::Result<Option<(SortKey, Vec<u8>)>> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+    line.pop();
+    let tab = line
+        .iter()
+        .position(|b| *b == b'\t')
+        .ok_or_else(|| io::Error::other("corrupt sort chunk"))?;
+    let key = serde_json::from_slice(&line[..tab])?;
+    Ok(Some((key, line.split_off(tab + 1))))
+}
+
+/// Writes records in order, dropping those whose key equals the previous record's when deduping.
+struct SortedWriter<W: Write> {
+    writer: W,
+    dedupe: bool,
+    previous: Option<SortKey>,
+    stats: ExternalSortStats,
+    /// Whether records are written with their keys, to an intermediate chunk rather than the output.
+    with_keys: bool,
+}
+
+impl<W: Write> SortedWriter<W> {
+    fn write(&mut self, key: SortKey, line: &[u8]) -> io::Result<()> {
+        // Records without a key are never duplicates of each other
+        if self.dedupe && key.is_some() && self.previous.as_ref() == Some(&key) {
+            self.stats.duplicates += 1;
+            return Ok(());
+        }
+        if self.with_keys {
+            write_chunk_line(&mut self.writer, &key, line)?;
+        } else {
+            self.writer.write_all(line)?;
+            self.writer.write_all(b"\n")?;
+        }
+        self.stats.written += 1;
+        self.previous = Some(key);
+        Ok(())
+    }
+}
+
+/// Merges sorted chunk files into a writer, breaking ties between equal keys by chunk order so that the merge is
+/// stable.
+fn merge_chunks<W: Write>(paths: &[PathBuf], out: &mut SortedWriter<W>) -> io::Result<()> {
+    let mut readers = paths
+        .iter()
+        .map(|path| File::open(path).map(BufReader::new))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut heap = BinaryHeap::new();
+    for (chunk, reader) in readers.iter_mut().enumerate() {
+        if let Some((key, line)) = read_chunk_line(reader)? {
+            heap.push(Reverse((key, chunk, line)));
+        }
+    }
+    while let Some(Reverse((key, chunk, line))) = heap.pop() {
+        out.write(key, &line)?;
+        if let Some((key, line)) = read_chunk_line(&mut readers[chunk])? {
+            heap.push(Reverse((key, chunk, line)));
+        }
+    }
+    Ok(())
+}
+
+/// Reads the next chunk of at most `max_chunk_bytes` of records, with their line numbers, skipping blank lines. A
+/// single record larger than the limit is a chunk of its own.
+fn read_chunk(
+    lines: &mut impl Iterator<Item = (usize, io::Result<Vec<u8>>)>,
+    max_chunk_bytes: usize,
+) -> io::Result<Vec<(usize, Vec<u8>)>> {
+    let mut chunk = Vec::new();
+    let mut bytes = 0;
+    for (line_number, line) in lines.by_ref() {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        bytes += line.len();
+        chunk.push((line_number, line));
+        if bytes >= max_chunk_bytes {
+            break;
+        }
+    }
+    Ok(chunk)
+}
+
+/// Parses the records of a chunk and sorts them by key in parallel on the current rayon thread pool, keeping
+/// records with equal keys in input order.
+fn sort_chunk(chunk: Vec<(usize, Vec<u8>)>, key: &Selector) -> io::Result<Vec<(SortKey, Vec<u8>)>> {
+    let mut records = chunk
+        .into_par_iter()
+        .map(|(line_number, line)| {
+            let record: Value = serde_json::from_slice(&line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid json on line {}: {e}", line_number + 1),
+                )
+            })?;
+            Ok((sort_key(&record, key), line))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    records.par_sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(records)
+}
+
+/// Sorts a plain or gzip-compressed JSONL file by the value at `key` into a plain JSONL file, with memory bounded by
+/// `max_chunk_bytes` however large the input: the input is sorted in chunks of about that many bytes, which are
+/// written to temporary files in `temp_dir`, by default the output's directory, and merged. Keys are compared as
+/// strings, with other JSON values as their JSON text, and records without a key come first. The sort is stable, so
+/// records with equal keys keep their input order, and with `dedupe` only the first record of each key is kept.
+/// Records are written exactly as read, without reformatting, and blank lines are skipped.
+pub fn external_sort(
+    input: &Path,
+    output: &Path,
+    key: &Selector,
+    dedupe: bool,
+    max_chunk_bytes: usize,
+    temp_dir: Option<&Path>,
+) -> io::Result<ExternalSortStats> {
+    let mut lines = open_text(input)?.split(b'\n').enumerate();
+    let temp_dir = match temp_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => match output.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        },
+    };
+    let mut chunk_files = ChunkFiles::new(&temp_dir, output);
+
+    let mut first = Some(sort_chunk(read_chunk(&mut lines, max_chunk_bytes)?, key)?);
+    let mut chunks = Vec::new();
+    loop {
+        let chunk = read_chunk(&mut lines, max_chunk_bytes)?;
+        if chunk.is_empty() {
+            break;
+        }
+        // Spill the previous chunk, now that there is more than one
+        for records in first.take().into_iter().chain([sort_chunk(chunk, key)?]) {
+            let (path, mut writer) = chunk_files.create()?;
+            for (key, line) in &records {
+                write_chunk_line(&mut writer, key, line)?;
+            }
+            writer.flush()?;
+            chunks.push(path);
+        }
+    }
+
+    let mut out = SortedWriter {
+        writer: BufWriter::new(File::create(output)?),
+        dedupe,
+        previous: None,
+        stats: ExternalSortStats::default(),
+        with_keys: false,
+    };
+    if let Some(records) = first {
+        for (key, line) in records {
+            out.write(key, &line)?;
+        }
+    } else {
+        // Merge in passes of at most `MAX_MERGE_WIDTH` chunks until one pass can write the output
+        let mut duplicates = 0;
+        while chunks.len() > MAX_MERGE_WIDTH {
+            let mut merged = Vec::new();
+            for group in chunks.chunks(MAX_MERGE_WIDTH) {
+                let (path, writer) = chunk_files.create()?;
+                let mut pass = SortedWriter {
+                    writer,
+                    dedupe,
+                    previous: None,
+                    stats: ExternalSortStats::default(),
+                    with_keys: true,
+                };
+                merge_chunks(group, &mut pass)?;
+                pass.writer.flush()?;
+                duplicates += pass.stats.duplicates;
+                merged.push(path);
+            }
+            for path in chunks {
+                let _ = fs::remove_file(path);
+            }
+            chunks = merged;
+        }
+        merge_chunks(&chunks, &mut out)?;
+        out.stats.duplicates += duplicates;
+    }
+    out.writer.flush()?;
+    Ok(out.stats)
+}
@@ -0,0 +1,126 @@
+use crate::core::{read_jsonl, tokenize_with_options, TokenizerOptions};
+use crate::settings;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::Path;
+
+/// Document frequencies of the terms of a corpus, for weighting terms by how rare they are. Built incrementally, so
+/// that a corpus larger than memory can be streamed through it in batches.
+#[derive(Debug, Clone, Default)]
+pub struct IdfTable {
+    options: TokenizerOptions,
+    num_docs: u64,
+    doc_freqs: HashMap<String, u64>,
+}
+
+impl IdfTable {
+    /// An empty table whose documents and compared texts are tokenized with `options`.
+    pub fn new(options: TokenizerOptions) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Number of documents added to the table.
+    pub fn num_docs(&self) -> u64 {
+        self.num_docs
+    }
+
+    /// Number of distinct terms in the table's documents.
+    pub fn num_terms(&self) -> usize {
+        self.doc_freqs.len()
+    }
+
+    /// Adds documents to the table, tokenizing them in parallel on the current rayon thread pool. Missing texts count
+    /// as empty documents.
+    pub fn update(&mut self, texts: &[Option<String>]) {
+        let doc_terms: Vec<HashSet<String>> = texts
+            .par_iter()
+            .map(|text| {
+                text.as_deref()
+                    .map(|text| tokenize_with_options(text, &self.options))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect()
+            })
+            .collect();
+        self.num_docs += texts.len() as u64;
+        for terms in doc_terms {
+            for term in terms {
+                *self.doc_freqs.entry(term).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Adds a document for each record of a plain or gzip-compressed JSONL file, e.g. of works, streaming it in chunks.
+    /// A document is the text of the record's `fields` joined by a space, skipping fields that are missing or aren't
+    /// strings. Returns the number of documents added.
+    pub fn update_from_jsonl(&mut self, path: &Path, fields: &[String]) -> io::Result<usize> {
+        let mut records = read_jsonl(path, Some(fields.to_vec()))?;
+        let mut added = 0;
+        loop {
+            let chunk = records
+                .by_ref()
+                .take(settings::chunk_size())
+                .map(|record| {
+                    let record = record?;
+                    let parts: Vec<&str> = fields
+                        .iter()
+                        .filter_map(|field| record.get(field)?.as_str())
+                        .collect();
+                    Ok((!parts.is_empty()).then(|| parts.join(" ")))
+                })
+                .collect::<io::Result<Vec<Option<String>>>>()?;
+            if chunk.is_empty() {
+                return Ok(added);
+            }
+            added += chunk.len();
+            self.update(&chunk);
+        }
+    }
+
+    /// The smoothed inverse document frequency of a term, `ln((1 + N) / (1 + df)) + 1` as in scikit-learn, so that
+    /// terms in every document still count a little and terms in none count the most.
+    pub fn idf(&self, term: &str) -> f64 {
+        let df = self.doc_freqs.get(term).copied().unwrap_or(0);
+        ((1.0 + self.num_docs as f64) / (1.0 + df as f64)).ln() + 1.0
+    }
+
+    /// The TF-IDF vector of a text, its term counts weighted by their IDF.
+    fn vector(&self, text: &str) -> HashMap<String, f64> {
+        let mut counts: HashMap<String, f64> = HashMap::new();
+        for token in tokenize_with_options(text, &self.options) {
+            *counts.entry(token).or_insert(0.0) += 1.0;
+        }
+        for (term, weight) in counts.iter_mut() {
+            *weight *= self.idf(term);
+        }
+        counts
+    }
+}
+
+/// Cosine similarity (0–1) of two texts' TF-IDF vectors, e.g. a DMP's narrative and a work's abstract, tokenized with
+/// the options of `idf_table` and weighted by its IDF so that shared rare terms count more than shared common ones.
+/// Returns 0.0 when either text has no tokens.
+pub fn tfidf_cosine(a: &str, b: &str, idf_table: &IdfTable) -> f64 {
+    let a = idf_table.vector(a);
+    let b = idf_table.vector(b);
+    let (short, long) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    let dot: f64 = short
+        .iter()
+        .filter_map(|(term, weight)| Some(weight * long.get(term)?))
+        .sum();
+    let norm = |vector: &HashMap<String, f64>| vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    let norms = norm(&a) * norm(&b);
+    if norms > 0.0 {
+        (dot / norms).min(1.0)
+    } else {
+        0.0
+    }
+}
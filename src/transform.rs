@@ -0,0 +1,1260 @@
+use crate::core::{
+    date_from_parts, join_abstract_words, normalize_doi, normalize_issn, normalize_orcid,
+    normalize_ror, parse_date, parse_name, strip_markup, strip_markup_with_options,
+    words_by_position, MarkupOptions, MathMl, ParseError, ParsedDate, DEFAULT_NULL_RULES,
+};
+use log::warn;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// A work in our normalized schema, as produced by the record transformers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Work {
+    /// The source's own identifier for the record, e.g. an OpenAlex work ID.
+    pub id: Option<String>,
+    pub doi: String,
+    pub title: Option<String>,
+    #[serde(rename = "abstract")]
+    pub abstract_text: Option<String>,
+    pub work_type: Option<String>,
+    pub authors: Vec<Author>,
+    /// People who contributed to the work without being credited as its authors, e.g. DataCite data curators.
+    pub contributors: Vec<Author>,
+    pub institutions: Vec<Institution>,
+    pub issns: Vec<String>,
+    pub funders: Vec<Funder>,
+    pub relations: Vec<Relation>,
+    /// Publication date as an ISO 8601 date, month ("2021-03") or year ("2021").
+    pub publication_date: Option<String>,
+    /// Date-time at which the record was last updated by its source.
+    pub updated_date: Option<String>,
+}
+
+/// An author of a work, with their name parsed by `parse_name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Author {
+    pub first_initial: Option<String>,
+    pub given_name: Option<String>,
+    pub middle_initials: Option<String>,
+    pub middle_names: Option<String>,
+    pub surname: Option<String>,
+    pub full: Option<String>,
+    pub suffix: Option<String>,
+    pub title: Option<String>,
+    pub orcid: Option<String>,
+}
+
+impl Author {
+    /// Parses an author's name parts and normalizes their ORCID iD.
+    fn new(
+        given: Option<&str>,
+        surname: Option<&str>,
+        full: Option<&str>,
+        orcid: Option<&str>,
+    ) -> Self {
+        let parsed = parse_name(given, surname, full, false);
+        Self {
+            first_initial: parsed.first_initial,
+            given_name: parsed.given_name,
+            middle_initials: parsed.middle_initials,
+            middle_names: parsed.middle_names,
+            surname: parsed.surname,
+            full: parsed.full,
+            suffix: parsed.suffix,
+            title: parsed.title,
+            orcid: normalize_orcid(orcid),
+        }
+    }
+}
+
+/// An institution the authors of a work are affiliated with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Institution {
+    pub name: Option<String>,
+    pub ror: Option<String>,
+}
+
+impl Institution {
+    fn new(name: Option<&str>, ror: Option<&str>) -> Option<Self> {
+        let institution = Self {
+            name: name.map(str::to_string),
+            ror: normalize_ror(ror),
+        };
+        (institution.name.is_some() || institution.ror.is_some()).then_some(institution)
+    }
+}
+
+/// A funder of a work with one of its awards, or without an award when the source gives none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Funder {
+    pub name: Option<String>,
+    pub funder_doi: Option<String>,
+    pub award: Option<String>,
+}
+
+/// An identifier of a related work, e.g. the article a dataset supplements.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relation {
+    /// How the work relates to the identified work, e.g. "IsSupplementTo".
+    pub relation_type: Option<String>,
+    pub related_identifier: Option<String>,
+    /// The identifier type, "DOI" for any identifier that could be normalized to a DOI.
+    pub related_identifier_type: Option<String>,
+}
+
+impl Relation {
+    /// Normalizes DOIs, including DOI URLs, and clears a DOI type on identifiers that are not valid DOIs.
+    fn new(
+        relation_type: Option<&str>,
+        identifier: Option<&str>,
+        identifier_type: Option<&str>,
+    ) -> Option<Self> {
+        let (related_identifier, related_identifier_type) = match normalize_doi(identifier) {
+            Some(doi) => (Some(doi), Some("DOI".to_string())),
+            None => (
+                identifier.map(str::to_string),
+                identifier_type
+                    .filter(|t| !t.eq_ignore_ascii_case("doi"))
+                    .map(str::to_string),
+            ),
+        };
+        related_identifier.as_ref()?;
+        Some(Self {
+            relation_type: relation_type.map(str::to_string),
+            related_identifier,
+            related_identifier_type,
+        })
+    }
+}
+
+/// Strips JATS markup from an abstract, dropping section titles and cross-reference labels and decoding HTML entities.
+fn strip_jats(text: &str) -> Option<String> {
+    let options = MarkupOptions {
+        decode_entities: true,
+        jats: true,
+        mathml: MathMl::Keep,
+        null_rules: None,
+    };
+    let stripped = strip_markup_with_options(Some(text), None, &options)?;
+    let collapsed = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!collapsed.is_empty()).then_some(collapsed)
+}
+
+/// Formats Crossref `date-parts`, e.g. `[[2021, 3, 5]]`, as an ISO 8601 date, month or year.
+fn format_date_parts(date: &Value) -> Option<String> {
+    let parts: Vec<u64> = date
+        .get("date-parts")?
+        .get(0)?
+        .as_array()?
+        .iter()
+        .map_while(Value::as_u64)
+        .collect();
+    match parts.as_slice() {
+        [year, month, day, ..] => Some(format!("{year:04}-{month:02}-{day:02}")),
+        [year, month] => Some(format!("{year:04}-{month:02}")),
+        [year] => Some(format!("{year:04}")),
+        [] => None,
+    }
+}
+
+/// Returns a JSON value as a trimmed non-empty string.
+fn as_text(value: &Value) -> Option<&str> {
+    value.as_str().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Iterates over the elements of a JSON array field, yielding nothing when the field is missing or not an array.
+fn array_field<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+/// Appends an item unless it is already present, keeping first-occurrence order.
+fn push_unique<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
+
+/// Transforms a raw Crossref work message into our normalized work schema: the DOI is normalized, the type is mapped
+/// with `normalize_work_type`, the first non-empty title is kept, the abstract is stripped of JATS markup, authors are
+/// parsed with `parse_name` and their affiliations collected as institutions, ISSNs are validated and deduplicated,
+/// each funder award becomes a funder entry, as does each funder without awards, relations are flattened and the
+/// publication date is taken from `issued`, falling back to `published`.
+/// Returns None when the message has no valid DOI.
+pub fn transform_crossref_work(bytes: &[u8]) -> serde_json::Result<Option<Work>> {
+    let message: Value = serde_json::from_slice(bytes)?;
+
+    let Some(doi) = normalize_doi(message.get("DOI").and_then(Value::as_str)) else {
+        return Ok(None);
+    };
+
+    let title = array_field(&message, "title").find_map(|title| strip_markup(title.as_str(), None));
+
+    let abstract_text = message
+        .get("abstract")
+        .and_then(Value::as_str)
+        .and_then(strip_jats);
+
+    let mut authors = Vec::new();
+    let mut institutions = Vec::new();
+    for author in array_field(&message, "author") {
+        let field = |key| author.get(key).and_then(as_text);
+        let parsed = Author::new(
+            field("given"),
+            field("family"),
+            field("name"),
+            field("ORCID"),
+        );
+        if parsed.full.is_some() {
+            push_unique(&mut authors, parsed);
+        }
+
+        for affiliation in array_field(author, "affiliation") {
+            let ror = array_field(affiliation, "id")
+                .filter(|id| id.get("id-type").and_then(Value::as_str) == Some("ROR"))
+                .find_map(|id| id.get("id").and_then(Value::as_str));
+            if let Some(institution) =
+                Institution::new(affiliation.get("name").and_then(as_text), ror)
+            {
+                push_unique(&mut institutions, institution);
+            }
+        }
+    }
+
+    let mut issns = Vec::new();
+    let typed = array_field(&message, "issn-type").filter_map(|issn| issn.get("value"));
+    for issn in array_field(&message, "ISSN").chain(typed) {
+        if let Some(issn) = normalize_issn(issn.as_str()) {
+            push_unique(&mut issns, issn);
+        }
+    }
+
+    let mut funders = Vec::new();
+    for funder in array_field(&message, "funder") {
+        let name = funder.get("name").and_then(as_text).map(str::to_string);
+        let funder_doi = normalize_doi(funder.get("DOI").and_then(Value::as_str));
+        let awards: Vec<String> = array_field(funder, "award")
+            .filter_map(Value::as_str)
+            .flat_map(|award| award.split(','))
+            .map(str::trim)
+            .filter(|award| !award.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        if awards.is_empty() {
+            // A funder is evidence for matching even without an award
+            if name.is_some() || funder_doi.is_some() {
+                funders.push(Funder {
+                    name,
+                    funder_doi,
+                    award: None,
+                });
+            }
+            continue;
+        }
+        for award in awards {
+            funders.push(Funder {
+                name: name.clone(),
+                funder_doi: funder_doi.clone(),
+                award: Some(award),
+            });
+        }
+    }
+
+    let publication_date = ["issued", "published"]
+        .iter()
+        .find_map(|key| format_date_parts(message.get(key)?));
+
+    let updated_date = message
+        .get("deposited")
+        .and_then(|deposited| deposited.get("date-time"))
+        .and_then(as_text)
+        .map(str::to_string);
+
+    let mut relations = Vec::new();
+    if let Some(relation) = message.get("relation").and_then(Value::as_object) {
+        for (relation_type, related) in relation {
+            for related in related.as_array().into_iter().flatten() {
+                let field = |key| related.get(key).and_then(as_text);
+                if let Some(relation) =
+                    Relation::new(Some(relation_type), field("id"), field("id-type"))
+                {
+                    push_unique(&mut relations, relation);
+                }
+            }
+        }
+    }
+
+    let work_type = message.get("type").and_then(as_text).map(|raw_type| {
+        normalize_work_type(WorkSource::Crossref, raw_type)
+            .as_str()
+            .to_string()
+    });
+
+    Ok(Some(Work {
+        id: None,
+        doi,
+        title,
+        abstract_text,
+        work_type,
+        authors,
+        contributors: Vec::new(),
+        institutions,
+        issns,
+        funders,
+        relations,
+        publication_date,
+        updated_date,
+    }))
+}
+
+/// Placeholder abstracts found in OpenAlex, treated as missing.
+static OPENALEX_NO_ABSTRACT: LazyLock<Vec<String>> = LazyLock::new(|| {
+    [
+        "a",
+        ":unav",
+        "No abstract.",
+        "Abstract",
+        "abstract",
+        "peer reviewed",
+        "Peer Reviewed",
+        "No abstract available.",
+        "No description provided.",
+        "No abstract available",
+        "This article has no abstract.",
+        "Abstract not Available.",
+        "status: Published",
+        "Version of Record",
+        "[TODO] Add abstract here.",
+        "No abstract",
+        "No Abstract.",
+        "No abstract is available for this article.",
+        "Cover title.",
+        "(Abstract to follow)",
+        "No contiene resumen",
+        "not provided.",
+        "No abstract received.",
+        "n/a",
+        "N/A",
+        ".",
+        "-",
+    ]
+    .map(str::to_string)
+    .to_vec()
+});
+
+/// Strips the URL prefix from an identifier, e.g. "https://openalex.org/W123" -> "W123".
+fn strip_url_prefix(text: &str) -> Option<String> {
+    let text = text.trim();
+    let id = match text.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or(rest, |(_, path)| path),
+        None => text,
+    };
+    let id = id.trim_matches('/');
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Reconstructs an OpenAlex abstract from its inverted index, treating placeholder abstracts as missing.
+fn revert_openalex_abstract(index: Value) -> serde_json::Result<Option<String>> {
+    if index.is_null() {
+        return Ok(None);
+    }
+    let data = serde_json::from_value::<HashMap<String, Vec<u32>>>(index)?;
+    Ok(join_abstract_words(
+        words_by_position(data),
+        Some(&OPENALEX_NO_ABSTRACT),
+    ))
+}
+
+/// Transforms a raw OpenAlex work into our normalized work schema in one pass: the abstract is reverted from its
+/// inverted index, authorships are flattened into deduplicated authors (parsed with `parse_name`) and institutions,
+/// the work and ROR IDs are stripped of their URL prefixes, the type is mapped with `normalize_work_type`, the ISSNs
+/// are those of the primary location's source, and each award becomes a funder entry, as does each funder without
+/// awards. Returns None when the work has no valid DOI, or is an xpac work and `include_xpac` is false.
+pub fn transform_openalex_work(
+    bytes: &[u8],
+    include_xpac: bool,
+) -> serde_json::Result<Option<Work>> {
+    let mut field_errors = Vec::new();
+    let work = openalex_work(bytes, include_xpac, &mut field_errors)?;
+    for (field, message) in field_errors {
+        warn!("transform_openalex_work: invalid {field}: {message}");
+    }
+    Ok(work)
+}
+
+/// The fields of a record that couldn't be parsed, with their error messages.
+type FieldErrors = Vec<(&'static str, String)>;
+
+/// Transforms a raw OpenAlex work like `transform_openalex_work`, adding the fields that couldn't be parsed, and so
+/// are missing from the work, to `field_errors` with their error messages.
+fn openalex_work(
+    bytes: &[u8],
+    include_xpac: bool,
+    field_errors: &mut FieldErrors,
+) -> serde_json::Result<Option<Work>> {
+    let mut work: Value = serde_json::from_slice(bytes)?;
+
+    let Some(doi) = normalize_doi(work.get("doi").and_then(Value::as_str)) else {
+        return Ok(None);
+    };
+    let is_xpac = work
+        .get("is_xpac")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if is_xpac && !include_xpac {
+        return Ok(None);
+    }
+
+    let id = work
+        .get("id")
+        .and_then(Value::as_str)
+        .and_then(strip_url_prefix);
+    let title = strip_markup(work.get("title").and_then(Value::as_str), None);
+    let abstract_text = work
+        .get_mut("abstract_inverted_index")
+        .map(Value::take)
+        .and_then(|index| {
+            revert_openalex_abstract(index).unwrap_or_else(|e| {
+                field_errors.push(("abstract_inverted_index", e.to_string()));
+                None
+            })
+        });
+    let work_type = work.get("type").and_then(as_text).map(|raw_type| {
+        normalize_work_type(WorkSource::OpenAlex, raw_type)
+            .as_str()
+            .to_string()
+    });
+
+    let mut authors = Vec::new();
+    let mut institutions = Vec::new();
+    for authorship in array_field(&work, "authorships") {
+        if let Some(author) = authorship.get("author") {
+            let field = |key| author.get(key).and_then(as_text);
+            let parsed = Author::new(None, None, field("display_name"), field("orcid"));
+            if parsed.full.is_some() || parsed.orcid.is_some() {
+                push_unique(&mut authors, parsed);
+            }
+        }
+
+        for institution in array_field(authorship, "institutions") {
+            let field = |key| institution.get(key).and_then(as_text);
+            if let Some(institution) = Institution::new(field("display_name"), field("ror")) {
+                push_unique(&mut institutions, institution);
+            }
+        }
+    }
+
+    let mut funders: Vec<Funder> = array_field(&work, "awards")
+        .filter_map(|award| {
+            let field = |key| award.get(key).and_then(as_text);
+            let funder = Funder {
+                name: field("funder_display_name").map(str::to_string),
+                funder_doi: None,
+                award: field("funder_award_id").map(str::to_string),
+            };
+            (funder.name.is_some() || funder.award.is_some()).then_some(funder)
+        })
+        .collect();
+    // Funders without awards are only listed in `funders`
+    for funder in array_field(&work, "funders") {
+        let Some(name) = funder.get("display_name").and_then(as_text) else {
+            continue;
+        };
+        if !funders.iter().any(|f| f.name.as_deref() == Some(name)) {
+            funders.push(Funder {
+                name: Some(name.to_string()),
+                funder_doi: None,
+                award: None,
+            });
+        }
+    }
+
+    // The journal ISSNs of the primary location, its linking ISSN first
+    let mut issns = Vec::new();
+    if let Some(source) = work.pointer("/primary_location/source") {
+        for issn in source
+            .get("issn_l")
+            .into_iter()
+            .chain(array_field(source, "issn"))
+        {
+            if let Some(issn) = normalize_issn(issn.as_str()) {
+                push_unique(&mut issns, issn);
+            }
+        }
+    }
+
+    let publication_date = work
+        .get("publication_date")
+        .and_then(as_text)
+        .map(str::to_string);
+    let updated_date = work
+        .get("updated_date")
+        .and_then(as_text)
+        .map(str::to_string);
+
+    Ok(Some(Work {
+        id,
+        doi,
+        title,
+        abstract_text,
+        work_type,
+        authors,
+        contributors: Vec::new(),
+        institutions,
+        issns,
+        funders,
+        relations: Vec::new(),
+        publication_date,
+        updated_date,
+    }))
+}
+
+/// Placeholder descriptions found in DataCite, treated as missing.
+static DATACITE_NO_ABSTRACT: LazyLock<Vec<String>> =
+    LazyLock::new(|| vec![":unav".to_string(), "Cover title.".to_string()]);
+
+/// Our normalized work types, a superset of the Crossref, DataCite and OpenAlex type vocabularies; see the
+/// datacite_index.work_types model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkType {
+    Article,
+    AudioVisual,
+    Book,
+    BookChapter,
+    Dataset,
+    Dissertation,
+    Editorial,
+    Erratum,
+    Event,
+    Grant,
+    Image,
+    InteractiveResource,
+    Letter,
+    Other,
+    OutputManagementPlan,
+    Paratext,
+    PeerReview,
+    PhysicalObject,
+    Preprint,
+    ReferenceEntry,
+    Report,
+    Retraction,
+    Review,
+    Software,
+    Sound,
+    Standard,
+    SupplementaryMaterials,
+}
+
+impl WorkType {
+    pub const ALL: [WorkType; 27] = [
+        WorkType::Article,
+        WorkType::AudioVisual,
+        WorkType::Book,
+        WorkType::BookChapter,
+        WorkType::Dataset,
+        WorkType::Dissertation,
+        WorkType::Editorial,
+        WorkType::Erratum,
+        WorkType::Event,
+        WorkType::Grant,
+        WorkType::Image,
+        WorkType::InteractiveResource,
+        WorkType::Letter,
+        WorkType::Other,
+        WorkType::OutputManagementPlan,
+        WorkType::Paratext,
+        WorkType::PeerReview,
+        WorkType::PhysicalObject,
+        WorkType::Preprint,
+        WorkType::ReferenceEntry,
+        WorkType::Report,
+        WorkType::Retraction,
+        WorkType::Review,
+        WorkType::Software,
+        WorkType::Sound,
+        WorkType::Standard,
+        WorkType::SupplementaryMaterials,
+    ];
+
+    /// The work type's name in upper snake case, e.g. "BOOK_CHAPTER".
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WorkType::Article => "ARTICLE",
+            WorkType::AudioVisual => "AUDIO_VISUAL",
+            WorkType::Book => "BOOK",
+            WorkType::BookChapter => "BOOK_CHAPTER",
+            WorkType::Dataset => "DATASET",
+            WorkType::Dissertation => "DISSERTATION",
+            WorkType::Editorial => "EDITORIAL",
+            WorkType::Erratum => "ERRATUM",
+            WorkType::Event => "EVENT",
+            WorkType::Grant => "GRANT",
+            WorkType::Image => "IMAGE",
+            WorkType::InteractiveResource => "INTERACTIVE_RESOURCE",
+            WorkType::Letter => "LETTER",
+            WorkType::Other => "OTHER",
+            WorkType::OutputManagementPlan => "OUTPUT_MANAGEMENT_PLAN",
+            WorkType::Paratext => "PARATEXT",
+            WorkType::PeerReview => "PEER_REVIEW",
+            WorkType::PhysicalObject => "PHYSICAL_OBJECT",
+            WorkType::Preprint => "PREPRINT",
+            WorkType::ReferenceEntry => "REFERENCE_ENTRY",
+            WorkType::Report => "REPORT",
+            WorkType::Retraction => "RETRACTION",
+            WorkType::Review => "REVIEW",
+            WorkType::Software => "SOFTWARE",
+            WorkType::Sound => "SOUND",
+            WorkType::Standard => "STANDARD",
+            WorkType::SupplementaryMaterials => "SUPPLEMENTARY_MATERIALS",
+        }
+    }
+}
+
+/// A source of work metadata, each with its own type vocabulary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkSource {
+    Crossref,
+    DataCite,
+    OpenAlex,
+}
+
+impl WorkSource {
+    /// Looks up a source by its name, e.g. "datacite", ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "crossref" => Some(WorkSource::Crossref),
+            "datacite" => Some(WorkSource::DataCite),
+            "openalex" => Some(WorkSource::OpenAlex),
+            _ => None,
+        }
+    }
+}
+
+/// Maps a Crossref type, e.g. "journal-article". Container types such as journals and proceedings map to `Other`,
+/// as OpenAlex does.
+fn crossref_work_type(raw_type: &str) -> WorkType {
+    match raw_type {
+        "journal-article" | "proceedings-article" => WorkType::Article,
+        "book" | "book-series" | "book-set" | "edited-book" | "monograph" | "reference-book" => {
+            WorkType::Book
+        },
+        "book-chapter" | "book-part" | "book-section" | "book-track" => WorkType::BookChapter,
+        "database" | "dataset" => WorkType::Dataset,
+        "dissertation" => WorkType::Dissertation,
+        "grant" => WorkType::Grant,
+        "peer-review" => WorkType::PeerReview,
+        "posted-content" => WorkType::Preprint,
+        "reference-entry" => WorkType::ReferenceEntry,
+        "report" | "report-component" => WorkType::Report,
+        "standard" => WorkType::Standard,
+        _ => WorkType::Other,
+    }
+}
+
+/// Maps a DataCite resourceTypeGeneral, e.g. "JournalArticle", compared lowercased. Similar types are merged and
+/// article-like types map to `Article`, as in the datacite_index.work_types model.
+fn datacite_work_type(raw_type: &str) -> WorkType {
+    match raw_type {
+        "audiovisual" | "film" => WorkType::AudioVisual,
+        "book" => WorkType::Book,
+        "bookchapter" => WorkType::BookChapter,
+        "computationalnotebook" | "software" => WorkType::Software,
+        "conferencepaper" | "datapaper" | "journalarticle" | "text" => WorkType::Article,
+        "dataset" | "model" => WorkType::Dataset,
+        "dissertation" => WorkType::Dissertation,
+        "event" => WorkType::Event,
+        "image" => WorkType::Image,
+        "instrument" | "physicalobject" => WorkType::PhysicalObject,
+        "interactiveresource" => WorkType::InteractiveResource,
+        "outputmanagementplan" => WorkType::OutputManagementPlan,
+        "peerreview" => WorkType::PeerReview,
+        "preprint" => WorkType::Preprint,
+        "report" => WorkType::Report,
+        "sound" => WorkType::Sound,
+        "standard" => WorkType::Standard,
+        _ => WorkType::Other,
+    }
+}
+
+/// Maps an OpenAlex type, e.g. "book-chapter".
+fn openalex_work_type(raw_type: &str) -> WorkType {
+    match raw_type {
+        "article" => WorkType::Article,
+        "book" => WorkType::Book,
+        "book-chapter" => WorkType::BookChapter,
+        "dataset" => WorkType::Dataset,
+        "dissertation" => WorkType::Dissertation,
+        "editorial" => WorkType::Editorial,
+        "erratum" => WorkType::Erratum,
+        "grant" => WorkType::Grant,
+        "letter" => WorkType::Letter,
+        "paratext" => WorkType::Paratext,
+        "peer-review" => WorkType::PeerReview,
+        "preprint" => WorkType::Preprint,
+        "reference-entry" => WorkType::ReferenceEntry,
+        "report" => WorkType::Report,
+        "retraction" => WorkType::Retraction,
+        "review" => WorkType::Review,
+        "standard" => WorkType::Standard,
+        "supplementary-materials" => WorkType::SupplementaryMaterials,
+        _ => WorkType::Other,
+    }
+}
+
+/// Maps a source's raw work type onto our normalized `WorkType`, ignoring case and surrounding whitespace. Unknown
+/// types map to `Other`.
+pub fn normalize_work_type(source: WorkSource, raw_type: &str) -> WorkType {
+    let raw_type = raw_type.trim().to_lowercase();
+    match source {
+        WorkSource::Crossref => crossref_work_type(&raw_type),
+        WorkSource::DataCite => datacite_work_type(&raw_type),
+        WorkSource::OpenAlex => openalex_work_type(&raw_type),
+    }
+}
+
+/// Parses the people in a DataCite creators or contributors array, skipping organizations, and collects their
+/// affiliations as institutions.
+fn datacite_people(
+    attributes: &Value,
+    key: &str,
+    institutions: &mut Vec<Institution>,
+) -> Vec<Author> {
+    let mut authors = Vec::new();
+    for person in array_field(attributes, key) {
+        let field = |key| person.get(key).and_then(as_text);
+        if field("nameType") == Some("Organizational") {
+            continue;
+        }
+
+        let orcid = array_field(person, "nameIdentifiers")
+            .find_map(|id| normalize_orcid(id.get("nameIdentifier").and_then(Value::as_str)));
+        let mut author = Author::new(field("givenName"), field("familyName"), field("name"), None);
+        author.orcid = orcid;
+        if author.full.is_some() || author.orcid.is_some() {
+            push_unique(&mut authors, author);
+        }
+
+        for affiliation in array_field(person, "affiliation") {
+            let institution = match affiliation {
+                Value::String(name) => {
+                    Institution::new(Some(name.trim()).filter(|name| !name.is_empty()), None)
+                },
+                _ => Institution::new(
+                    affiliation.get("name").and_then(as_text),
+                    affiliation.get("affiliationIdentifier").and_then(as_text),
+                ),
+            };
+            if let Some(institution) = institution {
+                push_unique(institutions, institution);
+            }
+        }
+    }
+    authors
+}
+
+/// Transforms a raw DataCite record into our normalized work schema: creators and contributors are parsed with
+/// `parse_name` (organizations are skipped) and their affiliations collected as institutions, resourceTypeGeneral is
+/// mapped with `normalize_work_type`, each funding reference award becomes a funder entry, as does each funding
+/// reference without awards, and related identifiers are kept with their relation types, with DOIs and DOI URLs
+/// normalized. Returns None when the record has no valid DOI.
+pub fn transform_datacite_record(bytes: &[u8]) -> serde_json::Result<Option<Work>> {
+    let record: Value = serde_json::from_slice(bytes)?;
+
+    let Some(doi) = normalize_doi(record.get("id").and_then(Value::as_str)) else {
+        return Ok(None);
+    };
+    let attributes = record.get("attributes").unwrap_or(&Value::Null);
+
+    let title = array_field(attributes, "titles")
+        .find_map(|title| strip_markup(title.get("title").and_then(Value::as_str), None));
+    let abstract_text = array_field(attributes, "descriptions").find_map(|description| {
+        strip_markup(
+            description.get("description").and_then(Value::as_str),
+            Some(&DATACITE_NO_ABSTRACT),
+        )
+    });
+    let work_type = attributes
+        .get("types")
+        .and_then(|types| types.get("resourceTypeGeneral"))
+        .and_then(as_text)
+        .map(|resource_type| {
+            normalize_work_type(WorkSource::DataCite, resource_type)
+                .as_str()
+                .to_string()
+        });
+
+    let mut institutions = Vec::new();
+    let authors = datacite_people(attributes, "creators", &mut institutions);
+    let contributors = datacite_people(attributes, "contributors", &mut institutions);
+
+    let mut funders = Vec::new();
+    for reference in array_field(attributes, "fundingReferences") {
+        let field = |key| reference.get(key).and_then(as_text);
+        let name = field("funderName").map(str::to_string);
+        let funder_doi = normalize_doi(field("funderIdentifier"));
+        let awards: Vec<&str> = field("awardNumber")
+            .into_iter()
+            .flat_map(|awards| awards.split(','))
+            .map(str::trim)
+            .filter(|award| !award.is_empty())
+            .collect();
+        if awards.is_empty() {
+            if name.is_some() || funder_doi.is_some() {
+                funders.push(Funder {
+                    name,
+                    funder_doi,
+                    award: None,
+                });
+            }
+            continue;
+        }
+        for award in awards {
+            funders.push(Funder {
+                name: name.clone(),
+                funder_doi: funder_doi.clone(),
+                award: Some(award.to_string()),
+            });
+        }
+    }
+
+    let mut relations = Vec::new();
+    for related in array_field(attributes, "relatedIdentifiers") {
+        let field = |key| related.get(key).and_then(as_text);
+        if let Some(relation) = Relation::new(
+            field("relationType"),
+            field("relatedIdentifier"),
+            field("relatedIdentifierType"),
+        ) {
+            push_unique(&mut relations, relation);
+        }
+    }
+
+    // Journals the work is part of are recorded as ISSN related identifiers
+    let mut issns = Vec::new();
+    for relation in &relations {
+        if relation.related_identifier_type.as_deref() == Some("ISSN") {
+            if let Some(issn) = normalize_issn(relation.related_identifier.as_deref()) {
+                push_unique(&mut issns, issn);
+            }
+        }
+    }
+
+    let publication_date = attributes
+        .get("created")
+        .and_then(as_text)
+        .map(|created| created.split('T').next().unwrap_or(created).to_string())
+        .or_else(|| match attributes.get("publicationYear")? {
+            Value::Number(year) => Some(year.to_string()),
+            year => as_text(year).map(str::to_string),
+        });
+    let updated_date = attributes
+        .get("updated")
+        .and_then(as_text)
+        .map(str::to_string);
+
+    Ok(Some(Work {
+        id: None,
+        doi,
+        title,
+        abstract_text,
+        work_type,
+        authors,
+        contributors,
+        institutions,
+        issns,
+        funders,
+        relations,
+        publication_date,
+        updated_date,
+    }))
+}
+
+/// A Crossref date, as a timestamp with its date parts.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CrossrefDate {
+    #[serde(rename = "date-time")]
+    date_time: Option<String>,
+    #[serde(rename = "date-parts")]
+    date_parts: Vec<Vec<Option<i64>>>,
+}
+
+impl CrossrefDate {
+    fn parse(&self) -> Option<ParsedDate> {
+        parse_date(self.date_time.as_deref()).or_else(|| date_from_parts(self.date_parts.first()?))
+    }
+}
+
+/// The dates of a Crossref work that `IncrementalFilter` compares, leaving the rest of the record unparsed.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CrossrefDates {
+    indexed: Option<CrossrefDate>,
+    created: Option<CrossrefDate>,
+}
+
+/// The dates of an OpenAlex work that `IncrementalFilter` compares.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct OpenAlexDates {
+    updated_date: Option<String>,
+    created_date: Option<String>,
+}
+
+/// The dates of a DataCite record that `IncrementalFilter` compares.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DataCiteDates {
+    attributes: DataCiteDateAttributes,
+}
+
+/// The attributes of a DataCite record holding its dates.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DataCiteDateAttributes {
+    updated: Option<String>,
+    created: Option<String>,
+}
+
+/// High-water marks for incremental loads: records last updated, or created, before these dates were loaded by an
+/// earlier run and are skipped. Each source's own dates are compared: Crossref's `indexed` and `created`, OpenAlex's
+/// `updated_date` and `created_date`, and DataCite's `updated` and `created`. A record is kept when its date falls on
+/// or after the mark, or when it lacks the date.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalFilter {
+    pub updated_since: Option<ParsedDate>,
+    pub created_since: Option<ParsedDate>,
+}
+
+impl IncrementalFilter {
+    /// Whether the filter has no high-water marks, and so keeps every record.
+    fn is_empty(&self) -> bool {
+        self.updated_since.is_none() && self.created_since.is_none()
+    }
+
+    /// Whether a raw record from a source passes the filter, reading only its dates. Records that can't be read are
+    /// kept, so that their transformer reports the error.
+    fn keeps(&self, source: WorkSource, bytes: &[u8]) -> bool {
+        self.is_empty()
+            || self.keeps_dates(record_dates(
+                source,
+                &mut serde_json::Deserializer::from_slice(bytes),
+            ))
+    }
+
+    /// Whether a parsed record from a source passes the filter, like `keeps`.
+    pub fn keeps_record(&self, source: WorkSource, record: &Value) -> bool {
+        self.is_empty() || self.keeps_dates(record_dates(source, record))
+    }
+
+    /// Whether a record's last updated and created dates are on or after the marks.
+    fn keeps_dates<E>(&self, dates: Result<(Option<ParsedDate>, Option<ParsedDate>), E>) -> bool {
+        let Ok((updated, created)) = dates else {
+            return true;
+        };
+        // A partial date, e.g. a year, is on or after the mark when any of its days are
+        let on_or_after = |date: Option<ParsedDate>, mark: &Option<ParsedDate>| match (date, mark) {
+            (Some(date), Some(mark)) => date.day_range().1 >= mark.day_range().0,
+            _ => true,
+        };
+        on_or_after(updated, &self.updated_since) && on_or_after(created, &self.created_since)
+    }
+}
+
+/// Reads the last updated and created dates that `IncrementalFilter` compares from a record of a source.
+fn record_dates<'de, D: serde::Deserializer<'de>>(
+    source: WorkSource,
+    deserializer: D,
+) -> Result<(Option<ParsedDate>, Option<ParsedDate>), D::Error> {
+    Ok(match source {
+        WorkSource::Crossref => {
+            let dates = CrossrefDates::deserialize(deserializer)?;
+            (
+                dates.indexed.as_ref().and_then(CrossrefDate::parse),
+                dates.created.as_ref().and_then(CrossrefDate::parse),
+            )
+        },
+        WorkSource::DataCite => {
+            let dates = DataCiteDates::deserialize(deserializer)?;
+            (
+                parse_date(dates.attributes.updated.as_deref()),
+                parse_date(dates.attributes.created.as_deref()),
+            )
+        },
+        WorkSource::OpenAlex => {
+            let dates = OpenAlexDates::deserialize(deserializer)?;
+            (
+                parse_date(dates.updated_date.as_deref()),
+                parse_date(dates.created_date.as_deref()),
+            )
+        },
+    })
+}
+
+/// Transforms many raw works from a source in parallel on the current rayon thread pool, preserving order, with
+/// `transform_crossref_work`, `transform_openalex_work` (passing `include_xpac`) or `transform_datacite_record`.
+/// Instead of failing or logging warnings, returns a `ParseError` for each record that isn't valid JSON, which
+/// transforms to None, and for each field that couldn't be parsed and is missing from its work, e.g. an invalid
+/// OpenAlex `abstract_inverted_index`. Records that `filter` skips transform to None without being parsed in full, and
+/// their rows are returned apart from the errors.
+pub fn transform_works_many(
+    source: WorkSource,
+    records: &[Vec<u8>],
+    include_xpac: bool,
+    filter: &IncrementalFilter,
+) -> (Vec<Option<Work>>, Vec<ParseError>, Vec<usize>) {
+    // None for records the filter skips
+    let transformed: Vec<Option<(serde_json::Result<Option<Work>>, FieldErrors)>> = records
+        .par_iter()
+        .map(|bytes| {
+            if !filter.keeps(source, bytes) {
+                return None;
+            }
+            let mut field_errors = Vec::new();
+            let work = match source {
+                WorkSource::Crossref => transform_crossref_work(bytes),
+                WorkSource::DataCite => transform_datacite_record(bytes),
+                WorkSource::OpenAlex => openalex_work(bytes, include_xpac, &mut field_errors),
+            };
+            Some((work, field_errors))
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut skipped = Vec::new();
+    let works = transformed
+        .into_iter()
+        .enumerate()
+        .map(|(row, transformed)| {
+            let Some((work, field_errors)) = transformed else {
+                skipped.push(row);
+                return None;
+            };
+            errors.extend(field_errors.into_iter().map(|(field, message)| ParseError {
+                row,
+                field: Some(field.to_string()),
+                message,
+            }));
+            work.unwrap_or_else(|e| {
+                errors.push(ParseError {
+                    row,
+                    field: None,
+                    message: format!("invalid json: {e}"),
+                });
+                None
+            })
+        })
+        .collect();
+    (works, errors, skipped)
+}
+
+/// A DMP flattened from the RDA DMP Common Standard into the fields that are indexed for search.
+#[derive(Debug, Clone, Serialize)]
+pub struct Dmp {
+    pub doi: String,
+    pub title: Option<String>,
+    pub abstract_text: Option<String>,
+    /// The DMP's contact followed by its contributors, with names parsed by `parse_name`.
+    pub authors: Vec<Author>,
+    /// The affiliations of the contact and contributors.
+    pub institutions: Vec<Institution>,
+    pub funding: Vec<DmpFunding>,
+    /// Start of the earliest project, as an ISO 8601 date.
+    pub project_start: Option<String>,
+    /// End of the latest project, as an ISO 8601 date.
+    pub project_end: Option<String>,
+    pub datasets: Vec<Dataset>,
+    /// DOIs of the works the DMP lists as related, e.g. its published outputs.
+    pub published_outputs: Vec<String>,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+/// A funder of a DMP's project, identified by a ROR ID or a Crossref Funder Registry DOI.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DmpFunder {
+    pub name: Option<String>,
+    pub ror: Option<String>,
+    pub funder_doi: Option<String>,
+}
+
+/// A funding item of a DMP's project.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DmpFunding {
+    pub funder: DmpFunder,
+    /// The RDA funding status: "planned", "applied", "granted" or "rejected".
+    pub status: Option<String>,
+    pub award_id: Option<String>,
+    pub funding_opportunity_id: Option<String>,
+}
+
+/// A dataset a DMP plans to produce.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dataset {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// The dataset type, e.g. "dataset" or "software".
+    pub dataset_type: Option<String>,
+}
+
+/// Markup options for DMP fields, which often hold placeholders such as "N/A" or "TBD".
+static DMP_MARKUP: LazyLock<MarkupOptions> = LazyLock::new(|| MarkupOptions {
+    decode_entities: true,
+    jats: false,
+    mathml: MathMl::Keep,
+    null_rules: Some(DEFAULT_NULL_RULES.clone()),
+});
+
+/// Strips HTML markup from a DMP field, decoding entities, collapsing whitespace and treating placeholder values as
+/// null.
+fn strip_html(text: Option<&str>) -> Option<String> {
+    let stripped = strip_markup_with_options(text, None, &DMP_MARKUP)?;
+    let collapsed = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!collapsed.is_empty()).then_some(collapsed)
+}
+
+/// The identifier of an RDA `{"identifier": ..., "type": ...}` object when its type is one of `types`.
+fn rda_identifier<'a>(id: Option<&'a Value>, types: &[&str]) -> Option<&'a str> {
+    let id = id?;
+    let id_type = id.get("type").and_then(as_text)?;
+    types
+        .iter()
+        .any(|t| id_type.eq_ignore_ascii_case(t))
+        .then(|| id.get("identifier").and_then(as_text))
+        .flatten()
+}
+
+/// The date part of an RDA date or date-time.
+fn rda_date(value: Option<&Value>) -> Option<String> {
+    let date = as_text(value?)?;
+    Some(date.split('T').next().unwrap_or(date).to_string())
+}
+
+/// Parses the DMP contact or a contributor, with their ORCID iD, and collects their affiliation as an institution.
+fn rda_person(person: &Value, id_key: &str, institutions: &mut Vec<Institution>) -> Option<Author> {
+    if let Some(affiliation) = person.get("dmproadmap_affiliation") {
+        let ror = rda_identifier(affiliation.get("affiliation_id"), &["ror", "url"]);
+        if let Some(institution) = Institution::new(affiliation.get("name").and_then(as_text), ror)
+        {
+            push_unique(institutions, institution);
+        }
+    }
+    let orcid = rda_identifier(person.get(id_key), &["orcid", "url"]);
+    let author = Author::new(None, None, person.get("name").and_then(as_text), orcid);
+    (author.full.is_some() || author.orcid.is_some()).then_some(author)
+}
+
+/// Transforms a DMP in the RDA DMP Common Standard, as exported from DMPTool, into a flattened search document: the
+/// DOI is taken from `dmp_id`, the title and description are stripped of HTML, the contact and contributors are
+/// parsed into deduplicated authors and their affiliations collected as institutions, each project funding becomes a
+/// funding item, project dates are widened to span all projects, and related identifiers that are DOIs become
+/// published outputs. The DMP may be wrapped in a top-level `dmp` object. Returns None when it has no valid DOI.
+pub fn transform_dmp(bytes: &[u8]) -> serde_json::Result<Option<Dmp>> {
+    let record: Value = serde_json::from_slice(bytes)?;
+    let dmp = record.get("dmp").unwrap_or(&record);
+
+    let Some(doi) = normalize_doi(rda_identifier(dmp.get("dmp_id"), &["doi", "url"])) else {
+        return Ok(None);
+    };
+
+    let projects: Vec<&Value> = array_field(dmp, "project").collect();
+    let title = strip_html(dmp.get("title").and_then(Value::as_str)).or_else(|| {
+        projects
+            .iter()
+            .find_map(|p| strip_html(p.get("title")?.as_str()))
+    });
+    let abstract_text = strip_html(dmp.get("description").and_then(Value::as_str)).or_else(|| {
+        projects
+            .iter()
+            .find_map(|p| strip_html(p.get("description")?.as_str()))
+    });
+
+    let mut authors = Vec::new();
+    let mut institutions = Vec::new();
+    let contact = dmp.get("contact").map(|contact| (contact, "contact_id"));
+    let contributors =
+        array_field(dmp, "contributor").map(|contributor| (contributor, "contributor_id"));
+    for (person, id_key) in contact.into_iter().chain(contributors) {
+        if let Some(author) = rda_person(person, id_key, &mut institutions) {
+            push_unique(&mut authors, author);
+        }
+    }
+
+    let mut funding = Vec::new();
+    for item in projects
+        .iter()
+        .flat_map(|project| array_field(project, "funding"))
+    {
+        let field = |key| item.get(key).and_then(as_text).map(str::to_string);
+        let funder_id = item.get("funder_id");
+        let funding_item = DmpFunding {
+            funder: DmpFunder {
+                name: field("name"),
+                ror: normalize_ror(rda_identifier(funder_id, &["ror", "url"])),
+                funder_doi: normalize_doi(rda_identifier(funder_id, &["fundref", "doi", "url"])),
+            },
+            status: field("funding_status"),
+            award_id: rda_identifier(item.get("grant_id"), &["other", "url", "doi"])
+                .map(str::to_string),
+            funding_opportunity_id: field("dmproadmap_funding_opportunity_id").or_else(|| {
+                rda_identifier(
+                    item.get("dmproadmap_funding_opportunity_id"),
+                    &["other", "url"],
+                )
+                .map(str::to_string)
+            }),
+        };
+        let funder = &funding_item.funder;
+        if funder.name.is_some() || funder.ror.is_some() || funder.funder_doi.is_some() {
+            push_unique(&mut funding, funding_item);
+        }
+    }
+
+    let project_start = projects
+        .iter()
+        .filter_map(|p| rda_date(p.get("start")))
+        .min();
+    let project_end = projects.iter().filter_map(|p| rda_date(p.get("end"))).max();
+
+    let mut datasets = Vec::new();
+    for dataset in array_field(dmp, "dataset") {
+        let dataset = Dataset {
+            title: strip_html(dataset.get("title").and_then(Value::as_str)),
+            description: strip_html(dataset.get("description").and_then(Value::as_str)),
+            dataset_type: dataset.get("type").and_then(as_text).map(str::to_string),
+        };
+        if dataset.title.is_some() || dataset.description.is_some() {
+            push_unique(&mut datasets, dataset);
+        }
+    }
+
+    let mut published_outputs = Vec::new();
+    for related in array_field(dmp, "dmproadmap_related_identifiers") {
+        if let Some(doi) = normalize_doi(related.get("identifier").and_then(Value::as_str)) {
+            push_unique(&mut published_outputs, doi);
+        }
+    }
+
+    Ok(Some(Dmp {
+        doi,
+        title,
+        abstract_text,
+        authors,
+        institutions,
+        funding,
+        project_start,
+        project_end,
+        datasets,
+        published_outputs,
+        created: dmp.get("created").and_then(as_text).map(str::to_string),
+        modified: dmp.get("modified").and_then(as_text).map(str::to_string),
+    }))
+}
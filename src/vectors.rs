@@ -0,0 +1,83 @@
+use numpy::ndarray::{Array2, ArrayView1, ArrayView2};
+use rayon::prelude::*;
+
+/// Number of independent accumulators in `dot`, so that the loop auto-vectorizes to SIMD instructions where the
+/// target supports them.
+const LANES: usize = 8;
+
+/// Computes the dot product of two equal-length vectors.
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let mut sums = [0.0f32; LANES];
+    let (chunks_a, chunks_b) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let tail: f32 = chunks_a
+        .remainder()
+        .iter()
+        .zip(chunks_b.remainder())
+        .map(|(x, y)| x * y)
+        .sum();
+    for (chunk_a, chunk_b) in chunks_a.zip(chunks_b) {
+        for lane in 0..LANES {
+            sums[lane] += chunk_a[lane] * chunk_b[lane];
+        }
+    }
+    sums.iter().sum::<f32>() + tail
+}
+
+/// The rows of a matrix in standard layout as slices.
+fn rows<'a>(matrix: &'a ArrayView2<'_, f32>) -> Vec<&'a [f32]> {
+    matrix
+        .rows()
+        .into_iter()
+        .map(|row| {
+            row.to_slice()
+                .expect("rows of a standard layout array are contiguous")
+        })
+        .collect()
+}
+
+/// The cosine similarity of two vectors given their norms, 0.0 when either is a zero vector.
+fn cosine(a: &[f32], norm_a: f32, b: &[f32], norm_b: f32) -> f32 {
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot(a, b) / (norm_a * norm_b)
+}
+
+/// Computes the cosine similarity of every row of `queries` with every row of `matrix` in parallel on the current
+/// rayon thread pool, returning a (queries × matrix rows) array. Rows that are zero vectors have a similarity of 0.0.
+/// Both arrays must have the same number of columns.
+pub fn cosine_similarity(queries: ArrayView2<f32>, matrix: ArrayView2<f32>) -> Array2<f32> {
+    let (queries, matrix) = (queries.as_standard_layout(), matrix.as_standard_layout());
+    let (queries, matrix) = (queries.view(), matrix.view());
+    let rows = rows(&matrix);
+    let norms: Vec<f32> = rows.iter().map(|row| dot(row, row).sqrt()).collect();
+
+    let similarities: Vec<f32> = self::rows(&queries)
+        .into_par_iter()
+        .flat_map_iter(|query| {
+            let query_norm = dot(query, query).sqrt();
+            rows.iter()
+                .zip(&norms)
+                .map(move |(row, norm)| cosine(query, query_norm, row, *norm))
+        })
+        .collect();
+    Array2::from_shape_vec((queries.nrows(), matrix.nrows()), similarities)
+        .expect("one similarity per query and matrix row")
+}
+
+/// Ranks the rows of `matrix` by cosine similarity to `query`, returning the `k` most similar as (row index,
+/// similarity) pairs, most similar first with ties broken by row index. Rows are scored in parallel on the current
+/// rayon thread pool. The query must have as many elements as the matrix has columns.
+pub fn top_k(query: ArrayView1<f32>, matrix: ArrayView2<f32>, k: usize) -> Vec<(usize, f32)> {
+    let query = query.to_vec();
+    let query_norm = dot(&query, &query).sqrt();
+    let matrix = matrix.as_standard_layout();
+    let mut ranked: Vec<(usize, f32)> = rows(&matrix.view())
+        .into_par_iter()
+        .enumerate()
+        .map(|(i, row)| (i, cosine(&query, query_norm, row, dot(row, row).sqrt())))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked.truncate(k);
+    ranked
+}
@@ -0,0 +1,68 @@
+import pytest
+
+from dmpworks.rust.acronyms import expand_acronyms, extract_acronyms
+
+
+class TestExtractAcronyms:
+    @pytest.mark.parametrize(
+        "text,expected",
+        [
+            (
+                "Data from the Legacy Survey of Space and Time (LSST)",
+                [("LSST", "Legacy Survey of Space and Time")],
+            ),
+            ("LSST (Legacy Survey of Space and Time) catalog", [("LSST", "Legacy Survey of Space and Time")]),
+            ("Deutsche Forschungsgemeinschaft (DFG)", [("DFG", "Deutsche Forschungsgemeinschaft")]),
+            ("The National Science Foundation (NSF)", [("NSF", "National Science Foundation")]),
+        ],
+    )
+    def test_definitions(self, text, expected):
+        assert extract_acronyms(text) == expected
+
+    def test_undefined(self):
+        assert extract_acronyms("Sharing mRNA and COVID-19 data with the NIH") == [
+            ("mRNA", None),
+            ("COVID-19", None),
+            ("NIH", None),
+        ]
+
+    def test_distinct_in_order(self):
+        assert extract_acronyms("NSF and NIH grants; more NSF grants") == [("NSF", None), ("NIH", None)]
+
+    def test_plural(self):
+        assert extract_acronyms("Writing DMPs") == [("DMP", None)]
+
+    def test_unrelated_parenthetical(self):
+        assert extract_acronyms("Soil carbon (SM) in alpine meadows") == [("SM", None)]
+
+    @pytest.mark.parametrize("text", ["Phase II results", "SOIL CARBON DATA FROM ALPINE MEADOWS", "", None])
+    def test_no_acronyms(self, text):
+        assert extract_acronyms(text) == []
+
+
+class TestExpandAcronyms:
+    def test_dictionary(self):
+        dictionary = {"LSST": "Legacy Survey of Space and Time"}
+        assert expand_acronyms("LSST data management", dictionary) == (
+            "LSST (Legacy Survey of Space and Time) data management"
+        )
+
+    def test_funder_acronyms(self):
+        assert expand_acronyms("Funded by NSF and NIH") == (
+            "Funded by NSF (National Science Foundation) and NIH (National Institutes of Health)"
+        )
+
+    def test_first_occurrence(self):
+        assert expand_acronyms("NSF data and NSF code") == "NSF (National Science Foundation) data and NSF code"
+
+    def test_already_defined(self):
+        assert expand_acronyms("National Science Foundation (NSF) award") == "National Science Foundation (NSF) award"
+
+    def test_expansion_present(self):
+        assert expand_acronyms("NSF, the National Science Foundation") == "NSF, the National Science Foundation"
+
+    def test_unknown(self):
+        assert expand_acronyms("LSST data") == "LSST data"
+
+    def test_none(self):
+        assert expand_acronyms(None) is None
@@ -0,0 +1,63 @@
+import pytest
+
+from dmpworks.rust.blocking import blocking_keys, blocking_keys_many
+
+WORK = {
+    "doi": "10.1234/abc",
+    "title": "The Dynamics of Soil Moisture in Drylands",
+    "authors": [
+        {"surname": "Müller", "first_initial": "J", "full": "Jan Müller"},
+        {"surname": None, "first_initial": None, "full": "Ana Lee"},
+    ],
+    "funders": [{"name": "NSF", "funder_doi": "10.13039/100000001", "award": "1234567"}],
+    "publication_date": "2021-03-05",
+}
+
+DMP = {
+    "doi": "10.48321/D1ABC",
+    "title": "Soil moisture dynamics",
+    "authors": [{"surname": "Muller", "first_initial": "J"}],
+    "funding": [{"funder": {"name": "National Science Foundation", "ror": "https://ror.org/021nxhr62"}}],
+    "project_start": "2019-01-01",
+    "project_end": "2020-12-31",
+}
+
+
+class TestBlockingKeys:
+    def test_work(self):
+        assert blocking_keys(WORK) == [
+            "surname_initial_year:muller_j_2021",
+            "surname_initial_year:lee_a_2021",
+            "title_prefix:dynamics_soil_moisture",
+            "funder_year:10.13039.100000001_2021",
+            "funder_year:national-science-foundation_2021",
+        ]
+
+    def test_dmp_spans_project_years(self):
+        keys = blocking_keys(DMP, schemes=["surname_initial_year", "funder_year"])
+        assert keys[:4] == [
+            "surname_initial_year:muller_j_2019",
+            "surname_initial_year:muller_j_2020",
+            "surname_initial_year:muller_j_2021",
+            "surname_initial_year:muller_j_2022",
+        ]
+        assert "funder_year:021nxhr62_2020" in keys
+        assert set(keys) & set(blocking_keys(WORK)) == {
+            "surname_initial_year:muller_j_2021",
+            "funder_year:national-science-foundation_2021",
+        }
+
+    def test_missing_fields(self):
+        assert blocking_keys({"title": "Data"}) == ["title_prefix:data"]
+        assert blocking_keys({}) == []
+        assert blocking_keys({"authors": [{"surname": "Smith", "first_initial": "J"}]}) == []
+
+    def test_unknown_scheme(self):
+        with pytest.raises(ValueError, match="unknown blocking scheme: doi"):
+            blocking_keys(WORK, schemes=["doi"])
+
+
+class TestBlockingKeysMany:
+    def test_matches_blocking_keys(self):
+        records = [WORK, DMP, {}]
+        assert blocking_keys_many(records, num_threads=2) == [blocking_keys(record) for record in records]
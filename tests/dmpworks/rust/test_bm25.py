@@ -0,0 +1,52 @@
+import math
+
+import pytest
+
+from dmpworks.rust import TokenizerOptions
+from dmpworks.rust.bm25 import Bm25Index
+
+
+class TestBm25Index:
+    texts = [
+        "Soil carbon in alpine meadows",
+        "Alpine glacier retreat",
+        None,
+        "Soil carbon soil nitrogen and soil moisture across a long grassland transect",
+    ]
+
+    def test_score_matches_lucene_formula(self):
+        index = Bm25Index(self.texts)
+        avg_length = (5 + 3 + 0 + 12) / 4
+
+        def expected(df, tf, length):
+            idf = math.log(1 + (4 - df + 0.5) / (df + 0.5))
+            return idf * tf / (tf + 1.2 * (1 - 0.75 + 0.75 * length / avg_length))
+
+        assert index.score("glacier") == pytest.approx([0.0, expected(1, 1, 3), 0.0, 0.0])
+        assert index.score("soil")[3] == pytest.approx(expected(2, 3, 12))
+        assert index.score("alpine alpine")[1] == pytest.approx(2 * expected(2, 1, 3))
+
+    def test_rank(self):
+        index = Bm25Index(self.texts)
+        ranked = index.rank("soil carbon")
+        assert [doc for doc, _ in ranked] == [0, 3]
+        assert ranked[0][1] > ranked[1][1]
+        assert index.rank("soil carbon", top_k=1) == ranked[:1]
+        assert index.rank("volcano") == []
+
+    def test_parameters(self):
+        no_length_norm = Bm25Index(self.texts, b=0.0)
+        assert no_length_norm.score("carbon")[0] == pytest.approx(no_length_norm.score("carbon")[3])
+        with pytest.raises(ValueError):
+            Bm25Index(self.texts, b=1.5)
+        with pytest.raises(ValueError):
+            Bm25Index(self.texts, k1=-1.0)
+
+    def test_options(self):
+        index = Bm25Index(self.texts, options=TokenizerOptions(word_ngrams=(2, 2)))
+        assert [doc for doc, _ in index.rank("soil carbon")] == [0, 3]
+        assert index.rank("meadows alpine") == []
+
+    def test_len(self):
+        assert len(Bm25Index(self.texts)) == 4
+        assert Bm25Index([]).rank("soil") == []
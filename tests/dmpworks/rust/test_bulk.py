@@ -0,0 +1,401 @@
+import base64
+from datetime import datetime, timedelta, timezone
+import gzip
+import hashlib
+import hmac
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+import json
+import socket
+import ssl
+import threading
+import time
+
+import pytest
+
+from dmpworks.rust.bulk import OpenSearchLoader, to_bulk_actions
+from tests.utils import get_fixtures_path
+
+TLS_FIXTURES = get_fixtures_path() / "tls"
+
+
+def parse_body(body):
+    assert body.endswith(b"\n")
+    lines = [json.loads(line) for line in body.decode().split("\n")[:-1]]
+    return list(zip(lines[::2], lines[1::2]))
+
+
+class TestToBulkActions:
+    def test_actions(self):
+        records = [{"doi": "10.1234/a", "title": "Soil"}, {"doi": "10.1234/b", "title": None}]
+        (body,) = to_bulk_actions(records, "works-index", id_field="doi")
+        assert parse_body(body) == [
+            ({"index": {"_index": "works-index", "_id": "10.1234/a"}}, {"doi": "10.1234/a", "title": "Soil"}),
+            ({"index": {"_index": "works-index", "_id": "10.1234/b"}}, {"doi": "10.1234/b", "title": None}),
+        ]
+
+    def test_without_id(self):
+        (body,) = to_bulk_actions([{"title": "Soil"}], "works-index")
+        assert parse_body(body) == [({"index": {"_index": "works-index"}}, {"title": "Soil"})]
+
+    def test_integer_id(self):
+        (body,) = to_bulk_actions([{"id": 42}], "dmps-index", id_field="id")
+        assert parse_body(body)[0][0]["index"]["_id"] == "42"
+
+    def test_escaping(self):
+        record = {"title": 'Line one\nline "two" ', "abstract": "Tab\there \\ and café"}
+        (body,) = to_bulk_actions([record], "works-index")
+        assert body.count(b"\n") == 2
+        assert parse_body(body)[0][1] == record
+
+    def test_chunk_bytes(self):
+        records = [{"doi": f"10.1234/{i}", "title": "x" * 50} for i in range(10)]
+        (single,) = to_bulk_actions(records[:1], "works-index", id_field="doi")
+        bodies = to_bulk_actions(records, "works-index", id_field="doi", max_chunk_bytes=len(single) * 3)
+        assert [len(parse_body(body)) for body in bodies] == [3, 3, 3, 1]
+        assert all(len(body) <= len(single) * 3 for body in bodies)
+        dois = [source["doi"] for body in bodies for _, source in parse_body(body)]
+        assert dois == [record["doi"] for record in records]
+
+    def test_chunk_actions(self):
+        records = [{"id": i} for i in range(5)]
+        bodies = to_bulk_actions(records, "works-index", max_chunk_actions=2)
+        assert [len(parse_body(body)) for body in bodies] == [2, 2, 1]
+
+    def test_oversized_record(self):
+        records = [{"title": "a"}, {"title": "x" * 1000}, {"title": "b"}]
+        bodies = to_bulk_actions(records, "works-index", max_chunk_bytes=100)
+        assert [parse_body(body)[0][1]["title"][0] for body in bodies] == ["a", "x", "b"]
+
+    def test_empty(self):
+        assert to_bulk_actions([], "works-index") == []
+
+    def test_missing_id(self):
+        with pytest.raises(ValueError, match="record 1 has no doi"):
+            to_bulk_actions([{"doi": "10.1234/a"}, {"title": "Soil"}], "works-index", id_field="doi")
+
+    def test_not_a_dict(self):
+        with pytest.raises(TypeError):
+            to_bulk_actions(["10.1234/a"], "works-index")
+
+    def test_invalid_limits(self):
+        with pytest.raises(ValueError):
+            to_bulk_actions([], "works-index", max_chunk_bytes=0)
+
+
+class StubOpenSearch(ThreadingHTTPServer):
+    """An HTTP server answering bulk requests with queued (status, body) responses, then with success, or after
+    `stall` seconds not answering at all."""
+
+    def __init__(self):
+        super().__init__(("127.0.0.1", 0), StubBulkHandler)
+        self.responses = []
+        self.requests = []
+        self.stall = None
+
+    @property
+    def url(self):
+        return f"http://127.0.0.1:{self.server_address[1]}"
+
+
+class StubOpenSearch6(StubOpenSearch):
+    address_family = socket.AF_INET6
+
+    def __init__(self):
+        ThreadingHTTPServer.__init__(self, ("::1", 0), StubBulkHandler)
+        self.responses = []
+        self.requests = []
+        self.stall = None
+
+
+class StubBulkHandler(BaseHTTPRequestHandler):
+    def do_POST(self):
+        body = self.rfile.read(int(self.headers["Content-Length"]))
+        if self.headers.get("Content-Encoding") == "gzip":
+            body = gzip.decompress(body)
+        self.server.requests.append((self.path, dict(self.headers), body))
+        if self.server.stall is not None:
+            time.sleep(self.server.stall)
+            return
+        if self.server.responses:
+            status, response = self.server.responses.pop(0)
+        else:
+            items = [{"index": {"_id": action["index"].get("_id"), "status": 201}} for action, _ in parse_body(body)]
+            status, response = 200, {"took": 1, "errors": False, "items": items}
+        data = json.dumps(response).encode()
+        self.send_response(status)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(data)))
+        self.end_headers()
+        self.wfile.write(data)
+
+    def log_message(self, *args):
+        pass
+
+
+@pytest.fixture
+def server():
+    server = StubOpenSearch()
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield server
+    server.shutdown()
+    server.server_close()
+
+
+@pytest.fixture
+def tls_server():
+    """A `StubOpenSearch` served over TLS with a self-signed certificate for localhost and 127.0.0.1."""
+    server = StubOpenSearch()
+    context = ssl.SSLContext(ssl.PROTOCOL_TLS_SERVER)
+    context.load_cert_chain(TLS_FIXTURES / "localhost.crt", TLS_FIXTURES / "localhost.key")
+    server.socket = context.wrap_socket(server.socket, server_side=True)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield server
+    server.shutdown()
+    server.server_close()
+
+
+def sigv4_authorization(headers, path, body, region, service, access_key_id, secret_access_key):
+    """The Signature Version 4 `Authorization` header of a POST with the given lowercased headers."""
+    signed = sorted(name for name in headers if name == "host" or name.startswith("x-amz-"))
+    canonical_request = "\n".join(
+        [
+            "POST",
+            path,
+            "",
+            "".join(f"{name}:{headers[name]}\n" for name in signed),
+            ";".join(signed),
+            hashlib.sha256(body).hexdigest(),
+        ]
+    )
+    date_time = headers["x-amz-date"]
+    scope = f"{date_time[:8]}/{region}/{service}/aws4_request"
+    request_hash = hashlib.sha256(canonical_request.encode()).hexdigest()
+    string_to_sign = f"AWS4-HMAC-SHA256\n{date_time}\n{scope}\n{request_hash}"
+    key = f"AWS4{secret_access_key}".encode()
+    for part in [date_time[:8], region, service, "aws4_request"]:
+        key = hmac.new(key, part.encode(), hashlib.sha256).digest()
+    signature = hmac.new(key, string_to_sign.encode(), hashlib.sha256).hexdigest()
+    return (
+        f"AWS4-HMAC-SHA256 Credential={access_key_id}/{scope}, SignedHeaders={';'.join(signed)}, "
+        f"Signature={signature}"
+    )
+
+
+class TestOpenSearchLoader:
+    def test_send(self, server):
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}, {"doi": "10.1234/b"}], "works-index", id_field="doi")
+        report = OpenSearchLoader(server.url).send(body)
+        assert report["items"] == 2
+        assert report["errors"] == []
+        assert report["attempts"] == 1
+        assert report["error"] is None
+        ((path, headers, received),) = server.requests
+        assert path == "/_bulk"
+        assert headers["Host"] == f"127.0.0.1:{server.server_address[1]}"
+        assert headers["Content-Type"] == "application/x-ndjson"
+        assert headers["Content-Encoding"] == "gzip"
+        assert received == body
+
+    def test_ipv6_host(self):
+        try:
+            server = StubOpenSearch6()
+        except OSError:
+            pytest.skip("IPv6 isn't available")
+        thread = threading.Thread(target=server.serve_forever, daemon=True)
+        thread.start()
+        try:
+            (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+            report = OpenSearchLoader(f"http://[::1]:{server.server_address[1]}").send(body)
+            assert report["error"] is None
+            assert server.requests[0][1]["Host"] == f"[::1]:{server.server_address[1]}"
+        finally:
+            server.shutdown()
+            server.server_close()
+
+    def test_without_gzip(self, server):
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        OpenSearchLoader(server.url, gzip=False).send(body)
+        ((_, headers, received),) = server.requests
+        assert "Content-Encoding" not in headers
+        assert received == body
+
+    def test_index_and_path(self, server):
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        OpenSearchLoader(server.url + "/search/").send(body, index="works-index")
+        assert server.requests[0][0] == "/search/works-index/_bulk"
+
+    def test_credentials(self, server):
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        OpenSearchLoader(server.url, username="admin", password="secret").send(body)
+        token = base64.b64encode(b"admin:secret").decode()
+        assert server.requests[0][1]["Authorization"] == f"Basic {token}"
+
+    def test_item_errors(self, server):
+        items = [
+            {"index": {"_id": "10.1234/a", "status": 201}},
+            {
+                "index": {
+                    "_id": "10.1234/b",
+                    "status": 400,
+                    "error": {"type": "mapper_parsing_exception", "reason": "failed to parse field [title]"},
+                }
+            },
+        ]
+        server.responses.append((200, {"took": 1, "errors": True, "items": items}))
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}, {"doi": "10.1234/b"}], "works-index", id_field="doi")
+        report = OpenSearchLoader(server.url).send(body)
+        assert report["items"] == 2
+        assert report["error"] is None
+        assert report["errors"] == [
+            {
+                "position": 1,
+                "id": "10.1234/b",
+                "status": 400,
+                "type": "mapper_parsing_exception",
+                "reason": "failed to parse field [title]",
+            }
+        ]
+
+    @pytest.mark.parametrize("status", [429, 503])
+    def test_retry(self, server, status):
+        server.responses.extend([(status, {"error": "busy"}), (status, {"error": "busy"})])
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        report = OpenSearchLoader(server.url, initial_backoff=0.01).send(body)
+        assert report["attempts"] == 3
+        assert report["items"] == 1
+        assert report["error"] is None
+        assert [received for _, _, received in server.requests] == [body] * 3
+
+    def test_retries_exhausted(self, server):
+        server.responses.extend([(429, {"error": "busy"})] * 3)
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        report = OpenSearchLoader(server.url, max_retries=2, initial_backoff=0.01).send(body)
+        assert report["attempts"] == 3
+        assert report["items"] == 0
+        assert report["error"] == "gave up after 3 attempts: HTTP 429"
+
+    def test_not_retried(self, server):
+        server.responses.append((400, {"error": "bad request"}))
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        report = OpenSearchLoader(server.url, initial_backoff=0.01).send(body)
+        assert report["attempts"] == 1
+        assert report["error"] == 'HTTP 400: {"error": "bad request"}'
+
+    def test_connection_refused(self, server):
+        url = server.url
+        server.shutdown()
+        server.server_close()
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        report = OpenSearchLoader(url, max_retries=1, initial_backoff=0.01).send(body)
+        assert report["attempts"] == 2
+        assert report["error"].startswith("gave up after 2 attempts")
+
+    def test_read_timeout_not_retried(self, server):
+        server.stall = 0.5
+        (body,) = to_bulk_actions([{"title": "Soil"}], "works-index")
+        report = OpenSearchLoader(server.url, timeout=0.1, initial_backoff=0.01).send(body)
+        assert report["attempts"] == 1
+        assert report["error"].startswith("no response after sending the request")
+        assert len(server.requests) == 1
+
+    def test_oversized_response(self):
+        listener = socket.create_server(("127.0.0.1", 0))
+
+        def respond():
+            connection, _ = listener.accept()
+            with connection:
+                connection.recv(65536)
+                connection.sendall(b"HTTP/1.1 200 OK\r\nContent-Length: 4611686018427387904\r\n\r\n{}")
+
+        thread = threading.Thread(target=respond, daemon=True)
+        thread.start()
+        (body,) = to_bulk_actions([{"title": "Soil"}], "works-index")
+        with listener:
+            report = OpenSearchLoader(f"http://127.0.0.1:{listener.getsockname()[1]}", gzip=False).send(body)
+        thread.join()
+        assert report["attempts"] == 1
+        assert report["error"].endswith("HTTP response body too large")
+
+    def test_load(self, server):
+        records = [{"doi": f"10.1234/{i}"} for i in range(10)]
+        bodies = to_bulk_actions(records, "works-index", id_field="doi", max_chunk_actions=3)
+        server.responses.append((503, {"error": "busy"}))
+        reports = OpenSearchLoader(server.url, initial_backoff=0.01).load(bodies, num_threads=2)
+        assert [report["items"] for report in reports] == [3, 3, 3, 1]
+        assert sum(report["attempts"] for report in reports) == 5
+        assert all(report["error"] is None for report in reports)
+
+    def test_aws_signing(self, server):
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        loader = OpenSearchLoader(
+            server.url + "/search",
+            aws_region="us-west-2",
+            aws_access_key_id="AKIDEXAMPLE",
+            aws_secret_access_key="wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            aws_session_token="session-token",
+            gzip=False,
+        )
+        assert loader.send(body, index="works-index")["error"] is None
+        ((path, headers, _),) = server.requests
+        headers = {name.lower(): value for name, value in headers.items()}
+        assert path == "/search/works-index/_bulk"
+        assert headers["x-amz-content-sha256"] == hashlib.sha256(body).hexdigest()
+        assert headers["x-amz-security-token"] == "session-token"
+        signed_at = datetime.strptime(headers["x-amz-date"], "%Y%m%dT%H%M%SZ").replace(tzinfo=timezone.utc)
+        assert abs(datetime.now(timezone.utc) - signed_at) < timedelta(minutes=1)
+        assert headers["authorization"] == sigv4_authorization(
+            headers, path, body, "us-west-2", "es", "AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY"
+        )
+
+    def test_invalid_aws_signing(self):
+        with pytest.raises(ValueError, match="must be given together"):
+            OpenSearchLoader("https://localhost", aws_region="us-west-2")
+        with pytest.raises(ValueError, match="not both"):
+            OpenSearchLoader(
+                "https://localhost",
+                username="admin",
+                password="secret",
+                aws_region="us-west-2",
+                aws_access_key_id="AKIDEXAMPLE",
+                aws_secret_access_key="secret",
+            )
+
+    def test_https(self, tls_server):
+        url = tls_server.url.replace("http://", "https://")
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index", id_field="doi")
+        report = OpenSearchLoader(url, verify_certs=False).send(body)
+        assert report["items"] == 1
+        assert report["error"] is None
+        assert [received for _, _, received in tls_server.requests] == [body]
+
+    def test_https_verifies_certificates(self, tls_server):
+        url = tls_server.url.replace("http://", "https://")
+        (body,) = to_bulk_actions([{"doi": "10.1234/a"}], "works-index")
+        report = OpenSearchLoader(url, max_retries=0).send(body)
+        assert report["attempts"] == 1
+        assert "certificate" in report["error"]
+        assert tls_server.requests == []
+
+    def test_url(self):
+        assert OpenSearchLoader("http://localhost:9200/").url == "http://localhost:9200"
+        assert OpenSearchLoader("http://localhost").url == "http://localhost:80"
+        assert OpenSearchLoader("https://localhost").url == "https://localhost:443"
+        assert OpenSearchLoader("http://[::1]:9200/search").url == "http://[::1]:9200/search"
+
+    @pytest.mark.parametrize(
+        "url",
+        ["ftp://localhost:9200", "localhost:9200", "http://:9200", "http://localhost:port", "http://a:b@localhost"],
+    )
+    def test_invalid_url(self, url):
+        with pytest.raises(ValueError):
+            OpenSearchLoader(url)
+
+    def test_invalid_options(self):
+        with pytest.raises(ValueError, match="together"):
+            OpenSearchLoader("http://localhost:9200", username="admin")
+        with pytest.raises(ValueError):
+            OpenSearchLoader("http://localhost:9200", timeout=0)
+        with pytest.raises(ValueError):
+            OpenSearchLoader("http://localhost:9200", initial_backoff=-1)
@@ -0,0 +1,113 @@
+import gzip
+import json
+
+import pytest
+
+from dmpworks.rust.csv import CsvOptions, transform_csv
+
+
+def read_jsonl(path):
+    return [json.loads(line) for line in path.read_text().splitlines()]
+
+
+class TestTransformCsv:
+    def test_quoting(self, tmp_path):
+        source = tmp_path / "works.csv"
+        source.write_text(
+            'doi,title\r\n10.1234/A,"Soil, water and ""carbon"""\r\n\r\n10.1234/B,"Two\nlines"\r\n10.1234/C,\r\n'
+        )
+        output = tmp_path / "works.jsonl"
+        assert transform_csv(source, output, {"doi": "normalize_doi"}) == 3
+        assert read_jsonl(output) == [
+            {"doi": "10.1234/a", "title": 'Soil, water and "carbon"'},
+            {"doi": "10.1234/b", "title": "Two\nlines"},
+            {"doi": "10.1234/c", "title": None},
+        ]
+
+    def test_tsv_without_quotes(self, tmp_path):
+        source = tmp_path / "people.tsv"
+        source.write_text('orcid\tnote\nhttps://orcid.org/0000-0002-1825-0097\tsays "hi"\n')
+        output = tmp_path / "people.jsonl"
+        transform_csv(source, output, {"orcid": "normalize_orcid"}, csv_options=CsvOptions(delimiter="\t", quote=None))
+        assert read_jsonl(output) == [{"orcid": "0000-0002-1825-0097", "note": 'says "hi"'}]
+
+    def test_gzip_with_column_names(self, tmp_path):
+        source = tmp_path / "orgs.csv.gz"
+        with gzip.open(source, "wt") as f:
+            f.write("https://ror.org/01an7q238,<i>UC</i> Berkeley\n")
+        output = tmp_path / "orgs.ndjson"
+        options = CsvOptions(column_names=["ror", "name"])
+        transform_csv(source, output, {"ror": "normalize_ror", "name": "strip_markup"}, csv_options=options)
+        assert read_jsonl(output) == [{"ror": "01an7q238", "name": "UC Berkeley"}]
+
+    def test_parse_name(self, tmp_path):
+        source = tmp_path / "authors.csv"
+        source.write_text("name\nJane A. Smith\n\"\"\n")
+        output = tmp_path / "authors.jsonl"
+        transform_csv(source, output, {"name": "parse_name"})
+        rows = read_jsonl(output)
+        assert rows[0]["name"]["given_name"] == "Jane"
+        assert rows[0]["name"]["surname"] == "Smith"
+        assert rows[1] == {"name": None}
+
+    def test_parquet(self, tmp_path):
+        pq = pytest.importorskip("pyarrow.parquet")
+        source = tmp_path / "authors.csv"
+        source.write_text("doi,author\n10.1234/A,Jane Smith\n10.1234/B,\n")
+        output = tmp_path / "authors.parquet"
+        assert transform_csv(source, output, {"doi": "normalize_doi", "author": "parse_name"}) == 2
+        rows = pq.read_table(output).to_pylist()
+        assert [row["doi"] for row in rows] == ["10.1234/a", "10.1234/b"]
+        assert rows[0]["author"]["surname"] == "Smith"
+        assert rows[1]["author"]["surname"] is None
+
+    def test_progress(self, tmp_path):
+        source = tmp_path / "works.csv"
+        source.write_text("doi\n" + "10.1234/a\n" * 10_000)
+        calls = []
+        transform_csv(source, tmp_path / "works.jsonl", progress=lambda *args: calls.append(args))
+        assert calls == [(8192, None), (10_000, None)]
+
+    def test_progress_error(self, tmp_path):
+        def fail(processed, total):
+            raise RuntimeError("stop")
+
+        source = tmp_path / "works.csv"
+        source.write_text("doi\n10.1234/a\n")
+        with pytest.raises(RuntimeError, match="stop"):
+            transform_csv(source, tmp_path / "works.jsonl", progress=fail)
+
+    def test_missing_column(self, tmp_path):
+        source = tmp_path / "works.csv"
+        source.write_text("doi\n10.1234/a\n")
+        with pytest.raises(ValueError, match="column not found: ror"):
+            transform_csv(source, tmp_path / "works.jsonl", {"ror": "normalize_ror"})
+
+    def test_wrong_field_count(self, tmp_path):
+        source = tmp_path / "works.csv"
+        source.write_text("doi,title\n10.1234/a,Title\n10.1234/b\n")
+        with pytest.raises(ValueError, match="record 2 has 1 fields, expected 2"):
+            transform_csv(source, tmp_path / "works.jsonl")
+
+    def test_unterminated_quote(self, tmp_path):
+        source = tmp_path / "works.csv"
+        source.write_text('doi,title\n10.1234/a,"Title\n')
+        with pytest.raises(ValueError, match="unterminated quoted field starting on line 2"):
+            transform_csv(source, tmp_path / "works.jsonl")
+
+    @pytest.mark.parametrize(
+        "transforms,output,match",
+        [
+            ({"doi": "lowercase"}, "works.jsonl", "unknown transform for column doi: lowercase"),
+            (None, "works.csv", "output_path must end in"),
+        ],
+    )
+    def test_invalid_arguments(self, tmp_path, transforms, output, match):
+        source = tmp_path / "works.csv"
+        source.write_text("doi\n10.1234/a\n")
+        with pytest.raises(ValueError, match=match):
+            transform_csv(source, tmp_path / output, transforms)
+
+    def test_invalid_delimiter(self):
+        with pytest.raises(ValueError, match="delimiter must be a single ASCII character"):
+            CsvOptions(delimiter="::")
@@ -0,0 +1,60 @@
+import hashlib
+
+from dmpworks.rust.dedupe import dedupe_works
+
+
+class TestDedupeWorks:
+    def test_doi_equality(self):
+        works = [
+            {"doi": "10.1234/ABC", "title": "Soil carbon", "source": "openalex"},
+            {"doi": "https://doi.org/10.1234/abc", "title": "Something else", "source": "crossref"},
+            {"doi": "10.1234/other", "title": "Unrelated", "source": "crossref"},
+        ]
+        cluster_ids, representatives = dedupe_works(works)
+        assert cluster_ids == [0, 0, 1]
+        assert representatives == [0, 2]
+
+    def test_title_and_year(self):
+        works = [
+            {"doi": "10.1/a", "title": "Soil Carbon in <i>Alpine</i> Meadows", "publication_date": "2021-03-05"},
+            {"doi": "10.1/b", "title": "soil carbon in alpine meadows.", "publication_year": 2021},
+            {"doi": "10.1/c", "title": "Soil carbon in alpine meadows", "publication_date": "2019"},
+        ]
+        cluster_ids, _ = dedupe_works(works)
+        assert cluster_ids == [0, 0, 1]
+
+    def test_fuzzy_title(self):
+        works = [
+            {"doi": "10.1/a", "title": "Soil carbon dynamics in alpine meadows", "publication_year": 2021},
+            {"doi": "10.1/b", "title": "Soil carbon dynamic in alpine meadows", "publication_year": 2021},
+            {"doi": "10.1/c", "title": "Glacier retreat in the Andes", "publication_year": 2021},
+        ]
+        assert dedupe_works(works)[0] == [0, 0, 1]
+        assert dedupe_works(works, title_threshold=1.0)[0] == [0, 1, 2]
+
+    def test_fuzzy_title_word_order(self):
+        works = [
+            {"doi": "10.1/a", "title": "Alpine meadows: soil carbon dynamics", "publication_year": 2021},
+            {"doi": "10.1/b", "title": "Soil carbon dynamics, alpine meadows", "publication_year": 2021},
+        ]
+        assert dedupe_works(works)[0] == [0, 0]
+
+    def test_large_year_block(self):
+        titles = [hashlib.sha256(str(i).encode()).hexdigest()[:32] for i in range(5_000)]
+        works = [{"doi": f"10.1/{i}", "title": title, "publication_year": 2021} for i, title in enumerate(titles)]
+        works.append({"doi": "10.1/dup", "title": titles[123] + "s", "publication_year": 2021})
+        cluster_ids, _ = dedupe_works(works)
+        assert cluster_ids[-1] == cluster_ids[123]
+        assert len(set(cluster_ids)) == 5_000
+
+    def test_representative(self):
+        works = [
+            {"doi": "10.1/a", "title": "Soil carbon", "source": "openalex", "abstract": None},
+            {"doi": "10.1/a", "title": "Soil carbon", "source": "datacite", "abstract": "Full abstract"},
+            {"doi": "10.1/a", "title": "Soil carbon", "source": "crossref", "abstract": None},
+        ]
+        assert dedupe_works(works)[1] == [1]
+        assert dedupe_works(iter(works), source_priority=["crossref", "openalex"])[1] == [2]
+
+    def test_empty(self):
+        assert dedupe_works([]) == ([], [])
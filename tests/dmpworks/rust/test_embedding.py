@@ -0,0 +1,56 @@
+import json
+
+import pytest
+
+from dmpworks.rust import MarkupOptions
+from dmpworks.rust.embedding import EmbeddingTextOptions, prepare_embedding_text, prepare_embedding_text_many
+
+
+class TestPrepareEmbeddingText:
+    def test_markup_and_whitespace(self):
+        text = prepare_embedding_text(
+            "Soil <i>carbon</i>  stocks",
+            "<jats:title>Abstract</jats:title><jats:p>Carbon &amp; nitrogen\n were measured "
+            '<mml:math alttext="x^2"><mml:mi>x</mml:mi></mml:math>.</jats:p>',
+        )
+        assert text == "Soil carbon stocks\nCarbon & nitrogen were measured $x^2$."
+
+    def test_inverted_index(self):
+        index = json.dumps({"Carbon": [0], "was": [1], "measured.": [2]}).encode()
+        assert prepare_embedding_text("Soil", index) == "Soil\nCarbon was measured."
+
+    def test_truncation_keeps_title(self):
+        options = EmbeddingTextOptions(max_tokens=5, separator=". ")
+        text = prepare_embedding_text("Soil carbon", "One two three four five six.", options)
+        assert text == "Soil carbon. One two three"
+        assert prepare_embedding_text("One two three four five six", "Abstract", options) == "One two three four five"
+
+    def test_normalization(self):
+        options = EmbeddingTextOptions(form="NFKC", fold_diacritics=True, case_fold=True, max_tokens=None)
+        assert prepare_embedding_text("Café ﬁeld", None, options) == "cafe field"
+
+    def test_missing_fields(self):
+        assert prepare_embedding_text(None, "<p>Abstract</p>") == "Abstract"
+        assert prepare_embedding_text("Title", None) == "Title"
+        assert prepare_embedding_text("<b></b>", " ") is None
+
+    def test_markup_options(self):
+        options = EmbeddingTextOptions(markup=MarkupOptions())
+        assert prepare_embedding_text("Fish &amp; chips", None, options) == "Fish &amp; chips"
+
+    def test_invalid_form(self):
+        with pytest.raises(ValueError, match="form"):
+            EmbeddingTextOptions(form="NFX")
+
+
+class TestPrepareEmbeddingTextMany:
+    def test_matches_single(self):
+        titles = ["Soil carbon", None, "Ocean"]
+        abstracts = ["Measured <b>carbon</b>.", json.dumps({"Hello": [0]}).encode(), None]
+        assert prepare_embedding_text_many(titles, abstracts, num_threads=2) == [
+            prepare_embedding_text(title, abstract) for title, abstract in zip(titles, abstracts)
+        ]
+
+    def test_length_mismatch(self):
+        with pytest.raises(ValueError, match="same length"):
+            prepare_embedding_text_many(["a"], [])
@@ -0,0 +1,274 @@
+import math
+
+import pytest
+
+from dmpworks.rust.eval import (
+    choose_threshold,
+    cohen_kappa,
+    GroundTruthRecord,
+    krippendorff_alpha,
+    load_ground_truth,
+    pr_curve,
+    ranking_metrics,
+    save_ground_truth,
+)
+
+
+class TestRankingMetrics:
+    ground_truth = {
+        "dmp-a": {"w1": 1, "w3": 1, "w9": 1},
+        "dmp-b": {"w2": 1},
+        "dmp-c": {"w5": 1},
+    }
+    results = {
+        "dmp-a": ["w1", "w2", "w3", "w4"],
+        "dmp-b": ["w7", "w2"],
+        "dmp-x": ["w1"],
+    }
+
+    def test_per_query(self):
+        _, per_query = ranking_metrics(self.ground_truth, self.results, ks=[2, 4])
+        assert list(per_query) == ["dmp-a", "dmp-b", "dmp-c"]
+
+        a = per_query["dmp-a"]
+        assert a["precision@2"] == pytest.approx(0.5)
+        assert a["precision@4"] == pytest.approx(0.5)
+        assert a["recall@4"] == pytest.approx(2 / 3)
+        assert a["mrr@4"] == pytest.approx(1.0)
+        assert a["map@4"] == pytest.approx((1 + 2 / 3) / 3)
+        dcg = 1 + 1 / math.log2(4)
+        idcg = 1 + 1 / math.log2(3) + 1 / math.log2(4)
+        assert a["ndcg@4"] == pytest.approx(dcg / idcg)
+
+        b = per_query["dmp-b"]
+        assert b["mrr@2"] == pytest.approx(0.5)
+        assert b["ndcg@2"] == pytest.approx(1 / math.log2(3))
+
+        assert all(value == 0.0 for value in per_query["dmp-c"].values())
+
+    def test_mean(self):
+        mean, per_query = ranking_metrics(self.ground_truth, self.results, ks=[4])
+        assert list(mean) == ["map@4", "mrr@4", "ndcg@4", "precision@4", "recall@4"]
+        assert mean["recall@4"] == pytest.approx((2 / 3 + 1 + 0) / 3)
+        assert mean["mrr@4"] == pytest.approx(sum(m["mrr@4"] for m in per_query.values()) / 3)
+
+    def test_duplicates_count_once(self):
+        _, per_query = ranking_metrics({"q": {"w1": 1}}, {"q": ["w1", "w1"]}, ks=[2], num_threads=1)
+        assert per_query["q"]["precision@2"] == pytest.approx(0.5)
+
+    def test_empty_ground_truth(self):
+        mean, per_query = ranking_metrics({}, {}, ks=[10])
+        assert mean == {"map@10": 0.0, "mrr@10": 0.0, "ndcg@10": 0.0, "precision@10": 0.0, "recall@10": 0.0}
+        assert per_query == {}
+
+    def test_invalid_ks(self):
+        with pytest.raises(ValueError):
+            ranking_metrics(self.ground_truth, self.results, ks=[0])
+        with pytest.raises(ValueError):
+            ranking_metrics(self.ground_truth, self.results, ks=[])
+
+
+class TestPrCurve:
+    scores = [0.9, 0.8, 0.8, 0.6, 0.4, 0.2]
+    labels = [True, True, False, True, False, False]
+
+    def test_curve(self):
+        thresholds, precision, recall = pr_curve(self.scores, self.labels)
+        assert thresholds == [0.9, 0.8, 0.6, 0.4, 0.2]
+        assert precision == pytest.approx([1.0, 2 / 3, 3 / 4, 3 / 5, 1 / 2])
+        assert recall == pytest.approx([1 / 3, 2 / 3, 1.0, 1.0, 1.0])
+
+    def test_order_independent(self):
+        pairs = sorted(zip(self.scores, self.labels))
+        scores, labels = zip(*pairs)
+        assert pr_curve(list(scores), list(labels)) == pr_curve(self.scores, self.labels)
+
+    def test_empty_and_no_matches(self):
+        assert pr_curve([], []) == ([], [], [])
+        assert pr_curve([0.5, 0.1], [False, False]) == ([0.5, 0.1], [0.0, 0.0], [0.0, 0.0])
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="same length"):
+            pr_curve([0.5], [])
+        with pytest.raises(ValueError, match="score 1 is NaN"):
+            pr_curve([0.5, math.nan], [True, False])
+
+
+class TestChooseThreshold:
+    scores = TestPrCurve.scores
+    labels = TestPrCurve.labels
+
+    def test_target_precision(self):
+        assert choose_threshold(self.scores, self.labels, 0.75) == pytest.approx((0.6, 0.75, 1.0))
+        assert choose_threshold(self.scores, self.labels, 0.9) == pytest.approx((0.9, 1.0, 1 / 3))
+        assert choose_threshold(self.scores, self.labels, 0.5) == pytest.approx((0.6, 0.75, 1.0))
+
+    def test_unreachable(self):
+        assert choose_threshold([0.5], [False], 0.5) is None
+        assert choose_threshold([], [], 0.5) is None
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="target_precision"):
+            choose_threshold(self.scores, self.labels, 1.5)
+
+
+class TestGroundTruth:
+    def test_load_csv(self, tmp_path):
+        path = tmp_path / "labels.csv"
+        path.write_text(
+            "dmp_doi,work_doi,status,reviewer,notes\n"
+            "https://doi.org/10.48321/D1ABC,https://doi.org/10.1234/ABC,accepted,jane,\n"
+            "D2DEF,doi:10.5678/xyz,REJECTED,,\"Wrong, project\"\n"
+            "10.48321/d2def,10.1234/abc, Pending ,,\n"
+        )
+        assert load_ground_truth(path) == [
+            GroundTruthRecord("10.48321/d1abc", "10.1234/abc", "ACCEPTED", "jane"),
+            GroundTruthRecord("10.48321/d2def", "10.5678/xyz", "REJECTED"),
+            GroundTruthRecord("10.48321/d2def", "10.1234/abc", "PENDING"),
+        ]
+
+    def test_load_jsonl(self, tmp_path):
+        path = tmp_path / "labels.jsonl"
+        path.write_text(
+            '{"dmp_doi": "10.48321/D1ABC", "work_doi": "10.1234/abc", "status": "ACCEPTED", "reviewer": "jane"}\n'
+            "\n"
+            '{"dmp_doi": "10.48321/D1ABC", "work_doi": "10.1234/def", "status": "REJECTED", "reviewer": null}\n'
+        )
+        records = load_ground_truth(path)
+        assert [record.to_dict() for record in records] == [
+            {"dmp_doi": "10.48321/d1abc", "work_doi": "10.1234/abc", "status": "ACCEPTED", "reviewer": "jane"},
+            {"dmp_doi": "10.48321/d1abc", "work_doi": "10.1234/def", "status": "REJECTED", "reviewer": None},
+        ]
+
+    def test_invalid_rows(self, tmp_path):
+        path = tmp_path / "labels.csv"
+        path.write_text(
+            "dmp_doi,work_doi,status\n"
+            "10.1234/not-a-dmp,10.1234/abc,ACCEPTED\n"
+            "10.48321/D1ABC,not a doi,ACCEPTED\n"
+            "10.48321/D1ABC,10.1234/abc,MAYBE\n"
+            "10.48321/D1ABC,10.1234/abc,ACCEPTED\n"
+            "10.48321/d1abc,https://doi.org/10.1234/ABC,REJECTED\n"
+            "10.48321/D1ABC,10.1234/def,ACCEPTED,extra\n"
+        )
+        with pytest.raises(ValueError) as excinfo:
+            load_ground_truth(path)
+        message = str(excinfo.value)
+        assert message.startswith("5 invalid rows in ground truth file")
+        assert "line 2: dmp_doi: invalid DMP ID, expected a 10.48321 DOI: 10.1234/not-a-dmp" in message
+        assert "line 3: work_doi: invalid DOI: not a doi" in message
+        assert "line 4: status: unknown status: MAYBE" in message
+        assert "line 6: duplicate of the pair on line 5" in message
+        assert "line 7: expected 3 fields, got 4" in message
+
+    def test_invalid_jsonl_rows(self, tmp_path):
+        path = tmp_path / "labels.ndjson"
+        path.write_text('{"dmp_doi": "10.48321/D1ABC", "work_doi": 5, "status": "ACCEPTED"}\n[1]\n{"dmp_doi"\n')
+        with pytest.raises(ValueError) as excinfo:
+            load_ground_truth(path)
+        message = str(excinfo.value)
+        assert "line 1: work_doi: work_doi must be a string" in message
+        assert "line 2: expected a JSON object" in message
+        assert "line 3: invalid json" in message
+
+    def test_require_reviewer(self, tmp_path):
+        path = tmp_path / "labels.csv"
+        path.write_text("dmp_doi,work_doi,status,reviewer\nD1ABC,10.1234/abc,PENDING,\nD1ABC,10.1234/def,ACCEPTED, \n")
+        assert len(load_ground_truth(path)) == 2
+        with pytest.raises(ValueError, match="line 3: reviewer: missing reviewer of ACCEPTED pair"):
+            load_ground_truth(path, require_reviewer=True)
+
+    def test_missing_columns(self, tmp_path):
+        path = tmp_path / "labels.csv"
+        path.write_text("dmp_doi,doi\nD1ABC,10.1234/abc\n")
+        with pytest.raises(ValueError, match="missing columns: work_doi, status"):
+            load_ground_truth(path)
+
+    def test_unknown_format(self, tmp_path):
+        with pytest.raises(ValueError, match=r"must end in \.csv, \.jsonl or \.ndjson"):
+            load_ground_truth(tmp_path / "labels.tsv")
+        with pytest.raises(ValueError, match=r"must end in \.csv, \.jsonl or \.ndjson"):
+            save_ground_truth(tmp_path / "labels.csv.gz", [])
+
+    def test_record(self):
+        record = GroundTruthRecord("D1ABC", "https://doi.org/10.1234/ABC", "rejected")
+        assert (record.dmp_doi, record.work_doi, record.status, record.reviewer) == (
+            "10.48321/d1abc",
+            "10.1234/abc",
+            "REJECTED",
+            None,
+        )
+        assert repr(record) == (
+            'GroundTruthRecord(dmp_doi="10.48321/d1abc", work_doi="10.1234/abc", status="REJECTED", reviewer=None)'
+        )
+        assert len({record, GroundTruthRecord("10.48321/d1abc", "10.1234/abc", "REJECTED")}) == 1
+        with pytest.raises(ValueError, match="status: unknown status: maybe"):
+            GroundTruthRecord("D1ABC", "10.1234/abc", "maybe")
+
+    @pytest.mark.parametrize("name", ["labels.csv", "labels.jsonl"])
+    def test_round_trip(self, tmp_path, name):
+        records = [
+            GroundTruthRecord("D1ABC", "10.1234/abc", "ACCEPTED", 'Jane "JD" Doe, PhD'),
+            GroundTruthRecord("D1ABC", "10.1234/def", "PENDING"),
+        ]
+        path = tmp_path / name
+        save_ground_truth(path, records)
+        assert load_ground_truth(path) == records
+
+
+class TestCohenKappa:
+    def test_kappa(self):
+        # 20 pairs both accepted, 5 and 10 where they disagree, 15 both rejected
+        a = ["ACCEPTED"] * 25 + ["REJECTED"] * 25
+        b = ["ACCEPTED"] * 20 + ["REJECTED"] * 5 + ["ACCEPTED"] * 10 + ["REJECTED"] * 15
+        assert cohen_kappa(a, b) == pytest.approx(0.4)
+        assert cohen_kappa(a, a) == pytest.approx(1.0)
+
+    def test_missing_labels(self):
+        assert cohen_kappa([1, 0, None, 1], [1, 0, 1, None]) == pytest.approx(1.0)
+        assert cohen_kappa([1.0, 0, 1], [1, 0.0, True]) == pytest.approx(1.0)
+
+    def test_undefined(self):
+        assert cohen_kappa([], []) is None
+        assert cohen_kappa([None], ["ACCEPTED"]) is None
+        assert cohen_kappa(["ACCEPTED"] * 3, ["ACCEPTED"] * 3) is None
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="same length"):
+            cohen_kappa([1, 0], [1])
+
+
+class TestKrippendorffAlpha:
+    # Krippendorff's example of four observers labelling twelve items
+    matrix = [
+        [1, 2, 3, 3, 2, 1, 4, 1, 2, None, None, None],
+        [1, 2, 3, 3, 2, 2, 4, 1, 2, 5, None, 3],
+        [None, 3, 3, 3, 2, 3, 4, 2, 2, 5, 1, None],
+        [1, 2, 3, 3, 2, 4, 4, 1, 2, 5, 1, None],
+    ]
+
+    def test_nominal(self):
+        assert krippendorff_alpha(self.matrix) == pytest.approx(0.743, abs=1e-3)
+        labels = [["ACCEPTED", "REJECTED", "ACCEPTED"], ["ACCEPTED", "REJECTED", "ACCEPTED"]]
+        assert krippendorff_alpha(labels) == pytest.approx(1.0)
+
+    def test_interval(self):
+        assert krippendorff_alpha(self.matrix, level="interval") == pytest.approx(0.849, abs=1e-3)
+
+    def test_matches_cohen_kappa_closely(self):
+        a = ["ACCEPTED"] * 25 + ["REJECTED"] * 25
+        b = ["ACCEPTED"] * 20 + ["REJECTED"] * 5 + ["ACCEPTED"] * 10 + ["REJECTED"] * 15
+        assert krippendorff_alpha([a, b]) == pytest.approx(cohen_kappa(a, b), abs=0.02)
+
+    def test_undefined(self):
+        assert krippendorff_alpha([]) is None
+        assert krippendorff_alpha([[1, None], [None, 1]]) is None
+        assert krippendorff_alpha([[1, 1], [1, 1]]) is None
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="same length"):
+            krippendorff_alpha([[1, 2], [1]])
+        with pytest.raises(ValueError, match="unknown level: ratio"):
+            krippendorff_alpha(self.matrix, level="ratio")
+        with pytest.raises(ValueError, match="requires numeric labels"):
+            krippendorff_alpha([["ACCEPTED", 1], [1, 1]], level="interval")
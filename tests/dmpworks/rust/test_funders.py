@@ -0,0 +1,55 @@
+import pytest
+
+from dmpworks.rust.funders import FunderRegistry, match_funder, normalize_funder_name
+
+
+class TestNormalizeFunderName:
+    @pytest.mark.parametrize(
+        "name",
+        ["NSF", "N.S.F.", "National Science Foundation (NSF)", "The National Science Foundation"],
+    )
+    def test_variants(self, name):
+        assert normalize_funder_name(name) == "national science foundation"
+
+    def test_punctuation_and_stop_words(self):
+        assert normalize_funder_name("Bill &amp; Melinda Gates Foundation") == "bill melinda gates foundation"
+        assert normalize_funder_name("Deutsche Forschungsgemeinschaft, für") == "deutsche forschungsgemeinschaft"
+
+    def test_lowercase_acronym_not_expanded(self):
+        assert normalize_funder_name("nsf") == "nsf"
+
+    def test_only_aside(self):
+        assert normalize_funder_name("(NSF)") == "national science foundation"
+
+    def test_empty(self):
+        assert normalize_funder_name(None) is None
+        assert normalize_funder_name("The (of)") is None
+
+
+class TestMatchFunder:
+    registry = FunderRegistry(
+        {
+            "100000001": ["National Science Foundation", "NSF"],
+            "100000002": ["National Institutes of Health", "NIH"],
+            "501100001711": ["Swiss National Science Foundation"],
+        }
+    )
+
+    def test_len(self):
+        assert len(self.registry) == 5
+
+    def test_exact(self):
+        assert match_funder("National Science Foundation (NSF)", self.registry) == ("100000001", 1.0)
+        assert match_funder("NIH", self.registry) == ("100000002", 1.0)
+
+    def test_fuzzy(self):
+        funder_id, score = match_funder("National Institute of Health", self.registry)
+        assert funder_id == "100000002"
+        assert 0.9 <= score < 1.0
+
+    def test_threshold(self):
+        assert match_funder("Wellcome Trust", self.registry) is None
+        assert match_funder("Wellcome Trust", self.registry, threshold=0.0) is not None
+
+    def test_none(self):
+        assert match_funder(None, self.registry) is None
@@ -0,0 +1,59 @@
+import pytest
+
+from dmpworks.rust.fusion import fuse_rankings, normalize_scores
+
+LEXICAL = [("w1", 12.0), ("w2", 8.0), ("w3", 4.0)]
+KNN = [("w2", 0.9), ("w4", 0.8), ("w1", 0.5)]
+
+
+class TestNormalizeScores:
+    def test_minmax(self):
+        assert normalize_scores([12.0, 8.0, 4.0]) == [1.0, 0.5, 0.0]
+        assert normalize_scores([3.0, 3.0]) == [1.0, 1.0]
+        assert normalize_scores([]) == []
+
+    def test_zscore(self):
+        assert normalize_scores([12.0, 8.0, 4.0], "zscore") == pytest.approx([1.2247, 0.0, -1.2247], abs=1e-4)
+        assert normalize_scores([3.0, 3.0], "zscore") == [0.0, 0.0]
+
+    def test_unknown(self):
+        with pytest.raises(ValueError, match="normalization must be"):
+            normalize_scores([1.0], "softmax")
+
+
+class TestFuseRankings:
+    def test_rrf(self):
+        fused = fuse_rankings([LEXICAL, KNN])
+        assert [doc for doc, _ in fused] == ["w2", "w1", "w4", "w3"]
+        assert dict(fused)["w2"] == pytest.approx(1 / 62 + 1 / 61)
+        assert dict(fuse_rankings([LEXICAL], rrf_k=0.0)) == pytest.approx({"w1": 1.0, "w2": 0.5, "w3": 1 / 3})
+
+    def test_combsum(self):
+        fused = dict(fuse_rankings([LEXICAL, KNN], method="combsum", normalization="minmax"))
+        assert fused == pytest.approx({"w1": 1.0, "w2": 0.5 + 1.0, "w3": 0.0, "w4": 0.75})
+        raw = dict(fuse_rankings([LEXICAL, KNN], method="combsum"))
+        assert raw["w1"] == pytest.approx(12.5)
+
+    def test_combmnz(self):
+        fused = dict(fuse_rankings([LEXICAL, KNN], method="combmnz", normalization="minmax"))
+        assert fused == pytest.approx({"w1": 2.0, "w2": 3.0, "w3": 0.0, "w4": 0.75})
+
+    def test_weights(self):
+        fused = fuse_rankings([LEXICAL, KNN], method="combsum", normalization="minmax", weights=[1.0, 0.0])
+        assert [doc for doc, _ in fused] == ["w1", "w2", "w3", "w4"]
+        with pytest.raises(ValueError, match="weights must have one weight"):
+            fuse_rankings([LEXICAL, KNN], weights=[1.0])
+
+    def test_duplicates_and_ties(self):
+        fused = fuse_rankings([[("a", 1.0), ("b", 1.0), ("a", 0.5)], [("b", 1.0), ("a", 1.0)]])
+        assert [doc for doc, _ in fused] == ["a", "b"]
+        assert fused[0][1] == fused[1][1] == pytest.approx(1 / 61 + 1 / 62)
+        assert fuse_rankings([]) == []
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="method must be"):
+            fuse_rankings([LEXICAL], method="borda")
+        with pytest.raises(ValueError, match="normalization only applies"):
+            fuse_rankings([LEXICAL], normalization="minmax")
+        with pytest.raises(ValueError, match="rrf_k"):
+            fuse_rankings([LEXICAL], rrf_k=-1.0)
@@ -0,0 +1,132 @@
+import gzip
+import json
+
+import pytest
+
+from dmpworks.rust.institutions import (
+    InstitutionIndex,
+    match_institution,
+    match_institution_many,
+    normalize_institution_name,
+)
+
+ENTRIES = {
+    "01an7q238": ["University of California, Berkeley", "UC Berkeley"],
+    "042nb2s44": ["Massachusetts Institute of Technology"],
+    "046rm7j60": ["University of California, Los Angeles"],
+    "05t99sp05": ["Manipal Institute of Technology"],
+    "03vek6s52": ["Harvard University"],
+}
+ACRONYMS = {"042nb2s44": ["MIT"], "05t99sp05": ["MIT"], "046rm7j60": ["UCLA"]}
+
+
+class TestNormalizeInstitutionName:
+    @pytest.mark.parametrize(
+        "name",
+        ["University of Montana", "Univ. of Montana", "The University of Montana", "UNIVERSITY OF MONTANA (UM)"],
+    )
+    def test_variants(self, name):
+        assert normalize_institution_name(name) == "university of montana"
+
+    def test_folding(self):
+        assert normalize_institution_name("Universität Zürich") == "university zurich"
+        assert normalize_institution_name("King’s College London") == "kings college london"
+        assert normalize_institution_name("Texas A&amp;M University") == "texas a and m university"
+        assert normalize_institution_name("Natl. Ctr. for Atmospheric Research") == (
+            "national center for atmospheric research"
+        )
+
+    def test_empty(self):
+        assert normalize_institution_name(None) is None
+        assert normalize_institution_name(" - ") is None
+
+
+class TestMatchInstitution:
+    index = InstitutionIndex(ENTRIES, acronyms=ACRONYMS)
+
+    def test_len(self):
+        assert len(self.index) == 6
+        assert self.index.num_institutions == 5
+
+    def test_exact(self):
+        assert match_institution("Univ. of California, Berkeley", self.index) == ("01an7q238", 1.0)
+        assert match_institution("uc berkeley", self.index) == ("01an7q238", 1.0)
+
+    def test_acronyms(self):
+        assert match_institution("UCLA", self.index) == ("046rm7j60", 1.0)
+        assert match_institution("U.C.L.A.", self.index) == ("046rm7j60", 1.0)
+        assert match_institution("MIT", self.index) is None
+        assert match_institution("ucla", self.index) is None
+
+    def test_affiliation(self):
+        affiliation = "Department of Molecular Biology, Harvard University, Cambridge, MA 02138, USA"
+        assert match_institution(affiliation, self.index) == ("03vek6s52", 1.0)
+
+    def test_fuzzy(self):
+        institution, score = match_institution("Massachusets Institute of Technology", self.index)
+        assert institution == "042nb2s44"
+        assert 0.9 <= score < 1.0
+        assert match_institution("Harvard Medical School", self.index) is None
+        assert match_institution("Harvard Medical School", self.index, threshold=0.5)[0] == "03vek6s52"
+        assert match_institution("Stanford University", self.index) is None
+
+    def test_empty(self):
+        assert match_institution(None, self.index) is None
+        assert match_institution("Harvard University", InstitutionIndex({})) is None
+
+    def test_many(self):
+        names = ["UC Berkeley", None, "Stanford University", "UCLA"]
+        assert match_institution_many(names, self.index, num_threads=2) == [
+            ("01an7q238", 1.0),
+            None,
+            None,
+            ("046rm7j60", 1.0),
+        ]
+
+
+class TestFromRorDump:
+    records = [
+        {
+            "id": "https://ror.org/02mhbdp94",
+            "status": "active",
+            "names": [
+                {"value": "Universidad de los Andes", "types": ["ror_display", "label"], "lang": "es"},
+                {"value": "University of the Andes", "types": ["label"], "lang": "en"},
+                {"value": "Uniandes", "types": ["alias"], "lang": None},
+                {"value": "UNIANDES", "types": ["acronym"], "lang": None},
+            ],
+        },
+        {
+            "id": "https://ror.org/03yrm5c26",
+            "status": "active",
+            "name": "California Digital Library",
+            "aliases": [],
+            "acronyms": ["CDL"],
+            "labels": [{"label": "Bibliothèque numérique de Californie", "iso639": "fr"}],
+        },
+        {
+            "id": "https://ror.org/00f54p054",
+            "status": "withdrawn",
+            "names": [{"value": "Stanford University", "types": ["ror_display"]}],
+        },
+    ]
+
+    @pytest.mark.parametrize("compressed", [False, True])
+    def test_dump(self, tmp_path, compressed):
+        data = json.dumps(self.records).encode()
+        path = tmp_path / "ror.json"
+        path.write_bytes(gzip.compress(data) if compressed else data)
+        index = InstitutionIndex.from_ror_dump(path, aliases={"03yrm5c26": ["UC Curation Center"]})
+        assert index.num_institutions == 2
+        assert match_institution("University of the Andes", index) == ("02mhbdp94", 1.0)
+        assert match_institution("UNIANDES", index) == ("02mhbdp94", 1.0)
+        assert match_institution("CDL", index) == ("03yrm5c26", 1.0)
+        assert match_institution("Bibliotheque numerique de Californie", index) == ("03yrm5c26", 1.0)
+        assert match_institution("UC Curation Center", index) == ("03yrm5c26", 1.0)
+        assert match_institution("Stanford University", index) is None
+
+    def test_invalid_dump(self, tmp_path):
+        path = tmp_path / "ror.json"
+        path.write_text('{"id": "https://ror.org/02mhbdp94"}')
+        with pytest.raises(ValueError, match="invalid ROR dump"):
+            InstitutionIndex.from_ror_dump(path)
@@ -0,0 +1,35 @@
+from dmpworks.rust.keyphrases import extract_keyphrases, extract_keyphrases_many
+
+ABSTRACT = (
+    "Compatibility of systems of linear constraints over the set of natural numbers. Criteria of compatibility of a "
+    "system of linear Diophantine equations, strict inequations, and nonstrict inequations are considered."
+)
+
+
+class TestExtractKeyphrases:
+    def test_rake_scores(self):
+        assert extract_keyphrases(ABSTRACT, top_n=5) == [
+            ("linear diophantine equations", 8.5),
+            ("linear constraints", 4.5),
+            ("natural numbers", 4.0),
+            ("strict inequations", 4.0),
+            ("nonstrict inequations", 4.0),
+        ]
+
+    def test_delimiters(self):
+        phrases = [phrase for phrase, _ in extract_keyphrases("Soil carbon in alpine meadows; 2021 soil-carbon data.")]
+        assert phrases == ["soil carbon data", "soil carbon", "alpine meadows"]
+
+    def test_distinct_phrases(self):
+        assert extract_keyphrases("Soil carbon. Soil carbon.") == [("soil carbon", 4.0)]
+
+    def test_long_phrases_dropped(self):
+        assert extract_keyphrases("Soil organic carbon stock change estimates") == []
+
+    def test_empty(self):
+        assert extract_keyphrases("") == []
+        assert extract_keyphrases("of the and") == []
+        assert extract_keyphrases(None) == []
+
+    def test_many(self):
+        assert extract_keyphrases_many(["Soil carbon", None], top_n=1) == [[("soil carbon", 4.0)], []]
@@ -0,0 +1,103 @@
+import pickle
+
+import pytest
+
+from dmpworks.rust.lookup import LookupTable
+
+
+class TestLookupTable:
+    def test_build(self, tmp_path):
+        path = tmp_path / "funders.table"
+        table = LookupTable.build(
+            path,
+            {
+                "National Science Foundation": "021nxhr62",
+                "NSF": ["021nxhr62", "05gq02987"],
+                "Wellcome Trust": ["029chgv08"],
+                "-": ["ignored"],
+                "Empty": [],
+            },
+            normalization="funder",
+        )
+        assert table.path == path
+        assert table.normalization == "funder"
+        assert len(table) == 2
+        assert table.get("THE NATIONAL SCIENCE FOUNDATION") == "021nxhr62"
+        assert table.get_all("National Science Foundation") == ["021nxhr62", "05gq02987"]
+        assert table.get("Wellcome Trust") == "029chgv08"
+        assert table.get("Gates Foundation") is None
+        assert table.get("Gates Foundation", "unknown") == "unknown"
+        assert table.get_all("Gates Foundation") == []
+        assert table.get_many(["NSF", None, "Gates Foundation"]) == ["021nxhr62", None, None]
+        assert "wellcome trust" in table
+        assert "Empty" not in table
+
+    def test_exact(self, tmp_path):
+        table = LookupTable.build(tmp_path / "ids.table", {"Bill": ["William"], "kāne": ["Kane"]})
+        assert table.normalization == "exact"
+        assert table.get("Bill") == "William"
+        assert table.get("bill") is None
+        assert table.get("kāne") == "Kane"
+        assert table.get("") is None
+
+    def test_names(self, tmp_path):
+        table = LookupTable.build(
+            tmp_path / "nicknames.table",
+            {"Chris": ["Christopher", "Christine"], "Zoë": ["Zoe"]},
+            normalization="name",
+        )
+        assert table.get_all("CHRIS") == ["Christopher", "Christine"]
+        assert table.get("zoe") == "Zoe"
+
+    def test_reopen(self, tmp_path):
+        path = tmp_path / "institutions.table"
+        LookupTable.build(path, {"Univ. of Montana": "0078xmy34"}, normalization="institution")
+        table = LookupTable(path)
+        assert table.normalization == "institution"
+        assert table.get("The University of Montana") == "0078xmy34"
+        assert not list(tmp_path.glob("*.tmp"))
+
+    def test_rebuild(self, tmp_path):
+        path = tmp_path / "funders.table"
+        old = LookupTable.build(path, {"a": "1"})
+        new = LookupTable.build(path, {"b": "2"})
+        assert old.get("a") == "1"
+        assert new.get("a") is None
+        assert new.get("b") == "2"
+
+    def test_empty(self, tmp_path):
+        table = LookupTable.build(tmp_path / "empty.table", {})
+        assert len(table) == 0
+        assert table.get("a") is None
+
+    def test_pickle(self, tmp_path):
+        table = LookupTable.build(tmp_path / "funders.table", {"NSF": "021nxhr62"})
+        unpickled = pickle.loads(pickle.dumps(table))
+        assert unpickled.path == table.path
+        assert unpickled.get("NSF") == "021nxhr62"
+
+    def test_invalid(self, tmp_path):
+        with pytest.raises(ValueError, match="unknown key normalization"):
+            LookupTable.build(tmp_path / "funders.table", {}, normalization="ror")
+        path = tmp_path / "funders.json"
+        path.write_text('{"NSF": "021nxhr62"}')
+        with pytest.raises(ValueError, match="invalid lookup table"):
+            LookupTable(path)
+        path = tmp_path / "truncated.table"
+        LookupTable.build(path, {"National Science Foundation": "021nxhr62"})
+        path.write_bytes(path.read_bytes()[:40])
+        with pytest.raises(ValueError, match="invalid lookup table"):
+            LookupTable(path)
+        path = tmp_path / "overflowing.table"
+        LookupTable.build(path, {"NSF": "021nxhr62", "NIH": "01cwqze88"})
+        data = bytearray(path.read_bytes())
+        data[40:48] = (2**64 - 1).to_bytes(8, "little")
+        path.write_bytes(bytes(data))
+        with pytest.raises(ValueError, match="invalid lookup table"):
+            LookupTable(path)
+        path = tmp_path / "blank.table"
+        path.write_bytes(b"")
+        with pytest.raises(ValueError, match="invalid lookup table"):
+            LookupTable(path)
+        with pytest.raises(FileNotFoundError):
+            LookupTable(tmp_path / "missing.table")
@@ -0,0 +1,60 @@
+from importlib.resources import files
+import json
+
+import pytest
+
+from dmpworks.rust.mappings import generate_index_mapping, IndexSchemaOptions
+
+
+def load_mapping_file(name):
+    return json.loads((files("dmpworks.opensearch.mappings") / name).read_text(encoding="utf-8"))
+
+
+class TestGenerateIndexMapping:
+    @pytest.mark.parametrize("index,filename", [("works", "works-mapping.json"), ("dmps", "dmps-mapping.json")])
+    def test_matches_mapping_files(self, index, filename):
+        assert generate_index_mapping(IndexSchemaOptions(index)) == load_mapping_file(filename)
+
+    def test_transformer_fields(self):
+        properties = generate_index_mapping(IndexSchemaOptions("works"))["mappings"]["properties"]
+        authors = properties["authors"]["properties"]
+        assert authors["orcid"] == {"type": "keyword", "normalizer": "lowercase"}
+        assert authors["surname"]["fields"]["keyword"]["normalizer"] == "name_normalizer"
+        assert set(properties["institutions"]["properties"]) == {"name", "ror"}
+
+    def test_settings(self):
+        mapping = generate_index_mapping(IndexSchemaOptions("works", number_of_shards=2, number_of_replicas=0))
+        assert mapping["settings"]["number_of_shards"] == 2
+        assert mapping["settings"]["number_of_replicas"] == 0
+        settings = generate_index_mapping(IndexSchemaOptions("dmps", number_of_replicas=2))["settings"]
+        assert "number_of_shards" not in settings
+        assert settings["number_of_replicas"] == 2
+
+    def test_embedding(self):
+        options = IndexSchemaOptions(
+            "dmps", embedding_dimension=384, embedding_field="title_embedding", space_type="l2"
+        )
+        mapping = generate_index_mapping(options)
+        assert mapping["settings"]["index.knn"] is True
+        assert mapping["mappings"]["properties"]["title_embedding"] == {
+            "type": "knn_vector",
+            "dimension": 384,
+            "method": {"name": "hnsw", "space_type": "l2", "engine": "lucene"},
+        }
+        assert "index.knn" not in generate_index_mapping(IndexSchemaOptions("dmps"))["settings"]
+
+    def test_title_keyword(self):
+        properties = generate_index_mapping(IndexSchemaOptions("works", title_keyword=True))["mappings"]["properties"]
+        assert properties["title"]["fields"] == {"keyword": {"type": "keyword", "normalizer": "name_normalizer"}}
+        assert "fields" not in properties["abstract_text"]
+
+    def test_invalid(self):
+        assert IndexSchemaOptions("works").index == "works"
+        with pytest.raises(ValueError, match="index must be"):
+            IndexSchemaOptions("funders")
+        with pytest.raises(ValueError, match="space_type"):
+            IndexSchemaOptions("works", embedding_dimension=384, space_type="cosine")
+        with pytest.raises(ValueError, match="embedding_dimension"):
+            IndexSchemaOptions("works", embedding_dimension=0)
+        with pytest.raises(ValueError, match="number_of_shards"):
+            IndexSchemaOptions("works", number_of_shards=0)
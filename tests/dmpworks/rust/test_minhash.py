@@ -0,0 +1,54 @@
+import pytest
+
+from dmpworks.rust.minhash import lsh_candidate_pairs, minhash_signature, minhash_signature_many
+
+
+def similarity(a, b):
+    return sum(x == y for x, y in zip(a, b)) / len(a)
+
+
+class TestMinhashSignature:
+    def test_deterministic(self):
+        signature = minhash_signature("Soil carbon dynamics in alpine meadows", num_perm=64)
+        assert len(signature) == 64
+        assert signature == minhash_signature("Soil carbon dynamics in alpine meadows", num_perm=64)
+
+    def test_folding(self):
+        assert minhash_signature("Soil Carbon: Alpine Meadows!") == minhash_signature("soil carbon alpine meadows")
+
+    def test_similarity(self):
+        a = minhash_signature("Soil carbon dynamics in alpine meadows")
+        b = minhash_signature("Soil carbon dynamics in alpine meadows (version 2)")
+        c = minhash_signature("Glacier retreat in the Andes")
+        assert similarity(a, b) > 0.5
+        assert similarity(a, c) < 0.2
+
+    def test_empty(self):
+        assert minhash_signature("", num_perm=4) == [2**64 - 1] * 4
+
+    def test_invalid_num_perm(self):
+        with pytest.raises(ValueError):
+            minhash_signature("text", num_perm=0)
+
+    def test_many(self):
+        signatures = minhash_signature_many(["Soil carbon", None], num_perm=8)
+        assert signatures == [minhash_signature("Soil carbon", num_perm=8), None]
+
+
+class TestLshCandidatePairs:
+    def test_near_duplicates(self):
+        signatures = minhash_signature_many(
+            [
+                "Soil carbon dynamics in alpine meadows",
+                "Glacier retreat in the Andes",
+                "Soil carbon dynamics in alpine meadows, version 2",
+                None,
+                "",
+                "",
+            ]
+        )
+        assert lsh_candidate_pairs(signatures, num_bands=32) == [(0, 2)]
+
+    def test_invalid_num_bands(self):
+        with pytest.raises(ValueError):
+            lsh_candidate_pairs([], num_bands=0)
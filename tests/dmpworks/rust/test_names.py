@@ -0,0 +1,80 @@
+import pytest
+
+from dmpworks.rust import author_overlap, compare_names, initials_compatible, NameOptions, parse_name
+from dmpworks.rust.names import parse_name_many_struct, parse_name_struct, parse_names_list_struct, ParsedName
+
+
+class TestParsedName:
+    def test_attributes(self):
+        parsed = parse_name_struct(raw_full="Dr. Martin Luther King Jr.")
+        assert parsed.first_initial == "M"
+        assert parsed.given_name == "Martin"
+        assert parsed.middle_initials == "L"
+        assert parsed.middle_names == "Luther"
+        assert parsed.surname == "King"
+        assert parsed.full == "Dr. Martin Luther King Jr."
+        assert parsed.title == "Dr."
+        assert parsed.suffix == "Jr."
+
+    def test_matches_parse_name(self):
+        for raw_full in ["John Doe", "Doe, John", "Wang Wei", "Org Inc.", None]:
+            parsed = parse_name_struct(raw_full=raw_full)
+            assert parsed.to_dict() == {**parse_name(raw_full=raw_full)._asdict(), "suffix": None, "title": None}
+        parsed = parse_name_struct("Jane", "SMITH", recase_surname=True)
+        assert parsed.to_dict() == {
+            **parse_name("Jane", "SMITH", recase_surname=True)._asdict(),
+            "suffix": None,
+            "title": None,
+        }
+
+    def test_equality_and_hash(self):
+        assert parse_name_struct(raw_full="John Doe") == parse_name_struct(raw_full="John Doe")
+        assert parse_name_struct(raw_full="John Doe") != parse_name_struct(raw_full="Jane Doe")
+        assert len({parse_name_struct(raw_full="John Doe"), parse_name_struct(raw_full="John Doe")}) == 1
+        assert ParsedName(surname="Doe") == ParsedName(surname="Doe", given_name=None)
+
+    def test_repr(self):
+        assert repr(ParsedName(first_initial="J", surname="O'Neil")) == (
+            "ParsedName(first_initial='J', given_name=None, middle_initials=None, middle_names=None, "
+            "surname=\"O'Neil\", full=None, suffix=None, title=None)"
+        )
+
+    def test_frozen(self):
+        with pytest.raises(AttributeError):
+            ParsedName().surname = "Doe"
+
+    def test_comparison(self):
+        a = parse_name_struct(raw_full="J. R. Smith")
+        b = parse_name_struct(raw_full="John Robert Smith")
+        assert initials_compatible(a, b) == initials_compatible(parse_name(raw_full="J. R. Smith"), b)
+        assert initials_compatible(a, b) > 0.0
+
+    def test_compare_names(self):
+        a = parse_name_struct(raw_full="Dr. John Smith Jr.")
+        assert compare_names(a, "Smith, J.") == compare_names("Dr. John Smith Jr.", "Smith, J.")
+        assert compare_names(a, parse_name_struct(raw_full="Jane Doe")).score == 0.0
+        assert compare_names(ParsedName(first_initial="J", surname="Smith"), "John Smith").exact_surname
+
+    def test_author_overlap(self):
+        contributors = [parse_name_struct(raw_full="Jane Doe"), None, "Smith, J."]
+        overlap = author_overlap(contributors, parse_names_list_struct("J. Smith; J. Doe"))
+        assert overlap == author_overlap(["Jane Doe", None, "Smith, J."], ["J. Smith", "J. Doe"])
+        assert [(i, j) for i, j, _ in overlap.pairs] == [(0, 1), (2, 0)]
+
+
+class TestBatch:
+    def test_parse_name_many_struct(self):
+        names = ["John Doe", None, "Doe, Jane"]
+        parsed = parse_name_many_struct(names, num_threads=2)
+        assert parsed == [parse_name_struct(raw_full=name) for name in names]
+
+    def test_parse_names_list_struct(self):
+        parsed = parse_names_list_struct("Smith, J.; Doe, A. and Lee, K.")
+        assert [name.surname for name in parsed] == ["Smith", "Doe", "Lee"]
+        assert parse_names_list_struct(None) == []
+
+    def test_options(self):
+        options = NameOptions(surname_particles=True)
+        assert parse_name_struct(raw_full="Jan ten Brink", options=options).surname == "ten Brink"
+        assert parse_name_many_struct(["Jan ten Brink"], options=options)[0].surname == "ten Brink"
+        assert parse_names_list_struct("Jan ten Brink", options=options)[0].surname == "ten Brink"
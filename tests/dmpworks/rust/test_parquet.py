@@ -0,0 +1,317 @@
+import datetime
+import json
+
+import pyarrow as pa
+import pyarrow.parquet as pq
+import pytest
+
+from dmpworks.rust import transform_crossref_work
+from dmpworks.rust.parquet import (
+    ParquetWriteOptions,
+    WorksFilter,
+    diff_snapshots,
+    read_candidates,
+    read_works_parquet,
+    write_candidates,
+    write_candidates_many,
+    write_works_parquet,
+)
+
+
+def crossref_work(doi, **fields):
+    message = {
+        "DOI": doi,
+        "title": ["Soil carbon in alpine meadows"],
+        "author": [{"given": "Jane A.", "family": "Smith", "ORCID": "http://orcid.org/0000-0002-1825-0097"}],
+        "ISSN": ["1234-5679"],
+        "funder": [{"name": "National Science Foundation", "DOI": "10.13039/100000001", "award": ["1234567"]}],
+        "issued": {"date-parts": [[2021, 3]]},
+        **fields,
+    }
+    return transform_crossref_work(json.dumps(message).encode())
+
+
+class TestWriteWorksParquet:
+    def test_round_trip(self, tmp_path):
+        path = tmp_path / "works.parquet"
+        works = [crossref_work("10.1234/a"), crossref_work("10.1234/b", title=None, author=[])]
+        assert write_works_parquet(iter(works), path) == 2
+
+        rows = pq.read_table(path).to_pylist()
+        assert [row["doi"] for row in rows] == ["10.1234/a", "10.1234/b"]
+        assert rows[0]["title"] == "Soil carbon in alpine meadows"
+        assert rows[0]["authors"][0]["surname"] == "Smith"
+        assert rows[0]["authors"][0]["orcid"] == "0000-0002-1825-0097"
+        assert rows[0]["issns"] == ["1234-5679"]
+        assert rows[0]["funders"] == [
+            {"name": "National Science Foundation", "funder_doi": "10.13039/100000001", "award": "1234567"}
+        ]
+        assert rows[0]["publication_date"] == "2021-03"
+        assert rows[1]["title"] is None
+        assert rows[1]["authors"] == []
+
+    def test_row_groups_and_compression(self, tmp_path):
+        path = tmp_path / "works.parquet"
+        works = [crossref_work(f"10.1234/{i}") for i in range(5)]
+        assert write_works_parquet(works, path, ParquetWriteOptions(row_group_size=2, zstd_level=9)) == 5
+
+        metadata = pq.ParquetFile(path).metadata
+        assert metadata.num_rows == 5
+        assert [metadata.row_group(i).num_rows for i in range(metadata.num_row_groups)] == [2, 2, 1]
+        assert metadata.row_group(0).column(0).compression == "ZSTD"
+
+    def test_empty(self, tmp_path):
+        path = tmp_path / "works.parquet"
+        assert write_works_parquet([], path) == 0
+        table = pq.read_table(path)
+        assert table.num_rows == 0
+        assert "doi" in table.column_names
+
+    def test_invalid_record(self, tmp_path):
+        with pytest.raises(ValueError, match="work"):
+            write_works_parquet([{"title": "No DOI"}], tmp_path / "works.parquet")
+
+    def test_invalid_options(self):
+        with pytest.raises(ValueError, match="row_group_size"):
+            ParquetWriteOptions(row_group_size=0)
+        with pytest.raises(ValueError, match="zstd_level"):
+            ParquetWriteOptions(zstd_level=23)
+
+
+def read_rows(path, **kwargs):
+    return [row for batch in read_works_parquet(path, **kwargs) for row in pa.record_batch(batch).to_pylist()]
+
+
+class TestReadWorksParquet:
+    @pytest.fixture
+    def works_path(self, tmp_path):
+        path = tmp_path / "works.parquet"
+        works = [
+            crossref_work("10.1234/a", issued={"date-parts": [[2019, 5]]}),
+            crossref_work("10.1234/b", abstract="<jats:p>Soil carbon.</jats:p>"),
+            crossref_work("10.1234/c", issued={"date-parts": [[2023]]}, abstract="   "),
+            crossref_work("10.1234/d", issued={"date-parts": [[None]]}),
+        ]
+        write_works_parquet(works, path, ParquetWriteOptions(row_group_size=2))
+        return path
+
+    def test_round_trip(self, works_path):
+        rows = read_rows(works_path)
+        assert [row["doi"] for row in rows] == ["10.1234/a", "10.1234/b", "10.1234/c", "10.1234/d"]
+        assert rows[1]["abstract"] == "Soil carbon."
+        assert rows[0]["authors"][0]["surname"] == "Smith"
+
+    def test_columns(self, works_path):
+        reader = read_works_parquet(works_path, columns=["title", "doi"])
+        assert pa.schema(reader.schema).names == ["title", "doi"]
+        assert read_rows(works_path, columns=["title", "doi"])[0] == {
+            "title": "Soil carbon in alpine meadows",
+            "doi": "10.1234/a",
+        }
+
+    def test_year_range(self, works_path):
+        rows = read_rows(works_path, columns=["doi"], filters=WorksFilter(min_year=2020, max_year=2022))
+        assert rows == [{"doi": "10.1234/b"}]
+        rows = read_rows(works_path, columns=["doi"], filters=WorksFilter(min_year=2021), batch_size=1)
+        assert rows == [{"doi": "10.1234/b"}, {"doi": "10.1234/c"}]
+
+    def test_has_abstract(self, works_path):
+        assert read_rows(works_path, columns=["doi"], filters=WorksFilter(has_abstract=True)) == [{"doi": "10.1234/b"}]
+        rows = read_rows(works_path, columns=["doi"], filters=WorksFilter(has_abstract=False))
+        assert [row["doi"] for row in rows] == ["10.1234/a", "10.1234/c", "10.1234/d"]
+
+    def test_works_index_export(self, tmp_path):
+        path = tmp_path / "export.parquet"
+        source = pa.struct([("name", pa.string()), ("url", pa.string())])
+        table = pa.table(
+            {
+                "doi": ["10.1234/a", "10.1234/b", "10.1234/c"],
+                "abstract_text": ["Soil carbon.", None, "Nitrogen."],
+                "publication_date": pa.array(
+                    [datetime.date(2021, 3, 1), datetime.date(2018, 1, 1), datetime.date(2022, 6, 30)]
+                ),
+                "source": pa.array([{"name": "OpenAlex"}, {"name": "OpenAlex"}, {"name": "DataCite"}], type=source),
+            }
+        )
+        pq.write_table(table, path)
+        filters = WorksFilter(min_year=2020, source="openalex", has_abstract=True)
+        assert read_rows(path, columns=["doi"], filters=filters) == [{"doi": "10.1234/a"}]
+
+    def test_row_groups_ruled_out_by_statistics(self, tmp_path):
+        path = tmp_path / "export.parquet"
+        table = pa.table(
+            {
+                "doi": ["10.1234/a", "10.1234/b", "10.1234/c", "10.1234/d"],
+                "publication_date": ["2019-05-01", "2019-12-31", "2023-01-01", None],
+                "source": ["Crossref", "Crossref", "OpenAlex", "OpenAlex"],
+            }
+        )
+        pq.write_table(table, path, row_group_size=2)
+        assert read_rows(path, columns=["doi"], filters=WorksFilter(min_year=2020)) == [{"doi": "10.1234/c"}]
+        assert read_rows(path, columns=["doi"], filters=WorksFilter(max_year=2019)) == [
+            {"doi": "10.1234/a"},
+            {"doi": "10.1234/b"},
+        ]
+        rows = read_rows(path, columns=["doi"], filters=WorksFilter(source="openalex"))
+        assert rows == [{"doi": "10.1234/c"}, {"doi": "10.1234/d"}]
+        assert read_rows(path, columns=["doi"], filters=WorksFilter(source="DataCite")) == []
+
+    def test_missing_column(self, works_path):
+        with pytest.raises(ValueError, match="nope"):
+            read_works_parquet(works_path, columns=["nope"])
+        with pytest.raises(ValueError, match="source"):
+            read_works_parquet(works_path, filters=WorksFilter(source="OpenAlex"))
+
+    def test_missing_file(self, tmp_path):
+        with pytest.raises(FileNotFoundError):
+            read_works_parquet(tmp_path / "missing.parquet")
+
+    def test_invalid_filter(self):
+        with pytest.raises(ValueError, match="min_year"):
+            WorksFilter(min_year=2022, max_year=2020)
+
+
+class TestDiffSnapshots:
+    @pytest.fixture
+    def snapshots(self, tmp_path):
+        old_path = tmp_path / "old.parquet"
+        new_path = tmp_path / "new.parquet"
+        old = [
+            crossref_work("10.1234/a"),
+            crossref_work("10.1234/b"),
+            crossref_work("10.1234/c"),
+            crossref_work("10.1234/d"),
+        ]
+        new = [
+            crossref_work("10.1234/a"),
+            crossref_work("10.1234/b", title=["Soil carbon in alpine grasslands"]),
+            crossref_work("10.1234/d", abstract="<jats:p>Soil carbon.</jats:p>", author=[]),
+            crossref_work("10.1234/e"),
+        ]
+        write_works_parquet(old, old_path, ParquetWriteOptions(row_group_size=3))
+        write_works_parquet(new, new_path, ParquetWriteOptions(row_group_size=2))
+        return old_path, new_path
+
+    @pytest.mark.parametrize("batch_size", [1, 8192])
+    def test_diff(self, snapshots, batch_size):
+        added, removed, changed = diff_snapshots(*snapshots, "doi", batch_size=batch_size)
+        assert added == ["10.1234/e"]
+        assert removed == ["10.1234/c"]
+        assert changed == {"10.1234/b": ["title"], "10.1234/d": ["abstract", "authors"]}
+
+    def test_fields(self, snapshots):
+        assert diff_snapshots(*snapshots, "doi", fields=["abstract"])[2] == {"10.1234/d": ["abstract"]}
+
+    def test_unchanged(self, snapshots):
+        old_path, _ = snapshots
+        assert diff_snapshots(old_path, old_path, "doi") == ([], [], {})
+
+    def test_schema_change(self, tmp_path):
+        old_path = tmp_path / "old.parquet"
+        new_path = tmp_path / "new.parquet"
+        pq.write_table(pa.table({"id": [1, 2], "title": ["Soil", "Carbon"], "year": [2020, 2021]}), old_path)
+        pq.write_table(pa.table({"id": [1, 2], "title": ["Soil", "Carbon"], "score": [None, 0.5]}), new_path)
+        assert diff_snapshots(old_path, new_path, "id") == ([], [], {"1": ["year"], "2": ["year", "score"]})
+
+    def test_unsorted(self, tmp_path):
+        old_path = tmp_path / "old.parquet"
+        new_path = tmp_path / "new.parquet"
+        pq.write_table(pa.table({"doi": ["10.1234/a", "10.1234/b"]}), old_path)
+        pq.write_table(pa.table({"doi": ["10.1234/b", "10.1234/a"]}), new_path)
+        with pytest.raises(ValueError, match="not sorted by doi"):
+            diff_snapshots(old_path, new_path, "doi")
+        pq.write_table(pa.table({"doi": ["10.1234/a", "10.1234/a"]}), new_path)
+        with pytest.raises(ValueError, match="not sorted by doi"):
+            diff_snapshots(old_path, new_path, "doi")
+
+    def test_invalid(self, snapshots, tmp_path):
+        with pytest.raises(ValueError, match="no work_id column"):
+            diff_snapshots(*snapshots, "work_id")
+        with pytest.raises(ValueError, match="neither snapshot has a score column"):
+            diff_snapshots(*snapshots, "doi", fields=["score"])
+        with pytest.raises(ValueError, match="batch_size"):
+            diff_snapshots(*snapshots, "doi", batch_size=0)
+        old_path, _ = snapshots
+        new_path = tmp_path / "titles.parquet"
+        pq.write_table(pa.table({"doi": ["10.1234/a"], "title": [1]}), new_path)
+        with pytest.raises(ValueError, match="column title is Utf8 in the old snapshot but Int64 in the new"):
+            diff_snapshots(old_path, new_path, "doi")
+
+
+class TestCandidates:
+    CANDIDATES = [
+        {
+            "doi": "10.1234/a",
+            "score": 2.5,
+            "features": {"title_similarity": 0.75, "funder_match": 1.0},
+            "legs": [{"leg": "lexical", "rank": 1, "score": 12.5}, {"leg": "knn", "rank": 3, "score": 0.875}],
+        },
+        {
+            "doi": "10.1234/b",
+            "score": None,
+            "features": {"title_similarity": 0.5, "award_match": None},
+            "legs": [{"leg": "knn", "rank": 1, "score": None}],
+        },
+    ]
+
+    def test_round_trip(self, tmp_path):
+        path = tmp_path / "candidates.parquet"
+        assert write_candidates(path, "10.48321/d1", self.CANDIDATES) == 2
+
+        [(dmp_id, candidates)] = read_candidates(path).items()
+        assert dmp_id == "10.48321/d1"
+        assert candidates[0] == {
+            "doi": "10.1234/a",
+            "score": 2.5,
+            "features": {"award_match": None, "funder_match": 1.0, "title_similarity": 0.75},
+            "legs": [{"leg": "lexical", "rank": 1, "score": 12.5}, {"leg": "knn", "rank": 3, "score": 0.875}],
+        }
+        assert candidates[1] == {
+            "doi": "10.1234/b",
+            "score": None,
+            "features": {"award_match": None, "funder_match": None, "title_similarity": 0.5},
+            "legs": [{"leg": "knn", "rank": 1, "score": None}],
+        }
+
+    def test_layout(self, tmp_path):
+        path = tmp_path / "candidates.parquet"
+        write_candidates(path, "10.48321/d1", self.CANDIDATES)
+        table = pq.read_table(path)
+        assert table.column_names == ["dmp_id", "rank", "doi", "score", "legs", "features"]
+        assert table.column("rank").to_pylist() == [1, 2]
+        assert table.schema.field("features").type.field("title_similarity").type == pa.float32()
+
+    def test_many(self, tmp_path):
+        path = tmp_path / "candidates.parquet"
+        dmps = {"10.48321/d2": [{"doi": "10.1234/c", "score": 1.0}], "10.48321/d1": self.CANDIDATES, "10.48321/d3": []}
+        assert write_candidates_many(path, dmps) == 3
+        candidates = read_candidates(path)
+        assert list(candidates) == ["10.48321/d2", "10.48321/d1"]
+        assert candidates["10.48321/d2"] == [
+            {
+                "doi": "10.1234/c",
+                "score": 1.0,
+                "features": {"award_match": None, "funder_match": None, "title_similarity": None},
+                "legs": [],
+            }
+        ]
+        assert [candidate["doi"] for candidate in candidates["10.48321/d1"]] == ["10.1234/a", "10.1234/b"]
+
+    def test_without_features(self, tmp_path):
+        path = tmp_path / "candidates.parquet"
+        assert write_candidates(path, "10.48321/d1", [{"doi": "10.1234/a", "score": 1.0}]) == 1
+        assert read_candidates(path) == {
+            "10.48321/d1": [{"doi": "10.1234/a", "score": 1.0, "features": {}, "legs": []}]
+        }
+        assert write_candidates(path, "10.48321/d1", []) == 0
+        assert read_candidates(path) == {}
+
+    def test_invalid(self, tmp_path):
+        path = tmp_path / "candidates.parquet"
+        with pytest.raises(ValueError, match="invalid candidates"):
+            write_candidates(path, "10.48321/d1", [{"doi": "10.1234/a", "score": 1.0, "legs": [{"leg": "knn"}]}])
+        write_works_parquet(iter([crossref_work("10.1234/a")]), path)
+        with pytest.raises(ValueError, match="no valid dmp_id column"):
+            read_candidates(path)
+        with pytest.raises(OSError):
+            read_candidates(tmp_path / "missing.parquet")
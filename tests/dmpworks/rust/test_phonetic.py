@@ -0,0 +1,44 @@
+import pytest
+
+from dmpworks.rust.phonetic import phonetic_encode
+
+
+class TestPhoneticEncode:
+    @pytest.mark.parametrize(
+        "text, expected",
+        [
+            ("Smith", ["SM0", "XMT"]),
+            ("Schmidt", ["XMT", "SMT"]),
+            ("Müller", ["MLR"]),
+            ("Mueller", ["MLR"]),
+            ("Michael", ["MKL", "MXL"]),
+            ("Knight", ["NT"]),
+            ("Jose", ["HS"]),
+            ("Filipowicz", ["FLPT", "FLPF"]),
+            ("Gallegos", ["KLKS", "KKS"]),
+        ],
+    )
+    def test_double_metaphone(self, text, expected):
+        assert phonetic_encode(text) == expected
+
+    @pytest.mark.parametrize(
+        "text, expected",
+        [
+            ("Knight", ["NAGT"]),
+            ("Müller", ["MALAR"]),
+            ("Mueller", ["MALAR"]),
+            ("Maier", ["MAR"]),
+            ("Thompson", ["TANPSA"]),
+        ],
+    )
+    def test_nysiis(self, text, expected):
+        assert phonetic_encode(text, "nysiis") == expected
+        assert phonetic_encode(text, "NYSIIS") == expected
+
+    def test_no_letters(self):
+        assert phonetic_encode("") == []
+        assert phonetic_encode(" 12 ", "nysiis") == []
+
+    def test_unknown_algorithm(self):
+        with pytest.raises(ValueError, match="unknown phonetic algorithm"):
+            phonetic_encode("Smith", "soundex")
@@ -0,0 +1,81 @@
+import json
+
+import pyarrow as pa
+import pytest
+
+from dmpworks.rust import clean_text, normalize_doi, revert_inverted_index, strip_markup
+from dmpworks.rust.pipeline import Pipeline
+
+INVERTED_INDEX = json.dumps({"<p>Soil": [0], "moisture": [1], "controls\u200b": [2], "runoff.</p>": [3]})
+
+
+class TestPipeline:
+    def test_run(self):
+        pipeline = Pipeline(
+            {
+                "abstract": ["revert_inverted_index", "strip_markup", "clean_text", ("truncate", 3)],
+                "doi": ["normalize_doi"],
+            }
+        )
+        batch = pa.record_batch(
+            {
+                "abstract": [INVERTED_INDEX, None],
+                "doi": ["https://doi.org/10.1234/ABC", "not a doi"],
+                "id": [1, 2],
+            }
+        )
+        result = pa.record_batch(pipeline.run(batch))
+        assert result.to_pylist() == [
+            {"abstract": "Soil moisture controls", "doi": "10.1234/abc", "id": 1},
+            {"abstract": None, "doi": None, "id": 2},
+        ]
+        assert result.schema.field("abstract").type == pa.string()
+
+    def test_matches_functions(self):
+        pipeline = Pipeline({"abstract": ["revert_inverted_index", "strip_markup", "clean_text"]})
+        result = pa.record_batch(pipeline.run(pa.record_batch({"abstract": [INVERTED_INDEX]})))
+        expected = clean_text(strip_markup(revert_inverted_index(INVERTED_INDEX.encode())))
+        assert result.to_pylist() == [{"abstract": expected}]
+
+    def test_binary_column(self):
+        pipeline = Pipeline({"abstract": ["revert_inverted_index"]})
+        batch = pa.record_batch({"abstract": pa.array([INVERTED_INDEX.encode(), None], type=pa.binary())})
+        result = pa.record_batch(pipeline.run(batch))
+        assert result.to_pylist()[0]["abstract"] == revert_inverted_index(INVERTED_INDEX.encode())
+        assert result.schema.field("abstract").type == pa.string()
+
+    def test_order(self):
+        dois = ["https://doi.org/10.1234/ABC", "N/A"]
+        batch = pa.record_batch({"doi": dois})
+        result = pa.record_batch(Pipeline({"doi": ["nullify", "normalize_doi"]}).run(batch))
+        assert result.to_pylist() == [{"doi": normalize_doi(dois[0])}, {"doi": None}]
+
+    def test_num_threads(self):
+        batch = pa.record_batch({"title": [f"<b>Title {i}</b>" for i in range(100)]})
+        pipeline = Pipeline({"title": ["strip_markup", "normalize_title"]})
+        assert pa.record_batch(pipeline.run(batch, num_threads=2)).to_pylist() == (
+            pa.record_batch(pipeline.run(batch)).to_pylist()
+        )
+
+    def test_fields(self):
+        assert Pipeline({"title": ["strip_markup"], "doi": ["normalize_doi"]}).fields == ["title", "doi"]
+
+    @pytest.mark.parametrize(
+        "transforms,message",
+        [
+            (["strip_html"], "unknown transform: strip_html"),
+            (["truncate"], "truncate requires"),
+            ([("strip_markup", 3)], "strip_markup takes no argument"),
+        ],
+    )
+    def test_invalid_transform(self, transforms, message):
+        with pytest.raises(ValueError, match=message):
+            Pipeline({"abstract": transforms})
+
+    def test_missing_column(self):
+        with pytest.raises(ValueError, match="missing column: abstract"):
+            Pipeline({"abstract": ["strip_markup"]}).run(pa.record_batch({"title": ["A title"]}))
+
+    def test_non_string_column(self):
+        with pytest.raises(ValueError, match="must be strings or binary"):
+            Pipeline({"id": ["strip_markup"]}).run(pa.record_batch({"id": [1, 2]}))
@@ -0,0 +1,68 @@
+import pyarrow as pa
+import pytest
+
+from dmpworks.rust import NullRules
+from dmpworks.rust.profile import profile_texts
+
+ENGLISH = "The effects of soil carbon dynamics on alpine meadow ecosystems under a changing climate"
+GERMAN = "Die Auswirkungen der Bodenkohlenstoffdynamik auf alpine Wiesenökosysteme im Klimawandel"
+
+
+class TestProfileTexts:
+    def test_counts(self):
+        texts = [ENGLISH, None, "  ", "N/A", "No abstract available.", "<jats:p>" + ENGLISH + "</jats:p>", GERMAN]
+        profile = profile_texts(pa.array(texts))
+        assert profile["count"] == 7
+        assert profile["nulls"] == 1
+        assert profile["blanks"] == 1
+        assert profile["placeholders"] == 2
+        assert profile["values"] == 3
+        assert profile["markup"] == 1
+        assert profile["invalid_utf8"] == 0
+        assert profile["null_rate"] == pytest.approx(1 / 7)
+        assert profile["placeholder_rate"] == pytest.approx(2 / 7)
+        assert profile["markup_rate"] == pytest.approx(1 / 3)
+        assert profile["languages"] == {"de": 1, "en": 2}
+        assert profile["undetected_language"] == 0
+
+    def test_lengths(self):
+        texts = ["x" * n for n in range(1, 101)]
+        lengths = profile_texts(pa.array(texts), languages=False)["lengths"]
+        assert lengths == {"min": 1, "p10": 10, "p50": 50, "p90": 90, "p99": 99, "max": 100, "mean": 50.5}
+        assert profile_texts(pa.array(["  é  "]), languages=False)["lengths"]["max"] == 1
+
+    @pytest.mark.parametrize("text", ["a &amp; b", "line<br/>break", "x &#233; y", "<i>Homo sapiens</i>"])
+    def test_markup(self, text):
+        assert profile_texts(pa.array([text]), languages=False)["markup"] == 1
+
+    @pytest.mark.parametrize("text", ["a & b", "x < y and y > z", "R&D; testing", "p<0.05"])
+    def test_not_markup(self, text):
+        assert profile_texts(pa.array([text]), languages=False)["markup"] == 0
+
+    def test_invalid_utf8(self):
+        profile = profile_texts(pa.array([b"caf\xe9", "café".encode(), None]), languages=False)
+        assert profile["invalid_utf8"] == 1
+        assert profile["values"] == 2
+        assert profile_texts(pa.array(["caf�"]), languages=False)["invalid_utf8"] == 1
+
+    def test_null_rules(self):
+        profile = profile_texts(pa.array(["N/A", "missing"]), null_rules=NullRules(values=["missing"], presets=[]))
+        assert profile["placeholders"] == 1
+
+    def test_without_languages(self):
+        profile = profile_texts(pa.array([ENGLISH]), languages=False)
+        assert "languages" not in profile
+
+    def test_large_string(self):
+        texts = [ENGLISH, None]
+        assert profile_texts(pa.array(texts, type=pa.large_string())) == profile_texts(pa.array(texts), num_threads=1)
+
+    def test_empty(self):
+        profile = profile_texts(pa.array([], type=pa.string()))
+        assert profile["count"] == 0
+        assert profile["null_rate"] == 0.0
+        assert profile["lengths"] is None
+
+    def test_invalid(self):
+        with pytest.raises(TypeError, match="string or binary array"):
+            profile_texts(pa.array([1, 2]))
@@ -0,0 +1,356 @@
+import datetime
+import json
+
+import pytest
+
+from dmpworks.rust.query import build_work_query, parse_msearch_response, WorkQueryOptions
+
+DMP = {
+    "doi": "10.48321/D1ABC",
+    "title": "Soil carbon",
+    "abstract_text": "Alpine meadows",
+    "project_start": datetime.date(2021, 3, 1),
+    "project_end": "2024-02-29",
+    "funded_dois": ["10.1234/funded"],
+    "authors": [{"orcid": "0000-0002-1825-0097", "surname": "Carberry"}, {"surname": "Smith"}],
+    "institutions": [{"ror": "01an7q238", "name": "University of California, Berkeley"}],
+    "funders": [{"name": "National Science Foundation"}],
+    "awards": [["DBI-1234567", "1234567"]],
+    "published_output_dois": ["10.1234/output"],
+}
+
+
+def must_clauses(query):
+    return query["query"]["bool"]["must"][0]["bool"]["should"]
+
+
+class TestBuildWorkQuery:
+    def test_baseline(self):
+        query = build_work_query(DMP)
+        assert query["size"] == 100
+        must = must_clauses(query)
+        assert must[0] == {
+            "constant_score": {"_name": "funded_dois", "boost": 15.0, "filter": {"ids": {"values": ["10.1234/funded"]}}}
+        }
+        authors = must[1]["nested"]
+        assert authors["path"] == "authors"
+        assert authors["inner_hits"] == {"name": "authors", "size": 50}
+        assert authors["query"]["bool"]["should"][0]["dis_max"]["queries"][0] == {
+            "constant_score": {
+                "_name": "authors.orcid.0000-0002-1825-0097",
+                "boost": 2.0,
+                "filter": {"term": {"authors.orcid": "0000-0002-1825-0097"}},
+            }
+        }
+        assert authors["query"]["bool"]["should"][1] == {
+            "constant_score": {
+                "_name": "authors.full.Smith",
+                "boost": 1.0,
+                "filter": {"match_phrase": {"authors.full": {"query": "Smith"}}},
+            }
+        }
+        awards = must[2]["nested"]["query"]["bool"]["should"][0]["dis_max"]["queries"]
+        assert [award["constant_score"]["_name"] for award in awards] == [
+            "awards.award_id.DBI-1234567",
+            "awards.award_id.1234567",
+        ]
+        assert [clause["nested"]["path"] for clause in must[3:]] == [
+            "relations.intra_work_dois",
+            "relations.possible_shared_project_dois",
+            "relations.dataset_citation_dois",
+        ]
+
+    def test_should_and_filters(self):
+        query = build_work_query(DMP)
+        should = query["query"]["bool"]["should"]
+        assert [clause.get("nested", {}).get("path") for clause in should] == ["institutions", "funders", None]
+        funder = should[1]["nested"]["query"]["bool"]["should"][0]["constant_score"]
+        assert funder["filter"] == {
+            "match_phrase": {"funders.name": {"query": "National Science Foundation", "slop": 3}},
+        }
+        assert should[2] == {
+            "more_like_this": {
+                "_name": "content",
+                "fields": ["title", "abstract_text"],
+                "like": "Soil carbon Alpine meadows",
+                "min_term_freq": 1,
+            }
+        }
+        assert query["query"]["bool"]["filter"] == [
+            {"bool": {"must_not": {"term": {"work_type": "OUTPUT_MANAGEMENT_PLAN"}}}},
+            {"range": {"publication_date": {"gte": "2021-03-01", "lte": "2027-02-28"}}},
+        ]
+        assert query["highlight"]["highlight_query"]["more_like_this"]["like"] == "Soil carbon Alpine meadows"
+
+    def test_options(self):
+        options = WorkQueryOptions(
+            max_results=10,
+            project_end_buffer_years=0,
+            boosts={"funded_dois": 20, "content": 2},
+            minimum_should_match="50%",
+            stop_words=["data"],
+        )
+        query = build_work_query(DMP, options)
+        assert query["size"] == 10
+        assert must_clauses(query)[0]["constant_score"]["boost"] == 20.0
+        content = query["query"]["bool"]["should"][2]["more_like_this"]
+        assert content["boost"] == 2.0
+        assert content["minimum_should_match"] == "50%"
+        assert content["stop_words"] == ["data"]
+        assert query["query"]["bool"]["filter"][1]["range"]["publication_date"]["lte"] == "2024-02-29"
+
+    def test_empty_dmp(self):
+        query = build_work_query({})
+        assert must_clauses(query) == []
+        assert query["query"]["bool"]["should"] == []
+        assert query["query"]["bool"]["filter"][1] == {"range": {"publication_date": {"gte": "1990-01-01"}}}
+        assert "highlight" not in query
+
+    def test_integral_boosts_are_integers(self):
+        query = build_work_query(DMP, WorkQueryOptions(boosts={"content": 2.0, "entity_name": 0.5}))
+        assert json.dumps(must_clauses(query)[0]["constant_score"]["boost"]) == "15"
+        assert json.dumps(query["query"]["bool"]["should"][2]["more_like_this"]["boost"]) == "2"
+        authors = must_clauses(query)[1]["nested"]["query"]["bool"]["should"]
+        assert json.dumps(authors[1]["constant_score"]["boost"]) == "0.5"
+        relations = must_clauses(query)[5]["nested"]["query"]["bool"]["should"][0]
+        assert json.dumps(relations["constant_score"]["boost"]) == "2.5"
+
+    def test_key_order(self):
+        query = build_work_query(DMP)
+        assert list(query) == ["size", "query", "highlight"]
+        assert list(query["query"]["bool"]) == ["must", "should", "filter"]
+        assert json.dumps(query["query"]["bool"]["should"][2]) == (
+            '{"more_like_this": {"_name": "content", "fields": ["title", "abstract_text"], '
+            '"like": "Soil carbon Alpine meadows", "min_term_freq": 1}}'
+        )
+        assert json.dumps(must_clauses(query)[3]["nested"]["query"]["bool"]["should"][0]) == (
+            '{"constant_score": {"_name": "relations.intra_work_dois.doi.10.1234/output", '
+            '"filter": {"term": {"relations.intra_work_dois.doi": "10.1234/output"}}, "boost": 10.0}}'
+        )
+
+    def test_features(self):
+        options = WorkQueryOptions(features={"funded_dois": False, "authors": False, "relations": False})
+        query = build_work_query(DMP, options)
+        assert [clause["nested"]["path"] for clause in must_clauses(query)] == ["awards"]
+        options = WorkQueryOptions(features={"institutions": False, "content": False})
+        query = build_work_query(DMP, options)
+        assert [clause["nested"]["path"] for clause in query["query"]["bool"]["should"]] == ["funders"]
+        assert "highlight" not in query
+
+    def test_raw_awards_fallback(self):
+        raw_awards = [["GBMF1234"], []]
+        query = build_work_query({**DMP, "awards": [], "raw_awards": raw_awards})
+        awards = must_clauses(query)[2]["nested"]["query"]["bool"]["should"]
+        assert awards == [
+            {
+                "dis_max": {
+                    "tie_breaker": 0,
+                    "queries": [
+                        {
+                            "constant_score": {
+                                "_name": "awards.award_id.GBMF1234",
+                                "filter": {"term": {"awards.award_id": "GBMF1234"}},
+                                "boost": 10,
+                            }
+                        }
+                    ],
+                }
+            }
+        ]
+        # Parsed awards take precedence
+        query = build_work_query({**DMP, "raw_awards": raw_awards})
+        awards = must_clauses(query)[2]["nested"]["query"]["bool"]["should"]
+        assert awards[0]["dis_max"]["queries"][0]["constant_score"]["_name"] == "awards.award_id.DBI-1234567"
+
+    def test_invalid(self):
+        with pytest.raises(ValueError):
+            build_work_query({"authors": "Smith"})
+        with pytest.raises(ValueError):
+            WorkQueryOptions(boosts={"title": 2.0})
+        with pytest.raises(ValueError):
+            WorkQueryOptions(boosts={"content": -1.0})
+        with pytest.raises(ValueError, match="unknown feature 'title'"):
+            WorkQueryOptions(features={"title": False})
+
+
+class TestBuildWorkQueryParity:
+    """The query must match python/dmpworks/opensearch/query_builder.py's baseline query on the same DMPs."""
+
+    @pytest.fixture(scope="class")
+    def query_builder(self):
+        pytest.importorskip("pendulum")
+        pytest.importorskip("pydantic")
+        from dmpworks.opensearch import query_builder
+
+        return query_builder
+
+    @pytest.fixture(scope="class")
+    def dmps(self, query_builder):
+        from dmpworks.funders.nsf_award_id import NSFAwardID
+        from dmpworks.model.dmp_model import Award, DMPModel, ExternalData
+
+        def dmp(**fields):
+            return DMPModel.model_validate(
+                {
+                    "doi": "10.48321/D1ABC",
+                    "created": None,
+                    "registered": None,
+                    "modified": None,
+                    "title": "Soil carbon",
+                    "abstract_text": "Alpine meadows",
+                    "project_start": "2021-03-01",
+                    "project_end": "2024-02-29",
+                    "institutions": [{"name": "University of California, Berkeley", "ror": "01an7q238"}],
+                    "authors": [
+                        {
+                            "orcid": "0000-0002-1825-0097",
+                            "first_initial": "J",
+                            "given_name": "Josiah",
+                            "middle_initials": None,
+                            "middle_names": None,
+                            "surname": "Carberry",
+                            "full": "Josiah Carberry",
+                        }
+                    ],
+                    "funding": [
+                        {
+                            "funder": {"name": "National Science Foundation", "ror": "021nxhr62"},
+                            "funding_opportunity_id": None,
+                            "status": "granted",
+                            "award_id": "DBI-1234567",
+                            "funder_project_number": None,
+                        }
+                    ],
+                    "published_outputs": [{"doi": "10.1234/output"}],
+                    **fields,
+                }
+            )
+
+        award = Award(
+            funder=None,
+            award_id=NSFAwardID("DBI-1234567", org_id="DBI", award_id="1234567"),
+            funded_dois=["10.1234/funded"],
+        )
+        external_data = ExternalData(updated="2024-01-01T00:00:00Z", awards=[award])
+        return [
+            dmp(external_data=external_data),
+            # No parsed awards, so the raw funding identifiers are matched
+            dmp(external_data=ExternalData(updated="2024-01-01T00:00:00Z", awards=[]), published_outputs=None),
+            dmp(
+                title=None,
+                abstract_text=None,
+                project_start=None,
+                project_end=None,
+                external_data=ExternalData(updated="2024-01-01T00:00:00Z", awards=[]),
+            ),
+        ]
+
+    @staticmethod
+    def dmp_fields(dmp):
+        published_outputs = dmp.published_outputs or []
+        return {
+            "doi": dmp.doi,
+            "title": dmp.title,
+            "abstract_text": dmp.abstract_text,
+            "project_start": dmp.project_start and dmp.project_start.format("YYYY-MM-DD"),
+            "project_end": dmp.project_end and dmp.project_end.format("YYYY-MM-DD"),
+            "funded_dois": dmp.funded_dois,
+            "authors": [{"orcid": author.orcid, "surname": author.surname} for author in dmp.authors],
+            "institutions": [{"ror": inst.ror, "name": inst.name} for inst in dmp.institutions],
+            "funders": [{"ror": fund.funder.ror, "name": fund.funder.name} for fund in dmp.funding],
+            "awards": [award.award_id.all_variants for award in dmp.external_data.awards],
+            # Built like build_raw_awards_query's sets, so that they iterate in the same order
+            "raw_awards": [
+                list({raw for raw in (fund.funding_opportunity_id, fund.award_id, fund.funder_project_number) if raw})
+                for fund in dmp.funding
+            ],
+            "published_output_dois": [output.doi for output in published_outputs],
+        }
+
+    @pytest.mark.parametrize(
+        "features",
+        [{}, {"funded_dois": False, "content": False}, {"awards": False, "relations": False, "authors": False}],
+    )
+    def test_matches_python_query(self, query_builder, dmps, features):
+        for dmp in dmps:
+            expected = query_builder.build_dmp_works_search_baseline_query(
+                dmp, 100, 3, 50, features=query_builder.QueryFeatures(**features)
+            )
+            query = build_work_query(self.dmp_fields(dmp), WorkQueryOptions(features=features))
+            assert json.dumps(query) == json.dumps(expected)
+
+
+def hit(doi, score, doc_id=None, matched_queries=()):
+    return {
+        "_id": doc_id or doi.lower(),
+        "_score": score,
+        "_source": {"doi": doi, "title": f"Work {doi}"},
+        "matched_queries": list(matched_queries),
+    }
+
+
+def msearch_response(*hit_lists):
+    responses = [{"hits": {"hits": hits}, "status": 200} for hits in hit_lists]
+    return json.dumps({"took": 5, "responses": responses}).encode()
+
+
+class TestParseMsearchResponse:
+    def test_fuses_sub_queries(self):
+        response = msearch_response(
+            [hit("10.1234/A", 12.0, matched_queries=["funded_dois"]), hit("10.1234/B", 8.0)],
+            [hit("10.1234/b", 3.0, doc_id="other-b", matched_queries=["content"]), hit("10.1234/C", 2.0)],
+        )
+        candidates = parse_msearch_response(response)
+        assert [candidate["key"] for candidate in candidates] == ["10.1234/b", "10.1234/a", "10.1234/c"]
+        b = candidates[0]
+        assert b["id"] == "10.1234/b"
+        assert b["score"] == pytest.approx(1 / 62 + 1 / 61)
+        assert b["ranks"] == [2, 1]
+        assert b["scores"] == [8.0, 3.0]
+        assert b["matched_queries"] == ["content"]
+        assert b["source"] == {"doi": "10.1234/B", "title": "Work 10.1234/B"}
+        assert candidates[1]["ranks"] == [1, None]
+        assert candidates[1]["matched_queries"] == ["funded_dois"]
+
+    def test_dedupe_by_id(self):
+        response = msearch_response([hit("10.1234/A", 1.0)], [hit("10.1234/A", 1.0, doc_id="other")])
+        assert [candidate["key"] for candidate in parse_msearch_response(response, dedupe_by="id")] == [
+            "10.1234/a",
+            "other",
+        ]
+        assert len(parse_msearch_response(response)) == 1
+
+    def test_rrf_k(self):
+        response = msearch_response([hit("10.1234/A", 1.0)])
+        assert parse_msearch_response(response, rrf_k=0.0)[0]["score"] == 1.0
+
+    def test_named_query_scores_and_missing_dois(self):
+        response = msearch_response(
+            [
+                {**hit("10.1234/A", 1.0), "matched_queries": {"awards": 10.0, "content": 0.5}},
+                {"_id": "no-doi", "_score": 0.5, "_source": {}},
+                {"_score": 0.1},
+            ]
+        )
+        candidates = parse_msearch_response(response)
+        assert [candidate["key"] for candidate in candidates] == ["10.1234/a", "no-doi"]
+        assert candidates[0]["matched_queries"] == ["awards", "content"]
+
+    def test_failed_sub_query(self):
+        error = {"type": "search_phase_execution_exception", "reason": "all shards failed"}
+        response = json.dumps(
+            {
+                "responses": [
+                    {"error": error, "status": 400},
+                    {"hits": {"hits": [hit("10.1234/A", 1.0)]}, "status": 200},
+                ]
+            }
+        ).encode()
+        candidates = parse_msearch_response(response)
+        assert [candidate["ranks"] for candidate in candidates] == [[None, 1]]
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="invalid msearch response"):
+            parse_msearch_response(b"{}")
+        with pytest.raises(ValueError, match="dedupe_by"):
+            parse_msearch_response(msearch_response(), dedupe_by="title")
@@ -1,6 +1,106 @@
+import gzip
 import json
+import logging
 
-from dmpworks.rust import has_alphabetic_initials, parse_name, revert_inverted_index, strip_markup
+import pyarrow as pa
+import pytest
+
+from dmpworks.rust import (
+    author_count,
+    author_overlap,
+    BoilerplateRules,
+    build_inverted_index,
+    bylines_match,
+    citation_name,
+    citekey,
+    cjk_surname_match,
+    clean_text,
+    combined_relevance,
+    compare_award_ids,
+    compare_names,
+    configure,
+    date_distance,
+    detect_language,
+    detect_language_many,
+    dmp_work_relevance,
+    doi_prefix,
+    doi_same_registrant,
+    ENGLISH_STOP_WORDS,
+    expand_given_name_variants,
+    extract_emails,
+    extract_funding_statements,
+    extract_grant_ids,
+    extract_identifiers,
+    extract_institution_ids,
+    extract_title_metadata,
+    has_alphabetic_initials,
+    initials_compatible,
+    is_organization,
+    issn_set_match,
+    JsonlReader,
+    keyword_overlap,
+    MarkupOptions,
+    NameOptions,
+    normalize_author_join,
+    normalize_award_id,
+    normalize_country,
+    normalize_date,
+    normalize_doi,
+    normalize_isbn,
+    normalize_issn,
+    normalize_keywords,
+    normalize_license,
+    normalize_orcid,
+    normalize_ror,
+    normalize_text,
+    normalize_title,
+    normalize_url,
+    normalize_work_type,
+    nullify,
+    NullRules,
+    parse_affiliation,
+    parse_citation,
+    parse_date,
+    parse_name,
+    parse_name_many,
+    parse_names_batch,
+    parse_names_list,
+    ParsedDate,
+    ParseError,
+    record_completeness,
+    record_fingerprint,
+    revert_and_detect_language,
+    revert_file,
+    revert_inverted_index,
+    revert_inverted_index_many,
+    revert_inverted_indexes,
+    revert_iter,
+    romanize,
+    set_log_level,
+    shard_bucket,
+    shard_key,
+    split_sentences,
+    strip_boilerplate,
+    strip_markup,
+    strip_markup_batch,
+    strip_markup_many,
+    surname_matches_expected,
+    tidy_reconstructed,
+    title_type_hint,
+    to_canonical_json,
+    tokenize,
+    tokenize_with_spans,
+    TokenizerOptions,
+    transform_crossref_work,
+    transform_datacite_record,
+    transform_dmp,
+    transform_openalex_work,
+    transform_works_many,
+    truncate_authors,
+    truncate_to_tokens,
+    WORK_TYPES,
+)
+from dmpworks.rust.names import parse_name_struct, parse_names_list_struct
 
 
 class TestParseName:
@@ -111,6 +211,19 @@ class TestParseName:
         assert has_alphabetic_initials("") is False
         assert has_alphabetic_initials(None) is False
 
+    def test_recase_surname(self):
+        assert parse_name(raw_given_name="Ronald", raw_surname="MCDONALD", recase_surname=True).surname == "McDonald"
+        assert parse_name(raw_given_name="Sean", raw_surname="o'brien", recase_surname=True).surname == "O'Brien"
+        assert parse_name(raw_given_name="Ann", raw_surname="macdonald", recase_surname=True).surname == "MacDonald"
+        assert parse_name(raw_given_name="Ann", raw_surname="MACHADO", recase_surname=True).surname == "Machado"
+        parsed = parse_name(raw_given_name="Jan", raw_surname="VAN DER BERG", recase_surname=True)
+        assert parsed.surname == "van der Berg"
+        assert parse_name(raw_given_name="Ann", raw_surname="smith-JONES", recase_surname=True).surname == "Smith-Jones"
+        assert parse_name(raw_full="john mcdonald", recase_surname=True).surname == "McDonald"
+
+    def test_recase_surname_disabled_by_default(self):
+        assert parse_name(raw_given_name="Ronald", raw_surname="MCDONALD").surname == "MCDONALD"
+
     def test_none_or_empty(self):
         for val in [None, "", "   "]:
             parsed = parse_name(raw_full=val)
@@ -141,6 +254,38 @@ class TestStripMarkup:
     def test_none(self):
         assert strip_markup(None) is None
 
+    def test_decode_entities(self):
+        options = MarkupOptions(decode_entities=True)
+        text = "<p>Soil &amp; water&#x2014;caf&#233;</p>"
+        assert strip_markup(text, options=options) == "Soil & water\u2014caf\u00e9"
+        assert strip_markup("<p>Soil &amp; water</p>") == "Soil &amp; water"
+        assert strip_markup("&nbsp;", null_if_equals=[""], options=options) is None
+
+    def test_jats(self):
+        text = (
+            "<jats:sec><jats:title>Abstract</jats:title><jats:p>Carbon in <jats:italic>alpine</jats:italic> soils"
+            '<jats:xref ref-type="bibr" rid="b1">1</jats:xref>.</jats:p></jats:sec>'
+        )
+        assert strip_markup(text, options=MarkupOptions(jats=True)) == "Carbon in alpine soils."
+        assert strip_markup("<p>Carbon<xref>2</xref></p>", options=MarkupOptions(jats=True)) == "Carbon"
+
+    def test_mathml(self):
+        text = (
+            'Flux of <mml:math alttext="x^2"><mml:msup><mml:mi>x</mml:mi><mml:mn>2</mml:mn></mml:msup></mml:math> m'
+        )
+        assert strip_markup(text) == "Flux of x2 m"
+        assert strip_markup(text, options=MarkupOptions(mathml="remove")) == "Flux of  m"
+        assert strip_markup(text, options=MarkupOptions(mathml="placeholder")) == "Flux of $x^2$ m"
+        assert strip_markup("<math><mi>y</mi></math>", options=MarkupOptions(mathml="placeholder")) == "[formula]"
+
+    def test_invalid_mathml_option(self):
+        with pytest.raises(ValueError):
+            MarkupOptions(mathml="latex")
+
+    def test_many_with_options(self):
+        options = MarkupOptions(decode_entities=True)
+        assert strip_markup_many(["<b>A &amp; B</b>", None], options=options) == ["A & B", None]
+
 
 class TestRevertInvertedIndex:
     def test_basic(self):
@@ -179,3 +324,2729 @@ class TestRevertInvertedIndex:
         data = {"<b>The</b>": [0], "<i>prelims</i>": [1], "<span class='x'>comprise:</span>": [2]}
         encoded = json.dumps(data).encode("utf-8")
         assert revert_inverted_index(encoded) == "The prelims comprise:"
+
+
+class TestNormalizeTitle:
+    def test_basic(self):
+        assert normalize_title("<i>The</i>  Quick, Brown Fox!") == "the quick brown fox"
+
+    def test_none(self):
+        assert normalize_title(None) is None
+        assert normalize_title(" !? ") is None
+
+    def test_subscripts(self):
+        assert normalize_title("H₂O in CO₂-rich brines", normalize_subscripts=True) == "h2o in co2 rich brines"
+        assert normalize_title("H₂O", normalize_subscripts=True) == normalize_title("H<sub>2</sub>O")
+
+    def test_superscripts(self):
+        assert normalize_title("E=mc²", normalize_subscripts=True) == "e mc2"
+        assert normalize_title("10⁻³ ⁰¹⁴", normalize_subscripts=True) == "10 3 014"
+
+    def test_subscripts_disabled_by_default(self):
+        assert normalize_title("H₂O") == "h₂o"
+
+    def test_strip_site_suffixes(self):
+        assert normalize_title("Ocean warming trends | Nature", strip_site_suffixes=True) == "ocean warming trends"
+        assert normalize_title("Ocean warming trends - PubMed", strip_site_suffixes=True) == "ocean warming trends"
+        assert normalize_title("Ocean warming - PubMed | NCBI", strip_site_suffixes=True) == "ocean warming"
+        assert normalize_title("Ocean warming trends | Nature") == "ocean warming trends nature"
+
+    def test_strip_site_suffixes_keeps_legitimate_separators(self):
+        title = "Climate change - a global perspective"
+        assert normalize_title(title, strip_site_suffixes=True) == "climate change a global perspective"
+        assert normalize_title("Nature | PubMed", strip_site_suffixes=True) == "nature"
+
+    def test_strip_site_suffixes_keeps_journal_names_in_titles(self):
+        assert normalize_title("Mind and body - Science", strip_site_suffixes=True) == "mind and body science"
+        assert normalize_title("Stem cells | cell", strip_site_suffixes=True) == "stem cells cell"
+        title = "Cell biology of the nucleus | Cell"
+        assert normalize_title(title, strip_site_suffixes=True) == "cell biology of the nucleus"
+
+    def test_case_folding(self):
+        assert normalize_title("Die Straße") == normalize_title("DIE STRASSE") == "die strasse"
+
+    def test_stop_words(self):
+        title = "The Effects of Soil Carbon on Crop Yields"
+        assert normalize_title(title, stop_words=["the", "of", "on"]) == "effects soil carbon crop yields"
+        assert normalize_title("The Of", stop_words=["THE", "of"]) is None
+
+
+class TestNormalizeLicense:
+    def test_creative_commons_phrasings(self):
+        assert normalize_license("CC BY 4.0") == "CC-BY-4.0"
+        assert normalize_license("Creative Commons Attribution 4.0 International") == "CC-BY-4.0"
+        assert normalize_license("https://creativecommons.org/licenses/by/4.0/") == "CC-BY-4.0"
+        assert normalize_license("cc-by-4.0") == "CC-BY-4.0"
+
+    def test_creative_commons_variants(self):
+        assert normalize_license("Creative Commons Attribution-NonCommercial 4.0 International") == "CC-BY-NC-4.0"
+        assert normalize_license("CC BY-NC-SA 3.0") == "CC-BY-NC-SA-3.0"
+        assert normalize_license("Attribution-NoDerivatives 4.0 International (CC BY-ND 4.0)") == "CC-BY-ND-4.0"
+        assert normalize_license("CC0 1.0 Universal Public Domain Dedication") == "CC0-1.0"
+
+    def test_other_licenses(self):
+        assert normalize_license("MIT License") == "MIT"
+        assert normalize_license("Apache License, Version 2.0") == "Apache-2.0"
+        assert normalize_license("GNU General Public License v3.0") == "GPL-3.0"
+
+    def test_unrecognized(self):
+        assert normalize_license("All rights reserved") is None
+        assert normalize_license("") is None
+        assert normalize_license(None) is None
+
+
+class TestNormalizeDoi:
+    def test_clean_doi(self):
+        assert normalize_doi("10.1234/ABC.def") == "10.1234/abc.def"
+        assert normalize_doi("  doi:10.1234/abc ") == "10.1234/abc"
+        assert normalize_doi("https://doi.org/10.1234/abc") == "10.1234/abc"
+        assert normalize_doi("http://dx.doi.org/10.1234/abc") == "10.1234/abc"
+
+    def test_tracking_query_and_fragment(self):
+        assert normalize_doi("https://doi.org/10.1234/abc?utm_source=x#section") == "10.1234/abc"
+        assert normalize_doi("https://doi.org/10.1234/abc#section") == "10.1234/abc"
+
+    def test_legitimate_special_characters_preserved(self):
+        # SICI-style DOIs can end in a bare "#"
+        sici = "10.1002/(sici)1097-4636(199706)35:4<433::aid-jbm3>3.0.co;2-#"
+        assert normalize_doi(f"https://doi.org/{sici}") == sici
+        # A "?" not followed by key=value parameters is kept
+        assert normalize_doi("https://doi.org/10.1234/what?") == "10.1234/what?"
+        # Bare DOIs are never truncated
+        assert normalize_doi("10.1234/abc?x=1") == "10.1234/abc?x=1"
+
+    def test_invalid(self):
+        assert normalize_doi(None) is None
+        assert normalize_doi("") is None
+        assert normalize_doi("11.1234/abc") is None
+        assert normalize_doi("10.1234/") is None
+        assert normalize_doi("https://example.com/10.1234/abc") is None
+
+
+class TestDmpWorkRelevance:
+    def test_relevant_pair(self):
+        dmp = "We will collect soil microbiome sequencing data from agricultural field sites to study nitrogen cycling."
+        work = "<p>Soil microbiome sequencing reveals drivers of nitrogen cycling in agricultural fields.</p>"
+        assert dmp_work_relevance(dmp, work) > 0.4
+
+    def test_irrelevant_pair(self):
+        dmp = "We will collect soil microbiome sequencing data from agricultural field sites to study nitrogen cycling."
+        work = "A survey of medieval poetry and the history of European manuscripts."
+        assert dmp_work_relevance(dmp, work) == 0.0
+
+    def test_identical_and_empty(self):
+        assert dmp_work_relevance("Ocean temperature records", "ocean temperature records") == 1.0
+        assert dmp_work_relevance("", "ocean temperature records") == 0.0
+        # Stop words alone carry no signal
+        assert dmp_work_relevance("the and of", "of the and") == 0.0
+
+
+class TestNormalizeKeywords:
+    def test_mixed_delimiters(self):
+        text = "Genomics; Machine  Learning,Bioinformatics\nÉcologie"
+        assert normalize_keywords(text) == ["genomics", "machine learning", "bioinformatics", "ecologie"]
+
+    def test_duplicates(self):
+        assert normalize_keywords("Genomics; genomics , GENOMICS; Proteomics") == ["genomics", "proteomics"]
+
+    def test_empty(self):
+        assert normalize_keywords(None) == []
+        assert normalize_keywords("") == []
+        assert normalize_keywords(" ; , \n") == []
+
+
+class TestKeywordOverlap:
+    def test_overlapping(self):
+        # {genomics, machine learning} shared out of {genomics, machine learning, bioinformatics, statistics}
+        a = "Genomics; Machine Learning, Bioinformatics"
+        b = "machine learning; genomics; Statistics"
+        assert keyword_overlap(a, b) == 0.5
+
+    def test_disjoint(self):
+        assert keyword_overlap("Genomics; Proteomics", "Oceanography, Climate") == 0.0
+
+    def test_empty(self):
+        assert keyword_overlap(None, None) == 0.0
+        assert keyword_overlap("", "Genomics") == 0.0
+
+
+class TestShardBucket:
+    def test_deterministic(self):
+        assert shard_bucket("10.1234/abc", 16) == shard_bucket("10.1234/abc", 16)
+        assert all(0 <= shard_bucket(f"key-{i}", 7) < 7 for i in range(100))
+        assert shard_bucket("anything", 1) == 0
+
+    def test_even_distribution(self):
+        num_buckets = 8
+        counts = [0] * num_buckets
+        for i in range(8000):
+            counts[shard_bucket(f"10.1234/work.{i}", num_buckets)] += 1
+        # Each bucket should receive roughly 1000 keys
+        assert all(800 < count < 1200 for count in counts)
+
+    def test_zero_buckets(self):
+        with pytest.raises(ValueError):
+            shard_bucket("10.1234/abc", 0)
+
+
+class TestShardKey:
+    def test_normalized(self):
+        shard = shard_key("Müller", 64)
+        assert shard is not None and 0 <= shard < 64
+        assert shard_key("MULLER ", 64) == shard
+        assert shard_key("muller\u200b", 64) == shard
+        assert shard_key("Soil Moisture: Dynamics", 64) == shard_key("soil  moisture dynamics", 64)
+
+    def test_stable(self):
+        assert shard_key("muller", 1000) == shard_bucket("muller", 1000)
+
+    def test_no_words(self):
+        assert shard_key(None, 8) is None
+        assert shard_key(" -- ", 8) is None
+
+    def test_zero_shards(self):
+        with pytest.raises(ValueError, match="num_shards"):
+            shard_key("Smith", 0)
+
+
+class TestRevertAndDetectLanguage:
+    def test_english(self):
+        words = "This study describes the collection of long term ocean temperature measurements".split()
+        data = {}
+        for i, word in enumerate(words):
+            data.setdefault(word, []).append(i)
+        encoded = json.dumps(data).encode("utf-8")
+
+        assert revert_and_detect_language(encoded) == (" ".join(words), "en")
+
+    def test_none(self):
+        assert revert_and_detect_language(None) is None
+        assert revert_and_detect_language(b"") is None
+        assert revert_and_detect_language(b"not json") is None
+
+
+class TestSurnameMatchesExpected:
+    def test_exact(self):
+        assert surname_matches_expected("John Smith", "Smith") is True
+        assert surname_matches_expected("Smith, John", "smith") is True
+
+    def test_accent_difference(self):
+        assert surname_matches_expected("José Muñoz", "Munoz") is True
+        assert surname_matches_expected("Hans Müller", "MULLER") is True
+
+    def test_small_typo(self):
+        assert surname_matches_expected("Jane Robertson", "Robertsen") is True
+
+    def test_clearly_wrong(self):
+        assert surname_matches_expected("John Smith", "Jones") is False
+        assert surname_matches_expected("Li Wu", "Xu") is False
+        assert surname_matches_expected(None, "Smith") is False
+        assert surname_matches_expected("John Smith", "") is False
+
+
+class TestCitationName:
+    def test_vancouver(self):
+        assert citation_name("John Michael Smith", "vancouver") == "Smith JM"
+        assert citation_name("Smith, J.M.") == "Smith JM"
+
+    def test_apa(self):
+        assert citation_name("John Michael Smith", "apa") == "Smith, J. M."
+        assert citation_name("Jane Doe", "apa") == "Doe, J."
+
+    def test_surname_only(self):
+        assert citation_name("Smith", "vancouver") == "Smith"
+        assert citation_name("Smith", "apa") == "Smith"
+
+    def test_empty_or_unknown_style(self):
+        assert citation_name(None) is None
+        assert citation_name("   ", "apa") is None
+        assert citation_name("John Smith", "mla") is None
+
+
+class TestAuthorCount:
+    def test_separators(self):
+        assert author_count("Smith, J.; Doe, A. and Lee, K.") == 3
+        assert author_count("Smith, J., Doe, A., Lee, K.") == 3
+        assert author_count("John Smith, Jane Doe & Kim Lee") == 3
+        assert author_count("John Smith, Jane Doe, et al.") == 2
+
+    def test_name_ending_in_et(self):
+        assert author_count("John Smith; Janet Al") == 2
+        assert author_count("Smith, J.; Bennet, Al") == 2
+
+    def test_empty(self):
+        assert author_count(None) == 0
+        assert author_count("  ") == 0
+
+
+class TestTruncateAuthors:
+    def test_ten_authors_capped_at_three(self):
+        byline = "; ".join(f"Author{i}, A." for i in range(10))
+        assert author_count(byline) == 10
+        assert truncate_authors(byline, 3) == "Author0, A.; Author1, A.; Author2, A. et al."
+
+    def test_not_truncated(self):
+        assert truncate_authors("John Smith and Jane Doe", 3) == "John Smith; Jane Doe"
+        assert truncate_authors(None, 3) == ""
+
+    def test_name_ending_in_et(self):
+        assert truncate_authors("John Smith; Janet Al", 3) == "John Smith; Janet Al"
+        assert truncate_authors("John Smith; Janet Al", 1) == "John Smith et al."
+
+
+class TestNormalizeAuthorJoin:
+    def test_join_styles(self):
+        expected = "Ada Lovelace; Alan Turing; Grace Hopper"
+        assert normalize_author_join("Ada Lovelace, Alan Turing, and Grace Hopper") == expected
+        assert normalize_author_join("Ada Lovelace, Alan Turing & Grace Hopper") == expected
+        assert normalize_author_join("Ada Lovelace; Alan Turing; Grace Hopper") == expected
+        assert normalize_author_join("Ada Lovelace, Alan Turing and Grace Hopper") == expected
+
+    def test_surname_initials(self):
+        assert normalize_author_join("Lovelace, A., Turing, A. & Hopper, G.") == "Lovelace, A.; Turing, A.; Hopper, G."
+
+    def test_empty(self):
+        assert normalize_author_join(None) is None
+        assert normalize_author_join(" ; ") is None
+
+
+class TestExtractGrantIds:
+    def test_nih(self):
+        text = "Supported by NIH Grant No. R01-CA123456 and 1U54 HG004028-01, and again R01CA123456."
+        assert extract_grant_ids(text) == ["R01CA123456", "U54HG004028"]
+
+    def test_nsf(self):
+        text = "This work was funded by NSF award 1234567 and DBI-7654321 (see also DBI 7654321)."
+        assert extract_grant_ids(text) == ["1234567", "DBI-7654321"]
+
+    def test_mixed_order(self):
+        text = "Funding: NSF DMR-1507101; NIH R21 AI111143."
+        assert extract_grant_ids(text) == ["DMR-1507101", "R21AI111143"]
+
+    def test_nsf_organization_codes_only(self):
+        assert extract_grant_ids("Funded by NIH 1234567 and DBI-7654321, not ABCD 1111111.") == ["DBI-7654321"]
+
+    def test_none_found(self):
+        assert extract_grant_ids("No funding was received for this study in 2021.") == []
+        assert extract_grant_ids("Call 5551234 for details") == []
+        assert extract_grant_ids(None) == []
+
+
+class TestCombinedRelevance:
+    weights = {"title": 3.0, "abstract": 2.0, "author": 4.0, "keyword": 1.0, "year": 1.0, "identifier": 5.0}
+
+    def test_full_signals(self):
+        signals = {"title": 1.0, "abstract": 1.0, "author": 1.0, "keyword": 1.0, "year": 1.0, "identifier": 1.0}
+        assert combined_relevance(signals, self.weights) == pytest.approx(1.0)
+
+        signals = {"title": 0.5, "abstract": 0.5, "author": 0.5, "keyword": 0.5, "year": 0.5, "identifier": 0.5}
+        assert combined_relevance(signals, self.weights) == pytest.approx(0.5)
+
+    def test_partial_signals_are_renormalized(self):
+        # Only title and author are present: (3 * 1.0 + 4 * 0.5) / (3 + 4)
+        signals = {"title": 1.0, "author": 0.5}
+        assert combined_relevance(signals, self.weights) == pytest.approx(5.0 / 7.0)
+
+    def test_unweighted_and_empty(self):
+        assert combined_relevance({"unknown": 1.0}, self.weights) == 0.0
+        assert combined_relevance({}, self.weights) == 0.0
+
+    def test_clamped(self):
+        assert combined_relevance({"title": 2.0}, self.weights) == 1.0
+        assert combined_relevance({"title": -1.0}, self.weights) == 0.0
+
+
+class TestNormalizeDate:
+    def test_numeric_forms(self):
+        assert normalize_date("2021") == "2021"
+        assert normalize_date("2021-3") == "2021-03"
+        assert normalize_date("2021-03-05") == "2021-03-05"
+        assert normalize_date("2021/3/5") == "2021-03-05"
+
+    def test_rfc3339(self):
+        assert normalize_date("2021-03-05T12:30:00Z") == "2021-03-05"
+        assert normalize_date("2021-03-05T12:30:00.123+02:00") == "2021-03-05"
+
+    def test_month_names(self):
+        assert normalize_date("March 2021") == "2021-03"
+        assert normalize_date("Sept. 2021") == "2021-09"
+        assert normalize_date("5 Mar 2021") == "2021-03-05"
+        assert normalize_date("March 5, 2021") == "2021-03-05"
+
+    def test_unparseable(self):
+        assert normalize_date("sometime last year") is None
+        assert normalize_date("2021-13") is None
+        assert normalize_date("2021-02-30") is None
+        assert normalize_date("") is None
+        assert normalize_date(None) is None
+
+
+class TestBylinesMatch:
+    def test_matching(self):
+        a = "John Smith, Maria Garcia and Wei Chen"
+        b = "Garcia, M.; Chen, W.; Smith, J."
+        assert bylines_match(a, b) is True
+
+    def test_truncated_et_al(self):
+        full = "John Smith; Maria Garcia; Wei Chen; Anna Kowalski; Omar Haddad"
+        assert bylines_match("Smith, J., Garcia, M., et al.", full) is True
+        assert bylines_match("Smith, J., Garcia, M.", full) is False
+
+    def test_name_ending_in_et(self):
+        assert bylines_match("Smith J, Janet Al", "Smith J") is False
+        assert bylines_match("Smith J, et Al", "Smith J") is True
+
+    def test_partial(self):
+        a = "John Smith; Maria Garcia; Wei Chen"
+        b = "John Smith; Peter Jones; Li Wang"
+        assert bylines_match(a, b, threshold=0.8) is False
+        assert bylines_match(a, b, threshold=0.3) is True
+
+    def test_disjoint(self):
+        assert bylines_match("John Smith; Maria Garcia", "Peter Jones; Li Wang", threshold=0.1) is False
+        # Same surname with conflicting initials is not a match
+        assert bylines_match("John Smith", "Karen Smith", threshold=0.1) is False
+        assert bylines_match(None, "John Smith") is False
+
+
+class TestTokenizeWithSpans:
+    def test_spans_slice_back_to_tokens(self):
+        text = "Étude of the Ocean's uptake, 2021 — naïve models!"
+        encoded = text.encode("utf-8")
+        spans = tokenize_with_spans(text)
+
+        tokens = [token for token, _, _ in spans]
+        assert tokens == ["étude", "of", "the", "ocean's", "uptake", "2021", "naïve", "models"]
+        for token, start, end in spans:
+            assert encoded[start:end].decode("utf-8").lower() == token
+
+    @pytest.mark.parametrize(
+        "text",
+        ["Hy\xadbrid mod\u200bels of cli\xadmate", "soil\u00a0carbon\u2009data", "\ufeffbyte\u2060order \x07bell"],
+    )
+    def test_invisible_characters(self, text):
+        spans = tokenize_with_spans(text)
+        assert [token for token, _, _ in spans] == tokenize(text)
+        encoded = text.encode("utf-8")
+        for token, start, end in spans:
+            assert "".join(c for c in encoded[start:end].decode("utf-8").lower() if c.isprintable()) == token
+
+    def test_empty(self):
+        assert tokenize_with_spans("") == []
+        assert tokenize_with_spans(" , . ") == []
+
+
+class TestInitialsCompatible:
+    def test_identical(self):
+        assert initials_compatible(parse_name(raw_full="John Michael Smith"), parse_name(raw_full="J. M. Smith")) == 1.0
+
+    def test_subset(self):
+        assert initials_compatible(parse_name(raw_full="J. Smith"), parse_name(raw_full="John Michael Smith")) == 1.0
+        a = parse_name(raw_full="John Luther Smith")
+        b = parse_name(raw_full="John Michael Luther Smith")
+        assert initials_compatible(a, b) == 1.0
+
+    def test_conflict(self):
+        assert initials_compatible(parse_name(raw_full="John Smith"), parse_name(raw_full="Karen Smith")) == 0.0
+        partial = initials_compatible(parse_name(raw_full="John Michael Smith"), parse_name(raw_full="John K. Smith"))
+        assert 0.0 < partial < 1.0
+
+    def test_missing_initials(self):
+        assert initials_compatible(parse_name(raw_full="Smith"), parse_name(raw_full="John Smith")) < 1.0
+
+
+class TestTidyReconstructed:
+    def test_space_before_punctuation(self):
+        assert tidy_reconstructed("soil , water and air") == "soil, water and air"
+        assert tidy_reconstructed("the final word .") == "the final word."
+        assert tidy_reconstructed("results : see below ; really ?") == "results: see below; really?"
+
+    def test_repeated_punctuation(self):
+        assert tidy_reconstructed("Remarkable!! Really??") == "Remarkable! Really?"
+        assert tidy_reconstructed("one,, two.. three...") == "one, two. three..."
+
+    def test_brackets(self):
+        assert tidy_reconstructed("samples ( n = 12 ) were [ re ] analysed") == "samples (n = 12) were [re] analysed"
+
+    def test_quotes(self):
+        assert tidy_reconstructed('the so-called " dark matter " problem') == 'the so-called "dark matter" problem'
+        assert tidy_reconstructed('an unbalanced " quote') == 'an unbalanced " quote'
+
+    def test_scientific_notation_unchanged(self):
+        text = "significant at p < .05 with 1,000 samples of 3.5 mg and H2O: 10.1"
+        assert tidy_reconstructed(text) == text
+
+    def test_strip_trailing_authors(self):
+        text = "We measured carbon in soils. Jane Smith, J. Doe, University of Oxford, 0000-0002-1825-0097"
+        assert tidy_reconstructed(text, strip_trailing_authors=True) == "We measured carbon in soils."
+        text = "We measured carbon in soils. Jane Smith, John Doe, Alice Jones."
+        assert tidy_reconstructed(text, strip_trailing_authors=True) == "We measured carbon in soils."
+
+    def test_strip_trailing_authors_keeps_abstract_text(self):
+        text = "We measured carbon in soils. Sites included Oxford, Cambridge and London."
+        assert tidy_reconstructed(text, strip_trailing_authors=True) == text
+        text = "Samples from Oxford, Cambridge, London. Jane Smith"
+        assert tidy_reconstructed(text, strip_trailing_authors=True) == text
+        text = "We measured carbon in soils. Contact: jane.smith@example.org"
+        assert tidy_reconstructed(text, strip_trailing_authors=True) == text
+
+    def test_trailing_authors_kept_by_default(self):
+        text = "We measured carbon in soils. Jane Smith, John Doe, Alice Jones"
+        assert tidy_reconstructed(text) == text
+
+
+class TestRecordCompleteness:
+    def test_complete(self):
+        score = record_completeness(has_title=True, has_abstract=True, has_doi=True, author_count=5, has_year=True)
+        assert score == pytest.approx(1.0)
+
+    def test_sparse(self):
+        score = record_completeness(has_title=True, has_abstract=False, has_doi=False, author_count=0, has_year=False)
+        assert score == pytest.approx(0.25)
+        assert record_completeness(False, False, False, 0, False) == 0.0
+
+    def test_authors_scale(self):
+        one = record_completeness(True, True, True, 1, True)
+        three = record_completeness(True, True, True, 3, True)
+        assert one < three
+        assert three == pytest.approx(1.0)
+
+
+class TestRevertIter:
+    def test_matches_revert_inverted_index(self):
+        data = {"The": [0, 4], "prelims": [1], "<b>comprise:</b>": [2], "Half-Title": [3], "end": [6]}
+        encoded = json.dumps(data).encode("utf-8")
+        words = list(revert_iter(encoded))
+        assert words == ["The", "prelims", "comprise:", "Half-Title", "The", "end"]
+        assert words == revert_inverted_index(encoded).split()
+
+    def test_lazy_iteration(self):
+        encoded = json.dumps({"A": [0], "B": [1]}).encode("utf-8")
+        words = revert_iter(encoded)
+        assert next(words) == "A"
+        assert next(words) == "B"
+        with pytest.raises(StopIteration):
+            next(words)
+
+    def test_none(self):
+        assert list(revert_iter(None)) == []
+        assert list(revert_iter(b"")) == []
+        assert list(revert_iter(b"not json")) == []
+
+
+class TestParseNameOrganization:
+    def test_entities_and_abbreviations_cleaned(self):
+        parsed = parse_name(raw_full="Dept. of Health &amp; Human Services")
+        assert parsed.first_initial is None
+        assert parsed.given_name is None
+        assert parsed.middle_initials is None
+        assert parsed.middle_names is None
+        assert parsed.surname is None
+        assert parsed.full == "Department of Health & Human Services"
+
+    def test_numeric_entities(self):
+        parsed = parse_name(raw_full="Univ. of S&#227;o Paulo")
+        assert parsed.full == "University of São Paulo"
+
+
+class TestDoiPrefix:
+    def test_prefix(self):
+        assert doi_prefix("https://doi.org/10.1371/journal.pone.0123456") == "10.1371"
+        assert doi_prefix("10.5281/ZENODO.123") == "10.5281"
+
+    def test_invalid(self):
+        assert doi_prefix(None) is None
+        assert doi_prefix("not a doi") is None
+
+
+class TestDoiSameRegistrant:
+    def test_same_registrant(self):
+        assert doi_same_registrant("10.1371/journal.pone.0123456", "doi:10.1371/journal.pbio.0000001")
+
+    def test_different_registrant(self):
+        assert not doi_same_registrant("10.1371/journal.pone.0123456", "10.5281/zenodo.123")
+
+    def test_invalid(self):
+        assert not doi_same_registrant(None, "10.5281/zenodo.123")
+        assert not doi_same_registrant("not a doi", "not a doi")
+
+
+class TestCjkSurnameMatch:
+    def test_identical(self):
+        assert cjk_surname_match("张伟", "张伟") == 1.0
+        assert cjk_surname_match("张 伟", "张伟") == 1.0
+
+    def test_same_pinyin_spellings(self):
+        # Simplified and traditional forms of Zhang Wei share the pinyin "zhang wei"
+        score = cjk_surname_match("张伟", "張偉")
+        assert score == pytest.approx(0.81)
+
+    def test_same_surname_different_given_name(self):
+        assert cjk_surname_match("张伟", "张芳") == pytest.approx(0.5)
+
+    def test_different_names(self):
+        assert cjk_surname_match("张伟", "李娜") == 0.0
+
+    def test_romanized(self):
+        assert cjk_surname_match("Zhāng Wěi", "Zhang Wei") == 1.0
+        assert cjk_surname_match("张伟", "Zhang Wei") == pytest.approx(0.81)
+        assert cjk_surname_match("张伟", "Wei Zhang") == pytest.approx(0.81)
+        assert cjk_surname_match("Zhang Wei", "Zhang Fang") == pytest.approx(0.5)
+        assert cjk_surname_match("张伟", "Li Na") == 0.0
+
+    def test_compound_surnames(self):
+        assert cjk_surname_match("欧阳修", "Ouyang Xiu") == pytest.approx(0.81)
+        assert cjk_surname_match("欧阳修", "欧阳娜") == pytest.approx(0.5)
+        assert cjk_surname_match("欧阳修", "欧修") == 0.0
+
+    def test_not_cjk(self):
+        assert cjk_surname_match("", "张伟") == 0.0
+        assert cjk_surname_match("1234", "张伟") == 0.0
+        assert cjk_surname_match("Чжан Вэй", "张伟") == 0.0
+
+
+class TestCitekey:
+    def test_citekey(self):
+        assert citekey("John Smith", 2021) == "smith2021"
+        assert citekey("Smith, John", 2021) == "smith2021"
+        assert citekey("José Muñoz", 1999) == "munoz1999"
+
+    def test_missing_year(self):
+        assert citekey("John Smith", None) is None
+
+    def test_missing_author(self):
+        assert citekey(None, 2021) is None
+        assert citekey("", 2021) is None
+
+
+class TestIssnSetMatch:
+    def test_share_one_issn(self):
+        # Print and electronic ISSNs of the same journal, with one work listing only the electronic ISSN
+        assert issn_set_match(["0028-0836", "1476-4687"], ["1476-4687"])
+        assert issn_set_match(["ISSN 0028-0836", None], ["00280836"])
+        assert issn_set_match(["2434-561x"], ["2434-561X"])
+
+    def test_share_none(self):
+        assert not issn_set_match(["0028-0836", "1476-4687"], ["0036-8075", "1095-9203"])
+
+    def test_invalid_issns_ignored(self):
+        # Wrong check digit
+        assert not issn_set_match(["0028-0837"], ["0028-0837"])
+        assert not issn_set_match([None, ""], [None, ""])
+
+
+class TestTitleTypeHint:
+    def test_dataset(self):
+        assert title_type_hint("Dataset: Soil carbon measurements 2019") == "dataset"
+        assert title_type_hint("Data from: Soil carbon in alpine meadows") == "dataset"
+        assert title_type_hint("Soil carbon measurements [Dataset]") == "dataset"
+
+    def test_software(self):
+        assert title_type_hint("Software: soilcarbon v1.2") == "software"
+        assert title_type_hint("soilcarbon (software)") == "software"
+
+    def test_correction(self):
+        assert title_type_hint("Correction: Soil carbon in alpine meadows") == "correction"
+        assert title_type_hint("Erratum to Soil carbon in alpine meadows") == "correction"
+
+    def test_review(self):
+        assert title_type_hint("Review of Soil Carbon Dynamics") == "review"
+
+    def test_research_article(self):
+        assert title_type_hint("Soil carbon in alpine meadows") is None
+        assert title_type_hint("A review of soil carbon dynamics") is None
+        assert title_type_hint(None) is None
+
+
+class TestRevertFile:
+    def write_input(self, path):
+        lines = [
+            json.dumps({"Soil": [0], "carbon": [1]}),
+            "not json",
+            "",
+            json.dumps({"Water": [0], "quality": [1]}),
+            json.dumps({}),
+        ]
+        path.write_text("\n".join(lines) + "\n")
+
+    def test_ordered(self, tmp_path):
+        input_path = tmp_path / "input.jsonl"
+        output_path = tmp_path / "output.jsonl"
+        self.write_input(input_path)
+
+        counts = []
+        processed, malformed = revert_file(input_path, output_path, progress=counts.append, chunk_size=2)
+        assert processed == 3
+        assert malformed == 1
+        # Chunks of two non-blank lines, the first containing the malformed line
+        assert counts == [1, 3]
+
+        records = [json.loads(line) for line in output_path.read_text().splitlines()]
+        assert records == [
+            {"line": 0, "abstract": "Soil carbon"},
+            {"line": 3, "abstract": "Water quality"},
+            {"line": 4, "abstract": None},
+        ]
+
+    def test_parallel_unordered(self, tmp_path):
+        input_path = tmp_path / "input.jsonl"
+        output_path = tmp_path / "output.jsonl"
+        self.write_input(input_path)
+
+        counts = []
+        processed, malformed = revert_file(
+            input_path, output_path, parallel=True, ordered=False, progress=counts.append
+        )
+        assert (processed, malformed) == (3, 1)
+        assert counts == [3]
+
+        records = [json.loads(line) for line in output_path.read_text().splitlines()]
+        assert sorted(records, key=lambda record: record["line"])[0] == {"line": 0, "abstract": "Soil carbon"}
+
+    def test_callback_error(self, tmp_path):
+        input_path = tmp_path / "input.jsonl"
+        self.write_input(input_path)
+
+        def fail(_processed):
+            raise RuntimeError("stop")
+
+        with pytest.raises(RuntimeError):
+            revert_file(input_path, tmp_path / "output.jsonl", progress=fail)
+
+    def test_invalid_chunk_size(self, tmp_path):
+        with pytest.raises(ValueError):
+            revert_file(tmp_path / "input.jsonl", tmp_path / "output.jsonl", chunk_size=0)
+
+
+class TestParseNamesBatch:
+    def test_matches_parse_name(self):
+        names = ["John Doe", "Dr. Martin Luther King Jr.", "sam wu", None]
+        result = pa.array(parse_names_batch(pa.array(names, type=pa.string()))).to_pylist()
+        assert len(result) == 4
+        for name, row in zip(names, result):
+            assert row == parse_name_struct(raw_full=name).to_dict()
+
+    def test_recase_surname(self):
+        result = pa.array(parse_names_batch(pa.array(["JOHN MCDONALD"]), recase_surname=True)).to_pylist()
+        assert result[0]["surname"] == "McDonald"
+
+    def test_large_string(self):
+        result = pa.array(parse_names_batch(pa.array(["John Doe"], type=pa.large_string()))).to_pylist()
+        assert result[0]["surname"] == "Doe"
+
+    def test_non_string_array(self):
+        with pytest.raises(TypeError):
+            parse_names_batch(pa.array([1, 2, 3]))
+
+
+class TestParseNameMany:
+    def test_matches_parse_name(self):
+        names = ["John Doe", "Doe, John", "sam wu", None]
+        assert parse_name_many(names) == [parse_name(raw_full=name) for name in names]
+
+    def test_num_threads(self):
+        names = [f"John Doe{i}" for i in range(100)]
+        assert parse_name_many(names, num_threads=2) == parse_name_many(names)
+
+    def test_invalid_num_threads(self):
+        with pytest.raises(ValueError):
+            parse_name_many(["John Doe"], num_threads=0)
+
+    def test_collect_errors(self):
+        names = ["John Doe", "123", None, "Smith"]
+        parsed, errors = parse_name_many(names, collect_errors=True)
+        assert parsed == parse_name_many(names)
+        assert [(error.row, error.field) for error in errors] == [(1, None), (3, None)]
+        assert "full='123'" in errors[0].message
+
+
+class TestRevertInvertedIndexMany:
+    def test_matches_revert_inverted_index(self):
+        texts = [json.dumps({"Hello": [0], "world": [1]}).encode("utf-8"), None, b"not json"]
+        expected = [revert_inverted_index(text) for text in texts]
+        assert revert_inverted_index_many(texts) == expected
+        assert revert_inverted_index_many(texts, num_threads=2) == expected
+
+    def test_collect_errors(self):
+        texts = [b"not json", json.dumps({"Hello": [0]}).encode("utf-8"), None, b"[1, 2]"]
+        reverted, errors = revert_inverted_index_many(texts, collect_errors=True)
+        assert reverted == [None, "Hello", None, None]
+        assert [(error.row, error.field) for error in errors] == [(0, None), (3, None)]
+        assert errors[0].message.startswith("invalid json: ")
+
+
+
+class TestRevertInvertedIndexes:
+    def test_matches_revert_inverted_index(self):
+        texts = [json.dumps({"Hello": [0], "world": [1]}).encode("utf-8"), None, b"", b"not json"]
+        result = revert_inverted_indexes(pa.array(texts, type=pa.large_binary()))
+        assert pa.array(result).type == pa.string()
+        assert pa.array(result).to_pylist() == [revert_inverted_index(text) for text in texts]
+
+    def test_binary_and_string_arrays(self):
+        index = json.dumps({"N/A": [0]})
+        for array in [pa.array([index.encode("utf-8")]), pa.array([index], type=pa.large_string())]:
+            assert pa.array(revert_inverted_indexes(array)).to_pylist() == ["N/A"]
+            assert pa.array(revert_inverted_indexes(array, null_if_equals=["N/A"], num_threads=2)).to_pylist() == [
+                None
+            ]
+
+    def test_invalid_type(self):
+        with pytest.raises(TypeError, match="binary or string array"):
+            revert_inverted_indexes(pa.array([1, 2, 3]))
+
+class TestStripMarkupMany:
+    def test_matches_strip_markup(self):
+        texts = ["<b>Hello</b>", "  ", None, "N/A"]
+        assert strip_markup_many(texts, null_if_equals=["N/A"]) == ["Hello", None, None, None]
+
+
+class TestStripMarkupBatch:
+    def test_matches_strip_markup_many(self):
+        texts = ["<b>Hello</b>", "  ", None, "<p>N/A</p>", "<i>Abstract not available</i>"]
+        result = strip_markup_batch(pa.array(texts), null_rules=NullRules(values=["N/A"], presets=[]), num_threads=2)
+        assert pa.array(result).to_pylist() == strip_markup_many(texts, null_if_equals=["N/A"])
+
+    def test_null_rules(self):
+        texts = ["<p>Abstract not available</p>", "<p>No abstract available for this work.</p>", "Abstracts matter"]
+        rules = NullRules(patterns=[r"abstract not available", r"no abstract.*"], presets=[])
+        result = strip_markup_batch(pa.array(texts), null_rules=rules)
+        assert pa.array(result).to_pylist() == [None, None, "Abstracts matter"]
+
+    def test_options(self):
+        options = MarkupOptions(decode_entities=True, null_rules=NullRules(values=["n/a"]))
+        texts = pa.array(["<b>A &amp; B</b>", "<p>N/A</p>"])
+        assert pa.array(strip_markup_batch(texts, options=options)).to_pylist() == ["A & B", None]
+
+    @pytest.mark.parametrize("string_type", [pa.string(), pa.large_string(), pa.string_view()])
+    def test_keeps_string_type(self, string_type):
+        result = pa.array(strip_markup_batch(pa.array(["<b>Hello</b>", None], type=string_type)))
+        assert result.type == string_type
+        assert result.to_pylist() == ["Hello", None]
+
+    def test_invalid(self):
+        with pytest.raises(TypeError, match="string array"):
+            strip_markup_batch(pa.array([1, 2, 3]))
+
+
+class TestNormalizeOrcid:
+    def test_formats(self):
+        assert normalize_orcid("0000-0002-1825-0097") == "0000-0002-1825-0097"
+        assert normalize_orcid("https://orcid.org/0000-0002-1825-0097") == "0000-0002-1825-0097"
+        assert normalize_orcid("http://orcid.org/0000-0002-1825-0097/") == "0000-0002-1825-0097"
+        assert normalize_orcid("ORCID: 0000 0002 1825 0097") == "0000-0002-1825-0097"
+        assert normalize_orcid("0000000218250097") == "0000-0002-1825-0097"
+
+    def test_check_character_x(self):
+        assert normalize_orcid("orcid.org/0000-0002-1694-233x") == "0000-0002-1694-233X"
+
+    def test_invalid_checksum(self):
+        assert normalize_orcid("0000-0002-1825-0098") is None
+
+    def test_no_orcid(self):
+        assert normalize_orcid(None) is None
+        assert normalize_orcid("") is None
+        assert normalize_orcid("12345") is None
+        assert normalize_orcid("00000002182500971") is None
+
+    @pytest.mark.parametrize(
+        "text",
+        [
+            "0000-0002-1825-00\u06697",  # Arabic-Indic nine
+            "0000-0002-1825-\uff10\uff10\uff19\uff17",  # fullwidth digits
+            "\u0966" * 16,  # Devanagari zeros
+        ],
+    )
+    def test_non_ascii_digits(self, text):
+        assert normalize_orcid(text) is None
+
+
+class TestNormalizeRor:
+    def test_formats(self):
+        assert normalize_ror("https://ror.org/03yrm5c26") == "03yrm5c26"
+        assert normalize_ror("ror.org/03YRM5C26") == "03yrm5c26"
+        assert normalize_ror("03yrm5c26") == "03yrm5c26"
+        assert normalize_ror("ROR: 03yrm5c26") == "03yrm5c26"
+
+    def test_invalid_checksum(self):
+        assert normalize_ror("https://ror.org/03yrm5c27") is None
+
+    def test_no_ror(self):
+        assert normalize_ror(None) is None
+        assert normalize_ror("University of California") is None
+
+    @pytest.mark.parametrize("text", ["0abcde\u017f12", "03yrm5c2\u0666", "03yrm\u212a026"])
+    def test_non_ascii(self, text):
+        # Long s, Arabic-Indic six and the Kelvin sign, which case-insensitive matching would take for ASCII
+        assert normalize_ror(text) is None
+        assert extract_institution_ids(f"ror {text}").ror == []
+
+
+class TestExtractInstitutionIds:
+    def test_affiliation_string(self):
+        ids = extract_institution_ids(
+            "California Digital Library, https://ror.org/03yrm5c26, grid.30389.31, Ringgold: 8785"
+        )
+        assert ids.ror == ["03yrm5c26"]
+        assert ids.grid == ["grid.30389.31"]
+        assert ids.ringgold == ["8785"]
+
+    def test_identifier_objects(self):
+        text = json.dumps(
+            [
+                {"affiliationIdentifier": "https://ror.org/03yrm5c26", "affiliationIdentifierScheme": "ROR"},
+                {"identifier": "8785", "scheme": "Ringgold"},
+                {"ror": "https://ror.org/05dxps055", "grid": "grid.20861.3d"},
+            ]
+        )
+        ids = extract_institution_ids(text)
+        assert ids.ror == ["03yrm5c26", "05dxps055"]
+        assert ids.grid == ["grid.20861.3d"]
+        assert ids.ringgold == ["8785"]
+
+    def test_unlabelled_numbers_ignored(self):
+        ids = extract_institution_ids("Room 8785, University of California")
+        assert ids == ([], [], [])
+        assert extract_institution_ids(None) == ([], [], [])
+
+
+class TestCompareNames:
+    def test_same_person(self):
+        similarity = compare_names("John M. Smith", "Smith, J.")
+        assert similarity.exact_surname
+        assert similarity.initials_compatible
+        assert similarity.transliteration_match
+        assert similarity.score > 0.5
+
+    def test_transliteration(self):
+        similarity = compare_names("José Muñoz", "Jose Munoz")
+        assert not similarity.exact_surname
+        assert similarity.transliteration_match
+        assert similarity.score == pytest.approx(1.0)
+
+    def test_conflicting_initials(self):
+        similarity = compare_names("John Smith", "Alice Smith")
+        assert similarity.exact_surname
+        assert not similarity.initials_compatible
+        assert similarity.score == 0.0
+
+    def test_different_people(self):
+        similarity = compare_names("John Smith", "John Doe")
+        assert not similarity.exact_surname
+        assert not similarity.transliteration_match
+        assert similarity.score == 0.0
+        assert compare_names(None, "John Doe").score == 0.0
+
+    def test_phonetic(self):
+        assert compare_names("John Müller", "J. Mueller").score == 0.0
+        similarity = compare_names("John Müller", "J. Mueller", phonetic=True)
+        assert similarity.phonetic_match
+        assert not similarity.transliteration_match
+        assert similarity.score == pytest.approx(0.9)
+        assert compare_names("John Schmidt", "J. Smith", phonetic=True).phonetic_match
+        assert compare_names("John Smith", "Alice Schmidt", phonetic=True).score == 0.0
+        assert not compare_names("John Smith", "John Doe", phonetic=True).phonetic_match
+        assert not compare_names("John Müller", "J. Mueller").phonetic_match
+
+
+class TestBuildInvertedIndex:
+    def test_build(self):
+        index = build_inverted_index('The prelims comprise: "Half-Title", The end')
+        assert json.loads(index) == {
+            "The": [0, 4],
+            "prelims": [1],
+            "comprise:": [2],
+            '"Half-Title",': [3],
+            "end": [5],
+        }
+
+    def test_round_trip(self):
+        text = "Soil carbon and soil   nitrogen were measured in soil cores."
+        index = build_inverted_index(text)
+        assert revert_inverted_index(index.encode("utf-8")) == " ".join(text.split())
+
+    def test_empty(self):
+        assert build_inverted_index(None) is None
+        assert build_inverted_index("   ") is None
+
+
+class TestJsonlReader:
+    records = [
+        {"id": "W1", "title": "Soil carbon", "cited_by_count": 3, "authorships": [{"name": "Jane Smith"}]},
+        {"id": "W2", "title": None, "is_oa": True, "score": 0.5},
+    ]
+
+    def test_plain(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        path.write_text("\n".join(json.dumps(record) for record in self.records) + "\n\n")
+        assert list(JsonlReader(path)) == self.records
+
+    def test_gzip(self, tmp_path):
+        path = tmp_path / "works.gz"
+        with gzip.open(path, "wt") as f:
+            f.write("\n".join(json.dumps(record) for record in self.records))
+        assert list(JsonlReader(str(path))) == self.records
+
+    def test_field_projection(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        path.write_text("\n".join(json.dumps(record) for record in self.records))
+        assert list(JsonlReader(path, fields=["id", "title", "is_oa"])) == [
+            {"id": "W1", "title": "Soil carbon", "is_oa": None},
+            {"id": "W2", "title": None, "is_oa": True},
+        ]
+
+    def test_progress(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        path.write_text("{}\n" * 20_005)
+        calls = []
+        reader = JsonlReader(path, progress=lambda processed, total: calls.append((processed, total)))
+        assert sum(1 for _ in reader) == 20_005
+        assert list(reader) == []
+        assert calls == [(10_000, None), (20_000, None), (20_005, None)]
+
+    def test_invalid_json(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        path.write_text('{"id": "W1"}\nnot json\n')
+        reader = JsonlReader(path)
+        assert next(reader) == {"id": "W1"}
+        with pytest.raises(ValueError, match="line 2"):
+            next(reader)
+
+    def test_missing_file(self, tmp_path):
+        with pytest.raises(FileNotFoundError):
+            JsonlReader(tmp_path / "missing.jsonl")
+
+    def test_since(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        records = [
+            {"id": "W1", "updated_date": "2024-03-01T00:00:00", "created_date": "2020-01-01"},
+            {"id": "W2", "updated_date": "2023-12-31T00:00:00", "created_date": "2020-01-01"},
+            {"id": "W3"},
+        ]
+        path.write_text("\n".join(json.dumps(record) for record in records))
+        reader = JsonlReader(path, fields=["id"], source="openalex", updated_since="2024")
+        assert list(reader) == [{"id": "W1"}, {"id": "W3"}]
+        assert reader.skipped == 1
+        reader = JsonlReader(path, source="openalex", created_since="2021")
+        assert [record["id"] for record in reader] == ["W3"]
+        assert reader.skipped == 2
+        assert JsonlReader(path, source="openalex").skipped == 0
+
+    def test_since_requires_source(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        path.write_text("{}\n")
+        with pytest.raises(ValueError, match="source is required"):
+            JsonlReader(path, updated_since="2024")
+        with pytest.raises(ValueError, match="unknown source: pubmed"):
+            JsonlReader(path, source="pubmed")
+
+
+class TestNormalizeText:
+    def test_composed_and_decomposed_forms(self):
+        composed = "Müller"
+        decomposed = "Mu\u0308ller"
+        assert composed != decomposed
+        assert normalize_text(decomposed) == composed
+        assert normalize_text(composed, form="NFD") == decomposed
+
+    def test_compatibility_forms(self):
+        assert normalize_text("ﬁnal H₂O", form="NFKC") == "final H2O"
+        assert normalize_text("ﬁnal H₂O") == "ﬁnal H₂O"
+
+    def test_fold_diacritics(self):
+        assert normalize_text("José Müller-Lüdenscheidt", fold_diacritics=True) == "Jose Muller-Ludenscheidt"
+        assert normalize_text("Müller", fold_diacritics=True) == "Muller"
+        assert normalize_text("Søren", fold_diacritics=True) == "Søren"
+
+    def test_case_fold(self):
+        assert normalize_text("Straße", case_fold=True) == "strasse"
+        assert normalize_text("CAFÉ", fold_diacritics=True, case_fold=True) == "cafe"
+
+    def test_invalid_form(self):
+        with pytest.raises(ValueError):
+            normalize_text("text", form="NFX")
+
+    def test_none(self):
+        assert normalize_text(None) is None
+
+
+class TestDetectLanguage:
+    english = "This study measures soil carbon and nitrogen in alpine meadows over a period of ten years."
+    french = "Cette étude mesure le carbone et l'azote du sol dans les prairies alpines pendant dix ans."
+
+    def test_detect(self):
+        code, confidence = detect_language(self.english)
+        assert code == "en"
+        assert 0.0 < confidence <= 1.0
+        assert detect_language(self.french)[0] == "fr"
+
+    def test_none(self):
+        assert detect_language(None) is None
+        assert detect_language("") is None
+
+    def test_many(self):
+        results = detect_language_many([self.english, None, self.french], num_threads=2)
+        assert [result[0] if result else None for result in results] == ["en", None, "fr"]
+
+
+class TestTransformCrossrefWork:
+    message = {
+        "DOI": "10.1234/ABC.5678",
+        "title": ["", "Soil <i>carbon</i> in alpine meadows"],
+        "abstract": "<jats:title>Abstract</jats:title><jats:p>We measure soil carbon &amp; nitrogen.</jats:p>",
+        "author": [
+            {
+                "given": "Jane A.",
+                "family": "Smith",
+                "ORCID": "http://orcid.org/0000-0002-1825-0097",
+                "affiliation": [
+                    {"name": "University of Montana", "id": [{"id": "https://ror.org/0078xmk34", "id-type": "ROR"}]}
+                ],
+            },
+            {"name": "Alpine Research Consortium"},
+        ],
+        "ISSN": ["1234-5679", "2434-561x"],
+        "issn-type": [{"type": "print", "value": "12345679"}],
+        "funder": [
+            {"name": "National Science Foundation", "DOI": "10.13039/100000001", "award": ["1234567, 7654321"]},
+            {"name": "Unawarded Foundation"},
+        ],
+        "issued": {"date-parts": [[2021, 3]]},
+        "deposited": {"date-time": "2021-04-01T12:00:00Z"},
+    }
+
+    def transform(self, **overrides):
+        return transform_crossref_work(json.dumps({**self.message, **overrides}).encode())
+
+    def test_transform(self):
+        work = self.transform()
+        assert work["doi"] == "10.1234/abc.5678"
+        assert work["title"] == "Soil carbon in alpine meadows"
+        assert work["abstract"] == "We measure soil carbon & nitrogen."
+        assert work["issns"] == ["1234-5679", "2434-561X"]
+        assert work["publication_date"] == "2021-03"
+        assert work["updated_date"] == "2021-04-01T12:00:00Z"
+
+    def test_work_types(self):
+        assert self.transform(type="journal-article")["work_type"] == "ARTICLE"
+        assert self.transform(type="posted-content")["work_type"] == "PREPRINT"
+        assert self.transform()["work_type"] is None
+
+    def test_authors(self):
+        smith, consortium = self.transform()["authors"]
+        assert smith["surname"] == "Smith"
+        assert smith["first_initial"] == "J"
+        assert smith["orcid"] == "0000-0002-1825-0097"
+        assert consortium["full"] == "Alpine Research Consortium"
+        assert consortium["orcid"] is None
+
+
+    def test_author_affixes(self):
+        (author,) = self.transform(author=[{"given": "Dr. Jane", "family": "Smith Jr."}])["authors"]
+        assert (author["given_name"], author["surname"]) == ("Jane", "Smith")
+        assert (author["title"], author["suffix"]) == ("Dr.", "Jr.")
+        assert self.transform()["authors"][0]["suffix"] is None
+
+    def test_affiliations(self):
+        assert self.transform()["institutions"] == [{"name": "University of Montana", "ror": "0078xmk34"}]
+
+    def test_funders(self):
+        assert self.transform()["funders"] == [
+            {"name": "National Science Foundation", "funder_doi": "10.13039/100000001", "award": "1234567"},
+            {"name": "National Science Foundation", "funder_doi": "10.13039/100000001", "award": "7654321"},
+            {"name": "Unawarded Foundation", "funder_doi": None, "award": None},
+        ]
+
+    def test_funders_without_names(self):
+        assert self.transform(funder=[{"award": []}, {"DOI": "10.13039/100000002"}])["funders"] == [
+            {"name": None, "funder_doi": "10.13039/100000002", "award": None}
+        ]
+
+    def test_published_fallback(self):
+        work = self.transform(issued={"date-parts": [[None]]}, published={"date-parts": [[2020, 1, 5]]})
+        assert work["publication_date"] == "2020-01-05"
+
+    def test_missing_doi(self):
+        assert self.transform(DOI="not a doi") is None
+
+    def test_invalid_json(self):
+        with pytest.raises(ValueError):
+            transform_crossref_work(b"{not json")
+
+
+class TestTransformOpenalexWork:
+    work = {
+        "id": "https://openalex.org/W2741809807",
+        "doi": "https://doi.org/10.1234/ABC.5678",
+        "is_xpac": False,
+        "title": "Soil <i>carbon</i> in alpine meadows",
+        "abstract_inverted_index": {"Soil": [0], "carbon": [1], "matters.": [2]},
+        "type": "article",
+        "publication_date": "2021-03-05",
+        "updated_date": "2024-01-01T00:00:00",
+        "authorships": [
+            {
+                "author": {"display_name": "Jane A. Smith", "orcid": "https://orcid.org/0000-0002-1825-0097"},
+                "institutions": [{"display_name": "University of Montana", "ror": "https://ror.org/0078xmk34"}],
+            },
+            {
+                "author": {"display_name": "Jane A. Smith", "orcid": "https://orcid.org/0000-0002-1825-0097"},
+                "institutions": [{"display_name": "University of Montana", "ror": "https://ror.org/0078xmk34"}],
+            },
+        ],
+        "awards": [{"funder_display_name": "National Science Foundation", "funder_award_id": "1234567"}],
+    }
+
+    def transform(self, include_xpac=False, **overrides):
+        return transform_openalex_work(json.dumps({**self.work, **overrides}).encode(), include_xpac=include_xpac)
+
+    def test_transform(self):
+        work = self.transform()
+        assert work["id"] == "W2741809807"
+        assert work["doi"] == "10.1234/abc.5678"
+        assert work["title"] == "Soil carbon in alpine meadows"
+        assert work["abstract"] == "Soil carbon matters."
+        assert work["work_type"] == "ARTICLE"
+        assert work["publication_date"] == "2021-03-05"
+        assert work["funders"] == [{"name": "National Science Foundation", "funder_doi": None, "award": "1234567"}]
+
+    def test_funders_without_awards(self):
+        work = self.transform(
+            awards=[
+                {"funder_display_name": "National Science Foundation", "funder_award_id": "1234567"},
+                {"funder_display_name": "Unawarded Foundation", "funder_award_id": None},
+            ],
+            funders=[
+                {"id": "https://openalex.org/F4320306076", "display_name": "National Science Foundation"},
+                {"id": "https://openalex.org/F4320332161", "display_name": "Wellcome Trust"},
+            ],
+        )
+        assert work["funders"] == [
+            {"name": "National Science Foundation", "funder_doi": None, "award": "1234567"},
+            {"name": "Unawarded Foundation", "funder_doi": None, "award": None},
+            {"name": "Wellcome Trust", "funder_doi": None, "award": None},
+        ]
+
+    def test_authorships(self):
+        work = self.transform()
+        assert len(work["authors"]) == 1
+        assert work["authors"][0]["surname"] == "Smith"
+        assert work["authors"][0]["orcid"] == "0000-0002-1825-0097"
+        assert work["institutions"] == [{"name": "University of Montana", "ror": "0078xmk34"}]
+
+    def test_issns(self):
+        work = self.transform(primary_location={"source": {"issn_l": "1234-5679", "issn": ["2434-561x", "12345679"]}})
+        assert work["issns"] == ["1234-5679", "2434-561X"]
+        assert self.transform(primary_location={"source": None})["issns"] == []
+        assert self.transform()["issns"] == []
+
+    def test_placeholder_abstract(self):
+        assert self.transform(abstract_inverted_index={"N/A": [0]})["abstract"] is None
+        assert self.transform(abstract_inverted_index=None)["abstract"] is None
+
+    def test_xpac(self):
+        assert self.transform(is_xpac=True) is None
+        assert self.transform(include_xpac=True, is_xpac=True)["doi"] == "10.1234/abc.5678"
+
+    def test_missing_doi(self):
+        assert self.transform(doi=None) is None
+
+
+class TestTransformDataciteRecord:
+    record = {
+        "id": "10.5061/DRYAD.ABC123",
+        "attributes": {
+            "titles": [{"title": "Alpine soil carbon measurements"}],
+            "descriptions": [{"description": ":unav"}, {"description": "Soil carbon <b>measurements</b>."}],
+            "types": {"resourceTypeGeneral": "Dataset"},
+            "created": "2021-03-05T10:00:00Z",
+            "updated": "2022-01-01T00:00:00Z",
+            "creators": [
+                {
+                    "nameType": "Personal",
+                    "name": "Smith, Jane A.",
+                    "givenName": "Jane A.",
+                    "familyName": "Smith",
+                    "nameIdentifiers": [{"nameIdentifier": "https://orcid.org/0000-0002-1825-0097"}],
+                    "affiliation": [
+                        {"name": "University of Montana", "affiliationIdentifier": "https://ror.org/0078xmk34"}
+                    ],
+                },
+                {"nameType": "Organizational", "name": "Alpine Research Consortium"},
+            ],
+            "contributors": [{"name": "Doe, John", "givenName": "John", "familyName": "Doe", "affiliation": ["Dryad"]}],
+            "fundingReferences": [
+                {
+                    "funderName": "National Science Foundation",
+                    "funderIdentifier": "https://doi.org/10.13039/100000001",
+                    "awardNumber": "1234567",
+                }
+            ],
+            "relatedIdentifiers": [
+                {
+                    "relationType": "IsSupplementTo",
+                    "relatedIdentifier": "https://doi.org/10.1234/ABC",
+                    "relatedIdentifierType": "URL",
+                },
+                {
+                    "relationType": "IsDocumentedBy",
+                    "relatedIdentifier": "https://example.org/docs",
+                    "relatedIdentifierType": "URL",
+                },
+                {"relationType": "IsPartOf", "relatedIdentifier": "not a doi", "relatedIdentifierType": "DOI"},
+            ],
+        },
+    }
+
+    def transform(self, **attributes):
+        record = {**self.record, "attributes": {**self.record["attributes"], **attributes}}
+        return transform_datacite_record(json.dumps(record).encode())
+
+    def test_transform(self):
+        work = self.transform()
+        assert work["doi"] == "10.5061/dryad.abc123"
+        assert work["title"] == "Alpine soil carbon measurements"
+        assert work["abstract"] == "Soil carbon measurements."
+        assert work["work_type"] == "DATASET"
+        assert work["publication_date"] == "2021-03-05"
+        assert work["funders"] == [
+            {"name": "National Science Foundation", "funder_doi": "10.13039/100000001", "award": "1234567"}
+        ]
+
+    def test_funders_without_awards(self):
+        work = self.transform(
+            fundingReferences=[
+                {"funderName": "Unawarded Foundation"},
+                {"funderName": "Wellcome Trust", "funderIdentifier": "10.13039/100004440", "awardNumber": " "},
+                {"awardNumber": ""},
+            ]
+        )
+        assert work["funders"] == [
+            {"name": "Unawarded Foundation", "funder_doi": None, "award": None},
+            {"name": "Wellcome Trust", "funder_doi": "10.13039/100004440", "award": None},
+        ]
+
+    def test_people(self):
+        work = self.transform()
+        assert [author["surname"] for author in work["authors"]] == ["Smith"]
+        assert work["authors"][0]["orcid"] == "0000-0002-1825-0097"
+        assert [contributor["surname"] for contributor in work["contributors"]] == ["Doe"]
+        assert work["institutions"] == [
+            {"name": "University of Montana", "ror": "0078xmk34"},
+            {"name": "Dryad", "ror": None},
+        ]
+
+    def test_relations(self):
+        assert self.transform()["relations"] == [
+            {"relation_type": "IsSupplementTo", "related_identifier": "10.1234/abc", "related_identifier_type": "DOI"},
+            {
+                "relation_type": "IsDocumentedBy",
+                "related_identifier": "https://example.org/docs",
+                "related_identifier_type": "URL",
+            },
+            {"relation_type": "IsPartOf", "related_identifier": "not a doi", "related_identifier_type": None},
+        ]
+
+    def test_work_types(self):
+        assert self.transform(types={"resourceTypeGeneral": "JournalArticle"})["work_type"] == "ARTICLE"
+        assert self.transform(types={"resourceTypeGeneral": "ComputationalNotebook"})["work_type"] == "SOFTWARE"
+        assert self.transform(types={"resourceTypeGeneral": "Workflow"})["work_type"] == "OTHER"
+
+    def test_publication_year_fallback(self):
+        assert self.transform(created=None, publicationYear=2020)["publication_date"] == "2020"
+
+    def test_missing_doi(self):
+        assert transform_datacite_record(json.dumps({"id": "abc", "attributes": {}}).encode()) is None
+
+
+class TestParseAffiliation:
+    def test_full(self):
+        parsed = parse_affiliation("Dept. of Biology, University of Montana, Missoula, MT 59812, USA")
+        assert parsed.institution == "University of Montana"
+        assert parsed.department == "Department of Biology"
+        assert parsed.city == "Missoula"
+        assert parsed.country == "United States"
+
+    def test_institute_hosted_by_university(self):
+        parsed = parse_affiliation("1 Institute of Ecology; Universität Heidelberg; 69117 Heidelberg; Germany")
+        assert parsed.institution == "Universität Heidelberg"
+        assert parsed.department is None
+        assert parsed.city == "Heidelberg"
+        assert parsed.country == "Germany"
+
+    def test_school_of(self):
+        parsed = parse_affiliation("School of Earth Sciences, The Ohio State University, Columbus, Ohio, U.S.A.")
+        assert parsed.institution == "The Ohio State University"
+        assert parsed.department == "School of Earth Sciences"
+        assert parsed.city == "Columbus"
+
+    def test_email_dropped(self):
+        parsed = parse_affiliation("Lawrence Berkeley National Laboratory, Berkeley, CA, jane@lbl.gov")
+        assert parsed.institution == "Lawrence Berkeley National Laboratory"
+        assert parsed.city == "Berkeley"
+        assert parsed.country is None
+
+    def test_institution_only(self):
+        assert parse_affiliation("University of Oxford") == ("University of Oxford", None, None, None)
+
+    def test_none(self):
+        assert parse_affiliation(None) == (None, None, None, None)
+        assert parse_affiliation(" , ") == (None, None, None, None)
+
+
+class TestTokenize:
+    def test_default(self):
+        assert tokenize("Café Ångström ﬁeld-work, 2021!") == ["cafe", "angstrom", "field", "work", "2021"]
+
+    def test_no_fold(self):
+        assert tokenize("Café", TokenizerOptions(fold=False)) == ["café"]
+
+    def test_stop_words_and_min_length(self):
+        options = TokenizerOptions(stop_words=ENGLISH_STOP_WORDS, min_length=3)
+        assert tokenize("The effects of an El Niño on soil", options) == ["effects", "nino", "soil"]
+
+    def test_word_ngrams(self):
+        options = TokenizerOptions(word_ngrams=(1, 2))
+        assert tokenize("soil carbon flux", options) == ["soil", "soil carbon", "carbon", "carbon flux", "flux"]
+        assert tokenize("soil carbon flux", TokenizerOptions(word_ngrams=(2, 2))) == ["soil carbon", "carbon flux"]
+
+    def test_char_ngrams(self):
+        options = TokenizerOptions(char_ngrams=(2, 3))
+        assert tokenize("soil a", options) == ["so", "soi", "oi", "oil", "il"]
+
+    def test_invalid_ranges(self):
+        with pytest.raises(ValueError):
+            TokenizerOptions(word_ngrams=(0, 1))
+        with pytest.raises(ValueError):
+            TokenizerOptions(char_ngrams=(3, 2))
+
+    def test_empty(self):
+        assert tokenize("") == []
+
+
+class TestParseDate:
+    def test_strings(self):
+        assert parse_date("2021") == ParsedDate(2021, None, None)
+        assert parse_date("2021-03") == ParsedDate(2021, 3, None)
+        assert parse_date("2021-03-05T12:30:00Z") == ParsedDate(2021, 3, 5)
+        assert parse_date("March 2021") == ParsedDate(2021, 3, None)
+
+    def test_date_parts(self):
+        assert parse_date([2021, 3, 5]) == ParsedDate(2021, 3, 5)
+        assert parse_date([[2021, 3]]) == ParsedDate(2021, 3, None)
+        assert parse_date({"date-parts": [[2021]]}) == ParsedDate(2021, None, None)
+        assert parse_date([2021, None, 5]) == ParsedDate(2021, None, None)
+
+    def test_invalid(self):
+        assert parse_date("2021-02-30") is None
+        assert parse_date([2021, 13]) is None
+        assert parse_date({"date-parts": [[None]]}) is None
+        assert parse_date({}) is None
+        assert parse_date(None) is None
+
+
+class TestDateDistance:
+    def test_overlapping(self):
+        assert date_distance("2021", "2021-03-05") == 0.0
+        assert date_distance([2021, 3], "2021-03-31") == 0.0
+
+    def test_gap(self):
+        assert date_distance("2021-12-31", "2022-01-31") == 31 / 365
+        assert date_distance("2021", "2022-07", max_days=365) == 182 / 365
+        assert date_distance("2019", "2023") == 1.0
+
+    def test_unparseable(self):
+        assert date_distance("2021", "soon") is None
+        assert date_distance(None, "2021") is None
+
+
+class TestNormalizeIssn:
+    def test_forms(self):
+        assert normalize_issn("0378-5955") == "0378-5955"
+        assert normalize_issn("ISSN 03785955") == "0378-5955"
+        assert normalize_issn("ISSN: 2434-561x") == "2434-561X"
+
+    def test_invalid(self):
+        assert normalize_issn("0378-5954") is None
+        assert normalize_issn("0378-595") is None
+        assert normalize_issn(None) is None
+
+
+class TestNormalizeIsbn:
+    def test_isbn13(self):
+        assert normalize_isbn("978-0-306-40615-7") == "9780306406157"
+        assert normalize_isbn("ISBN-13: 978 0 306 40615 7") == "9780306406157"
+        assert normalize_isbn("979-10-90636-07-1") == "9791090636071"
+
+    def test_isbn10_converted(self):
+        assert normalize_isbn("0-306-40615-2") == "9780306406157"
+        assert normalize_isbn("ISBN 0-8044-2957-x") == "9780804429573"
+        assert normalize_isbn("isbn-10: 0306406152") == "9780306406157"
+
+    def test_invalid(self):
+        assert normalize_isbn("0-306-40615-3") is None
+        assert normalize_isbn("978-0-306-40615-8") is None
+        assert normalize_isbn("123-0-306-40615-7") is None
+        assert normalize_isbn("ISBN") is None
+        assert normalize_isbn(None) is None
+
+
+class TestAuthorOverlap:
+    def test_reordered_and_abbreviated(self):
+        dmp = ["Maria Garcia", "John Smith", "Wei Chen"]
+        work = ["Smith, J.", "Jones, P.", "Garcia, M. L."]
+        overlap = author_overlap(dmp, work)
+        assert [(i, j) for i, j, _ in overlap.pairs] == [(0, 2), (1, 0)]
+        assert all(score > 0.5 for _, _, score in overlap.pairs)
+        assert overlap.score == pytest.approx(sum(score for _, _, score in overlap.pairs) / 3)
+
+    def test_conflicting_initials(self):
+        # The same surname with different initials is not a match
+        assert author_overlap(["John Smith"], ["Karen Smith"]) == ([], 0.0)
+
+    def test_each_author_matched_once(self):
+        overlap = author_overlap(["John Smith", "Jane Smith"], ["J. Smith"])
+        assert len(overlap.pairs) == 1
+        assert overlap.score == pytest.approx(overlap.pairs[0][2])
+
+    def test_uncomparable_names(self):
+        assert author_overlap([], ["John Smith"]) == ([], 0.0)
+        assert author_overlap([None, ""], ["John Smith"]) == ([], 0.0)
+        # Indexes refer to the original lists
+        overlap = author_overlap([None, "John Smith"], ["John Smith"])
+        assert [(i, j) for i, j, _ in overlap.pairs] == [(1, 0)]
+        assert overlap.score == pytest.approx(1.0)
+
+
+class TestRomanize:
+    def test_cyrillic(self):
+        assert romanize("Юрий Хрущёв") == "Yury Khrushchev"
+        assert romanize("Олександр Ковальчук") == "Oleksandr Kovalchuk"
+        assert romanize("ЩЕРБАКОВ") == "SHCHERBAKOV"
+
+    def test_greek(self):
+        assert romanize("Γιώργος Παπαδόπουλος") == "Giorgos Papadopoulos"
+
+    def test_cjk_names_reordered(self):
+        assert romanize("张伟") == "Wei Zhang"
+        assert romanize("張偉") == "Wei Zhang"
+        assert romanize("张小龙") == "Xiaolong Zhang"
+        assert romanize("欧阳修") == "Xiu Ouyang"
+        assert romanize("김민준") == "Minjun Kim"
+
+    def test_latin_unchanged(self):
+        assert romanize("José  Muñoz") == "José  Muñoz"
+        assert romanize("") == ""
+
+    def test_compare_names(self):
+        assert compare_names("Дмитрий Соболев", "D. Sobolev").score == pytest.approx(1.0)
+        assert compare_names("张伟", "Zhang, Wei").score == pytest.approx(1.0)
+        assert compare_names("김민준", "Minjun Kim").score == pytest.approx(1.0)
+        assert compare_names("张伟", "Wei Wang").score == 0.0
+
+
+class TestExpandGivenNameVariants:
+    def test_nickname_to_formal(self):
+        assert expand_given_name_variants("Bill") == ["Bill", "William", "Billy", "Will", "Willy", "Liam"]
+        assert "Robert" in expand_given_name_variants("bob")
+
+    def test_formal_to_nicknames(self):
+        variants = expand_given_name_variants("Elizabeth")
+        assert variants[0] == "Elizabeth"
+        assert {"Liz", "Beth", "Betty"} <= set(variants)
+
+    def test_shared_nickname(self):
+        variants = expand_given_name_variants("Chris")
+        assert "Christopher" in variants
+        assert "Christine" in variants
+
+    def test_no_variants(self):
+        assert expand_given_name_variants("Xiaolong") == ["Xiaolong"]
+        assert expand_given_name_variants("  ") == []
+        assert expand_given_name_variants(None) == []
+
+    def test_compare_names(self):
+        assert compare_names("Bill Smith", "William Smith").score == 0.0
+        similarity = compare_names("Bill Smith", "William Smith", nicknames=True)
+        assert similarity.initials_compatible
+        assert similarity.score == pytest.approx(1.0)
+        assert compare_names("Bob Jones", "Smith, Robert", nicknames=True).score == 0.0
+        assert compare_names("Bill Smith", "Robert Smith", nicknames=True).score == 0.0
+
+
+class TestExtractEmails:
+    def test_free_text(self):
+        text = "Jane Doe (Jane.Doe@Example.ORG), PI; contact: mailto:lab@uni.edu. Also jane.doe@example.org"
+        assert extract_emails(text) == ["jane.doe@example.org", "lab@uni.edu"]
+
+    def test_obfuscated(self):
+        assert extract_emails("j.smith [at] uni (dot) ac (dot) uk") == ["j.smith@uni.ac.uk"]
+
+    def test_none(self):
+        assert extract_emails("No contact details @ all") == []
+        assert extract_emails(None) == []
+
+
+class TestNormalizeUrl:
+    def test_url(self):
+        assert normalize_url("HTTPS://Lab.Example.ORG:443/People/?utm_source=x&id=3&fbclid=abc#team") == (
+            "url",
+            "https://lab.example.org/People/?id=3#team",
+        )
+        assert normalize_url("http://example.org/.") == ("url", "http://example.org")
+        assert normalize_url("http://example.org/).") == ("url", "http://example.org")
+        assert normalize_url("www.example.org/lab?utm_medium=email") == ("url", "http://www.example.org/lab")
+
+    def test_identifiers(self):
+        assert normalize_url("https://doi.org/10.1234/ABC.5?utm_source=x") == ("doi", "10.1234/abc.5")
+        assert normalize_url("http://hdl.handle.net/20.500.12345/678") == ("handle", "20.500.12345/678")
+        assert normalize_url("hdl: 2027/mdp.39015") == ("handle", "2027/mdp.39015")
+        assert normalize_url("https://hdl.handle.net/10.1234/abc") == ("doi", "10.1234/abc")
+        assert normalize_url("https://hdl.handle.net/20.500.1/2").kind == "handle"
+
+    def test_invalid(self):
+        assert normalize_url("ftp://example.org/file") is None
+        assert normalize_url("example") is None
+        assert normalize_url(None) is None
+
+
+class TestNormalizeAwardId:
+    def test_nih(self):
+        assert normalize_award_id("1 R01 GM123456-01A1") == "R01GM123456"
+        assert normalize_award_id("r01gm123456") == "R01GM123456"
+
+    def test_nsf(self):
+        assert normalize_award_id("NSF DBI 1234567") == "DBI-1234567"
+        assert normalize_award_id("NIH 1234567") == "1234567"
+        assert normalize_award_id("ABCD-1234567") == "ABCD1234567"
+        assert normalize_award_id("https://www.nsf.gov/awardsearch/showAward?AWD_ID=2234213") == "2234213"
+        assert normalize_award_id("Grant No. 1234567", funder_hint="https://ror.org/021nxhr62") == "1234567"
+
+    def test_erc(self):
+        assert normalize_award_id("ERC-2017-STG 757123") == "757123"
+        assert normalize_award_id("grant agreement No. 757123") == "757123"
+        assert normalize_award_id("757123", funder_hint="European Research Council") == "757123"
+
+    def test_other(self):
+        assert normalize_award_id("EP/K503757/1") == "EPK5037571"
+        assert normalize_award_id("Award #ab-12") == "AB12"
+        assert normalize_award_id("not an award") is None
+        assert normalize_award_id(None) is None
+
+
+class TestCompareAwardIds:
+    def test_same_award(self):
+        assert compare_award_ids("r01-gm123456-02", "1R01GM123456-01") is True
+        assert compare_award_ids("GM123456", "R01GM123456") is True
+        assert compare_award_ids("1234567", "DBI-1234567") is True
+        assert compare_award_ids("EP/K503757/1", "EPK503757-1") is True
+        assert compare_award_ids("757123", "ERC-2017-STG 757123") is True
+
+    def test_different_awards(self):
+        assert compare_award_ids("DBI-1234567", "DMR-1234567") is False
+        assert compare_award_ids("R01GM123456", "R01GM654321") is False
+        assert compare_award_ids("123", "456") is False
+        assert compare_award_ids(None, "123") is False
+
+
+class TestSplitSentences:
+    def test_split(self):
+        text = "Soils were sampled.  Did carbon increase? Yes!\nSee below."
+        assert split_sentences(text) == ["Soils were sampled.", "Did carbon increase?", "Yes!", "See below."]
+
+    def test_abbreviations_and_initials(self):
+        text = "As shown by Smith et al. in 2020, results (Fig. 2) vary. Samples from J. Smith were lost."
+        assert split_sentences(text) == [
+            "As shown by Smith et al. in 2020, results (Fig. 2) vary.",
+            "Samples from J. Smith were lost.",
+        ]
+
+    def test_cjk(self):
+        assert split_sentences("数据很好。下一句。") == ["数据很好。", "下一句。"]
+
+    def test_empty(self):
+        assert split_sentences("   ") == []
+        assert split_sentences(None) == []
+
+
+class TestTruncateToTokens:
+    TEXT = "Soil carbon was measured at ten sites. Nitrogen was also measured in every core sample we took."
+
+    def test_within_limit(self):
+        assert truncate_to_tokens(self.TEXT, 100) == self.TEXT
+        assert truncate_to_tokens(None, 10) is None
+
+    def test_cuts_after_whole_words(self):
+        assert truncate_to_tokens(self.TEXT, 3) == "Soil carbon was"
+        assert truncate_to_tokens(self.TEXT, 7) == "Soil carbon was measured at ten sites."
+        assert truncate_to_tokens(self.TEXT, 0) == ""
+
+    def test_prefers_sentence_boundary(self):
+        assert truncate_to_tokens(self.TEXT, 12) == "Soil carbon was measured at ten sites."
+        # The boundary is too early to keep half the words
+        assert truncate_to_tokens(self.TEXT, 15) == (
+            "Soil carbon was measured at ten sites. Nitrogen was also measured in every core sample"
+        )
+
+    def test_invisible_characters(self):
+        assert truncate_to_tokens("Hy\xadbrid mod\u200bels of cli\xadmate", 3) == "Hy\xadbrid mod\u200bels of"
+
+    def test_multibyte_characters(self):
+        assert truncate_to_tokens("Café naïve über façade", 2) == "Café naïve"
+        assert truncate_to_tokens("数据很好", 2) == "数据"
+
+
+class TestTransformDmp:
+    dmp = {
+        "dmp": {
+            "title": "Soil carbon <b>data</b> management plan",
+            "description": "<p>We will collect soil cores &amp; measure carbon.</p>",
+            "created": "2023-01-05T10:00:00Z",
+            "modified": "2023-02-01T09:30:00Z",
+            "dmp_id": {"identifier": "https://doi.org/10.48321/D1ABCD", "type": "doi"},
+            "contact": {
+                "name": "Smith, Jane A.",
+                "mbox": "jane@example.edu",
+                "contact_id": {"identifier": "https://orcid.org/0000-0002-1825-0097", "type": "orcid"},
+                "dmproadmap_affiliation": {
+                    "name": "University of Montana",
+                    "affiliation_id": {"identifier": "https://ror.org/0078xmk34", "type": "ror"},
+                },
+            },
+            "contributor": [
+                {
+                    "name": "Smith, Jane A.",
+                    "contributor_id": {"identifier": "https://orcid.org/0000-0002-1825-0097", "type": "orcid"},
+                    "role": ["http://credit.niso.org/contributor-roles/investigation"],
+                },
+                {"name": "Carlos Pérez", "dmproadmap_affiliation": {"name": "Alpine Research Consortium"}},
+            ],
+            "project": [
+                {
+                    "title": "Alpine soils",
+                    "start": "2023-03-01T00:00:00Z",
+                    "end": "2025-02-28",
+                    "funding": [
+                        {
+                            "name": "National Science Foundation",
+                            "funder_id": {"identifier": "https://ror.org/021nxhr62", "type": "ror"},
+                            "funding_status": "granted",
+                            "grant_id": {"identifier": "2134567", "type": "other"},
+                            "dmproadmap_funding_opportunity_id": {"identifier": "NSF 22-500", "type": "other"},
+                        },
+                        {"name": "Unnamed", "funding_status": "planned"},
+                    ],
+                },
+                {"title": "Follow-up", "start": "2022-06-01", "end": "2026-01-01"},
+            ],
+            "dataset": [
+                {"title": "Soil cores", "description": "<p>Carbon content of cores.</p>", "type": "dataset"},
+                {"type": "software"},
+            ],
+            "dmproadmap_related_identifiers": [
+                {"descriptor": "is_referenced_by", "identifier": "https://doi.org/10.1234/ABC", "type": "doi"},
+                {"descriptor": "is_referenced_by", "identifier": "https://example.org/report", "type": "url"},
+            ],
+        }
+    }
+
+    def transform(self, **overrides):
+        return transform_dmp(json.dumps({"dmp": {**self.dmp["dmp"], **overrides}}).encode())
+
+    def test_transform(self):
+        dmp = self.transform()
+        assert dmp["doi"] == "10.48321/d1abcd"
+        assert dmp["title"] == "Soil carbon data management plan"
+        assert dmp["abstract_text"] == "We will collect soil cores & measure carbon."
+        assert dmp["project_start"] == "2022-06-01"
+        assert dmp["project_end"] == "2026-01-01"
+        assert dmp["created"] == "2023-01-05T10:00:00Z"
+        assert dmp["modified"] == "2023-02-01T09:30:00Z"
+        assert dmp["published_outputs"] == ["10.1234/abc"]
+
+    def test_authors(self):
+        smith, perez = self.transform()["authors"]
+        assert smith["surname"] == "Smith"
+        assert smith["given_name"] == "Jane"
+        assert smith["orcid"] == "0000-0002-1825-0097"
+        assert perez["full"] == "Carlos Pérez"
+        assert perez["orcid"] is None
+
+    def test_institutions(self):
+        assert self.transform()["institutions"] == [
+            {"name": "University of Montana", "ror": "0078xmk34"},
+            {"name": "Alpine Research Consortium", "ror": None},
+        ]
+
+    def test_funding(self):
+        assert self.transform()["funding"] == [
+            {
+                "funder": {"name": "National Science Foundation", "ror": "021nxhr62", "funder_doi": None},
+                "status": "granted",
+                "award_id": "2134567",
+                "funding_opportunity_id": "NSF 22-500",
+            },
+            {
+                "funder": {"name": "Unnamed", "ror": None, "funder_doi": None},
+                "status": "planned",
+                "award_id": None,
+                "funding_opportunity_id": None,
+            },
+        ]
+
+    def test_fundref_funder(self):
+        project = {
+            "title": "Alpine soils",
+            "funding": [{"name": "NIH", "funder_id": {"identifier": "10.13039/100000002", "type": "fundref"}}],
+        }
+        (funding,) = self.transform(project=[project])["funding"]
+        assert funding["funder"]["funder_doi"] == "10.13039/100000002"
+
+    def test_datasets(self):
+        assert self.transform()["datasets"] == [
+            {"title": "Soil cores", "description": "Carbon content of cores.", "dataset_type": "dataset"}
+        ]
+
+    def test_project_fallbacks(self):
+        dmp = self.transform(title=None, description=None, project=[{"title": "Alpine soils", "description": "Cores"}])
+        assert dmp["title"] == "Alpine soils"
+        assert dmp["abstract_text"] == "Cores"
+        assert dmp["project_start"] is None
+
+    def test_placeholders(self):
+        dmp = self.transform(description="<p>TBD</p>", dataset=[{"title": "Soil cores", "description": "N/A"}])
+        assert dmp["abstract_text"] is None
+        assert dmp["datasets"] == [{"title": "Soil cores", "description": None, "dataset_type": None}]
+
+    def test_unwrapped(self):
+        assert transform_dmp(json.dumps(self.dmp["dmp"]).encode())["doi"] == "10.48321/d1abcd"
+
+    def test_missing_doi(self):
+        assert self.transform(dmp_id={"identifier": "12345", "type": "other"}) is None
+
+    def test_invalid_json(self):
+        with pytest.raises(ValueError):
+            transform_dmp(b"{not json")
+
+
+class TestNullify:
+    @pytest.mark.parametrize(
+        "text",
+        [
+            "N/A",
+            "n/a",
+            "NA",
+            "None.",
+            ":unav",
+            "Not applicable",
+            "TBD",
+            "to be determined",
+            "No abstract available.",
+            "No abstract available for this article",
+            "Abstract not available",
+            "No description provided.",
+            "&nbsp;",
+            " &#160; ",
+            "\u200b",
+            "-",
+            "...",
+            "?",
+            "   ",
+            "",
+        ],
+    )
+    def test_presets(self, text):
+        assert nullify(text) is None
+
+    @pytest.mark.parametrize("text", ["Nano", "Soil carbon in alpine meadows", "NASA", "Abstracts of talks", "3.14"])
+    def test_kept(self, text):
+        assert nullify(text) == text
+
+    def test_trims(self):
+        assert nullify("  Soil carbon \n") == "Soil carbon"
+
+    def test_none(self):
+        assert nullify(None) is None
+
+    def test_values(self):
+        rules = NullRules(values=["Lorem ipsum"], presets=[])
+        assert nullify("lorem IPSUM ", rules) is None
+        assert nullify("N/A", rules) == "N/A"
+
+    def test_case_sensitive(self):
+        rules = NullRules(values=["Lorem ipsum"], patterns=["x+"], presets=[], case_insensitive=False)
+        assert nullify("Lorem ipsum", rules) is None
+        assert nullify("lorem ipsum", rules) == "lorem ipsum"
+        assert nullify("xxx", rules) is None
+        assert nullify("XXX", rules) == "XXX"
+
+    def test_patterns_match_whole_text(self):
+        rules = NullRules(patterns=[r"insert (abstract|summary) here"], presets=[])
+        assert nullify("Insert abstract here", rules) is None
+        assert nullify("Please insert abstract here later", rules) == "Please insert abstract here later"
+
+    def test_selected_presets(self):
+        rules = NullRules(presets=["to_be_determined"])
+        assert nullify("TBA", rules) is None
+        assert nullify("N/A", rules) == "N/A"
+
+    def test_invalid_rules(self):
+        with pytest.raises(ValueError, match="pattern"):
+            NullRules(patterns=["("])
+        with pytest.raises(ValueError, match="presets"):
+            NullRules(presets=["junk"])
+
+    def test_strip_markup(self):
+        options = MarkupOptions(decode_entities=True, null_rules=NullRules())
+        assert strip_markup("<p>N/A</p>", options=options) is None
+        assert strip_markup("<p>&nbsp;</p>", options=options) is None
+        assert strip_markup("<p>Soil &amp; carbon</p>", options=options) == "Soil & carbon"
+        assert strip_markup("<p>N/A</p>") == "N/A"
+
+
+class TestParseNamesList:
+    @pytest.mark.parametrize(
+        "text,surnames",
+        [
+            ("Smith, J.; Doe, A. and Lee, K.", ["Smith", "Doe", "Lee"]),
+            ("Smith, J., Doe, A., Lee, K.", ["Smith", "Doe", "Lee"]),
+            ("Jane Smith, John Doe, Ann Lee", ["Smith", "Doe", "Lee"]),
+            ("Jane Smith & John Doe", ["Smith", "Doe"]),
+            ("Smith, Jane Ann", ["Smith"]),
+            ("van der Berg, Jan | Doe, John", ["van der Berg", "Doe"]),
+            ("Jane Smith\nJohn Doe", ["Smith", "Doe"]),
+            ("Smith, J., Doe, A., et al.", ["Smith", "Doe"]),
+        ],
+    )
+    def test_people(self, text, surnames):
+        assert [parsed.surname for parsed in parse_names_list(text)] == surnames
+
+    def test_organizations(self):
+        text = "Jane Smith; Department of Health and Human Services; University of California, Berkeley"
+        parsed = parse_names_list(text)
+        assert [name.full for name in parsed] == [
+            "Jane Smith",
+            "Department of Health and Human Services",
+            "University of California, Berkeley",
+        ]
+        assert parsed[1].surname is None
+        assert parsed[2].given_name is None
+
+    def test_person_and_organization(self):
+        parsed = parse_names_list("Jane Smith and Alpine Research Consortium")
+        assert [name.full for name in parsed] == ["Jane Smith", "Alpine Research Consortium"]
+        assert parsed[1].surname is None
+
+    def test_organization_with_conjunction(self):
+        parsed = parse_names_list("Bill and Melinda Gates Foundation")
+        assert [name.full for name in parsed] == ["Bill and Melinda Gates Foundation"]
+
+    def test_recase_surname(self):
+        assert [name.surname for name in parse_names_list("JOHN MCDONALD; Jane O'BRIEN", recase_surname=True)] == [
+            "McDonald",
+            "O'Brien",
+        ]
+
+    @pytest.mark.parametrize("text", [None, "", " ; "])
+    def test_empty(self, text):
+        assert parse_names_list(text) == []
+        assert parse_names_list(text, collect_errors=True) == ([], [])
+
+    def test_collect_errors(self):
+        parsed, errors = parse_names_list("Smith, J.; 123; Jane Doe", collect_errors=True)
+        assert parsed == parse_names_list("Smith, J.; 123; Jane Doe")
+        assert [(error.row, error.field) for error in errors] == [(1, None)]
+
+
+class TestIsOrganization:
+    @pytest.mark.parametrize(
+        "text",
+        ["Alpine Research Consortium", "Smith Lab", "Dept. of Health &amp; Human Services", "Sanger Institute"],
+    )
+    def test_organizations(self, text):
+        assert is_organization(text)
+
+    @pytest.mark.parametrize("text", ["Jane Smith", "Smith, J.", "LEE", "J. R. R. Tolkien", None])
+    def test_people(self, text):
+        assert not is_organization(text)
+
+    @pytest.mark.parametrize(
+        "text", ["IPCC Working Group", "British Geological Survey", "Human Genome Project", "Jane School", "Tom Team"]
+    )
+    def test_ambiguous_words(self, text):
+        assert not is_organization(text)
+        assert is_organization(text, options=NameOptions(ambiguous_organization_words=True))
+        assert parse_name(raw_full=text).surname is not None
+        parsed = parse_name(raw_full=text, options=NameOptions(ambiguous_organization_words=True))
+        assert (parsed.surname, parsed.full) == (None, text)
+
+
+class TestNameAffixes:
+    @pytest.mark.parametrize(
+        "raw_full,title,suffix",
+        [
+            ("Dr. John Smith Jr.", "Dr.", "Jr."),
+            ("Smith Jr., John", None, "Jr."),
+            ("Smith, John, Jr.", None, "Jr."),
+            ("John Smith III", None, "III"),
+            ("Prof. Jane A. Doe, PhD", "Prof.", "PhD"),
+            ("Dr Jane Smith PhD MD", "Dr", "PhD MD"),
+        ],
+    )
+    def test_affixes(self, raw_full, title, suffix):
+        parsed = parse_name_struct(raw_full=raw_full)
+        assert parsed.surname in ("Smith", "Doe")
+        assert (parsed.title, parsed.suffix) == (title, suffix)
+
+    def test_given_and_surname(self):
+        parsed = parse_name_struct(raw_given_name="Dr. Jane", raw_surname="Doe Jr.")
+        assert (parsed.given_name, parsed.surname, parsed.title, parsed.suffix) == ("Jane", "Doe", "Dr.", "Jr.")
+
+    def test_title_and_surname(self):
+        parsed = parse_name_struct(raw_full="Dr. Smith")
+        assert (parsed.given_name, parsed.surname, parsed.title) == (None, "Smith", "Dr.")
+
+    def test_initials_kept(self):
+        parsed = parse_name_struct(raw_full="Smith, M.D.")
+        assert (parsed.surname, parsed.first_initial, parsed.middle_initials) == ("Smith", "M", "D")
+        assert parsed.suffix is None
+
+    def test_no_affixes(self):
+        parsed = parse_name_struct(raw_full="Jane Doe")
+        assert (parsed.title, parsed.suffix) == (None, None)
+
+    def test_names_list(self):
+        parsed = parse_names_list_struct("Smith, John, Jr.; Lee, K., MD, Doe, A.")
+        assert [(name.surname, name.suffix) for name in parsed] == [("Smith", "Jr."), ("Lee", "MD"), ("Doe", None)]
+
+    def test_compare_names_ignores_affixes(self):
+        similarity = compare_names("Dr. John Smith Jr.", "Smith, J.")
+        assert similarity.exact_surname
+        assert similarity.score == 1.0
+
+
+class TestRecordFingerprint:
+    def test_stable(self):
+        # Pinned so that IDs derived from fingerprints never change between releases
+        assert record_fingerprint(["10.1234/abc", "a title", None]) == "3e2dcef14ceaac9aae64c9ade5cd4e5d"
+        assert record_fingerprint([]) == "99aa06d3014798d86001c324468d497f"
+
+    def test_format(self):
+        fingerprint = record_fingerprint(["a title", "2024"])
+        assert len(fingerprint) == 32
+        assert int(fingerprint, 16) >= 0
+
+    @pytest.mark.parametrize(
+        "a,b",
+        [
+            (["ab", "c"], ["a", "bc"]),
+            (["abc"], ["abc", None]),
+            (["abc", ""], ["abc", None]),
+            ([None], [""]),
+        ],
+    )
+    def test_field_boundaries(self, a, b):
+        assert record_fingerprint(a) != record_fingerprint(b)
+
+
+class TestToCanonicalJson:
+    def test_key_order(self):
+        a = {"title": "A", "doi": "10.1/x", "authors": [{"surname": "Smith", "given": "J"}]}
+        b = {"authors": [{"given": "J", "surname": "Smith"}], "doi": "10.1/x", "title": "A"}
+        assert to_canonical_json(a) == to_canonical_json(b)
+        assert to_canonical_json(a) == '{"authors":[{"given":"J","surname":"Smith"}],"doi":"10.1/x","title":"A"}'
+
+    def test_key_order_by_utf16(self):
+        assert to_canonical_json({"\uff41": 1, "\U0001f600": 2}) == '{"\U0001f600":2,"\uff41":1}'
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            (1.0, "1"),
+            (-0.0, "0"),
+            (0.1, "0.1"),
+            (1 / 3, "0.3333333333333333"),
+            (1e21, "1e+21"),
+            (1.5e-7, "1.5e-7"),
+            (123456789.0, "123456789"),
+            (-42, "-42"),
+            (True, "true"),
+            (None, "null"),
+        ],
+    )
+    def test_numbers(self, value, expected):
+        assert to_canonical_json(value) == expected
+
+    def test_strings(self):
+        assert to_canonical_json('a "quoted"\\ line\n\x01') == '"a \\"quoted\\"\\\\ line\\n\\u0001"'
+        assert to_canonical_json("Universität Zürich") == '"Universität Zürich"'
+
+    def test_tuples_and_dates(self):
+        import datetime
+
+        assert to_canonical_json({"dates": (datetime.date(2024, 1, 2), None)}) == '{"dates":["2024-01-02",null]}'
+
+    def test_invalid(self):
+        with pytest.raises(TypeError):
+            to_canonical_json({"value": object()})
+
+
+class TestNormalizeWorkType:
+    @pytest.mark.parametrize(
+        "source,raw_type,expected",
+        [
+            ("crossref", "journal-article", "ARTICLE"),
+            ("crossref", "proceedings-article", "ARTICLE"),
+            ("crossref", "posted-content", "PREPRINT"),
+            ("crossref", "edited-book", "BOOK"),
+            ("crossref", "book-part", "BOOK_CHAPTER"),
+            ("crossref", "database", "DATASET"),
+            ("crossref", "journal-issue", "OTHER"),
+            ("datacite", "Dataset", "DATASET"),
+            ("datacite", "Model", "DATASET"),
+            ("datacite", "JournalArticle", "ARTICLE"),
+            ("datacite", "ComputationalNotebook", "SOFTWARE"),
+            ("datacite", "OutputManagementPlan", "OUTPUT_MANAGEMENT_PLAN"),
+            ("datacite", "Collection", "OTHER"),
+            ("openalex", "article", "ARTICLE"),
+            ("openalex", "book-chapter", "BOOK_CHAPTER"),
+            ("openalex", "supplementary-materials", "SUPPLEMENTARY_MATERIALS"),
+            ("openalex", "libguides", "OTHER"),
+        ],
+    )
+    def test_sources(self, source, raw_type, expected):
+        assert normalize_work_type(source, raw_type) == expected
+
+    def test_case_and_whitespace(self):
+        assert normalize_work_type("DataCite", " dataset ") == "DATASET"
+        assert normalize_work_type("crossref", "Journal-Article") == "ARTICLE"
+
+    def test_unknown_type(self):
+        assert normalize_work_type("openalex", "something-new") == "OTHER"
+
+    @pytest.mark.parametrize("raw_type", [None, "", "  "])
+    def test_missing(self, raw_type):
+        assert normalize_work_type("crossref", raw_type) is None
+
+    def test_unknown_source(self):
+        with pytest.raises(ValueError, match="unknown source"):
+            normalize_work_type("pubmed", "article")
+
+    def test_work_types(self):
+        assert "DATASET" in WORK_TYPES
+        for source in ["crossref", "datacite", "openalex"]:
+            assert normalize_work_type(source, "other") in WORK_TYPES
+
+
+class TestNormalizeCountry:
+    @pytest.mark.parametrize(
+        "text,expected",
+        [
+            ("United States", "US"),
+            ("United States of America", "US"),
+            ("U.S.A.", "US"),
+            ("us", "US"),
+            ("USA", "US"),
+            ("UK", "GB"),
+            ("Scotland", "GB"),
+            ("GBR", "GB"),
+            ("Deutschland", "DE"),
+            ("Côte d'Ivoire", "CI"),
+            ("Ivory Coast", "CI"),
+            ("Korea, Republic of", "KR"),
+            ("Republic of Korea", "KR"),
+            ("The Netherlands", "NL"),
+            ("Bosnia & Herzegovina", "BA"),
+            ("Guinea-Bissau", "GW"),
+            ("Türkiye", "TR"),
+            ("  new   zealand ", "NZ"),
+        ],
+    )
+    def test_countries(self, text, expected):
+        assert normalize_country(text) == expected
+
+    @pytest.mark.parametrize("text", [None, "", "Atlantis", "XX", "Berkeley"])
+    def test_unknown(self, text):
+        assert normalize_country(text) is None
+
+
+class TestTransformWorksMany:
+    openalex_work = {"doi": "https://doi.org/10.1234/A", "title": "Soil carbon"}
+
+    def records(self, *works):
+        return [json.dumps(work).encode() for work in works]
+
+    def test_work_types_match_across_sources(self):
+        crossref = self.records({"DOI": "10.1234/A", "type": "journal-article"})
+        openalex = self.records({**self.openalex_work, "type": "article"})
+        datacite = self.records({"id": "10.1234/A", "attributes": {"types": {"resourceTypeGeneral": "JournalArticle"}}})
+        work_types = [
+            work["work_type"]
+            for source, records in [("crossref", crossref), ("openalex", openalex), ("datacite", datacite)]
+            for work in transform_works_many(source, records)
+        ]
+        assert work_types == ["ARTICLE", "ARTICLE", "ARTICLE"]
+
+    def test_matches_transform(self):
+        records = self.records({"DOI": "10.1234/A", "title": ["Soil carbon"]}, {"title": ["No DOI"]})
+        expected = [transform_crossref_work(record) for record in records]
+        assert transform_works_many("crossref", records) == expected
+        assert transform_works_many("Crossref", records, num_threads=2) == expected
+
+    def test_collect_errors(self):
+        records = [
+            *self.records(self.openalex_work, {**self.openalex_work, "abstract_inverted_index": {"Soil": "zero"}}),
+            b"{not json",
+        ]
+        works, errors, skipped = transform_works_many("openalex", records, collect_errors=True)
+        assert [work and work["doi"] for work in works] == ["10.1234/a", "10.1234/a", None]
+        assert skipped == []
+        assert works[1]["abstract"] is None
+        assert [(error.row, error.field) for error in errors] == [(1, "abstract_inverted_index"), (2, None)]
+        assert isinstance(errors[0], ParseError)
+        assert errors[1].message.startswith("invalid json: ")
+
+    def test_invalid_record_raises(self):
+        records = self.records({"id": "10.1234/a"}) + [b"{not json"]
+        with pytest.raises(ValueError, match="record 1: invalid json"):
+            transform_works_many("datacite", records)
+
+    def test_invalid_field_is_dropped(self):
+        records = self.records({**self.openalex_work, "abstract_inverted_index": []})
+        assert transform_works_many("openalex", records)[0]["abstract"] is None
+
+    def test_updated_since(self):
+        records = self.records(
+            {**self.openalex_work, "updated_date": "2024-03-01T12:00:00.000000", "created_date": "2020-01-01"},
+            {**self.openalex_work, "updated_date": "2024-01-15T00:00:00", "created_date": "2024-01-10"},
+            self.openalex_work,
+        )
+        works = transform_works_many("openalex", records, updated_since="2024-02-01")
+        assert [work is not None for work in works] == [True, False, True]
+        works = transform_works_many("openalex", records, updated_since="2024-01", created_since="2023")
+        assert [work is not None for work in works] == [False, True, True]
+
+    def test_since_source_dates(self):
+        crossref = self.records(
+            {"DOI": "10.1234/a", "indexed": {"date-time": "2024-05-01T00:00:00Z"}, "created": {"date-parts": [[2019]]}},
+            {"DOI": "10.1234/b", "indexed": {"date-parts": [[2023, 12, 31]]}},
+        )
+        works = transform_works_many("crossref", crossref, updated_since="2024")
+        assert [work is not None for work in works] == [True, False]
+        works = transform_works_many("crossref", crossref, created_since="2020")
+        assert [work is not None for work in works] == [False, True]
+        datacite = self.records({"id": "10.1234/c", "attributes": {"updated": "2022-06-01T00:00:00Z"}})
+        assert transform_works_many("datacite", datacite, updated_since="2022-06-01")[0] is not None
+        assert transform_works_many("datacite", datacite, updated_since="2022-06-02") == [None]
+
+    def test_since_invalid(self):
+        with pytest.raises(ValueError, match="invalid updated_since: yesterday"):
+            transform_works_many("openalex", [], updated_since="yesterday")
+        works, errors, skipped = transform_works_many(
+            "openalex", [b"{not json"], collect_errors=True, updated_since="2024"
+        )
+        assert works == [None]
+        assert errors[0].message.startswith("invalid json: ")
+        assert skipped == []
+
+    def test_skipped_reported_apart_from_errors(self):
+        records = [
+            *self.records({**self.openalex_work, "updated_date": "2023-01-01"}, self.openalex_work),
+            b"{not json",
+        ]
+        works, errors, skipped = transform_works_many("openalex", records, collect_errors=True, updated_since="2024")
+        assert works == [None, works[1], None]
+        assert works[1]["doi"] == "10.1234/a"
+        assert [error.row for error in errors] == [2]
+        assert skipped == [0]
+
+    def test_progress(self):
+        records = self.records(*[{"DOI": f"10.1234/{i}"} for i in range(25_000)])
+        calls = []
+        works = transform_works_many("crossref", records, progress=lambda *args: calls.append(args))
+        assert len(works) == 25_000
+        assert calls == [(10_000, 25_000), (20_000, 25_000), (25_000, 25_000)]
+
+    def test_progress_error(self):
+        def fail(processed, total):
+            raise RuntimeError("stop")
+
+        with pytest.raises(RuntimeError, match="stop"):
+            transform_works_many("crossref", self.records({"DOI": "10.1234/a"}), progress=fail)
+
+    def test_unknown_source(self):
+        with pytest.raises(ValueError, match="unknown source: orcid"):
+            transform_works_many("orcid", [])
+
+
+class RecordingHandler(logging.Handler):
+    def __init__(self):
+        super().__init__()
+        self.records = []
+
+    def emit(self, record):
+        self.records.append(record)
+
+
+class TestSetLogLevel:
+    def log_records(self, level=None):
+        handler = RecordingHandler()
+        logger = logging.getLogger("dmpworks.rust")
+        logger.addHandler(handler)
+        try:
+            if level is not None:
+                set_log_level(level)
+            revert_inverted_index(b"not json")
+        finally:
+            logger.removeHandler(handler)
+            set_log_level("WARNING")
+        return handler.records
+
+    def test_forwards_warnings(self):
+        records = self.log_records()
+        assert [(record.name, record.levelno) for record in records] == [("dmpworks.rust.core", logging.WARNING)]
+        assert records[0].getMessage().startswith("revert_inverted_index: invalid json")
+
+    @pytest.mark.parametrize("level", [logging.ERROR, "error", "off", 100])
+    def test_filters_below_level(self, level):
+        assert self.log_records(level) == []
+
+    def test_invalid_level(self):
+        with pytest.raises(ValueError, match="unknown log level: LOUD"):
+            set_log_level("LOUD")
+
+
+class TestParseCitation:
+    def test_apa(self):
+        citation = parse_citation(
+            "Smith, J., & Doe, A. (2020). Deep learning for soil moisture. Journal of Hydrology, 12(3), 45–67. "
+            "https://doi.org/10.1016/J.JHYDROL.2020.123"
+        )
+        assert citation.title == "Deep learning for soil moisture"
+        assert citation.authors == ["Smith, J.", "Doe, A."]
+        assert citation.year == 2020
+        assert citation.container == "Journal of Hydrology"
+        assert citation.doi == "10.1016/j.jhydrol.2020.123"
+
+    def test_mla(self):
+        citation = parse_citation(
+            'Smith, John, and Jane Doe. "Deep Learning for Soil Moisture." Journal of Hydrology 12.3 (2020): 45-67.'
+        )
+        assert citation.title == "Deep Learning for Soil Moisture"
+        assert citation.authors == ["Smith, John", "Jane Doe"]
+        assert citation.year == 2020
+        assert citation.container == "Journal of Hydrology"
+        assert citation.doi is None
+
+    def test_vancouver(self):
+        citation = parse_citation(
+            "Smith J, Doe AB, Lee K. Deep learning for soil moisture. Nature. 2020;12(3):45-7. "
+            "doi:10.1038/s41586-020-1234-5"
+        )
+        assert citation.authors == ["Smith J", "Doe AB", "Lee K"]
+        assert citation.title == "Deep learning for soil moisture"
+        assert citation.container == "Nature"
+        assert citation.year == 2020
+        assert citation.doi == "10.1038/s41586-020-1234-5"
+
+    def test_dataset(self):
+        citation = parse_citation(
+            "Smith J. 2019. A dataset of ocean temperatures. Dryad. https://doi.org/10.5061/dryad.abc123"
+        )
+        assert citation == ("A dataset of ocean temperatures", ["Smith J"], 2019, "Dryad", "10.5061/dryad.abc123")
+
+    def test_agu(self):
+        citation = parse_citation("Smith, J. A., and M. Lee (2015), Sea ice extent, Geophys. Res. Lett., 42, 1-10.")
+        assert citation == ("Sea ice extent", ["Smith, J. A.", "M. Lee"], 2015, "Geophys. Res. Lett.", None)
+
+    def test_question_title(self):
+        citation = parse_citation("Doe, J. (2018a). Are we there yet? Proceedings of the ACM, pp. 1-10.")
+        assert citation.title == "Are we there yet?"
+        assert citation.container == "Proceedings of the ACM"
+
+    def test_missing_parts(self):
+        assert parse_citation("Ocean temperature dataset, 2022, Zenodo").authors == []
+        assert parse_citation("") == (None, [], None, None, None)
+        assert parse_citation(None) == (None, [], None, None, None)
+
+
+class TestExtractIdentifiers:
+    def test_identifiers(self):
+        text = (
+            "Data will be in Dryad (https://doi.org/10.5061/DRYAD.abc123). See arXiv:2101.01234v2, PMID: 12345678 "
+            "and PMC7654321; reads in SRA (SRR1234567, PRJNA123456), GEO GSE12345 and hdl:20.500.12345/678."
+        )
+        hits = extract_identifiers(text)
+        assert [(hit.kind, hit.value) for hit in hits] == [
+            ("doi", "10.5061/dryad.abc123"),
+            ("arxiv", "2101.01234"),
+            ("pmid", "12345678"),
+            ("pmcid", "PMC7654321"),
+            ("accession", "SRR1234567"),
+            ("accession", "PRJNA123456"),
+            ("accession", "GSE12345"),
+            ("handle", "20.500.12345/678"),
+        ]
+        assert text[hits[0].start : hits[0].end] == "https://doi.org/10.5061/DRYAD.abc123"
+        assert text[hits[1].start : hits[1].end] == "arXiv:2101.01234v2"
+        assert text[hits[-1].start : hits[-1].end] == "hdl:20.500.12345/678"
+
+    def test_labelled_accessions(self):
+        hits = extract_identifiers("GenBank accession MN908947.3 and PDB ID 6vxx, not MN908948 or 1ABC.")
+        assert [(hit.kind, hit.value) for hit in hits] == [("accession", "MN908947.3"), ("accession", "6VXX")]
+
+    def test_character_offsets(self):
+        text = "Über die Daten (doi:10.1234/ÄBC-1)."
+        [hit] = extract_identifiers(text)
+        assert hit.value == "10.1234/äbc-1"
+        assert text[hit.start : hit.end] == "doi:10.1234/ÄBC-1"
+
+    def test_overlapping(self):
+        hits = extract_identifiers("https://doi.org/10.1234/PMC1234567 and https://arxiv.org/abs/hep-th/9901001")
+        assert [(hit.kind, hit.value) for hit in hits] == [
+            ("doi", "10.1234/pmc1234567"),
+            ("arxiv", "hep-th/9901001"),
+        ]
+
+    def test_none(self):
+        assert extract_identifiers("No identifiers in 2020, see page 10.") == []
+        assert extract_identifiers(None) == []
+
+
+class TestExtractFundingStatements:
+    def test_statement(self):
+        text = (
+            "We study coral reefs. This work was supported by the National Science Foundation under grant "
+            "No. DBI-1234567 and award 7654321."
+        )
+        [statement] = extract_funding_statements(text)
+        assert statement.sentence == (
+            "This work was supported by the National Science Foundation under grant No. DBI-1234567 and award 7654321."
+        )
+        assert statement.funders == ["National Science Foundation"]
+        assert statement.award_ids == ["DBI-1234567", "7654321"]
+
+    def test_several_funders(self):
+        [statement] = extract_funding_statements(
+            "Funding was provided by the Engineering and Physical Sciences Research Council (EPSRC), the Wellcome "
+            "Trust and the NIH (grants R01-GM123456-01 and EP/K503757/1)."
+        )
+        assert statement.funders == ["Engineering and Physical Sciences Research Council", "Wellcome Trust", "NIH"]
+        assert statement.award_ids == ["R01GM123456", "EPK5037571"]
+
+    def test_funders_continued(self):
+        [statement] = extract_funding_statements(
+            "This research was funded in part by the Gordon and Betty Moore Foundation through Grant GBMF3834 and by "
+            "the Alfred P. Sloan Foundation."
+        )
+        assert statement.funders == ["Gordon and Betty Moore Foundation", "Alfred P. Sloan Foundation"]
+        assert statement.award_ids == ["GBMF3834"]
+
+    def test_funder_hint(self):
+        [statement] = extract_funding_statements(
+            "This project has received funding from the European Research Council under grant agreement No 757123."
+        )
+        assert statement.funders == ["European Research Council"]
+        assert statement.award_ids == ["757123"]
+
+    def test_prefixed_award_kept(self):
+        [statement] = extract_funding_statements("Supported by NSF award 1234567 (DBI-1234567).")
+        assert statement.funders == ["NSF"]
+        assert statement.award_ids == ["DBI-1234567"]
+
+    @pytest.mark.parametrize(
+        "text,funders,award_ids",
+        [
+            ("This work was funded by grant No. 12345 from the Wellcome Trust.", ["Wellcome Trust"], ["12345"]),
+            (
+                "The authors thank the National Science Foundation (grant DBI-1234567) for support.",
+                ["National Science Foundation"],
+                ["DBI-1234567"],
+            ),
+            (
+                "Funded by the Deutsche Forschungsgemeinschaft (DFG, German Research Foundation) - Project number "
+                "390685813.",
+                ["Deutsche Forschungsgemeinschaft"],
+                ["390685813"],
+            ),
+            ("Supported by the NSF grant DMR 1507101 and the NIH.", ["NSF", "NIH"], ["DMR-1507101"]),
+        ],
+    )
+    def test_funder_phrasings(self, text, funders, award_ids):
+        [statement] = extract_funding_statements(text)
+        assert statement.funders == funders
+        assert statement.award_ids == award_ids
+
+    @pytest.mark.parametrize(
+        "text",
+        [
+            None,
+            "",
+            "Research funding in Europe has grown over the last decade.",
+            "The study was funded by our department.",
+            "We thank the reviewers for their comments.",
+            "We thank John Smith for advice on the grant application.",
+        ],
+    )
+    def test_no_statements(self, text):
+        assert extract_funding_statements(text) == []
+
+
+class TestCleanText:
+    def test_invisible_characters(self):
+        assert clean_text("\ufeffSoil\u200b moisture\u00ad dynamics\u2060") == "Soil moisture dynamics"
+        assert clean_text("clim\u00adate\x07 data\u200e") == "climate data"
+
+    def test_unusual_whitespace(self):
+        assert clean_text("Soil\u00a0moisture\u2009and\u3000carbon") == "Soil moisture and carbon"
+        assert clean_text("line one\nline\ttwo") == "line one\nline\ttwo"
+
+    def test_none(self):
+        assert clean_text(None) is None
+        assert clean_text("") == ""
+
+    def test_applied_before_normalizing_titles(self):
+        title = "Climate change and soil"
+        rendered_alike = "Cli\u00admate\u200b change\u00a0and soil"
+        assert normalize_title(rendered_alike) == normalize_title(title)
+
+
+class TestNameOptions:
+    @pytest.mark.parametrize(
+        "raw_full,given_name,surname",
+        [
+            ("Jan ten Brink", "Jan", "ten Brink"),
+            ("Pieter van der Berg", "Pieter", "van der Berg"),
+            ("Jean-Claude Van Damme", "Jean-Claude", "Van Damme"),
+            ("Maria de la Cruz", "Maria", "de la Cruz"),
+            ("John Smith", "John", "Smith"),
+        ],
+    )
+    def test_surname_particles(self, raw_full, given_name, surname):
+        parsed = parse_name(raw_full=raw_full, options=NameOptions(surname_particles=True))
+        assert (parsed.given_name, parsed.surname) == (given_name, surname)
+
+    def test_surname_particles_keep_middle_names_and_affixes(self):
+        parsed = parse_name_struct(
+            raw_full="Dr. Juan Carlos dos Santos Jr.", options=NameOptions(surname_particles=True)
+        )
+        assert parsed.given_name == "Juan"
+        assert (parsed.middle_initials, parsed.middle_names) == ("C", "Carlos")
+        assert (parsed.surname, parsed.title, parsed.suffix) == ("dos Santos", "Dr.", "Jr.")
+
+    def test_surname_particles_initials(self):
+        parsed = parse_name(raw_full="J. R. van der Berg", options=NameOptions(surname_particles=True))
+        assert (parsed.first_initial, parsed.given_name, parsed.middle_initials) == ("J", None, "R")
+        assert parsed.surname == "van der Berg"
+
+    @pytest.mark.parametrize(
+        "raw_full,given_name,surname",
+        [
+            ("张伟", "伟", "张"),
+            ("欧阳娜娜", "娜娜", "欧阳"),
+            ("田中 太郎", "太郎", "田中"),
+            ("김민준", "민준", "김"),
+            ("WANG Wei", "Wei", "WANG"),
+            ("TANAKA Taro", "Taro", "TANAKA"),
+            ("Nagy Imre", "Imre", "Nagy"),
+            ("Kovács János", "János", "Kovács"),
+        ],
+    )
+    def test_family_name_first(self, raw_full, given_name, surname):
+        parsed = parse_name(raw_full=raw_full, options=NameOptions(family_name_first=True))
+        assert (parsed.given_name, parsed.surname, parsed.full) == (given_name, surname, raw_full)
+
+    @pytest.mark.parametrize("raw_full", ["Anna Kovács", "Wei Wang", "Wang Wei", "John SMITH"])
+    def test_given_name_first(self, raw_full):
+        options = NameOptions(family_name_first=True)
+        assert parse_name(raw_full=raw_full, options=options) == parse_name(raw_full=raw_full)
+
+    def test_family_name_first_cjk_has_no_initials(self):
+        parsed = parse_name(raw_full="张伟", options=NameOptions(family_name_first=True))
+        assert parsed.first_initial is None
+
+    def test_family_name_first_middle_names(self):
+        parsed = parse_name(raw_full="WANG Xiao Ming", options=NameOptions(family_name_first=True), recase_surname=True)
+        assert (parsed.first_initial, parsed.given_name, parsed.middle_names) == ("X", "Xiao", "Ming")
+        assert parsed.surname == "Wang"
+
+    def test_inverted_names_unchanged(self):
+        options = NameOptions(surname_particles=True, family_name_first=True)
+        for raw_full in ["van Gogh, Vincent", "Wang, Wei", "Nagy, Imre"]:
+            assert parse_name(raw_full=raw_full, options=options) == parse_name(raw_full=raw_full)
+
+    def test_disabled_by_default(self):
+        assert parse_name(raw_full="Nagy Imre").surname == "Imre"
+        assert parse_name(raw_full="Nagy Imre", options=NameOptions()) == parse_name(raw_full="Nagy Imre")
+
+    def test_batch_variants(self):
+        options = NameOptions(surname_particles=True, family_name_first=True)
+        names = ["Jan ten Brink", "NAGY Imre", None]
+        expected = [parse_name(raw_full=name, options=options) for name in names]
+        assert parse_name_many(names, options=options) == expected
+        assert parse_names_list("Jan ten Brink; NAGY Imre", options=options) == expected[:2]
+        result = pa.array(parse_names_batch(pa.array(names), options=options)).to_pylist()
+        assert result == [parse_name_struct(raw_full=name, options=options).to_dict() for name in names]
+
+
+class TestStripBoilerplate:
+    @pytest.mark.parametrize(
+        "text",
+        [
+            "Abstract: Soil moisture controls runoff.",
+            "ABSTRACT - Soil moisture controls runoff.",
+            "Summary. Soil moisture controls runoff.",
+            "Abstract Soil moisture controls runoff.",
+            "Graphical abstract: Soil moisture controls runoff.",
+        ],
+    )
+    def test_labels(self, text):
+        assert strip_boilerplate(text) == "Soil moisture controls runoff."
+
+    def test_label_kept_without_separator(self):
+        assert strip_boilerplate("Summary statistics were computed.") == "Summary statistics were computed."
+
+    @pytest.mark.parametrize(
+        "statement",
+        [
+            "© 2020 Elsevier Ltd. All rights reserved.",
+            "Copyright © 2021 The Authors. Published by Elsevier B.V. This is an open access article.",
+            "(c) 2019 Wiley Periodicals, Inc.",
+            "(c) The Author(s) 2021. Published by Oxford University Press.",
+            "All rights reserved.",
+        ],
+    )
+    def test_copyright(self, statement):
+        assert strip_boilerplate(f"Soil moisture controls runoff. {statement}") == "Soil moisture controls runoff."
+
+    @pytest.mark.parametrize(
+        "sentence",
+        [
+            "This article is protected by copyright.",
+            "This is an open access article under the CC BY license.",
+            "This article is distributed under the terms of the Creative Commons Attribution 4.0 License.",
+            "Published by Elsevier Inc. on behalf of the Society.",
+            "Springer Nature remains neutral with regard to jurisdictional claims in published maps.",
+            "This article is part of the theme issue 'Soils'.",
+            "The online version contains supplementary material available at https://example.org.",
+            "Supplementary data are available at Bioinformatics online.",
+            "This work was supported by the National Science Foundation.",
+            "The research was partly funded by NSF grant 123.",
+            "Funding: NSF grant 123.",
+        ],
+    )
+    def test_sentences(self, sentence):
+        text = f"Soil moisture controls runoff. {sentence} Wet soils flood sooner."
+        assert strip_boilerplate(text) == "Soil moisture controls runoff. Wet soils flood sooner."
+
+    def test_kept(self):
+        text = "Published by the WHO in 2019, the guidelines changed practice. Copyright law is discussed (c) below."
+        assert strip_boilerplate(text) == text
+        text = "Three outcomes were measured. (a) Runoff rose. (b) Erosion fell. (c) The soils flooded sooner."
+        assert strip_boilerplate(text) == text
+
+    def test_rules(self):
+        rules = BoilerplateRules(patterns=[r"\[[a-z ]+ press\]"])
+        assert strip_boilerplate("[Alpine Press] Soil moisture controls runoff.", rules) == (
+            "Soil moisture controls runoff."
+        )
+        rules = BoilerplateRules(patterns=[r"\[[a-z ]+ press\]"], case_insensitive=False)
+        assert strip_boilerplate("[Alpine Press] Soil.", rules) == "[Alpine Press] Soil."
+
+    def test_invalid_pattern(self):
+        with pytest.raises(ValueError, match="invalid pattern"):
+            BoilerplateRules(patterns=["("])
+
+    def test_nothing_left(self):
+        assert strip_boilerplate("© 2020 Elsevier Ltd. All rights reserved.") is None
+        assert strip_boilerplate("Abstract:") is None
+        assert strip_boilerplate(None) is None
+
+
+class TestExtractTitleMetadata:
+    @pytest.mark.parametrize(
+        "text,expected",
+        [
+            ("Soil moisture observations v2", ("Soil moisture observations", "2", None, [])),
+            ("Soil moisture observations (version 3)", ("Soil moisture observations", "3", None, [])),
+            ("Soil moisture observations - ver. 1.0.2", ("Soil moisture observations", "1.0.2", None, [])),
+            ("Soil moisture observations_v4", ("Soil moisture observations", "4", None, [])),
+            ("Soil moisture observations [V2]", ("Soil moisture observations", "2", None, [])),
+            ("Soil moisture observations, Part 2", ("Soil moisture observations", None, 2, [])),
+            ("Soil moisture observations (part II of IV)", ("Soil moisture observations", None, 2, [])),
+            ("Soil moisture observations pt. 3 of 5", ("Soil moisture observations", None, 3, [])),
+            ("Soil moisture observations (2019)", ("Soil moisture observations", None, None, [2019])),
+            ("Soil moisture observations, 2010–2015", ("Soil moisture observations", None, None, [2010, 2015])),
+            (
+                "Soil moisture observations, Part 2 (2019) v3",
+                ("Soil moisture observations", "3", 2, [2019]),
+            ),
+        ],
+    )
+    def test_markers(self, text, expected):
+        assert extract_title_metadata(text) == expected
+
+    def test_years_in_title(self):
+        expected = ("The 2018 drought survey", None, None, [2018, 2020])
+        assert extract_title_metadata("The 2018 drought survey (2020)") == expected
+        assert extract_title_metadata("Survey 2018-2019") == ("Survey 2018-2019", None, None, [2018, 2019])
+        assert extract_title_metadata("Data from 2010") == ("Data from 2010", None, None, [2010])
+
+    def test_not_markers(self):
+        for text in ["Vitamin D levels in adults", "Developing v2x networks", "Counterpart analysis", "Version 2"]:
+            assert extract_title_metadata(text) == (text, None, None, [])
+
+    def test_version_families(self):
+        titles = ["Global soil moisture v1", "Global soil moisture (version 2)", "Global soil moisture - v3.1"]
+        assert {extract_title_metadata(title).title for title in titles} == {"Global soil moisture"}
+
+    def test_empty(self):
+        assert extract_title_metadata(None) == (None, None, None, [])
+        assert extract_title_metadata("  ") == (None, None, None, [])
+
+
+@pytest.fixture
+def default_settings():
+    yield configure()
+    configure(threads=0, chunk_size=10_000, string_cache=0)
+
+
+class TestConfigure:
+    def test_defaults(self, default_settings):
+        assert default_settings == {"threads": None, "chunk_size": 10_000, "string_cache": 0}
+
+    def test_threads(self, default_settings):
+        texts = ["The quick brown fox jumps over the lazy dog", None]
+        expected = detect_language_many(texts)
+        assert configure(threads=2)["threads"] == 2
+        assert detect_language_many(texts) == expected
+        assert configure(threads=0)["threads"] is None
+
+    def test_chunk_size(self, default_settings, tmp_path):
+        input_path = tmp_path / "input.jsonl"
+        input_path.write_text("".join(json.dumps({"Soil": [i]}) + "\n" for i in range(5)))
+        configure(chunk_size=2)
+        counts = []
+        assert revert_file(input_path, tmp_path / "output.jsonl", progress=counts.append) == (5, 0)
+        assert counts == [2, 4, 5]
+
+    def test_string_cache(self, default_settings):
+        from dmpworks.rust.funders import normalize_funder_name
+        from dmpworks.rust.institutions import normalize_institution_name
+
+        uncached = [normalize_funder_name("NSF"), normalize_institution_name("NSF")]
+        assert configure(string_cache=2)["string_cache"] == 2
+        for _ in range(2):
+            assert [normalize_funder_name("NSF"), normalize_institution_name("NSF")] == uncached
+            assert [normalize_funder_name(name) for name in ["N.S.F.", "NIH", ""]] == [
+                "national science foundation",
+                "national institutes health",
+                None,
+            ]
+        configure(string_cache=0)
+        assert normalize_institution_name("Univ. of Montana") == "university of montana"
+
+    def test_invalid(self, default_settings):
+        with pytest.raises(ValueError):
+            configure(chunk_size=0)
+        assert configure()["chunk_size"] == 10_000
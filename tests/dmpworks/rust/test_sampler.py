@@ -0,0 +1,139 @@
+import json
+
+import pytest
+
+from dmpworks.rust.sampler import generate_negative_pairs, HardNegativeOptions, sample_candidate_pairs, SamplingStrategy
+
+
+def write_jsonl(path, records):
+    path.write_text("".join(json.dumps(record) + "\n" for record in records))
+    return path
+
+
+@pytest.fixture
+def dmps(tmp_path):
+    return write_jsonl(
+        tmp_path / "dmps.jsonl",
+        [{"doi": "10.48321/D1ABC", "project_start": "2020-01-01"}, {"doi": "10.48321/d2def", "project_start": None}],
+    )
+
+
+@pytest.fixture
+def works(tmp_path):
+    records = []
+    for i in range(200):
+        records.append(
+            {
+                "dmp_doi": "10.48321/d1abc",
+                "work_doi": f"10.1234/{i}",
+                "work_type": "dataset" if i % 2 else "article",
+                "publication_date": f"{2019 + i % 8}-06-01",
+                "score": (i % 10) / 10,
+            }
+        )
+    records.append({"dmp_doi": "10.48321/d2def", "work_doi": "10.1234/x", "work_type": "article", "score": None})
+    return write_jsonl(tmp_path / "works.jsonl", records)
+
+
+class TestSampleCandidatePairs:
+    def test_stratified(self, dmps, works):
+        sample = sample_candidate_pairs(dmps, works, SamplingStrategy(per_stratum=3))
+        counts = {}
+        for pair in sample:
+            key = tuple(pair["stratum"].values())
+            counts[key] = counts.get(key, 0) + 1
+        assert max(counts.values()) == 3
+        assert ("article", "unknown", "unknown") in counts
+        assert ("dataset", "[2, 5)", ">=0.75") in counts
+        assert ("article", "<0", "<0.25") in counts
+
+    def test_record_and_order(self, dmps, works):
+        sample = sample_candidate_pairs(dmps, works, SamplingStrategy(per_stratum=2))
+        first = sample[0]
+        assert first["record"]["work_doi"] == f"10.1234/{first['index']}"
+        strata = [tuple(pair["stratum"].values()) for pair in sample]
+        assert strata == sorted(strata, key=lambda s: (s[0] is not None, s))
+
+    def test_reproducible(self, dmps, works):
+        a = sample_candidate_pairs(dmps, works, SamplingStrategy(per_stratum=2, seed=7))
+        b = sample_candidate_pairs(dmps, works, SamplingStrategy(per_stratum=2, seed=7))
+        c = sample_candidate_pairs(dmps, works, SamplingStrategy(per_stratum=2, seed=8))
+        assert [pair["index"] for pair in a] == [pair["index"] for pair in b]
+        assert [pair["index"] for pair in a] != [pair["index"] for pair in c]
+
+    def test_without_work_type(self, dmps, works):
+        sample = sample_candidate_pairs(dmps, works, SamplingStrategy(per_stratum=1, by_work_type=False))
+        assert all(pair["stratum"]["work_type"] is None for pair in sample)
+
+    def test_default_strategy(self, dmps, works):
+        assert len(sample_candidate_pairs(dmps, works)) == 201
+
+    def test_invalid_strategy(self):
+        with pytest.raises(ValueError):
+            SamplingStrategy(per_stratum=0)
+        with pytest.raises(ValueError):
+            SamplingStrategy(similarity_edges=[0.5, 0.25])
+
+    def test_missing_file(self, dmps, tmp_path):
+        with pytest.raises(FileNotFoundError):
+            sample_candidate_pairs(dmps, tmp_path / "missing.jsonl")
+
+
+NSF = {"name": "National Science Foundation", "funder_doi": "10.13039/100000001"}
+BERKELEY = {"ror": "https://ror.org/01an7q238", "name": "University of California, Berkeley"}
+
+NEGATIVE_DMPS = [
+    {"doi": "10.48321/D1ABC", "dois": ["10.1234/0"], "funders": [{"name": "NSF"}], "institutions": [BERKELEY]},
+    {"doi": "10.48321/D2DEF"},
+]
+
+NEGATIVE_WORKS = (
+    [{"doi": "10.1234/0", "funders": [NSF]}, {"doi": "10.1234/1", "funders": [NSF]}]
+    + [{"doi": "10.1234/2", "institutions": [{"ror": "01an7q238"}]}, {"doi": "10.1234/3", "funders": [NSF]}]
+    + [{"doi": f"10.1234/{i}"} for i in range(4, 100)]
+)
+
+
+class TestGenerateNegativePairs:
+    def test_hard_negatives(self):
+        pairs = generate_negative_pairs(NEGATIVE_DMPS, NEGATIVE_WORKS, n_per_dmp=6)
+        first = [(work, kind) for dmp, work, kind in pairs if dmp == 0]
+        assert len(first) == 6
+        hard = sorted((work, kind) for work, kind in first if kind != "random")
+        assert hard == [(1, "funder"), (2, "institution"), (3, "funder")]
+        assert all(work not in (0, 1, 2, 3) for work, kind in first if kind == "random")
+        assert [kind for dmp, _, kind in pairs if dmp == 1] == ["random"] * 6
+
+    def test_never_known_matches(self):
+        pairs = generate_negative_pairs(
+            NEGATIVE_DMPS, NEGATIVE_WORKS[:5], n_per_dmp=10, positives=[("10.48321/d2def", "https://doi.org/10.1234/4")]
+        )
+        assert sorted(work for dmp, work, _ in pairs if dmp == 0) == [1, 2, 3, 4]
+        assert sorted(work for dmp, work, _ in pairs if dmp == 1) == [0, 1, 2, 3]
+
+    def test_reproducible(self):
+        pairs = generate_negative_pairs(NEGATIVE_DMPS, NEGATIVE_WORKS, n_per_dmp=5, seed=7)
+        assert generate_negative_pairs(NEGATIVE_DMPS, NEGATIVE_WORKS, n_per_dmp=5, seed=7, num_threads=1) == pairs
+        assert generate_negative_pairs(NEGATIVE_DMPS, NEGATIVE_WORKS, n_per_dmp=5, seed=8) != pairs
+        assert len({(dmp, work) for dmp, work, _ in pairs}) == len(pairs) == 10
+
+    def test_options(self):
+        random_only = HardNegativeOptions(fraction=0.0)
+        pairs = generate_negative_pairs(NEGATIVE_DMPS, NEGATIVE_WORKS, n_per_dmp=4, hard_negative_opts=random_only)
+        assert {kind for _, _, kind in pairs} == {"random"}
+        funders_only = HardNegativeOptions(fraction=1.0, same_institution=False)
+        pairs = generate_negative_pairs(NEGATIVE_DMPS[:1], NEGATIVE_WORKS, n_per_dmp=4, hard_negative_opts=funders_only)
+        assert sorted(work for _, work, kind in pairs if kind == "funder") == [1, 3]
+        assert [kind for _, _, kind in pairs].count("random") == 2
+
+    def test_empty(self):
+        assert generate_negative_pairs(NEGATIVE_DMPS, []) == []
+        assert generate_negative_pairs([], NEGATIVE_WORKS) == []
+
+    def test_invalid(self):
+        with pytest.raises(ValueError):
+            generate_negative_pairs(NEGATIVE_DMPS, NEGATIVE_WORKS, n_per_dmp=0)
+        with pytest.raises(ValueError):
+            HardNegativeOptions(fraction=1.5)
+        with pytest.raises(ValueError):
+            generate_negative_pairs([{"funders": "NSF"}], NEGATIVE_WORKS)
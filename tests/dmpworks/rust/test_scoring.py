@@ -0,0 +1,159 @@
+import json
+import math
+
+import numpy as np
+import pytest
+
+from dmpworks.rust.scoring import MatchModel, featurize_pairs, score_candidate, score_candidates
+
+DMP = {
+    "title": "Soil carbon dynamics in alpine meadows",
+    "project_start": "2020-01-01",
+    "project_end": "2022-12-31",
+    "authors": [{"given_name": "Josiah", "surname": "Carberry"}, {"full": "Jane Smith"}],
+    "institutions": [{"ror": "https://ror.org/01an7q238", "name": "University of California, Berkeley"}],
+    "funders": [{"name": "NSF"}],
+    "dois": ["https://doi.org/10.1234/LISTED"],
+}
+
+WORK = {
+    "doi": "10.1234/listed",
+    "title": "Soil Carbon Dynamics in Alpine Meadows",
+    "publication_date": "2021-06",
+    "authors": [{"given_name": "J.", "surname": "Carberry"}, {"full": "A. Other"}],
+    "institutions": [{"ror": "01an7q238"}],
+    "funders": [{"name": "National Science Foundation", "funder_doi": "10.13039/100000001"}],
+}
+
+
+class TestScoreCandidate:
+    def test_features(self):
+        features, score = score_candidate(DMP, WORK)
+        assert features == {
+            "title": 1.0,
+            "authors": 0.5,
+            "affiliations": 1.0,
+            "funders": 1.0,
+            "date": 1.0,
+            "doi": 1.0,
+        }
+        assert score == pytest.approx((1.0 + 2.0 * 0.5 + 0.5 + 1.0 + 0.5 + 3.0) / 8.0)
+
+    def test_missing_features(self):
+        features, score = score_candidate({"title": "Soil carbon"}, {"title": "Glacier retreat"})
+        assert list(features) == ["title", "authors", "affiliations", "funders", "date", "doi"]
+        assert features["title"] < 0.5
+        assert [features[name] for name in list(features)[1:]] == [None] * 5
+        assert score == features["title"]
+        assert score_candidate({}, {})[1] == 0.0
+
+    def test_date_proximity(self):
+        dmp = {"project_start": "2020-01-01", "project_end": "2020-12-31"}
+        assert score_candidate(dmp, {"publication_date": "2020"})[0]["date"] == 1.0
+        assert score_candidate(dmp, {"publication_date": "2022-06-30"})[0]["date"] == pytest.approx(1 - 546 / 1096)
+        assert score_candidate(dmp, {"publication_date": "2015"})[0]["date"] == 0.0
+        assert score_candidate({"project_start": "2020"}, {"publication_date": "2030"})[0]["date"] == 1.0
+
+    def test_doi_not_listed(self):
+        assert score_candidate(DMP, {**WORK, "doi": "10.1234/other"})[0]["doi"] == 0.0
+
+    def test_weights(self):
+        features, score = score_candidate(DMP, WORK, weights={"authors": 1.0})
+        assert score == features["authors"]
+        with pytest.raises(ValueError):
+            score_candidate(DMP, WORK, weights={"abstract": 1.0})
+
+    def test_invalid_record(self):
+        with pytest.raises(ValueError):
+            score_candidate({"authors": "Carberry"}, WORK)
+
+
+class TestScoreCandidates:
+    def test_batch(self):
+        works = [WORK, {"title": "Glacier retreat"}]
+        assert score_candidates(DMP, works) == [score_candidate(DMP, work) for work in works]
+        assert score_candidates(DMP, []) == []
+
+
+
+class TestFeaturizePairs:
+    def test_matrix(self):
+        works = [WORK, {"title": "Glacier retreat"}]
+        matrix, names = featurize_pairs([(DMP, work) for work in works])
+        assert names == ["title", "authors", "affiliations", "funders", "date", "doi"]
+        assert matrix.dtype == np.float32
+        assert matrix.shape == (2, 6)
+        for row, work in zip(matrix, works):
+            features = score_candidate(DMP, work)[0]
+            expected = [np.nan if features[name] is None else features[name] for name in names]
+            np.testing.assert_allclose(row, np.array(expected, dtype=np.float32))
+
+    def test_distinct_dmps(self):
+        pairs = ((dict(DMP, title=title), WORK) for title in ["Soil carbon dynamics in alpine meadows", "Glaciers"])
+        matrix, _ = featurize_pairs(pairs)
+        assert matrix[0, 0] == 1.0
+        assert matrix[1, 0] < 0.5
+
+    def test_empty(self):
+        matrix, _ = featurize_pairs([])
+        assert matrix.shape == (0, 6)
+
+    def test_invalid(self):
+        with pytest.raises(TypeError, match="pair 0"):
+            featurize_pairs([DMP])
+        with pytest.raises(ValueError):
+            featurize_pairs([(DMP, {"authors": "Carberry"})])
+
+MODEL = {
+    "intercept": -4.0,
+    "weights": {"title": 2.0, "authors": 3.0, "doi": 5.0},
+    "fill_values": {"authors": 0.25},
+}
+
+
+def logistic(x):
+    return 1.0 / (1.0 + math.exp(-x))
+
+
+class TestMatchModel:
+    def test_load(self):
+        model = MatchModel(json.dumps(MODEL))
+        assert model.intercept == -4.0
+        assert model.weights == {
+            "title": 2.0,
+            "authors": 3.0,
+            "affiliations": 0.0,
+            "funders": 0.0,
+            "date": 0.0,
+            "doi": 5.0,
+        }
+
+    def test_invalid(self):
+        with pytest.raises(ValueError, match="invalid match model: unknown feature 'abstract' in weights"):
+            MatchModel(json.dumps({"intercept": 0.0, "weights": {"abstract": 1.0}}))
+        with pytest.raises(ValueError, match="invalid match model"):
+            MatchModel(json.dumps({"weights": {}}))
+        with pytest.raises(ValueError, match="invalid match model"):
+            MatchModel("{")
+
+    def test_predict(self):
+        model = MatchModel(json.dumps(MODEL))
+        features = {"title": 0.5, "authors": None, "doi": 1.0}
+        expected = logistic(-4.0 + 2.0 * 0.5 + 3.0 * 0.25 + 5.0)
+        assert model.predict(features) == pytest.approx(expected)
+        assert model.predict([0.5, None, 1.0, 1.0, 1.0, 1.0]) == pytest.approx(expected)
+        assert model.predict({}) == pytest.approx(logistic(-4.0 + 0.75))
+        assert model.predict_many([features, {}], num_threads=2) == [model.predict(features), model.predict({})]
+        with pytest.raises(ValueError, match="unknown feature 'abstract'"):
+            model.predict({"abstract": 1.0})
+        with pytest.raises(ValueError, match="expected 6 features"):
+            model.predict([1.0])
+
+    def test_score(self):
+        model = MatchModel(json.dumps(MODEL))
+        features, _ = score_candidate(DMP, WORK)
+        assert model.score(DMP, WORK) == pytest.approx(model.predict(features))
+        other = {**WORK, "doi": "10.1234/other", "title": "Glacier retreat"}
+        scores = model.score_candidates(DMP, [WORK, other], num_threads=2)
+        assert scores == [model.score(DMP, WORK), model.score(DMP, other)]
+        assert scores[0] > 0.9 > scores[1]
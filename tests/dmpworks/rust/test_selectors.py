@@ -0,0 +1,78 @@
+import json
+
+import pytest
+
+from dmpworks.rust.selectors import extract_fields, extract_fields_many, FieldSelectors
+
+WORK = json.dumps(
+    {
+        "doi": "https://doi.org/10.1234/abc",
+        "primary_location": {"source": {"issn_l": "1234-5678"}},
+        "authorships": [
+            {"author": {"orcid": "0000-0001"}, "institutions": [{"ror": "r1"}, {"ror": "r2"}]},
+            {"author": {"orcid": None}, "institutions": []},
+            {"author": {"orcid": "0000-0003"}, "institutions": [{"ror": "r3"}]},
+        ],
+        "ids.extra": {"mag": 42},
+    }
+).encode()
+
+
+class TestExtractFields:
+    def test_paths(self):
+        assert extract_fields(WORK, ["doi", "primary_location.source.issn_l", "$.authorships[0].author.orcid"]) == [
+            "https://doi.org/10.1234/abc",
+            "1234-5678",
+            "0000-0001",
+        ]
+
+    def test_negative_index_and_quoted_key(self):
+        assert extract_fields(WORK, ["authorships[-1].author.orcid", '["ids.extra"].mag']) == ["0000-0003", 42]
+
+    def test_wildcards(self):
+        assert extract_fields(WORK, ["authorships[*].author.orcid", "authorships.*.institutions[*].ror"]) == [
+            ["0000-0001", None, "0000-0003"],
+            ["r1", "r2", "r3"],
+        ]
+        assert extract_fields(WORK, ["missing[*].ror"]) == [[]]
+
+    def test_missing(self):
+        assert extract_fields(WORK, ["title", "authorships[5].author", "doi.value", "authorships.author"]) == [
+            None,
+            None,
+            None,
+            None,
+        ]
+
+    def test_whole_record(self):
+        assert extract_fields(b'{"a": 1}', ["$"]) == [{"a": 1}]
+
+    def test_compiled(self):
+        selectors = FieldSelectors(["doi", "authorships[1].author.orcid"])
+        assert len(selectors) == 2
+        assert extract_fields(WORK, selectors) == ["https://doi.org/10.1234/abc", None]
+
+    @pytest.mark.parametrize("selector", ["a..b", "a.", "a[0", "a[x]", "a[0]b", '["a"b]'])
+    def test_invalid_selector(self, selector):
+        with pytest.raises(ValueError, match="selector"):
+            FieldSelectors([selector])
+        with pytest.raises(ValueError, match="selector"):
+            extract_fields(WORK, [selector])
+
+    def test_invalid_json(self):
+        with pytest.raises(ValueError, match="invalid json"):
+            extract_fields(b"{", ["doi"])
+
+
+class TestExtractFieldsMany:
+    def test_many(self):
+        selectors = FieldSelectors(["doi", "authorships[*].author.orcid"])
+        records = [WORK, b'{"doi": "10.1/x"}']
+        assert extract_fields_many(records, selectors, num_threads=2) == [
+            ["https://doi.org/10.1234/abc", ["0000-0001", None, "0000-0003"]],
+            ["10.1/x", []],
+        ]
+
+    def test_invalid_json(self):
+        with pytest.raises(ValueError, match="record 1: invalid json"):
+            extract_fields_many([b'{"doi": "a"}', b"{"], ["doi"])
@@ -0,0 +1,97 @@
+import pytest
+
+from dmpworks.rust.similarity import (
+    containment_score,
+    containment_score_many,
+    jaro_winkler,
+    jaro_winkler_many,
+    normalized_levenshtein,
+    normalized_levenshtein_many,
+    token_set_ratio,
+    token_set_ratio_many,
+    token_sort_ratio,
+    token_sort_ratio_many,
+)
+
+
+class TestJaroWinkler:
+    def test_pair(self):
+        assert jaro_winkler("martha", "marhta") == pytest.approx(0.961, abs=1e-3)
+        assert jaro_winkler("same", "same") == 1.0
+
+    def test_invisible_characters_ignored(self):
+        assert jaro_winkler("\ufeffsoil\u00a0carbon", "soil carbon") == 1.0
+
+    def test_many(self):
+        assert jaro_winkler_many(["martha", None, "same"], ["marhta", "x", "same"]) == [
+            pytest.approx(0.961, abs=1e-3),
+            None,
+            1.0,
+        ]
+
+
+class TestNormalizedLevenshtein:
+    def test_pair(self):
+        assert normalized_levenshtein("kitten", "sitting") == pytest.approx(1 - 3 / 7)
+        assert normalized_levenshtein("", "") == 1.0
+
+    def test_many_mismatched_lengths(self):
+        with pytest.raises(ValueError):
+            normalized_levenshtein_many(["a", "b"], ["a"])
+
+
+class TestTokenSortRatio:
+    def test_word_order_ignored(self):
+        assert token_sort_ratio("National Science Foundation", "foundation, national science") == 1.0
+
+    def test_invisible_characters_ignored(self):
+        assert token_sort_ratio("Cli\u00admate\u200b change", "climate change") == 1.0
+
+    def test_partial(self):
+        assert 0.0 < token_sort_ratio("soil carbon dynamics", "soil nitrogen dynamics") < 1.0
+        assert token_sort_ratio("", "soil") == 0.0
+
+
+class TestTokenSetRatio:
+    def test_subset(self):
+        assert token_set_ratio("National Science Foundation", "The National Science Foundation (NSF)") == 1.0
+
+    def test_partial(self):
+        score = token_set_ratio("soil carbon in alpine meadows", "soil nitrogen in alpine grasslands")
+        assert token_sort_ratio("soil carbon in alpine meadows", "soil nitrogen in alpine grasslands") <= score < 1.0
+
+    def test_no_tokens(self):
+        assert token_set_ratio("", "soil") == 0.0
+
+    def test_many(self):
+        a = ["National Science Foundation", "soil carbon", None]
+        b = ["The National Science Foundation", "ocean acidification", "soil"]
+        expected = [token_set_ratio(a[0], b[0]), token_set_ratio(a[1], b[1]), None]
+        assert token_set_ratio_many(a, b) == expected
+        assert token_set_ratio_many(a, b, num_threads=2) == expected
+        assert token_sort_ratio_many(a, b)[2] is None
+
+
+class TestContainmentScore:
+    dataset = "Soil moisture measurements: Montana grasslands"
+    article = "Long-term soil moisture measurements in Montana grasslands under drought"
+
+    def test_contained(self):
+        assert containment_score(self.dataset, self.article, 2) == pytest.approx(3 / 4)
+        assert containment_score(self.dataset, self.article) == pytest.approx(1 / 3)
+        assert containment_score(self.dataset, self.article, 1) == 1.0
+
+    def test_asymmetric(self):
+        assert containment_score(self.article, self.dataset, 2) == pytest.approx(3 / 9)
+
+    def test_short_text(self):
+        assert containment_score("Soil carbon", "Soil carbon in alpine meadows") == 1.0
+        assert containment_score("Carbon soil", "Soil carbon in alpine meadows") == 0.0
+        assert containment_score("", "Soil carbon") == 0.0
+
+    def test_many(self):
+        assert containment_score_many([self.dataset, None], [self.article, "x"], shingle_size=1) == [1.0, None]
+
+    def test_invalid_shingle_size(self):
+        with pytest.raises(ValueError, match="shingle_size must be greater than 0"):
+            containment_score("a", "b", 0)
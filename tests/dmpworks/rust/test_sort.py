@@ -0,0 +1,78 @@
+import gzip
+import json
+
+import pytest
+
+from dmpworks.rust.sort import external_sort
+
+
+def write_jsonl(path, records):
+    path.write_text("".join(json.dumps(record) + "\n" for record in records))
+
+
+def read_jsonl(path):
+    return [json.loads(line) for line in path.read_text().splitlines()]
+
+
+class TestExternalSort:
+    records = [
+        {"doi": "10.1234/c", "n": 0},
+        {"doi": "10.1234/a", "n": 1},
+        {"doi": "10.1234/b", "n": 2},
+        {"doi": "10.1234/a", "n": 3},
+        {"n": 4},
+        {"doi": None, "n": 5},
+        {"doi": "10.1234/b", "n": 6},
+    ]
+
+    @pytest.mark.parametrize("max_chunk_bytes", [1, 64, 1 << 20])
+    def test_sort(self, tmp_path, max_chunk_bytes):
+        input_path = tmp_path / "works.jsonl"
+        output_path = tmp_path / "sorted.jsonl"
+        write_jsonl(input_path, self.records)
+        assert external_sort(input_path, output_path, "doi", max_chunk_bytes=max_chunk_bytes) == (7, 0)
+        assert [record["n"] for record in read_jsonl(output_path)] == [4, 5, 1, 3, 2, 6, 0]
+        assert sorted(path.name for path in tmp_path.iterdir()) == ["sorted.jsonl", "works.jsonl"]
+
+    @pytest.mark.parametrize("max_chunk_bytes", [1, 1 << 20])
+    def test_dedupe(self, tmp_path, max_chunk_bytes):
+        input_path = tmp_path / "works.jsonl"
+        output_path = tmp_path / "sorted.jsonl"
+        write_jsonl(input_path, self.records)
+        stats = external_sort(input_path, output_path, "doi", dedupe=True, max_chunk_bytes=max_chunk_bytes)
+        assert stats == (5, 2)
+        assert [record["n"] for record in read_jsonl(output_path)] == [4, 5, 1, 2, 0]
+
+    def test_many_chunks(self, tmp_path):
+        input_path = tmp_path / "works.jsonl"
+        output_path = tmp_path / "sorted.jsonl"
+        temp_dir = tmp_path / "tmp"
+        temp_dir.mkdir()
+        records = [{"id": {"value": f"W{i % 150:03}"}, "n": i} for i in range(300)]
+        write_jsonl(input_path, records)
+        stats = external_sort(
+            input_path, output_path, "id.value", dedupe=True, max_chunk_bytes=1, temp_dir=temp_dir, num_threads=2
+        )
+        assert stats == (150, 150)
+        assert read_jsonl(output_path) == records[:150]
+        assert not list(temp_dir.iterdir())
+
+    def test_raw_lines(self, tmp_path):
+        input_path = tmp_path / "works.jsonl.gz"
+        output_path = tmp_path / "sorted.jsonl"
+        input_path.write_bytes(gzip.compress(b'{"year": 2021,  "title": "B"}\r\n\n{"year": 2020, "title": "A"}'))
+        assert external_sort(input_path, output_path, "year") == (2, 0)
+        assert output_path.read_text() == '{"year": 2020, "title": "A"}\n{"year": 2021,  "title": "B"}\n'
+
+    def test_invalid(self, tmp_path):
+        input_path = tmp_path / "works.jsonl"
+        output_path = tmp_path / "sorted.jsonl"
+        input_path.write_text('{"doi": "10.1234/a"}\n{"doi": \n')
+        with pytest.raises(ValueError, match="invalid json on line 2"):
+            external_sort(input_path, output_path, "doi")
+        with pytest.raises(ValueError, match="empty key"):
+            external_sort(input_path, output_path, "doi.")
+        with pytest.raises(ValueError, match="max_chunk_bytes"):
+            external_sort(input_path, output_path, "doi", max_chunk_bytes=0)
+        with pytest.raises(FileNotFoundError):
+            external_sort(tmp_path / "missing.jsonl", output_path, "doi")
@@ -0,0 +1,87 @@
+import gzip
+import json
+import math
+
+import pytest
+
+from dmpworks.rust import TokenizerOptions
+from dmpworks.rust.tfidf import IdfTable, tfidf_cosine, tfidf_cosine_many
+
+CORPUS = [
+    "Soil moisture dynamics in semi-arid grasslands",
+    "Soil carbon storage under grazing",
+    "Deep learning for protein structure prediction",
+    None,
+]
+
+
+class TestIdfTable:
+    def test_idf(self):
+        table = IdfTable(CORPUS)
+        assert (table.num_docs, table.num_terms) == (4, 17)
+        assert table.idf("soil") == pytest.approx(math.log(5 / 3) + 1)
+        assert table.idf("protein") == pytest.approx(math.log(5 / 2) + 1)
+        assert table.idf("unseen") == pytest.approx(math.log(5) + 1)
+
+    def test_update(self):
+        table = IdfTable()
+        assert (table.num_docs, table.idf("soil")) == (0, pytest.approx(1.0))
+        table.update(CORPUS[:2])
+        table.update(CORPUS[2:], num_threads=2)
+        assert table.num_docs == 4
+        assert table.idf("soil") == pytest.approx(IdfTable(CORPUS).idf("soil"))
+
+    def test_repeated_terms_count_once(self):
+        table = IdfTable(["soil soil soil", "water"])
+        assert table.idf("soil") == table.idf("water")
+
+    def test_options(self):
+        table = IdfTable(CORPUS, options=TokenizerOptions(stop_words=["in", "under", "for"]))
+        assert table.num_terms == 14
+
+    @pytest.mark.parametrize("compressed", [False, True])
+    def test_update_from_jsonl(self, tmp_path, compressed):
+        records = [{"doi": f"10.1234/{i}", "title": title, "abstract": None} for i, title in enumerate(CORPUS)]
+        lines = "".join(json.dumps(record) + "\n" for record in records)
+        path = tmp_path / ("works.jsonl.gz" if compressed else "works.jsonl")
+        if compressed:
+            path.write_bytes(gzip.compress(lines.encode()))
+        else:
+            path.write_text(lines)
+        table = IdfTable()
+        assert table.update_from_jsonl(path) == 4
+        assert table.num_docs == 4
+        assert table.idf("soil") == pytest.approx(IdfTable(CORPUS).idf("soil"))
+
+        assert IdfTable().update_from_jsonl(path, fields=["doi"]) == 4
+
+    def test_update_from_invalid_jsonl(self, tmp_path):
+        path = tmp_path / "works.jsonl"
+        path.write_text('{"title": "Soil"}\n{"title"\n')
+        with pytest.raises(ValueError, match="invalid json on line 2"):
+            IdfTable().update_from_jsonl(path)
+
+
+class TestTfidfCosine:
+    table = IdfTable(CORPUS)
+
+    def test_cosine(self):
+        assert tfidf_cosine("Soil moisture", "soil moisture", self.table) == pytest.approx(1.0)
+        assert tfidf_cosine("Soil moisture", "Protein structure", self.table) == 0.0
+        assert tfidf_cosine("", "Soil moisture", self.table) == 0.0
+
+    def test_rare_terms_count_more(self):
+        common = tfidf_cosine("soil grasslands", "soil carbon", self.table)
+        rare = tfidf_cosine("soil grasslands", "grasslands carbon", self.table)
+        assert 0.0 < common < rare < 1.0
+
+    def test_many(self):
+        dmp_texts = ["Soil moisture", None, "Protein structure"]
+        work_texts = ["Soil moisture in grasslands", "Soil", "Soil carbon"]
+        scores = tfidf_cosine_many(dmp_texts, work_texts, self.table, num_threads=2)
+        assert scores[0] == pytest.approx(tfidf_cosine(dmp_texts[0], work_texts[0], self.table))
+        assert scores[1:] == [None, 0.0]
+
+    def test_many_mismatched_lengths(self):
+        with pytest.raises(ValueError, match="same length"):
+            tfidf_cosine_many(["Soil"], [], self.table)
@@ -0,0 +1,49 @@
+import numpy as np
+import pytest
+
+from dmpworks.rust.vectors import cosine_similarity, top_k
+
+MATRIX = np.array([[1, 0, 0], [0, 1, 0], [1, 1, 0], [0, 0, 0]], dtype=np.float32)
+
+
+class TestCosineSimilarity:
+    def test_matrix(self):
+        queries = np.array([[1, 0, 0], [0, 2, 0]], dtype=np.float32)
+        similarities = cosine_similarity(queries, MATRIX)
+        assert similarities.dtype == np.float32
+        assert similarities.shape == (2, 4)
+        expected = [[1.0, 0.0, np.sqrt(0.5), 0.0], [0.0, 1.0, np.sqrt(0.5), 0.0]]
+        np.testing.assert_allclose(similarities, expected, rtol=1e-6)
+
+    def test_matches_numpy(self):
+        rng = np.random.default_rng(0)
+        queries = rng.standard_normal((5, 67), dtype=np.float32)
+        matrix = rng.standard_normal((11, 67), dtype=np.float32)
+        expected = (queries / np.linalg.norm(queries, axis=1, keepdims=True)) @ (
+            matrix / np.linalg.norm(matrix, axis=1, keepdims=True)
+        ).T
+        np.testing.assert_allclose(cosine_similarity(queries, matrix, num_threads=2), expected, rtol=1e-4, atol=1e-6)
+
+    def test_non_contiguous(self):
+        matrix = np.asfortranarray(MATRIX)
+        np.testing.assert_allclose(cosine_similarity(MATRIX[:2], matrix), cosine_similarity(MATRIX[:2], MATRIX))
+
+    def test_column_mismatch(self):
+        with pytest.raises(ValueError, match="same number of columns"):
+            cosine_similarity(np.zeros((1, 2), dtype=np.float32), MATRIX)
+
+
+class TestTopK:
+    def test_ranked(self):
+        ranked = top_k(np.array([1, 0.9, 0], dtype=np.float32), MATRIX, 3)
+        assert [i for i, _ in ranked] == [2, 0, 1]
+        assert ranked[0][1] == pytest.approx(0.9986, abs=1e-4)
+
+    def test_ties_and_k(self):
+        ranked = top_k(np.array([0, 0, 1], dtype=np.float32), MATRIX, 10)
+        assert ranked == [(0, 0.0), (1, 0.0), (2, 0.0), (3, 0.0)]
+        assert top_k(np.array([1, 0, 0], dtype=np.float32), MATRIX, 0) == []
+
+    def test_length_mismatch(self):
+        with pytest.raises(ValueError, match="as many elements"):
+            top_k(np.zeros(2, dtype=np.float32), MATRIX, 1)